@@ -0,0 +1,90 @@
+//! Compares loading source files by reading them into memory and by memory-mapping them.
+//!
+//! Besides the load times measured by Criterion, prints how much anonymous and file-backed memory
+//! the loaded files keep resident, on Linux.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use solar_bench::get_srcs;
+use solar_parse::interface::SourceMap;
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// How many copies of each source to load, to simulate a large dependency tree.
+const COPIES: usize = 100;
+
+fn load_benches(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+    let paths = write_sources(dir.path());
+    let bytes = paths.iter().map(|path| std::fs::metadata(path).unwrap().len()).sum::<u64>();
+    eprintln!("{} files, {bytes} bytes", paths.len());
+
+    // Memory freed by the allocator is not always returned to the OS, so the mapped files are
+    // measured first to avoid reusing the memory of the read files.
+    for (name, mapped) in [("mmap", true), ("read", false)] {
+        let before = resident_memory();
+        let sm = load(&paths, mapped);
+        if let (Some((anon_before, file_before)), Some((anon, file))) = (before, resident_memory())
+        {
+            let anon = anon as i64 - anon_before as i64;
+            let file = file as i64 - file_before as i64;
+            eprintln!("{name}: {anon:+} KiB anonymous, {file:+} KiB file-backed");
+        }
+        drop(sm);
+    }
+    eprintln!();
+
+    let mut g = c.benchmark_group("load");
+    g.warm_up_time(Duration::from_secs(3));
+    g.measurement_time(Duration::from_secs(10));
+    g.sample_size(20);
+    g.noise_threshold(0.05);
+    for (name, mapped) in [("mmap", true), ("read", false)] {
+        g.bench_function(name, |b| b.iter(|| load(&paths, mapped)));
+    }
+    g.finish();
+}
+
+/// Writes `COPIES` copies of each benchmark source to `dir`, and returns their paths.
+fn write_sources(dir: &Path) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    for source in get_srcs().iter().filter(|source| !source.src.is_empty()) {
+        for i in 0..COPIES {
+            let path = dir.join(format!("{}_{i}.sol", source.name));
+            std::fs::write(&path, source.src).unwrap();
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+/// Loads the given files into a new source map.
+fn load(paths: &[PathBuf], mapped: bool) -> SourceMap {
+    let sm = SourceMap::empty();
+    for path in paths {
+        let file = if mapped {
+            // SAFETY: The files are not modified.
+            unsafe { sm.load_file_mapped(path) }
+        } else {
+            sm.load_file(path)
+        };
+        file.unwrap();
+    }
+    sm
+}
+
+/// Returns the anonymous and the file-backed resident memory of the process, in KiB.
+///
+/// Only supported on Linux.
+fn resident_memory() -> Option<(u64, u64)> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let field = |name: &str| {
+        let value = status.lines().find_map(|line| line.strip_prefix(name))?;
+        value.trim().trim_end_matches("kB").trim().parse::<u64>().ok()
+    };
+    Some((field("RssAnon:")?, field("RssFile:")?))
+}
+
+criterion_group!(benches, load_benches);
+criterion_main!(benches);
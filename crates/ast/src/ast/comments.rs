@@ -0,0 +1,170 @@
+use super::{CommentKind, Item, SourceUnit, Stmt, VariableDefinition};
+use crate::visit::Visit;
+use solar_data_structures::Never;
+use solar_interface::{source_map::SourceFile, BytePos, Span, Symbol};
+use std::{cmp::Reverse, ops::ControlFlow};
+
+/// A comment collected by the lexer, including doc-comments.
+///
+/// Comments are not part of the AST; they are collected in a side table when requested. See
+/// [`Comments`].
+#[derive(Clone, Copy, Debug)]
+pub struct Comment {
+    /// The comment kind.
+    pub kind: CommentKind,
+    /// Whether this is a doc-comment.
+    pub is_doc: bool,
+    /// The comment's span including its "quotes" (`//`, `/*`).
+    pub span: Span,
+    /// The comment's contents excluding its "quotes" (`//`, `/*`).
+    pub symbol: Symbol,
+    /// The position of the comment relative to the code around it.
+    pub style: CommentStyle,
+    /// The AST node that this comment is attached to. Set by [`Comments::attach`].
+    pub attachment: Option<CommentAttachment>,
+}
+
+/// The position of a comment relative to the code on the same lines.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommentStyle {
+    /// No code on the same line: `// comment`.
+    Isolated,
+    /// Code before the comment, but not after it: `foo(); // comment`.
+    Trailing,
+    /// Code after the comment on the same line: `foo(/* comment */ 1);`.
+    Mixed,
+}
+
+/// The AST node that a comment is attached to.
+///
+/// Nodes are identified by their span. Comments are only attached to items, statements, and
+/// variable definitions, including parameters and struct fields.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CommentAttachment {
+    /// The position of the comment relative to the node.
+    pub position: CommentPosition,
+    /// The span of the node.
+    pub span: Span,
+}
+
+/// The position of a comment relative to the node that it is attached to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommentPosition {
+    /// Before the node, with no other node in between.
+    Leading,
+    /// After the node, on the same line.
+    Trailing,
+    /// Inside the node, but not before or after any of its child nodes, like a comment in an
+    /// empty block.
+    Inner,
+}
+
+/// The comments of a source file, in source order.
+#[derive(Clone, Debug, Default)]
+pub struct Comments {
+    comments: Vec<Comment>,
+}
+
+impl std::ops::Deref for Comments {
+    type Target = [Comment];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.comments
+    }
+}
+
+impl Comments {
+    /// Creates a new comment table from comments in source order.
+    pub fn new(comments: Vec<Comment>) -> Self {
+        Self { comments }
+    }
+
+    /// Returns the comments that are attached to the node with the given span.
+    pub fn attached_to(&self, span: Span) -> impl Iterator<Item = &Comment> + '_ {
+        self.comments.iter().filter(move |c| c.attachment.is_some_and(|a| a.span == span))
+    }
+
+    /// Attaches each comment to the nearest AST node of `source_unit`, which must have been
+    /// parsed from `file`.
+    ///
+    /// A comment that follows a node on the same line is attached to it as [trailing]. Otherwise,
+    /// it is attached to the next node as [leading], unless the enclosing node ends first, in which
+    /// case it is attached to the enclosing node as [inner]. Comments that are not in or before
+    /// any node are not attached.
+    ///
+    /// [trailing]: CommentPosition::Trailing
+    /// [leading]: CommentPosition::Leading
+    /// [inner]: CommentPosition::Inner
+    pub fn attach(&mut self, source_unit: &SourceUnit<'_>, file: &SourceFile) {
+        let mut collector = NodeCollector { nodes: Vec::new() };
+        let _ = collector.visit_source_unit(source_unit);
+        // Nodes are collected in pre-order, so parents come before their children.
+        let nodes = collector.nodes;
+        let mut by_lo = (0..nodes.len()).collect::<Vec<_>>();
+        by_lo.sort_by_key(|&i| (nodes[i].lo(), i));
+        let mut by_hi = (0..nodes.len()).collect::<Vec<_>>();
+        by_hi.sort_by_key(|&i| (nodes[i].hi(), Reverse(i)));
+
+        let src = &file.src[..];
+        let offset = |pos: BytePos| (pos.0 - file.start_pos.0) as usize;
+        for comment in &mut self.comments {
+            let (lo, hi) = (comment.span.lo(), comment.span.hi());
+
+            // The outermost node that ends last before the comment, on the same line.
+            let prev = by_hi.partition_point(|&i| nodes[i].hi() <= lo);
+            if let Some(&i) = prev.checked_sub(1).and_then(|prev| by_hi.get(prev)) {
+                if !src[offset(nodes[i].hi())..offset(lo)].contains('\n') {
+                    let position = CommentPosition::Trailing;
+                    comment.attachment = Some(CommentAttachment { position, span: nodes[i] });
+                    continue;
+                }
+            }
+
+            // The outermost node that starts first after the comment, if no node ends in between.
+            let next = by_lo.partition_point(|&i| nodes[i].lo() < hi);
+            if let Some(&i) = by_lo.get(next) {
+                let next_lo = nodes[i].lo();
+                let first_end = by_hi.partition_point(|&i| nodes[i].hi() <= hi);
+                if by_hi.get(first_end).is_none_or(|&j| nodes[j].hi() > next_lo) {
+                    let position = CommentPosition::Leading;
+                    comment.attachment = Some(CommentAttachment { position, span: nodes[i] });
+                    continue;
+                }
+            }
+
+            // The innermost node that contains the comment.
+            let enclosing =
+                nodes.iter().filter(|n| n.lo() <= lo && hi <= n.hi()).max_by_key(|n| n.lo());
+            comment.attachment =
+                enclosing.map(|&span| CommentAttachment { position: CommentPosition::Inner, span });
+        }
+    }
+}
+
+/// Collects the spans of the nodes that comments can be attached to.
+struct NodeCollector {
+    nodes: Vec<Span>,
+}
+
+impl<'ast> Visit<'ast> for NodeCollector {
+    type BreakValue = Never;
+
+    fn visit_item(&mut self, item: &'ast Item<'ast>) -> ControlFlow<Self::BreakValue> {
+        self.nodes.push(item.span);
+        self.walk_item(item)
+    }
+
+    fn visit_stmt(&mut self, stmt: &'ast Stmt<'ast>) -> ControlFlow<Self::BreakValue> {
+        self.nodes.push(stmt.span);
+        self.walk_stmt(stmt)
+    }
+
+    fn visit_variable_definition(
+        &mut self,
+        var: &'ast VariableDefinition<'ast>,
+    ) -> ControlFlow<Self::BreakValue> {
+        self.nodes.push(var.span);
+        self.walk_variable_definition(var)
+    }
+}
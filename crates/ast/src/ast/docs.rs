@@ -0,0 +1,75 @@
+use super::{Box, CommentKind};
+use solar_interface::{Ident, Span, Symbol};
+use std::fmt;
+
+/// A list of doc-comments.
+pub type DocComments<'ast> = Box<'ast, [DocComment<'ast>]>;
+
+/// A single doc-comment: `/// foo`, `/** bar */`.
+#[derive(Debug)]
+pub struct DocComment<'ast> {
+    /// The comment kind.
+    pub kind: CommentKind,
+    /// The comment's span including its "quotes" (`//`, `/**`).
+    pub span: Span,
+    /// The comment's contents excluding its "quotes" (`//`, `/**`)
+    /// similarly to symbols in string literal tokens.
+    pub symbol: Symbol,
+    /// The NatSpec items whose tags start in this comment.
+    ///
+    /// Lines without a tag continue the previous item, which may start in a previous comment of
+    /// the same list.
+    pub natspec: Box<'ast, [NatSpecItem]>,
+}
+
+/// A NatSpec item: `@notice ...`, `@param name ...`.
+///
+/// Reference: <https://docs.soliditylang.org/en/latest/natspec-format.html>
+#[derive(Clone, Copy, Debug)]
+pub struct NatSpecItem {
+    /// The span of the tag, or of the start of the text if the item is an untagged `@notice`.
+    pub span: Span,
+    /// The tag.
+    pub kind: NatSpecKind,
+    /// The text following the tag and its arguments, with continuation lines joined by spaces.
+    pub content: Symbol,
+}
+
+/// A NatSpec tag. See [`NatSpecItem`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NatSpecKind {
+    /// `@title`: a title that should describe the contract or interface.
+    Title,
+    /// `@author`: the name of the author.
+    Author,
+    /// `@notice`, or untagged text: explains to an end user what this does.
+    Notice,
+    /// `@dev`: explains to a developer any extra details.
+    Dev,
+    /// `@param <name>`: documents a parameter.
+    Param { name: Ident },
+    /// `@return`: documents the return variables of a function.
+    Return,
+    /// `@inheritdoc <contract>`: copies all missing tags from the base function.
+    Inheritdoc { contract: Ident },
+    /// `@custom:<name>`: a custom tag, whose semantics are application-defined.
+    Custom { name: Ident },
+    /// Any other tag. The identifier does not include the `@`.
+    Unknown(Ident),
+}
+
+impl fmt::Display for NatSpecKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Title => f.write_str("@title"),
+            Self::Author => f.write_str("@author"),
+            Self::Notice => f.write_str("@notice"),
+            Self::Dev => f.write_str("@dev"),
+            Self::Param { .. } => f.write_str("@param"),
+            Self::Return => f.write_str("@return"),
+            Self::Inheritdoc { .. } => f.write_str("@inheritdoc"),
+            Self::Custom { name } => write!(f, "@custom:{name}"),
+            Self::Unknown(name) => write!(f, "@{name}"),
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use super::{Box, Lit, SubDenomination, Type};
+use super::{Box, Lit, NodeId, SubDenomination, Type};
 use either::Either;
 use solar_interface::{Ident, Span};
 use std::fmt;
@@ -11,6 +11,7 @@ pub type NamedArgList<'ast> = Box<'ast, [NamedArg<'ast>]>;
 /// Reference: <https://docs.soliditylang.org/en/latest/grammar.html#a4.SolidityParser.expression>
 #[derive(Debug)]
 pub struct Expr<'ast> {
+    pub id: NodeId,
     pub span: Span,
     pub kind: ExprKind<'ast>,
 }
@@ -23,13 +24,13 @@ impl AsRef<Self> for Expr<'_> {
 
 impl<'ast> Expr<'ast> {
     /// Creates a new expression from an identifier.
-    pub fn from_ident(ident: Ident) -> Self {
-        Self { span: ident.span, kind: ExprKind::Ident(ident) }
+    pub fn from_ident(id: NodeId, ident: Ident) -> Self {
+        Self { id, span: ident.span, kind: ExprKind::Ident(ident) }
     }
 
     /// Creates a new expression from a type.
-    pub fn from_ty(ty: Type<'ast>) -> Self {
-        Self { span: ty.span, kind: ExprKind::Type(ty) }
+    pub fn from_ty(id: NodeId, ty: Type<'ast>) -> Self {
+        Self { id, span: ty.span, kind: ExprKind::Type(ty) }
     }
 }
 
@@ -1,5 +1,6 @@
 use super::{
-    AstPath, BinOpKind, Block, Box, CallArgs, DocComments, Expr, SemverReq, StrLit, Type, UnOpKind,
+    AstPath, BinOpKind, Block, Box, CallArgs, DocComments, Expr, NodeId, SemverReq, StrLit, Type,
+    UnOpKind,
 };
 use crate::token::Token;
 use either::Either;
@@ -13,6 +14,7 @@ pub type ParameterList<'ast> = Box<'ast, [VariableDefinition<'ast>]>;
 /// A top-level item in a Solidity source file.
 #[derive(Debug)]
 pub struct Item<'ast> {
+    pub id: NodeId,
     pub docs: DocComments<'ast>,
     pub span: Span,
     /// The item's kind.
@@ -331,6 +333,45 @@ impl UserDefinableOperator {
             Self::Ne => Either::Right(BinOpKind::Ne),
         }
     }
+
+    /// Returns the user-definable operator for the given unary or binary operator, if any.
+    ///
+    /// Unary `-` is defined together with binary `-`.
+    pub const fn from_op(op: Either<UnOpKind, BinOpKind>) -> Option<Self> {
+        Some(match op {
+            Either::Left(UnOpKind::BitNot) => Self::BitNot,
+            Either::Left(UnOpKind::Neg) => Self::Sub,
+            Either::Left(_) => return None,
+            Either::Right(BinOpKind::BitAnd) => Self::BitAnd,
+            Either::Right(BinOpKind::BitOr) => Self::BitOr,
+            Either::Right(BinOpKind::BitXor) => Self::BitXor,
+            Either::Right(BinOpKind::Add) => Self::Add,
+            Either::Right(BinOpKind::Div) => Self::Div,
+            Either::Right(BinOpKind::Rem) => Self::Rem,
+            Either::Right(BinOpKind::Mul) => Self::Mul,
+            Either::Right(BinOpKind::Sub) => Self::Sub,
+            Either::Right(BinOpKind::Eq) => Self::Eq,
+            Either::Right(BinOpKind::Ge) => Self::Ge,
+            Either::Right(BinOpKind::Gt) => Self::Gt,
+            Either::Right(BinOpKind::Le) => Self::Le,
+            Either::Right(BinOpKind::Lt) => Self::Lt,
+            Either::Right(BinOpKind::Ne) => Self::Ne,
+            Either::Right(_) => return None,
+        })
+    }
+
+    /// Returns the string representation of the operator.
+    pub const fn to_str(self) -> &'static str {
+        match self.to_op() {
+            Either::Left(op) => op.to_str(),
+            Either::Right(op) => op.to_str(),
+        }
+    }
+
+    /// Returns `true` if the operator is a comparison operator.
+    pub const fn is_comparison(self) -> bool {
+        matches!(self, Self::Eq | Self::Ge | Self::Gt | Self::Le | Self::Lt | Self::Ne)
+    }
 }
 
 /// A contract, abstract contract, interface, or library definition:
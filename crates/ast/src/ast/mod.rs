@@ -6,6 +6,12 @@ use std::fmt;
 pub use crate::token::CommentKind;
 pub use solar_interface::{Ident, Span, Symbol};
 
+mod comments;
+pub use comments::*;
+
+mod docs;
+pub use docs::*;
+
 mod expr;
 pub use expr::*;
 
@@ -69,21 +75,6 @@ impl std::ops::Deref for Arena {
     }
 }
 
-/// A list of doc-comments.
-pub type DocComments<'ast> = Box<'ast, [DocComment]>;
-
-/// A single doc-comment: `/// foo`, `/** bar */`.
-#[derive(Clone, Debug)]
-pub struct DocComment {
-    /// The comment kind.
-    pub kind: CommentKind,
-    /// The comment's span including its "quotes" (`//`, `/**`).
-    pub span: Span,
-    /// The comment's contents excluding its "quotes" (`//`, `/**`)
-    /// similarly to symbols in string literal tokens.
-    pub symbol: Symbol,
-}
-
 /// A Solidity source file.
 pub struct SourceUnit<'ast> {
     /// The source unit's items.
@@ -107,6 +98,13 @@ impl<'ast> SourceUnit<'ast> {
 newtype_index! {
     /// A [source unit item](Item) ID. Only used in [`SourceUnit`].
     pub struct ItemId;
+
+    /// An AST node ID, assigned by the parser to each [`Item`], [`Stmt`], and [`Expr`].
+    ///
+    /// IDs are unique within a [`SourceUnit`], and are assigned in the order in which the nodes
+    /// finish parsing, so they are the same every time a file is parsed. They can be used to key
+    /// side tables by AST node.
+    pub struct NodeId;
 }
 
 #[cfg(test)]
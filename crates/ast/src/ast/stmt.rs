@@ -1,8 +1,8 @@
 use super::{
-    yul, AstPath, Box, CallArgs, DocComments, Expr, ParameterList, PathSlice, StrLit,
+    yul, AstPath, Box, CallArgs, DocComments, Expr, NodeId, ParameterList, PathSlice, StrLit,
     VariableDefinition,
 };
-use solar_interface::{Ident, Span};
+use solar_interface::{diagnostics::ErrorGuaranteed, sym, Ident, Span};
 
 /// A block of statements.
 pub type Block<'ast> = Box<'ast, [Stmt<'ast>]>;
@@ -12,6 +12,7 @@ pub type Block<'ast> = Box<'ast, [Stmt<'ast>]>;
 /// Reference: <https://docs.soliditylang.org/en/latest/grammar.html#a4.SolidityParser.statement>
 #[derive(Debug)]
 pub struct Stmt<'ast> {
+    pub id: NodeId,
     pub docs: DocComments<'ast>,
     pub span: Span,
     pub kind: StmtKind<'ast>,
@@ -77,6 +78,9 @@ pub enum StmtKind<'ast> {
 
     /// A modifier placeholder statement: `_;`.
     Placeholder,
+
+    /// A statement that failed to parse. The error has already been emitted.
+    Err(ErrorGuaranteed),
 }
 
 /// An assembly block, with optional flags: `assembly "evmasm" (...) { ... }`.
@@ -90,6 +94,13 @@ pub struct StmtAssembly<'ast> {
     pub block: yul::Block<'ast>,
 }
 
+impl StmtAssembly<'_> {
+    /// Returns `true` if the block is marked as memory-safe with the `"memory-safe"` flag.
+    pub fn is_memory_safe(&self) -> bool {
+        self.flags.iter().any(|flag| flag.value == sym::memory_dash_safe)
+    }
+}
+
 /// A try statement: `try fooBar(42) returns (...) { ... } catch (...) { ... }`.
 ///
 /// Reference: <https://docs.soliditylang.org/en/latest/grammar.html#a4.SolidityParser.tryStatement>
@@ -0,0 +1,1036 @@
+//! AST pretty-printer.
+//!
+//! Converts AST nodes back into Solidity or Yul source code. The output is valid source code that
+//! parses back into an equivalent AST, but it does not preserve the original formatting, and
+//! comments other than doc-comments are lost. See the `solar-fmt` crate for a formatter.
+
+use crate::ast::{yul, *};
+use crate::token::{TokenKind, TokenLit};
+use std::fmt::{self, Write};
+
+/// Prints a source unit.
+pub fn source_unit_to_string(source_unit: &SourceUnit<'_>) -> String {
+    to_string(|p| p.print_source_unit(source_unit))
+}
+
+/// Prints an item, including its doc-comments.
+pub fn item_to_string(item: &Item<'_>) -> String {
+    to_string(|p| p.print_item(item))
+}
+
+/// Prints a statement, including its doc-comments.
+pub fn stmt_to_string(stmt: &Stmt<'_>) -> String {
+    to_string(|p| p.print_stmt(stmt))
+}
+
+/// Prints an expression.
+pub fn expr_to_string(expr: &Expr<'_>) -> String {
+    to_string(|p| p.print_expr(expr))
+}
+
+/// Prints a type.
+pub fn ty_to_string(ty: &Type<'_>) -> String {
+    to_string(|p| p.print_ty(ty))
+}
+
+/// Prints a Yul object.
+pub fn yul_object_to_string(object: &yul::Object<'_>) -> String {
+    to_string(|p| p.print_yul_object(object))
+}
+
+fn to_string(f: impl FnOnce(&mut Printer)) -> String {
+    let mut printer = Printer::new();
+    f(&mut printer);
+    printer.finish()
+}
+
+/// Expression precedence, from loosest to tightest binding.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Prec {
+    Assign,
+    Ternary,
+    Or,
+    And,
+    Equality,
+    Comparison,
+    BitOr,
+    BitXor,
+    BitAnd,
+    Shift,
+    Additive,
+    Multiplicative,
+    Pow,
+    Prefix,
+    Postfix,
+    Primary,
+}
+
+impl Prec {
+    fn of(expr: &Expr<'_>) -> Self {
+        match &expr.kind {
+            ExprKind::Assign(..) => Self::Assign,
+            ExprKind::Ternary(..) => Self::Ternary,
+            ExprKind::Binary(_, op, _) => Self::of_bin_op(op.kind),
+            ExprKind::Unary(op, _) if op.kind.is_prefix() => Self::Prefix,
+            ExprKind::Delete(_) => Self::Prefix,
+            ExprKind::Unary(..) | ExprKind::New(_) => Self::Postfix,
+            ExprKind::Call(..)
+            | ExprKind::CallOptions(..)
+            | ExprKind::Index(..)
+            | ExprKind::Member(..)
+            | ExprKind::Array(_)
+            | ExprKind::Ident(_)
+            | ExprKind::Lit(..)
+            | ExprKind::Payable(_)
+            | ExprKind::Tuple(_)
+            | ExprKind::TypeCall(_)
+            | ExprKind::Type(_) => Self::Primary,
+        }
+    }
+
+    fn of_bin_op(op: BinOpKind) -> Self {
+        match op {
+            BinOpKind::Or => Self::Or,
+            BinOpKind::And => Self::And,
+            BinOpKind::Eq | BinOpKind::Ne => Self::Equality,
+            BinOpKind::Lt | BinOpKind::Le | BinOpKind::Gt | BinOpKind::Ge => Self::Comparison,
+            BinOpKind::BitOr => Self::BitOr,
+            BinOpKind::BitXor => Self::BitXor,
+            BinOpKind::BitAnd => Self::BitAnd,
+            BinOpKind::Shl | BinOpKind::Shr | BinOpKind::Sar => Self::Shift,
+            BinOpKind::Add | BinOpKind::Sub => Self::Additive,
+            BinOpKind::Mul | BinOpKind::Div | BinOpKind::Rem => Self::Multiplicative,
+            BinOpKind::Pow => Self::Pow,
+        }
+    }
+
+    /// Returns the next tighter precedence.
+    fn next(self) -> Self {
+        match self {
+            Self::Assign => Self::Ternary,
+            Self::Ternary => Self::Or,
+            Self::Or => Self::And,
+            Self::And => Self::Equality,
+            Self::Equality => Self::Comparison,
+            Self::Comparison => Self::BitOr,
+            Self::BitOr => Self::BitXor,
+            Self::BitXor => Self::BitAnd,
+            Self::BitAnd => Self::Shift,
+            Self::Shift => Self::Additive,
+            Self::Additive => Self::Multiplicative,
+            Self::Multiplicative => Self::Pow,
+            Self::Pow => Self::Prefix,
+            Self::Prefix => Self::Postfix,
+            Self::Postfix | Self::Primary => Self::Primary,
+        }
+    }
+}
+
+/// The AST pretty-printer.
+///
+/// Nodes are printed with 4-space indentation, one statement or item per line.
+#[derive(Debug, Default)]
+pub struct Printer {
+    out: String,
+    indent: usize,
+}
+
+impl Printer {
+    /// Creates a new, empty printer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the printed source code.
+    pub fn finish(self) -> String {
+        self.out
+    }
+
+    /// Prints a source unit, followed by a newline.
+    pub fn print_source_unit(&mut self, source_unit: &SourceUnit<'_>) {
+        self.print_items(&source_unit.items.raw);
+        if !self.out.is_empty() {
+            self.newline();
+        }
+    }
+
+    /// Prints an item, including its doc-comments.
+    pub fn print_item(&mut self, item: &Item<'_>) {
+        self.print_docs(&item.docs);
+        match &item.kind {
+            ItemKind::Pragma(pragma) => self.print_pragma(pragma),
+            ItemKind::Import(import) => self.print_import(import),
+            ItemKind::Using(using) => {
+                self.print_using(using);
+                self.word(";");
+            }
+            ItemKind::Contract(contract) => self.print_contract(contract),
+            ItemKind::Function(function) => self.print_function(function),
+            ItemKind::Variable(var) => {
+                self.print_var(var);
+                self.word(";");
+            }
+            ItemKind::Struct(ItemStruct { name, fields }) => {
+                self.display(format_args!("struct {name} "));
+                self.print_block(fields, |this, field| {
+                    this.print_var(field);
+                    this.word(";");
+                });
+            }
+            ItemKind::Enum(ItemEnum { name, variants }) => {
+                self.display(format_args!("enum {name} {{"));
+                if !variants.is_empty() {
+                    self.word(" ");
+                    self.commasep(variants, |this, variant| this.display(variant));
+                    self.word(" ");
+                }
+                self.word("}");
+            }
+            ItemKind::Udvt(ItemUdvt { name, ty }) => {
+                self.display(format_args!("type {name} is "));
+                self.print_ty(ty);
+                self.word(";");
+            }
+            ItemKind::Error(ItemError { name, parameters }) => {
+                self.display(format_args!("error {name}"));
+                self.print_params(parameters);
+                self.word(";");
+            }
+            ItemKind::Event(ItemEvent { name, parameters, anonymous }) => {
+                self.display(format_args!("event {name}"));
+                self.print_params(parameters);
+                if *anonymous {
+                    self.word(" anonymous");
+                }
+                self.word(";");
+            }
+        }
+    }
+
+    /// Prints items one per line, separating definitions with a body by an empty line.
+    fn print_items(&mut self, items: &[Item<'_>]) {
+        fn has_body(item: &Item<'_>) -> bool {
+            match &item.kind {
+                ItemKind::Contract(_) => true,
+                ItemKind::Function(f) => f.body.is_some(),
+                _ => false,
+            }
+        }
+
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                if has_body(&items[i - 1]) || has_body(item) {
+                    self.newline();
+                }
+                self.newline();
+            }
+            self.print_item(item);
+        }
+    }
+
+    fn print_pragma(&mut self, pragma: &PragmaDirective<'_>) {
+        self.word("pragma ");
+        match &pragma.tokens {
+            PragmaTokens::Version(name, req) => self.display(format_args!("{name} {req}")),
+            PragmaTokens::Custom(name, value) => {
+                self.print_ident_or_str_lit(name);
+                if let Some(value) = value {
+                    self.word(" ");
+                    self.print_ident_or_str_lit(value);
+                }
+            }
+            PragmaTokens::Verbatim(tokens) => {
+                for (i, token) in tokens.iter().enumerate() {
+                    if i > 0 {
+                        self.word(" ");
+                    }
+                    match token.kind {
+                        TokenKind::Literal(kind, symbol) => {
+                            self.display(TokenLit::new(kind, symbol));
+                        }
+                        ref kind => self.word(kind.as_str()),
+                    }
+                }
+            }
+        }
+        self.word(";");
+    }
+
+    fn print_ident_or_str_lit(&mut self, value: &IdentOrStrLit) {
+        match value {
+            IdentOrStrLit::Ident(ident) => self.display(ident),
+            IdentOrStrLit::StrLit(lit) => self.print_str_lit(lit),
+        }
+    }
+
+    fn print_import(&mut self, import: &ImportDirective<'_>) {
+        self.word("import ");
+        match &import.items {
+            ImportItems::Plain(alias) => {
+                self.print_str_lit(&import.path);
+                if let Some(alias) = alias {
+                    self.display(format_args!(" as {alias}"));
+                }
+            }
+            ImportItems::Aliases(aliases) => {
+                self.word("{");
+                self.commasep(aliases, |this, (name, alias)| {
+                    this.display(name);
+                    if let Some(alias) = alias {
+                        this.display(format_args!(" as {alias}"));
+                    }
+                });
+                self.word("} from ");
+                self.print_str_lit(&import.path);
+            }
+            ImportItems::Glob(alias) => {
+                self.word("*");
+                if let Some(alias) = alias {
+                    self.display(format_args!(" as {alias}"));
+                }
+                self.word(" from ");
+                self.print_str_lit(&import.path);
+            }
+        }
+        self.word(";");
+    }
+
+    fn print_using(&mut self, using: &UsingDirective<'_>) {
+        self.word("using ");
+        match &using.list {
+            UsingList::Single(path) => self.display(path),
+            UsingList::Multiple(paths) => {
+                self.word("{");
+                self.commasep(paths, |this, (path, op)| {
+                    this.display(path);
+                    if let Some(op) = op {
+                        let op = op.to_op().either(UnOpKind::to_str, BinOpKind::to_str);
+                        this.display(format_args!(" as {op}"));
+                    }
+                });
+                self.word("}");
+            }
+        }
+        self.word(" for ");
+        match &using.ty {
+            Some(ty) => self.print_ty(ty),
+            None => self.word("*"),
+        }
+        if using.global {
+            self.word(" global");
+        }
+    }
+
+    fn print_contract(&mut self, contract: &ItemContract<'_>) {
+        let ItemContract { kind, name, bases, body } = contract;
+        self.display(format_args!("{kind} {name} "));
+        if !bases.is_empty() {
+            self.word("is ");
+            self.commasep(bases, Self::print_modifier);
+            self.word(" ");
+        }
+        if body.is_empty() {
+            self.word("{}");
+            return;
+        }
+        self.word("{");
+        self.indent += 1;
+        self.newline();
+        self.print_items(body);
+        self.indent -= 1;
+        self.newline();
+        self.word("}");
+    }
+
+    fn print_function(&mut self, function: &ItemFunction<'_>) {
+        let ItemFunction { kind, header, body } = function;
+        let FunctionHeader {
+            name,
+            parameters,
+            visibility,
+            state_mutability,
+            modifiers,
+            virtual_,
+            override_,
+            returns,
+        } = header;
+        self.display(kind);
+        if let Some(name) = name {
+            self.display(format_args!(" {name}"));
+        }
+        self.print_params(parameters);
+        if let Some(visibility) = visibility {
+            self.display(format_args!(" {visibility}"));
+        }
+        if !state_mutability.is_non_payable() {
+            self.display(format_args!(" {state_mutability}"));
+        }
+        if *virtual_ {
+            self.word(" virtual");
+        }
+        if let Some(override_) = override_ {
+            self.word(" ");
+            self.print_override(override_);
+        }
+        for modifier in modifiers.iter() {
+            self.word(" ");
+            self.print_modifier(modifier);
+        }
+        if !returns.is_empty() {
+            self.word(" returns ");
+            self.print_params(returns);
+        }
+        match body {
+            Some(body) => {
+                self.word(" ");
+                self.print_stmts(body);
+            }
+            None => self.word(";"),
+        }
+    }
+
+    fn print_modifier(&mut self, modifier: &Modifier<'_>) {
+        self.display(&modifier.name);
+        if !modifier.arguments.is_empty() {
+            self.print_call_args(&modifier.arguments);
+        }
+    }
+
+    fn print_override(&mut self, override_: &Override<'_>) {
+        self.word("override");
+        if !override_.paths.is_empty() {
+            self.word("(");
+            self.commasep(&override_.paths, |this, path| this.display(path));
+            self.word(")");
+        }
+    }
+
+    fn print_params(&mut self, params: &[VariableDefinition<'_>]) {
+        self.word("(");
+        self.commasep(params, Self::print_var);
+        self.word(")");
+    }
+
+    /// Prints a variable definition, without a trailing semicolon.
+    pub fn print_var(&mut self, var: &VariableDefinition<'_>) {
+        let VariableDefinition {
+            span: _,
+            ty,
+            visibility,
+            mutability,
+            data_location,
+            override_,
+            indexed,
+            name,
+            initializer,
+        } = var;
+        self.print_ty(ty);
+        if *indexed {
+            self.word(" indexed");
+        }
+        if let Some(data_location) = data_location {
+            self.display(format_args!(" {data_location}"));
+        }
+        if let Some(visibility) = visibility {
+            self.display(format_args!(" {visibility}"));
+        }
+        if let Some(mutability) = mutability {
+            self.display(format_args!(" {mutability}"));
+        }
+        if let Some(override_) = override_ {
+            self.word(" ");
+            self.print_override(override_);
+        }
+        if let Some(name) = name {
+            self.display(format_args!(" {name}"));
+        }
+        if let Some(initializer) = initializer {
+            self.word(" = ");
+            self.print_expr(initializer);
+        }
+    }
+
+    /// Prints a type.
+    pub fn print_ty(&mut self, ty: &Type<'_>) {
+        match &ty.kind {
+            TypeKind::Elementary(ty) => self.display(ty),
+            TypeKind::Array(array) => {
+                self.print_ty(&array.element);
+                self.word("[");
+                if let Some(size) = &array.size {
+                    self.print_expr(size);
+                }
+                self.word("]");
+            }
+            TypeKind::Function(f) => {
+                self.word("function");
+                self.print_params(&f.parameters);
+                if let Some(visibility) = f.visibility {
+                    self.display(format_args!(" {visibility}"));
+                }
+                if !f.state_mutability.is_non_payable() {
+                    self.display(format_args!(" {}", f.state_mutability));
+                }
+                if !f.returns.is_empty() {
+                    self.word(" returns ");
+                    self.print_params(&f.returns);
+                }
+            }
+            TypeKind::Mapping(mapping) => {
+                self.word("mapping(");
+                self.print_ty(&mapping.key);
+                if let Some(key_name) = mapping.key_name {
+                    self.display(format_args!(" {key_name}"));
+                }
+                self.word(" => ");
+                self.print_ty(&mapping.value);
+                if let Some(value_name) = mapping.value_name {
+                    self.display(format_args!(" {value_name}"));
+                }
+                self.word(")");
+            }
+            TypeKind::Custom(path) => self.display(path),
+        }
+    }
+
+    /// Prints a statement, including its doc-comments.
+    pub fn print_stmt(&mut self, stmt: &Stmt<'_>) {
+        self.print_docs(&stmt.docs);
+        match &stmt.kind {
+            StmtKind::Assembly(StmtAssembly { dialect, flags, block }) => {
+                self.word("assembly ");
+                if let Some(dialect) = dialect {
+                    self.print_str_lit(dialect);
+                    self.word(" ");
+                }
+                if !flags.is_empty() {
+                    self.word("(");
+                    self.commasep(flags, Self::print_str_lit);
+                    self.word(") ");
+                }
+                self.print_yul_block(block);
+            }
+            StmtKind::DeclSingle(var) => {
+                self.print_var(var);
+                self.word(";");
+            }
+            StmtKind::DeclMulti(vars, expr) => {
+                self.word("(");
+                self.commasep(vars, |this, var| {
+                    if let Some(var) = var {
+                        this.print_var(var);
+                    }
+                });
+                self.word(") = ");
+                self.print_expr(expr);
+                self.word(";");
+            }
+            StmtKind::Block(stmts) => self.print_stmts(stmts),
+            StmtKind::Break => self.word("break;"),
+            StmtKind::Continue => self.word("continue;"),
+            StmtKind::DoWhile(body, cond) => {
+                self.word("do ");
+                self.print_stmt(body);
+                self.word(" while (");
+                self.print_expr(cond);
+                self.word(");");
+            }
+            StmtKind::Emit(path, args) => {
+                self.display(format_args!("emit {path}"));
+                self.print_call_args(args);
+                self.word(";");
+            }
+            StmtKind::Expr(expr) => {
+                self.print_expr(expr);
+                self.word(";");
+            }
+            StmtKind::For { init, cond, next, body } => {
+                self.word("for (");
+                match init {
+                    Some(init) => self.print_stmt(init),
+                    None => self.word(";"),
+                }
+                if let Some(cond) = cond {
+                    self.word(" ");
+                    self.print_expr(cond);
+                }
+                self.word(";");
+                if let Some(next) = next {
+                    self.word(" ");
+                    self.print_expr(next);
+                }
+                self.word(") ");
+                self.print_stmt(body);
+            }
+            StmtKind::If(cond, then, els) => {
+                self.word("if (");
+                self.print_expr(cond);
+                self.word(") ");
+                match els {
+                    // Keep the `else` from binding to an `if` nested in the `then` branch.
+                    Some(_) if has_dangling_if(then) => {
+                        self.print_stmts(std::slice::from_ref(&**then));
+                    }
+                    _ => self.print_stmt(then),
+                }
+                if let Some(els) = els {
+                    self.word(" else ");
+                    self.print_stmt(els);
+                }
+            }
+            StmtKind::Return(expr) => {
+                self.word("return");
+                if let Some(expr) = expr {
+                    self.word(" ");
+                    self.print_expr(expr);
+                }
+                self.word(";");
+            }
+            StmtKind::Revert(path, args) => {
+                self.display(format_args!("revert {path}"));
+                self.print_call_args(args);
+                self.word(";");
+            }
+            StmtKind::Try(try_) => {
+                let StmtTry { expr, returns, block, catch } = &**try_;
+                self.word("try ");
+                self.print_expr(expr);
+                if !returns.is_empty() {
+                    self.word(" returns ");
+                    self.print_params(returns);
+                }
+                self.word(" ");
+                self.print_stmts(block);
+                for CatchClause { name, args, block } in catch.iter() {
+                    self.word(" catch ");
+                    if let Some(name) = name {
+                        self.display(name);
+                    }
+                    if name.is_some() || !args.is_empty() {
+                        self.print_params(args);
+                        self.word(" ");
+                    }
+                    self.print_stmts(block);
+                }
+            }
+            StmtKind::UncheckedBlock(stmts) => {
+                self.word("unchecked ");
+                self.print_stmts(stmts);
+            }
+            StmtKind::While(cond, body) => {
+                self.word("while (");
+                self.print_expr(cond);
+                self.word(") ");
+                self.print_stmt(body);
+            }
+            StmtKind::Placeholder => self.word("_;"),
+            StmtKind::Err(_) => self.word("/*ERROR*/"),
+        }
+    }
+
+    fn print_stmts(&mut self, stmts: &[Stmt<'_>]) {
+        self.print_block(stmts, Self::print_stmt);
+    }
+
+    /// Prints an expression.
+    pub fn print_expr(&mut self, expr: &Expr<'_>) {
+        match &expr.kind {
+            ExprKind::Array(exprs) => {
+                self.word("[");
+                self.commasep(exprs, |this, expr| this.print_expr(expr));
+                self.word("]");
+            }
+            ExprKind::Assign(lhs, op, rhs) => {
+                self.print_expr_prec(lhs, Prec::Or);
+                self.word(" ");
+                if let Some(op) = op {
+                    self.display(op);
+                }
+                self.word("= ");
+                self.print_expr_prec(rhs, Prec::Assign);
+            }
+            ExprKind::Binary(lhs, op, rhs) => {
+                let prec = Prec::of_bin_op(op.kind);
+                // `**` is right-associative; parenthesize both sides to avoid relying on it.
+                let (lhs_prec, rhs_prec) = if op.kind == BinOpKind::Pow {
+                    (Prec::Postfix, Prec::Postfix)
+                } else {
+                    (prec, prec.next())
+                };
+                self.print_expr_prec(lhs, lhs_prec);
+                self.display(format_args!(" {op} "));
+                self.print_expr_prec(rhs, rhs_prec);
+            }
+            ExprKind::Call(callee, args) => {
+                match callee.kind {
+                    ExprKind::New(_) => self.print_expr(callee),
+                    _ => self.print_expr_prec(callee, Prec::Primary),
+                }
+                self.print_call_args(args);
+            }
+            ExprKind::CallOptions(callee, options) => {
+                self.print_expr_prec(callee, Prec::Primary);
+                self.print_named_args(options);
+            }
+            ExprKind::Delete(expr) => {
+                self.word("delete ");
+                self.print_expr_prec(expr, Prec::Prefix);
+            }
+            ExprKind::Ident(ident) => self.display(ident),
+            ExprKind::Index(expr, index) => {
+                self.print_expr_prec(expr, Prec::Primary);
+                self.word("[");
+                match index {
+                    IndexKind::Index(index) => {
+                        if let Some(index) = index {
+                            self.print_expr(index);
+                        }
+                    }
+                    IndexKind::Range(start, end) => {
+                        if let Some(start) = start {
+                            self.print_expr(start);
+                        }
+                        self.word(":");
+                        if let Some(end) = end {
+                            self.print_expr(end);
+                        }
+                    }
+                }
+                self.word("]");
+            }
+            ExprKind::Lit(lit, sub_denomination) => {
+                self.print_lit(lit);
+                if let Some(sub_denomination) = sub_denomination {
+                    self.display(format_args!(" {sub_denomination}"));
+                }
+            }
+            ExprKind::Member(expr, member) => {
+                self.print_expr_prec(expr, Prec::Primary);
+                self.display(format_args!(".{member}"));
+            }
+            ExprKind::New(ty) => {
+                self.word("new ");
+                self.print_ty(ty);
+            }
+            ExprKind::Payable(args) => {
+                self.word("payable");
+                self.print_call_args(args);
+            }
+            ExprKind::Ternary(cond, then, els) => {
+                self.print_expr_prec(cond, Prec::Or);
+                self.word(" ? ");
+                self.print_expr_prec(then, Prec::Ternary);
+                self.word(" : ");
+                self.print_expr_prec(els, Prec::Ternary);
+            }
+            ExprKind::Tuple(exprs) => {
+                self.word("(");
+                self.commasep(exprs, |this, expr| {
+                    if let Some(expr) = expr {
+                        this.print_expr(expr);
+                    }
+                });
+                self.word(")");
+            }
+            ExprKind::TypeCall(ty) => {
+                self.word("type(");
+                self.print_ty(ty);
+                self.word(")");
+            }
+            ExprKind::Type(ty) => self.print_ty(ty),
+            ExprKind::Unary(op, expr) => {
+                if op.kind.is_prefix() {
+                    self.display(op);
+                    // Avoid gluing `-` and `-x` into `--x`.
+                    let glued = match &expr.kind {
+                        ExprKind::Unary(inner, _) if inner.kind.is_prefix() => {
+                            op.kind.to_str().as_bytes().last()
+                                == inner.kind.to_str().as_bytes().first()
+                        }
+                        _ => false,
+                    };
+                    if glued {
+                        self.word("(");
+                        self.print_expr(expr);
+                        self.word(")");
+                    } else {
+                        self.print_expr_prec(expr, Prec::Prefix);
+                    }
+                } else {
+                    self.print_expr_prec(expr, Prec::Postfix);
+                    self.display(op);
+                }
+            }
+        }
+    }
+
+    /// Prints an expression, parenthesized if it binds looser than `prec`.
+    fn print_expr_prec(&mut self, expr: &Expr<'_>, prec: Prec) {
+        if Prec::of(expr) < prec {
+            self.word("(");
+            self.print_expr(expr);
+            self.word(")");
+        } else {
+            self.print_expr(expr);
+        }
+    }
+
+    fn print_call_args(&mut self, args: &CallArgs<'_>) {
+        self.word("(");
+        match args {
+            CallArgs::Unnamed(exprs) => self.commasep(exprs, |this, expr| this.print_expr(expr)),
+            CallArgs::Named(args) => self.print_named_args(args),
+        }
+        self.word(")");
+    }
+
+    fn print_named_args(&mut self, args: &[NamedArg<'_>]) {
+        self.word("{");
+        self.commasep(args, |this, NamedArg { name, value }| {
+            this.display(format_args!("{name}: "));
+            this.print_expr(value);
+        });
+        self.word("}");
+    }
+
+    /// Prints a literal.
+    pub fn print_lit(&mut self, lit: &Lit) {
+        match &lit.kind {
+            LitKind::Str(kind, bytes) => self.print_str(*kind, bytes),
+            LitKind::Number(_)
+            | LitKind::Rational(_)
+            | LitKind::Address(_)
+            | LitKind::Bool(_)
+            | LitKind::Err(_) => self.display(lit.symbol),
+        }
+    }
+
+    /// Prints a string literal from its value, escaping it as necessary.
+    fn print_str(&mut self, kind: StrKind, bytes: &[u8]) {
+        match kind {
+            StrKind::Str => {
+                self.word("\"");
+                bytes.iter().for_each(|&b| self.escape_byte(b));
+            }
+            StrKind::Unicode => {
+                self.word("unicode\"");
+                match std::str::from_utf8(bytes) {
+                    Ok(s) => {
+                        for c in s.chars() {
+                            if c.is_ascii() {
+                                self.escape_byte(c as u8);
+                            } else {
+                                self.out.push(c);
+                            }
+                        }
+                    }
+                    Err(_) => bytes.iter().for_each(|&b| self.escape_byte(b)),
+                }
+            }
+            StrKind::Hex => {
+                self.word("hex\"");
+                self.word(&alloy_primitives::hex::encode(bytes));
+            }
+        }
+        self.word("\"");
+    }
+
+    fn escape_byte(&mut self, b: u8) {
+        match b {
+            b'"' => self.word("\\\""),
+            b'\\' => self.word("\\\\"),
+            b'\n' => self.word("\\n"),
+            b'\r' => self.word("\\r"),
+            b'\t' => self.word("\\t"),
+            b' '..=b'~' => self.out.push(b as char),
+            _ => self.display(format_args!("\\x{b:02x}")),
+        }
+    }
+
+    /// Prints a string literal whose value is kept as written in the source.
+    fn print_str_lit(&mut self, lit: &StrLit) {
+        let value = lit.value.as_str();
+        let quote = if value.contains('"') { '\'' } else { '"' };
+        self.display(format_args!("{quote}{value}{quote}"));
+    }
+
+    fn print_docs(&mut self, docs: &[DocComment<'_>]) {
+        for doc in docs {
+            match doc.kind {
+                CommentKind::Line => self.display(format_args!("///{}", doc.symbol)),
+                CommentKind::Block => self.display(format_args!("/**{}*/", doc.symbol)),
+            }
+            self.newline();
+        }
+    }
+
+    /// Prints a Yul object.
+    pub fn print_yul_object(&mut self, object: &yul::Object<'_>) {
+        let yul::Object { docs, span: _, name, code, children, data } = object;
+        self.print_docs(docs);
+        self.word("object ");
+        self.print_str_lit(name);
+        self.word(" {");
+        self.indent += 1;
+        self.newline();
+        self.word("code ");
+        self.print_yul_block(&code.code);
+        for child in children.iter() {
+            self.newline();
+            self.print_yul_object(child);
+        }
+        for data in data.iter() {
+            self.newline();
+            self.word("data ");
+            self.print_str_lit(&data.name);
+            self.word(" ");
+            self.print_lit(&data.data);
+        }
+        self.indent -= 1;
+        self.newline();
+        self.word("}");
+    }
+
+    /// Prints a Yul statement, including its doc-comments.
+    pub fn print_yul_stmt(&mut self, stmt: &yul::Stmt<'_>) {
+        self.print_docs(&stmt.docs);
+        match &stmt.kind {
+            yul::StmtKind::Block(block) => self.print_yul_block(block),
+            yul::StmtKind::AssignSingle(path, expr) => {
+                self.display(format_args!("{path} := "));
+                self.print_yul_expr(expr);
+            }
+            yul::StmtKind::AssignMulti(paths, call) => {
+                self.commasep(paths, |this, path| this.display(path));
+                self.word(" := ");
+                self.print_yul_call(call);
+            }
+            yul::StmtKind::Expr(call) => self.print_yul_call(call),
+            yul::StmtKind::If(cond, body) => {
+                self.word("if ");
+                self.print_yul_expr(cond);
+                self.word(" ");
+                self.print_yul_block(body);
+            }
+            yul::StmtKind::For { init, cond, step, body } => {
+                self.word("for ");
+                self.print_yul_block(init);
+                self.word(" ");
+                self.print_yul_expr(cond);
+                self.word(" ");
+                self.print_yul_block(step);
+                self.word(" ");
+                self.print_yul_block(body);
+            }
+            yul::StmtKind::Switch(yul::StmtSwitch { selector, branches, default_case }) => {
+                self.word("switch ");
+                self.print_yul_expr(selector);
+                for yul::StmtSwitchCase { constant, body } in branches.iter() {
+                    self.newline();
+                    self.word("case ");
+                    self.print_lit(constant);
+                    self.word(" ");
+                    self.print_yul_block(body);
+                }
+                if let Some(default_case) = default_case {
+                    self.newline();
+                    self.word("default ");
+                    self.print_yul_block(default_case);
+                }
+            }
+            yul::StmtKind::Leave => self.word("leave"),
+            yul::StmtKind::Break => self.word("break"),
+            yul::StmtKind::Continue => self.word("continue"),
+            yul::StmtKind::FunctionDef(yul::Function { name, parameters, returns, body }) => {
+                self.display(format_args!("function {name}("));
+                self.commasep(parameters, |this, param| this.display(param));
+                self.word(")");
+                if !returns.is_empty() {
+                    self.word(" -> ");
+                    self.commasep(returns, |this, ret| this.display(ret));
+                }
+                self.word(" ");
+                self.print_yul_block(body);
+            }
+            yul::StmtKind::VarDecl(names, value) => {
+                self.word("let ");
+                self.commasep(names, |this, name| this.display(name));
+                if let Some(value) = value {
+                    self.word(" := ");
+                    self.print_yul_expr(value);
+                }
+            }
+        }
+    }
+
+    fn print_yul_block(&mut self, block: &[yul::Stmt<'_>]) {
+        self.print_block(block, Self::print_yul_stmt);
+    }
+
+    /// Prints a Yul expression.
+    pub fn print_yul_expr(&mut self, expr: &yul::Expr<'_>) {
+        match &expr.kind {
+            yul::ExprKind::Path(path) => self.display(path),
+            yul::ExprKind::Call(call) => self.print_yul_call(call),
+            yul::ExprKind::Lit(lit) => self.print_lit(lit),
+        }
+    }
+
+    fn print_yul_call(&mut self, call: &yul::ExprCall<'_>) {
+        self.display(format_args!("{}(", call.name));
+        self.commasep(&call.arguments, |this, arg| this.print_yul_expr(arg));
+        self.word(")");
+    }
+
+    /// Prints a brace-delimited block with one element per line, or `{}` if it is empty.
+    fn print_block<T>(&mut self, elems: &[T], mut f: impl FnMut(&mut Self, &T)) {
+        if elems.is_empty() {
+            self.word("{}");
+            return;
+        }
+        self.word("{");
+        self.indent += 1;
+        for elem in elems {
+            self.newline();
+            f(self, elem);
+        }
+        self.indent -= 1;
+        self.newline();
+        self.word("}");
+    }
+
+    fn commasep<T>(&mut self, elems: &[T], mut f: impl FnMut(&mut Self, &T)) {
+        for (i, elem) in elems.iter().enumerate() {
+            if i > 0 {
+                self.word(", ");
+            }
+            f(self, elem);
+        }
+    }
+
+    fn word(&mut self, s: &str) {
+        self.out.push_str(s);
+    }
+
+    fn display(&mut self, value: impl fmt::Display) {
+        let _ = write!(self.out, "{value}");
+    }
+
+    fn newline(&mut self) {
+        let len = self.out.trim_end_matches(' ').len();
+        self.out.truncate(len);
+        self.out.push('\n');
+        self.out.extend(std::iter::repeat(' ').take(self.indent * 4));
+    }
+}
+
+/// Returns `true` if the statement ends with an `if` without an `else`, which would take the `else`
+/// of an enclosing `if`.
+fn has_dangling_if(stmt: &Stmt<'_>) -> bool {
+    match &stmt.kind {
+        StmtKind::If(_, _, None) => true,
+        StmtKind::If(_, _, Some(els)) => has_dangling_if(els),
+        StmtKind::For { body, .. } | StmtKind::While(_, body) => has_dangling_if(body),
+        _ => false,
+    }
+}
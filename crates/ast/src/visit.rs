@@ -8,6 +8,10 @@ use std::ops::ControlFlow;
 
 declare_visitors! {
     /// AST traversal.
+    ///
+    /// Every `visit_*` method has a corresponding `walk_*` method that recurses into the node's
+    /// children. When overriding a `visit_*` method, code placed before or after the call to
+    /// `walk_*` acts as a pre- or post-order hook for that node, respectively.
     pub trait Visit VisitMut <'ast> {
         /// The value returned when breaking from the traversal.
         ///
@@ -26,7 +30,7 @@ declare_visitors! {
         }
 
         fn visit_item(&mut self, item: &'ast #mut Item<'ast>) -> ControlFlow<Self::BreakValue> {
-            let Item { docs, span, kind } = item;
+            let Item { id: _, docs, span, kind } = item;
             self.visit_span #_mut(span)?;
             self.visit_doc_comments #_mut(docs)?;
             match kind {
@@ -53,7 +57,8 @@ declare_visitors! {
 
         fn visit_import_directive(&mut self, import: &'ast #mut ImportDirective<'ast>) -> ControlFlow<Self::BreakValue> {
             let ImportDirective { path, items } = import;
-            let _ = path; // TODO: ?
+            let StrLit { span, value: _ } = path;
+            self.visit_span #_mut(span)?;
             match items {
                 ImportItems::Plain(alias) => {
                     if let Some(alias) = alias {
@@ -162,13 +167,16 @@ declare_visitors! {
                 visibility: _,
                 mutability: _,
                 data_location: _,
-                override_: _,
+                override_,
                 indexed: _,
                 name,
                 initializer,
             } = var;
             self.visit_span #_mut(span)?;
             self.visit_ty #_mut(ty)?;
+            if let Some(override_) = override_ {
+                self.visit_override #_mut(override_)?;
+            }
             if let Some(name) = name {
                 self.visit_ident #_mut(name)?;
             }
@@ -218,7 +226,7 @@ declare_visitors! {
                 state_mutability: _,
                 modifiers,
                 virtual_: _,
-                override_: _,
+                override_,
                 returns,
             } = header;
             if let Some(name) = name {
@@ -228,10 +236,22 @@ declare_visitors! {
             for modifier in modifiers.iter #_mut() {
                 self.visit_modifier #_mut(modifier)?;
             }
+            if let Some(override_) = override_ {
+                self.visit_override #_mut(override_)?;
+            }
             self.visit_parameter_list #_mut(returns)?;
             ControlFlow::Continue(())
         }
 
+        fn visit_override(&mut self, override_: &'ast #mut Override<'ast>) -> ControlFlow<Self::BreakValue> {
+            let Override { span, paths } = override_;
+            self.visit_span #_mut(span)?;
+            for path in paths.iter #_mut() {
+                self.visit_path #_mut(path)?;
+            }
+            ControlFlow::Continue(())
+        }
+
         fn visit_modifier(&mut self, modifier: &'ast #mut Modifier<'ast>) -> ControlFlow<Self::BreakValue> {
             let Modifier { name, arguments } = modifier;
             self.visit_path #_mut(name)?;
@@ -262,7 +282,7 @@ declare_visitors! {
         }
 
         fn visit_stmt(&mut self, stmt: &'ast #mut Stmt<'ast>) -> ControlFlow<Self::BreakValue> {
-            let Stmt { docs, span, kind } = stmt;
+            let Stmt { id: _, docs, span, kind } = stmt;
             self.visit_doc_comments #_mut(docs)?;
             self.visit_span #_mut(span)?;
             match kind {
@@ -334,7 +354,7 @@ declare_visitors! {
                     self.visit_expr #_mut(cond)?;
                     self.visit_stmt #_mut(stmt)?;
                 }
-                StmtKind::Placeholder => {}
+                StmtKind::Placeholder | StmtKind::Err(_) => {}
             }
             ControlFlow::Continue(())
         }
@@ -374,7 +394,7 @@ declare_visitors! {
         }
 
         fn visit_expr(&mut self, expr: &'ast #mut Expr<'ast>) -> ControlFlow<Self::BreakValue> {
-            let Expr { span, kind } = expr;
+            let Expr { id: _, span, kind } = expr;
             self.visit_span #_mut(span)?;
             match kind {
                 ExprKind::Array(exprs) => {
@@ -597,8 +617,8 @@ declare_visitors! {
             ControlFlow::Continue(())
         }
 
-        fn visit_doc_comment(&mut self, doc_comment: &'ast #mut DocComment) -> ControlFlow<Self::BreakValue> {
-            let DocComment { kind: _, span, symbol: _ } = doc_comment;
+        fn visit_doc_comment(&mut self, doc_comment: &'ast #mut DocComment<'ast>) -> ControlFlow<Self::BreakValue> {
+            let DocComment { kind: _, span, symbol: _, natspec: _ } = doc_comment;
             self.visit_span #_mut(span)?;
             ControlFlow::Continue(())
         }
@@ -0,0 +1,30 @@
+//! Warning baselines, enabled with `--baseline`.
+//!
+//! A baseline file lists the warnings of a project at some point in time. Passing it with
+//! `--baseline` suppresses these warnings, so that only new warnings are reported; this allows
+//! legacy projects to enable new lints without fixing all the existing warnings first.
+
+use solar_interface::{
+    diagnostics::{Baseline, DiagCtxt},
+    Result,
+};
+use std::path::Path;
+
+/// Reads the baseline file at `path`.
+pub(crate) fn read(dcx: &DiagCtxt, path: &Path) -> Result<Baseline> {
+    let contents = std::fs::read_to_string(path).map_err(|e| {
+        let msg = format!("failed to read baseline {}: {e}", path.display());
+        dcx.err(msg).help("generate it with `--write-baseline`").emit()
+    })?;
+    serde_json::from_str(&contents)
+        .map_err(|e| dcx.err(format!("failed to parse baseline {}: {e}", path.display())).emit())
+}
+
+/// Writes the warnings recorded by `dcx` to the baseline file at `path`.
+pub(crate) fn write(dcx: &DiagCtxt, path: &Path) -> Result {
+    let baseline = dcx.recorded_baseline().unwrap_or_default();
+    let mut contents = serde_json::to_string_pretty(&baseline).unwrap();
+    contents.push('\n');
+    std::fs::write(path, contents)
+        .map_err(|e| dcx.err(format!("failed to write baseline {}: {e}", path.display())).emit())
+}
@@ -0,0 +1,293 @@
+//! Compilation cache, enabled with `--cache-dir`.
+//!
+//! The cache records, for each input file that compiled without diagnostics, the hashes of the
+//! source files that it loads, including through imports, along with a fingerprint of the
+//! compiler version and options. Only the input files for which one of these files changed are
+//! compiled again, together with the files that they import.
+//!
+//! The outputs written to `--out-dir` cover all the input files, so when outputs are emitted, the
+//! hashes of the output files are recorded too, and all the input files are compiled again if
+//! anything changed.
+//!
+//! The cache also records the import candidates that did not exist, such as the same file in an
+//! earlier import path, since creating them changes how imports resolve. Creating any of them
+//! invalidates the whole cache.
+
+use crate::{import_graph::ImportGraph, Compiler};
+use alloy_primitives::keccak256;
+use serde::{Deserialize, Serialize};
+use solar_interface::{
+    source_map::{FileName, FileResolver},
+    Result,
+};
+use solar_sema::CombinedJson;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+/// The name of the cache file in the cache directory.
+const CACHE_FILE: &str = "solar-cache.json";
+
+/// The files that can be written to `--out-dir`.
+const OUTPUT_FILES: &[&str] =
+    &["combined.json", "call_graph.dot", "import_graph.dot", "ast_pretty.sol", "interfaces.sol"];
+
+/// The contents of the cache file.
+#[derive(Default, Serialize, Deserialize)]
+struct Cache {
+    /// The hash of the compiler version and options.
+    fingerprint: String,
+    /// The input files that compiled without diagnostics, after expanding directories and glob
+    /// patterns, with the hashes of the source files that they load.
+    inputs: BTreeMap<PathBuf, BTreeMap<PathBuf, String>>,
+    /// The hashes of the output files.
+    outputs: BTreeMap<PathBuf, String>,
+    /// The paths that were looked up when resolving the files, but did not exist.
+    missing: Vec<PathBuf>,
+}
+
+impl Cache {
+    /// Returns `true` if the given input file was compiled, and none of the source files that it
+    /// loads were modified or removed since.
+    fn is_fresh(&self, input: &Path) -> bool {
+        self.inputs.get(input).is_some_and(is_unchanged)
+    }
+
+    /// Returns `true` if none of the output files were modified or removed.
+    fn outputs_are_fresh(&self) -> bool {
+        is_unchanged(&self.outputs)
+    }
+
+    /// Returns `true` if any of the missing files was created.
+    fn missing_was_created(&self) -> bool {
+        self.missing.iter().any(|path| path.exists())
+    }
+}
+
+/// Compiles the input files that were not already compiled with the same options and without any
+/// diagnostics, or whose sources changed since.
+///
+/// Compilations that read from stdin, that print anything other than diagnostics, or that write a
+/// baseline are not cached.
+pub(crate) fn compile(compiler: &Compiler, dir: &Path, file_resolver: FileResolver<'_>) -> Result {
+    let sess = &compiler.sess;
+    let (stdin, _, _) = compiler.inputs();
+    let prints = sess.dump.is_some()
+        || sess.ast_stats
+        || sess.time_passes
+        || (!sess.emit.is_empty() && sess.out_dir.is_none());
+    // The baseline is written from the warnings of the compilation, so it must not be skipped.
+    if stdin || prints || compiler.args.write_baseline {
+        return compiler.compile(file_resolver);
+    }
+
+    let fingerprint = fingerprint(compiler, &file_resolver);
+    let inputs = compiler.input_paths()?;
+    if inputs.is_empty() {
+        return compiler.compile(file_resolver);
+    }
+    let path = dir.join(CACHE_FILE);
+    let cache = std::fs::read(&path).ok().and_then(|s| serde_json::from_slice::<Cache>(&s).ok());
+    let mut cache = cache
+        .filter(|cache| cache.fingerprint == fingerprint && !cache.missing_was_created())
+        .unwrap_or_else(|| Cache { fingerprint, ..Default::default() });
+    cache.inputs.retain(|input, _| inputs.contains(input));
+
+    // The outputs are written for all the input files at once.
+    let emits = !sess.emit.is_empty();
+    let dirty = inputs.iter().filter(|input| !cache.is_fresh(input)).cloned().collect::<Vec<_>>();
+    if dirty.is_empty() && (!emits || cache.outputs_are_fresh()) {
+        return Ok(());
+    }
+
+    // The clones of the resolver share the missing files.
+    let graph = if emits {
+        cache.inputs.clear();
+        compiler.compile(file_resolver.clone())?;
+        None
+    } else {
+        let mut output = CombinedJson::new();
+        compiler.compile_files(file_resolver.clone(), &dirty, &mut output)?;
+        let mut graph = ImportGraph::default();
+        graph.update(&output.dependencies);
+        Some(graph)
+    };
+    if sess.dcx.err_count() > 0 || sess.dcx.warn_count() > 0 {
+        return Ok(());
+    }
+
+    // Without the import graph, every input file depends on all the loaded files.
+    let loaded = sess
+        .source_map()
+        .files()
+        .iter()
+        .filter_map(|file| match &file.name {
+            FileName::Real(path) => Some(path.clone()),
+            _ => None,
+        })
+        .collect::<BTreeSet<_>>();
+    let compiled = if emits { &inputs } else { &dirty };
+    for input in compiled {
+        let sources = graph.as_ref().map_or_else(|| loaded.clone(), |graph| graph.imports(input));
+        let Some(sources) = hash_files(&sources) else { return Ok(()) };
+        cache.inputs.insert(input.clone(), sources);
+    }
+    let outputs = sess
+        .out_dir
+        .iter()
+        .flat_map(|dir| OUTPUT_FILES.iter().map(|name| dir.join(name)))
+        .filter(|path| path.is_file())
+        .collect::<Vec<_>>();
+    let Some(outputs) = hash_files(&outputs) else { return Ok(()) };
+    cache.outputs = outputs;
+    cache.missing.extend(file_resolver.missing_files());
+    cache.missing.sort_unstable();
+    cache.missing.dedup();
+    let r = std::fs::create_dir_all(dir).and_then(|()| {
+        let json = serde_json::to_vec(&cache)?;
+        std::fs::write(&path, json)
+    });
+    if let Err(e) = r {
+        sess.dcx.warn(format!("failed to write the compilation cache: {e}")).emit();
+    }
+    Ok(())
+}
+
+/// Returns the hash of the compiler version, the working directory, and the options that can change
+/// the outputs or the diagnostics of the compilation, including the contents of the baseline.
+fn fingerprint(compiler: &Compiler, file_resolver: &FileResolver<'_>) -> String {
+    let Compiler { sess, args } = compiler;
+    let baseline = args.baseline.as_ref().map(|path| (path, std::fs::read(path).ok()));
+    let options = format!(
+        "{:?}",
+        (
+            crate::version::SHORT_VERSION,
+            std::env::current_dir().ok(),
+            (sess.language, sess.evm_version, &sess.solidity_version, sess.stop_after),
+            (&sess.emit, &sess.out_dir, sess.pretty_json, sess.metadata_hash, sess.cbor_metadata),
+            (sess.optimize, sess.optimizer_runs, &sess.yul_optimizations, &sess.libraries),
+            (file_resolver.get_import_paths(), file_resolver.get_import_maps()),
+            (args.node_modules, &args.allow_paths),
+            (&args.allow, &args.warn, &args.deny, args.deny_warnings),
+            (baseline, args.write_baseline),
+        )
+    );
+    keccak256(options).to_string()
+}
+
+/// Returns `true` if the given files still have the given hashes.
+fn is_unchanged(hashes: &BTreeMap<PathBuf, String>) -> bool {
+    hash_files(hashes.keys()).as_ref() == Some(hashes)
+}
+
+/// Returns the hashes of the contents of the given files, or `None` if any of them can't be read.
+fn hash_files<'a>(
+    paths: impl IntoIterator<Item = &'a PathBuf>,
+) -> Option<BTreeMap<PathBuf, String>> {
+    paths
+        .into_iter()
+        .map(|path| Some((path.clone(), keccak256(std::fs::read(path).ok()?).to_string())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solar_interface::SourceMap;
+
+    #[test]
+    fn missing_import_candidates() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        std::fs::create_dir_all(dir.join("a")).unwrap();
+        std::fs::create_dir_all(dir.join("b")).unwrap();
+        std::fs::write(dir.join("b/x.sol"), "contract X {}").unwrap();
+
+        let sm = SourceMap::empty();
+        let mut resolver = FileResolver::new(&sm);
+        resolver.add_import_path(dir.join("a"));
+        resolver.add_import_path(dir.join("b"));
+        let file = resolver.clone().resolve_file(Path::new("x.sol"), Some(Path::new("y.sol")));
+        assert_eq!(file.unwrap().src.as_str(), "contract X {}");
+
+        let source = dir.join("b/x.sol");
+        let cache = Cache {
+            inputs: BTreeMap::from([(source.clone(), hash_files([&source]).unwrap())]),
+            missing: resolver.missing_files(),
+            ..Default::default()
+        };
+        assert_eq!(cache.missing, [dir.join("a/x.sol")]);
+        assert!(cache.is_fresh(&source));
+        assert!(!cache.missing_was_created());
+
+        // The import now resolves to both files.
+        std::fs::write(dir.join("a/x.sol"), "contract Y {}").unwrap();
+        assert!(cache.missing_was_created());
+    }
+
+    #[test]
+    fn dependents() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = |name: &str| dir.path().join(name);
+        let write = |name: &str, src: &str| {
+            let header = "// SPDX-License-Identifier: MIT\npragma solidity ^0.8.0;\n";
+            std::fs::write(path(name), format!("{header}{src}")).unwrap();
+        };
+        write("a.sol", "import \"./c.sol\";\ncontract A is C {}");
+        write("b.sol", "contract B {}");
+        write("c.sol", "contract C {}");
+        let cache_dir = path("cache");
+        let args = ["solar", "--no-config", "--cache-dir"].iter().map(PathBuf::from).chain([
+            cache_dir.clone(),
+            path("a.sol"),
+            path("b.sol"),
+        ]);
+        crate::run_compiler_args(crate::parse_args(args).unwrap()).unwrap();
+
+        let cache = std::fs::read(cache_dir.join(CACHE_FILE)).unwrap();
+        let cache = serde_json::from_slice::<Cache>(&cache).unwrap();
+        let canonical = |name: &str| crate::import_graph::canonicalize(&path(name));
+        let sources = |input: &str| cache.inputs[&path(input)].keys().cloned().collect::<Vec<_>>();
+        assert_eq!(sources("a.sol"), [canonical("a.sol"), canonical("c.sol")]);
+        assert_eq!(sources("b.sol"), [canonical("b.sol")]);
+
+        // Only the input files that import the modified file are compiled again.
+        write("c.sol", "contract C { uint x; }");
+        assert!(!cache.is_fresh(&path("a.sol")));
+        assert!(cache.is_fresh(&path("b.sol")));
+    }
+
+    #[test]
+    fn baseline() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = |name: &str| dir.path().join(name).to_str().unwrap().to_string();
+        // Warns about the missing SPDX license identifier.
+        std::fs::write(path("a.sol"), "contract C {}").unwrap();
+        let run = |extra: &[&str]| {
+            let (cache_dir, input) = (path("cache"), path("a.sol"));
+            let args = ["solar", "--no-config", "--cache-dir", &cache_dir, &input];
+            let args = args.iter().chain(extra).copied();
+            crate::run_compiler_args(crate::parse_args(args).unwrap())
+        };
+        let baseline = path("baseline.json");
+        let read_baseline = || std::fs::read_to_string(&baseline).unwrap();
+
+        run(&["--baseline", &baseline, "--write-baseline"]).unwrap();
+        let written = read_baseline();
+        assert!(written.contains("SPDX"), "{written}");
+
+        // Cached, as all the warnings are suppressed.
+        run(&["--baseline", &baseline, "--deny-warnings"]).unwrap();
+        run(&["--baseline", &baseline, "--deny-warnings"]).unwrap();
+
+        // Writing the baseline compiles again instead of writing an empty baseline.
+        run(&["--baseline", &baseline, "--write-baseline"]).unwrap();
+        assert_eq!(read_baseline(), written);
+
+        // The warnings are reported again without the baseline, or with a different baseline.
+        assert!(run(&["--deny-warnings"]).is_err());
+        std::fs::write(&baseline, "{\"warnings\":[]}").unwrap();
+        assert!(run(&["--baseline", &baseline, "--deny-warnings"]).is_err());
+    }
+}
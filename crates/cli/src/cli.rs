@@ -1,8 +1,12 @@
 //! Solar CLI arguments.
 
+use alloy_primitives::Address;
 use clap::{ColorChoice, Parser, ValueHint};
-use solar_config::{CompilerOutput, CompilerStage, Dump, EvmVersion, Language};
-use std::path::PathBuf;
+use solar_config::{CompilerOutput, CompilerStage, Dump, EvmVersion, Language, MetadataHash};
+use solar_optimizer::Sequence;
+use std::{num::NonZeroUsize, path::PathBuf, str::FromStr};
+
+pub use solar_interface::{diagnostics::Lint, source_map::ImportMap};
 
 /// Blazingly fast Solidity compiler.
 #[derive(Parser)]
@@ -10,29 +14,97 @@ use std::path::PathBuf;
     name = "solar",
     version = crate::version::SHORT_VERSION,
     long_version = crate::version::LONG_VERSION,
-    arg_required_else_help = true,
 )]
 #[non_exhaustive]
 pub struct Args {
-    /// Files to compile or import remappings.
+    /// Subcommand to run instead of compiling the inputs.
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Files, directories, or glob patterns to compile, or import remappings.
+    ///
+    /// Defaults to the `src` of the configuration file, if any.
     #[arg(value_hint = ValueHint::FilePath)]
     pub input: Vec<PathBuf>,
     /// Directory to search for files.
-    #[arg(help_heading = "Input options", long, short = 'I', visible_alias = "base-path", value_hint = ValueHint::FilePath)]
+    #[arg(help_heading = "Input options", global = true, long, short = 'I', value_hint = ValueHint::FilePath)]
     pub import_path: Vec<PathBuf>,
+    /// Root of the source tree, like solc's `--base-path`.
+    ///
+    /// Imports are searched in the base path first, then in the include paths, and then in the
+    /// import paths.
+    #[arg(help_heading = "Input options", global = true, long, value_hint = ValueHint::DirPath)]
+    pub base_path: Option<PathBuf>,
+    /// Additional directory to search for files after the base path, like solc's
+    /// `--include-path`.
+    #[arg(help_heading = "Input options", global = true, long, requires = "base_path", value_hint = ValueHint::DirPath)]
+    pub include_path: Vec<PathBuf>,
+    /// Comma separated list of directories that files can be read from, like solc's
+    /// `--allow-paths`.
+    ///
+    /// If specified, reading files outside of these directories, the base path, the include and
+    /// import paths, the directories of the input files, and the targets of the import maps is an
+    /// error. Otherwise, all files can be read.
+    #[arg(
+        help_heading = "Input options",
+        global = true,
+        long,
+        value_delimiter = ',',
+        value_name = "PATHS"
+    )]
+    pub allow_paths: Option<Vec<PathBuf>>,
     /// Map to search for files. Can also be provided as a positional argument.
-    #[arg(help_heading = "Input options", long, short = 'm', value_name = "MAP=PATH")]
+    ///
+    /// A `remappings.txt` file in the current directory is also loaded automatically, unless
+    /// `--no-remappings-txt` is passed.
+    #[arg(
+        help_heading = "Input options",
+        global = true,
+        long,
+        short = 'm',
+        value_name = "[CONTEXT:]MAP=PATH"
+    )]
     pub import_map: Vec<ImportMap>,
-    /// Source code language. Only Solidity is currently implemented.
-    #[arg(help_heading = "Input options", long, value_enum, default_value_t, hide = true)]
+    /// Do not load import maps from `remappings.txt`.
+    #[arg(help_heading = "Input options", global = true, long)]
+    pub no_remappings_txt: bool,
+    /// Resolve non-relative imports that are not found in the import paths in the closest
+    /// `node_modules` directory, like Hardhat.
+    #[arg(help_heading = "Input options", global = true, long)]
+    pub node_modules: bool,
+    /// Path to the configuration file.
+    ///
+    /// Defaults to `solar.toml` in the current directory, or `foundry.toml` if it does not exist.
+    /// Options given on the command line take precedence over the configuration file.
+    #[arg(help_heading = "Input options", global = true, long, value_hint = ValueHint::FilePath)]
+    pub config: Option<PathBuf>,
+    /// Do not load a configuration file.
+    #[arg(help_heading = "Input options", global = true, long, conflicts_with = "config")]
+    pub no_config: bool,
+    /// Skip the files matching this pattern when expanding directory and glob inputs.
+    ///
+    /// Patterns without a `/` are matched against each path component, e.g. `test` or `*.t.sol`.
+    #[arg(help_heading = "Input options", global = true, long, value_name = "PATTERN")]
+    pub exclude: Vec<String>,
+    /// Source code language. Yul sources are only parsed and validated.
+    ///
+    /// Inferred as Yul if all the input files have a `.yul` extension.
+    #[arg(help_heading = "Input options", long, value_enum, default_value_t)]
     pub language: Language,
 
     /// Number of threads to use. Zero specifies the number of logical cores.
     #[arg(long, short = 'j', visible_alias = "jobs", default_value = "8")]
     pub threads: usize,
     /// EVM version.
-    #[arg(long, value_enum, default_value_t)]
-    pub evm_version: EvmVersion,
+    ///
+    /// Defaults to the latest stable version.
+    #[arg(long, value_enum)]
+    pub evm_version: Option<EvmVersion>,
+    /// Solidity version to check `pragma solidity` directives against.
+    ///
+    /// Defaults to the version implemented by the compiler.
+    #[arg(long, value_name = "VERSION")]
+    pub pragma_version: Option<semver::Version>,
     /// Stop execution after the given compiler stage.
     #[arg(long, value_enum)]
     pub stop_after: Option<CompilerStage>,
@@ -40,9 +112,116 @@ pub struct Args {
     /// Directory to write output files.
     #[arg(long, value_hint = ValueHint::DirPath)]
     pub out_dir: Option<PathBuf>,
+    /// Directory to cache the results of the compilation in.
+    ///
+    /// Input files that compiled without diagnostics with the same options are skipped, unless
+    /// they or their imports changed since. All the input files are compiled again if any of them
+    /// or of the written output files changed when outputs are emitted. Outputs written to stdout
+    /// are not cached.
+    #[arg(long, value_hint = ValueHint::DirPath, conflicts_with_all = ["standard_json", "watch"])]
+    pub cache_dir: Option<PathBuf>,
     /// Comma separated list of types of output for the compiler to emit.
     #[arg(long, value_delimiter = ',')]
     pub emit: Vec<CompilerOutput>,
+    /// Comma separated list of outputs to emit as a single JSON object, in the legacy format of
+    /// solc's `--combined-json`.
+    ///
+    /// This is the same as `--emit`, but only accepts the output names supported by solc.
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        value_name = "OUTPUTS",
+        conflicts_with = "emit"
+    )]
+    pub combined_json: Vec<CombinedJsonOutput>,
+    /// The hash of the contract metadata to append to the runtime bytecode.
+    #[arg(long, value_enum, default_value_t)]
+    pub metadata_hash: MetadataHash,
+    /// Do not append the CBOR-encoded metadata to the runtime bytecode.
+    #[arg(long)]
+    pub no_cbor_metadata: bool,
+    /// Run the Yul optimizer on the generated code, and the peephole optimizer on the generated
+    /// assembly.
+    #[arg(long)]
+    pub optimize: bool,
+    /// The expected number of times each opcode of the deployed code is executed.
+    ///
+    /// Lower values optimize for code size, higher values for the cost of execution.
+    #[arg(long, value_name = "RUNS", default_value_t = 200)]
+    pub optimizer_runs: u32,
+    /// The sequence of Yul optimizer steps to run instead of the default one, in the format of
+    /// solc's `--yul-optimizations`.
+    #[arg(long, value_name = "SEQUENCE", requires = "optimize")]
+    pub yul_optimizations: Option<Sequence>,
+    /// Comma separated list of library addresses to link, as `path:Name=0x...`.
+    ///
+    /// The references to the other libraries are left as `__$...$__` placeholders in the
+    /// bytecode.
+    #[arg(long, value_delimiter = ',', value_name = "LIBRARIES")]
+    pub libraries: Vec<Library>,
+    /// Read the standard JSON input from stdin, or from the given input file, and write the
+    /// standard JSON output to stdout.
+    ///
+    /// The source files, import maps, and output options are read from the JSON input instead of
+    /// the command line.
+    #[arg(long, conflicts_with_all = ["import_map", "emit", "combined_json", "out_dir", "config"])]
+    pub standard_json: bool,
+    /// Watch the input files and their imports for changes, and recompile when they are modified.
+    ///
+    /// Only the modified files are read again from disk, and only the input files that import
+    /// them, directly or transitively, are compiled again, unless outputs are emitted.
+    #[arg(long, conflicts_with = "standard_json")]
+    pub watch: bool,
+    /// Print the long explanation of the given diagnostic code, such as `7576`, and exit.
+    #[arg(long, value_name = "CODE", conflicts_with_all = ["input", "standard_json", "watch"])]
+    pub explain: Option<String>,
+    /// Run as a daemon that compiles standard JSON inputs received over a local socket.
+    ///
+    /// The socket is either the path of a Unix domain socket, or a loopback TCP address such as
+    /// `127.0.0.1:9545`. Requests use JSON-RPC 2.0, with one message per line, and must contain the
+    /// token that is printed when the daemon starts.
+    #[arg(
+        long,
+        value_name = "SOCKET",
+        conflicts_with_all = ["input", "standard_json", "watch", "explain"]
+    )]
+    pub daemon: Option<String>,
+
+    /// Silence the warnings of the given lint.
+    ///
+    /// A lint is either `warnings`, for all warnings, or a warning code. A warning code takes
+    /// precedence over `warnings`; otherwise `--deny` takes precedence over `--warn`, which takes
+    /// precedence over `--allow`.
+    #[arg(help_heading = "Lint options", long, short = 'A', value_name = "LINT")]
+    pub allow: Vec<Lint>,
+    /// Emit the warnings of the given lint. See `--allow`.
+    ///
+    /// Some lints, like `6508`, are allowed by default and are only run when their code is passed
+    /// to `--warn` or `--deny`.
+    #[arg(help_heading = "Lint options", long, short = 'W', value_name = "LINT")]
+    pub warn: Vec<Lint>,
+    /// Turn the warnings of the given lint into errors. See `--allow`.
+    #[arg(help_heading = "Lint options", long, short = 'D', value_name = "LINT")]
+    pub deny: Vec<Lint>,
+    /// Turn all warnings into errors. Equivalent to `-D warnings`.
+    #[arg(help_heading = "Lint options", long)]
+    pub deny_warnings: bool,
+    /// Suppress the warnings listed in the given baseline file.
+    ///
+    /// Warnings are identified by their file, code, and message, so moving code around in a file
+    /// does not invalidate the baseline. Baselined warnings are suppressed before `--deny` is
+    /// applied.
+    #[arg(
+        help_heading = "Lint options",
+        long,
+        value_name = "FILE",
+        value_hint = ValueHint::FilePath
+    )]
+    pub baseline: Option<PathBuf>,
+    /// Write the current warnings to the file given with `--baseline` instead of suppressing them.
+    #[arg(help_heading = "Lint options", long, requires = "baseline")]
+    pub write_baseline: bool,
 
     /// Coloring.
     #[arg(help_heading = "Display options", long, value_enum, default_value = "auto")]
@@ -61,6 +240,25 @@ pub struct Args {
     /// How errors and other messages are produced.
     #[arg(help_heading = "Display options", long, value_enum, default_value_t)]
     pub error_format: ErrorFormat,
+    /// The maximum width of human-readable diagnostics, in columns.
+    ///
+    /// Longer source lines are cut around the annotated code. Defaults to 140.
+    #[arg(help_heading = "Display options", long, value_name = "WIDTH")]
+    pub diagnostic_width: Option<usize>,
+    /// Expand tabs in source code to tab stops of the given width in human-readable diagnostics.
+    #[arg(help_heading = "Display options", long, value_name = "WIDTH")]
+    pub tab_width: Option<usize>,
+    /// Show the progress of the compilation on stderr.
+    ///
+    /// Ignored if stderr is not a terminal, or if the error format is not `human`.
+    #[arg(help_heading = "Display options", long)]
+    pub progress: bool,
+    /// Stop emitting diagnostics after the given number of errors.
+    #[arg(help_heading = "Display options", long, value_name = "N")]
+    pub error_limit: Option<NonZeroUsize>,
+    /// Do not link to the Solidity documentation in diagnostics.
+    #[arg(help_heading = "Display options", long)]
+    pub no_docs_links: bool,
 
     /// Unstable flags. WARNING: these are completely unstable, and may change at any time.
     ///
@@ -77,14 +275,21 @@ pub struct Args {
 impl Args {
     /// Finishes argument parsing.
     ///
-    /// This currently only parses the `-Z` arguments into the `unstable` field, but may be extended
-    /// in the future.
+    /// This parses the `-Z` arguments into the `unstable` field, and checks their conflicts with
+    /// the other arguments, which clap cannot express across the two.
     pub fn finish(&mut self) -> Result<(), clap::Error> {
         if !self._unstable.is_empty() {
             let hack = self._unstable.iter().map(|s| format!("--{s}"));
             self.unstable =
                 UnstableFeatures::try_parse_from(std::iter::once(String::new()).chain(hack))?;
         }
+        if self.unstable.mmap_threshold.is_some() && (self.watch || self.daemon.is_some()) {
+            // Files may be modified while a long-running compiler has them mapped.
+            return Err(clap::Error::raw(
+                clap::error::ErrorKind::ArgumentConflict,
+                "`-Z mmap-threshold` cannot be used with `--watch` or `--daemon`\n",
+            ));
+        }
         Ok(())
     }
 }
@@ -114,13 +319,9 @@ pub struct UnstableFeatures {
     #[arg(long)]
     pub track_diagnostics: bool,
 
-    /// Enables parsing Yul files for testing.
-    #[arg(long)]
-    pub parse_yul: bool,
-
     /// Print additional information about the compiler's internal state.
     ///
-    /// Valid kinds are `ast` and `hir`.
+    /// Valid kinds are `tokens`, `ast`, and `hir`.
     #[arg(long, value_name = "KIND[=PATHS...]")]
     pub dump: Option<Dump>,
 
@@ -128,6 +329,32 @@ pub struct UnstableFeatures {
     #[arg(long)]
     pub ast_stats: bool,
 
+    /// Print the wall time of each compiler pass, along with the number of files, bytes, and AST
+    /// nodes processed, and the code size before and after optimization, to stderr.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "human"
+    )]
+    pub time_passes: Option<TimePassesFormat>,
+
+    /// Memory-map source files of at least this many bytes instead of reading them into memory.
+    ///
+    /// Mapped files are not copied into memory. They must not be modified during compilation, so
+    /// this cannot be used with `--watch` or `--daemon`.
+    #[arg(long, value_name = "BYTES")]
+    pub mmap_threshold: Option<u64>,
+
+    /// Drop the source code of the files once they have been lowered, and read it again from disk
+    /// when it is needed, such as to render diagnostics.
+    ///
+    /// Diagnostics in files that were modified during compilation are emitted without a snippet.
+    #[arg(long)]
+    pub drop_source_text: bool,
+
     /// Print help.
     #[arg(long, action = clap::ArgAction::Help)]
     help: (),
@@ -140,6 +367,172 @@ pub struct UnstableFeatures {
     test_value: Option<usize>,
 }
 
+/// Solar subcommands.
+#[derive(Clone, Debug, clap::Subcommand)]
+#[non_exhaustive]
+pub enum Command {
+    /// Flatten a source file and its imports into a single source file.
+    ///
+    /// The sources are concatenated so that every file comes after the files it imports. Import
+    /// directives are removed, pragmas are unified, and SPDX license identifiers are merged.
+    Flatten(FlattenArgs),
+    /// Format source files.
+    ///
+    /// Files are formatted in place, unless `--check` is passed.
+    Fmt(FmtArgs),
+    /// Print the selectors of a function, event, or error signature, or of the functions, events,
+    /// and errors of the contracts in a source file.
+    ///
+    /// Signatures are canonicalized like in the ABI, so `transfer(address to, uint amount)` prints
+    /// the selector of `transfer(address,uint256)`.
+    Selectors(SelectorsArgs),
+    /// Print the nodes of the AST that match a structural query, like `call:delegatecall`,
+    /// `member:tx.origin`, or `undocumented`.
+    AstGrep(AstGrepArgs),
+}
+
+/// Arguments of `solar flatten`.
+#[derive(Clone, Debug, clap::Args)]
+#[non_exhaustive]
+pub struct FlattenArgs {
+    /// The source file to flatten.
+    #[arg(value_hint = ValueHint::FilePath)]
+    pub file: PathBuf,
+    /// Write the flattened source to this file instead of stdout.
+    #[arg(long, short, value_hint = ValueHint::FilePath)]
+    pub output: Option<PathBuf>,
+}
+
+/// Arguments of `solar selectors`.
+#[derive(Clone, Debug, clap::Args)]
+#[non_exhaustive]
+pub struct SelectorsArgs {
+    /// A signature, like `transfer(address,uint256)` or `event Transfer(address,address,uint)`,
+    /// or a source file.
+    ///
+    /// Signatures are functions, unless they start with `event` or `error`.
+    #[arg(value_name = "FILE_OR_SIGNATURE", value_hint = ValueHint::FilePath)]
+    pub input: String,
+}
+
+/// Arguments of `solar ast-grep`.
+#[derive(Clone, Debug, clap::Args)]
+#[non_exhaustive]
+pub struct AstGrepArgs {
+    /// The query, written as `KIND` or `KIND:NAME`.
+    ///
+    /// - `member:NAME`: member accesses, optionally qualified, like `member:tx.origin`;
+    /// - `call:NAME`: calls of a function or member, including Yul builtins;
+    /// - `ident:NAME`: identifiers used in expressions;
+    /// - `undocumented`: functions and modifiers without NatSpec comments;
+    /// - `contract`, `function`, `modifier`, `variable`, `struct`, `enum`, `error`, `event`: items,
+    ///   optionally with the given name.
+    #[arg(verbatim_doc_comment)]
+    pub query: solar_sema::ast_grep::Query,
+    /// Files, directories, or glob patterns to search. Defaults to the configured sources.
+    #[arg(value_hint = ValueHint::FilePath)]
+    pub paths: Vec<PathBuf>,
+}
+
+/// Arguments of `solar fmt`.
+#[derive(Clone, Debug, clap::Args)]
+#[non_exhaustive]
+pub struct FmtArgs {
+    /// Files, directories, or glob patterns to format. Defaults to the configured sources.
+    ///
+    /// `-` formats stdin and writes the result to stdout.
+    #[arg(value_hint = ValueHint::FilePath)]
+    pub paths: Vec<PathBuf>,
+    /// Don't write the files; report the ones that are not formatted and exit with an error.
+    #[arg(long)]
+    pub check: bool,
+    /// Maximum line width.
+    #[arg(long, value_name = "WIDTH", default_value_t = 120)]
+    pub line_width: usize,
+    /// Number of spaces per indentation level.
+    #[arg(long, value_name = "WIDTH", default_value_t = 4)]
+    pub tab_width: usize,
+}
+
+/// An output of `--combined-json`, named like in solc.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+#[value(rename_all = "kebab-case")]
+pub enum CombinedJsonOutput {
+    /// JSON ABI.
+    Abi,
+    /// Compact JSON AST.
+    Ast,
+    /// Creation bytecode.
+    Bin,
+    /// Runtime bytecode.
+    BinRuntime,
+    /// NatSpec developer documentation.
+    Devdoc,
+    /// Function signature hashes.
+    Hashes,
+    /// Contract metadata JSON.
+    Metadata,
+    /// Source map of the creation bytecode.
+    Srcmap,
+    /// Source map of the runtime bytecode.
+    SrcmapRuntime,
+    /// Storage layout.
+    StorageLayout,
+    /// NatSpec user documentation.
+    Userdoc,
+}
+
+impl From<CombinedJsonOutput> for CompilerOutput {
+    fn from(output: CombinedJsonOutput) -> Self {
+        match output {
+            CombinedJsonOutput::Abi => Self::Abi,
+            CombinedJsonOutput::Ast => Self::Ast,
+            CombinedJsonOutput::Bin => Self::Bin,
+            CombinedJsonOutput::BinRuntime => Self::BinRuntime,
+            CombinedJsonOutput::Devdoc => Self::Devdoc,
+            CombinedJsonOutput::Hashes => Self::Hashes,
+            CombinedJsonOutput::Metadata => Self::Metadata,
+            CombinedJsonOutput::Srcmap => Self::Srcmap,
+            CombinedJsonOutput::SrcmapRuntime => Self::SrcmapRuntime,
+            CombinedJsonOutput::StorageLayout => Self::StorageLayout,
+            CombinedJsonOutput::Userdoc => Self::Userdoc,
+        }
+    }
+}
+
+/// The address of a library to link, given as `path:Name=0x...`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Library {
+    /// The fully qualified name of the library.
+    pub name: String,
+    /// The address of the library.
+    pub address: Address,
+}
+
+impl FromStr for Library {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, address) =
+            s.split_once('=').ok_or_else(|| format!("expected `path:Name=0x...`, found `{s}`"))?;
+        if !name.contains(':') {
+            return Err(format!("expected a fully qualified library name, found `{name}`"));
+        }
+        let address = address.parse().map_err(|e| format!("invalid address `{address}`: {e}"))?;
+        Ok(Self { name: name.to_string(), address })
+    }
+}
+
+/// How the pass timings are printed with `-Ztime-passes`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+#[value(rename_all = "kebab-case")]
+pub enum TimePassesFormat {
+    /// Human-readable table.
+    Human,
+    /// JSON object.
+    Json,
+}
+
 /// How errors and other messages are produced.
 #[derive(Clone, Debug, Default, clap::ValueEnum)]
 #[value(rename_all = "kebab-case")]
@@ -151,30 +544,14 @@ pub enum ErrorFormat {
     Json,
     /// Rustc-like JSON output.
     RustcJson,
-}
-
-/// A single import map, AKA remapping: `map=path`.
-#[derive(Clone, Debug)]
-pub struct ImportMap {
-    pub map: PathBuf,
-    pub path: PathBuf,
-}
-
-impl std::str::FromStr for ImportMap {
-    type Err = &'static str;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let Some((a, b)) = s.split_once('=') {
-            Ok(Self { map: a.into(), path: b.into() })
-        } else {
-            Err("missing '='")
-        }
-    }
+    /// SARIF 2.1.0 log, written once compilation finishes.
+    Sarif,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloy_primitives::address;
     use clap::CommandFactory;
 
     #[test]
@@ -218,5 +595,272 @@ mod tests {
 
         let unstable = parse(&["solar", "-Zast-stats", "a.sol"]).unwrap();
         assert!(unstable.ast_stats);
+
+        let unstable = parse(&["solar", "-Ztime-passes", "a.sol"]).unwrap();
+        assert_eq!(unstable.time_passes, Some(TimePassesFormat::Human));
+        let unstable = parse(&["solar", "-Ztime-passes=json", "a.sol"]).unwrap();
+        assert_eq!(unstable.time_passes, Some(TimePassesFormat::Json));
+        assert!(parse(&["solar", "-Ztime-passes=csv", "a.sol"]).is_err());
+    }
+
+    #[test]
+    fn standard_json() {
+        let args = Args::try_parse_from(["solar", "--standard-json"]).unwrap();
+        assert!(args.standard_json);
+        let args = Args::try_parse_from(["solar", "--standard-json", "-I", "lib"]).unwrap();
+        assert!(args.standard_json);
+
+        let args = Args::try_parse_from(["solar", "--standard-json", "input.json"]).unwrap();
+        assert_eq!(args.input, [PathBuf::from("input.json")]);
+
+        assert!(Args::try_parse_from(["solar", "--standard-json", "--emit=abi"]).is_err());
+    }
+
+    #[test]
+    fn combined_json() {
+        let args =
+            Args::try_parse_from(["solar", "--combined-json", "abi,bin-runtime", "a.sol"]).unwrap();
+        assert_eq!(args.combined_json, [CombinedJsonOutput::Abi, CombinedJsonOutput::BinRuntime]);
+
+        assert!(Args::try_parse_from(["solar", "--combined-json", "ir", "a.sol"]).is_err());
+        assert!(Args::try_parse_from(["solar", "--combined-json=abi", "--emit=abi"]).is_err());
+        assert!(Args::try_parse_from(["solar", "--combined-json=abi", "--standard-json"]).is_err());
+    }
+
+    #[test]
+    fn watch() {
+        let args = Args::try_parse_from(["solar", "--watch", "a.sol"]).unwrap();
+        assert!(args.watch);
+
+        assert!(Args::try_parse_from(["solar", "--watch", "--standard-json"]).is_err());
+    }
+
+    #[test]
+    fn baseline() {
+        let args = Args::try_parse_from(["solar", "--baseline=b.json", "a.sol"]).unwrap();
+        assert_eq!(args.baseline, Some(PathBuf::from("b.json")));
+        assert!(!args.write_baseline);
+
+        let args =
+            Args::try_parse_from(["solar", "--baseline=b.json", "--write-baseline", "a.sol"]);
+        assert!(args.unwrap().write_baseline);
+        assert!(Args::try_parse_from(["solar", "--write-baseline", "a.sol"]).is_err());
+    }
+
+    #[test]
+    fn human_display_options() {
+        let args = ["solar", "--diagnostic-width=80", "--tab-width=4", "a.sol"];
+        let args = Args::try_parse_from(args).unwrap();
+        assert_eq!(args.diagnostic_width, Some(80));
+        assert_eq!(args.tab_width, Some(4));
+    }
+
+    #[test]
+    fn progress() {
+        assert!(Args::try_parse_from(["solar", "--progress", "a.sol"]).unwrap().progress);
+        assert!(!Args::try_parse_from(["solar", "a.sol"]).unwrap().progress);
+    }
+
+    #[test]
+    fn error_limit() {
+        let args = Args::try_parse_from(["solar", "--error-limit=10", "a.sol"]).unwrap();
+        assert_eq!(args.error_limit, NonZeroUsize::new(10));
+
+        assert!(Args::try_parse_from(["solar", "--error-limit=0", "a.sol"]).is_err());
+    }
+
+    #[test]
+    fn daemon() {
+        let args = Args::try_parse_from(["solar", "--daemon", "solar.sock"]).unwrap();
+        assert_eq!(args.daemon.as_deref(), Some("solar.sock"));
+
+        assert!(Args::try_parse_from(["solar", "--daemon", "solar.sock", "a.sol"]).is_err());
+        assert!(Args::try_parse_from(["solar", "--daemon", "a", "--standard-json"]).is_err());
+    }
+
+    #[test]
+    fn mmap_threshold() {
+        let args = crate::parse_args(["solar", "-Zmmap-threshold=1024", "a.sol"]).unwrap();
+        assert_eq!(args.unstable.mmap_threshold, Some(1024));
+
+        assert!(crate::parse_args(["solar", "-Zmmap-threshold=1024", "--watch", "a.sol"]).is_err());
+        let daemon = ["solar", "-Zmmap-threshold=1024", "--daemon", "solar.sock"];
+        assert!(crate::parse_args(daemon).is_err());
+    }
+
+    #[test]
+    fn explain() {
+        let args = Args::try_parse_from(["solar", "--explain", "7576"]).unwrap();
+        assert_eq!(args.explain.as_deref(), Some("7576"));
+
+        assert!(Args::try_parse_from(["solar", "--explain", "7576", "a.sol"]).is_err());
+    }
+
+    #[test]
+    fn config() {
+        let args = Args::try_parse_from(["solar", "--config", "solar.toml"]).unwrap();
+        assert_eq!(args.config, Some(PathBuf::from("solar.toml")));
+        assert_eq!(args.evm_version, None);
+
+        assert!(Args::try_parse_from(["solar", "--config", "a.toml", "--no-config"]).is_err());
+    }
+
+    #[test]
+    fn metadata_hash() {
+        let args = Args::try_parse_from(["solar", "a.sol"]).unwrap();
+        assert_eq!(args.metadata_hash, MetadataHash::Ipfs);
+
+        let args = Args::try_parse_from(["solar", "--metadata-hash=swarm", "a.sol"]).unwrap();
+        assert_eq!(args.metadata_hash, MetadataHash::Swarm);
+
+        let args = Args::try_parse_from(["solar", "--metadata-hash", "none", "a.sol"]).unwrap();
+        assert_eq!(args.metadata_hash, MetadataHash::None);
+
+        assert!(Args::try_parse_from(["solar", "--metadata-hash=sha256", "a.sol"]).is_err());
+    }
+
+    #[test]
+    fn optimizer() {
+        let args = Args::try_parse_from(["solar", "a.sol"]).unwrap();
+        assert!(!args.optimize);
+        assert_eq!(args.optimizer_runs, 200);
+        assert_eq!(args.yul_optimizations, None);
+
+        let args = Args::try_parse_from([
+            "solar",
+            "--optimize",
+            "--optimizer-runs=1000",
+            "--yul-optimizations=[sT]u:D",
+            "a.sol",
+        ])
+        .unwrap();
+        assert!(args.optimize);
+        assert_eq!(args.optimizer_runs, 1000);
+        assert_eq!(args.yul_optimizations.unwrap().to_string(), "[sT]u:D");
+
+        assert!(Args::try_parse_from(["solar", "--yul-optimizations=s", "a.sol"]).is_err());
+        assert!(Args::try_parse_from(["solar", "--optimize", "--yul-optimizations=x"]).is_err());
+        assert!(Args::try_parse_from(["solar", "--optimize", "--yul-optimizations=[s"]).is_err());
+    }
+
+    #[test]
+    fn libraries() {
+        let args = Args::try_parse_from(["solar", "a.sol"]).unwrap();
+        assert!(args.libraries.is_empty());
+
+        let args = Args::try_parse_from([
+            "solar",
+            "--libraries=a.sol:L=0x000000000000000000000000000000000000dEaD",
+            "--libraries",
+            "b.sol:M=0x0000000000000000000000000000000000000001",
+            "a.sol",
+        ])
+        .unwrap();
+        assert_eq!(
+            args.libraries,
+            [
+                Library {
+                    name: "a.sol:L".into(),
+                    address: address!("0x000000000000000000000000000000000000dEaD"),
+                },
+                Library { name: "b.sol:M".into(), address: Address::with_last_byte(1) },
+            ]
+        );
+
+        assert!(Args::try_parse_from(["solar", "--libraries=L=0x01", "a.sol"]).is_err());
+        assert!(Args::try_parse_from(["solar", "--libraries=a.sol:L", "a.sol"]).is_err());
+        assert!(Args::try_parse_from(["solar", "--libraries=a.sol:L=0x01", "a.sol"]).is_err());
+    }
+
+    #[test]
+    fn solc_paths() {
+        let args = Args::try_parse_from([
+            "solar",
+            "--base-path=.",
+            "--include-path=lib",
+            "--include-path=node_modules",
+            "--allow-paths=/tmp,../shared",
+            "a.sol",
+        ])
+        .unwrap();
+        assert_eq!(args.base_path, Some(PathBuf::from(".")));
+        assert_eq!(args.include_path, [PathBuf::from("lib"), PathBuf::from("node_modules")]);
+        assert_eq!(args.allow_paths, Some(vec![PathBuf::from("/tmp"), PathBuf::from("../shared")]));
+
+        let args = Args::try_parse_from(["solar", "a.sol"]).unwrap();
+        assert_eq!(args.allow_paths, None);
+        assert!(Args::try_parse_from(["solar", "--include-path=lib", "a.sol"]).is_err());
+    }
+
+    #[test]
+    fn flatten() {
+        let args =
+            Args::try_parse_from(["solar", "flatten", "a.sol", "-I", "lib", "-o", "out.sol"])
+                .unwrap();
+        let Some(Command::Flatten(flatten)) = args.command else { panic!() };
+        assert_eq!(flatten.file, PathBuf::from("a.sol"));
+        assert_eq!(flatten.output, Some(PathBuf::from("out.sol")));
+        assert_eq!(args.import_path, [PathBuf::from("lib")]);
+        assert!(args.input.is_empty());
+
+        let args = Args::try_parse_from(["solar", "a.sol"]).unwrap();
+        assert!(args.command.is_none());
+        assert!(Args::try_parse_from(["solar", "flatten"]).is_err());
+    }
+
+    #[test]
+    fn selectors() {
+        let args =
+            Args::try_parse_from(["solar", "selectors", "event Transfer(address,uint)"]).unwrap();
+        let Some(Command::Selectors(selectors)) = args.command else { panic!() };
+        assert_eq!(selectors.input, "event Transfer(address,uint)");
+        assert!(Args::try_parse_from(["solar", "selectors"]).is_err());
+    }
+
+    #[test]
+    fn ast_grep() {
+        let args = Args::try_parse_from(["solar", "ast-grep", "member:tx.origin", "src"]).unwrap();
+        let Some(Command::AstGrep(ast_grep)) = args.command else { panic!() };
+        assert_eq!(ast_grep.query.to_string(), "member:tx.origin");
+        assert_eq!(ast_grep.paths, [PathBuf::from("src")]);
+        assert!(Args::try_parse_from(["solar", "ast-grep", "statement"]).is_err());
+    }
+
+    #[test]
+    fn fmt() {
+        let args =
+            Args::try_parse_from(["solar", "fmt", "src", "--check", "--line-width=80"]).unwrap();
+        let Some(Command::Fmt(fmt)) = args.command else { panic!() };
+        assert_eq!(fmt.paths, [PathBuf::from("src")]);
+        assert!(fmt.check);
+        assert_eq!(fmt.line_width, 80);
+        assert_eq!(fmt.tab_width, 4);
+
+        let args = Args::try_parse_from(["solar", "fmt", "--exclude=test"]).unwrap();
+        let Some(Command::Fmt(fmt)) = args.command else { panic!() };
+        assert!(fmt.paths.is_empty());
+        assert!(!fmt.check);
+        assert_eq!(args.exclude, ["test"]);
+    }
+
+    #[test]
+    fn exclude() {
+        let args = Args::try_parse_from(["solar", "src", "--exclude", "test", "--exclude=*.t.sol"])
+            .unwrap();
+        assert_eq!(args.input, [PathBuf::from("src")]);
+        assert_eq!(args.exclude, ["test", "*.t.sol"]);
+    }
+
+    #[test]
+    fn lint_levels() {
+        let args =
+            Args::try_parse_from(["solar", "-D", "warnings", "-A3445", "--warn=6162"]).unwrap();
+        assert_eq!(args.deny, [Lint::Warnings]);
+        assert_eq!(args.allow, ["3445".parse::<Lint>().unwrap()]);
+        assert_eq!(args.warn.len(), 1);
+        assert!(!args.deny_warnings);
+
+        assert!(Args::try_parse_from(["solar", "-D", "unused"]).is_err());
+        assert!(Args::try_parse_from(["solar", "-A", "12345"]).is_err());
     }
 }
@@ -0,0 +1,279 @@
+//! Project configuration files: `solar.toml` and `foundry.toml`.
+
+use crate::cli::{Args, ImportMap};
+use serde::Deserialize;
+use solar_config::{CompilerOutput, EvmVersion};
+use std::path::{Path, PathBuf};
+
+/// The name of the Solar configuration file.
+pub const SOLAR_TOML: &str = "solar.toml";
+/// The name of the Foundry configuration file, which is used if no `solar.toml` is found.
+pub const FOUNDRY_TOML: &str = "foundry.toml";
+
+/// Project configuration, loaded from a `solar.toml` or a `foundry.toml` file.
+///
+/// Relative paths are resolved against the directory containing the configuration file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Config {
+    /// Files, directories, or glob patterns to compile when no input is given on the command line.
+    pub src: Vec<PathBuf>,
+    /// Directories to search for imported files.
+    pub import_paths: Vec<PathBuf>,
+    /// Import maps, AKA remappings.
+    pub remappings: Vec<ImportMap>,
+    /// Whether to resolve imports in `node_modules` directories.
+    pub node_modules: bool,
+    /// Patterns of files to skip when expanding directory and glob inputs.
+    pub exclude: Vec<String>,
+    /// EVM version.
+    pub evm_version: Option<EvmVersion>,
+    /// Types of output for the compiler to emit.
+    pub emit: Vec<CompilerOutput>,
+    /// Directory to write output files.
+    pub out_dir: Option<PathBuf>,
+}
+
+/// The contents of a `solar.toml` file.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+struct SolarToml {
+    src: Vec<PathBuf>,
+    import_paths: Vec<PathBuf>,
+    remappings: Vec<String>,
+    node_modules: bool,
+    exclude: Vec<String>,
+    evm_version: Option<EvmVersion>,
+    emit: Vec<CompilerOutput>,
+    out_dir: Option<PathBuf>,
+}
+
+/// The solc-related keys of a `foundry.toml` profile. All the other keys are ignored.
+#[derive(Deserialize)]
+#[serde(default)]
+struct FoundryProfile {
+    src: PathBuf,
+    libs: Vec<PathBuf>,
+    remappings: Vec<String>,
+    evm_version: Option<EvmVersion>,
+}
+
+impl Default for FoundryProfile {
+    fn default() -> Self {
+        Self {
+            src: PathBuf::from("src"),
+            libs: vec![PathBuf::from("lib")],
+            remappings: Vec::new(),
+            evm_version: None,
+        }
+    }
+}
+
+impl Config {
+    /// Finds and loads the configuration file in the given directory.
+    ///
+    /// `solar.toml` is preferred over `foundry.toml`. Returns `Ok(None)` if neither exists.
+    pub fn find(dir: &Path) -> Result<Option<Self>, String> {
+        for name in [SOLAR_TOML, FOUNDRY_TOML] {
+            let path = dir.join(name);
+            if path.is_file() {
+                return Self::load(&path).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Loads the configuration file at the given path.
+    ///
+    /// Files named `foundry.toml` are read as Foundry configuration files, using the profile
+    /// selected by the `FOUNDRY_PROFILE` environment variable, and `default` otherwise; all other
+    /// files are read as `solar.toml` files.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+        let root = path.parent().unwrap_or(Path::new(""));
+        let config = if path.file_name().is_some_and(|name| name == FOUNDRY_TOML) {
+            let profile = std::env::var("FOUNDRY_PROFILE").ok();
+            Self::parse_foundry(&contents, profile.as_deref().unwrap_or("default"), root)
+        } else {
+            Self::parse_solar(&contents, root)
+        };
+        config.map_err(|e| format!("failed to parse {}: {e}", path.display()))
+    }
+
+    /// Parses the contents of a `solar.toml` file.
+    pub fn parse_solar(contents: &str, root: &Path) -> Result<Self, String> {
+        let toml: SolarToml = toml::from_str(contents).map_err(|e| e.to_string())?;
+        Ok(Self {
+            src: join_all(root, toml.src),
+            import_paths: join_all(root, toml.import_paths),
+            remappings: parse_remappings(root, &toml.remappings)?,
+            node_modules: toml.node_modules,
+            exclude: toml.exclude,
+            evm_version: toml.evm_version,
+            emit: toml.emit,
+            out_dir: toml.out_dir.map(|dir| root.join(dir)),
+        })
+    }
+
+    /// Parses the solc-related keys of the given profile of a `foundry.toml` file.
+    ///
+    /// As in Foundry, the keys that are not set in the profile are inherited from the `default`
+    /// profile.
+    pub fn parse_foundry(contents: &str, profile: &str, root: &Path) -> Result<Self, String> {
+        let mut toml: toml::Table = toml::from_str(contents).map_err(|e| e.to_string())?;
+        let mut profiles = match toml.remove("profile") {
+            Some(toml::Value::Table(profiles)) => profiles,
+            Some(_) => return Err("`profile` must be a table".into()),
+            None => toml::Table::new(),
+        };
+        let mut merged = match profiles.remove("default") {
+            Some(toml::Value::Table(default)) => default,
+            _ => toml::Table::new(),
+        };
+        if profile != "default" {
+            match profiles.remove(profile) {
+                Some(toml::Value::Table(selected)) => merged.extend(selected),
+                _ => return Err(format!("profile `{profile}` not found")),
+            }
+        }
+        let profile: FoundryProfile =
+            toml::Value::Table(merged).try_into().map_err(|e: toml::de::Error| e.to_string())?;
+        Ok(Self {
+            src: vec![root.join(profile.src)],
+            import_paths: join_all(root, profile.libs),
+            remappings: parse_remappings(root, &profile.remappings)?,
+            evm_version: profile.evm_version,
+            ..Default::default()
+        })
+    }
+
+    /// Merges the configuration into the command-line arguments.
+    ///
+    /// Arguments given on the command line take precedence: `src` is only used if there are no
+    /// inputs, and the evm version, outputs, and output directory are only used if not specified.
+    /// Import paths are searched after the ones given on the command line, and remappings are
+    /// overridden by the ones given with `--import-map`.
+    pub fn merge_into(self, args: &mut Args) {
+        if args.input.is_empty() {
+            args.input = self.src;
+        }
+        for path in self.import_paths {
+            if !args.import_path.contains(&path) {
+                args.import_path.push(path);
+            }
+        }
+        args.import_map.splice(0..0, self.remappings);
+        args.node_modules |= self.node_modules;
+        args.exclude.extend(self.exclude);
+        args.evm_version = args.evm_version.or(self.evm_version);
+        if args.emit.is_empty() && args.combined_json.is_empty() {
+            args.emit = self.emit;
+        }
+        args.out_dir = args.out_dir.take().or(self.out_dir);
+    }
+}
+
+fn join_all(root: &Path, paths: Vec<PathBuf>) -> Vec<PathBuf> {
+    paths.into_iter().map(|path| root.join(path)).collect()
+}
+
+/// Parses remappings like the ones in `remappings.txt`, resolving relative paths against `root`.
+fn parse_remappings(root: &Path, remappings: &[String]) -> Result<Vec<ImportMap>, String> {
+    remappings
+        .iter()
+        .map(|s| {
+            let mut import_map: ImportMap =
+                s.parse().map_err(|e| format!("invalid remapping `{s}`: {e}"))?;
+            import_map.path = root.join(&import_map.path);
+            if let Some(context) = &mut import_map.context {
+                *context = root.join(&*context);
+            }
+            Ok(import_map)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[test]
+    fn solar_toml() {
+        let contents = r#"
+            src = ["src"]
+            import-paths = ["lib"]
+            remappings = ["@oz/=lib/openzeppelin/"]
+            node-modules = true
+            exclude = ["test"]
+            evm-version = "paris"
+            emit = ["abi", "hashes"]
+        "#;
+        let config = Config::parse_solar(contents, Path::new("project")).unwrap();
+        assert_eq!(config.src, [PathBuf::from("project/src")]);
+        assert_eq!(config.import_paths, [PathBuf::from("project/lib")]);
+        assert_eq!(config.remappings[0].map, PathBuf::from("@oz/"));
+        assert_eq!(config.remappings[0].path, PathBuf::from("project/lib/openzeppelin/"));
+        assert!(config.node_modules);
+        assert_eq!(config.exclude, ["test"]);
+        assert_eq!(config.evm_version, Some(EvmVersion::Paris));
+        assert_eq!(config.emit, [CompilerOutput::Abi, CompilerOutput::Hashes]);
+        assert_eq!(config.out_dir, None);
+
+        assert!(Config::parse_solar("unknown = 1", Path::new("")).is_err());
+        assert!(Config::parse_solar("remappings = [\"a\"]", Path::new("")).is_err());
+    }
+
+    #[test]
+    fn foundry_toml() {
+        let contents = r#"
+            [profile.default]
+            libs = ["lib", "node_modules"]
+            remappings = ["forge-std/=lib/forge-std/src/"]
+            optimizer = true
+
+            [profile.old]
+            src = "contracts"
+            evm_version = "london"
+        "#;
+        let config = Config::parse_foundry(contents, "default", Path::new("")).unwrap();
+        assert_eq!(config.src, [PathBuf::from("src")]);
+        assert_eq!(config.import_paths, [PathBuf::from("lib"), PathBuf::from("node_modules")]);
+        assert_eq!(config.remappings.len(), 1);
+        assert_eq!(config.evm_version, None);
+
+        let config = Config::parse_foundry(contents, "old", Path::new("")).unwrap();
+        assert_eq!(config.src, [PathBuf::from("contracts")]);
+        assert_eq!(config.import_paths.len(), 2);
+        assert_eq!(config.evm_version, Some(EvmVersion::London));
+
+        assert!(Config::parse_foundry(contents, "missing", Path::new("")).is_err());
+        let config = Config::parse_foundry("", "default", Path::new("")).unwrap();
+        assert_eq!(config.import_paths, [PathBuf::from("lib")]);
+    }
+
+    #[test]
+    fn cli_wins() {
+        let config = Config {
+            src: vec![PathBuf::from("src")],
+            import_paths: vec![PathBuf::from("lib")],
+            evm_version: Some(EvmVersion::London),
+            emit: vec![CompilerOutput::Abi],
+            ..Default::default()
+        };
+
+        let mut args = Args::try_parse_from(["solar", "--evm-version=paris", "-I", "lib"]).unwrap();
+        config.clone().merge_into(&mut args);
+        assert_eq!(args.input, [PathBuf::from("src")]);
+        assert_eq!(args.import_path, [PathBuf::from("lib")]);
+        assert_eq!(args.evm_version, Some(EvmVersion::Paris));
+        assert_eq!(args.emit, [CompilerOutput::Abi]);
+
+        let mut args = Args::try_parse_from(["solar", "a.sol", "--emit=hashes"]).unwrap();
+        config.merge_into(&mut args);
+        assert_eq!(args.input, [PathBuf::from("a.sol")]);
+        assert_eq!(args.evm_version, Some(EvmVersion::London));
+        assert_eq!(args.emit, [CompilerOutput::Hashes]);
+    }
+}
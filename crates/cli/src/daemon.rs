@@ -0,0 +1,512 @@
+//! Daemon mode, enabled with `--daemon`.
+//!
+//! The daemon listens on a local socket and compiles the standard JSON inputs it receives, which
+//! avoids starting a new compiler process for every compilation.
+//!
+//! Each connection is served on its own thread, up to [`MAX_CONNECTIONS`] at a time, and is closed
+//! after being idle for [`IDLE_TIMEOUT`]. Each line received on a connection is a
+//! [JSON-RPC 2.0](https://www.jsonrpc.org/specification) request of at most [`MAX_REQUEST_LEN`]
+//! bytes, and each response is written on its own line.
+//!
+//! `params` must be an object with a `token` field, where `token` is the token printed when the
+//! daemon starts, so that only whoever started the daemon can use it. The supported methods are:
+//! - `compile`: compiles the standard JSON input in `params`, and returns the standard JSON output;
+//! - `diagnostics`: like `compile`, but ignores `settings.outputSelection` and only returns the
+//!   `errors` field of the output;
+//! - `shutdown`: returns `null` and stops the daemon once the open connections are closed.
+//!
+//! The daemon keeps the outputs of the last [`CACHED_OUTPUTS`] compilations, along with the hashes
+//! of the files that they read from disk. A request with the same input as one of them is answered
+//! without compiling again, unless one of these files changed. Other requests are compiled from
+//! scratch with [`standard_json::compile_json`].
+//!
+//! Panics are reported as internal errors only if the compiler was built with `panic = "unwind"`;
+//! the `release` profile aborts on panic, which stops the daemon.
+
+use crate::{cli::Args, standard_json};
+use alloy_primitives::{keccak256, B256};
+use serde_json::{json, Value};
+use solar_interface::{diagnostics::DiagCtxt, Result};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    hash::{BuildHasher, RandomState},
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+const SERVER_BUSY: i64 = -32000;
+
+/// How long a connection can stay idle before it is closed.
+pub const IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The maximum number of connections served at the same time. Further connections are rejected.
+pub const MAX_CONNECTIONS: usize = 64;
+
+/// The maximum length of a request, including the newline. The connection is closed after a longer
+/// request.
+pub const MAX_REQUEST_LEN: usize = 64 * 1024 * 1024;
+
+/// The number of compilation outputs that are kept to answer identical requests.
+pub const CACHED_OUTPUTS: usize = 16;
+
+/// How long to wait before accepting connections again after failing to accept one.
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Listens on the given socket and serves requests until a `shutdown` request is received.
+///
+/// The socket is a loopback TCP address if it parses as one, or the path of a Unix domain socket
+/// otherwise, which is only accessible to the current user.
+pub fn run(args: &Args, socket: &str) -> Result<()> {
+    let token = token();
+    let daemon = Daemon::new(args, &token);
+    let r = match socket.parse::<SocketAddr>() {
+        Ok(addr) => serve_tcp(&daemon, socket, addr),
+        Err(_) => serve_unix(&daemon, socket, Path::new(socket)),
+    };
+    r.map_err(|e| DiagCtxt::new_early().err(format!("daemon failed on {socket}: {e}")).emit())
+}
+
+fn serve_tcp(daemon: &Daemon<'_>, socket: &str, addr: SocketAddr) -> io::Result<()> {
+    if !addr.ip().is_loopback() {
+        let msg = "only loopback addresses are allowed";
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, msg));
+    }
+    let listener = TcpListener::bind(addr)?;
+    listening(socket, daemon.token);
+    serve_tcp_listener(daemon, listener)
+}
+
+fn serve_tcp_listener(daemon: &Daemon<'_>, listener: TcpListener) -> io::Result<()> {
+    let addr = listener.local_addr()?;
+    serve(
+        daemon,
+        listener.incoming(),
+        |stream| {
+            stream.set_read_timeout(Some(IDLE_TIMEOUT))?;
+            stream.try_clone()
+        },
+        || drop(TcpStream::connect(addr)),
+    );
+    Ok(())
+}
+
+#[cfg(unix)]
+fn serve_unix(daemon: &Daemon<'_>, socket: &str, path: &Path) -> io::Result<()> {
+    use std::os::unix::{
+        fs::PermissionsExt,
+        net::{UnixListener, UnixStream},
+    };
+
+    let listener = UnixListener::bind(path)?;
+    let r = std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600));
+    if let Err(e) = r {
+        let _ = std::fs::remove_file(path);
+        return Err(e);
+    }
+    listening(socket, daemon.token);
+    serve(
+        daemon,
+        listener.incoming(),
+        |stream| {
+            stream.set_read_timeout(Some(IDLE_TIMEOUT))?;
+            stream.try_clone()
+        },
+        || drop(UnixStream::connect(path)),
+    );
+    let _ = std::fs::remove_file(path);
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn serve_unix(_daemon: &Daemon<'_>, _socket: &str, _path: &Path) -> io::Result<()> {
+    let msg = "Unix domain sockets are not supported on this platform; use a TCP address instead";
+    Err(io::Error::new(io::ErrorKind::Unsupported, msg))
+}
+
+fn listening(socket: &str, token: &str) {
+    DiagCtxt::new_early().note(format!("listening on {socket}; token: {token}")).emit();
+}
+
+/// Returns a random token that requests must contain.
+fn token() -> String {
+    let state = RandomState::new();
+    format!("{:016x}{:016x}", state.hash_one(0u8), state.hash_one(1u8))
+}
+
+/// Serves each incoming connection on its own thread until a `shutdown` request is received, then
+/// waits for the open connections to be closed.
+///
+/// `reader` sets up a new connection and returns a handle to read from it. `wake` connects to the
+/// listener, so that it stops waiting for new connections after a `shutdown` request.
+///
+/// Connections that fail to be accepted are skipped.
+fn serve<S: Read + Write + Send>(
+    daemon: &Daemon<'_>,
+    incoming: impl Iterator<Item = io::Result<S>>,
+    reader: impl Fn(&S) -> io::Result<S> + Sync,
+    wake: impl Fn() + Sync,
+) {
+    let shutdown = AtomicBool::new(false);
+    let connections = AtomicUsize::new(0);
+    let (reader, wake, shutdown, connections) = (&reader, &wake, &shutdown, &connections);
+    thread::scope(|s| {
+        for stream in incoming {
+            if shutdown.load(Ordering::Relaxed) {
+                break;
+            }
+            let mut stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    // Such as when running out of file descriptors, which may be temporary.
+                    let msg = format!("failed to accept a connection: {e}");
+                    DiagCtxt::new_early().warn(msg).emit();
+                    thread::sleep(ACCEPT_RETRY_DELAY);
+                    continue;
+                }
+            };
+            if connections.fetch_add(1, Ordering::Relaxed) >= MAX_CONNECTIONS {
+                connections.fetch_sub(1, Ordering::Relaxed);
+                let busy = error(Value::Null, SERVER_BUSY, "too many connections");
+                let _ = write_response(&mut stream, &busy);
+                continue;
+            }
+            s.spawn(move || {
+                if let Ok(r) = reader(&stream) {
+                    if serve_connection(daemon, BufReader::new(r), stream) {
+                        shutdown.store(true, Ordering::Relaxed);
+                        wake();
+                    }
+                }
+                connections.fetch_sub(1, Ordering::Relaxed);
+            });
+        }
+    })
+}
+
+/// Serves the requests received on a connection until it is closed.
+///
+/// Returns `true` if the daemon should shut down. I/O errors only close the connection.
+fn serve_connection(daemon: &Daemon<'_>, mut reader: impl BufRead, mut writer: impl Write) -> bool {
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        let limit = daemon.max_request_len as u64 + 1;
+        match (&mut reader).take(limit).read_until(b'\n', &mut line) {
+            Ok(0) | Err(_) => return false,
+            Ok(_) => {}
+        }
+        if line.len() > daemon.max_request_len {
+            // The rest of the request would have to be read to find the next one.
+            let msg = format!("requests must be at most {} bytes long", daemon.max_request_len);
+            let _ = write_response(&mut writer, &error(Value::Null, INVALID_REQUEST, &msg));
+            return false;
+        }
+        let request = line.trim_ascii();
+        if request.is_empty() {
+            continue;
+        }
+        let (response, shutdown) = daemon.handle(request);
+        if let Some(response) = response {
+            if write_response(&mut writer, &response).is_err() {
+                return shutdown;
+            }
+        }
+        if shutdown {
+            return true;
+        }
+    }
+}
+
+fn write_response(writer: &mut impl Write, response: &Value) -> io::Result<()> {
+    serde_json::to_writer(&mut *writer, response)?;
+    writeln!(writer)?;
+    writer.flush()
+}
+
+type RpcResult = Result<Value, (i64, String)>;
+
+/// The state shared by all the connections.
+struct Daemon<'a> {
+    args: &'a Args,
+    token: &'a str,
+    max_request_len: usize,
+    /// The outputs of the last compilations, the most recently used first.
+    outputs: Mutex<VecDeque<CachedOutput>>,
+}
+
+/// The output of a compilation, along with the files that it read from disk.
+struct CachedOutput {
+    /// The hash of the standard JSON input.
+    input: B256,
+    /// The hashes of the files that were read from disk, or `None` for the files that did not
+    /// exist.
+    files: BTreeMap<PathBuf, Option<B256>>,
+    output: Value,
+}
+
+impl<'a> Daemon<'a> {
+    fn new(args: &'a Args, token: &'a str) -> Self {
+        Self { args, token, max_request_len: MAX_REQUEST_LEN, outputs: Default::default() }
+    }
+
+    /// Handles a request, returning the response, if any, and whether the daemon should shut
+    /// down.
+    ///
+    /// Notifications, which are requests without an `id`, have no response.
+    fn handle(&self, line: &[u8]) -> (Option<Value>, bool) {
+        let mut request = match serde_json::from_slice::<Value>(line) {
+            Ok(request) if request.is_object() => request,
+            Ok(_) => {
+                return (Some(error(Value::Null, INVALID_REQUEST, "expected an object")), false)
+            }
+            Err(e) => return (Some(error(Value::Null, PARSE_ERROR, &e.to_string())), false),
+        };
+        let id = request.get("id").cloned();
+        let mut params = request.get_mut("params").map(Value::take).unwrap_or_default();
+        let (result, shutdown) = match request.get("method").and_then(Value::as_str) {
+            Some(method @ ("compile" | "diagnostics" | "shutdown")) => {
+                let token = params.as_object_mut().and_then(|params| params.remove("token"));
+                if token.as_ref().and_then(Value::as_str) != Some(self.token) {
+                    (Err((INVALID_PARAMS, "invalid token".into())), false)
+                } else if method == "compile" {
+                    (self.compile(params), false)
+                } else if method == "diagnostics" {
+                    (self.diagnostics(params), false)
+                } else {
+                    (Ok(Value::Null), true)
+                }
+            }
+            Some(method) => (Err((METHOD_NOT_FOUND, format!("unknown method `{method}`"))), false),
+            None => (Err((INVALID_REQUEST, "missing `method`".into())), false),
+        };
+        let response = id.map(|id| match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error(id, code, &message),
+        });
+        (response, shutdown)
+    }
+
+    fn compile(&self, input: Value) -> RpcResult {
+        if !input.is_object() {
+            return Err((INVALID_PARAMS, "expected a standard JSON input object".into()));
+        }
+        let input = input.to_string();
+        let hash = keccak256(&input);
+        if let Some(output) = self.cached_output(hash) {
+            return Ok(output);
+        }
+        let (output, files) = panic::catch_unwind(AssertUnwindSafe(|| {
+            standard_json::compile_json_with_files(self.args, &input)
+        }))
+        .map_err(|_| (INTERNAL_ERROR, "the compiler panicked".into()))?;
+        let files = files
+            .into_iter()
+            .map(|path| {
+                let hash = hash_file(&path);
+                (path, hash)
+            })
+            .collect();
+        self.cache_output(CachedOutput { input: hash, files, output: output.clone() });
+        Ok(output)
+    }
+
+    fn diagnostics(&self, mut input: Value) -> RpcResult {
+        if let Some(settings) = input.get_mut("settings").and_then(Value::as_object_mut) {
+            settings.remove("outputSelection");
+        }
+        let mut output = self.compile(input)?;
+        let errors = output.get_mut("errors").map(Value::take).unwrap_or_else(|| json!([]));
+        Ok(json!({ "errors": errors }))
+    }
+
+    /// Returns the cached output of the given input, unless one of the files that it read changed.
+    fn cached_output(&self, input: B256) -> Option<Value> {
+        let cached = {
+            let mut outputs = self.outputs.lock().unwrap();
+            let i = outputs.iter().position(|cached| cached.input == input)?;
+            outputs.remove(i).unwrap()
+        };
+        // Hashing the files can take a while, so it is done without holding the lock.
+        if !cached.files.iter().all(|(path, hash)| hash_file(path) == *hash) {
+            return None;
+        }
+        let output = cached.output.clone();
+        self.cache_output(cached);
+        Some(output)
+    }
+
+    fn cache_output(&self, cached: CachedOutput) {
+        let mut outputs = self.outputs.lock().unwrap();
+        outputs.retain(|other| other.input != cached.input);
+        outputs.push_front(cached);
+        outputs.truncate(CACHED_OUTPUTS);
+    }
+}
+
+fn hash_file(path: &Path) -> Option<B256> {
+    std::fs::read(path).ok().map(keccak256)
+}
+
+fn error(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args() -> Args {
+        crate::parse_args(["solar", "--daemon", "solar.sock"]).unwrap()
+    }
+
+    fn serve(daemon: &Daemon<'_>, requests: &str) -> (Vec<Value>, bool) {
+        let mut output = Vec::new();
+        let shutdown = serve_connection(daemon, requests.as_bytes(), &mut output);
+        let responses = output
+            .split(|&b| b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_slice(line).unwrap())
+            .collect();
+        (responses, shutdown)
+    }
+
+    #[test]
+    fn requests() {
+        let input = json!({
+            "language": "Solidity",
+            "sources": { "a.sol": { "content": "contract C { function f() external pure {} }" } },
+            "settings": { "outputSelection": { "*": { "*": ["abi"] } } },
+            "token": "token",
+        });
+        let requests = [
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "compile", "params": input }),
+            json!({ "jsonrpc": "2.0", "id": 2, "method": "diagnostics", "params": input }),
+        ];
+        let requests = requests.map(|r| r.to_string()).join("\n");
+        let args = args();
+        let (responses, shutdown) = serve(&Daemon::new(&args, "token"), &requests);
+        assert!(!shutdown);
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0]["id"], 1);
+        assert_eq!(responses[0]["result"]["contracts"]["a.sol"]["C"]["abi"][0]["name"], "f");
+        assert_eq!(responses[1]["id"], 2);
+        assert_eq!(responses[1]["result"], json!({ "errors": [] }));
+    }
+
+    #[test]
+    fn errors() {
+        let requests = [
+            "{",
+            r#"{"jsonrpc":"2.0","id":1,"method":"build"}"#,
+            r#"{"jsonrpc":"2.0","id":2,"method":"compile","params":[]}"#,
+            r#"{"jsonrpc":"2.0","id":3,"method":"compile","params":{"language":"Solidity"}}"#,
+            r#"{"jsonrpc":"2.0","method":"compile","params":[]}"#,
+        ];
+        let args = args();
+        let (responses, _) = serve(&Daemon::new(&args, "token"), &requests.join("\n"));
+        let codes = responses.iter().map(|r| r["error"]["code"].clone()).collect::<Vec<_>>();
+        assert_eq!(codes, [PARSE_ERROR, METHOD_NOT_FOUND, INVALID_PARAMS, INVALID_PARAMS]);
+        assert_eq!(responses[3]["error"]["message"], "invalid token");
+    }
+
+    #[test]
+    fn long_requests() {
+        let args = args();
+        let mut daemon = Daemon::new(&args, "token");
+        daemon.max_request_len = 64;
+        let ok = r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#;
+        let long = format!(r#"{{"jsonrpc":"2.0","id":2,"method":"{}"}}"#, "a".repeat(64));
+        let (responses, _) = serve(&daemon, &[ok, &long, ok].join("\n"));
+        // The connection is closed after the long request.
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0]["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(responses[1]["error"]["code"], INVALID_REQUEST);
+    }
+
+    #[test]
+    fn cached_outputs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.sol");
+        std::fs::write(&path, "contract A {}").unwrap();
+        let input = json!({
+            "language": "Solidity",
+            "sources": { "a.sol": { "urls": [path] } },
+            "settings": { "outputSelection": { "*": { "*": ["abi"] } } },
+        });
+        let dir_arg = dir.path().to_str().unwrap();
+        let args = crate::parse_args(["solar", "--daemon", "solar.sock", "--allow-paths", dir_arg])
+            .unwrap();
+        let daemon = Daemon::new(&args, "token");
+        let contracts = |output: Value| {
+            let files = output["contracts"].as_object().unwrap().values();
+            files.flat_map(|file| file.as_object().unwrap().keys().cloned()).collect::<Vec<_>>()
+        };
+        assert_eq!(contracts(daemon.compile(input.clone()).unwrap()), ["A"]);
+
+        // Identical requests are answered from the cache.
+        daemon.outputs.lock().unwrap()[0].output = json!("cached");
+        assert_eq!(daemon.compile(input.clone()).unwrap(), "cached");
+
+        // The input is compiled again when a file that it read changes.
+        std::fs::write(&path, "contract B {}").unwrap();
+        assert_eq!(contracts(daemon.compile(input).unwrap()), ["B"]);
+    }
+
+    #[test]
+    fn shutdown() {
+        let requests = [
+            r#"{"jsonrpc":"2.0","id":1,"method":"shutdown"}"#,
+            r#"{"jsonrpc":"2.0","id":2,"method":"shutdown","params":{"token":"nope"}}"#,
+            r#"{"jsonrpc":"2.0","id":3,"method":"shutdown","params":{"token":"token"}}"#,
+            r#"{"jsonrpc":"2.0","id":4,"method":"shutdown","params":{"token":"token"}}"#,
+        ];
+        let args = args();
+        let (responses, shutdown) = serve(&Daemon::new(&args, "token"), &requests.join("\n"));
+        assert!(shutdown);
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0]["error"]["code"], INVALID_PARAMS);
+        assert_eq!(responses[1]["error"]["code"], INVALID_PARAMS);
+        assert_eq!(responses[2], json!({ "jsonrpc": "2.0", "id": 3, "result": null }));
+    }
+
+    #[test]
+    fn concurrent_connections() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let daemon = thread::spawn(move || {
+            let args = args();
+            serve_tcp_listener(&Daemon::new(&args, "token"), listener)
+        });
+
+        let request = |stream: &mut TcpStream, request: &str| {
+            writeln!(stream, "{request}").unwrap();
+            let mut response = String::new();
+            BufReader::new(&*stream).read_line(&mut response).unwrap();
+            serde_json::from_str::<Value>(&response).unwrap()
+        };
+
+        // An idle connection does not prevent others from being served.
+        let idle = TcpStream::connect(addr).unwrap();
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let response = request(&mut stream, r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#);
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+
+        let shutdown = r#"{"jsonrpc":"2.0","id":2,"method":"shutdown","params":{"token":"token"}}"#;
+        assert_eq!(request(&mut stream, shutdown)["result"], Value::Null);
+        drop((idle, stream));
+        daemon.join().unwrap().unwrap();
+    }
+}
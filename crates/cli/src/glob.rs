@@ -0,0 +1,242 @@
+//! Expansion of glob and directory inputs.
+
+use std::{
+    collections::HashSet,
+    path::{Component, Path, PathBuf},
+};
+
+/// Expands the input paths into the list of files to compile.
+///
+/// - directories are searched recursively for files with the given extension;
+/// - paths containing glob metacharacters (`*`, `?`, `[`) are expanded into the files that they
+///   match, where `**` matches any number of directories;
+/// - all other paths are returned unchanged.
+///
+/// The files found in directories or by globs are sorted, and the ones matching any of the
+/// `exclude` patterns are skipped. Patterns without a `/` are matched against every component of
+/// the path, so `test` excludes all the files in `test` directories, and `*.t.sol` excludes files
+/// by name; other patterns are matched against the whole path.
+///
+/// Duplicates are removed, keeping the first occurrence.
+pub(crate) fn expand_inputs<'a>(
+    inputs: impl IntoIterator<Item = &'a PathBuf>,
+    extension: &str,
+    exclude: &[String],
+) -> Result<Vec<PathBuf>, String> {
+    let is_excluded = |path: &Path| {
+        let path = to_slash(path);
+        let components = path.split('/').collect::<Vec<_>>();
+        exclude.iter().any(|pattern| {
+            if pattern.contains('/') {
+                glob_match(pattern, &path)
+            } else {
+                components.iter().any(|component| segment_match(pattern, component))
+            }
+        })
+    };
+
+    let mut files = Vec::new();
+    for input in inputs {
+        if input.is_dir() {
+            let mut found = Vec::new();
+            walk(input, None, &mut |path| {
+                if path.extension().is_some_and(|ext| ext == extension) && !is_excluded(path) {
+                    found.push(path.to_path_buf());
+                }
+            })
+            .map_err(|e| format!("failed to read directory {}: {e}", input.display()))?;
+            found.sort();
+            files.extend(found);
+        } else if is_glob(input) {
+            let pattern = to_slash(input);
+            let base = glob_base(input);
+            // Without `**`, the pattern can only match paths at a fixed depth below the base.
+            let depth = input.components().count() - base.components().count();
+            let max_depth = (!pattern.split('/').any(|s| s == "**")).then_some(depth);
+            let mut found = Vec::new();
+            if base.as_os_str().is_empty() || base.is_dir() {
+                walk(&base, max_depth, &mut |path| {
+                    if glob_match(&pattern, &to_slash(path)) && !is_excluded(path) {
+                        found.push(path.to_path_buf());
+                    }
+                })
+                .map_err(|e| format!("failed to read directory {}: {e}", base.display()))?;
+            }
+            if found.is_empty() {
+                return Err(format!("pattern `{pattern}` did not match any files"));
+            }
+            found.sort();
+            files.extend(found);
+        } else {
+            files.push(input.clone());
+        }
+    }
+
+    let mut seen = HashSet::new();
+    files.retain(|path| seen.insert(path.clone()));
+    Ok(files)
+}
+
+/// Calls `f` with every file in `dir`, recursively, up to `max_depth` levels deep if specified.
+/// Symbolic links to directories are not followed.
+fn walk(dir: &Path, max_depth: Option<usize>, f: &mut impl FnMut(&Path)) -> std::io::Result<()> {
+    if max_depth == Some(0) {
+        return Ok(());
+    }
+    let read_dir = if dir.as_os_str().is_empty() { Path::new(".") } else { dir };
+    for entry in std::fs::read_dir(read_dir)? {
+        let entry = entry?;
+        let path = if dir.as_os_str().is_empty() {
+            PathBuf::from(entry.file_name())
+        } else {
+            dir.join(entry.file_name())
+        };
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            walk(&path, max_depth.map(|depth| depth - 1), f)?;
+        } else if file_type.is_file() || path.is_file() {
+            f(&path);
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn is_glob(path: &Path) -> bool {
+    path.to_str().is_some_and(|s| s.contains(['*', '?', '[']))
+}
+
+/// Returns the leading components of `path` that don't contain glob metacharacters.
+pub(crate) fn glob_base(path: &Path) -> PathBuf {
+    path.components()
+        .take_while(|component| match component {
+            Component::Normal(s) => !is_glob(Path::new(s)),
+            _ => true,
+        })
+        .collect()
+}
+
+fn to_slash(path: &Path) -> String {
+    let s = path.to_string_lossy();
+    let s = if cfg!(windows) { s.replace('\\', "/") } else { s.into_owned() };
+    s.strip_prefix("./").map(str::to_string).unwrap_or(s)
+}
+
+/// Matches a `/`-separated path against a glob pattern.
+fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.strip_prefix("./").unwrap_or(pattern);
+    let pattern = pattern.split('/').filter(|s| !s.is_empty()).collect::<Vec<_>>();
+    let path = path.split('/').filter(|s| !s.is_empty()).collect::<Vec<_>>();
+    components_match(&pattern, &path)
+}
+
+fn components_match(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => {
+            components_match(rest, path)
+                || (!path.is_empty() && components_match(pattern, &path[1..]))
+        }
+        Some((first, rest)) => match path.split_first() {
+            Some((component, path)) => {
+                segment_match(first, component) && components_match(rest, path)
+            }
+            None => false,
+        },
+    }
+}
+
+/// Matches a single path component against a pattern with `*`, `?`, and `[...]` wildcards.
+fn segment_match(pattern: &str, s: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let s = s.chars().collect::<Vec<_>>();
+    let (mut p, mut i) = (0, 0);
+    // The position after the last `*` in the pattern, and the position in `s` it matched up to.
+    let mut backtrack = None;
+    while i < s.len() {
+        if p < pattern.len() && pattern[p] == '*' {
+            p += 1;
+            backtrack = Some((p, i));
+            continue;
+        }
+        if p < pattern.len() {
+            if let Some((matched, len)) = char_match(&pattern[p..], s[i]) {
+                if matched {
+                    p += len;
+                    i += 1;
+                    continue;
+                }
+            }
+        }
+        match &mut backtrack {
+            Some((star_p, star_i)) => {
+                *star_i += 1;
+                p = *star_p;
+                i = *star_i;
+            }
+            None => return false,
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Matches a single character against the start of `pattern`, returning whether it matched and
+/// the length of the matched pattern element.
+fn char_match(pattern: &[char], c: char) -> Option<(bool, usize)> {
+    match pattern[0] {
+        '?' => Some((true, 1)),
+        '[' => {
+            let end = pattern.iter().skip(2).position(|&c| c == ']')? + 2;
+            let (negated, class) = match pattern[1] {
+                '!' | '^' => (true, &pattern[2..end]),
+                _ => (false, &pattern[1..end]),
+            };
+            let mut matched = false;
+            let mut i = 0;
+            while i < class.len() {
+                if i + 2 < class.len() && class[i + 1] == '-' {
+                    matched |= (class[i]..=class[i + 2]).contains(&c);
+                    i += 3;
+                } else {
+                    matched |= class[i] == c;
+                    i += 1;
+                }
+            }
+            Some((matched != negated, end + 1))
+        }
+        p => Some((p == c, 1)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segments() {
+        assert!(segment_match("*.sol", "Token.sol"));
+        assert!(segment_match("*.t.sol", "Token.t.sol"));
+        assert!(!segment_match("*.t.sol", "Token.sol"));
+        assert!(segment_match("T?ken.sol", "Token.sol"));
+        assert!(segment_match("[A-Z]*", "Token.sol"));
+        assert!(!segment_match("[!A-Z]*", "Token.sol"));
+        assert!(segment_match("*", ""));
+        assert!(!segment_match("a", ""));
+    }
+
+    #[test]
+    fn globs() {
+        assert!(glob_match("src/**/*.sol", "src/Token.sol"));
+        assert!(glob_match("src/**/*.sol", "src/tokens/erc20/Token.sol"));
+        assert!(glob_match("./src/*.sol", "src/Token.sol"));
+        assert!(!glob_match("src/*.sol", "src/tokens/Token.sol"));
+        assert!(!glob_match("src/**/*.sol", "lib/Token.sol"));
+        assert!(glob_match("**", "a/b/c"));
+    }
+
+    #[test]
+    fn base() {
+        assert_eq!(glob_base(Path::new("src/**/*.sol")), Path::new("src"));
+        assert_eq!(glob_base(Path::new("*.sol")), Path::new(""));
+        assert_eq!(glob_base(Path::new("src/a/[ab].sol")), Path::new("src/a"));
+    }
+}
@@ -0,0 +1,106 @@
+//! The import graph of the compiled sources, used to only compile again the affected inputs.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+/// The files that each source file imports directly, as of the last compilation of the source.
+///
+/// Paths are canonicalized, since the input files and the imports are not loaded with the same
+/// paths.
+#[derive(Default)]
+pub(crate) struct ImportGraph {
+    imports: BTreeMap<PathBuf, BTreeSet<PathBuf>>,
+}
+
+impl ImportGraph {
+    /// Records the imports of the compiled sources, keyed by source name.
+    pub(crate) fn update(&mut self, dependencies: &BTreeMap<String, BTreeSet<String>>) {
+        for (source, imports) in dependencies {
+            let imports = imports.iter().map(|import| canonicalize(Path::new(import))).collect();
+            self.imports.insert(canonicalize(Path::new(source)), imports);
+        }
+    }
+
+    /// Returns `path` and the files that it imports, directly or transitively.
+    pub(crate) fn imports(&self, path: &Path) -> BTreeSet<PathBuf> {
+        let path = canonicalize(path);
+        let mut stack = vec![path.clone()];
+        let mut imports = BTreeSet::from([path]);
+        while let Some(path) = stack.pop() {
+            for import in self.imports.get(&path).into_iter().flatten() {
+                if imports.insert(import.clone()) {
+                    stack.push(import.clone());
+                }
+            }
+        }
+        imports
+    }
+
+    /// Returns the `changed` files and the files that import them, directly or transitively.
+    ///
+    /// Returns `None` if one of the changed paths is not a compiled source file, such as an import
+    /// path or an input directory.
+    pub(crate) fn dependents(&self, changed: &[PathBuf]) -> Option<BTreeSet<PathBuf>> {
+        let mut dirty = changed.iter().map(|path| canonicalize(path)).collect::<BTreeSet<_>>();
+        if !dirty.iter().all(|path| self.imports.contains_key(path)) {
+            return None;
+        }
+        loop {
+            let len = dirty.len();
+            for (source, imports) in &self.imports {
+                if imports.iter().any(|import| dirty.contains(import)) {
+                    dirty.insert(source.clone());
+                }
+            }
+            if dirty.len() == len {
+                return Some(dirty);
+            }
+        }
+    }
+}
+
+pub(crate) fn canonicalize(path: &Path) -> PathBuf {
+    solar_interface::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn import_graph() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = |name: &str| dir.path().join(name);
+        for name in ["a.sol", "b.sol", "c.sol", "d.sol"] {
+            std::fs::write(path(name), "").unwrap();
+        }
+        let name = |name: &str| path(name).to_str().unwrap().to_string();
+        let dependencies = BTreeMap::from([
+            (name("a.sol"), BTreeSet::from([name("b.sol")])),
+            (name("b.sol"), BTreeSet::from([name("c.sol")])),
+            (name("c.sol"), BTreeSet::new()),
+            (name("d.sol"), BTreeSet::new()),
+        ]);
+        let mut graph = ImportGraph::default();
+        graph.update(&dependencies);
+
+        let names = |paths: BTreeSet<PathBuf>| {
+            paths.iter().map(|path| path.file_name().unwrap().to_owned()).collect::<Vec<_>>()
+        };
+        let dependents = |changed: &[&str]| {
+            let changed = changed.iter().map(|name| path(name)).collect::<Vec<_>>();
+            graph.dependents(&changed).map(names)
+        };
+        assert_eq!(dependents(&["c.sol"]).unwrap(), ["a.sol", "b.sol", "c.sol"]);
+        assert_eq!(dependents(&["a.sol"]).unwrap(), ["a.sol"]);
+        assert_eq!(dependents(&["d.sol"]).unwrap(), ["d.sol"]);
+        // Unknown paths, such as new files or import paths, affect all the inputs.
+        assert_eq!(dependents(&["e.sol"]), None);
+        assert_eq!(dependents(&[""]), None);
+
+        assert_eq!(names(graph.imports(&path("a.sol"))), ["a.sol", "b.sol", "c.sol"]);
+        assert_eq!(names(graph.imports(&path("c.sol"))), ["c.sol"]);
+    }
+}
@@ -5,17 +5,37 @@
 )]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
-use clap::Parser as _;
+use clap::{CommandFactory as _, Parser as _};
 use cli::Args;
 use solar_interface::{
-    diagnostics::{DiagCtxt, DynEmitter, HumanEmitter, JsonEmitter},
+    diagnostics::{
+        registry, DiagCtxt, DynEmitter, HumanEmitter, JsonEmitter, Level, Lint, SarifEmitter,
+    },
+    progress::Progress,
+    source_map::FileResolver,
+    timings::Timings,
     Result, Session, SourceMap,
 };
-use std::{collections::BTreeSet, num::NonZeroUsize, path::Path, sync::Arc};
+use std::{
+    collections::BTreeSet,
+    io::{IsTerminal as _, Write as _},
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
+mod baseline;
+mod cache;
 pub mod cli;
+pub mod config;
+pub mod daemon;
+mod glob;
+mod import_graph;
+mod selectors;
+pub mod standard_json;
 pub mod utils;
 pub mod version;
+pub mod watch;
 
 #[cfg(all(unix, any(target_env = "gnu", target_os = "macos")))]
 pub mod sigsegv_handler;
@@ -44,10 +64,131 @@ where
     Ok(args)
 }
 
-pub fn run_compiler_args(args: Args) -> Result<()> {
+pub fn run_compiler_args(mut args: Args) -> Result<()> {
+    if let Some(code) = &args.explain {
+        return explain(code);
+    }
+    if let Some(socket) = &args.daemon {
+        return daemon::run(&args, socket);
+    }
+    if !args.standard_json {
+        load_config(&mut args)?;
+        if args.input.is_empty() && args.command.is_none() {
+            let _ = Args::command().print_help();
+            return Ok(());
+        }
+    }
+    match args.command.clone() {
+        Some(cli::Command::Flatten(flatten)) => {
+            return run_compiler_with(args, |compiler| compiler.flatten(&flatten));
+        }
+        Some(cli::Command::Fmt(fmt)) => {
+            return run_compiler_with(args, |compiler| compiler.fmt(&fmt));
+        }
+        Some(cli::Command::AstGrep(ast_grep)) => {
+            return run_compiler_with(args, |compiler| compiler.ast_grep(&ast_grep));
+        }
+        Some(cli::Command::Selectors(selectors)) => {
+            // The selectors are read from the signature hashes.
+            args.emit = vec![solar_config::CompilerOutput::Hashes];
+            args.combined_json.clear();
+            return run_compiler_with(args, |compiler| selectors::run(compiler, &selectors));
+        }
+        None => {}
+    }
+    if args.watch {
+        return run_compiler_with(args, watch::run);
+    }
     run_compiler_with(args, Compiler::run_default)
 }
 
+/// Loads the configuration file, if any, and merges it into the arguments.
+fn load_config(args: &mut Args) -> Result<()> {
+    if args.no_config {
+        return Ok(());
+    }
+    let config = match &args.config {
+        Some(path) => config::Config::load(path).map(Some),
+        None => config::Config::find(Path::new("")),
+    };
+    match config {
+        Ok(Some(config)) => {
+            config.merge_into(args);
+            Ok(())
+        }
+        Ok(None) => Ok(()),
+        Err(e) => Err(DiagCtxt::new_early().err(e).emit()),
+    }
+}
+
+/// Prints the recorded compiler passes to stderr.
+fn print_timings(timings: &Timings, format: cli::TimePassesFormat) {
+    let passes = timings.passes();
+    let total = timings.total();
+    match format {
+        cli::TimePassesFormat::Human => {
+            let prefix = "time-passes";
+            let opt = |n: Option<usize>| n.map(|n| n.to_string()).unwrap_or_default();
+            let code_size = |size: Option<(usize, usize)>| {
+                size.map(|(before, after)| format!("{before} -> {after}")).unwrap_or_default()
+            };
+            let separator = "-".repeat(78);
+            eprintln!(
+                "{prefix} {:<16}{:>12}{:>8}{:>12}{:>10}{:>20}",
+                "Pass", "Time", "Files", "Bytes", "Nodes", "Code size"
+            );
+            eprintln!("{prefix} {separator}");
+            for pass in &passes {
+                eprintln!(
+                    "{prefix} {:<16}{:>12}{:>8}{:>12}{:>10}{:>20}",
+                    pass.name,
+                    format!("{:.3?}", pass.time),
+                    opt(pass.files),
+                    opt(pass.bytes),
+                    opt(pass.nodes),
+                    code_size(pass.code_size),
+                );
+            }
+            eprintln!("{prefix} {separator}");
+            eprintln!("{prefix} {:<16}{:>12}", "Total", format!("{total:.3?}"));
+        }
+        cli::TimePassesFormat::Json => {
+            let passes = passes
+                .iter()
+                .map(|pass| {
+                    serde_json::json!({
+                        "name": pass.name,
+                        "seconds": pass.time.as_secs_f64(),
+                        "files": pass.files,
+                        "bytes": pass.bytes,
+                        "nodes": pass.nodes,
+                        "code_size": pass.code_size.map(|(before, after)| {
+                            serde_json::json!({ "before": before, "after": after })
+                        }),
+                    })
+                })
+                .collect::<Vec<_>>();
+            let json = serde_json::json!({ "passes": passes, "seconds": total.as_secs_f64() });
+            eprintln!("{json}");
+        }
+    }
+}
+
+/// Prints the long explanation of the given diagnostic code.
+fn explain(code: &str) -> Result<()> {
+    match registry::explain(code) {
+        Some(explanation) => {
+            print!("{explanation}");
+            Ok(())
+        }
+        None => {
+            let msg = format!("`{code}` is not a known diagnostic code");
+            let help = "diagnostic codes are 4 digits long, such as `7576`";
+            Err(DiagCtxt::new_early().err(msg).help(help).emit())
+        }
+    }
+}
+
 pub struct Compiler {
     pub sess: Session,
     pub args: Args,
@@ -55,16 +196,22 @@ pub struct Compiler {
 
 impl Compiler {
     pub fn run_default(&self) -> Result<()> {
-        let Self { sess, args } = self;
-
-        if sess.language.is_yul() && !args.unstable.parse_yul {
-            return Err(sess.dcx.err("Yul is not supported yet").emit());
+        let file_resolver = self.file_resolver()?;
+        if let Some(cache_dir) = &self.args.cache_dir {
+            return cache::compile(self, cache_dir, file_resolver);
         }
+        self.compile(file_resolver)
+    }
 
-        // Partition arguments into three categories:
-        // - `stdin`: `-`, occurrences after the first are ignored
-        // - remappings: `path=mapped`
-        // - paths: everything else
+    /// Partitions the input arguments into three categories:
+    /// - `stdin`: `-`, occurrences after the first are ignored
+    /// - remappings: `path=mapped`
+    /// - paths: everything else
+    fn inputs(
+        &self,
+    ) -> (bool, impl Iterator<Item = cli::ImportMap> + '_, impl Iterator<Item = &PathBuf> + Clone)
+    {
+        let args = &self.args;
         let stdin = args.input.iter().any(|arg| *arg == Path::new("-"));
         let non_stdin_args = args.input.iter().filter(|arg| *arg != Path::new("-"));
         let arg_remappings = non_stdin_args
@@ -72,38 +219,232 @@ impl Compiler {
             .filter_map(|arg| arg.to_str().unwrap_or("").parse::<cli::ImportMap>().ok());
         let paths =
             non_stdin_args.filter(|arg| !arg.as_os_str().as_encoded_bytes().contains(&b'='));
+        (stdin, arg_remappings, paths)
+    }
 
-        let mut pcx = solar_sema::ParsingContext::new(sess);
+    /// Creates a file resolver with the import paths and maps specified in the arguments.
+    fn file_resolver(&self) -> Result<FileResolver<'_>> {
+        let Self { sess, args } = self;
+        let (_, arg_remappings, paths) = self.inputs();
+
+        let mut file_resolver = FileResolver::new(sess.source_map());
+        file_resolver.set_node_modules(args.node_modules);
+        // SAFETY: Opt-in, and rejected with `--watch` and `--daemon` in `Args::finish`; the user
+        // promises not to modify the sources during this single compilation.
+        unsafe { file_resolver.set_mmap_threshold(args.unstable.mmap_threshold) };
+        if !args.no_remappings_txt {
+            file_resolver
+                .load_remappings_txt(Path::new(""))
+                .map_err(|e| sess.dcx.err(e.to_string()).emit())?;
+        }
         let remappings = arg_remappings.chain(args.import_map.iter().cloned());
         for map in remappings {
-            pcx.file_resolver.add_import_map(map.map, map.path);
+            file_resolver.insert_import_map(map);
         }
-        for path in &args.import_path {
-            let new = pcx.file_resolver.add_import_path(path.clone());
+        let import_paths = args.base_path.iter().chain(&args.include_path).chain(&args.import_path);
+        for path in import_paths {
+            let new = file_resolver.add_import_path(path.clone());
             if !new {
                 let msg = format!("import path {} already specified", path.display());
                 return Err(sess.dcx.err(msg).emit());
             }
         }
+        if let Some(allow_paths) = &args.allow_paths {
+            let input_dirs = paths.map(|path| {
+                if path.is_dir() {
+                    path.clone()
+                } else if glob::is_glob(path) {
+                    glob::glob_base(path)
+                } else {
+                    path.parent().unwrap_or(Path::new("")).to_path_buf()
+                }
+            });
+            let map_targets = file_resolver.get_import_maps().iter().map(|m| m.path.clone());
+            let allowed = allow_paths
+                .iter()
+                .cloned()
+                .chain(file_resolver.get_import_paths().iter().cloned())
+                .chain(input_dirs)
+                .chain(map_targets)
+                .collect::<Vec<_>>();
+            file_resolver.allow_paths(allowed);
+        }
+        Ok(file_resolver)
+    }
+
+    /// Returns the input files, expanding the directories and glob patterns in the inputs.
+    fn input_paths(&self) -> Result<Vec<PathBuf>> {
+        let (_, _, paths) = self.inputs();
+        let extension = match self.sess.language {
+            solar_config::Language::Solidity => "sol",
+            solar_config::Language::Yul => "yul",
+        };
+        glob::expand_inputs(paths, extension, &self.args.exclude)
+            .map_err(|e| self.sess.dcx.err(e).emit())
+    }
+
+    /// Compiles the input files, resolving imports with the given file resolver.
+    ///
+    /// Directories and glob patterns in the inputs are expanded first.
+    fn compile(&self, file_resolver: FileResolver<'_>) -> Result<()> {
+        let (stdin, _, _) = self.inputs();
 
+        let mut pcx = solar_sema::ParsingContext::new(&self.sess);
+        pcx.file_resolver = file_resolver;
+        pcx.codegen = Some(&solar_codegen::EvmBackend);
         if stdin {
             pcx.load_stdin()?;
         }
-        pcx.load_files(paths)?;
+        pcx.load_files(&self.input_paths()?)?;
 
         pcx.parse_and_resolve()?;
 
         Ok(())
     }
 
+    /// Compiles the given files, resolving imports with the given file resolver, and stores the
+    /// requested outputs in `output` instead of writing them.
+    ///
+    /// The import graph of the loaded sources is always stored in `output`.
+    fn compile_files(
+        &self,
+        file_resolver: FileResolver<'_>,
+        paths: &[PathBuf],
+        output: &mut solar_sema::CombinedJson,
+    ) -> Result<()> {
+        let mut pcx = solar_sema::ParsingContext::new(&self.sess);
+        pcx.file_resolver = file_resolver;
+        pcx.codegen = Some(&solar_codegen::EvmBackend);
+        pcx.load_files(paths)?;
+        pcx.parse_and_resolve_output(output)
+    }
+
+    /// Flattens the given file and its imports, and writes the result to `--output`, or to stdout.
+    fn flatten(&self, args: &cli::FlattenArgs) -> Result<()> {
+        let mut pcx = solar_sema::ParsingContext::new(&self.sess);
+        pcx.file_resolver = self.file_resolver()?;
+        pcx.load_file(&args.file)?;
+        let flattened = pcx.flatten()?;
+        let r = match &args.output {
+            Some(path) => std::fs::write(path, flattened),
+            None => std::io::stdout().write_all(flattened.as_bytes()),
+        };
+        r.map_err(|e| self.sess.dcx.err(format!("failed to write flattened source: {e}")).emit())
+    }
+
+    /// Prints the nodes that match the query in the given files, or in the inputs if none are
+    /// given, as `file:line:column: code`.
+    fn ast_grep(&self, args: &cli::AstGrepArgs) -> Result<()> {
+        let sess = &self.sess;
+        let sm = sess.source_map();
+        let inputs = if args.paths.is_empty() { &self.args.input } else { &args.paths };
+        let paths = glob::expand_inputs(inputs, "sol", &self.args.exclude)
+            .map_err(|e| sess.dcx.err(e).emit())?;
+
+        for path in &paths {
+            let file = match sm.load_file(path) {
+                Ok(file) => file,
+                Err(e) => {
+                    sess.dcx.err(format!("failed to read {}: {e}", path.display())).emit();
+                    continue;
+                }
+            };
+            let Ok(spans) = solar_sema::ast_grep::grep_file(sess, &file, &args.query) else {
+                continue;
+            };
+            let mut out = String::new();
+            for span in spans {
+                let loc = sm.lookup_char_pos(span.lo());
+                let snippet = sm.span_to_snippet(span).unwrap_or_default();
+                let code = snippet.lines().next().unwrap_or_default().trim_end();
+                out.push_str(&format!(
+                    "{}:{}:{}: {code}\n",
+                    file.name.display(),
+                    loc.line,
+                    loc.col.to_usize() + 1
+                ));
+            }
+            let _ = std::io::stdout().write_all(out.as_bytes());
+        }
+        sess.dcx.has_errors()
+    }
+
+    /// Formats the given files, or the inputs if none are given.
+    ///
+    /// With `--check`, reports the files that are not formatted instead of writing them.
+    fn fmt(&self, args: &cli::FmtArgs) -> Result<()> {
+        let sess = &self.sess;
+        let inputs = if args.paths.is_empty() { &self.args.input } else { &args.paths };
+        let stdin = inputs.iter().any(|arg| *arg == Path::new("-"));
+        let paths = inputs.iter().filter(|arg| *arg != Path::new("-"));
+        let paths = glob::expand_inputs(paths, "sol", &self.args.exclude)
+            .map_err(|e| sess.dcx.err(e).emit())?;
+
+        let mut config = solar_fmt::FormatterConfig::default();
+        config.line_width = args.line_width;
+        config.tab_width = args.tab_width;
+
+        if stdin {
+            let file =
+                sess.source_map().load_stdin().map_err(|e| sess.dcx.err(e.to_string()).emit())?;
+            if let Ok(formatted) = solar_fmt::format_source_file(sess, &file, &config) {
+                if args.check {
+                    if formatted != file.src.as_str() {
+                        sess.dcx.err("<stdin> is not formatted").emit();
+                    }
+                } else {
+                    let _ = std::io::stdout().write_all(formatted.as_bytes());
+                }
+            }
+        }
+        for path in &paths {
+            let file = match sess.source_map().load_file(path) {
+                Ok(file) => file,
+                Err(e) => {
+                    sess.dcx.err(format!("failed to read {}: {e}", path.display())).emit();
+                    continue;
+                }
+            };
+            let Ok(formatted) = solar_fmt::format_source_file(sess, &file, &config) else {
+                continue;
+            };
+            if formatted == file.src.as_str() {
+                continue;
+            }
+            if args.check {
+                sess.dcx.err(format!("{} is not formatted", path.display())).emit();
+            } else if let Err(e) = std::fs::write(path, formatted) {
+                sess.dcx.err(format!("failed to write {}: {e}", path.display())).emit();
+            }
+        }
+        sess.dcx.has_errors()
+    }
+
     fn finish_diagnostics(&self) -> Result {
+        // The summary is not a diagnostic of the sources, so keep it out of the SARIF log.
+        if matches!(self.args.error_format, cli::ErrorFormat::Sarif) {
+            return self.sess.dcx.has_errors();
+        }
         self.sess.dcx.print_error_count()
     }
 }
 
 fn run_compiler_with(args: Args, f: impl FnOnce(&Compiler) -> Result + Send) -> Result {
+    if args.standard_json {
+        return standard_json::run(&args);
+    }
+
     let ui_testing = args.unstable.ui_testing;
     let source_map = Arc::new(SourceMap::empty());
+    let progress = if args.progress
+        && matches!(args.error_format, cli::ErrorFormat::Human)
+        && std::io::stderr().is_terminal()
+        && !ui_testing
+    {
+        Progress::stderr()
+    } else {
+        Progress::default()
+    };
     let emitter: Box<DynEmitter> = match args.error_format {
         cli::ErrorFormat::Human => {
             let color = match args.color {
@@ -111,9 +452,14 @@ fn run_compiler_with(args: Args, f: impl FnOnce(&Compiler) -> Result + Send) ->
                 clap::ColorChoice::Auto => solar_interface::ColorChoice::Auto,
                 clap::ColorChoice::Never => solar_interface::ColorChoice::Never,
             };
-            let human = HumanEmitter::stderr(color)
+            let mut human = HumanEmitter::stderr(color)
                 .source_map(Some(source_map.clone()))
+                .tab_width(args.tab_width)
+                .progress(progress.clone())
                 .ui_testing(ui_testing);
+            if let Some(width) = args.diagnostic_width {
+                human = human.term_width(width);
+            }
             Box::new(human)
         }
         cli::ErrorFormat::Json | cli::ErrorFormat::RustcJson => {
@@ -124,19 +470,56 @@ fn run_compiler_with(args: Args, f: impl FnOnce(&Compiler) -> Result + Send) ->
                 .ui_testing(ui_testing);
             Box::new(json)
         }
+        cli::ErrorFormat::Sarif => {
+            let writer = Box::new(std::io::BufWriter::new(std::io::stderr()));
+            let sarif = SarifEmitter::new(writer, source_map.clone()).pretty(args.pretty_json_err);
+            Box::new(sarif)
+        }
     };
-    let dcx = DiagCtxt::new(emitter).set_flags(|flags| {
+    let mut dcx = DiagCtxt::new(emitter).set_flags(|flags| {
         flags.deduplicate_diagnostics &= !ui_testing;
+        flags.error_limit = args.error_limit;
+        flags.docs_links = !args.no_docs_links && !ui_testing;
         flags.track_diagnostics &= !ui_testing;
         flags.track_diagnostics |= args.unstable.track_diagnostics;
     });
+    let lint_levels =
+        [(&args.allow, Level::Allow), (&args.warn, Level::Warning), (&args.deny, Level::Error)];
+    for (lints, level) in lint_levels {
+        for lint in lints {
+            dcx.set_lint_level(lint.clone(), level);
+        }
+    }
+    if args.deny_warnings {
+        dcx.set_lint_level(Lint::Warnings, Level::Error);
+    }
+    if let Some(path) = &args.baseline {
+        if args.write_baseline {
+            dcx.record_baseline();
+        } else {
+            dcx.set_baseline(baseline::read(&dcx, path)?);
+        }
+    }
 
     let mut sess = Session::new(dcx, source_map);
-    sess.evm_version = args.evm_version;
+    sess.evm_version = args.evm_version.unwrap_or_default();
+    if let Some(version) = &args.pragma_version {
+        sess.solidity_version = version.clone();
+    }
     sess.language = args.language;
     sess.stop_after = args.stop_after;
+    sess.metadata_hash = args.metadata_hash;
+    sess.cbor_metadata = !args.no_cbor_metadata;
+    sess.optimize = args.optimize;
+    sess.optimizer_runs = args.optimizer_runs;
+    sess.yul_optimizations = args.yul_optimizations.as_ref().map(ToString::to_string);
+    sess.libraries =
+        args.libraries.iter().map(|lib| (lib.name.clone(), lib.address.into_array())).collect();
     sess.dump = args.unstable.dump.clone();
     sess.ast_stats = args.unstable.ast_stats;
+    sess.drop_source_text = args.unstable.drop_source_text;
+    sess.time_passes = args.unstable.time_passes.is_some();
+    sess.progress = progress;
     sess.jobs = NonZeroUsize::new(args.threads)
         .unwrap_or_else(|| std::thread::available_parallelism().unwrap_or(NonZeroUsize::MIN));
     if !args.input.is_empty()
@@ -145,10 +528,12 @@ fn run_compiler_with(args: Args, f: impl FnOnce(&Compiler) -> Result + Send) ->
         sess.language = solar_config::Language::Yul;
     }
     sess.emit = {
+        let flag = if args.combined_json.is_empty() { "--emit" } else { "--combined-json" };
+        let combined_json = args.combined_json.iter().map(|&output| output.into());
         let mut set = BTreeSet::default();
-        for &emit in &args.emit {
+        for emit in args.emit.iter().copied().chain(combined_json) {
             if !set.insert(emit) {
-                let msg = format!("cannot specify `--emit {emit}` twice");
+                let msg = format!("cannot specify `{flag} {emit}` twice");
                 return Err(sess.dcx.err(msg).emit());
             }
         }
@@ -160,6 +545,13 @@ fn run_compiler_with(args: Args, f: impl FnOnce(&Compiler) -> Result + Send) ->
     let compiler = Compiler { sess, args };
     compiler.sess.enter(|| {
         let mut r = f(&compiler);
+        compiler.sess.progress.finish();
+        if let (true, Some(path)) = (compiler.args.write_baseline, &compiler.args.baseline) {
+            r = baseline::write(&compiler.sess.dcx, path).and(r);
+        }
+        if let Some(format) = compiler.args.unstable.time_passes {
+            print_timings(&compiler.sess.timings, format);
+        }
         r = compiler.finish_diagnostics().and(r);
         r
     })
@@ -0,0 +1,134 @@
+//! The `selectors` subcommand.
+
+use crate::{cli::SelectorsArgs, Compiler};
+use solar_interface::{source_map::FileName, Result};
+use std::{
+    fmt::Write as _,
+    io::{self, Write as _},
+    path::Path,
+};
+
+/// Prints the selectors of the signature or of the contracts of the source file in `args`.
+///
+/// The signatures and selectors are taken from the `hashes` output, so they are canonicalized by
+/// the same code as the ABI.
+pub(crate) fn run(compiler: &Compiler, args: &SelectorsArgs) -> Result<()> {
+    let sess = &compiler.sess;
+    let mut pcx = solar_sema::ParsingContext::new(sess);
+    pcx.file_resolver = compiler.file_resolver()?;
+
+    let input = args.input.as_str();
+    let file_name = if input.contains('(') && !Path::new(input).is_file() {
+        let src = signature_source(input);
+        let file = sess
+            .source_map()
+            .new_source_file(FileName::Custom("signature".into()), || Ok(src))
+            .map_err(|e| sess.dcx.err(e.to_string()).emit())?;
+        pcx.add_file(file);
+        None
+    } else {
+        pcx.load_file(Path::new(input))?;
+        sess.source_map().files().last().map(|file| file.name.display().to_string())
+    };
+    let mut output = solar_sema::CombinedJson::new();
+    pcx.parse_and_resolve_output(&mut output)?;
+
+    let mut s = String::new();
+    let Some(file_name) = file_name else {
+        // The signature is the only item declared in the source.
+        for contract in output.contracts.values() {
+            let hashes = contract.error_hashes.iter().chain(&contract.event_hashes).flatten();
+            for (signature, selector) in hashes {
+                let _ = writeln!(s, "0x{selector}  {signature}");
+            }
+        }
+        return write_stdout(compiler, &s);
+    };
+    for (name, contract) in &output.contracts {
+        // Skip the contracts of the imported files.
+        if name.rsplit_once(':').is_none_or(|(file, _)| file != file_name) {
+            continue;
+        }
+        let mut items = Vec::new();
+        for (kind, hashes) in [
+            ("function", &contract.hashes),
+            ("error", &contract.error_hashes),
+            ("event", &contract.event_hashes),
+        ] {
+            items.extend(hashes.iter().flatten().map(|(sig, sel)| (kind, sig, sel)));
+        }
+        if items.is_empty() {
+            continue;
+        }
+        let _ = writeln!(s, "{name}");
+        for (kind, signature, selector) in items {
+            let _ = writeln!(s, "    0x{selector}  {kind} {signature}");
+        }
+    }
+    write_stdout(compiler, &s)
+}
+
+fn write_stdout(compiler: &Compiler, s: &str) -> Result<()> {
+    io::stdout()
+        .write_all(s.as_bytes())
+        .map_err(|e| compiler.sess.dcx.err(format!("failed to write to stdout: {e}")).emit())
+}
+
+/// Returns a source file that declares the item with the given signature, like
+/// `transfer(address,uint256)` or `event Transfer(address indexed, address indexed, uint)`.
+///
+/// Functions are declared as errors, which have the same selectors but can be declared without a
+/// body or a visibility. Anything after the parameter list, such as modifiers and return
+/// parameters, is ignored.
+fn signature_source(signature: &str) -> String {
+    let signature = signature.trim();
+    let (keyword, signature) = match signature.split_once(char::is_whitespace) {
+        Some((keyword @ ("function" | "event" | "error"), rest)) => (keyword, rest.trim_start()),
+        _ => ("function", signature),
+    };
+    let item = if keyword == "event" { "event" } else { "error" };
+    let end = parameters_end(signature).unwrap_or(signature.len());
+    format!("contract Selectors {{\n    {item} {};\n}}\n", &signature[..end])
+}
+
+/// Returns the end of the first parenthesized list in `s`, after the closing parenthesis.
+fn parameters_end(s: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signatures() {
+        let source = |item: &str| format!("contract Selectors {{\n    {item};\n}}\n");
+        assert_eq!(signature_source("f()"), source("error f()"));
+        assert_eq!(
+            signature_source(" function transfer(address to, uint amount)"),
+            source("error transfer(address to, uint amount)")
+        );
+        assert_eq!(
+            signature_source("function balanceOf(address) external view returns (uint256)"),
+            source("error balanceOf(address)")
+        );
+        assert_eq!(
+            signature_source("event Transfer(address indexed, address indexed, uint)"),
+            source("event Transfer(address indexed, address indexed, uint)")
+        );
+        assert_eq!(signature_source("error E(uint[2][])"), source("error E(uint[2][])"));
+    }
+}
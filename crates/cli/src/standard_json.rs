@@ -0,0 +1,587 @@
+//! Standard JSON input/output mode, enabled with `--standard-json`.
+//!
+//! See <https://docs.soliditylang.org/en/latest/using-the-compiler.html#compiler-input-and-output-json-description>.
+
+use crate::cli::{Args, ImportMap};
+use alloy_primitives::Address;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use solar_config::{CompilerOutput, CompilerStage, EvmVersion, MetadataHash};
+use solar_interface::{
+    diagnostics::{DiagCtxt, JsonEmitter},
+    source_map::FileName,
+    Result, Session, SourceMap,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    io::{self, Read, Write},
+    num::NonZeroUsize,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+/// The standard JSON input.
+#[derive(Deserialize)]
+struct Input {
+    language: String,
+    sources: BTreeMap<String, InputSource>,
+    #[serde(default)]
+    settings: Settings,
+}
+
+/// A single source file in the standard JSON input.
+#[derive(Deserialize)]
+struct InputSource {
+    content: Option<String>,
+    #[serde(default)]
+    urls: Vec<PathBuf>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Settings {
+    #[serde(default)]
+    remappings: Vec<String>,
+    evm_version: Option<EvmVersion>,
+    stop_after: Option<CompilerStage>,
+    #[serde(default)]
+    optimizer: OptimizerSettings,
+    #[serde(default)]
+    metadata: MetadataSettings,
+    /// `file -> library -> address`.
+    #[serde(default)]
+    libraries: BTreeMap<String, BTreeMap<String, Address>>,
+    #[serde(default)]
+    output_selection: OutputSelection,
+}
+
+/// The outputs to produce, as `file -> contract -> outputs`.
+///
+/// Like in solc, `*` matches all the files, all the contracts or all the outputs, and the empty
+/// contract name selects the outputs of the file itself, like `ast`. Selecting an output also
+/// selects its sub-outputs, like `evm` and `evm.methodIdentifiers`.
+#[derive(Default, Deserialize)]
+#[serde(transparent)]
+struct OutputSelection(BTreeMap<String, BTreeMap<String, Vec<String>>>);
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OptimizerSettings {
+    #[serde(default)]
+    enabled: bool,
+    #[serde(default = "default_runs")]
+    runs: u32,
+    #[serde(default)]
+    details: OptimizerDetails,
+}
+
+impl Default for OptimizerSettings {
+    fn default() -> Self {
+        Self { enabled: false, runs: default_runs(), details: OptimizerDetails::default() }
+    }
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OptimizerDetails {
+    #[serde(default)]
+    yul_details: YulDetails,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YulDetails {
+    optimizer_steps: Option<String>,
+}
+
+fn default_runs() -> u32 {
+    200
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MetadataSettings {
+    #[serde(default)]
+    bytecode_hash: MetadataHash,
+    #[serde(default = "default_true", rename = "appendCBOR")]
+    append_cbor: bool,
+}
+
+impl Default for MetadataSettings {
+    fn default() -> Self {
+        Self { bytecode_hash: MetadataHash::default(), append_cbor: true }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// A cloneable writer that collects the diagnostics emitted by the session.
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Reads the standard JSON input from stdin or from the input file, compiles it, and writes the
+/// standard JSON output to stdout.
+///
+/// Compilation errors are reported in the `errors` field of the output rather than as a failed
+/// result.
+pub fn run(args: &Args) -> Result<()> {
+    let output = match read_input(&args.input) {
+        Ok(input) => compile_json(args, &input),
+        Err(e) => json!({ "errors": [general_error("JSONError", &e.to_string())] }),
+    };
+    let stdout = io::stdout().lock();
+    let mut writer = io::BufWriter::new(stdout);
+    let r = if args.pretty_json {
+        serde_json::to_writer_pretty(&mut writer, &output)
+    } else {
+        serde_json::to_writer(&mut writer, &output)
+    };
+    r.map_err(io::Error::from)
+        .and_then(|()| writeln!(writer))
+        .and_then(|()| writer.flush())
+        .map_err(|e| DiagCtxt::new_early().err(format!("failed to write to stdout: {e}")).emit())
+}
+
+/// Compiles the given standard JSON input and returns the standard JSON output.
+///
+/// Like [`run`], but without reading from stdin or writing to stdout.
+pub fn compile_json(args: &Args, input: &str) -> Value {
+    compile_json_with_files(args, input).0
+}
+
+/// Like [`compile_json`], but also returns the paths of the files that were read from disk, or
+/// that were looked up when resolving imports but did not exist.
+///
+/// The paths of the sources given with `content` are also returned.
+pub(crate) fn compile_json_with_files(args: &Args, input: &str) -> (Value, Vec<PathBuf>) {
+    let mut files = Vec::new();
+    let output = match serde_json::from_str(input) {
+        Ok(input) => compile(args, input, &mut files),
+        Err(e) => json!({ "errors": [general_error("JSONError", &e.to_string())] }),
+    };
+    (output, files)
+}
+
+fn read_input(paths: &[PathBuf]) -> io::Result<String> {
+    match paths {
+        [path] if path.as_os_str() != "-" => fs::read_to_string(path),
+        [] | [_] => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            Ok(input)
+        }
+        _ => {
+            let msg = "only one standard JSON input file can be given";
+            Err(io::Error::new(io::ErrorKind::InvalidInput, msg))
+        }
+    }
+}
+
+fn compile(args: &Args, input: Input, files: &mut Vec<PathBuf>) -> Value {
+    if input.language != "Solidity" {
+        let msg = format!("only \"Solidity\" is supported as a language, got {:?}", input.language);
+        return json!({ "errors": [general_error("JSONError", &msg)] });
+    }
+
+    let buffer = SharedBuffer::default();
+    let source_map = Arc::new(SourceMap::empty());
+    let emitter = JsonEmitter::new(Box::new(buffer.clone()), source_map.clone())
+        .ui_testing(args.unstable.ui_testing);
+    let dcx = DiagCtxt::new(Box::new(emitter)).set_flags(|flags| {
+        flags.track_diagnostics = false;
+    });
+
+    let mut sess = Session::new(dcx, source_map);
+    sess.evm_version = input.settings.evm_version.unwrap_or_default();
+    if let Some(version) = &args.pragma_version {
+        sess.solidity_version = version.clone();
+    }
+    sess.stop_after = input.settings.stop_after;
+    sess.metadata_hash = input.settings.metadata.bytecode_hash;
+    sess.cbor_metadata = input.settings.metadata.append_cbor;
+    sess.optimize = input.settings.optimizer.enabled;
+    sess.optimizer_runs = input.settings.optimizer.runs;
+    sess.yul_optimizations = input.settings.optimizer.details.yul_details.optimizer_steps.clone();
+    sess.libraries = input
+        .settings
+        .libraries
+        .iter()
+        .flat_map(|(file, libraries)| {
+            libraries
+                .iter()
+                .map(move |(name, address)| (format!("{file}:{name}"), address.into_array()))
+        })
+        .collect();
+    sess.jobs = NonZeroUsize::new(args.threads)
+        .unwrap_or_else(|| std::thread::available_parallelism().unwrap_or(NonZeroUsize::MIN));
+    sess.emit = input.settings.output_selection.compiler_outputs();
+
+    let mut output = solar_sema::CombinedJson::new();
+    let result = sess.enter(|| {
+        let mut pcx = solar_sema::ParsingContext::new(&sess);
+        pcx.codegen = Some(&solar_codegen::EvmBackend);
+        for remapping in &input.settings.remappings {
+            match remapping.parse::<ImportMap>() {
+                Ok(map) => pcx.file_resolver.insert_import_map(map),
+                Err(e) => {
+                    let msg = format!("invalid remapping {remapping:?}: {e}");
+                    let _ = sess.dcx.err(msg).emit();
+                }
+            }
+        }
+        for path in &args.import_path {
+            pcx.file_resolver.add_import_path(path.clone());
+        }
+        // Like in solc, only the current directory and the import paths can be read by default.
+        let allowed = args.allow_paths.iter().flatten().chain(&args.import_path).cloned();
+        pcx.file_resolver.allow_paths(allowed.chain([PathBuf::from(".")]));
+
+        for (name, source) in input.sources {
+            if let Some(content) = source.content {
+                // Added as a virtual file so that imports between the input sources are resolved
+                // without reading from the file system.
+                let file = pcx
+                    .file_resolver
+                    .add_virtual_file(PathBuf::from(&name), content)
+                    .map_err(|e| sess.dcx.err(format!("failed to load {name:?}: {e}")).emit())?;
+                pcx.add_file(file);
+            } else if source.urls.is_empty() {
+                let msg = format!("source {name:?} must have either `content` or `urls`");
+                return Err(sess.dcx.err(msg).emit());
+            } else {
+                let mut errors = Vec::new();
+                let file = source.urls.iter().find_map(|url| {
+                    pcx.file_resolver.resolve_file(url, None).map_err(|e| errors.push(e)).ok()
+                });
+                let Some(file) = file else {
+                    let msg = format!("cannot import {name:?}: file not found");
+                    let note = errors.iter().map(ToString::to_string).collect::<Vec<_>>();
+                    return Err(sess.dcx.err(msg).note(note.join("\n")).emit());
+                };
+                pcx.add_file(file);
+            }
+        }
+
+        // The clones of the resolver share the missing files.
+        let file_resolver = pcx.file_resolver.clone();
+        let result = pcx.parse_and_resolve_output(&mut output);
+        files.extend(file_resolver.missing_files());
+        result
+    });
+    let source_map = sess.source_map();
+    files.extend(source_map.files().iter().filter_map(|file| match &file.name {
+        FileName::Real(path) => Some(path.clone()),
+        _ => None,
+    }));
+
+    let errors = std::mem::take(&mut *buffer.0.lock().unwrap());
+    let mut errors = errors
+        .split(|&b| b == b'\n')
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| serde_json::from_slice::<Value>(line).ok())
+        .collect::<Vec<_>>();
+    sort_errors(&mut errors);
+
+    let selection = &input.settings.output_selection;
+    let mut out = Map::new();
+    out.insert("errors".into(), errors.into());
+
+    // Like in solc, the source IDs of all the parsed sources are returned even if compilation
+    // failed in a later stage, along with their ASTs if they were selected.
+    let mut sources = Map::new();
+    for (id, name) in output.source_ids.names().iter().enumerate() {
+        let name = name.display().to_string();
+        let mut source = Map::new();
+        if selection.is_selected(&name, "", "ast") {
+            if let Some(ast) = output.sources.remove(&name) {
+                source.insert("ast".into(), ast.ast);
+            }
+        }
+        source.insert("id".into(), id.into());
+        sources.insert(name, source.into());
+    }
+    if !sources.is_empty() {
+        out.insert("sources".into(), sources.into());
+    }
+    if result.is_err() {
+        return out.into();
+    }
+
+    let mut contracts = BTreeMap::<String, Map<String, Value>>::new();
+    for (full_name, contract) in output.contracts {
+        let (file, name) = full_name.rsplit_once(':').unwrap_or(("", full_name.as_str()));
+        let is_selected = |output: &str| selection.is_selected(file, name, output);
+        let mut artifact = Map::new();
+        if let Some(abi) = contract.abi.filter(|_| is_selected("abi")) {
+            artifact.insert("abi".into(), serde_json::to_value(abi).unwrap());
+        }
+        if let Some(devdoc) = contract.devdoc.filter(|_| is_selected("devdoc")) {
+            artifact.insert("devdoc".into(), serde_json::to_value(devdoc).unwrap());
+        }
+        let mut evm = Map::new();
+        for (key, object, source_map) in [
+            ("bytecode", contract.bin, contract.srcmap),
+            ("deployedBytecode", contract.bin_runtime, contract.srcmap_runtime),
+        ] {
+            let mut bytecode = Map::new();
+            if let Some(object) = object.filter(|_| is_selected(&format!("evm.{key}.object"))) {
+                bytecode.insert("object".into(), object.into());
+            }
+            if let Some(source_map) =
+                source_map.filter(|_| is_selected(&format!("evm.{key}.sourceMap")))
+            {
+                bytecode.insert("sourceMap".into(), source_map.into());
+            }
+            if !bytecode.is_empty() {
+                evm.insert(key.into(), bytecode.into());
+            }
+        }
+        if let Some(hashes) = contract.hashes.filter(|_| is_selected("evm.methodIdentifiers")) {
+            evm.insert("methodIdentifiers".into(), json!(hashes));
+        }
+        if !evm.is_empty() {
+            artifact.insert("evm".into(), evm.into());
+        }
+        if let Some(ir) = contract.ir.filter(|_| is_selected("ir")) {
+            artifact.insert("ir".into(), ir.into());
+        }
+        if let Some(metadata) = contract.metadata.filter(|_| is_selected("metadata")) {
+            artifact.insert("metadata".into(), metadata.into());
+        }
+        if let Some(layout) = contract.storage_layout.filter(|_| is_selected("storageLayout")) {
+            artifact.insert("storageLayout".into(), serde_json::to_value(layout).unwrap());
+        }
+        if let Some(userdoc) = contract.userdoc.filter(|_| is_selected("userdoc")) {
+            artifact.insert("userdoc".into(), serde_json::to_value(userdoc).unwrap());
+        }
+        if !artifact.is_empty() {
+            contracts
+                .entry(file.to_string())
+                .or_default()
+                .insert(name.to_string(), artifact.into());
+        }
+    }
+    if !contracts.is_empty() {
+        out.insert("contracts".into(), serde_json::to_value(contracts).unwrap());
+    }
+
+    out.into()
+}
+
+/// Sorts `errors` in canonical order, so that the output does not depend on the order in which the
+/// sources were resolved and checked.
+///
+/// Errors without a source location come first, then errors are sorted by file name and by
+/// position in the file. The sort is stable, so errors at the same location keep their order.
+fn sort_errors(errors: &mut [Value]) {
+    errors.sort_by_cached_key(|error| {
+        let location = error.get("sourceLocation").filter(|location| location.is_object())?;
+        let file = location["file"].as_str().unwrap_or_default().to_string();
+        Some((file, location["start"].as_i64(), location["end"].as_i64()))
+    });
+}
+
+/// The outputs of each contract that can be selected, and the compiler outputs they require.
+const CONTRACT_OUTPUTS: &[(&str, CompilerOutput)] = &[
+    ("abi", CompilerOutput::Abi),
+    ("devdoc", CompilerOutput::Devdoc),
+    ("evm.bytecode.object", CompilerOutput::Bin),
+    ("evm.bytecode.sourceMap", CompilerOutput::Srcmap),
+    ("evm.deployedBytecode.object", CompilerOutput::BinRuntime),
+    ("evm.deployedBytecode.sourceMap", CompilerOutput::SrcmapRuntime),
+    ("evm.methodIdentifiers", CompilerOutput::Hashes),
+    ("ir", CompilerOutput::Ir),
+    ("metadata", CompilerOutput::Metadata),
+    ("storageLayout", CompilerOutput::StorageLayout),
+    ("userdoc", CompilerOutput::Userdoc),
+];
+
+/// The outputs of each file that can be selected, and the compiler outputs they require.
+const FILE_OUTPUTS: &[(&str, CompilerOutput)] = &[("ast", CompilerOutput::Ast)];
+
+impl OutputSelection {
+    /// Returns the union of all the compiler outputs that are selected for any file or contract.
+    fn compiler_outputs(&self) -> BTreeSet<CompilerOutput> {
+        let mut outputs = BTreeSet::new();
+        for (contract, selected) in self.0.values().flatten() {
+            let available = if contract.is_empty() { FILE_OUTPUTS } else { CONTRACT_OUTPUTS };
+            for &(output, compiler_output) in available {
+                if selected.iter().any(|selected| matches_output(selected, output)) {
+                    outputs.insert(compiler_output);
+                }
+            }
+        }
+        outputs
+    }
+
+    /// Returns `true` if `output` is selected for `contract` in `file`, or for `file` itself if
+    /// `contract` is empty.
+    fn is_selected(&self, file: &str, contract: &str, output: &str) -> bool {
+        let contracts: &[&str] = if contract.is_empty() { &[""] } else { &[contract, "*"] };
+        [file, "*"]
+            .iter()
+            .filter_map(|file| self.0.get(*file))
+            .flat_map(|selection| contracts.iter().filter_map(|contract| selection.get(*contract)))
+            .flatten()
+            .any(|selected| matches_output(selected, output))
+    }
+}
+
+/// Returns `true` if the `selected` output name selects `output`.
+///
+/// The outputs of code generation are not selected by `*` or `evm`, and must be selected
+/// explicitly, since code generation only supports a subset of the language. This is similar to
+/// how solc treats its experimental outputs.
+fn matches_output(selected: &str, output: &str) -> bool {
+    match selected {
+        "*" | "evm" => {
+            let codegen = output == "ir"
+                || output.starts_with("evm.bytecode.")
+                || output.starts_with("evm.deployedBytecode.");
+            !codegen && (selected == "*" || output.starts_with("evm."))
+        }
+        _ => {
+            output == selected
+                || output.strip_prefix(selected).is_some_and(|rest| rest.starts_with('.'))
+        }
+    }
+}
+
+/// Creates an error that is not associated with any source location.
+fn general_error(kind: &str, message: &str) -> Value {
+    json!({
+        "component": "general",
+        "formattedMessage": format!("{kind}: {message}"),
+        "message": message,
+        "severity": "error",
+        "type": kind,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(threads: &str) -> Args {
+        crate::parse_args(["solar", "--standard-json", "--threads", threads]).unwrap()
+    }
+
+    #[test]
+    fn deterministic_output() {
+        let input = json!({
+            "language": "Solidity",
+            "sources": {
+                "a.sol": { "content": "contract A { function f() external pure { uint x; } }" },
+                "b.sol": { "content": "import \"b.sol\";\nimport \"a.sol\";\ncontract B is A {}" },
+                "c.sol": { "content": "import \"b.sol\";\ncontract C is B {}" },
+            },
+            "settings": { "outputSelection": { "*": { "*": ["*"], "": ["ast"] } } },
+        })
+        .to_string();
+
+        let output = compile_json(&args("1"), &input);
+        // The parser warning in `b.sol` is emitted before the type checker warning in `a.sol`.
+        let files = output["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|error| error["sourceLocation"]["file"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert!(files.is_sorted());
+        assert_eq!((files.first(), files.last()), (Some(&"a.sol"), Some(&"b.sol")));
+
+        let expected = output.to_string();
+        for threads in ["1", "4", "1", "4"] {
+            assert_eq!(compile_json(&args(threads), &input).to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn output_selection() {
+        let selection: OutputSelection = serde_json::from_value(json!({
+            "a.sol": { "A": ["abi", "evm.bytecode"], "": ["ast"] },
+            "*": { "*": ["evm"], "C": ["*"] },
+        }))
+        .unwrap();
+        assert!(selection.is_selected("a.sol", "A", "abi"));
+        assert!(selection.is_selected("a.sol", "A", "evm.bytecode.object"));
+        assert!(selection.is_selected("a.sol", "A", "evm.methodIdentifiers"));
+        assert!(!selection.is_selected("a.sol", "B", "abi"));
+        assert!(!selection.is_selected("a.sol", "B", "evm.bytecode.object"));
+        assert!(selection.is_selected("a.sol", "", "ast"));
+        assert!(!selection.is_selected("b.sol", "", "ast"));
+        assert!(selection.is_selected("b.sol", "C", "abi"));
+        assert!(!selection.is_selected("b.sol", "C", "ir"));
+        assert_eq!(
+            selection.compiler_outputs(),
+            BTreeSet::from([
+                CompilerOutput::Abi,
+                CompilerOutput::Ast,
+                CompilerOutput::Bin,
+                CompilerOutput::Devdoc,
+                CompilerOutput::Hashes,
+                CompilerOutput::Metadata,
+                CompilerOutput::Srcmap,
+                CompilerOutput::StorageLayout,
+                CompilerOutput::Userdoc,
+            ])
+        );
+    }
+
+    #[test]
+    fn sources_with_errors() {
+        let input = json!({
+            "language": "Solidity",
+            "sources": {
+                "a.sol": { "content": "contract A { function f() external { g(); } }" },
+                "b.sol": { "content": "contract B {}" },
+            },
+            "settings": { "outputSelection": { "*": { "*": ["abi"], "": ["ast"] } } },
+        })
+        .to_string();
+
+        let output = compile_json(&args("1"), &input);
+        assert_eq!(output["errors"][0]["severity"], "error");
+        assert_eq!(output["sources"]["a.sol"]["id"], 0);
+        assert_eq!(output["sources"]["a.sol"]["ast"]["nodeType"], "SourceUnit");
+        assert_eq!(output["sources"]["b.sol"]["id"], 1);
+        assert!(output.get("contracts").is_none());
+    }
+
+    #[test]
+    fn allowed_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.sol");
+        std::fs::write(&path, "contract A {}").unwrap();
+        let input = json!({
+            "language": "Solidity",
+            "sources": { "a.sol": { "urls": [path] } },
+            "settings": { "outputSelection": { "*": { "*": ["abi"] } } },
+        })
+        .to_string();
+
+        // Only the current directory and the import paths are allowed by default.
+        let output = compile_json(&args("1"), &input);
+        let message = output["errors"][0]["formattedMessage"].as_str().unwrap();
+        assert!(message.contains("outside of the allowed paths"), "{message}");
+
+        let dir_arg = dir.path().to_str().unwrap();
+        let args = crate::parse_args(["solar", "--standard-json", "--allow-paths", dir_arg]);
+        let (output, files) = compile_json_with_files(&args.unwrap(), &input);
+        assert!(output["errors"].as_array().unwrap().is_empty(), "{output}");
+        assert_eq!(files, [path]);
+    }
+}
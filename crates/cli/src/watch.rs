@@ -0,0 +1,138 @@
+//! Watch mode, enabled with `--watch`.
+
+use crate::{
+    import_graph::{canonicalize, ImportGraph},
+    Compiler,
+};
+use solar_interface::{
+    source_map::{FileName, FileResolver},
+    Result,
+};
+use solar_sema::CombinedJson;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+/// How often the watched paths are checked for modifications.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Compiles the input files, and recompiles them whenever one of the loaded source files or import
+/// paths is modified.
+///
+/// The source map and the file resolver are kept alive across compilations, so only the modified
+/// files are read again from disk. Only the input files that import a modified file, directly or
+/// transitively, are compiled again, and only their diagnostics are printed. All the input files
+/// are compiled again if outputs are emitted, since they are written for all the inputs at once,
+/// or if an import path or an input directory is modified.
+///
+/// Only returns if the arguments are invalid.
+pub fn run(compiler: &Compiler) -> Result<()> {
+    let sess = &compiler.sess;
+    let (stdin, _, _) = compiler.inputs();
+    if stdin {
+        return Err(sess.dcx.err("cannot read from stdin in watch mode").emit());
+    }
+    let file_resolver = compiler.file_resolver()?;
+    let mut graph = ImportGraph::default();
+    let mut changed: Option<Vec<PathBuf>> = None;
+    loop {
+        let _ = compile(compiler, file_resolver.clone(), &mut graph, changed.as_deref());
+        let _ = sess.dcx.print_error_count();
+
+        let mut watched = watched_paths(compiler, file_resolver.get_import_paths());
+        sess.dcx.note(format!("watching {} paths for changes", watched.len())).emit();
+        changed = Some(loop {
+            let modified = wait_for_changes(&mut watched)
+                .into_iter()
+                .filter(|path| reload(compiler, path))
+                .collect::<Vec<_>>();
+            if !modified.is_empty() {
+                break modified;
+            }
+        });
+        sess.dcx.reset();
+    }
+}
+
+/// Compiles the input files that are affected by the `changed` paths, or all of them if `changed`
+/// is `None`, and records their imports in `graph`.
+fn compile(
+    compiler: &Compiler,
+    file_resolver: FileResolver<'_>,
+    graph: &mut ImportGraph,
+    changed: Option<&[PathBuf]>,
+) -> Result<()> {
+    let sess = &compiler.sess;
+    if !sess.emit.is_empty() {
+        return compiler.compile(file_resolver);
+    }
+
+    let mut inputs = compiler.input_paths()?;
+    if let Some(dirty) = changed.and_then(|changed| graph.dependents(changed)) {
+        let total = inputs.len();
+        inputs.retain(|path| dirty.contains(&canonicalize(path)));
+        if inputs.is_empty() {
+            return Ok(());
+        }
+        sess.dcx.note(format!("recompiling {} of {total} input files", inputs.len())).emit();
+    }
+
+    let mut output = CombinedJson::new();
+    let result = compiler.compile_files(file_resolver, &inputs, &mut output);
+    graph.update(&output.dependencies);
+    result
+}
+
+/// Reloads the modified file at `path`, if it is loaded in the source map.
+///
+/// Returns `false` if the contents of the file did not change.
+fn reload(compiler: &Compiler, path: &Path) -> bool {
+    let sm = compiler.sess.source_map();
+    let Some(old) = sm.source_file_by_file_name(&path.to_path_buf().into()) else { return true };
+    match sm.reload_file(path) {
+        Ok(new) => old.is_src_dropped() || old.src.as_str() != new.src.as_str(),
+        // Errors are reported when the file is resolved again.
+        Err(_) => true,
+    }
+}
+
+/// Returns the paths to watch, along with their current modification time.
+///
+/// These are the input files, all the files loaded in the source map, and the import paths.
+fn watched_paths(
+    compiler: &Compiler,
+    import_paths: &[PathBuf],
+) -> BTreeMap<PathBuf, Option<SystemTime>> {
+    let (_, _, inputs) = compiler.inputs();
+    let files = compiler.sess.source_map().files();
+    let loaded = files.iter().filter_map(|file| match &file.name {
+        FileName::Real(path) => Some(path),
+        _ => None,
+    });
+    inputs.chain(loaded).chain(import_paths).map(|path| (path.clone(), modified(path))).collect()
+}
+
+/// Blocks until any of the watched paths is modified, created, or removed, and returns the
+/// changed paths, updating their modification times in `watched`.
+fn wait_for_changes(watched: &mut BTreeMap<PathBuf, Option<SystemTime>>) -> Vec<PathBuf> {
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        let mut changed = Vec::new();
+        for (path, time) in watched.iter_mut() {
+            let new_time = modified(path);
+            if new_time != *time {
+                *time = new_time;
+                changed.push(path.clone());
+            }
+        }
+        if !changed.is_empty() {
+            return changed;
+        }
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
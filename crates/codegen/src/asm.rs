@@ -0,0 +1,414 @@
+//! EVM assembly.
+
+use alloy_primitives::U256;
+use solar_interface::{config::EvmVersion, Span, Symbol};
+
+mod peephole;
+
+pub(super) const STOP: u8 = 0x00;
+pub(super) const EQ: u8 = 0x14;
+pub(super) const ISZERO: u8 = 0x15;
+pub(super) const POP: u8 = 0x50;
+pub(super) const JUMP: u8 = 0x56;
+pub(super) const JUMPI: u8 = 0x57;
+pub(super) const JUMPDEST: u8 = 0x5b;
+pub(super) const PUSH0: u8 = 0x5f;
+pub(super) const DUP1: u8 = 0x80;
+pub(super) const SWAP1: u8 = 0x90;
+
+/// An EVM opcode that can be called as a Yul builtin.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Opcode {
+    /// The opcode byte.
+    pub(super) code: u8,
+    /// The number of stack items that the opcode consumes.
+    pub(super) inputs: usize,
+    /// The number of stack items that the opcode produces.
+    pub(super) outputs: usize,
+}
+
+/// Returns the opcode of the Yul builtin with the given name, if it maps to a single opcode.
+pub(super) fn opcode(name: &str) -> Option<Opcode> {
+    let (code, inputs, outputs) = match name {
+        "stop" => (0x00, 0, 0),
+        "add" => (0x01, 2, 1),
+        "mul" => (0x02, 2, 1),
+        "sub" => (0x03, 2, 1),
+        "div" => (0x04, 2, 1),
+        "sdiv" => (0x05, 2, 1),
+        "mod" => (0x06, 2, 1),
+        "smod" => (0x07, 2, 1),
+        "addmod" => (0x08, 3, 1),
+        "mulmod" => (0x09, 3, 1),
+        "exp" => (0x0a, 2, 1),
+        "signextend" => (0x0b, 2, 1),
+        "lt" => (0x10, 2, 1),
+        "gt" => (0x11, 2, 1),
+        "slt" => (0x12, 2, 1),
+        "sgt" => (0x13, 2, 1),
+        "eq" => (0x14, 2, 1),
+        "iszero" => (0x15, 1, 1),
+        "and" => (0x16, 2, 1),
+        "or" => (0x17, 2, 1),
+        "xor" => (0x18, 2, 1),
+        "not" => (0x19, 1, 1),
+        "byte" => (0x1a, 2, 1),
+        "shl" => (0x1b, 2, 1),
+        "shr" => (0x1c, 2, 1),
+        "sar" => (0x1d, 2, 1),
+        "keccak256" => (0x20, 2, 1),
+        "address" => (0x30, 0, 1),
+        "balance" => (0x31, 1, 1),
+        "origin" => (0x32, 0, 1),
+        "caller" => (0x33, 0, 1),
+        "callvalue" => (0x34, 0, 1),
+        "calldataload" => (0x35, 1, 1),
+        "calldatasize" => (0x36, 0, 1),
+        "calldatacopy" => (0x37, 3, 0),
+        "codesize" => (0x38, 0, 1),
+        "codecopy" | "datacopy" => (0x39, 3, 0),
+        "gasprice" => (0x3a, 0, 1),
+        "extcodesize" => (0x3b, 1, 1),
+        "extcodecopy" => (0x3c, 4, 0),
+        "returndatasize" => (0x3d, 0, 1),
+        "returndatacopy" => (0x3e, 3, 0),
+        "extcodehash" => (0x3f, 1, 1),
+        "blockhash" => (0x40, 1, 1),
+        "coinbase" => (0x41, 0, 1),
+        "timestamp" => (0x42, 0, 1),
+        "number" => (0x43, 0, 1),
+        "difficulty" | "prevrandao" => (0x44, 0, 1),
+        "gaslimit" => (0x45, 0, 1),
+        "chainid" => (0x46, 0, 1),
+        "selfbalance" => (0x47, 0, 1),
+        "basefee" => (0x48, 0, 1),
+        "blobhash" => (0x49, 1, 1),
+        "blobbasefee" => (0x4a, 0, 1),
+        "pop" => (0x50, 1, 0),
+        "mload" => (0x51, 1, 1),
+        "mstore" => (0x52, 2, 0),
+        "mstore8" => (0x53, 2, 0),
+        "sload" => (0x54, 1, 1),
+        "sstore" => (0x55, 2, 0),
+        "msize" => (0x59, 0, 1),
+        "gas" => (0x5a, 0, 1),
+        "tload" => (0x5c, 1, 1),
+        "tstore" => (0x5d, 2, 0),
+        "mcopy" => (0x5e, 3, 0),
+        "log0" => (0xa0, 2, 0),
+        "log1" => (0xa1, 3, 0),
+        "log2" => (0xa2, 4, 0),
+        "log3" => (0xa3, 5, 0),
+        "log4" => (0xa4, 6, 0),
+        "create" => (0xf0, 3, 1),
+        "call" => (0xf1, 7, 1),
+        "callcode" => (0xf2, 7, 1),
+        "return" => (0xf3, 2, 0),
+        "delegatecall" => (0xf4, 6, 1),
+        "create2" => (0xf5, 4, 1),
+        "staticcall" => (0xfa, 6, 1),
+        "revert" => (0xfd, 2, 0),
+        "invalid" => (0xfe, 0, 0),
+        "selfdestruct" => (0xff, 1, 0),
+        _ => return None,
+    };
+    Some(Opcode { code, inputs, outputs })
+}
+
+/// A jump destination in an [`Assembly`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(super) struct Tag(usize);
+
+/// An item of an [`Assembly`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Item {
+    /// An opcode without immediate arguments.
+    Op(u8),
+    /// Pushes a constant.
+    Push(U256),
+    /// Pushes the code offset of a tag.
+    PushTag(Tag),
+    /// Marks the position of a tag with a `JUMPDEST`.
+    Tag(Tag),
+    /// Pushes the size of a sub-assembly.
+    PushSubSize(usize),
+    /// Pushes the code offset of a sub-assembly.
+    PushSubOffset(usize),
+    /// Pushes the address of a library, which is filled in when linking.
+    PushLibrary(Symbol),
+}
+
+/// The kind of a jump, as recorded in source maps.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum Jump {
+    /// Not a jump, or a jump within a function.
+    #[default]
+    Regular,
+    /// A jump into a function.
+    Into,
+    /// A jump out of a function.
+    Out,
+}
+
+impl Jump {
+    /// Returns the character representing the jump in source maps.
+    pub(crate) fn to_char(self) -> char {
+        match self {
+            Self::Regular => '-',
+            Self::Into => 'i',
+            Self::Out => 'o',
+        }
+    }
+}
+
+/// The source location of an instruction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Location {
+    /// The span of the code that the instruction was generated from.
+    pub(crate) span: Span,
+    /// The kind of jump, if the instruction is a jump.
+    pub(crate) jump: Jump,
+}
+
+/// A sequence of EVM instructions with symbolic jump destinations, followed by the bytecode of
+/// its sub-assemblies.
+#[derive(Debug, Default)]
+pub(super) struct Assembly {
+    items: Vec<(Item, Location)>,
+    tags: usize,
+    subs: Vec<Vec<u8>>,
+    /// The unresolved library references of each sub-assembly.
+    sub_links: Vec<Vec<LinkReference>>,
+    /// The span of the items that are appended.
+    span: Span,
+}
+
+impl Assembly {
+    /// Creates a new tag. Its position is set with [`tag`](Self::tag).
+    pub(super) fn new_tag(&mut self) -> Tag {
+        self.tags += 1;
+        Tag(self.tags - 1)
+    }
+
+    /// Appends a sub-assembly with the given unresolved library references, returning its index.
+    pub(super) fn add_sub(&mut self, code: Vec<u8>, links: Vec<LinkReference>) -> usize {
+        self.subs.push(code);
+        self.sub_links.push(links);
+        self.subs.len() - 1
+    }
+
+    /// Sets the span of the items that are appended next.
+    pub(super) fn set_span(&mut self, span: Span) {
+        self.span = span;
+    }
+
+    fn item(&mut self, item: Item) {
+        self.items.push((item, Location { span: self.span, jump: Jump::Regular }));
+    }
+
+    pub(super) fn op(&mut self, op: u8) {
+        self.item(Item::Op(op));
+    }
+
+    /// Appends a `JUMP` of the given kind.
+    pub(super) fn jump(&mut self, jump: Jump) {
+        self.items.push((Item::Op(JUMP), Location { span: self.span, jump }));
+    }
+
+    pub(super) fn push(&mut self, value: U256) {
+        self.item(Item::Push(value));
+    }
+
+    pub(super) fn push_tag(&mut self, tag: Tag) {
+        self.item(Item::PushTag(tag));
+    }
+
+    pub(super) fn tag(&mut self, tag: Tag) {
+        self.item(Item::Tag(tag));
+    }
+
+    pub(super) fn push_sub_size(&mut self, sub: usize) {
+        self.item(Item::PushSubSize(sub));
+    }
+
+    pub(super) fn push_sub_offset(&mut self, sub: usize) {
+        self.item(Item::PushSubOffset(sub));
+    }
+
+    /// Pushes the address of the library with the given fully qualified name.
+    pub(super) fn push_library(&mut self, name: Symbol) {
+        self.item(Item::PushLibrary(name));
+    }
+
+    /// Returns the source location of each instruction, in order.
+    pub(super) fn locations(&self) -> Vec<Location> {
+        self.items.iter().map(|&(_, location)| location).collect()
+    }
+
+    /// Applies peephole optimizations to the items. See [`peephole`] for details.
+    pub(super) fn optimize(&mut self, evm_version: EvmVersion) {
+        peephole::optimize(&mut self.items, evm_version.has_push0());
+    }
+
+    /// Returns the length of the assembled bytecode, including sub-assemblies.
+    pub(super) fn code_size(&self, evm_version: EvmVersion) -> usize {
+        self.assemble(evm_version).len()
+    }
+
+    /// Assembles the items into bytecode, resolving tags and sub-assembly references.
+    ///
+    /// Constants are pushed with the smallest possible `PUSH` instruction. Tags and sub-assembly
+    /// offsets all use the same width, which is the smallest one that can address the whole
+    /// bytecode. Library addresses are left zeroed; their positions are returned by
+    /// [`link_references`](Self::link_references).
+    pub(super) fn assemble(&self, evm_version: EvmVersion) -> Vec<u8> {
+        let push0 = evm_version.has_push0();
+        let layout = self.layout(push0);
+        let mut code = Vec::with_capacity(layout.code_len + layout.subs_len);
+        for &(item, _) in &self.items {
+            match item {
+                Item::Op(op) => code.push(op),
+                Item::Push(value) => push_value(&mut code, value, push0),
+                Item::PushTag(tag) => push_fixed(&mut code, layout.tags[tag.0], layout.width),
+                Item::Tag(_) => code.push(JUMPDEST),
+                Item::PushSubSize(sub) => {
+                    push_value(&mut code, U256::from(self.subs[sub].len()), push0)
+                }
+                Item::PushSubOffset(sub) => {
+                    push_fixed(&mut code, layout.sub_offsets[sub], layout.width)
+                }
+                Item::PushLibrary(_) => {
+                    code.push(PUSH0 + 20);
+                    code.extend_from_slice(&[0; 20]);
+                }
+            }
+        }
+        debug_assert_eq!(code.len(), layout.code_len);
+        for sub in &self.subs {
+            code.extend_from_slice(sub);
+        }
+        code
+    }
+
+    /// Returns the library references in the assembled bytecode, including the ones of the
+    /// sub-assemblies, sorted by offset.
+    pub(super) fn link_references(&self, evm_version: EvmVersion) -> Vec<LinkReference> {
+        let push0 = evm_version.has_push0();
+        let layout = self.layout(push0);
+        let mut links = Vec::new();
+        let mut offset = 0;
+        for (item, _) in &self.items {
+            if let Item::PushLibrary(name) = *item {
+                links.push(LinkReference { offset: offset + 1, name });
+            }
+            offset += self.item_len(item, layout.width, push0);
+        }
+        for (sub_links, &sub_offset) in self.sub_links.iter().zip(&layout.sub_offsets) {
+            links.extend(
+                sub_links.iter().map(|link| LinkReference {
+                    offset: sub_offset + link.offset,
+                    name: link.name,
+                }),
+            );
+        }
+        links
+    }
+
+    /// Computes the width of the references and the offsets of the tags and sub-assemblies.
+    fn layout(&self, push0: bool) -> Layout {
+        let subs_len = self.subs.iter().map(Vec::len).sum::<usize>();
+        let mut width = 1;
+        let code_len = loop {
+            let code_len = self.code_len(width, push0);
+            if byte_len(code_len + subs_len) <= width {
+                break code_len;
+            }
+            width += 1;
+        };
+
+        let mut tags = vec![0; self.tags];
+        let mut offset = 0;
+        for (item, _) in &self.items {
+            if let Item::Tag(tag) = *item {
+                tags[tag.0] = offset;
+            }
+            offset += self.item_len(item, width, push0);
+        }
+
+        let mut sub_offsets = Vec::with_capacity(self.subs.len());
+        let mut offset = code_len;
+        for sub in &self.subs {
+            sub_offsets.push(offset);
+            offset += sub.len();
+        }
+
+        Layout { width, code_len, subs_len, tags, sub_offsets }
+    }
+
+    /// Returns the length of the code, excluding sub-assemblies, when references are pushed with
+    /// `width` bytes.
+    fn code_len(&self, width: usize, push0: bool) -> usize {
+        self.items.iter().map(|(item, _)| self.item_len(item, width, push0)).sum()
+    }
+
+    fn item_len(&self, item: &Item, width: usize, push0: bool) -> usize {
+        match *item {
+            Item::Op(_) | Item::Tag(_) => 1,
+            Item::Push(value) => push_len(value, push0),
+            Item::PushTag(_) | Item::PushSubOffset(_) => 1 + width,
+            Item::PushSubSize(sub) => push_len(U256::from(self.subs[sub].len()), push0),
+            Item::PushLibrary(_) => 21,
+        }
+    }
+}
+
+/// The layout of an assembled [`Assembly`].
+struct Layout {
+    /// The width of the pushed tags and sub-assembly offsets.
+    width: usize,
+    /// The length of the code, excluding sub-assemblies.
+    code_len: usize,
+    /// The total length of the sub-assemblies.
+    subs_len: usize,
+    /// The offset of each tag.
+    tags: Vec<usize>,
+    /// The offset of each sub-assembly.
+    sub_offsets: Vec<usize>,
+}
+
+/// A reference to the address of a library in assembled bytecode, as `PUSH20 <address>`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct LinkReference {
+    /// The offset of the 20-byte address in the bytecode.
+    pub(crate) offset: usize,
+    /// The fully qualified name of the library, `path:Name`.
+    pub(crate) name: Symbol,
+}
+
+/// Returns the number of bytes needed to represent `value`, which is at least 1.
+fn byte_len(value: usize) -> usize {
+    (usize::BITS - value.leading_zeros()).div_ceil(8).max(1) as usize
+}
+
+fn push_len(value: U256, push0: bool) -> usize {
+    if value.is_zero() && push0 {
+        1
+    } else {
+        1 + value.bit_len().div_ceil(8).max(1)
+    }
+}
+
+fn push_value(code: &mut Vec<u8>, value: U256, push0: bool) {
+    if value.is_zero() && push0 {
+        code.push(PUSH0);
+        return;
+    }
+    let len = value.bit_len().div_ceil(8).max(1);
+    code.push(PUSH0 + len as u8);
+    code.extend_from_slice(&value.to_be_bytes::<32>()[32 - len..]);
+}
+
+fn push_fixed(code: &mut Vec<u8>, value: usize, width: usize) {
+    code.push(PUSH0 + width as u8);
+    code.extend_from_slice(&value.to_be_bytes()[size_of::<usize>() - width..]);
+}
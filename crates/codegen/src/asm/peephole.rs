@@ -0,0 +1,218 @@
+//! Peephole optimization of EVM assembly.
+//!
+//! Works on the assembly items after the Yul code transform, independently from the Yul
+//! optimizer, similarly to solc's `PeepholeOptimiser`. The rules are applied until the items do
+//! not change anymore:
+//! - a constant pushed twice in a row is duplicated with `DUP1` when that is smaller;
+//! - a value that is pushed or duplicated and then popped is removed;
+//! - a `SWAPn` that is repeated or that precedes a commutative opcode is removed;
+//! - `ISZERO ISZERO` before a conditional jump is removed;
+//! - a jump to the next item is removed;
+//! - the items that follow an unconditional jump or a halting opcode are removed up to the next
+//!   tag;
+//! - tags that are never jumped to are removed, along with their `JUMPDEST`.
+//!
+//! Rewritten items keep the source location of the item they replace.
+
+use super::{push_len, Item, Location, Tag, DUP1, EQ, ISZERO, JUMP, JUMPI, POP, STOP, SWAP1};
+use solar_data_structures::map::FxHashSet;
+
+const ADD: u8 = 0x01;
+const MUL: u8 = 0x02;
+const AND: u8 = 0x16;
+const OR: u8 = 0x17;
+const XOR: u8 = 0x18;
+const RETURN: u8 = 0xf3;
+const REVERT: u8 = 0xfd;
+const INVALID: u8 = 0xfe;
+const SELFDESTRUCT: u8 = 0xff;
+
+pub(super) fn optimize(items: &mut Vec<(Item, Location)>, push0: bool) {
+    loop {
+        let mut changed = simplify(items, push0);
+        changed |= remove_unreachable(items);
+        changed |= remove_unused_tags(items);
+        if !changed {
+            break;
+        }
+    }
+}
+
+/// Applies the rules that rewrite a few consecutive items.
+fn simplify(items: &mut Vec<(Item, Location)>, push0: bool) -> bool {
+    let mut changed = false;
+    let mut simplified = Vec::with_capacity(items.len());
+    for &item in items.iter() {
+        simplified.push(item);
+        while simplify_tail(&mut simplified, push0) {
+            changed = true;
+        }
+    }
+    *items = simplified;
+    changed
+}
+
+/// Rewrites the last items, returning `true` if a rule matched.
+fn simplify_tail(items: &mut Vec<(Item, Location)>, push0: bool) -> bool {
+    const MAX_LEN: usize = 4;
+    let n = items.len();
+    let k = n.min(MAX_LEN);
+    let mut tail = [Item::Op(STOP); MAX_LEN];
+    for (slot, &(item, _)) in tail[MAX_LEN - k..].iter_mut().zip(&items[n - k..]) {
+        *slot = item;
+    }
+    match tail[MAX_LEN - k..] {
+        [.., Item::Op(op), Item::Op(POP)] if is_dup(op) => items.truncate(n - 2),
+        [.., item, Item::Op(POP)] if is_push(item) => items.truncate(n - 2),
+        [.., Item::Op(a), Item::Op(b)] if is_swap(a) && a == b => items.truncate(n - 2),
+        [.., Item::Op(SWAP1), Item::Op(op)] if is_commutative(op) => {
+            items.remove(n - 2);
+        }
+        [.., Item::Push(a), Item::Push(b)] if a == b && push_len(a, push0) > 1 => {
+            items[n - 1].0 = Item::Op(DUP1);
+        }
+        [.., Item::PushTag(a), Item::PushTag(b)] if a == b => items[n - 1].0 = Item::Op(DUP1),
+        [.., Item::Op(ISZERO), Item::Op(ISZERO), Item::PushTag(_), Item::Op(JUMPI)] => {
+            items.drain(n - 4..n - 2);
+        }
+        [.., Item::PushTag(a), Item::Op(JUMP), Item::Tag(b)] if a == b => {
+            items.drain(n - 3..n - 1);
+        }
+        // The condition is still consumed.
+        [.., Item::PushTag(a), Item::Op(JUMPI), Item::Tag(b)] if a == b => {
+            let location = items[n - 2].1;
+            items.splice(n - 3..n - 1, [(Item::Op(POP), location)]);
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Removes the items that follow an unconditional jump or a halting opcode, up to the next tag.
+fn remove_unreachable(items: &mut Vec<(Item, Location)>) -> bool {
+    let len = items.len();
+    let mut reachable = true;
+    items.retain(|(item, _)| {
+        match *item {
+            Item::Tag(_) => reachable = true,
+            _ if !reachable => return false,
+            Item::Op(op) if is_terminating(op) => reachable = false,
+            _ => {}
+        }
+        true
+    });
+    items.len() != len
+}
+
+/// Removes the tags that are never pushed, and so never jumped to.
+fn remove_unused_tags(items: &mut Vec<(Item, Location)>) -> bool {
+    let used = items
+        .iter()
+        .filter_map(|(item, _)| match *item {
+            Item::PushTag(tag) => Some(tag),
+            _ => None,
+        })
+        .collect::<FxHashSet<Tag>>();
+    let len = items.len();
+    items.retain(|(item, _)| !matches!(item, Item::Tag(tag) if !used.contains(tag)));
+    items.len() != len
+}
+
+/// Returns `true` if the item only pushes a value, without side effects.
+fn is_push(item: Item) -> bool {
+    matches!(
+        item,
+        Item::Push(_)
+            | Item::PushTag(_)
+            | Item::PushSubSize(_)
+            | Item::PushSubOffset(_)
+            | Item::PushLibrary(_)
+    )
+}
+
+fn is_dup(op: u8) -> bool {
+    (DUP1..DUP1 + 16).contains(&op)
+}
+
+fn is_swap(op: u8) -> bool {
+    (SWAP1..SWAP1 + 16).contains(&op)
+}
+
+fn is_commutative(op: u8) -> bool {
+    matches!(op, ADD | MUL | EQ | AND | OR | XOR)
+}
+
+fn is_terminating(op: u8) -> bool {
+    matches!(op, JUMP | STOP | RETURN | REVERT | INVALID | SELFDESTRUCT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::U256;
+
+    #[track_caller]
+    fn check(items: Vec<Item>, push0: bool, expected: &[Item]) {
+        let mut items = items.into_iter().map(|item| (item, Location::default())).collect();
+        optimize(&mut items, push0);
+        assert_eq!(items.into_iter().map(|(item, _)| item).collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn stack_operations() {
+        let push = |n: u64| Item::Push(U256::from(n));
+        check(
+            vec![push(1), Item::Op(POP), Item::Op(DUP1 + 2), Item::Op(POP), Item::Op(STOP)],
+            true,
+            &[Item::Op(STOP)],
+        );
+        check(
+            vec![Item::Op(SWAP1 + 2), Item::Op(SWAP1 + 2), Item::Op(SWAP1), Item::Op(ADD)],
+            true,
+            &[Item::Op(ADD)],
+        );
+        check(
+            vec![Item::Op(SWAP1), Item::Op(SWAP1 + 1)],
+            true,
+            &[Item::Op(SWAP1), Item::Op(SWAP1 + 1)],
+        );
+        check(vec![push(0x1234), push(0x1234)], true, &[push(0x1234), Item::Op(DUP1)]);
+        check(vec![push(0), push(0)], true, &[push(0), push(0)]);
+        check(vec![push(0), push(0)], false, &[push(0), Item::Op(DUP1)]);
+    }
+
+    #[test]
+    fn control_flow() {
+        let (a, b) = (Tag(0), Tag(1));
+        check(
+            vec![
+                Item::PushTag(a),
+                Item::Op(JUMP),
+                Item::Op(ADD),
+                Item::Tag(b),
+                Item::Tag(a),
+                Item::Op(STOP),
+            ],
+            true,
+            &[Item::Op(STOP)],
+        );
+        check(
+            vec![
+                Item::Op(ISZERO),
+                Item::Op(ISZERO),
+                Item::PushTag(a),
+                Item::Op(JUMPI),
+                Item::Op(STOP),
+                Item::Tag(a),
+                Item::Op(STOP),
+            ],
+            true,
+            &[Item::PushTag(a), Item::Op(JUMPI), Item::Op(STOP), Item::Tag(a), Item::Op(STOP)],
+        );
+        check(
+            vec![Item::Push(U256::from(1)), Item::PushTag(a), Item::Op(JUMPI), Item::Tag(a)],
+            true,
+            &[],
+        );
+    }
+}
@@ -0,0 +1,589 @@
+//! Yul to EVM assembly code transform.
+//!
+//! Mirrors solc's legacy `CodeTransform`: every variable lives in a stack slot that is accessed
+//! with `DUP`/`SWAP`, and functions are called by pushing a return tag followed by the
+//! arguments, with the first argument on top of the stack.
+
+use super::{
+    asm::{self, Assembly, Jump, LinkReference, Location, Tag},
+    Result,
+};
+use alloy_primitives::U256;
+use solar_ast::{yul, LitKind};
+use solar_data_structures::map::FxHashMap;
+use solar_interface::{diagnostics::ErrorGuaranteed, Session, Span, Symbol};
+
+/// The bytecode of a Yul object.
+#[derive(Debug)]
+pub(crate) struct Bytecode {
+    /// The code of the object, followed by its sub-objects and data.
+    pub(crate) code: Vec<u8>,
+    /// The bytecode of each sub-object.
+    pub(crate) children: Vec<Bytecode>,
+    /// The source location of each instruction of the code, in order.
+    pub(crate) locations: Vec<Location>,
+    /// The library references in the code that were not linked, sorted by offset.
+    pub(crate) link_references: Vec<LinkReference>,
+}
+
+impl Bytecode {
+    /// Hex-encodes the code, replacing the addresses of the libraries that were not linked with
+    /// `__$<hash>$__` placeholders, where `<hash>` is the first 34 hex characters of the
+    /// Keccak-256 hash of the fully qualified library name.
+    pub(crate) fn to_hex(&self) -> String {
+        let mut hex = alloy_primitives::hex::encode(&self.code);
+        for link in &self.link_references {
+            let hash =
+                alloy_primitives::hex::encode(alloy_primitives::keccak256(link.name.as_str()));
+            let start = link.offset * 2;
+            hex.replace_range(start..start + 40, &format!("__${}$__", &hash[..34]));
+        }
+        hex
+    }
+}
+
+/// Compiles a Yul object and all of its sub-objects to bytecode.
+pub(super) fn object_bytecode(sess: &Session, object: &yul::Object<'_>) -> Result<Bytecode> {
+    let children = object
+        .children
+        .iter()
+        .map(|child| object_bytecode(sess, child))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut assembly = Assembly::default();
+    let mut subs = FxHashMap::default();
+    for (child, bytecode) in object.children.iter().zip(&children) {
+        let links = bytecode.link_references.clone();
+        subs.insert(child.name.value, assembly.add_sub(bytecode.code.clone(), links));
+    }
+    for data in object.data.iter() {
+        let LitKind::Str(_, bytes) = &data.data.kind else { unreachable!() };
+        subs.insert(data.name.value, assembly.add_sub(bytes.to_vec(), Vec::new()));
+    }
+
+    let mut transform = Transform::new(sess, &mut assembly, &subs);
+    transform.block(&object.code.code)?;
+    transform.asm.op(asm::STOP);
+    while let Some(queued) = transform.queue.pop() {
+        transform.scopes = queued.scopes;
+        transform.function(queued.function, queued.tag, queued.span)?;
+    }
+
+    if sess.optimize {
+        optimize(sess, &mut assembly);
+    }
+    let mut code = assembly.assemble(sess.evm_version);
+    let mut link_references = assembly.link_references(sess.evm_version);
+    link_references.retain(|link| match sess.libraries.get(link.name.as_str()) {
+        Some(address) => {
+            code[link.offset..link.offset + 20].copy_from_slice(address);
+            false
+        }
+        None => true,
+    });
+    Ok(Bytecode { code, children, locations: assembly.locations(), link_references })
+}
+
+/// Runs the peephole optimizer on the assembly, recording the code size before and after it with
+/// `-Ztime-passes`.
+fn optimize(sess: &Session, assembly: &mut Assembly) {
+    if !sess.time_passes {
+        assembly.optimize(sess.evm_version);
+        return;
+    }
+    let before = assembly.code_size(sess.evm_version);
+    sess.timings.accumulate("peephole", || assembly.optimize(sess.evm_version));
+    let after = assembly.code_size(sess.evm_version);
+    sess.timings.update("peephole", |pass| {
+        let (total_before, total_after) = pass.code_size.unwrap_or_default();
+        pass.code_size = Some((total_before + before, total_after + after));
+    });
+}
+
+/// A Yul function definition.
+#[derive(Clone, Copy)]
+struct FunctionInfo {
+    /// The entry point.
+    tag: Tag,
+    /// The number of return values.
+    returns: usize,
+}
+
+/// The functions defined in a block.
+type Scope = FxHashMap<Symbol, FunctionInfo>;
+
+/// A function whose code has not been generated yet.
+struct QueuedFunction<'a, 'ast> {
+    function: &'a yul::Function<'ast>,
+    tag: Tag,
+    /// The span of the function definition.
+    span: Span,
+    /// The scopes visible from the function definition.
+    scopes: Vec<Scope>,
+}
+
+/// An enclosing `for` loop.
+struct Loop {
+    /// The stack height at the start of the loop body.
+    height: usize,
+    /// The tag that `continue` jumps to.
+    continue_tag: Tag,
+    /// The tag that `break` jumps to.
+    break_tag: Tag,
+}
+
+struct Transform<'a, 'ast> {
+    sess: &'a Session,
+    asm: &'a mut Assembly,
+    /// The sub-objects and data segments of the object, by name.
+    subs: &'a FxHashMap<Symbol, usize>,
+    /// The stack slots of the current function, from the bottom. Variables are named slots.
+    stack: Vec<Option<Symbol>>,
+    /// The functions visible in the current block.
+    scopes: Vec<Scope>,
+    /// The enclosing loops of the current function.
+    loops: Vec<Loop>,
+    /// The stack height below the local variables of the current function and its exit tag.
+    function: Option<(usize, Tag)>,
+    /// Function definitions whose code has not been generated yet.
+    queue: Vec<QueuedFunction<'a, 'ast>>,
+}
+
+impl<'a, 'ast> Transform<'a, 'ast> {
+    fn new(sess: &'a Session, asm: &'a mut Assembly, subs: &'a FxHashMap<Symbol, usize>) -> Self {
+        Self {
+            sess,
+            asm,
+            subs,
+            stack: Vec::new(),
+            scopes: Vec::new(),
+            loops: Vec::new(),
+            function: None,
+            queue: Vec::new(),
+        }
+    }
+
+    fn err(&self, span: Span, msg: impl Into<String>) -> ErrorGuaranteed {
+        self.sess.dcx.err(msg.into()).span(span).emit()
+    }
+
+    fn stack_too_deep(&self, span: Span) -> ErrorGuaranteed {
+        self.err(span, "stack too deep; variable cannot be reached with `DUP16` or `SWAP16`")
+    }
+
+    /// Generates the code of a function definition.
+    fn function(&mut self, function: &'a yul::Function<'ast>, tag: Tag, span: Span) -> Result {
+        // The caller pushes the return tag and then the arguments, the first one on top.
+        self.asm.set_span(span);
+        self.stack.clear();
+        self.stack.push(None);
+        self.stack.extend(function.parameters.iter().rev().map(|param| Some(param.name)));
+        self.asm.tag(tag);
+        for ret in function.returns.iter() {
+            self.asm.push(U256::ZERO);
+            self.stack.push(Some(ret.name));
+        }
+        let exit = self.asm.new_tag();
+        self.function = Some((self.stack.len(), exit));
+        self.block(&function.body)?;
+        self.function = None;
+        self.asm.set_span(span);
+        self.asm.tag(exit);
+
+        // Move the return values below the return tag, and drop everything else.
+        let params = function.parameters.len();
+        let mut current = (0..self.stack.len()).collect::<Vec<_>>();
+        let target = (params + 1..self.stack.len()).chain([0]).collect::<Vec<_>>();
+        for (i, &slot) in target.iter().enumerate() {
+            if current[i] == slot {
+                continue;
+            }
+            let top = current.len() - 1;
+            let j = current.iter().position(|&s| s == slot).unwrap();
+            for depth in [top - j, top - i] {
+                if depth != 0 {
+                    self.swap(depth, function.name.span)?;
+                    current.swap(top, top - depth);
+                }
+            }
+        }
+        for _ in target.len()..current.len() {
+            self.asm.op(asm::POP);
+        }
+        self.asm.jump(Jump::Out);
+        self.stack.clear();
+        Ok(())
+    }
+
+    /// Generates the code of a block, and pops its variables.
+    fn block(&mut self, block: &'a [yul::Stmt<'ast>]) -> Result {
+        let height = self.stack.len();
+        self.enter_scope(block);
+        for stmt in block {
+            self.stmt(stmt)?;
+        }
+        self.scopes.pop();
+        self.pop_to(height);
+        Ok(())
+    }
+
+    /// Declares the functions of a block, which are visible in the whole block.
+    fn enter_scope(&mut self, block: &[yul::Stmt<'_>]) {
+        let mut scope = Scope::default();
+        for stmt in block {
+            if let yul::StmtKind::FunctionDef(f) = &stmt.kind {
+                let info = FunctionInfo { tag: self.asm.new_tag(), returns: f.returns.len() };
+                scope.insert(f.name.name, info);
+            }
+        }
+        self.scopes.push(scope);
+    }
+
+    fn stmt(&mut self, stmt: &'a yul::Stmt<'ast>) -> Result {
+        let span = stmt.span;
+        self.asm.set_span(span);
+        match &stmt.kind {
+            yul::StmtKind::Block(block) => self.block(block)?,
+            yul::StmtKind::AssignSingle(path, expr) => {
+                self.single_value(expr)?;
+                self.assign(path)?;
+            }
+            yul::StmtKind::AssignMulti(paths, call) => {
+                self.values(call, span, paths.len())?;
+                for path in paths.iter().rev() {
+                    self.assign(path)?;
+                }
+            }
+            yul::StmtKind::Expr(call) => self.values(call, span, 0)?,
+            yul::StmtKind::If(cond, body) => {
+                let end = self.asm.new_tag();
+                self.single_value(cond)?;
+                self.asm.set_span(span);
+                self.op(asm::ISZERO, 1, 1);
+                self.jump_if(end);
+                self.block(body)?;
+                self.asm.tag(end);
+            }
+            yul::StmtKind::For { init, cond, step, body } => {
+                // The scope of the init block extends to the rest of the loop.
+                let height = self.stack.len();
+                self.enter_scope(init);
+                for stmt in init.iter() {
+                    self.stmt(stmt)?;
+                }
+
+                let start = self.asm.new_tag();
+                let continue_tag = self.asm.new_tag();
+                let break_tag = self.asm.new_tag();
+                self.asm.tag(start);
+                self.single_value(cond)?;
+                self.asm.set_span(span);
+                self.op(asm::ISZERO, 1, 1);
+                self.jump_if(break_tag);
+
+                self.loops.push(Loop { height: self.stack.len(), continue_tag, break_tag });
+                self.block(body)?;
+                self.loops.pop();
+
+                self.asm.tag(continue_tag);
+                self.block(step)?;
+                self.asm.set_span(span);
+                self.jump(start);
+                self.asm.tag(break_tag);
+
+                self.scopes.pop();
+                self.pop_to(height);
+            }
+            yul::StmtKind::Switch(switch) => self.switch(switch)?,
+            yul::StmtKind::Leave => {
+                let Some((height, exit)) = self.function else {
+                    return Err(self.err(span, "`leave` outside of a function"));
+                };
+                self.jump_out(height, exit);
+            }
+            yul::StmtKind::Break | yul::StmtKind::Continue => {
+                let Some(l) = self.loops.last() else {
+                    return Err(self.err(span, "`break` or `continue` outside of a loop"));
+                };
+                let is_break = matches!(stmt.kind, yul::StmtKind::Break);
+                let tag = if is_break { l.break_tag } else { l.continue_tag };
+                self.jump_out(l.height, tag);
+            }
+            yul::StmtKind::FunctionDef(function) => {
+                let tag = self.scopes.last().unwrap()[&function.name.name].tag;
+                let scopes = self.scopes.clone();
+                self.queue.push(QueuedFunction { function, tag, span, scopes });
+            }
+            yul::StmtKind::VarDecl(names, value) => {
+                match value {
+                    Some(yul::Expr { kind: yul::ExprKind::Call(call), .. }) => {
+                        self.values(call, span, names.len())?
+                    }
+                    Some(expr) => self.single_value(expr)?,
+                    None => {
+                        for _ in names.iter() {
+                            self.asm.push(U256::ZERO);
+                            self.stack.push(None);
+                        }
+                    }
+                }
+                let first = self.stack.len() - names.len();
+                for (slot, name) in self.stack[first..].iter_mut().zip(names.iter()) {
+                    *slot = Some(name.name);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn switch(&mut self, switch: &'a yul::StmtSwitch<'ast>) -> Result {
+        self.single_value(&switch.selector)?;
+        let height = self.stack.len();
+        let end = self.asm.new_tag();
+
+        let mut tags = Vec::with_capacity(switch.branches.len());
+        for case in switch.branches.iter() {
+            let tag = self.asm.new_tag();
+            self.dup(0, switch.selector.span)?;
+            let value = self.lit(&case.constant)?;
+            self.asm.push(value);
+            self.stack.push(None);
+            self.op(asm::EQ, 2, 1);
+            self.jump_if(tag);
+            tags.push(tag);
+        }
+
+        self.pop();
+        if let Some(default) = &switch.default_case {
+            self.block(default)?;
+        }
+        for (i, (case, tag)) in switch.branches.iter().zip(tags).enumerate() {
+            self.jump(end);
+            self.stack.truncate(height - 1);
+            self.stack.push(None);
+            self.asm.tag(tag);
+            self.pop();
+            self.block(&case.body)?;
+            if i == switch.branches.len() - 1 {
+                self.asm.tag(end);
+            }
+        }
+        Ok(())
+    }
+
+    /// Evaluates a function call that must return exactly `n` values.
+    fn values(&mut self, call: &yul::ExprCall<'_>, span: Span, n: usize) -> Result {
+        let values = self.call(call, span)?;
+        if values != n {
+            let msg = format!("expected {n} values, but `{}` returns {values}", call.name);
+            return Err(self.err(span, msg));
+        }
+        Ok(())
+    }
+
+    /// Evaluates an expression that must evaluate to a single value.
+    fn single_value(&mut self, expr: &yul::Expr<'_>) -> Result {
+        self.asm.set_span(expr.span);
+        match &expr.kind {
+            yul::ExprKind::Path(path) => {
+                let depth = self.var_depth(path)?;
+                self.dup(depth, path.span())
+            }
+            yul::ExprKind::Lit(lit) => {
+                let value = self.lit(lit)?;
+                self.asm.push(value);
+                self.stack.push(None);
+                Ok(())
+            }
+            yul::ExprKind::Call(call) => self.values(call, expr.span, 1),
+        }
+    }
+
+    /// Evaluates a function call, returning the number of values that it pushes.
+    fn call(&mut self, call: &yul::ExprCall<'_>, span: Span) -> Result<usize> {
+        self.asm.set_span(span);
+        let name = call.name.as_str();
+        match name {
+            "datasize" | "dataoffset" => {
+                let sub = self.data_name(call, span)?;
+                if name == "datasize" {
+                    self.asm.push_sub_size(sub);
+                } else {
+                    self.asm.push_sub_offset(sub);
+                }
+                self.stack.push(None);
+                return Ok(1);
+            }
+            "linkersymbol" => {
+                let name = match &call.arguments[..] {
+                    [yul::Expr { kind: yul::ExprKind::Lit(lit), .. }] => match &lit.kind {
+                        LitKind::Str(_, bytes) => std::str::from_utf8(bytes).ok(),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                let Some(name) = name else {
+                    let msg = "`linkersymbol` expects a single string literal argument";
+                    return Err(self.err(span, msg));
+                };
+                self.asm.push_library(Symbol::intern(name));
+                self.stack.push(None);
+                return Ok(1);
+            }
+            "memoryguard" => {
+                let [yul::Expr { kind: yul::ExprKind::Lit(lit), .. }] = &call.arguments[..] else {
+                    return Err(self.err(span, "`memoryguard` expects a single literal argument"));
+                };
+                let value = self.lit(lit)?;
+                self.asm.push(value);
+                self.stack.push(None);
+                return Ok(1);
+            }
+            _ => {}
+        }
+
+        if let Some(op) = asm::opcode(name) {
+            if call.arguments.len() != op.inputs {
+                let msg = format!("`{name}` expects {} arguments", op.inputs);
+                return Err(self.err(span, msg));
+            }
+            for arg in call.arguments.iter().rev() {
+                self.single_value(arg)?;
+            }
+            self.asm.set_span(span);
+            self.op(op.code, op.inputs, op.outputs);
+            return Ok(op.outputs);
+        }
+
+        let Some(function) = self.scopes.iter().rev().find_map(|s| s.get(&call.name.name)).copied()
+        else {
+            return Err(self.err(call.name.span, format!("unsupported function `{name}`")));
+        };
+        let ret = self.asm.new_tag();
+        self.asm.push_tag(ret);
+        self.stack.push(None);
+        let height = self.stack.len();
+        for arg in call.arguments.iter().rev() {
+            self.single_value(arg)?;
+        }
+        self.asm.set_span(span);
+        self.asm.push_tag(function.tag);
+        self.asm.jump(Jump::Into);
+        self.asm.tag(ret);
+        self.stack.truncate(height - 1);
+        self.stack.extend(std::iter::repeat_n(None, function.returns));
+        Ok(function.returns)
+    }
+
+    /// Returns the index of the sub-object or data segment named by the argument of a `datasize`
+    /// or `dataoffset` call.
+    fn data_name(&self, call: &yul::ExprCall<'_>, span: Span) -> Result<usize> {
+        if let [yul::Expr { kind: yul::ExprKind::Lit(lit), .. }] = &call.arguments[..] {
+            if let LitKind::Str(_, bytes) = &lit.kind {
+                let name = std::str::from_utf8(bytes).ok().map(Symbol::intern);
+                if let Some(&sub) = name.and_then(|name| self.subs.get(&name)) {
+                    return Ok(sub);
+                }
+            }
+        }
+        Err(self.err(span, "unsupported argument: expected the name of a direct sub-object"))
+    }
+
+    /// Returns the value of a literal.
+    fn lit(&self, lit: &solar_ast::Lit) -> Result<U256> {
+        match &lit.kind {
+            LitKind::Number(n) => {
+                let (_, bytes) = n.to_bytes_be();
+                if bytes.len() > 32 {
+                    return Err(self.err(lit.span, "number literal is too large"));
+                }
+                Ok(U256::from_be_slice(&bytes))
+            }
+            LitKind::Bool(b) => Ok(U256::from(*b as u8)),
+            LitKind::Str(_, bytes) => {
+                if bytes.len() > 32 {
+                    return Err(self.err(lit.span, "string literal is too long"));
+                }
+                let mut word = [0; 32];
+                word[..bytes.len()].copy_from_slice(bytes);
+                Ok(U256::from_be_bytes(word))
+            }
+            LitKind::Err(guar) => Err(*guar),
+            kind => Err(self.err(lit.span, format!("unsupported {} literal", kind.description()))),
+        }
+    }
+
+    /// Returns the depth of a variable from the top of the stack.
+    fn var_depth(&self, path: &solar_ast::PathSlice) -> Result<usize> {
+        let name = path.get_ident().map(|ident| ident.name);
+        match self.stack.iter().rposition(|&slot| slot.is_some() && slot == name) {
+            Some(i) => Ok(self.stack.len() - 1 - i),
+            None => Err(self.err(path.span(), format!("unresolved variable `{path}`"))),
+        }
+    }
+
+    /// Assigns the value on top of the stack to a variable.
+    fn assign(&mut self, path: &solar_ast::PathSlice) -> Result {
+        let depth = self.var_depth(path)?;
+        self.swap(depth, path.span())?;
+        self.pop();
+        Ok(())
+    }
+
+    fn op(&mut self, op: u8, inputs: usize, outputs: usize) {
+        self.asm.op(op);
+        self.stack.truncate(self.stack.len() - inputs);
+        self.stack.extend(std::iter::repeat_n(None, outputs));
+    }
+
+    /// Duplicates the slot at the given depth from the top of the stack.
+    fn dup(&mut self, depth: usize, span: Span) -> Result {
+        if depth >= 16 {
+            return Err(self.stack_too_deep(span));
+        }
+        self.op(asm::DUP1 + depth as u8, 0, 1);
+        Ok(())
+    }
+
+    /// Swaps the top of the stack with the slot at the given depth.
+    fn swap(&mut self, depth: usize, span: Span) -> Result {
+        if !(1..=16).contains(&depth) {
+            return Err(self.stack_too_deep(span));
+        }
+        self.asm.op(asm::SWAP1 + depth as u8 - 1);
+        let top = self.stack.len() - 1;
+        self.stack.swap(top, top - depth);
+        Ok(())
+    }
+
+    fn pop(&mut self) {
+        self.op(asm::POP, 1, 0);
+    }
+
+    /// Pops slots until the stack has the given height.
+    fn pop_to(&mut self, height: usize) {
+        while self.stack.len() > height {
+            self.pop();
+        }
+    }
+
+    fn jump(&mut self, tag: Tag) {
+        self.asm.push_tag(tag);
+        self.asm.op(asm::JUMP);
+    }
+
+    /// Jumps to `tag` if the value on top of the stack is non-zero, consuming it.
+    fn jump_if(&mut self, tag: Tag) {
+        self.asm.push_tag(tag);
+        self.op(asm::JUMPI, 1, 0);
+    }
+
+    /// Pops the slots above the given height and jumps to `tag`, leaving the stack model intact
+    /// for the rest of the enclosing block.
+    fn jump_out(&mut self, height: usize, tag: Tag) {
+        for _ in height..self.stack.len() {
+            self.asm.op(asm::POP);
+        }
+        self.jump(tag);
+    }
+}
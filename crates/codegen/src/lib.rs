@@ -0,0 +1,525 @@
+#![doc = include_str!("../README.md")]
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/solar/main/assets/logo.png",
+    html_favicon_url = "https://raw.githubusercontent.com/paradigmxyz/solar/main/assets/favicon.ico"
+)]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+use alloy_primitives::U256;
+use solar_ast::{ContractKind, StateMutability};
+use solar_data_structures::map::FxHashMap;
+use solar_interface::{
+    config::EvmVersion,
+    diagnostics::ErrorGuaranteed,
+    error_code,
+    source_map::{FileName, SpanOrigin},
+    BytePos, RelativeBytePos, Span,
+};
+use solar_optimizer::{OptimizerSettings, DEFAULT_SEQUENCE};
+use solar_parse::Parser;
+use solar_sema::{
+    codegen::{CodegenBackend, ContractBytecode},
+    hir,
+    ty::{Gcx, TyKind},
+};
+use std::{fmt::Write, ops::Range};
+use utils::{function, Functions, ValTy};
+
+mod asm;
+
+mod evm;
+use evm::Bytecode;
+
+mod lower;
+use lower::{FunctionLowerer, Place};
+
+mod srcmap;
+use srcmap::source_map;
+
+mod utils;
+
+type Result<T = (), E = ErrorGuaranteed> = std::result::Result<T, E>;
+
+/// The EVM code generation backend.
+///
+/// Generates the Yul IR of the contracts, and assembles it into EVM bytecode.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EvmBackend;
+
+impl CodegenBackend for EvmBackend {
+    fn contract_ir(&self, gcx: Gcx<'_>, id: hir::ContractId) -> Result<String> {
+        contract_ir(gcx, id)
+    }
+
+    fn contract_bytecode(
+        &self,
+        gcx: Gcx<'_>,
+        id: hir::ContractId,
+        source_ids: &FxHashMap<BytePos, usize>,
+    ) -> Result<ContractBytecode> {
+        let bytecode = contract_bytecode(gcx, id)?;
+        let runtime = &bytecode.children[0];
+        Ok(ContractBytecode {
+            bin: bytecode.to_hex(),
+            bin_runtime: runtime.to_hex(),
+            srcmap: source_map(gcx.sess, &bytecode.locations, source_ids),
+            srcmap_runtime: source_map(gcx.sess, &runtime.locations, source_ids),
+        })
+    }
+}
+
+/// Generates the Yul IR of the given contract.
+fn contract_ir(gcx: Gcx<'_>, id: hir::ContractId) -> Result<String> {
+    contract_ir_with_origins(gcx, id).map(|(ir, _)| ir)
+}
+
+/// Generates the Yul IR of the given contract, along with the Solidity spans that the contract
+/// and each function of the IR were generated from.
+fn contract_ir_with_origins(
+    gcx: Gcx<'_>,
+    id: hir::ContractId,
+) -> Result<(String, Vec<SpanOrigin>)> {
+    let c = gcx.hir.contract(id);
+    if c.kind != ContractKind::Contract {
+        return Err(unimplemented(gcx, c.name.span, "code generation for libraries"));
+    }
+    for &base in c.linearized_bases {
+        let b = gcx.hir.contract(base);
+        if let Some(f) = b.fallback.or(b.receive) {
+            let span = gcx.hir.function(f).span;
+            return Err(unimplemented(gcx, span, "`fallback` and `receive` functions"));
+        }
+        if let Some(ctor) = b.ctor {
+            let ctor = gcx.hir.function(ctor);
+            if !ctor.parameters.is_empty() {
+                return Err(unimplemented(gcx, ctor.span, "constructor parameters"));
+            }
+        }
+    }
+
+    let name = format!("{}_{}", c.name, id.get());
+    let deployed_name = format!("{name}_deployed");
+    let (creation, creation_spans) = ContractCodegen::new(gcx, id).creation_code(&deployed_name)?;
+    let (runtime, runtime_spans) = ContractCodegen::new(gcx, id).runtime_code()?;
+
+    let mut s = String::new();
+    writeln!(s, "object \"{name}\" {{").unwrap();
+    writeln!(s, "    code {{").unwrap();
+    let creation_start = s.len();
+    push_indented(&mut s, &creation, 2);
+    let creation_range = creation_start..s.len();
+    writeln!(s, "    }}").unwrap();
+    writeln!(s, "    object \"{deployed_name}\" {{").unwrap();
+    writeln!(s, "        code {{").unwrap();
+    let runtime_start = s.len();
+    push_indented(&mut s, &runtime, 3);
+    let runtime_range = runtime_start..s.len();
+    writeln!(s, "        }}").unwrap();
+    if gcx.sess.cbor_metadata {
+        let cbor = gcx.metadata_cbor(&gcx.contract_metadata(id));
+        writeln!(s, "        data \".metadata\" hex\"{}\"", alloy_primitives::hex::encode(cbor))
+            .unwrap();
+    }
+    writeln!(s, "    }}").unwrap();
+    s.push_str("}\n");
+
+    let mut origins = vec![origin(0..s.len(), c.span)];
+    for (range, spans) in [(creation_range, creation_spans), (runtime_range, runtime_spans)] {
+        for (name, span) in spans {
+            if let Some(range) = function_range(&s, range.clone(), &name) {
+                origins.push(origin(range, span));
+            }
+        }
+    }
+    Ok((s, origins))
+}
+
+fn origin(range: Range<usize>, span: Span) -> SpanOrigin {
+    let lo = RelativeBytePos(range.start as u32);
+    let hi = RelativeBytePos(range.end as u32);
+    SpanOrigin { lo, hi, span }
+}
+
+/// Returns the range of the definition of the function with the given name in `code[range]`.
+fn function_range(code: &str, range: Range<usize>, name: &str) -> Option<Range<usize>> {
+    let start = range.start + code[range.clone()].find(&format!("function {name}("))?;
+    let line_start = code[..start].rfind('\n').map_or(0, |i| i + 1);
+    let end = format!("\n{}}}", &code[line_start..start]);
+    let end = start + code[start..range.end].find(&end)? + end.len();
+    Some(start..end)
+}
+
+/// Compiles the given contract to EVM bytecode, by assembling its Yul IR.
+///
+/// The first child of the returned bytecode is the runtime code.
+fn contract_bytecode(gcx: Gcx<'_>, id: hir::ContractId) -> Result<Bytecode> {
+    let (ir, origins) = contract_ir_with_origins(gcx, id)?;
+    let fqn = gcx.contract_fully_qualified_name(id);
+    let arena = solar_ast::Arena::new();
+    let name = FileName::Custom(format!("{fqn}.yul"));
+    // Map the IR back to the contract, so that the source maps point to the Solidity code.
+    let file = gcx
+        .sess
+        .source_map()
+        .new_generated_file(name, ir, origins)
+        .map_err(|e| gcx.dcx().err(e.to_string()).emit())?;
+    let mut parser = Parser::from_source_file(gcx.sess, &arena, &file);
+    let mut object = parser.parse_yul_file_object().map_err(|e| e.emit())?;
+    if gcx.sess.optimize {
+        if let Some(ir) = optimize(gcx, &object)? {
+            let name = FileName::Custom(format!("{fqn}.optimized.yul"));
+            let mut parser = Parser::from_source_code(gcx.sess, &arena, name, ir)?;
+            object = parser.parse_yul_file_object().map_err(|e| e.emit())?;
+        }
+    }
+    let bytecode = evm::object_bytecode(gcx.sess, &object)?;
+    check_code_size(gcx, id, &bytecode);
+    Ok(bytecode)
+}
+
+/// Runs the Yul optimizer on the given object, returning the optimized Yul code.
+///
+/// Returns `None` if the object can't be optimized; its errors are then reported by the assembler.
+fn optimize(gcx: Gcx<'_>, object: &solar_ast::yul::Object<'_>) -> Result<Option<String>> {
+    let sequence = match &gcx.sess.yul_optimizations {
+        Some(s) => s.parse().map_err(|e| {
+            gcx.dcx().err(format!("invalid Yul optimizer sequence {s:?}: {e}")).emit()
+        })?,
+        None => DEFAULT_SEQUENCE.parse().unwrap(),
+    };
+    let settings = OptimizerSettings { runs: gcx.sess.optimizer_runs, sequence };
+    let Some(mut ir) = solar_optimizer::Object::from_ast(object) else { return Ok(None) };
+    solar_optimizer::optimize(&mut ir, &settings);
+    Ok(Some(ir.to_string()))
+}
+
+/// The maximum size of deployed code, introduced in Spurious Dragon by EIP-170.
+const MAX_CODE_SIZE: usize = 0x6000;
+
+/// The maximum size of initcode, introduced in Shanghai by EIP-3860.
+const MAX_INITCODE_SIZE: usize = 2 * MAX_CODE_SIZE;
+
+/// Warns if the code of the given contract exceeds the size limits of the target EVM version.
+fn check_code_size(gcx: Gcx<'_>, id: hir::ContractId, bytecode: &Bytecode) {
+    let evm_version = gcx.sess.evm_version;
+    let span = gcx.hir.contract(id).name.span;
+    let help = "consider enabling the optimizer with a low `runs` value, turning off revert \
+                strings, or using libraries";
+    let runtime_size = bytecode.children[0].code.len();
+    if evm_version >= EvmVersion::SpuriousDragon && runtime_size > MAX_CODE_SIZE {
+        let msg = format!(
+            "contract code size is {runtime_size} bytes and exceeds {MAX_CODE_SIZE} bytes \
+             (a limit introduced in Spurious Dragon)"
+        );
+        gcx.dcx()
+            .warn(msg)
+            .code(error_code!(5574))
+            .span(span)
+            .note("this contract may not be deployable on Mainnet")
+            .help(help)
+            .emit();
+    }
+    let initcode_size = bytecode.code.len();
+    if evm_version >= EvmVersion::Shanghai && initcode_size > MAX_INITCODE_SIZE {
+        let msg = format!(
+            "contract initcode size is {initcode_size} bytes and exceeds {MAX_INITCODE_SIZE} \
+             bytes (a limit introduced in Shanghai)"
+        );
+        gcx.dcx()
+            .warn(msg)
+            .code(error_code!(3860))
+            .span(span)
+            .note("this contract may not be deployable on Mainnet")
+            .help(help)
+            .emit();
+    }
+}
+
+fn push_indented(s: &mut String, code: &str, indent: usize) {
+    for line in code.lines() {
+        if !line.is_empty() {
+            for _ in 0..indent {
+                s.push_str("    ");
+            }
+            s.push_str(line);
+        }
+        s.push('\n');
+    }
+}
+
+fn unimplemented(gcx: Gcx<'_>, span: Span, what: &str) -> ErrorGuaranteed {
+    gcx.dcx().err(format!("unimplemented feature: {what}")).span(span).emit()
+}
+
+/// The code generator of a single Yul object.
+struct ContractCodegen<'gcx> {
+    gcx: Gcx<'gcx>,
+    /// The contract being compiled.
+    contract: hir::ContractId,
+    /// The storage slot and byte offset of each state variable.
+    slots: FxHashMap<hir::VariableId, (U256, u8)>,
+    /// The transient storage slot and byte offset of each transient state variable.
+    transient_slots: FxHashMap<hir::VariableId, (U256, u8)>,
+    /// The functions defined in the object.
+    functions: Functions,
+    /// Internal functions that are referenced but not generated yet.
+    queue: Vec<hir::FunctionId>,
+    /// The names of the functions that were generated from Solidity code, and their spans.
+    spans: Vec<(String, Span)>,
+}
+
+impl<'gcx> ContractCodegen<'gcx> {
+    fn new(gcx: Gcx<'gcx>, contract: hir::ContractId) -> Self {
+        let slots =
+            gcx.storage_slots(contract).map(|(var, slot, offset)| (var, (slot, offset))).collect();
+        let transient_slots = gcx
+            .transient_storage_slots(contract)
+            .map(|(var, slot, offset)| (var, (slot, offset)))
+            .collect();
+        Self {
+            gcx,
+            contract,
+            slots,
+            transient_slots,
+            functions: Functions::default(),
+            queue: Vec::new(),
+            spans: Vec::new(),
+        }
+    }
+
+    fn unimplemented(&self, span: Span, what: &str) -> ErrorGuaranteed {
+        unimplemented(self.gcx, span, what)
+    }
+
+    /// Returns the value type of the given variable.
+    fn var_ty(&self, id: hir::VariableId) -> Result<ValTy> {
+        let ty = self.gcx.type_of_item(id.into());
+        ValTy::from_ty(ty).ok_or_else(|| {
+            let what = format!("variables of type `{}`", ty.display(self.gcx));
+            self.unimplemented(self.gcx.hir.variable(id).span, &what)
+        })
+    }
+
+    /// Returns the Yul name of the given variable.
+    fn var_name(&self, id: hir::VariableId) -> String {
+        let name = self.gcx.hir.variable(id).name.map(|name| name.to_string()).unwrap_or_default();
+        format!("var_{name}_{}", id.get())
+    }
+
+    /// Returns the storage location of the given state variable.
+    fn storage_place(&self, id: hir::VariableId, span: Span) -> Result<Place> {
+        let var = self.gcx.hir.variable(id);
+        if var.is_constant() || var.is_immutable() {
+            return Err(self.unimplemented(span, "constant and immutable state variables"));
+        }
+        let (&(slot, offset), transient) = match self.slots.get(&id) {
+            Some(slot) => (slot, false),
+            None => (&self.transient_slots[&id], true),
+        };
+        Ok(Place::Storage { slot, offset, ty: self.var_ty(id)?, transient })
+    }
+
+    /// Returns the Yul name of the given internal function.
+    fn function_name(&self, id: hir::FunctionId) -> String {
+        format!("fun_{}_{}", self.gcx.item_name(id), id.get())
+    }
+
+    /// Queues the given internal function for generation, returning its name.
+    fn internal_function(&mut self, id: hir::FunctionId) -> String {
+        let name = self.function_name(id);
+        if !self.functions.contains(&name) && !self.queue.contains(&id) {
+            self.queue.push(id);
+        }
+        name
+    }
+
+    /// Generates all the queued internal functions.
+    fn generate_queued(&mut self) -> Result {
+        while let Some(id) = self.queue.pop() {
+            let name = self.function_name(id);
+            if self.functions.contains(&name) {
+                continue;
+            }
+            let func = self.gcx.hir.function(id);
+            let Some(body) = func.body else {
+                return Err(self.unimplemented(func.span, "calls to unimplemented functions"));
+            };
+            if !func.modifiers.is_empty() {
+                return Err(self.unimplemented(func.span, "modifiers"));
+            }
+            let params = func.parameters.iter().map(|&p| self.var_name(p)).collect::<Vec<_>>();
+            let returns = func.returns.iter().map(|&r| self.var_name(r)).collect::<Vec<_>>();
+            for var in func.variables() {
+                self.var_ty(var)?;
+            }
+
+            let mut header = format!("{name}({})", params.join(", "));
+            if !returns.is_empty() {
+                write!(header, " -> {}", returns.join(", ")).unwrap();
+            }
+            let mut lowerer = FunctionLowerer::new(self, func.returns);
+            lowerer.block(body)?;
+            let body = lowerer.finish();
+            self.spans.push((name.clone(), func.span));
+            self.functions.insert(name, function(header, body));
+        }
+        Ok(())
+    }
+
+    /// Generates the creation code, which runs the constructors and returns the runtime code.
+    fn creation_code(mut self, deployed_name: &str) -> Result<(String, Vec<(String, Span)>)> {
+        let gcx = self.gcx;
+        let c = gcx.hir.contract(self.contract);
+        let mut code = vec!["mstore(64, memoryguard(0x80))".to_string()];
+        let payable = c.ctor.is_some_and(|ctor| {
+            gcx.hir.function(ctor).state_mutability == StateMutability::Payable
+        });
+        if !payable {
+            code.push("if callvalue() { revert(0, 0) }".into());
+        }
+        for &base in c.linearized_bases.iter().rev() {
+            if let Some(ctor) = self.constructor(base)? {
+                code.push(format!("{ctor}()"));
+            }
+        }
+        let allocate = self.functions.allocate_unbounded();
+        code.push(format!("let _1 := {allocate}()"));
+        code.push(format!(
+            "codecopy(_1, dataoffset(\"{deployed_name}\"), datasize(\"{deployed_name}\"))"
+        ));
+        code.push(format!("return(_1, datasize(\"{deployed_name}\"))"));
+        self.finish(code)
+    }
+
+    /// Generates the constructor function of the given base contract, which initializes its state
+    /// variables and runs its constructor body.
+    ///
+    /// Returns `None` if there is nothing to run.
+    fn constructor(&mut self, base: hir::ContractId) -> Result<Option<String>> {
+        let gcx = self.gcx;
+        let b = gcx.hir.contract(base);
+        if let Some(call) = b.bases_args.first() {
+            return Err(self.unimplemented(call.span, "base constructor arguments"));
+        }
+        let inits = b
+            .variables()
+            .filter(|&var| !gcx.hir.variable(var).is_constant())
+            .filter_map(|var| Some((var, gcx.hir.variable(var).initializer?)))
+            .collect::<Vec<_>>();
+        let ctor = b.ctor.map(|ctor| gcx.hir.function(ctor));
+        if inits.is_empty() && ctor.is_none_or(|ctor| ctor.body.is_none_or(|b| b.is_empty())) {
+            return Ok(None);
+        }
+
+        let name = format!("constructor_{}_{}", b.name, base.get());
+        let mut lowerer = FunctionLowerer::new(self, &[]);
+        for (var, init) in inits {
+            let span = gcx.hir.variable(var).span;
+            let place = lowerer.cx.storage_place(var, span)?;
+            let value = lowerer.expr(init)?;
+            lowerer.write_place(&place, &value);
+        }
+        if let Some(ctor) = ctor {
+            if !ctor.modifiers.is_empty() {
+                return Err(lowerer.cx.unimplemented(ctor.span, "modifiers"));
+            }
+            if let Some(body) = ctor.body {
+                lowerer.block(body)?;
+            }
+        }
+        let body = lowerer.finish();
+        self.spans.push((name.clone(), ctor.map_or(b.span, |ctor| ctor.span)));
+        self.functions.insert(name.clone(), function(format!("{name}()"), body));
+        Ok(Some(name))
+    }
+
+    /// Generates the runtime code, which dispatches calls to the external functions.
+    fn runtime_code(mut self) -> Result<(String, Vec<(String, Span)>)> {
+        let mut code = vec!["mstore(64, memoryguard(0x80))".to_string()];
+        let mut functions = self.gcx.interface_functions(self.contract).iter().collect::<Vec<_>>();
+        functions.sort_by_key(|f| f.selector);
+        if !functions.is_empty() {
+            let shift = self.functions.shift_right_224_unsigned();
+            code.push("if iszero(lt(calldatasize(), 4)) {".into());
+            code.push(format!("    let selector := {shift}(calldataload(0))"));
+            code.push("    switch selector".into());
+            for f in functions {
+                let external = self.external_function(f.id)?;
+                code.push(format!("    case {} {{ {external}() }}", f.selector));
+            }
+            code.push("    default {}".into());
+            code.push("}".into());
+        }
+        code.push("revert(0, 0)".into());
+        self.finish(code)
+    }
+
+    /// Generates the external entry point of the given function, which ABI-decodes the calldata,
+    /// calls the function, and returns the ABI-encoded return values.
+    fn external_function(&mut self, id: hir::FunctionId) -> Result<String> {
+        let gcx = self.gcx;
+        let func = gcx.hir.function(id);
+        let name = format!("external_fun_{}_{}", gcx.item_name(id), id.get());
+        let TyKind::FnPtr(ty) = gcx.type_of_item(id.into()).kind else { unreachable!() };
+        let param_tys =
+            ty.parameters.iter().map(|&ty| ValTy::from_ty(ty)).collect::<Option<Vec<_>>>();
+        let return_tys =
+            ty.returns.iter().map(|&ty| ValTy::from_ty(ty)).collect::<Option<Vec<_>>>();
+        let (Some(param_tys), Some(return_tys)) = (param_tys, return_tys) else {
+            return Err(self.unimplemented(func.span, "ABI coding of non-value types"));
+        };
+
+        let mut body = Vec::new();
+        if func.state_mutability != StateMutability::Payable {
+            body.push("if callvalue() { revert(0, 0) }".to_string());
+        }
+        let decode = self.functions.abi_decode_tuple(&param_tys);
+        let params = (0..param_tys.len()).map(|i| format!("param_{i}")).collect::<Vec<_>>();
+        if params.is_empty() {
+            body.push(format!("{decode}(4, calldatasize())"));
+        } else {
+            body.push(format!("let {} := {decode}(4, calldatasize())", params.join(", ")));
+        }
+        let call = if let Some(gettee) = func.gettee {
+            let Place::Storage { slot, offset, ty, transient } =
+                self.storage_place(gettee, func.span)?
+            else {
+                unreachable!()
+            };
+            format!("{}({slot})", self.functions.read_from_storage(ty, offset, transient))
+        } else {
+            format!("{}({})", self.internal_function(id), params.join(", "))
+        };
+        let returns = (0..return_tys.len()).map(|i| format!("ret_{i}")).collect::<Vec<_>>();
+        if returns.is_empty() {
+            body.push(call);
+        } else {
+            body.push(format!("let {} := {call}", returns.join(", ")));
+        }
+        let allocate = self.functions.allocate_unbounded();
+        let encode = self.functions.abi_encode_tuple(&return_tys);
+        body.push(format!("let memPos := {allocate}()"));
+        let args = std::iter::once("memPos".to_string()).chain(returns).collect::<Vec<_>>();
+        body.push(format!("let memEnd := {encode}({})", args.join(", ")));
+        body.push("return(memPos, sub(memEnd, memPos))".into());
+
+        self.spans.push((name.clone(), func.span));
+        self.functions.insert(name.clone(), function(format!("{name}()"), body));
+        Ok(name)
+    }
+
+    /// Generates the queued functions and appends all the functions to `code`.
+    ///
+    /// Also returns the names and spans of the functions that were generated from Solidity code.
+    fn finish(mut self, code: Vec<String>) -> Result<(String, Vec<(String, Span)>)> {
+        self.generate_queued()?;
+        let mut s = code.join("\n");
+        s.push('\n');
+        for function in self.functions.into_code() {
+            s.push('\n');
+            s.push_str(&function);
+            s.push('\n');
+        }
+        Ok((s, self.spans))
+    }
+}
@@ -0,0 +1,793 @@
+//! Lowering of function bodies.
+
+use super::{utils::ValTy, ContractCodegen, Result};
+use alloy_primitives::U256;
+use solar_ast::LitKind;
+use solar_data_structures::smallvec::SmallVec;
+use solar_interface::diagnostics::ErrorGuaranteed;
+use solar_sema::{
+    builtins::Builtin,
+    eval::ConstantEvaluator,
+    hir::{self, BinOpKind, CallArgs, Res, UnOpKind},
+};
+
+/// An assignable location.
+pub(super) enum Place {
+    /// A local variable.
+    Local(String),
+    /// A state variable, in storage or in transient storage.
+    Storage { slot: U256, offset: u8, ty: ValTy, transient: bool },
+}
+
+/// Lowers the statements and expressions of a function body to Yul.
+///
+/// Expressions are evaluated left to right into temporary variables, which hold clean values.
+pub(super) struct FunctionLowerer<'a, 'gcx> {
+    pub(super) cx: &'a mut ContractCodegen<'gcx>,
+    /// The return variables of the function.
+    returns: &'gcx [hir::VariableId],
+    lines: Vec<String>,
+    indent: usize,
+    /// The number of temporary variables declared so far.
+    temporaries: usize,
+    /// Whether we are inside of an `unchecked` block.
+    unchecked: bool,
+    /// The enclosing loops.
+    loops: Vec<hir::LoopSource>,
+}
+
+impl<'a, 'gcx> FunctionLowerer<'a, 'gcx> {
+    pub(super) fn new(cx: &'a mut ContractCodegen<'gcx>, returns: &'gcx [hir::VariableId]) -> Self {
+        Self {
+            cx,
+            returns,
+            lines: Vec::new(),
+            indent: 0,
+            temporaries: 0,
+            unchecked: false,
+            loops: Vec::new(),
+        }
+    }
+
+    /// Returns the lines of the function body.
+    pub(super) fn finish(self) -> Vec<String> {
+        self.lines
+    }
+
+    fn line(&mut self, line: impl Into<String>) {
+        let line = line.into();
+        self.lines.push(format!("{}{line}", "    ".repeat(self.indent)));
+    }
+
+    /// Declares a new temporary variable with the given value, returning its name.
+    fn temporary(&mut self, value: impl std::fmt::Display) -> String {
+        self.temporaries += 1;
+        let name = format!("expr_{}", self.temporaries);
+        self.line(format!("let {name} := {value}"));
+        name
+    }
+
+    fn unimplemented(&self, span: solar_interface::Span, what: &str) -> ErrorGuaranteed {
+        self.cx.unimplemented(span, what)
+    }
+
+    pub(super) fn block(&mut self, block: hir::Block<'gcx>) -> Result {
+        for stmt in block {
+            self.stmt(stmt)?;
+        }
+        Ok(())
+    }
+
+    /// Lowers a statement in a new indented block, without opening a new scope for blocks.
+    fn nested(&mut self, stmt: &'gcx hir::Stmt<'gcx>) -> Result {
+        self.indent += 1;
+        let result = match stmt.kind {
+            hir::StmtKind::Block(block) => self.block(block),
+            _ => self.stmt(stmt),
+        };
+        self.indent -= 1;
+        result
+    }
+
+    fn stmt(&mut self, stmt: &'gcx hir::Stmt<'gcx>) -> Result {
+        match stmt.kind {
+            hir::StmtKind::DeclSingle(id) => {
+                self.cx.var_ty(id)?;
+                let value = match self.cx.gcx.hir.variable(id).initializer {
+                    Some(init) => self.expr(init)?,
+                    None => "0".into(),
+                };
+                self.line(format!("let {} := {value}", self.cx.var_name(id)));
+            }
+            hir::StmtKind::DeclMulti(vars, expr) => {
+                let values = self.expr_values(expr)?;
+                for (&var, value) in vars.iter().zip(values) {
+                    let Some(var) = var else { continue };
+                    self.cx.var_ty(var)?;
+                    self.line(format!("let {} := {value}", self.cx.var_name(var)));
+                }
+            }
+            hir::StmtKind::Block(block) => {
+                self.line("{");
+                self.indent += 1;
+                self.block(block)?;
+                self.indent -= 1;
+                self.line("}");
+            }
+            hir::StmtKind::UncheckedBlock(block) => {
+                let unchecked = std::mem::replace(&mut self.unchecked, true);
+                self.line("{");
+                self.indent += 1;
+                self.block(block)?;
+                self.indent -= 1;
+                self.line("}");
+                self.unchecked = unchecked;
+            }
+            hir::StmtKind::Return(expr) => {
+                if let Some(expr) = expr {
+                    let values = self.expr_values(expr)?;
+                    let returns = self.returns;
+                    for (&var, value) in returns.iter().zip(values) {
+                        self.line(format!("{} := {value}", self.cx.var_name(var)));
+                    }
+                }
+                self.line("leave");
+            }
+            hir::StmtKind::Break => self.line("break"),
+            hir::StmtKind::Continue => {
+                // `for` and `do while` loops are desugared into a loop whose body ends with the
+                // loop expression or condition, which `continue` would skip.
+                if self.loops.last() != Some(&hir::LoopSource::While) {
+                    let what = "`continue` in `for` and `do while` loops";
+                    return Err(self.unimplemented(stmt.span, what));
+                }
+                self.line("continue");
+            }
+            hir::StmtKind::Loop(block, source) => {
+                self.line("for {} 1 {} {");
+                self.indent += 1;
+                self.loops.push(source);
+                let result = match (source, block) {
+                    // Lower the desugared `if (<cond>) continue; else break;` to a conditional break.
+                    (
+                        hir::LoopSource::DoWhile,
+                        [body, hir::Stmt { kind: hir::StmtKind::If(cond, _, _), .. }],
+                    ) => self.stmt(body).and_then(|()| {
+                        let cond = self.expr(cond)?;
+                        self.line(format!("if iszero({cond}) {{ break }}"));
+                        Ok(())
+                    }),
+                    _ => self.block(block),
+                };
+                self.loops.pop();
+                self.indent -= 1;
+                result?;
+                self.line("}");
+            }
+            hir::StmtKind::If(cond, then, else_) => {
+                let cond = self.expr(cond)?;
+                if let Some(else_) = else_ {
+                    self.line(format!("switch {cond}"));
+                    self.line("case 0 {");
+                    self.nested(else_)?;
+                    self.line("}");
+                    self.line("default {");
+                    self.nested(then)?;
+                    self.line("}");
+                } else {
+                    self.line(format!("if {cond} {{"));
+                    self.nested(then)?;
+                    self.line("}");
+                }
+            }
+            hir::StmtKind::Expr(expr) => {
+                self.expr_values(expr)?;
+            }
+            hir::StmtKind::Emit(..) => return Err(self.unimplemented(stmt.span, "events")),
+            hir::StmtKind::Revert(..) => return Err(self.unimplemented(stmt.span, "custom errors")),
+            hir::StmtKind::Try(_) => return Err(self.unimplemented(stmt.span, "try statements")),
+            hir::StmtKind::Assembly(_) => {
+                return Err(self.unimplemented(stmt.span, "inline assembly"));
+            }
+            hir::StmtKind::Placeholder => return Err(self.unimplemented(stmt.span, "modifiers")),
+            hir::StmtKind::Err(guar) => return Err(guar),
+        }
+        Ok(())
+    }
+
+    /// Lowers an expression that evaluates to a single value.
+    pub(super) fn expr(&mut self, expr: &'gcx hir::Expr<'gcx>) -> Result<String> {
+        let values = self.expr_values(expr)?;
+        match <[_; 1]>::try_from(values) {
+            Ok([value]) => Ok(value),
+            Err(_) => Err(self.unimplemented(expr.span, "expressions without a single value")),
+        }
+    }
+
+    /// Lowers an expression, returning its values.
+    fn expr_values(&mut self, expr: &'gcx hir::Expr<'gcx>) -> Result<Vec<String>> {
+        let expr = expr.peel_parens();
+        let value = match expr.kind {
+            hir::ExprKind::Lit(lit) => match lit.kind {
+                LitKind::Bool(value) => (value as u8).to_string(),
+                LitKind::Address(address) => {
+                    format!("{:#x}", U256::from_be_slice(address.as_slice()))
+                }
+                LitKind::Number(_) | LitKind::Rational(_) => self.constant(expr)?,
+                LitKind::Str(..) => return Err(self.unimplemented(expr.span, "string literals")),
+                LitKind::Err(guar) => return Err(guar),
+            },
+            hir::ExprKind::Ident(res) => self.ident(expr, res)?,
+            hir::ExprKind::Binary(..) | hir::ExprKind::Unary(..) | hir::ExprKind::Ternary(..)
+                if self.ty_of(expr)?.is_none() =>
+            {
+                self.constant(expr)?
+            }
+            hir::ExprKind::Binary(lhs, op, rhs) => self.binary(expr, lhs, op.kind, rhs)?,
+            hir::ExprKind::Unary(op, operand) => self.unary(expr, op.kind, operand)?,
+            hir::ExprKind::Ternary(cond, then, else_) => {
+                let cond = self.expr(cond)?;
+                self.temporaries += 1;
+                let result = format!("expr_{}", self.temporaries);
+                self.line(format!("let {result}"));
+                self.line(format!("switch {cond}"));
+                for (case, expr) in [("case 0", else_), ("default", then)] {
+                    self.line(format!("{case} {{"));
+                    self.indent += 1;
+                    let value = self.expr(expr)?;
+                    self.line(format!("{result} := {value}"));
+                    self.indent -= 1;
+                    self.line("}");
+                }
+                result
+            }
+            hir::ExprKind::Assign(lhs, op, rhs) => return self.assign(expr, lhs, op, rhs),
+            hir::ExprKind::Tuple(exprs) => {
+                let mut values = Vec::with_capacity(exprs.len());
+                for &component in exprs {
+                    let Some(component) = component else {
+                        return Err(self.unimplemented(expr.span, "tuples with empty components"));
+                    };
+                    values.push(self.expr(component)?);
+                }
+                return Ok(values);
+            }
+            hir::ExprKind::Call(callee, ref args) => return self.call(expr, callee, args),
+            hir::ExprKind::Err(guar) => return Err(guar),
+            _ => return Err(self.unimplemented(expr.span, "this expression")),
+        };
+        Ok(vec![value])
+    }
+
+    /// Evaluates a constant expression.
+    fn constant(&mut self, expr: &'gcx hir::Expr<'gcx>) -> Result<String> {
+        let mut evaluator = ConstantEvaluator::new(self.cx.gcx);
+        let value = evaluator.eval(expr)?.data;
+        Ok(if value <= U256::from(u32::MAX) { value.to_string() } else { format!("{value:#x}") })
+    }
+
+    /// Returns the type of an expression, or `None` if it is a constant without a fixed type, such
+    /// as a number literal.
+    fn ty_of(&self, expr: &'gcx hir::Expr<'gcx>) -> Result<Option<ValTy>> {
+        let expr = expr.peel_parens();
+        Ok(match expr.kind {
+            hir::ExprKind::Lit(lit) => match lit.kind {
+                LitKind::Bool(_) => Some(ValTy::Bool),
+                LitKind::Address(_) => Some(ValTy::Address),
+                LitKind::Number(_) | LitKind::Rational(_) => None,
+                LitKind::Str(..) => return Err(self.unimplemented(expr.span, "string literals")),
+                LitKind::Err(guar) => return Err(guar),
+            },
+            hir::ExprKind::Ident(&[Res::Item(hir::ItemId::Variable(id))]) => {
+                Some(self.cx.var_ty(id)?)
+            }
+            hir::ExprKind::Binary(lhs, op, rhs) => {
+                let lhs_ty = self.ty_of(lhs)?;
+                let rhs_ty = self.ty_of(rhs)?;
+                match op.kind {
+                    _ if lhs_ty.is_none() && rhs_ty.is_none() => None,
+                    BinOpKind::Lt
+                    | BinOpKind::Le
+                    | BinOpKind::Gt
+                    | BinOpKind::Ge
+                    | BinOpKind::Eq
+                    | BinOpKind::Ne
+                    | BinOpKind::And
+                    | BinOpKind::Or => Some(ValTy::Bool),
+                    BinOpKind::Shl | BinOpKind::Shr | BinOpKind::Sar => lhs_ty,
+                    _ => self.common_ty(expr, lhs_ty, rhs_ty)?,
+                }
+            }
+            hir::ExprKind::Unary(op, operand) => match self.ty_of(operand)? {
+                Some(_) if op.kind == UnOpKind::Not => Some(ValTy::Bool),
+                ty => ty,
+            },
+            hir::ExprKind::Ternary(_, then, else_) => {
+                let then_ty = self.ty_of(then)?;
+                let else_ty = self.ty_of(else_)?;
+                self.common_ty(expr, then_ty, else_ty)?
+            }
+            hir::ExprKind::Assign(lhs, _, _) => self.ty_of(lhs)?,
+            hir::ExprKind::Call(callee, _) => Some(self.call_ty(expr, callee)?),
+            hir::ExprKind::Err(guar) => return Err(guar),
+            _ => return Err(self.unimplemented(expr.span, "this expression")),
+        })
+    }
+
+    /// Returns the common type of two operands.
+    fn common_ty(
+        &self,
+        expr: &hir::Expr<'_>,
+        a: Option<ValTy>,
+        b: Option<ValTy>,
+    ) -> Result<Option<ValTy>> {
+        match (a, b) {
+            (None, None) => Ok(None),
+            (Some(ty), None) | (None, Some(ty)) => Ok(Some(ty)),
+            (Some(a), Some(b)) => ValTy::common(a, b).map(Some).ok_or_else(|| {
+                self.unimplemented(expr.span, "implicit conversions between these types")
+            }),
+        }
+    }
+
+    /// Returns the type of the single value returned by a call.
+    fn call_ty(&self, expr: &hir::Expr<'_>, callee: &'gcx hir::Expr<'gcx>) -> Result<ValTy> {
+        let gcx = self.cx.gcx;
+        let ty = match callee.kind {
+            hir::ExprKind::Type(ref ty) => Some(gcx.type_of_hir_ty(ty)),
+            hir::ExprKind::Ident(_) | hir::ExprKind::Member(..) => {
+                let mut callees = self.internal_callees(callee);
+                if callees.is_empty() {
+                    callees = self.external_library_callees(callee);
+                }
+                match *callees {
+                    [id] => match *gcx.hir.function(id).returns {
+                        [ret] => Some(gcx.type_of_item(ret.into())),
+                        _ => None,
+                    },
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        ty.and_then(ValTy::from_ty).ok_or_else(|| self.unimplemented(expr.span, "this expression"))
+    }
+
+    fn ident(&mut self, expr: &'gcx hir::Expr<'gcx>, res: &[Res]) -> Result<String> {
+        let &[Res::Item(hir::ItemId::Variable(id))] = res else {
+            return Err(self.unimplemented(expr.span, "this identifier"));
+        };
+        let var = self.cx.gcx.hir.variable(id);
+        if var.is_constant() {
+            let init = var.initializer.expect("constant variable has no initializer");
+            return self.expr(init);
+        }
+        let place = self.place(expr)?;
+        Ok(self.read_place(&place))
+    }
+
+    /// Returns the location that an expression refers to.
+    fn place(&mut self, expr: &'gcx hir::Expr<'gcx>) -> Result<Place> {
+        let expr = expr.peel_parens();
+        let hir::ExprKind::Ident(&[Res::Item(hir::ItemId::Variable(id))]) = expr.kind else {
+            return Err(self.unimplemented(expr.span, "assignments to this expression"));
+        };
+        self.cx.var_ty(id)?;
+        if self.cx.gcx.hir.variable(id).kind == hir::VarKind::State {
+            self.cx.storage_place(id, expr.span)
+        } else {
+            Ok(Place::Local(self.cx.var_name(id)))
+        }
+    }
+
+    /// Reads the value of a place into a temporary.
+    fn read_place(&mut self, place: &Place) -> String {
+        match *place {
+            Place::Local(ref name) => self.temporary(name),
+            Place::Storage { slot, offset, ty, transient } => {
+                let read = self.cx.functions.read_from_storage(ty, offset, transient);
+                self.temporary(format!("{read}({slot})"))
+            }
+        }
+    }
+
+    /// Writes a value to a place.
+    pub(super) fn write_place(&mut self, place: &Place, value: &str) {
+        match *place {
+            Place::Local(ref name) => self.line(format!("{name} := {value}")),
+            Place::Storage { slot, offset, ty, transient } => {
+                let update = self.cx.functions.update_storage(ty, offset, transient);
+                self.line(format!("{update}({slot}, {value})"));
+            }
+        }
+    }
+
+    fn assign(
+        &mut self,
+        expr: &'gcx hir::Expr<'gcx>,
+        lhs: &'gcx hir::Expr<'gcx>,
+        op: Option<hir::BinOp>,
+        rhs: &'gcx hir::Expr<'gcx>,
+    ) -> Result<Vec<String>> {
+        // Tuple assignment: `(a, b) = (b, a)`.
+        if let hir::ExprKind::Tuple(lhs_exprs @ [_, _, ..]) = lhs.kind {
+            let values = self.expr_values(rhs)?;
+            let mut places = Vec::with_capacity(lhs_exprs.len());
+            for &lhs in lhs_exprs {
+                places.push(lhs.map(|lhs| self.place(lhs)).transpose()?);
+            }
+            for (place, value) in places.iter().zip(&values) {
+                if let Some(place) = place {
+                    self.write_place(place, value);
+                }
+            }
+            return Ok(Vec::new());
+        }
+
+        let value = self.expr(rhs)?;
+        let place = self.place(lhs)?;
+        let value = if let Some(op) = op {
+            let ty = self.ty_of(lhs)?.expect("assigned expression has no type");
+            let old = self.read_place(&place);
+            let value = self.binary_op(expr, op.kind, ty, &old, &value)?;
+            self.temporary(value)
+        } else {
+            value
+        };
+        self.write_place(&place, &value);
+        Ok(vec![value])
+    }
+
+    fn binary(
+        &mut self,
+        expr: &'gcx hir::Expr<'gcx>,
+        lhs: &'gcx hir::Expr<'gcx>,
+        op: BinOpKind,
+        rhs: &'gcx hir::Expr<'gcx>,
+    ) -> Result<String> {
+        // Short-circuiting operators.
+        if let BinOpKind::And | BinOpKind::Or = op {
+            let lhs = self.expr(lhs)?;
+            let result = self.temporary(lhs);
+            let cond =
+                if op == BinOpKind::And { result.clone() } else { format!("iszero({result})") };
+            self.line(format!("if {cond} {{"));
+            self.indent += 1;
+            let rhs = self.expr(rhs)?;
+            self.line(format!("{result} := {rhs}"));
+            self.indent -= 1;
+            self.line("}");
+            return Ok(result);
+        }
+
+        let ty = match op {
+            BinOpKind::Shl | BinOpKind::Shr | BinOpKind::Sar => self.ty_of(lhs)?,
+            _ => {
+                let lhs_ty = self.ty_of(lhs)?;
+                let rhs_ty = self.ty_of(rhs)?;
+                self.common_ty(expr, lhs_ty, rhs_ty)?
+            }
+        };
+        let Some(ty) = ty else { return self.constant(expr) };
+        let lhs = self.expr(lhs)?;
+        let rhs = self.expr(rhs)?;
+        let value = self.binary_op(expr, op, ty, &lhs, &rhs)?;
+        Ok(self.temporary(value))
+    }
+
+    /// Returns the Yul expression of a binary operation on two values of type `ty`.
+    fn binary_op(
+        &mut self,
+        expr: &hir::Expr<'_>,
+        op: BinOpKind,
+        ty: ValTy,
+        lhs: &str,
+        rhs: &str,
+    ) -> Result<String> {
+        let f = &mut self.cx.functions;
+        let signed = ty.is_signed();
+        let function = match op {
+            BinOpKind::Add if self.unchecked => f.wrapping("add", ty),
+            BinOpKind::Add => f.checked_add(ty),
+            BinOpKind::Sub if self.unchecked => f.wrapping("sub", ty),
+            BinOpKind::Sub => f.checked_sub(ty),
+            BinOpKind::Mul if self.unchecked => f.wrapping("mul", ty),
+            BinOpKind::Mul => f.checked_mul(ty),
+            BinOpKind::Div if self.unchecked => f.wrapping_div(ty),
+            BinOpKind::Div => f.checked_div(ty),
+            BinOpKind::Rem => f.modulo(ty),
+            BinOpKind::Lt => (if signed { "slt" } else { "lt" }).into(),
+            BinOpKind::Gt => (if signed { "sgt" } else { "gt" }).into(),
+            BinOpKind::Le => {
+                return Ok(format!("iszero({}({lhs}, {rhs}))", if signed { "sgt" } else { "gt" }))
+            }
+            BinOpKind::Ge => {
+                return Ok(format!("iszero({}({lhs}, {rhs}))", if signed { "slt" } else { "lt" }))
+            }
+            BinOpKind::Eq => "eq".into(),
+            BinOpKind::Ne => return Ok(format!("iszero(eq({lhs}, {rhs}))")),
+            BinOpKind::BitAnd => "and".into(),
+            BinOpKind::BitOr => "or".into(),
+            BinOpKind::BitXor => "xor".into(),
+            BinOpKind::Shl => return Ok(format!("{}(shl({rhs}, {lhs}))", f.cleanup(ty))),
+            BinOpKind::Shr if signed => return Ok(format!("sar({rhs}, {lhs})")),
+            BinOpKind::Shr => return Ok(format!("shr({rhs}, {lhs})")),
+            BinOpKind::Pow => return Err(self.unimplemented(expr.span, "exponentiation")),
+            BinOpKind::Sar | BinOpKind::And | BinOpKind::Or => unreachable!(),
+        };
+        Ok(format!("{function}({lhs}, {rhs})"))
+    }
+
+    fn unary(
+        &mut self,
+        expr: &'gcx hir::Expr<'gcx>,
+        op: UnOpKind,
+        operand: &'gcx hir::Expr<'gcx>,
+    ) -> Result<String> {
+        let ty = self.ty_of(operand)?.expect("non-constant expression has no type");
+        match op {
+            UnOpKind::PreInc | UnOpKind::PreDec | UnOpKind::PostInc | UnOpKind::PostDec => {
+                let place = self.place(operand)?;
+                let old = self.read_place(&place);
+                let bin_op = match op {
+                    UnOpKind::PreInc | UnOpKind::PostInc => BinOpKind::Add,
+                    _ => BinOpKind::Sub,
+                };
+                let value = self.binary_op(expr, bin_op, ty, &old, "1")?;
+                let new = self.temporary(value);
+                self.write_place(&place, &new);
+                Ok(if matches!(op, UnOpKind::PreInc | UnOpKind::PreDec) { new } else { old })
+            }
+            UnOpKind::Not => {
+                let value = self.expr(operand)?;
+                Ok(self.temporary(format!("iszero({value})")))
+            }
+            UnOpKind::BitNot => {
+                let value = self.expr(operand)?;
+                let cleanup = self.cx.functions.cleanup(ty);
+                Ok(self.temporary(format!("{cleanup}(not({value}))")))
+            }
+            UnOpKind::Neg => {
+                let value = self.expr(operand)?;
+                let negate = self.cx.functions.negate(ty, !self.unchecked);
+                Ok(self.temporary(format!("{negate}({value})")))
+            }
+        }
+    }
+
+    fn call(
+        &mut self,
+        expr: &'gcx hir::Expr<'gcx>,
+        callee: &'gcx hir::Expr<'gcx>,
+        args: &'gcx CallArgs<'gcx>,
+    ) -> Result<Vec<String>> {
+        let gcx = self.cx.gcx;
+        let CallArgs::Unnamed(args) = *args else {
+            return Err(self.unimplemented(expr.span, "named arguments"));
+        };
+        match callee.kind {
+            // Type conversion: `uint8(x)`.
+            hir::ExprKind::Type(_) => {
+                let to = self.call_ty(expr, callee)?;
+                let [arg] = args else { unreachable!("type conversion with wrong argument count") };
+                let Some(from) = self.ty_of(arg)? else { return Ok(vec![self.constant(arg)?]) };
+                let value = self.expr(arg)?;
+                if from == to {
+                    return Ok(vec![value]);
+                }
+                let cleanup = self.cx.functions.cleanup(to);
+                Ok(vec![self.temporary(format!("{cleanup}({value})"))])
+            }
+            hir::ExprKind::Ident(_) | hir::ExprKind::Member(..) => {
+                if let hir::ExprKind::Ident(res) = callee.kind {
+                    if let Some(builtin) = res.iter().find_map(|res| match *res {
+                        Res::Builtin(builtin) => Some(builtin),
+                        _ => None,
+                    }) {
+                        self.builtin_call(expr, builtin, args)?;
+                        return Ok(Vec::new());
+                    }
+                }
+
+                let mut candidates = self.internal_callees(callee);
+                let is_external = candidates.is_empty();
+                if is_external {
+                    candidates = self.external_library_callees(callee);
+                }
+                if candidates.is_empty() {
+                    return Err(self.unimplemented(expr.span, "this call"));
+                }
+                let mut candidates = candidates
+                    .into_iter()
+                    .filter(|&id| gcx.hir.function(id).parameters.len() == args.len());
+                let (Some(id), None) = (candidates.next(), candidates.next()) else {
+                    return Err(self.unimplemented(expr.span, "calls to overloaded functions"));
+                };
+                if is_external {
+                    return self.external_library_call(id, args);
+                }
+                let id = self.resolve_virtual(id);
+
+                let mut values = Vec::with_capacity(args.len());
+                for arg in args {
+                    values.push(self.expr(arg)?);
+                }
+                let name = self.cx.internal_function(id);
+                let call = format!("{name}({})", values.join(", "));
+                let n_returns = gcx.hir.function(id).returns.len();
+                if n_returns == 0 {
+                    self.line(call);
+                    return Ok(Vec::new());
+                }
+                let returns = (0..n_returns)
+                    .map(|_| {
+                        self.temporaries += 1;
+                        format!("expr_{}", self.temporaries)
+                    })
+                    .collect::<Vec<_>>();
+                self.line(format!("let {} := {call}", returns.join(", ")));
+                Ok(returns)
+            }
+            _ => Err(self.unimplemented(expr.span, "this call")),
+        }
+    }
+
+    /// Calls the public or external library function `id` with `DELEGATECALL`, at the address
+    /// that the library is linked to.
+    fn external_library_call(
+        &mut self,
+        id: hir::FunctionId,
+        args: &'gcx [hir::Expr<'gcx>],
+    ) -> Result<Vec<String>> {
+        let gcx = self.cx.gcx;
+        let func = gcx.hir.function(id);
+        let library = func.contract.expect("library function without a library");
+        let var_tys = |vars: &[hir::VariableId]| {
+            vars.iter().map(|&var| self.cx.var_ty(var)).collect::<Result<Vec<_>>>()
+        };
+        let param_tys = var_tys(func.parameters)?;
+        let return_tys = var_tys(func.returns)?;
+
+        let mut values = Vec::with_capacity(args.len() + 1);
+        for arg in args {
+            values.push(self.expr(arg)?);
+        }
+        let allocate = self.cx.functions.allocate_unbounded();
+        let pos = self.temporary(format!("{allocate}()"));
+        let selector = alloy_primitives::hex::encode(gcx.function_selector(id));
+        self.line(format!("mstore({pos}, shl(224, 0x{selector}))"));
+        values.insert(0, format!("add({pos}, 4)"));
+        let encode = self.cx.functions.abi_encode_tuple(&param_tys);
+        let end = self.temporary(format!("{encode}({})", values.join(", ")));
+        let address = format!("linkersymbol(\"{}\")", gcx.contract_fully_qualified_name(library));
+        let success = self.temporary(format!(
+            "delegatecall(gas(), {address}, {pos}, sub({end}, {pos}), {pos}, {})",
+            return_tys.len() * 32
+        ));
+        let revert = self.cx.functions.revert_forward();
+        self.line(format!("if iszero({success}) {{ {revert}() }}"));
+        if return_tys.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let decode = self.cx.functions.abi_decode_tuple_from_memory(&return_tys);
+        let returns = (0..return_tys.len())
+            .map(|_| {
+                self.temporaries += 1;
+                format!("expr_{}", self.temporaries)
+            })
+            .collect::<Vec<_>>();
+        self.line(format!(
+            "let {} := {decode}({pos}, add({pos}, returndatasize()))",
+            returns.join(", ")
+        ));
+        Ok(returns)
+    }
+
+    fn builtin_call(
+        &mut self,
+        expr: &'gcx hir::Expr<'gcx>,
+        builtin: Builtin,
+        args: &'gcx [hir::Expr<'gcx>],
+    ) -> Result {
+        match (builtin, args) {
+            (Builtin::Require | Builtin::RequireMsg, [cond]) => {
+                let cond = self.expr(cond)?;
+                self.line(format!("if iszero({cond}) {{ revert(0, 0) }}"));
+            }
+            (Builtin::Assert, [cond]) => {
+                let cond = self.expr(cond)?;
+                let panic = self.cx.functions.panic(0x01);
+                self.line(format!("if iszero({cond}) {{ {panic}() }}"));
+            }
+            (Builtin::Revert | Builtin::RevertMsg, []) => self.line("revert(0, 0)"),
+            _ => {
+                let what = format!("`{}`", builtin.name());
+                return Err(self.unimplemented(expr.span, &what));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the function that is called when calling `id` internally from the contract being
+    /// compiled, taking overrides into account.
+    /// Returns the functions that an internal call to `callee` can refer to: `f`, `L.f` for an
+    /// internal function of a library `L`, or `M.f` for a free function of an imported module `M`.
+    fn internal_callees(&self, callee: &'gcx hir::Expr<'gcx>) -> SmallVec<[hir::FunctionId; 4]> {
+        let gcx = self.cx.gcx;
+        let function = |res: &Res| match *res {
+            Res::Item(hir::ItemId::Function(id)) => Some(id),
+            _ => None,
+        };
+        match callee.kind {
+            hir::ExprKind::Ident(res) => res.iter().filter_map(function).collect(),
+            hir::ExprKind::Member(base, member) => match base.peel_parens().kind {
+                hir::ExprKind::Ident(&[Res::Namespace(source)]) => gcx
+                    .source_declarations(source, member)
+                    .filter_map(|res| function(&res))
+                    .collect(),
+                hir::ExprKind::Ident(&[Res::Item(hir::ItemId::Contract(id))])
+                    if gcx.hir.contract(id).kind.is_library() =>
+                {
+                    let c = gcx.hir.contract(id);
+                    c.functions()
+                        .filter(|&f| {
+                            let f = gcx.hir.function(f);
+                            f.name.is_some_and(|name| name.name == member.name)
+                                && f.visibility <= hir::Visibility::Internal
+                        })
+                        .collect()
+                }
+                _ => SmallVec::new(),
+            },
+            _ => SmallVec::new(),
+        }
+    }
+
+    /// Returns the public and external functions that a call to `callee` can refer to, if it is a
+    /// member of a library: `L.f`.
+    fn external_library_callees(
+        &self,
+        callee: &'gcx hir::Expr<'gcx>,
+    ) -> SmallVec<[hir::FunctionId; 4]> {
+        let gcx = self.cx.gcx;
+        let hir::ExprKind::Member(base, member) = callee.kind else { return SmallVec::new() };
+        let hir::ExprKind::Ident(&[Res::Item(hir::ItemId::Contract(id))]) = base.peel_parens().kind
+        else {
+            return SmallVec::new();
+        };
+        let c = gcx.hir.contract(id);
+        if !c.kind.is_library() {
+            return SmallVec::new();
+        }
+        c.functions()
+            .filter(|&f| {
+                let f = gcx.hir.function(f);
+                f.name.is_some_and(|name| name.name == member.name)
+                    && f.visibility >= hir::Visibility::Public
+            })
+            .collect()
+    }
+
+    fn resolve_virtual(&self, id: hir::FunctionId) -> hir::FunctionId {
+        let gcx = self.cx.gcx;
+        let func = gcx.hir.function(id);
+        let bases = gcx.hir.contract(self.cx.contract).linearized_bases;
+        if !func.virtual_ || !func.contract.is_some_and(|c| bases.contains(&c)) {
+            return id;
+        }
+        let name = gcx.item_name(id).name;
+        let params = gcx.item_parameter_types(id);
+        bases
+            .iter()
+            .flat_map(|&base| gcx.hir.contract(base).functions())
+            .find(|&f| {
+                let f_func = gcx.hir.function(f);
+                f_func.kind == func.kind
+                    && f_func.body.is_some()
+                    && gcx.item_name(f).name == name
+                    && gcx.item_parameter_types(f) == params
+            })
+            .unwrap_or(id)
+    }
+}
@@ -0,0 +1,104 @@
+//! Source maps of the generated bytecode.
+//!
+//! Source maps use solc's compressed format: one `s:l:f:j:m` entry per instruction, separated by
+//! `;`, where fields that are equal to the ones of the previous entry are left empty, and trailing
+//! empty fields are omitted.
+//!
+//! Reference: <https://docs.soliditylang.org/en/latest/internals/source_mappings.html>
+
+use super::asm::Location;
+use solar_data_structures::map::FxHashMap;
+use solar_interface::{BytePos, Session};
+
+/// Returns the source map of the instructions at the given locations.
+///
+/// `source_ids` maps the start position of each source file to its source ID. Locations that
+/// don't map back to one of these files, like the ones of the generated Yul code, are encoded as
+/// `-1:-1:-1`.
+pub(crate) fn source_map(
+    sess: &Session,
+    locations: &[Location],
+    source_ids: &FxHashMap<BytePos, usize>,
+) -> String {
+    let sm = sess.source_map();
+    encode(locations.iter().map(|location| {
+        let span = sm.original_span(location.span);
+        let range = (!span.is_dummy())
+            .then(|| {
+                let file = sm.lookup_source_file(span.lo());
+                let &id = source_ids.get(&file.start_pos)?;
+                let start = span.lo().0 - file.start_pos.0;
+                let length = span.hi().0 - span.lo().0;
+                Some((start as i64, length as i64, id as i64))
+            })
+            .flatten();
+        let (start, length, source) = range.unwrap_or((-1, -1, -1));
+        Entry { start, length, source, jump: location.jump.to_char(), modifier_depth: 0 }
+    }))
+}
+
+/// A single instruction of a source map.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Entry {
+    start: i64,
+    length: i64,
+    source: i64,
+    jump: char,
+    modifier_depth: i64,
+}
+
+impl Entry {
+    fn fields(&self) -> [String; 5] {
+        [
+            self.start.to_string(),
+            self.length.to_string(),
+            self.source.to_string(),
+            self.jump.to_string(),
+            self.modifier_depth.to_string(),
+        ]
+    }
+}
+
+fn encode(entries: impl IntoIterator<Item = Entry>) -> String {
+    let mut s = String::new();
+    let mut prev =
+        Entry { start: -1, length: -1, source: -1, jump: '-', modifier_depth: -1 }.fields();
+    for (i, entry) in entries.into_iter().enumerate() {
+        if i > 0 {
+            s.push(';');
+        }
+        let fields = entry.fields();
+        let len = fields.iter().zip(&prev).rposition(|(a, b)| a != b).map_or(0, |i| i + 1);
+        for (j, (field, prev)) in fields.iter().zip(&prev).take(len).enumerate() {
+            if j > 0 {
+                s.push(':');
+            }
+            if field != prev {
+                s.push_str(field);
+            }
+        }
+        prev = fields;
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compression() {
+        let entry =
+            |start, length, source, jump| Entry { start, length, source, jump, modifier_depth: 0 };
+        let entries = [
+            entry(0, 10, 0, '-'),
+            entry(0, 10, 0, '-'),
+            entry(5, 10, 0, 'i'),
+            entry(5, 3, 1, 'i'),
+            entry(-1, -1, -1, 'o'),
+            entry(-1, -1, -1, '-'),
+        ];
+        assert_eq!(encode(entries), "0:10:0:-:0;;5:::i;:3:1;-1:-1:-1:o;:::-");
+        assert_eq!(encode([]), "");
+    }
+}
@@ -0,0 +1,437 @@
+//! Yul utility functions, mirroring solc's `YulUtilFunctions` and `ABIFunctions`.
+
+use alloy_primitives::U256;
+use solar_ast::ElementaryType;
+use solar_sema::ty::{Ty, TyKind};
+use std::{collections::BTreeMap, fmt::Write};
+
+/// A value type supported by the code generator.
+///
+/// Values are always kept clean on the stack, that is, the unused high-order bits of a value are
+/// zero, or sign-extended for signed integers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum ValTy {
+    Bool,
+    Address,
+    UInt(u16),
+    Int(u16),
+}
+
+impl ValTy {
+    /// Converts a type into a value type, if it is supported.
+    pub(super) fn from_ty(ty: Ty<'_>) -> Option<Self> {
+        match ty.kind {
+            TyKind::Elementary(ElementaryType::Bool) => Some(Self::Bool),
+            TyKind::Elementary(ElementaryType::Address(_)) => Some(Self::Address),
+            TyKind::Elementary(ElementaryType::UInt(size)) => Some(Self::UInt(size.bits())),
+            TyKind::Elementary(ElementaryType::Int(size)) => Some(Self::Int(size.bits())),
+            _ => None,
+        }
+    }
+
+    /// Returns the common type of two operands of a binary operation.
+    pub(super) fn common(a: Self, b: Self) -> Option<Self> {
+        match (a, b) {
+            _ if a == b => Some(a),
+            (Self::UInt(a), Self::UInt(b)) => Some(Self::UInt(a.max(b))),
+            (Self::Int(a), Self::Int(b)) => Some(Self::Int(a.max(b))),
+            _ => None,
+        }
+    }
+
+    /// Returns the identifier used in function names, e.g. `t_uint256`.
+    pub(super) fn id(self) -> String {
+        match self {
+            Self::Bool => "t_bool".into(),
+            Self::Address => "t_address".into(),
+            Self::UInt(bits) => format!("t_uint{bits}"),
+            Self::Int(bits) => format!("t_int{bits}"),
+        }
+    }
+
+    /// Returns the number of bytes the value occupies in storage.
+    pub(super) fn bytes(self) -> u8 {
+        match self {
+            Self::Bool => 1,
+            Self::Address => 20,
+            Self::UInt(bits) | Self::Int(bits) => (bits / 8) as u8,
+        }
+    }
+
+    pub(super) fn is_signed(self) -> bool {
+        matches!(self, Self::Int(_))
+    }
+
+    /// Returns the maximum value of an integer type.
+    fn max(self) -> U256 {
+        match self {
+            Self::Int(bits) => mask(bits / 8) >> 1,
+            _ => mask(self.bytes() as u16),
+        }
+    }
+
+    /// Returns the minimum value of a signed integer type, in two's complement.
+    fn min(self) -> U256 {
+        !self.max()
+    }
+}
+
+/// Returns a mask of the lowest `bytes` bytes.
+fn mask(bytes: u16) -> U256 {
+    if bytes >= 32 {
+        U256::MAX
+    } else {
+        (U256::from(1) << (bytes * 8)) - U256::from(1)
+    }
+}
+
+/// Formats a number as a hexadecimal Yul literal.
+pub(super) fn hex(value: U256) -> String {
+    format!("0x{value:x}")
+}
+
+/// Formats a Yul function definition.
+pub(super) fn function(
+    header: impl std::fmt::Display,
+    body: impl IntoIterator<Item = impl AsRef<str>>,
+) -> String {
+    let mut s = format!("function {header} {{\n");
+    for line in body {
+        writeln!(s, "    {}", line.as_ref()).unwrap();
+    }
+    s.push('}');
+    s
+}
+
+/// A collection of generated Yul functions, keyed by name.
+#[derive(Default)]
+pub(super) struct Functions {
+    functions: BTreeMap<String, String>,
+}
+
+impl Functions {
+    /// Returns `true` if a function with the given name has already been defined.
+    pub(super) fn contains(&self, name: &str) -> bool {
+        self.functions.contains_key(name)
+    }
+
+    /// Defines a function with the given name.
+    pub(super) fn insert(&mut self, name: String, code: String) {
+        self.functions.insert(name, code);
+    }
+
+    /// Defines the function `name` with the code returned by `f`, unless it is already defined.
+    ///
+    /// Returns `name`.
+    pub(super) fn create(
+        &mut self,
+        name: String,
+        f: impl FnOnce(&mut Self, &str) -> String,
+    ) -> String {
+        if !self.contains(&name) {
+            // Insert a placeholder first in case `f` refers to this function.
+            self.functions.insert(name.clone(), String::new());
+            let code = f(self, &name);
+            self.functions.insert(name.clone(), code);
+        }
+        name
+    }
+
+    /// Returns the definitions of all the functions, sorted by name.
+    pub(super) fn into_code(self) -> impl Iterator<Item = String> {
+        self.functions.into_values()
+    }
+
+    pub(super) fn allocate_unbounded(&mut self) -> String {
+        self.create("allocate_unbounded".into(), |_, name| {
+            function(format!("{name}() -> memPtr"), ["memPtr := mload(64)"])
+        })
+    }
+
+    pub(super) fn shift_right_224_unsigned(&mut self) -> String {
+        self.create("shift_right_224_unsigned".into(), |_, name| {
+            function(format!("{name}(value) -> newValue"), ["newValue := shr(224, value)"])
+        })
+    }
+
+    /// Reverts with a `Panic(uint256)` error with the given code.
+    pub(super) fn panic(&mut self, code: u8) -> String {
+        self.create(format!("panic_error_{code:#04x}"), |_, name| {
+            function(
+                format!("{name}()"),
+                [
+                    "mstore(0, shl(224, 0x4e487b71))".into(),
+                    format!("mstore(4, {code:#04x})"),
+                    "revert(0, 0x24)".into(),
+                ],
+            )
+        })
+    }
+
+    /// Cleans up the high-order bits of a value.
+    pub(super) fn cleanup(&mut self, ty: ValTy) -> String {
+        self.create(format!("cleanup_{}", ty.id()), |_, name| {
+            let value = match ty {
+                ValTy::Bool => "iszero(iszero(value))".into(),
+                ValTy::Int(bits) if bits < 256 => format!("signextend({}, value)", bits / 8 - 1),
+                ValTy::Int(_) | ValTy::UInt(256) => "value".into(),
+                ValTy::Address | ValTy::UInt(_) => format!("and(value, {})", hex(ty.max())),
+            };
+            function(format!("{name}(value) -> cleaned"), [format!("cleaned := {value}")])
+        })
+    }
+
+    /// Reverts if a value is not clean.
+    pub(super) fn validator(&mut self, ty: ValTy) -> String {
+        self.create(format!("validator_revert_{}", ty.id()), |this, name| {
+            let cleanup = this.cleanup(ty);
+            function(
+                format!("{name}(value)"),
+                [format!("if iszero(eq(value, {cleanup}(value))) {{ revert(0, 0) }}")],
+            )
+        })
+    }
+
+    pub(super) fn checked_add(&mut self, ty: ValTy) -> String {
+        self.create(format!("checked_add_{}", ty.id()), |this, name| {
+            let panic = this.panic(0x11);
+            let overflow = match ty {
+                ValTy::Int(256) => {
+                    "or(and(iszero(slt(y, 0)), slt(sum, x)), and(slt(y, 0), iszero(slt(sum, x))))"
+                        .into()
+                }
+                ValTy::Int(_) => {
+                    format!("or(sgt(sum, {}), slt(sum, {}))", hex(ty.max()), hex(ty.min()))
+                }
+                ValTy::UInt(256) => "gt(x, sum)".into(),
+                _ => format!("gt(sum, {})", hex(ty.max())),
+            };
+            function(
+                format!("{name}(x, y) -> sum"),
+                ["sum := add(x, y)".into(), format!("if {overflow} {{ {panic}() }}")],
+            )
+        })
+    }
+
+    pub(super) fn checked_sub(&mut self, ty: ValTy) -> String {
+        self.create(format!("checked_sub_{}", ty.id()), |this, name| {
+            let panic = this.panic(0x11);
+            let overflow = match ty {
+                ValTy::Int(256) => {
+                    "or(and(iszero(slt(y, 0)), sgt(diff, x)), and(slt(y, 0), slt(diff, x)))".into()
+                }
+                ValTy::Int(_) => {
+                    format!("or(sgt(diff, {}), slt(diff, {}))", hex(ty.max()), hex(ty.min()))
+                }
+                _ => "gt(diff, x)".into(),
+            };
+            function(
+                format!("{name}(x, y) -> diff"),
+                ["diff := sub(x, y)".into(), format!("if {overflow} {{ {panic}() }}")],
+            )
+        })
+    }
+
+    pub(super) fn checked_mul(&mut self, ty: ValTy) -> String {
+        self.create(format!("checked_mul_{}", ty.id()), |this, name| {
+            let panic = this.panic(0x11);
+            let mut body = vec![match ty {
+                ValTy::UInt(256) | ValTy::Int(256) => "product := mul(x, y)".into(),
+                _ => format!("product := {}(mul(x, y))", this.cleanup(ty)),
+            }];
+            let div = if ty.is_signed() { "sdiv" } else { "div" };
+            if ty == ValTy::Int(256) {
+                body.push(format!(
+                    "if and(eq(x, not(0)), eq(y, {})) {{ {panic}() }}",
+                    hex(ty.min())
+                ));
+            }
+            body.push(format!(
+                "if iszero(or(iszero(x), eq(y, {div}(product, x)))) {{ {panic}() }}"
+            ));
+            function(format!("{name}(x, y) -> product"), body)
+        })
+    }
+
+    pub(super) fn checked_div(&mut self, ty: ValTy) -> String {
+        self.create(format!("checked_div_{}", ty.id()), |this, name| {
+            let mut body = vec![format!("if iszero(y) {{ {}() }}", this.panic(0x12))];
+            if ty.is_signed() {
+                body.push(format!(
+                    "if and(eq(x, {}), eq(y, not(0))) {{ {}() }}",
+                    hex(ty.min()),
+                    this.panic(0x11)
+                ));
+            }
+            let div = if ty.is_signed() { "sdiv" } else { "div" };
+            body.push(format!("r := {div}(x, y)"));
+            function(format!("{name}(x, y) -> r"), body)
+        })
+    }
+
+    pub(super) fn wrapping_div(&mut self, ty: ValTy) -> String {
+        self.create(format!("wrapping_div_{}", ty.id()), |this, name| {
+            let panic = this.panic(0x12);
+            let div = if ty.is_signed() {
+                format!("{}(sdiv(x, y))", this.cleanup(ty))
+            } else {
+                "div(x, y)".into()
+            };
+            function(
+                format!("{name}(x, y) -> r"),
+                [format!("if iszero(y) {{ {panic}() }}"), format!("r := {div}")],
+            )
+        })
+    }
+
+    /// The remainder of a division. This is the same in checked and unchecked arithmetic.
+    pub(super) fn modulo(&mut self, ty: ValTy) -> String {
+        self.create(format!("mod_{}", ty.id()), |this, name| {
+            let panic = this.panic(0x12);
+            let rem = if ty.is_signed() { "smod" } else { "mod" };
+            function(
+                format!("{name}(x, y) -> r"),
+                [format!("if iszero(y) {{ {panic}() }}"), format!("r := {rem}(x, y)")],
+            )
+        })
+    }
+
+    /// Unchecked `add`, `sub` and `mul`.
+    pub(super) fn wrapping(&mut self, op: &str, ty: ValTy) -> String {
+        self.create(format!("wrapping_{op}_{}", ty.id()), |this, name| {
+            let cleanup = this.cleanup(ty);
+            function(format!("{name}(x, y) -> r"), [format!("r := {cleanup}({op}(x, y))")])
+        })
+    }
+
+    pub(super) fn negate(&mut self, ty: ValTy, checked: bool) -> String {
+        let kind = if checked { "checked" } else { "wrapping" };
+        self.create(format!("{kind}_negate_{}", ty.id()), |this, name| {
+            let body = if checked {
+                let panic = this.panic(0x11);
+                vec![
+                    format!("if eq(value, {}) {{ {panic}() }}", hex(ty.min())),
+                    "r := sub(0, value)".into(),
+                ]
+            } else {
+                vec![format!("r := {}(sub(0, value))", this.cleanup(ty))]
+            };
+            function(format!("{name}(value) -> r"), body)
+        })
+    }
+
+    /// Reads a value from the given storage slot, or transient storage slot if `transient` is
+    /// `true`, at the given byte offset.
+    pub(super) fn read_from_storage(&mut self, ty: ValTy, offset: u8, transient: bool) -> String {
+        let (prefix, load) = if transient { ("transient_", "tload") } else { ("", "sload") };
+        let id = format!("read_from_{prefix}storage_offset_{offset}_{}", ty.id());
+        self.create(id, |_, name| {
+            let bytes = ty.bytes();
+            let value = if offset == 0 {
+                format!("{load}(slot)")
+            } else {
+                format!("shr({}, {load}(slot))", offset as u16 * 8)
+            };
+            let value = match ty {
+                _ if bytes == 32 => value,
+                ValTy::Int(_) => format!("signextend({}, {value})", bytes - 1),
+                _ => format!("and({value}, {})", hex(mask(bytes as u16))),
+            };
+            function(format!("{name}(slot) -> value"), [format!("value := {value}")])
+        })
+    }
+
+    /// Writes a value to the given storage slot, or transient storage slot if `transient` is
+    /// `true`, at the given byte offset.
+    pub(super) fn update_storage(&mut self, ty: ValTy, offset: u8, transient: bool) -> String {
+        let (prefix, load, store) =
+            if transient { ("transient_", "tload", "tstore") } else { ("", "sload", "sstore") };
+        let id = format!("update_{prefix}storage_value_offset_{offset}_{}", ty.id());
+        self.create(id, |_, name| {
+            let bytes = ty.bytes();
+            let body = if bytes == 32 {
+                vec![format!("{store}(slot, value)")]
+            } else {
+                let value_mask = mask(bytes as u16);
+                let shift = offset as u16 * 8;
+                let to_insert = if offset == 0 {
+                    format!("and(value, {})", hex(value_mask))
+                } else {
+                    format!("shl({shift}, and(value, {}))", hex(value_mask))
+                };
+                vec![
+                    format!("let mask := {}", hex(value_mask << shift)),
+                    format!("let toInsert := {to_insert}"),
+                    format!("{store}(slot, or(and({load}(slot), not(mask)), toInsert))"),
+                ]
+            };
+            function(format!("{name}(slot, value)"), body)
+        })
+    }
+
+    /// Reverts with the return data of the last call.
+    pub(super) fn revert_forward(&mut self) -> String {
+        self.create("revert_forward_1".into(), |this, name| {
+            let allocate = this.allocate_unbounded();
+            function(
+                format!("{name}()"),
+                [
+                    format!("let pos := {allocate}()"),
+                    "returndatacopy(pos, 0, returndatasize())".into(),
+                    "revert(pos, returndatasize())".into(),
+                ],
+            )
+        })
+    }
+
+    /// ABI-decodes the given types from calldata.
+    pub(super) fn abi_decode_tuple(&mut self, tys: &[ValTy]) -> String {
+        self.abi_decode_tuple_impl(tys, false)
+    }
+
+    /// ABI-decodes the given types from memory.
+    pub(super) fn abi_decode_tuple_from_memory(&mut self, tys: &[ValTy]) -> String {
+        self.abi_decode_tuple_impl(tys, true)
+    }
+
+    fn abi_decode_tuple_impl(&mut self, tys: &[ValTy], from_memory: bool) -> String {
+        let (suffix, load) =
+            if from_memory { ("_fromMemory", "mload") } else { ("", "calldataload") };
+        self.create(format!("abi_decode_tuple_{}{suffix}", ids(tys)), |this, name| {
+            let mut header = format!("{name}(headStart, dataEnd)");
+            if !tys.is_empty() {
+                let values = (0..tys.len()).map(|i| format!("value{i}")).collect::<Vec<_>>();
+                write!(header, " -> {}", values.join(", ")).unwrap();
+            }
+            let mut body = vec![format!(
+                "if slt(sub(dataEnd, headStart), {}) {{ revert(0, 0) }}",
+                tys.len() * 32
+            )];
+            for (i, &ty) in tys.iter().enumerate() {
+                body.push(format!("value{i} := {load}(add(headStart, {}))", i * 32));
+                body.push(format!("{}(value{i})", this.validator(ty)));
+            }
+            function(header, body)
+        })
+    }
+
+    /// ABI-encodes the given types to memory, returning the end of the encoded data.
+    pub(super) fn abi_encode_tuple(&mut self, tys: &[ValTy]) -> String {
+        self.create(format!("abi_encode_tuple_{}", ids(tys)), |_, name| {
+            let mut header = format!("{name}(headStart");
+            for i in 0..tys.len() {
+                write!(header, ", value{i}").unwrap();
+            }
+            header.push_str(") -> tail");
+            let mut body = vec![format!("tail := add(headStart, {})", tys.len() * 32)];
+            for i in 0..tys.len() {
+                body.push(format!("mstore(add(headStart, {}), value{i})", i * 32));
+            }
+            function(header, body)
+        })
+    }
+}
+
+fn ids(tys: &[ValTy]) -> String {
+    tys.iter().map(|ty| ty.id()).collect::<Vec<_>>().join("_")
+}
@@ -17,10 +17,20 @@ str_enum! {
     #[derive(strum::EnumIs)]
     #[strum(serialize_all = "lowercase")]
     pub enum CompilerStage {
+        // NOTE: Order matters.
         /// Source code was parsed into an AST.
         #[strum(serialize = "parsed", serialize = "parsing")]
         Parsed,
-        // TODO: More
+        /// The AST was validated and lowered into the HIR, resolving names.
+        #[strum(serialize = "resolved", serialize = "resolving")]
+        Resolved,
+        /// The HIR was type checked.
+        #[strum(serialize = "checked", serialize = "checking")]
+        Checked,
+        /// The Yul IR of all the contracts was generated.
+        Ir,
+        /// The bytecode of all the contracts was generated.
+        Codegen,
     }
 }
 
@@ -89,6 +99,9 @@ impl EvmVersion {
     pub fn has_blob_base_fee(self) -> bool {
         self >= Self::Cancun
     }
+    pub fn has_transient_storage(self) -> bool {
+        self >= Self::Cancun
+    }
     pub fn has_prev_randao(self) -> bool {
         self >= Self::Paris
     }
@@ -97,18 +110,65 @@ impl EvmVersion {
     }
 }
 
+str_enum! {
+    /// The hash of the contract metadata that is appended to the runtime bytecode.
+    ///
+    /// The hash is CBOR-encoded together with the compiler version, like in solc.
+    #[derive(Default)]
+    #[strum(serialize_all = "lowercase")]
+    pub enum MetadataHash {
+        /// IPFS hash of the metadata.
+        #[default]
+        Ipfs,
+        /// Swarm hash of the metadata, as `bzzr1`.
+        #[strum(to_string = "swarm", serialize = "bzzr1")]
+        Swarm,
+        /// No hash; only the compiler version is appended.
+        None,
+    }
+}
+
 str_enum! {
     /// Type of output for the compiler to emit.
     #[strum(serialize_all = "kebab-case")]
     pub enum CompilerOutput {
         /// JSON ABI.
         Abi,
-        // /// Creation bytecode.
-        // Bin,
-        // /// Runtime bytecode.
-        // BinRuntime,
+        /// Compact JSON AST, in the same format as solc's `--ast-compact-json`.
+        Ast,
+        /// Source code printed back from the AST, for debugging the parser.
+        AstPretty,
+        /// Creation bytecode.
+        Bin,
+        /// Runtime bytecode.
+        BinRuntime,
+        /// Graph of the calls between functions and modifiers, as JSON.
+        CallGraph,
+        /// Graph of the calls between functions and modifiers, in the Graphviz DOT format.
+        CallGraphDot,
+        /// NatSpec developer documentation.
+        Devdoc,
         /// Function signature hashes.
         Hashes,
+        /// Graph of the resolved imports between source files, as JSON.
+        ImportGraph,
+        /// Graph of the resolved imports between source files, in the Graphviz DOT format.
+        ImportGraphDot,
+        /// Solidity interface declaring the external functions, events, and errors of each
+        /// contract.
+        Interface,
+        /// Yul intermediate representation.
+        Ir,
+        /// Contract metadata JSON, in the same format as solc's `--metadata`.
+        Metadata,
+        /// Source map of the creation bytecode, in the same format as solc's `srcmap`.
+        Srcmap,
+        /// Source map of the runtime bytecode, in the same format as solc's `srcmap-runtime`.
+        SrcmapRuntime,
+        /// Storage layout, in the same format as solc's `storageLayout` output.
+        StorageLayout,
+        /// NatSpec user documentation.
+        Userdoc,
     }
 }
 
@@ -140,6 +200,8 @@ str_enum! {
     #[derive(EnumIs)]
     #[strum(serialize_all = "kebab-case")]
     pub enum DumpKind {
+        /// Print the tokens of each source file, along with their line and column.
+        Tokens,
         /// Print the AST.
         Ast,
         /// Print the HIR.
@@ -0,0 +1,41 @@
+/*
+ * C bindings for the Solar compiler.
+ *
+ * All strings are null-terminated and UTF-8 encoded. Strings returned by `solar_compile_standard`
+ * are owned by the caller and must be freed with `solar_free_string`.
+ */
+
+#ifndef SOLAR_H
+#define SOLAR_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/*
+ * Returns the version of the compiler. The returned string is static and must not be freed.
+ */
+const char *solar_version(void);
+
+/*
+ * Compiles the standard JSON input in the `len` bytes at `input`, and returns the standard JSON
+ * output.
+ *
+ * Diagnostics, including invalid inputs, are reported in the `errors` field of the output.
+ * Returns NULL only if the compiler panicked. This requires the library to be built with
+ * `panic = "unwind"`, as in the `ffi` profile; otherwise a panic aborts the process.
+ */
+char *solar_compile_standard(const char *input, size_t len);
+
+/*
+ * Frees a string returned by `solar_compile_standard`. Does nothing if `ptr` is NULL.
+ */
+void solar_free_string(char *ptr);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* SOLAR_H */
@@ -0,0 +1,126 @@
+//! C bindings for the Solar compiler.
+//!
+//! The functions in this crate are declared in `include/solar.h`. They compile a standard JSON
+//! input from a buffer and return the standard JSON output, which contains the diagnostics and the
+//! artifacts.
+//!
+//! See <https://docs.soliditylang.org/en/latest/using-the-compiler.html#compiler-input-and-output-json-description>.
+
+use solar_cli::{cli::Args, standard_json};
+use std::{
+    ffi::{c_char, CStr, CString},
+    panic::{self, AssertUnwindSafe},
+    ptr,
+    sync::LazyLock,
+};
+
+/// Returns the version of the compiler.
+///
+/// The returned string is static and must not be freed.
+#[no_mangle]
+pub extern "C" fn solar_version() -> *const c_char {
+    static VERSION: LazyLock<CString> =
+        LazyLock::new(|| CString::new(solar_cli::version::SHORT_VERSION).unwrap());
+    VERSION.as_ptr()
+}
+
+/// Compiles the standard JSON input in the `len` bytes at `input`, and returns the standard JSON
+/// output.
+///
+/// Diagnostics, including invalid inputs, are reported in the `errors` field of the output.
+/// Returns a null pointer only if the compiler panicked. This requires the library to be built
+/// with `panic = "unwind"`, as in the `ffi` profile; otherwise a panic aborts the process.
+///
+/// The returned string must be freed with [`solar_free_string`].
+///
+/// # Safety
+///
+/// `input` must be valid for reads of `len` bytes, or null if `len` is zero.
+#[no_mangle]
+pub unsafe extern "C" fn solar_compile_standard(input: *const c_char, len: usize) -> *mut c_char {
+    let input = if len == 0 {
+        &[][..]
+    } else {
+        // SAFETY: guaranteed by the caller.
+        unsafe { std::slice::from_raw_parts(input.cast::<u8>(), len) }
+    };
+    let input = String::from_utf8_lossy(input);
+    catch_panic(|| compile_standard(&input))
+}
+
+/// Frees a string returned by [`solar_compile_standard`].
+///
+/// Does nothing if `ptr` is null.
+///
+/// # Safety
+///
+/// `ptr` must have been returned by [`solar_compile_standard`], and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn solar_free_string(ptr: *mut c_char) {
+    if !ptr.is_null() {
+        // SAFETY: guaranteed by the caller.
+        drop(unsafe { CString::from_raw(ptr) });
+    }
+}
+
+/// Returns the string returned by `f`, or a null pointer if it panicked.
+fn catch_panic(f: impl FnOnce() -> CString) -> *mut c_char {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(output) => output.into_raw(),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+fn compile_standard(input: &str) -> CString {
+    let args = solar_cli::parse_args(["solar", "--standard-json"]).unwrap();
+    let output = standard_json::compile_json(&args, input).to_string();
+    // JSON strings escape control characters, so the output never contains a null byte.
+    CString::new(output).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compile(input: &str) -> serde_json::Value {
+        unsafe {
+            let ptr = solar_compile_standard(input.as_ptr().cast(), input.len());
+            assert!(!ptr.is_null());
+            let output = serde_json::from_slice(CStr::from_ptr(ptr).to_bytes()).unwrap();
+            solar_free_string(ptr);
+            output
+        }
+    }
+
+    #[test]
+    fn abi() {
+        let input = r#"{
+            "language": "Solidity",
+            "sources": { "a.sol": { "content": "contract C { function f() external pure {} }" } },
+            "settings": { "outputSelection": { "*": { "*": ["abi"] } } }
+        }"#;
+        let output = compile(input);
+        assert_eq!(output["contracts"]["a.sol"]["C"]["abi"][0]["name"], "f");
+    }
+
+    #[test]
+    fn invalid_input() {
+        let output = compile("");
+        assert_eq!(output["errors"][0]["type"], "JSONError");
+        unsafe { solar_free_string(ptr::null_mut()) };
+    }
+
+    #[test]
+    fn panic() {
+        assert!(catch_panic(|| panic!("oops")).is_null());
+        let ptr = catch_panic(|| CString::new("ok").unwrap());
+        assert!(!ptr.is_null());
+        unsafe { solar_free_string(ptr) };
+    }
+
+    #[test]
+    fn version() {
+        let version = unsafe { CStr::from_ptr(solar_version()) };
+        assert_eq!(version.to_str().unwrap(), solar_cli::version::SHORT_VERSION);
+    }
+}
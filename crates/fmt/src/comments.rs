@@ -0,0 +1,70 @@
+//! Comments of a source file, which are not retained in the AST.
+
+use solar_parse::{lexer::token::RawTokenKind, Cursor};
+use std::ops::Range;
+
+/// A comment, including doc-comments.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Comment {
+    /// The byte range of the comment in the source file.
+    pub(crate) range: Range<usize>,
+}
+
+/// The comments of a source file, in source order.
+pub(crate) struct Comments {
+    comments: Vec<Comment>,
+    /// The index of the next comment that has not been printed.
+    next: usize,
+}
+
+impl Comments {
+    /// Collects the comments of the given source.
+    pub(crate) fn new(src: &str) -> Self {
+        let mut comments = Vec::new();
+        let mut pos = 0;
+        for token in Cursor::new(src) {
+            let start = pos;
+            pos += token.len as usize;
+            if matches!(
+                token.kind,
+                RawTokenKind::LineComment { .. } | RawTokenKind::BlockComment { .. }
+            ) {
+                comments.push(Comment { range: start..pos });
+            }
+        }
+        Self { comments, next: 0 }
+    }
+
+    /// Returns the next comment that has not been printed, if any.
+    pub(crate) fn peek(&self) -> Option<&Comment> {
+        self.comments.get(self.next)
+    }
+
+    /// Takes the next comment if it ends before `pos`.
+    pub(crate) fn next_before(&mut self, pos: usize) -> Option<Comment> {
+        let comment = self.peek().filter(|comment| comment.range.end <= pos)?.clone();
+        self.next += 1;
+        Some(comment)
+    }
+
+    /// Returns `true` if there is a comment that has not been printed and ends before `pos`.
+    pub(crate) fn has_before(&self, pos: usize) -> bool {
+        self.peek().is_some_and(|comment| comment.range.end <= pos)
+    }
+
+    /// Skips all the comments that end before `pos`.
+    pub(crate) fn skip_before(&mut self, pos: usize) {
+        while self.next_before(pos).is_some() {}
+    }
+}
+
+/// Returns the normalized text of all the comments in `src`, in source order.
+///
+/// Whitespace is collapsed, so that comments compare equal after re-indentation.
+pub(crate) fn comment_texts(src: &str) -> Vec<String> {
+    Comments::new(src)
+        .comments
+        .iter()
+        .map(|comment| src[comment.range.clone()].split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect()
+}
@@ -0,0 +1,186 @@
+//! A minimal Wadler-style pretty-printing document.
+
+use std::borrow::Cow;
+
+/// A pretty-printing document.
+///
+/// [`Group`](Doc::Group)s are printed on a single line if they fit in the remaining width, and
+/// all of their [`Line`](Doc::Line)s and [`SoftLine`](Doc::SoftLine)s are broken otherwise.
+#[derive(Clone, Debug)]
+pub(crate) enum Doc {
+    /// Text without newlines.
+    Text(Cow<'static, str>),
+    /// A space, or a newline if the enclosing group is broken.
+    Line,
+    /// Nothing, or a newline if the enclosing group is broken.
+    SoftLine,
+    /// A newline. Breaks all the enclosing groups.
+    HardLine,
+    /// Indents the lines in the document by one level.
+    Indent(Box<Doc>),
+    /// A document that is printed on a single line if it fits.
+    Group(Box<Doc>),
+    /// A sequence of documents.
+    Concat(Vec<Doc>),
+}
+
+impl Doc {
+    pub(crate) const NIL: Self = Self::Concat(Vec::new());
+
+    pub(crate) fn text(s: impl Into<Cow<'static, str>>) -> Self {
+        Self::Text(s.into())
+    }
+
+    pub(crate) fn indent(self) -> Self {
+        Self::Indent(Box::new(self))
+    }
+
+    pub(crate) fn group(self) -> Self {
+        Self::Group(Box::new(self))
+    }
+
+    /// Joins the documents with the separator.
+    pub(crate) fn join(docs: impl IntoIterator<Item = Self>, sep: impl Fn() -> Self) -> Self {
+        let mut v = Vec::new();
+        for (i, doc) in docs.into_iter().enumerate() {
+            if i != 0 {
+                v.push(sep());
+            }
+            v.push(doc);
+        }
+        Self::Concat(v)
+    }
+
+    /// Returns a group of the documents between `open` and `close`, separated by commas, and
+    /// broken one per line if they don't fit.
+    pub(crate) fn list(
+        open: &'static str,
+        docs: impl IntoIterator<Item = Self>,
+        close: &'static str,
+    ) -> Self {
+        let docs = docs.into_iter().collect::<Vec<_>>();
+        if docs.is_empty() {
+            return Self::text(format!("{open}{close}"));
+        }
+        let items = Self::join(docs, || Self::Concat(vec![Self::text(","), Self::Line]));
+        Self::Concat(vec![
+            Self::text(open),
+            Self::Concat(vec![Self::SoftLine, items]).indent(),
+            Self::SoftLine,
+            Self::text(close),
+        ])
+        .group()
+    }
+}
+
+impl From<&'static str> for Doc {
+    fn from(s: &'static str) -> Self {
+        Self::text(s)
+    }
+}
+
+impl From<String> for Doc {
+    fn from(s: String) -> Self {
+        Self::text(s)
+    }
+}
+
+/// Concatenates documents.
+macro_rules! cat {
+    ($($doc:expr),* $(,)?) => {
+        $crate::doc::Doc::Concat(vec![$($crate::doc::Doc::from($doc)),*])
+    };
+}
+pub(crate) use cat;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+type Cmd<'a> = (usize, Mode, &'a Doc);
+
+/// Renders the document, breaking groups that don't fit in `width` columns.
+pub(crate) fn render(doc: &Doc, width: usize, tab_width: usize) -> String {
+    let mut out = String::new();
+    let mut col = 0;
+    let mut cmds: Vec<Cmd<'_>> = vec![(0, Mode::Break, doc)];
+    while let Some((indent, mode, doc)) = cmds.pop() {
+        match doc {
+            Doc::Text(s) => {
+                out.push_str(s);
+                col += s.chars().count();
+            }
+            Doc::Line if mode == Mode::Flat => {
+                out.push(' ');
+                col += 1;
+            }
+            Doc::SoftLine if mode == Mode::Flat => {}
+            Doc::Line | Doc::SoftLine | Doc::HardLine => {
+                let len = out.trim_end_matches(' ').len();
+                out.truncate(len);
+                out.push('\n');
+                out.extend(std::iter::repeat(' ').take(indent));
+                col = indent;
+            }
+            Doc::Indent(doc) => cmds.push((indent + tab_width, mode, doc)),
+            Doc::Group(doc) => {
+                let fits = mode == Mode::Flat
+                    || fits((indent, Mode::Flat, doc), &cmds, width as isize - col as isize);
+                cmds.push((indent, if fits { Mode::Flat } else { Mode::Break }, doc));
+            }
+            Doc::Concat(docs) => cmds.extend(docs.iter().rev().map(|doc| (indent, mode, doc))),
+        }
+    }
+    let len = out.trim_end().len();
+    out.truncate(len);
+    out.push('\n');
+    out
+}
+
+/// Returns `true` if `next` fits in `width` columns, followed by the rest of the commands up to the
+/// next possible line break.
+fn fits(next: Cmd<'_>, rest: &[Cmd<'_>], mut width: isize) -> bool {
+    let mut rest = rest.iter().rev();
+    let mut cmds = vec![next];
+    while width >= 0 {
+        let Some((indent, mode, doc)) = cmds.pop().or_else(|| rest.next().copied()) else {
+            return true;
+        };
+        match doc {
+            Doc::Text(s) => width -= s.chars().count() as isize,
+            Doc::Line | Doc::SoftLine | Doc::HardLine if mode == Mode::Break => return true,
+            Doc::Line => width -= 1,
+            Doc::SoftLine => {}
+            Doc::HardLine => return false,
+            Doc::Indent(doc) | Doc::Group(doc) => cmds.push((indent, mode, doc)),
+            Doc::Concat(docs) => cmds.extend(docs.iter().rev().map(|doc| (indent, mode, doc))),
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(args: &[&'static str]) -> Doc {
+        cat!["foo", Doc::list("(", args.iter().map(|&arg| Doc::from(arg)), ")"), ";"]
+    }
+
+    #[test]
+    fn groups() {
+        let doc = call(&["aaaa", "bbbb"]);
+        assert_eq!(render(&doc, 20, 4), "foo(aaaa, bbbb);\n");
+        assert_eq!(render(&doc, 15, 4), "foo(\n    aaaa,\n    bbbb\n);\n");
+        assert_eq!(render(&call(&[]), 1, 4), "foo();\n");
+    }
+
+    #[test]
+    fn hard_lines() {
+        let doc = cat!["{", cat![Doc::HardLine, "a;", Doc::HardLine, "b;"].indent()];
+        let doc = cat![doc, Doc::HardLine, "}"].group();
+        assert_eq!(render(&doc, 100, 2), "{\n  a;\n  b;\n}\n");
+    }
+}
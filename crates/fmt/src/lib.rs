@@ -0,0 +1,138 @@
+#![doc = include_str!("../README.md")]
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/solar/main/assets/logo.png",
+    html_favicon_url = "https://raw.githubusercontent.com/paradigmxyz/solar/main/assets/favicon.ico"
+)]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+use solar_interface::{source_map::SourceFile, Result, Session};
+use solar_parse::{ast, Cursor, Parser};
+
+mod comments;
+mod doc;
+mod printer;
+
+/// Formatter configuration.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FormatterConfig {
+    /// The maximum line width.
+    pub line_width: usize,
+    /// The number of spaces per indentation level.
+    pub tab_width: usize,
+}
+
+impl Default for FormatterConfig {
+    fn default() -> Self {
+        Self { line_width: 120, tab_width: 4 }
+    }
+}
+
+/// Formats a Solidity source file, returning the formatted source.
+///
+/// Emits an error and returns `Err` if the file could not be parsed, or if the formatted source
+/// would differ from the original in anything other than whitespace.
+pub fn format_source_file(
+    sess: &Session,
+    file: &SourceFile,
+    config: &FormatterConfig,
+) -> Result<String> {
+    let arena = ast::Arena::new();
+    let errors = sess.dcx.err_count();
+    let mut parser = Parser::from_source_file(sess, &arena, file);
+    let source_unit = parser.parse_file().map_err(|e| e.emit())?;
+    if sess.dcx.err_count() > errors {
+        sess.dcx.has_errors()?;
+    }
+
+    let doc = printer::Printer::new(&file.src, file.start_pos.0).source_unit(&source_unit);
+    let formatted = doc::render(&doc, config.line_width, config.tab_width);
+    if !is_equivalent(&file.src, &formatted) {
+        let msg = format!("failed to format {}", file.name.display());
+        let note = "formatting would change the code; the file was left unchanged";
+        return Err(sess.dcx.err(msg).note(note).emit());
+    }
+    Ok(formatted)
+}
+
+/// Returns `true` if the sources contain the same tokens and comments, ignoring whitespace.
+///
+/// The order of the tokens is not compared, since attributes may be reordered.
+fn is_equivalent(a: &str, b: &str) -> bool {
+    fn tokens(src: &str) -> Vec<&str> {
+        let mut pos = 0;
+        let mut tokens = Vec::new();
+        for token in Cursor::new(src) {
+            let start = pos;
+            pos += token.len as usize;
+            if !printer::is_trivia(token.kind) {
+                tokens.push(&src[start..pos]);
+            }
+        }
+        tokens.sort_unstable();
+        tokens
+    }
+    tokens(a) == tokens(b) && comments::comment_texts(a) == comments::comment_texts(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solar_interface::source_map::FileName;
+
+    #[track_caller]
+    fn check(src: &str, expected: &str) {
+        let sess = Session::builder().with_test_emitter().build();
+        let formatted = sess.enter(|| {
+            let file = sess
+                .source_map()
+                .new_source_file(FileName::Custom("test".into()), || Ok(src.to_string()))
+                .unwrap();
+            format_source_file(&sess, &file, &FormatterConfig::default())
+        });
+        let formatted = formatted.unwrap();
+        assert_eq!(formatted, expected);
+
+        let sess = Session::builder().with_test_emitter().build();
+        let reformatted = sess.enter(|| {
+            let file = sess
+                .source_map()
+                .new_source_file(FileName::Custom("test".into()), || Ok(formatted.clone()))
+                .unwrap();
+            format_source_file(&sess, &file, &FormatterConfig::default())
+        });
+        assert_eq!(reformatted.unwrap(), expected, "formatting is not idempotent");
+    }
+
+    #[test]
+    fn items() {
+        check(
+            "pragma   solidity ^0.8.0;\nimport {A,B as C} from \"a.sol\";\ncontract  C is A ,B{\nuint  constant X=1;\nevent E(uint indexed a);\nfunction f(uint a)external view   returns(uint){return a+X;}\n}",
+            "pragma solidity ^0.8.0;\nimport {A, B as C} from \"a.sol\";\ncontract C is A, B {\n    uint constant X = 1;\n    event E(uint indexed a);\n    function f(uint a) external view returns (uint) {\n        return a + X;\n    }\n}\n",
+        );
+    }
+
+    #[test]
+    fn statements() {
+        check(
+            "function f(){if(a){b();}else if(c)d();else{}\nfor(uint i;i<n;i++){}\nunchecked{x-=1;}}",
+            "function f() {\n    if (a) {\n        b();\n    } else if (c) d();\n    else {}\n    for (uint i; i < n; i++) {}\n    unchecked {\n        x -= 1;\n    }\n}\n",
+        );
+    }
+
+    #[test]
+    fn comments() {
+        check(
+            "// SPDX-License-Identifier: MIT\n\n\n/// Docs.\ncontract C {\n  uint a; // trailing\n\n  // leading\n  uint /* inner */ b;\n  // last\n}\n",
+            "// SPDX-License-Identifier: MIT\n\n/// Docs.\ncontract C {\n    uint a; // trailing\n\n    // leading\n    uint /* inner */ b;\n    // last\n}\n",
+        );
+    }
+
+    #[test]
+    fn line_width() {
+        check(
+            "function f(uint aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa, uint bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb, uint c) {}",
+            "function f(\n    uint aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa,\n    uint bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb,\n    uint c\n) {}\n",
+        );
+    }
+}
@@ -0,0 +1,663 @@
+//! AST to [`Doc`] printer.
+
+use crate::{
+    comments::{Comment, Comments},
+    doc::{cat, Doc},
+};
+use solar_interface::Span;
+use solar_parse::{ast, lexer::token::RawTokenKind, Cursor};
+use std::ops::Range;
+
+/// Prints the AST of a single source file, together with its comments.
+///
+/// Simple items and statements that contain comments are printed verbatim, only re-indented.
+/// Comments in the headers of contracts, functions and control flow statements are moved before
+/// them.
+pub(crate) struct Printer<'a> {
+    src: &'a str,
+    /// The start position of the source file in the source map.
+    start: u32,
+    comments: Comments,
+}
+
+impl<'a> Printer<'a> {
+    pub(crate) fn new(src: &'a str, start: u32) -> Self {
+        Self { src, start, comments: Comments::new(src) }
+    }
+
+    pub(crate) fn source_unit(&mut self, source_unit: &ast::SourceUnit<'_>) -> Doc {
+        self.lines(
+            &source_unit.items.raw,
+            self.src.len(),
+            |_, item| item.span,
+            Self::item_header_end,
+            |this, item, _| this.item(item),
+        )
+    }
+
+    /* ----------------------------------------- Items ------------------------------------------ */
+
+    fn item(&mut self, item: &ast::Item<'_>) -> Doc {
+        let range = self.range(item.span);
+        match &item.kind {
+            ast::ItemKind::Contract(contract) => self.contract(contract, range.end),
+            ast::ItemKind::Function(function) => self.function(function, range.end),
+            ast::ItemKind::Struct(strukt) => {
+                let fields = self.braced(
+                    &strukt.fields,
+                    range.end,
+                    |_, field| field.span,
+                    |this, field| this.range(field.span).start,
+                    |this, field, _| {
+                        let range = this.range(field.span);
+                        let field = if this.comments.has_before(range.end) {
+                            this.verbatim(range)
+                        } else {
+                            this.var(field)
+                        };
+                        cat![field, ";"]
+                    },
+                );
+                cat![format!("struct {} ", strukt.name), fields]
+            }
+            ast::ItemKind::Enum(enumm) => {
+                let variants = self.braced(
+                    &enumm.variants,
+                    range.end,
+                    |_, variant| variant.span,
+                    |this, variant| this.range(variant.span).start,
+                    |_, variant, is_last| cat![variant.to_string(), if is_last { "" } else { "," }],
+                );
+                cat![format!("enum {} ", enumm.name), variants]
+            }
+            _ if self.comments.has_before(range.end) => self.verbatim(range),
+            ast::ItemKind::Pragma(_) => self.collapsed(range).into(),
+            ast::ItemKind::Import(import) => {
+                let path = self.snippet(import.path.span).to_string();
+                let doc = match &import.items {
+                    ast::ImportItems::Plain(alias) => cat![path, alias_doc(*alias)],
+                    ast::ImportItems::Glob(alias) => cat!["*", alias_doc(*alias), " from ", path],
+                    ast::ImportItems::Aliases(aliases) => {
+                        let aliases = aliases
+                            .iter()
+                            .map(|&(name, alias)| cat![name.to_string(), alias_doc(alias)]);
+                        cat![Doc::list("{", aliases, "}"), " from ", path]
+                    }
+                };
+                cat!["import ", doc, ";"]
+            }
+            ast::ItemKind::Using(using) => {
+                let list = match &using.list {
+                    ast::UsingList::Single(path) => path.to_string().into(),
+                    ast::UsingList::Multiple(paths) => {
+                        let paths = paths.iter().map(|(path, op)| match op {
+                            Some(op) => {
+                                let op = op.to_op().either(|op| op.to_str(), |op| op.to_str());
+                                format!("{path} as {op}").into()
+                            }
+                            None => path.to_string().into(),
+                        });
+                        Doc::list("{", paths, "}")
+                    }
+                };
+                let ty = using.ty.as_ref().map_or_else(|| "*".into(), |ty| self.ty(ty));
+                cat!["using ", list, " for ", ty, if using.global { " global;" } else { ";" }]
+            }
+            ast::ItemKind::Variable(var) => cat![self.var(var), ";"],
+            ast::ItemKind::Udvt(udvt) => {
+                cat![format!("type {} is ", udvt.name), self.ty(&udvt.ty), ";"]
+            }
+            ast::ItemKind::Error(error) => {
+                cat![format!("error {}", error.name), self.params(&error.parameters), ";"]
+            }
+            ast::ItemKind::Event(event) => {
+                let anonymous = if event.anonymous { " anonymous;" } else { ";" };
+                cat![format!("event {}", event.name), self.params(&event.parameters), anonymous]
+            }
+        }
+    }
+
+    /// Returns the end of the part of the item that comes before its body.
+    fn item_header_end(&self, item: &ast::Item<'_>) -> usize {
+        let range = self.range(item.span);
+        match &item.kind {
+            ast::ItemKind::Contract(_)
+            | ast::ItemKind::Struct(_)
+            | ast::ItemKind::Enum(_)
+            | ast::ItemKind::Function(ast::ItemFunction { body: Some(_), .. }) => {
+                self.find_open_brace(range.start, range.end)
+            }
+            ast::ItemKind::Function(_) => range.end,
+            _ => range.start,
+        }
+    }
+
+    fn contract(&mut self, contract: &ast::ItemContract<'_>, end: usize) -> Doc {
+        let mut header = vec![format!("{} {}", contract.kind, contract.name).into()];
+        if !contract.bases.is_empty() {
+            let bases = contract.bases.iter().map(|base| self.modifier(base));
+            let bases = Doc::join(bases, || cat![",", Doc::Line]);
+            header.push(cat![" is", cat![Doc::Line, bases].indent()].group());
+        }
+        let body = self.braced(
+            &contract.body,
+            end,
+            |_, item| item.span,
+            Self::item_header_end,
+            |this, item, _| this.item(item),
+        );
+        cat![Doc::Concat(header), " ", body]
+    }
+
+    fn function(&mut self, function: &ast::ItemFunction<'_>, end: usize) -> Doc {
+        let header = &function.header;
+        let mut name = vec![function.kind.to_str().into()];
+        let mut has_parens = true;
+        if let Some(ident) = header.name {
+            name.push(format!(" {ident}").into());
+            has_parens = !function.kind.is_modifier()
+                || !header.parameters.is_empty()
+                || self.has_paren_after(self.range(ident.span).end);
+        }
+        if has_parens {
+            name.push(self.params(&header.parameters));
+        }
+
+        let mut attrs = Vec::new();
+        if let Some(visibility) = header.visibility {
+            attrs.push(visibility.to_str().into());
+        }
+        if !header.state_mutability.is_non_payable() {
+            attrs.push(header.state_mutability.to_str().into());
+        }
+        if header.virtual_ {
+            attrs.push("virtual".into());
+        }
+        if let Some(override_) = &header.override_ {
+            attrs.push(self.override_(override_));
+        }
+        attrs.extend(header.modifiers.iter().map(|modifier| self.modifier(modifier)));
+        if !header.returns.is_empty() {
+            attrs.push(cat!["returns ", self.params(&header.returns)]);
+        }
+        let attrs = attrs.into_iter().map(|attr| cat![Doc::Line, attr]);
+        let header = cat![Doc::Concat(name), Doc::Concat(attrs.collect()).indent()].group();
+
+        match &function.body {
+            Some(body) => cat![header, " ", self.block(body, end)],
+            None => cat![header, ";"],
+        }
+    }
+
+    /// Prints a modifier invocation or an inheritance specifier, keeping empty parentheses only if
+    /// they are present in the source.
+    fn modifier(&self, modifier: &ast::Modifier<'_>) -> Doc {
+        let name = modifier.name.to_string();
+        if self.has_paren_after(self.range(modifier.name.span()).end) {
+            cat![name, self.call_args(&modifier.arguments)]
+        } else {
+            name.into()
+        }
+    }
+
+    fn override_(&self, override_: &ast::Override<'_>) -> Doc {
+        if override_.paths.is_empty() {
+            return "override".into();
+        }
+        let paths = override_.paths.iter().map(|path| path.to_string().into());
+        cat!["override", Doc::list("(", paths, ")")]
+    }
+
+    fn params(&self, params: &[ast::VariableDefinition<'_>]) -> Doc {
+        Doc::list("(", params.iter().map(|param| self.var(param)), ")")
+    }
+
+    fn var(&self, var: &ast::VariableDefinition<'_>) -> Doc {
+        let mut doc = vec![self.ty(&var.ty)];
+        if let Some(data_location) = var.data_location {
+            doc.push(format!(" {data_location}").into());
+        }
+        if let Some(visibility) = var.visibility {
+            doc.push(format!(" {visibility}").into());
+        }
+        if let Some(mutability) = var.mutability {
+            doc.push(format!(" {mutability}").into());
+        }
+        if let Some(override_) = &var.override_ {
+            doc.push(cat![" ", self.override_(override_)]);
+        }
+        if var.indexed {
+            doc.push(" indexed".into());
+        }
+        if let Some(name) = var.name {
+            doc.push(format!(" {name}").into());
+        }
+        if let Some(initializer) = &var.initializer {
+            doc.push(cat![" = ", self.expr(initializer)]);
+        }
+        Doc::Concat(doc)
+    }
+
+    /* --------------------------------------- Statements --------------------------------------- */
+
+    fn block(&mut self, block: &[ast::Stmt<'_>], end: usize) -> Doc {
+        self.braced(
+            block,
+            end,
+            |_, stmt| stmt.span,
+            Self::stmt_header_end,
+            |this, stmt, _| this.stmt(stmt),
+        )
+    }
+
+    /// Prints the block that starts after `from`, returning the end of the block.
+    fn block_after(&mut self, block: &[ast::Stmt<'_>], from: usize, to: usize) -> (Doc, usize) {
+        let end = self.find_close_brace(self.find_open_brace(from, to));
+        (self.block(block, end), end)
+    }
+
+    fn stmt(&mut self, stmt: &ast::Stmt<'_>) -> Doc {
+        let range = self.range(stmt.span);
+        match &stmt.kind {
+            ast::StmtKind::Block(block) => self.block(block, range.end),
+            ast::StmtKind::UncheckedBlock(block) => {
+                cat!["unchecked ", self.block(block, range.end)]
+            }
+            ast::StmtKind::If(cond, then, els) => {
+                let mut doc = vec![cat!["if ", self.paren(cond)], self.body(then)];
+                if let Some(els) = els {
+                    doc.push(if matches!(then.kind, ast::StmtKind::Block(_)) {
+                        " else".into()
+                    } else {
+                        cat![Doc::HardLine, "else"]
+                    });
+                    doc.push(self.body(els));
+                }
+                Doc::Concat(doc)
+            }
+            ast::StmtKind::While(cond, body) => cat!["while ", self.paren(cond), self.body(body)],
+            ast::StmtKind::DoWhile(body, cond) => {
+                cat!["do", self.body(body), " while ", self.paren(cond), ";"]
+            }
+            ast::StmtKind::For { init, cond, next, body } => {
+                let init = init.as_ref().map_or_else(|| ";".into(), |init| self.stmt(init));
+                let cond = cond
+                    .as_ref()
+                    .map_or_else(|| ";".into(), |cond| cat![" ", self.expr(cond), ";"]);
+                let next = next.as_ref().map_or(Doc::NIL, |next| cat![" ", self.expr(next)]);
+                cat!["for (", init, cond, next, ")", self.body(body)]
+            }
+            ast::StmtKind::Try(try_) => {
+                let mut doc = vec!["try ".into(), self.expr(&try_.expr)];
+                if !try_.returns.is_empty() {
+                    doc.push(cat![" returns ", self.params(&try_.returns)]);
+                }
+                let (block, mut pos) =
+                    self.block_after(&try_.block, self.range(try_.expr.span).end, range.end);
+                doc.push(cat![" ", block]);
+                for catch in try_.catch.iter() {
+                    doc.push(" catch".into());
+                    if let Some(name) = catch.name {
+                        doc.push(cat![format!(" {name}"), self.params(&catch.args)]);
+                    } else if !catch.args.is_empty() {
+                        doc.push(cat![" ", self.params(&catch.args)]);
+                    }
+                    let (block, end) = self.block_after(&catch.block, pos, range.end);
+                    doc.push(cat![" ", block]);
+                    pos = end;
+                }
+                Doc::Concat(doc)
+            }
+            ast::StmtKind::Assembly(_) | ast::StmtKind::Err(_) => self.verbatim(range),
+            _ if self.comments.has_before(range.end) => self.verbatim(range),
+            ast::StmtKind::DeclSingle(var) => cat![self.var(var), ";"],
+            ast::StmtKind::DeclMulti(vars, expr) => {
+                let vars =
+                    vars.iter().map(|var| var.as_ref().map_or(Doc::NIL, |var| self.var(var)));
+                cat![Doc::list("(", vars, ")"), " = ", self.expr(expr), ";"]
+            }
+            ast::StmtKind::Break => "break;".into(),
+            ast::StmtKind::Continue => "continue;".into(),
+            ast::StmtKind::Placeholder => "_;".into(),
+            ast::StmtKind::Emit(path, args) => {
+                cat![format!("emit {path}"), self.call_args(args), ";"]
+            }
+            ast::StmtKind::Revert(path, args) => {
+                cat![format!("revert {path}"), self.call_args(args), ";"]
+            }
+            ast::StmtKind::Return(expr) => match expr {
+                Some(expr) => cat!["return ", self.expr(expr), ";"],
+                None => "return;".into(),
+            },
+            ast::StmtKind::Expr(expr) => cat![self.expr(expr), ";"],
+        }
+    }
+
+    /// Returns the end of the part of the statement that comes before its body.
+    fn stmt_header_end(&self, stmt: &ast::Stmt<'_>) -> usize {
+        let range = self.range(stmt.span);
+        match &stmt.kind {
+            ast::StmtKind::If(_, body, _)
+            | ast::StmtKind::While(_, body)
+            | ast::StmtKind::For { body, .. } => self.range(body.span).start,
+            ast::StmtKind::Try(try_) => {
+                self.find_open_brace(self.range(try_.expr.span).end, range.end)
+            }
+            _ => range.start,
+        }
+    }
+
+    /// Prints the body of a control flow statement.
+    fn body(&mut self, stmt: &ast::Stmt<'_>) -> Doc {
+        cat![" ", self.stmt(stmt)]
+    }
+
+    /* -------------------------------------- Expressions --------------------------------------- */
+
+    fn expr(&self, expr: &ast::Expr<'_>) -> Doc {
+        match &expr.kind {
+            ast::ExprKind::Array(exprs) => {
+                Doc::list("[", exprs.iter().map(|expr| self.expr(expr)), "]")
+            }
+            ast::ExprKind::Assign(lhs, op, rhs) => {
+                let op = op.map_or("", |op| op.kind.to_str());
+                cat![self.expr(lhs), format!(" {op}= "), self.expr(rhs)]
+            }
+            ast::ExprKind::Binary(lhs, op, rhs) => {
+                let rhs = cat![Doc::Line, self.expr(rhs)].indent();
+                cat![self.expr(lhs), format!(" {}", op.kind.to_str()), rhs].group()
+            }
+            ast::ExprKind::Call(callee, args) => cat![self.expr(callee), self.call_args(args)],
+            ast::ExprKind::CallOptions(callee, options) => {
+                cat![self.expr(callee), self.named_args(options)]
+            }
+            ast::ExprKind::Delete(expr) => cat!["delete ", self.expr(expr)],
+            ast::ExprKind::Ident(ident) => ident.to_string().into(),
+            ast::ExprKind::Index(expr, kind) => {
+                let index = match kind {
+                    ast::IndexKind::Index(index) => self.opt_expr(index.as_deref()),
+                    ast::IndexKind::Range(start, end) => {
+                        cat![self.opt_expr(start.as_deref()), ":", self.opt_expr(end.as_deref())]
+                    }
+                };
+                cat![self.expr(expr), "[", index, "]"]
+            }
+            ast::ExprKind::Lit(lit, sub_denomination) => {
+                let lit = self.reindented(self.range(lit.span));
+                match sub_denomination {
+                    Some(sub_denomination) => cat![lit, format!(" {sub_denomination}")],
+                    None => lit,
+                }
+            }
+            ast::ExprKind::Member(expr, member) => cat![self.expr(expr), format!(".{member}")],
+            ast::ExprKind::New(ty) => cat!["new ", self.ty(ty)],
+            ast::ExprKind::Payable(args) => cat!["payable", self.call_args(args)],
+            ast::ExprKind::Ternary(cond, then, els) => {
+                let branches =
+                    cat![Doc::Line, "? ", self.expr(then), Doc::Line, ": ", self.expr(els)];
+                cat![self.expr(cond), branches.indent()].group()
+            }
+            ast::ExprKind::Tuple(exprs) => {
+                let exprs = exprs.iter().map(|expr| self.opt_expr(expr.as_deref()));
+                Doc::list("(", exprs, ")")
+            }
+            ast::ExprKind::TypeCall(ty) => cat!["type(", self.ty(ty), ")"],
+            ast::ExprKind::Type(ty) => self.ty(ty),
+            ast::ExprKind::Unary(op, expr) => {
+                let op = op.kind;
+                if op.is_postfix() {
+                    return cat![self.expr(expr), op.to_str()];
+                }
+                // Don't turn `- -x` into `--x`.
+                let space = matches!(op, ast::UnOpKind::Neg | ast::UnOpKind::PreDec)
+                    && matches!(
+                        &expr.kind,
+                        ast::ExprKind::Unary(inner, _)
+                            if matches!(inner.kind, ast::UnOpKind::Neg | ast::UnOpKind::PreDec)
+                    );
+                cat![op.to_str(), if space { " " } else { "" }, self.expr(expr)]
+            }
+        }
+    }
+
+    fn opt_expr(&self, expr: Option<&ast::Expr<'_>>) -> Doc {
+        expr.map_or(Doc::NIL, |expr| self.expr(expr))
+    }
+
+    fn call_args(&self, args: &ast::CallArgs<'_>) -> Doc {
+        match args {
+            ast::CallArgs::Unnamed(exprs) => {
+                Doc::list("(", exprs.iter().map(|expr| self.expr(expr)), ")")
+            }
+            ast::CallArgs::Named(args) => cat!["(", self.named_args(args), ")"],
+        }
+    }
+
+    fn named_args(&self, args: &[ast::NamedArg<'_>]) -> Doc {
+        let args = args.iter().map(|arg| cat![format!("{}: ", arg.name), self.expr(&arg.value)]);
+        Doc::list("{", args, "}")
+    }
+
+    /// Prints a parenthesized condition.
+    fn paren(&self, expr: &ast::Expr<'_>) -> Doc {
+        cat!["(", cat![Doc::SoftLine, self.expr(expr)].indent(), Doc::SoftLine, ")"].group()
+    }
+
+    fn ty(&self, ty: &ast::Type<'_>) -> Doc {
+        match &ty.kind {
+            ast::TypeKind::Elementary(_) => self.collapsed(self.range(ty.span)).into(),
+            ast::TypeKind::Array(array) => {
+                cat![self.ty(&array.element), "[", self.opt_expr(array.size.as_deref()), "]"]
+            }
+            ast::TypeKind::Function(function) => {
+                let mut doc = vec!["function".into(), self.params(&function.parameters)];
+                if let Some(visibility) = function.visibility {
+                    doc.push(format!(" {visibility}").into());
+                }
+                if !function.state_mutability.is_non_payable() {
+                    doc.push(format!(" {}", function.state_mutability).into());
+                }
+                if !function.returns.is_empty() {
+                    doc.push(cat![" returns ", self.params(&function.returns)]);
+                }
+                Doc::Concat(doc)
+            }
+            ast::TypeKind::Mapping(mapping) => {
+                let name =
+                    |name: Option<ast::Ident>| name.map_or(String::new(), |n| format!(" {n}"));
+                cat![
+                    "mapping(",
+                    self.ty(&mapping.key),
+                    name(mapping.key_name),
+                    " => ",
+                    self.ty(&mapping.value),
+                    name(mapping.value_name),
+                    ")",
+                ]
+            }
+            ast::TypeKind::Custom(path) => path.to_string().into(),
+        }
+    }
+
+    /* ----------------------------------------- Lines ------------------------------------------ */
+
+    /// Prints `elems` between braces, one per line, or `{}` if there is nothing to print.
+    fn braced<T>(
+        &mut self,
+        elems: &[T],
+        end: usize,
+        span: impl Fn(&Self, &T) -> Span,
+        header_end: impl Fn(&Self, &T) -> usize,
+        print: impl FnMut(&mut Self, &T, bool) -> Doc,
+    ) -> Doc {
+        if elems.is_empty() && !self.comments.has_before(end) {
+            return "{}".into();
+        }
+        let lines = self.lines(elems, end, span, header_end, print);
+        cat!["{", cat![Doc::HardLine, lines].indent(), Doc::HardLine, "}"]
+    }
+
+    /// Prints `elems` one per line, together with the comments before `end`.
+    ///
+    /// Comments before an element are printed on their own lines, and comments that follow an
+    /// element on the same line are kept there. Single blank lines between elements are preserved.
+    fn lines<T>(
+        &mut self,
+        elems: &[T],
+        end: usize,
+        span: impl Fn(&Self, &T) -> Span,
+        header_end: impl Fn(&Self, &T) -> usize,
+        mut print: impl FnMut(&mut Self, &T, bool) -> Doc,
+    ) -> Doc {
+        let mut out = Vec::new();
+        let mut prev_end = None;
+        for (i, elem) in elems.iter().enumerate() {
+            let range = self.range(span(self, elem));
+            let header_end = header_end(self, elem);
+            while let Some(comment) = self
+                .comments
+                .next_before(range.start)
+                .or_else(|| self.comments.next_before(header_end))
+            {
+                self.newline(&mut out, prev_end, comment.range.start);
+                out.push(self.comment(&comment));
+                prev_end = Some(comment.range.end);
+            }
+
+            self.newline(&mut out, prev_end, range.start);
+            out.push(print(self, elem, i == elems.len() - 1));
+            prev_end = Some(range.end);
+
+            if let Some(comment) = self.comments.peek() {
+                let is_trailing = comment.range.start >= range.end
+                    && comment.range.end <= end
+                    && !self.src[range.end..comment.range.start].contains('\n');
+                if is_trailing {
+                    let comment = self.comments.next_before(end).unwrap();
+                    out.push(cat![" ", self.comment(&comment)]);
+                    prev_end = Some(comment.range.end);
+                }
+            }
+        }
+        while let Some(comment) = self.comments.next_before(end) {
+            self.newline(&mut out, prev_end, comment.range.start);
+            out.push(self.comment(&comment));
+            prev_end = Some(comment.range.end);
+        }
+        Doc::Concat(out)
+    }
+
+    /// Pushes a newline between the previous line, if any, and the one starting at `next`,
+    /// preserving a single blank line.
+    fn newline(&self, out: &mut Vec<Doc>, prev_end: Option<usize>, next: usize) {
+        let Some(prev_end) = prev_end else { return };
+        out.push(Doc::HardLine);
+        if next > prev_end && self.src[prev_end..next].matches('\n').count() > 1 {
+            out.push(Doc::HardLine);
+        }
+    }
+
+    fn comment(&self, comment: &Comment) -> Doc {
+        self.reindented(comment.range.clone())
+    }
+
+    /* ---------------------------------------- Source ------------------------------------------ */
+
+    /// Returns the range of the span, relative to the start of the source file.
+    fn range(&self, span: Span) -> Range<usize> {
+        (span.lo().0 - self.start) as usize..(span.hi().0 - self.start) as usize
+    }
+
+    fn snippet(&self, span: Span) -> &'a str {
+        &self.src[self.range(span)]
+    }
+
+    /// Returns the source with consecutive whitespace collapsed into a single space.
+    fn collapsed(&self, range: Range<usize>) -> String {
+        self.src[range].split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    /// Prints the source as is, consuming the comments in it.
+    fn verbatim(&mut self, range: Range<usize>) -> Doc {
+        self.comments.skip_before(range.end);
+        self.reindented(range)
+    }
+
+    /// Prints the source as is, only removing the indentation of the line that it starts on from
+    /// the following lines.
+    fn reindented(&self, range: Range<usize>) -> Doc {
+        let line_start = self.src[..range.start].rfind('\n').map_or(0, |i| i + 1);
+        let prefix = &self.src[line_start..range.start];
+        let indent = prefix.len() - prefix.trim_start_matches([' ', '\t']).len();
+        let lines = self.src[range].lines().enumerate().map(|(i, line)| {
+            let line = line.trim_end();
+            if i == 0 {
+                return line.to_string().into();
+            }
+            let stripped = line.trim_start_matches([' ', '\t']);
+            let ws = line.len() - stripped.len();
+            line[ws.min(indent)..].to_string().into()
+        });
+        Doc::join(lines, || Doc::HardLine)
+    }
+
+    /// Returns `true` if the next token after `pos` is an opening parenthesis.
+    fn has_paren_after(&self, pos: usize) -> bool {
+        Cursor::new(&self.src[pos..])
+            .find(|token| !is_trivia(token.kind))
+            .is_some_and(|token| token.kind == RawTokenKind::OpenParen)
+    }
+
+    /// Returns the position of the first `{` in `from..to` that is not nested in parentheses or
+    /// brackets, or `to` if there is none.
+    fn find_open_brace(&self, from: usize, to: usize) -> usize {
+        let mut pos = from;
+        let mut depth = 0usize;
+        for token in Cursor::new(&self.src[from..to]) {
+            match token.kind {
+                RawTokenKind::OpenParen | RawTokenKind::OpenBracket => depth += 1,
+                RawTokenKind::CloseParen | RawTokenKind::CloseBracket => {
+                    depth = depth.saturating_sub(1)
+                }
+                RawTokenKind::OpenBrace if depth == 0 => return pos,
+                _ => {}
+            }
+            pos += token.len as usize;
+        }
+        to
+    }
+
+    /// Returns the end of the `{ ... }` block starting at `open`.
+    fn find_close_brace(&self, open: usize) -> usize {
+        let mut pos = open;
+        let mut depth = 0usize;
+        for token in Cursor::new(&self.src[open..]) {
+            pos += token.len as usize;
+            match token.kind {
+                RawTokenKind::OpenBrace => depth += 1,
+                RawTokenKind::CloseBrace => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 {
+                        return pos;
+                    }
+                }
+                _ => {}
+            }
+        }
+        pos
+    }
+}
+
+fn alias_doc(alias: Option<ast::Ident>) -> Doc {
+    alias.map_or(Doc::NIL, |alias| format!(" as {alias}").into())
+}
+
+/// Returns `true` if the token is whitespace or a comment.
+pub(crate) fn is_trivia(kind: RawTokenKind) -> bool {
+    matches!(
+        kind,
+        RawTokenKind::Whitespace
+            | RawTokenKind::LineComment { .. }
+            | RawTokenKind::BlockComment { .. }
+    )
+}
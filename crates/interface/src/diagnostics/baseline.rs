@@ -0,0 +1,30 @@
+//! Baselines of known warnings, used to adopt new lints incrementally.
+
+/// Identifies a warning in a [`Baseline`].
+///
+/// The location of the warning inside of the file is not included, so that unrelated changes to
+/// the file do not invalidate the baseline.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fingerprint {
+    /// The name of the file of the primary span, or the empty string if there is none.
+    pub file: String,
+    /// The diagnostic code, if any.
+    #[cfg_attr(feature = "json", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub code: Option<String>,
+    /// The main message.
+    pub message: String,
+}
+
+/// A list of known warnings that are not emitted.
+///
+/// Each fingerprint suppresses a single matching warning, so new occurrences of a warning in the
+/// same file are still reported.
+///
+/// See [`DiagCtxt::set_baseline`](super::DiagCtxt::set_baseline).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct Baseline {
+    /// The fingerprints of the warnings, sorted.
+    pub warnings: Vec<Fingerprint>,
+}
@@ -1,12 +1,20 @@
 use super::{
-    emitter::HumanEmitter, BugAbort, Diagnostic, DiagnosticBuilder, DiagnosticMessage, DynEmitter,
-    EmissionGuarantee, EmittedDiagnostics, ErrorGuaranteed, FatalAbort, HumanBufferEmitter, Level,
-    SilentEmitter,
+    emitter::HumanEmitter, Baseline, BugAbort, Diagnostic, DiagnosticBuilder, DiagnosticId,
+    DiagnosticMessage, DynEmitter, EmissionGuarantee, EmittedDiagnostics, ErrorGuaranteed,
+    FatalAbort, Fingerprint, HumanBufferEmitter, Level, Lint, SilentEmitter,
 };
-use crate::{Result, SourceMap};
+use crate::{Result, SourceMap, Span};
 use anstream::ColorChoice;
-use solar_data_structures::{map::FxHashSet, sync::Lock};
-use std::{borrow::Cow, hash::BuildHasher, num::NonZeroUsize, sync::Arc};
+use solar_data_structures::{
+    map::{FxHashMap, FxHashSet},
+    sync::Lock,
+};
+use std::{borrow::Cow, cell::RefCell, hash::BuildHasher, num::NonZeroUsize, sync::Arc};
+
+thread_local! {
+    /// Diagnostics buffered on the current thread by [`DiagCtxt::buffer_diagnostics`].
+    static BUFFERED: RefCell<Option<Vec<Diagnostic>>> = const { RefCell::new(None) };
+}
 
 /// Flags that control the behaviour of a [`DiagCtxt`].
 #[derive(Clone, Copy)]
@@ -16,7 +24,15 @@ pub struct DiagCtxtFlags {
     /// If Some, the Nth error-level diagnostic is upgraded to bug-level.
     pub treat_err_as_bug: Option<NonZeroUsize>,
     /// If true, identical diagnostics are reported only once.
+    ///
+    /// Diagnostics are identical if they have the same level, message, code, and primary span.
     pub deduplicate_diagnostics: bool,
+    /// If Some, diagnostics are no longer emitted after this many errors, except for bugs and
+    /// fatal errors.
+    pub error_limit: Option<NonZeroUsize>,
+    /// If true, diagnostics with a registered code get a note linking to the relevant section of
+    /// the Solidity documentation.
+    pub docs_links: bool,
     /// Track where errors are created. Enabled with `-Ztrack-diagnostics`, and by default in debug
     /// builds.
     pub track_diagnostics: bool,
@@ -28,6 +44,8 @@ impl Default for DiagCtxtFlags {
             can_emit_warnings: true,
             treat_err_as_bug: None,
             deduplicate_diagnostics: true,
+            error_limit: None,
+            docs_links: false,
             track_diagnostics: cfg!(debug_assertions),
         }
     }
@@ -45,6 +63,19 @@ struct DiagCtxtInner {
 
     flags: DiagCtxtFlags,
 
+    /// The levels of warnings set with [`DiagCtxt::set_lint_level`].
+    lint_levels: FxHashMap<Lint, Level>,
+    /// The regions of source code in which warnings are suppressed, added with
+    /// [`DiagCtxt::suppress`].
+    suppressions: Vec<(Lint, Span)>,
+    /// The number of occurrences of each warning in the baseline set with
+    /// [`DiagCtxt::set_baseline`].
+    baseline: FxHashMap<Fingerprint, usize>,
+    /// The number of occurrences of each baselined warning that have been suppressed so far.
+    baselined: FxHashMap<Fingerprint, usize>,
+    /// The warnings seen since [`DiagCtxt::record_baseline`] was called, if recording.
+    recorded_baseline: Option<Vec<Fingerprint>>,
+
     /// The number of errors that have been emitted, including duplicates.
     ///
     /// This is not necessarily the count that's reported to the user once
@@ -54,6 +85,8 @@ struct DiagCtxtInner {
     warn_count: usize,
     /// The warning count, used for a recap upon finishing
     deduplicated_warn_count: usize,
+    /// The number of errors that were not emitted because the error limit was reached.
+    suppressed_err_count: usize,
 
     /// This set contains a hash of every diagnostic that has been emitted by this `DiagCtxt`.
     /// These hashes are used to avoid emitting the same error twice.
@@ -67,10 +100,16 @@ impl DiagCtxt {
             inner: Lock::new(DiagCtxtInner {
                 emitter,
                 flags: DiagCtxtFlags::default(),
+                lint_levels: FxHashMap::default(),
+                suppressions: Vec::new(),
+                baseline: FxHashMap::default(),
+                baselined: FxHashMap::default(),
+                recorded_baseline: None,
                 err_count: 0,
                 deduplicated_err_count: 0,
                 warn_count: 0,
                 deduplicated_warn_count: 0,
+                suppressed_err_count: 0,
                 emitted_diagnostics: FxHashSet::default(),
             }),
         }
@@ -130,6 +169,70 @@ impl DiagCtxt {
         self
     }
 
+    /// Sets the level of the warnings in `lint`.
+    ///
+    /// `level` must be one of [`Level::Allow`], which silences the warnings, [`Level::Warning`], or
+    /// [`Level::Error`], which promotes them to errors. The level of a specific warning code takes
+    /// precedence over the level of [`Lint::Warnings`].
+    pub fn set_lint_level(&mut self, lint: Lint, level: Level) {
+        assert!(
+            matches!(level, Level::Allow | Level::Warning | Level::Error),
+            "invalid lint level: {level:?}"
+        );
+        self.inner.get_mut().lint_levels.insert(lint, level);
+    }
+
+    /// Returns the level at which the warnings with the given code are emitted, given the level
+    /// they have when it is not set with [`Self::set_lint_level`].
+    ///
+    /// Lints that are allowed by default are only enabled by setting the level of their code, not
+    /// by setting the level of [`Lint::Warnings`]. This is used to skip the lint passes whose
+    /// warnings would not be emitted.
+    pub fn lint_level(&self, code: &DiagnosticId, default: Level) -> Level {
+        let inner = self.inner.lock();
+        let level = match inner.lint_levels.get(&Lint::Code(code.clone())) {
+            Some(&level) => level,
+            None if default == Level::Allow => Level::Allow,
+            None => inner.lint_levels.get(&Lint::Warnings).copied().unwrap_or(default),
+        };
+        if level == Level::Warning && !inner.flags.can_emit_warnings {
+            return Level::Allow;
+        }
+        level
+    }
+
+    /// Suppresses the warnings in `lint` whose primary span starts inside of `span`.
+    ///
+    /// This is used to implement the `solar-disable` comments.
+    pub fn suppress(&self, lint: Lint, span: Span) {
+        self.inner.lock().suppressions.push((lint, span));
+    }
+
+    /// Suppresses the warnings in `baseline`.
+    ///
+    /// Warnings are matched by [`Fingerprint`] before lint levels are applied, so baselined
+    /// warnings are suppressed even if they are promoted to errors.
+    pub fn set_baseline(&mut self, baseline: Baseline) {
+        let inner = self.inner.get_mut();
+        inner.baseline.clear();
+        inner.baselined.clear();
+        for fingerprint in baseline.warnings {
+            *inner.baseline.entry(fingerprint).or_default() += 1;
+        }
+    }
+
+    /// Starts recording the emitted warnings, to be retrieved with [`Self::recorded_baseline`].
+    pub fn record_baseline(&mut self) {
+        self.inner.get_mut().recorded_baseline = Some(Vec::new());
+    }
+
+    /// Returns the baseline of the warnings emitted since [`Self::record_baseline`] was called.
+    pub fn recorded_baseline(&self) -> Option<Baseline> {
+        let mut warnings = self.inner.lock().recorded_baseline.clone()?;
+        warnings.sort();
+        Some(Baseline { warnings })
+    }
+
     /// Disables emitting warnings.
     pub fn disable_warnings(self) -> Self {
         self.set_flags(|f| f.can_emit_warnings = false)
@@ -154,14 +257,62 @@ impl DiagCtxt {
         &self,
         diagnostic: &mut Diagnostic,
     ) -> Result<(), ErrorGuaranteed> {
+        if !matches!(diagnostic.level, Level::Bug | Level::Fatal) {
+            let buffered = BUFFERED.with_borrow_mut(|buffer| {
+                buffer.as_mut().map(|buffer| buffer.push(diagnostic.clone())).is_some()
+            });
+            if buffered {
+                return if self.inner.lock().will_be_error(diagnostic) {
+                    Err(ErrorGuaranteed::new_unchecked())
+                } else {
+                    Ok(())
+                };
+            }
+        }
         self.inner.lock().emit_diagnostic_without_consuming(diagnostic)
     }
 
+    /// Calls `f`, buffering all the diagnostics emitted on the current thread instead of emitting
+    /// them. Bugs and fatal errors are always emitted immediately.
+    ///
+    /// The buffered diagnostics are returned in emission order, and must be emitted later with
+    /// [`emit_buffered`](Self::emit_buffered). Until then, they are not reflected in
+    /// [`err_count`](Self::err_count) and [`has_errors`](Self::has_errors), but emitting them
+    /// already returns `Err` for the warnings that lint levels promote to errors.
+    ///
+    /// This is used to emit diagnostics in a deterministic order when running work in parallel.
+    pub fn buffer_diagnostics<R>(&self, f: impl FnOnce() -> R) -> (R, Vec<Diagnostic>) {
+        struct Guard(Option<Vec<Diagnostic>>);
+        impl Drop for Guard {
+            fn drop(&mut self) {
+                BUFFERED.set(self.0.take());
+            }
+        }
+
+        let guard = Guard(BUFFERED.replace(Some(Vec::new())));
+        let r = f();
+        let buffered = BUFFERED.take().unwrap_or_default();
+        drop(guard);
+        (r, buffered)
+    }
+
+    /// Emits diagnostics previously buffered with [`buffer_diagnostics`](Self::buffer_diagnostics).
+    pub fn emit_buffered(&self, diagnostics: Vec<Diagnostic>) {
+        for diagnostic in diagnostics {
+            let _ = self.emit_diagnostic(diagnostic);
+        }
+    }
+
     /// Returns the number of errors that have been emitted, including duplicates.
     pub fn err_count(&self) -> usize {
         self.inner.lock().err_count
     }
 
+    /// Returns the number of warnings that have been emitted, including duplicates.
+    pub fn warn_count(&self) -> usize {
+        self.inner.lock().warn_count
+    }
+
     /// Returns `Err` if any errors have been emitted.
     pub fn has_errors(&self) -> Result<(), ErrorGuaranteed> {
         if self.inner.lock().has_errors() {
@@ -194,6 +345,25 @@ impl DiagCtxt {
     pub fn print_error_count(&self) -> Result {
         self.inner.lock().print_error_count()
     }
+
+    /// Resets the error and warning counts, and forgets which diagnostics have been emitted and
+    /// which warnings have been suppressed.
+    ///
+    /// This is used to reuse the same context for multiple compilations.
+    pub fn reset(&self) {
+        let mut inner = self.inner.lock();
+        inner.err_count = 0;
+        inner.deduplicated_err_count = 0;
+        inner.warn_count = 0;
+        inner.deduplicated_warn_count = 0;
+        inner.suppressed_err_count = 0;
+        inner.emitted_diagnostics.clear();
+        inner.suppressions.clear();
+        inner.baselined.clear();
+        if let Some(recorded) = &mut inner.recorded_baseline {
+            recorded.clear();
+        }
+    }
 }
 
 /// Diagnostic constructors.
@@ -258,6 +428,15 @@ impl DiagCtxtInner {
         &mut self,
         diagnostic: &mut Diagnostic,
     ) -> Result<(), ErrorGuaranteed> {
+        self.map_original_spans(diagnostic);
+
+        if diagnostic.level == Level::Warning {
+            if self.is_suppressed(diagnostic) || self.is_baselined(diagnostic) {
+                return Ok(());
+            }
+            self.apply_lint_level(diagnostic);
+        }
+
         if diagnostic.level == Level::Warning && !self.flags.can_emit_warnings {
             return Ok(());
         }
@@ -270,8 +449,18 @@ impl DiagCtxtInner {
             diagnostic.level = Level::Bug;
         }
 
-        let already_emitted = self.insert_diagnostic(diagnostic);
-        if !(self.flags.deduplicate_diagnostics && already_emitted) {
+        let already_emitted = self.insert_diagnostic(&diagnostic.dedup_key());
+        let duplicate = self.flags.deduplicate_diagnostics && already_emitted;
+        if !duplicate
+            && self.error_limit_reached()
+            && !matches!(diagnostic.level, Level::Bug | Level::Fatal)
+        {
+            if diagnostic.is_error() {
+                self.suppressed_err_count += 1;
+            }
+            return self.count(diagnostic);
+        }
+        if !duplicate {
             // Remove duplicate `Once*` subdiagnostics.
             diagnostic.children.retain(|sub| {
                 if !matches!(sub.level, Level::OnceNote | Level::OnceHelp) {
@@ -287,6 +476,12 @@ impl DiagCtxtInner {
             //     );
             // }
 
+            if self.flags.docs_links {
+                if let Some(url) = diagnostic.code.as_ref().and_then(DiagnosticId::docs_url) {
+                    diagnostic.note(format!("see: {url}"));
+                }
+            }
+
             self.emitter.emit_diagnostic(diagnostic);
             if diagnostic.is_error() {
                 self.deduplicated_err_count += 1;
@@ -295,6 +490,11 @@ impl DiagCtxtInner {
             }
         }
 
+        self.count(diagnostic)
+    }
+
+    /// Bumps the error or warning count for the given diagnostic.
+    fn count(&mut self, diagnostic: &Diagnostic) -> Result<(), ErrorGuaranteed> {
         if diagnostic.is_error() {
             self.bump_err_count();
             Err(ErrorGuaranteed::new_unchecked())
@@ -322,7 +522,14 @@ impl DiagCtxtInner {
             count => Cow::from(format!("aborting due to {count} previous errors")),
         };
 
-        match (self.deduplicated_err_count, self.deduplicated_warn_count) {
+        if let (n @ 1.., Some(limit)) = (self.suppressed_err_count, self.flags.error_limit) {
+            let errors = if n == 1 { "error was" } else { "errors were" };
+            let msg = format!("too many errors; {n} more {errors} not emitted (limit: {limit})");
+            self.emitter.emit_diagnostic(&Diagnostic::new(Level::Note, msg));
+        }
+
+        let err_count = self.deduplicated_err_count + self.suppressed_err_count;
+        match (err_count, self.deduplicated_warn_count) {
             (0, 0) => Ok(()),
             (0, w) => {
                 self.emitter.emit_diagnostic(&Diagnostic::new(Level::Warning, warnings(w)));
@@ -343,6 +550,10 @@ impl DiagCtxtInner {
         !self.emitted_diagnostics.insert(hash)
     }
 
+    fn error_limit_reached(&self) -> bool {
+        self.flags.error_limit.is_some_and(|limit| self.deduplicated_err_count >= limit.get())
+    }
+
     fn treat_err_as_bug(&self) -> bool {
         self.flags.treat_err_as_bug.is_some_and(|c| self.err_count >= c.get())
     }
@@ -356,6 +567,109 @@ impl DiagCtxtInner {
         self.warn_count += 1;
     }
 
+    /// Returns `true` if the given warning is suppressed by a `solar-disable` comment.
+    fn is_suppressed(&self, diagnostic: &Diagnostic) -> bool {
+        if self.suppressions.is_empty() {
+            return false;
+        }
+        let Some(span) = diagnostic.span.primary_span().filter(|span| !span.is_dummy()) else {
+            return false;
+        };
+        self.suppressions.iter().any(|(lint, region)| {
+            let matches = match lint {
+                Lint::Warnings => true,
+                Lint::Code(code) => diagnostic.code.as_ref() == Some(code),
+            };
+            matches && region.contains(span.shrink_to_lo())
+        })
+    }
+
+    /// Returns `true` if the given warning is in the baseline, and records it if requested.
+    fn is_baselined(&mut self, diagnostic: &Diagnostic) -> bool {
+        if self.baseline.is_empty() && self.recorded_baseline.is_none() {
+            return false;
+        }
+        let fingerprint = self.fingerprint(diagnostic);
+        if let Some(recorded) = &mut self.recorded_baseline {
+            recorded.push(fingerprint.clone());
+        }
+        let Some(&max) = self.baseline.get(&fingerprint) else { return false };
+        let count = self.baselined.entry(fingerprint).or_default();
+        if *count >= max {
+            return false;
+        }
+        *count += 1;
+        true
+    }
+
+    fn fingerprint(&self, diagnostic: &Diagnostic) -> Fingerprint {
+        let span = diagnostic.span.primary_span().filter(|span| !span.is_dummy());
+        let file = match (self.emitter.source_map(), span) {
+            (Some(sm), Some(span)) => {
+                let file = sm.lookup_char_pos(span.lo()).file;
+                sm.filename_for_diagnostics(&file.name).to_string()
+            }
+            _ => String::new(),
+        };
+        Fingerprint {
+            file,
+            code: diagnostic.code.as_ref().map(DiagnosticId::as_string),
+            message: diagnostic.label().into_owned(),
+        }
+    }
+
+    /// Changes the level of the given warning according to the lint levels.
+    /// Points the spans of the given diagnostic at the source code that generated code was
+    /// generated from.
+    fn map_original_spans(&self, diagnostic: &mut Diagnostic) {
+        if let Some(source_map) = self.emitter.source_map() {
+            let mut original_span = |span| source_map.original_span(span);
+            diagnostic.span.map_spans(&mut original_span);
+            for child in &mut diagnostic.children {
+                child.span.map_spans(&mut original_span);
+            }
+        }
+    }
+
+    /// Returns `true` if emitting the given diagnostic is guaranteed to emit an error.
+    ///
+    /// Buffered diagnostics only go through suppressions, the baseline and lint levels when they
+    /// are emitted, so this is used to know whether they will be errors beforehand. Warnings in
+    /// the baseline may not be emitted at all, so they are never guaranteed to be errors.
+    fn will_be_error(&self, diagnostic: &Diagnostic) -> bool {
+        if diagnostic.level != Level::Warning {
+            return diagnostic.is_error();
+        }
+        let mut diagnostic = diagnostic.clone();
+        self.map_original_spans(&mut diagnostic);
+        if self.is_suppressed(&diagnostic) {
+            return false;
+        }
+        if !self.baseline.is_empty() && self.baseline.contains_key(&self.fingerprint(&diagnostic)) {
+            return false;
+        }
+        matches!(self.lint_level_of(&diagnostic), Some((_, Level::Error)))
+    }
+
+    fn apply_lint_level(&self, diagnostic: &mut Diagnostic) {
+        let Some((lint, level)) = self.lint_level_of(diagnostic) else { return };
+        if level == Level::Error {
+            diagnostic.note(format!("requested on the command line with `-D {lint}`"));
+        }
+        diagnostic.level = level;
+    }
+
+    /// Returns the lint level set for the given warning, and the lint that it was set for.
+    fn lint_level_of(&self, diagnostic: &Diagnostic) -> Option<(&Lint, Level)> {
+        let code_level = diagnostic.code.as_ref().and_then(|code| {
+            let lint = Lint::Code(code.clone());
+            self.lint_levels.get_key_value(&lint)
+        });
+        let (lint, &level) =
+            code_level.or_else(|| self.lint_levels.get_key_value(&Lint::Warnings))?;
+        Some((lint, level))
+    }
+
     fn has_errors(&self) -> bool {
         self.err_count > 0
     }
@@ -371,3 +685,163 @@ impl DiagCtxtInner {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buffer_diagnostics() {
+        let dcx = DiagCtxt::with_buffer_emitter(None, ColorChoice::Never)
+            .set_flags(|flags| flags.track_diagnostics = false);
+        let ((), buffered) = dcx.buffer_diagnostics(|| {
+            let _ = dcx.err("first").emit();
+            dcx.warn("second").emit();
+        });
+        assert_eq!(buffered.len(), 2);
+        assert_eq!(dcx.err_count(), 0);
+        assert_eq!(dcx.emitted_diagnostics().unwrap().to_string(), "");
+
+        let _ = dcx.err("zeroth").emit();
+        dcx.emit_buffered(buffered);
+        assert_eq!(dcx.err_count(), 2);
+        let emitted = dcx.emitted_diagnostics().unwrap().to_string();
+        let positions = ["zeroth", "first", "second"].map(|msg| emitted.find(msg).unwrap());
+        assert!(positions.is_sorted(), "{emitted}");
+    }
+
+    #[test]
+    fn buffer_denied_warnings() {
+        let mut dcx = DiagCtxt::with_buffer_emitter(None, ColorChoice::Never)
+            .set_flags(|flags| flags.track_diagnostics = false);
+        dcx.set_lint_level(Lint::Warnings, Level::Error);
+        dcx.set_lint_level(Lint::Code(crate::error_code!(1111)), Level::Allow);
+        let ((denied, allowed), buffered) = dcx.buffer_diagnostics(|| {
+            let denied = dcx.emit_diagnostic(Diagnostic::new(Level::Warning, "denied"));
+            let mut allowed = Diagnostic::new(Level::Warning, "allowed");
+            allowed.code(crate::error_code!(1111));
+            (denied, dcx.emit_diagnostic(allowed))
+        });
+        // The results reflect the lint levels even though they are only applied on emission.
+        assert!(denied.is_err());
+        assert!(allowed.is_ok());
+
+        dcx.emit_buffered(buffered);
+        assert_eq!(dcx.err_count(), 1);
+        let emitted = dcx.emitted_diagnostics().unwrap().to_string();
+        assert!(emitted.contains("error: denied"), "{emitted}");
+        assert!(!emitted.contains("allowed"), "{emitted}");
+    }
+
+    #[test]
+    fn deduplicate() {
+        let dcx = DiagCtxt::with_buffer_emitter(None, ColorChoice::Never)
+            .set_flags(|flags| flags.track_diagnostics = false);
+        let _ = dcx.err("duplicate").note("first").emit();
+        let _ = dcx.err("duplicate").note("second").emit();
+        dcx.warn("duplicate").emit();
+        assert_eq!(dcx.err_count(), 2);
+        let emitted = dcx.emitted_diagnostics().unwrap().to_string();
+        assert_eq!(emitted.matches("error: duplicate").count(), 1, "{emitted}");
+        assert_eq!(emitted.matches("warning: duplicate").count(), 1, "{emitted}");
+    }
+
+    #[test]
+    fn error_limit() {
+        let dcx = DiagCtxt::with_buffer_emitter(None, ColorChoice::Never).set_flags(|flags| {
+            flags.track_diagnostics = false;
+            flags.error_limit = NonZeroUsize::new(2);
+        });
+        for msg in ["first", "second", "third", "fourth"] {
+            let _ = dcx.err(msg).emit();
+        }
+        dcx.warn("warning").emit();
+        assert_eq!(dcx.err_count(), 4);
+        let _ = dcx.print_error_count();
+        let emitted = dcx.emitted_diagnostics().unwrap().to_string();
+        assert!(emitted.contains("first") && emitted.contains("second"), "{emitted}");
+        assert!(!emitted.contains("third") && !emitted.contains("warning:"), "{emitted}");
+        assert!(emitted.contains("too many errors; 2 more errors were not emitted"), "{emitted}");
+        assert!(emitted.contains("aborting due to 4 previous errors"), "{emitted}");
+    }
+
+    #[test]
+    fn docs_links() {
+        let emit = |docs_links| {
+            let dcx = DiagCtxt::with_buffer_emitter(None, ColorChoice::Never).set_flags(|flags| {
+                flags.track_diagnostics = false;
+                flags.docs_links = docs_links;
+            });
+            let _ = dcx.err("undeclared").code(crate::error_code!(7576)).emit();
+            let _ = dcx.err("unregistered").code(crate::error_code!(1111)).emit();
+            dcx.emitted_diagnostics().unwrap().to_string()
+        };
+
+        let emitted = emit(true);
+        let url = crate::error_code!(7576).docs_url().unwrap();
+        assert!(emitted.contains(&format!("note: see: {url}")), "{emitted}");
+        assert_eq!(emitted.matches("see: ").count(), 1, "{emitted}");
+
+        let emitted = emit(false);
+        assert!(!emitted.contains("see: "), "{emitted}");
+    }
+
+    #[test]
+    fn baseline() {
+        let warn = |dcx: &DiagCtxt, msg| dcx.warn(msg).code(crate::error_code!(1111)).emit();
+
+        let mut dcx = DiagCtxt::with_buffer_emitter(None, ColorChoice::Never)
+            .set_flags(|flags| flags.track_diagnostics = false);
+        dcx.record_baseline();
+        warn(&dcx, "old");
+        warn(&dcx, "old");
+        let baseline = dcx.recorded_baseline().unwrap();
+        assert_eq!(baseline.warnings.len(), 2);
+        assert_eq!(baseline.warnings[0].code.as_deref(), Some("1111"));
+        assert_eq!(baseline.warnings[0].message, "old");
+
+        let mut dcx = DiagCtxt::with_buffer_emitter(None, ColorChoice::Never).set_flags(|flags| {
+            flags.track_diagnostics = false;
+            flags.deduplicate_diagnostics = false;
+        });
+        dcx.set_lint_level(Lint::Warnings, Level::Error);
+        dcx.set_baseline(baseline);
+        warn(&dcx, "old");
+        warn(&dcx, "new");
+        warn(&dcx, "old");
+        warn(&dcx, "old");
+        assert_eq!(dcx.err_count(), 2);
+        let emitted = dcx.emitted_diagnostics().unwrap().to_string();
+        assert_eq!(emitted.matches("error[1111]: old").count(), 1, "{emitted}");
+        assert_eq!(emitted.matches("error[1111]: new").count(), 1, "{emitted}");
+    }
+
+    #[test]
+    fn lint_levels() {
+        let mut dcx = DiagCtxt::with_buffer_emitter(None, ColorChoice::Never)
+            .set_flags(|flags| flags.track_diagnostics = false);
+        dcx.set_lint_level(Lint::Warnings, Level::Error);
+        dcx.set_lint_level(Lint::Code(crate::error_code!(1111)), Level::Allow);
+        dcx.set_lint_level(Lint::Code(crate::error_code!(2222)), Level::Warning);
+
+        dcx.warn("allowed").code(crate::error_code!(1111)).emit();
+        dcx.warn("warned").code(crate::error_code!(2222)).emit();
+        assert!(dcx.has_errors().is_ok());
+
+        dcx.warn("denied").emit();
+        assert_eq!(dcx.err_count(), 1);
+        assert!(dcx.has_errors().is_err());
+
+        let emitted = dcx.emitted_diagnostics().unwrap().to_string();
+        assert!(!emitted.contains("allowed"), "{emitted}");
+        assert!(emitted.contains("warning[2222]: warned"), "{emitted}");
+        assert!(emitted.contains("error: denied"), "{emitted}");
+        assert!(emitted.contains("requested on the command line with `-D warnings`"), "{emitted}");
+
+        let level = |code, default| dcx.lint_level(&code, default);
+        assert_eq!(level(crate::error_code!(1111), Level::Warning), Level::Allow);
+        assert_eq!(level(crate::error_code!(2222), Level::Allow), Level::Warning);
+        assert_eq!(level(crate::error_code!(3333), Level::Warning), Level::Error);
+        assert_eq!(level(crate::error_code!(3333), Level::Allow), Level::Allow);
+    }
+}
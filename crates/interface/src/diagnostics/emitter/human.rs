@@ -1,6 +1,7 @@
 use super::{io_panic, rustc::FileWithAnnotatedLines, Diagnostic, Emitter};
 use crate::{
-    diagnostics::{Level, MultiSpan, Style, SubDiagnostic},
+    diagnostics::{CodeSuggestion, Level, MultiSpan, Style, SubDiagnostic},
+    progress::Progress,
     source_map::SourceFile,
     SourceMap,
 };
@@ -12,8 +13,7 @@ use std::{
     ops::Range,
     sync::Arc,
 };
-
-// TODO: Tabs are not formatted correctly: https://github.com/rust-lang/annotate-snippets-rs/issues/25
+use unicode_width::UnicodeWidthChar;
 
 type Writer = dyn Write + Send + 'static;
 
@@ -28,12 +28,18 @@ const DEFAULT_RENDERER: Renderer = Renderer::plain()
     .none(anstyle::Style::new());
 
 /// Diagnostic emitter that emits to an arbitrary [`io::Write`] writer in human-readable format.
+///
+/// The width of the output and of tabs can be configured. Decorations are always drawn with ASCII
+/// characters, and the number of context lines and the `file:line:col` locations are chosen by
+/// `annotate-snippets`, which does not allow configuring them yet.
 pub struct HumanEmitter {
     writer_type_id: std::any::TypeId,
     real_writer: *mut Writer,
     writer: AutoStream<Box<Writer>>,
     source_map: Option<Arc<SourceMap>>,
     renderer: Renderer,
+    tab_width: Option<usize>,
+    progress: Progress,
 }
 
 // SAFETY: `real_writer` always points to the `Writer` in `writer`.
@@ -41,6 +47,7 @@ unsafe impl Send for HumanEmitter {}
 
 impl Emitter for HumanEmitter {
     fn emit_diagnostic(&mut self, diagnostic: &Diagnostic) {
+        self.progress.clear();
         self.snippet(diagnostic, |this, snippet| {
             writeln!(this.writer, "{}\n", this.renderer.render(snippet))?;
             this.writer.flush()
@@ -76,6 +83,8 @@ impl HumanEmitter {
             writer: AutoStream::new(real_writer, color),
             source_map: None,
             renderer: DEFAULT_RENDERER,
+            tab_width: None,
+            progress: Progress::default(),
         }
     }
 
@@ -136,6 +145,30 @@ impl HumanEmitter {
             std::mem::replace(&mut self.renderer, DEFAULT_RENDERER).anonymized_line_numbers(yes);
     }
 
+    /// Sets the maximum width of the rendered diagnostics, in columns.
+    ///
+    /// Source lines that are longer than this are cut around the annotated code. Defaults to 140.
+    pub fn term_width(mut self, width: usize) -> Self {
+        self.renderer = self.renderer.term_width(width);
+        self
+    }
+
+    /// Sets the width of the tab stops to expand tabs in source code to.
+    ///
+    /// Tabs are rendered as-is by default, which misaligns the annotations in terminals that
+    /// expand them.
+    pub fn tab_width(mut self, width: Option<usize>) -> Self {
+        self.tab_width = width;
+        self
+    }
+
+    /// Sets the progress reporter that shares the terminal with this emitter. Its line is erased
+    /// before emitting each diagnostic.
+    pub fn progress(mut self, progress: Progress) -> Self {
+        self.progress = progress;
+        self
+    }
+
     /// Downcasts the underlying writer to the specified type.
     fn downcast_writer<T: Any>(&self) -> Option<&T> {
         if self.writer_type_id == std::any::TypeId::of::<T>() {
@@ -184,7 +217,7 @@ impl HumanEmitter {
         let owned_snippets = self
             .source_map
             .as_deref()
-            .map(|sm| OwnedSnippet::collect(sm, diagnostic))
+            .map(|sm| OwnedSnippet::collect(sm, diagnostic, self.tab_width))
             .unwrap_or_default();
 
         // Dummy subdiagnostics go in the footer, while non-dummy ones go in the slices.
@@ -193,6 +226,7 @@ impl HumanEmitter {
             .iter()
             .filter(|sub| sub.span.is_dummy())
             .map(OwnedMessage::from_subdiagnostic)
+            .chain(diagnostic.suggestions.iter().map(OwnedMessage::from_suggestion))
             .collect();
 
         let snippet = title
@@ -246,6 +280,18 @@ impl HumanBufferEmitter {
         self
     }
 
+    /// Sets the maximum width of the rendered diagnostics. See [`HumanEmitter::term_width`].
+    pub fn term_width(mut self, width: usize) -> Self {
+        self.inner = self.inner.term_width(width);
+        self
+    }
+
+    /// Sets the width of the tab stops. See [`HumanEmitter::tab_width`].
+    pub fn tab_width(mut self, width: Option<usize>) -> Self {
+        self.inner = self.inner.tab_width(width);
+        self
+    }
+
     /// Returns a reference to the underlying human emitter.
     pub fn inner(&self) -> &HumanEmitter {
         &self.inner
@@ -289,6 +335,11 @@ impl OwnedMessage {
         Self { id: None, label: sub.label().into_owned(), level: to_as_level(sub.level) }
     }
 
+    fn from_suggestion(suggestion: &CodeSuggestion) -> Self {
+        let label = format!("{}: `{}`", suggestion.msg.as_str(), suggestion.replacement);
+        Self { id: None, label, level: ASLevel::Help }
+    }
+
     fn as_ref(&self) -> Message<'_> {
         let mut msg = self.level.title(&self.label);
         if let Some(id) = &self.id {
@@ -321,7 +372,7 @@ struct OwnedSnippet {
 }
 
 impl OwnedSnippet {
-    fn collect(sm: &SourceMap, diagnostic: &Diagnostic) -> Vec<Self> {
+    fn collect(sm: &SourceMap, diagnostic: &Diagnostic, tab_width: Option<usize>) -> Vec<Self> {
         // Collect main diagnostic.
         let mut files = Self::collect_files(sm, &diagnostic.span);
         files.iter_mut().for_each(|file| file.set_level(diagnostic.level));
@@ -349,12 +400,56 @@ impl OwnedSnippet {
             }
         }
 
+        // Files whose source code was dropped and could not be read again have no snippet.
         files
             .iter()
-            .map(|file| file_to_snippet(sm, &file.file, &file.lines, diagnostic.level))
+            .filter(|file| sm.ensure_source_present(&file.file))
+            .map(|file| {
+                let mut snippet = file_to_snippet(sm, &file.file, &file.lines, diagnostic.level);
+                if let Some(width) = tab_width {
+                    snippet.expand_tabs(width);
+                }
+                snippet
+            })
             .collect()
     }
 
+    /// Replaces the tabs in the source with spaces up to the next multiple of `width` columns,
+    /// adjusting the annotations.
+    fn expand_tabs(&mut self, width: usize) {
+        if width == 0 || !self.source.contains('\t') {
+            return;
+        }
+        // The byte position of each tab, and the number of bytes added up to and including it.
+        let mut tabs = Vec::new();
+        let mut added = 0;
+        let mut col = 0;
+        let mut source = String::with_capacity(self.source.len());
+        for (i, c) in self.source.char_indices() {
+            match c {
+                '\t' => {
+                    let spaces = width - col % width;
+                    source.extend(std::iter::repeat_n(' ', spaces));
+                    col += spaces;
+                    added += spaces - 1;
+                    tabs.push((i, added));
+                    continue;
+                }
+                '\n' => col = 0,
+                c => col += c.width().unwrap_or(0),
+            }
+            source.push(c);
+        }
+        let expand = |pos: usize| match tabs.partition_point(|&(tab, _)| tab < pos) {
+            0 => pos,
+            n => pos + tabs[n - 1].1,
+        };
+        for ann in &mut self.annotations {
+            ann.range = expand(ann.range.start)..expand(ann.range.end);
+        }
+        self.source = source;
+    }
+
     fn collect_files(sm: &SourceMap, msp: &MultiSpan) -> Vec<FileWithAnnotatedLines> {
         let mut annotated_files = FileWithAnnotatedLines::collect_annotations(sm, msp);
         if let Some(primary_span) = msp.primary_span() {
@@ -458,3 +553,34 @@ fn to_as_level(level: Level) -> ASLevel {
 fn char_to_byte_pos(s: &str, char_pos: usize) -> usize {
     s.chars().take(char_pos).map(char::len_utf8).sum()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{diagnostics::DiagCtxt, source_map::FileName, BytePos, Span};
+
+    #[test]
+    fn tab_width() {
+        let source_map = Arc::new(SourceMap::empty());
+        let src = "contract A {\n\tuint x = y;\n   \tuint z = w;\n}\n";
+        let file = source_map.new_source_file(FileName::real("a.sol"), || Ok(src.into())).unwrap();
+        let emitter = HumanBufferEmitter::new(ColorChoice::Never)
+            .source_map(Some(source_map))
+            .tab_width(Some(2));
+        let dcx =
+            DiagCtxt::new(Box::new(emitter)).set_flags(|flags| flags.track_diagnostics = false);
+
+        let lo = file.start_pos + BytePos(23);
+        let _ = dcx.err("unresolved symbol `y`").span(Span::new(lo, lo + BytePos(1))).emit();
+        let emitted = dcx.emitted_diagnostics().unwrap().to_string();
+        assert!(emitted.contains(&format!("|{}uint x = y;\n", " ".repeat(3))), "{emitted}");
+        assert!(emitted.contains(&format!("|{}^\n", " ".repeat(12))), "{emitted}");
+
+        // Tabs expand to the next tab stop.
+        let lo = file.start_pos + BytePos(39);
+        let _ = dcx.err("unresolved symbol `w`").span(Span::new(lo, lo + BytePos(1))).emit();
+        let emitted = dcx.emitted_diagnostics().unwrap().to_string();
+        assert!(emitted.contains(&format!("|{}uint z = w;\n", " ".repeat(5))), "{emitted}");
+        assert!(emitted.contains(&format!("|{}^\n", " ".repeat(14))), "{emitted}");
+    }
+}
@@ -1,6 +1,6 @@
 use super::{human::HumanBufferEmitter, io_panic, Emitter};
 use crate::{
-    diagnostics::{Level, MultiSpan, SpanLabel},
+    diagnostics::{CodeSuggestion, Level, MultiSpan, SpanLabel},
     source_map::{LineInfo, SourceFile},
     SourceMap, Span,
 };
@@ -72,10 +72,18 @@ impl JsonEmitter {
     fn diagnostic(&mut self, diagnostic: &crate::diagnostics::Diagnostic) -> Diagnostic {
         Diagnostic {
             message: diagnostic.label().into_owned(),
-            code: diagnostic.id().map(|code| DiagnosticCode { code, explanation: None }),
+            code: diagnostic.code.as_ref().map(|code| DiagnosticCode {
+                code: code.as_string(),
+                explanation: code.explanation(),
+            }),
             level: diagnostic.level.to_str(),
             spans: self.spans(&diagnostic.span),
-            children: diagnostic.children.iter().map(|sub| self.sub_diagnostic(sub)).collect(),
+            children: diagnostic
+                .children
+                .iter()
+                .map(|sub| self.sub_diagnostic(sub))
+                .chain(diagnostic.suggestions.iter().map(|sugg| self.suggestion(sugg)))
+                .collect(),
             rendered: Some(self.emit_diagnostic_to_buffer(diagnostic)),
         }
     }
@@ -91,6 +99,21 @@ impl JsonEmitter {
         }
     }
 
+    fn suggestion(&self, suggestion: &CodeSuggestion) -> Diagnostic {
+        let label = SpanLabel { span: suggestion.span, is_primary: true, label: None };
+        let mut span = self.span(&label);
+        span.suggested_replacement = Some(suggestion.replacement.clone());
+        span.suggestion_applicability = Some(suggestion.applicability.to_str());
+        Diagnostic {
+            message: suggestion.msg.as_str().into(),
+            code: None,
+            level: Level::Help.to_str(),
+            spans: vec![span],
+            children: vec![],
+            rendered: None,
+        }
+    }
+
     fn spans(&self, msp: &MultiSpan) -> Vec<DiagnosticSpan> {
         msp.span_labels().iter().map(|label| self.span(label)).collect()
     }
@@ -111,12 +134,15 @@ impl JsonEmitter {
             is_primary: label.is_primary,
             text: self.span_lines(span),
             label: label.label.as_ref().map(|msg| msg.as_str().into()),
+            suggested_replacement: None,
+            suggestion_applicability: None,
         }
     }
 
     fn span_lines(&self, span: Span) -> Vec<DiagnosticSpanLine> {
         let Ok(f) = self.source_map().span_to_lines(span) else { return Vec::new() };
         let sf = &*f.file;
+        self.source_map().ensure_source_present(sf);
         f.lines.iter().map(|line| self.span_line(sf, line)).collect()
     }
 
@@ -148,6 +174,16 @@ impl JsonEmitter {
                 .children
                 .iter()
                 .map(|sub| self.solc_span(&sub.span, &file, Some(sub.label().into_owned())))
+                .chain(diagnostic.span.span_labels().into_iter().filter(|l| !l.is_primary).map(
+                    |l| {
+                        let msg = l.label.map(|msg| msg.as_str().to_string());
+                        self.solc_span(&l.span.into(), &file, msg)
+                    },
+                ))
+                .chain(diagnostic.suggestions.iter().map(|sugg| {
+                    let msg = format!("{}: `{}`", sugg.msg.as_str(), sugg.replacement);
+                    self.solc_span(&sugg.span.into(), &file, Some(msg))
+                }))
                 .collect(),
             r#type: match severity {
                 Severity::Error => match diagnostic.level {
@@ -250,9 +286,11 @@ struct DiagnosticSpan {
     text: Vec<DiagnosticSpanLine>,
     /// Label that should be placed at this location (if any)
     label: Option<String>,
-    // /// If we are suggesting a replacement, this will contain text
-    // /// that should be sliced in atop this span.
-    // suggested_replacement: Option<String>,
+    /// If we are suggesting a replacement, this will contain text
+    /// that should be sliced in atop this span.
+    suggested_replacement: Option<String>,
+    /// If the suggestion is approximate.
+    suggestion_applicability: Option<&'static str>,
 }
 
 #[derive(Serialize)]
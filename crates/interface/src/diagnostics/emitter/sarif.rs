@@ -0,0 +1,342 @@
+use super::Emitter;
+use crate::{
+    diagnostics::{Diagnostic, Level},
+    SourceMap, Span,
+};
+use serde::Serialize;
+use solar_data_structures::map::FxBuildHasher;
+use std::{collections::BTreeMap, hash::BuildHasher, io, path::Path, sync::Arc};
+
+/// Diagnostic emitter that emits diagnostics as a [SARIF 2.1.0] log.
+///
+/// Since the log is a single JSON document, the diagnostics are collected and the log is only
+/// written when the emitter is dropped.
+///
+/// [SARIF 2.1.0]: https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+pub struct SarifEmitter {
+    writer: Box<dyn io::Write + Send>,
+    source_map: Arc<SourceMap>,
+    pretty: bool,
+
+    rules: Vec<Rule>,
+    results: Vec<SarifResult>,
+}
+
+impl Emitter for SarifEmitter {
+    fn emit_diagnostic(&mut self, diagnostic: &Diagnostic) {
+        let result = self.result(diagnostic);
+        self.results.push(result);
+    }
+
+    fn source_map(&self) -> Option<&Arc<SourceMap>> {
+        Some(&self.source_map)
+    }
+}
+
+impl Drop for SarifEmitter {
+    fn drop(&mut self) {
+        if let Err(e) = self.write_log() {
+            eprintln!("failed to write SARIF log: {e}");
+        }
+    }
+}
+
+impl SarifEmitter {
+    /// Creates a new `SarifEmitter` that writes to given writer.
+    pub fn new(writer: Box<dyn io::Write + Send>, source_map: Arc<SourceMap>) -> Self {
+        Self { writer, source_map, pretty: false, rules: Vec::new(), results: Vec::new() }
+    }
+
+    /// Sets whether to pretty print the JSON.
+    pub fn pretty(mut self, pretty: bool) -> Self {
+        self.pretty = pretty;
+        self
+    }
+
+    fn result(&mut self, diagnostic: &Diagnostic) -> SarifResult {
+        let rule_index = diagnostic.id().map(|id| self.rule_index(id));
+        let mut text = diagnostic.label().into_owned();
+        for child in &diagnostic.children {
+            text.push_str(&format!("\n{}: {}", child.level.to_str(), child.label()));
+        }
+        for suggestion in &diagnostic.suggestions {
+            let msg = suggestion.msg.as_str();
+            text.push_str(&format!("\nhelp: {msg}: `{}`", suggestion.replacement));
+        }
+
+        let primary = diagnostic.span.primary_span().filter(|span| !span.is_dummy());
+        let related_locations = diagnostic
+            .span
+            .span_labels()
+            .into_iter()
+            .filter(|label| !label.is_primary)
+            .filter_map(|label| {
+                let message = label.label.map(|msg| Message { text: msg.as_str().into() });
+                self.location(label.span, message)
+            })
+            .chain(diagnostic.children.iter().filter_map(|child| {
+                let span = child.span.primary_span()?;
+                self.location(span, Some(Message { text: child.label().into_owned() }))
+            }))
+            .collect();
+
+        let mut fingerprints = BTreeMap::new();
+        fingerprints.insert("solar/v1", self.fingerprint(diagnostic, primary));
+
+        SarifResult {
+            rule_id: rule_index.map(|i| self.rules[i].id.clone()),
+            rule_index,
+            level: to_level(diagnostic.level),
+            message: Message { text },
+            locations: primary.and_then(|span| self.location(span, None)).into_iter().collect(),
+            related_locations,
+            fingerprints,
+        }
+    }
+
+    fn rule_index(&mut self, id: String) -> usize {
+        if let Some(i) = self.rules.iter().position(|rule| rule.id == id) {
+            return i;
+        }
+        self.rules.push(Rule { id });
+        self.rules.len() - 1
+    }
+
+    fn location(&self, span: Span, message: Option<Message>) -> Option<Location> {
+        if span.is_dummy() {
+            return None;
+        }
+        let start = self.source_map.lookup_char_pos(span.lo());
+        let end = self.source_map.lookup_char_pos(span.hi());
+        Some(Location {
+            physical_location: PhysicalLocation {
+                artifact_location: self.artifact_location(span),
+                region: Region {
+                    start_line: start.line,
+                    start_column: start.col.0 + 1,
+                    end_line: end.line,
+                    end_column: end.col.0 + 1,
+                },
+            },
+            message,
+        })
+    }
+
+    fn artifact_location(&self, span: Span) -> ArtifactLocation {
+        let file = self.source_map.lookup_source_file(span.lo());
+        let name = self.source_map.filename_for_diagnostics(&file.name).to_string();
+        if Path::new(&name).is_absolute() {
+            ArtifactLocation { uri: format!("file://{name}"), uri_base_id: None }
+        } else {
+            ArtifactLocation { uri: name, uri_base_id: Some("%SRCROOT%") }
+        }
+    }
+
+    /// Returns a fingerprint of the diagnostic that does not depend on its line number, so that
+    /// results can be matched across runs when unrelated code moves.
+    fn fingerprint(&self, diagnostic: &Diagnostic, primary: Option<Span>) -> String {
+        let (file, line) = primary
+            .and_then(|span| {
+                let file = self.source_map.lookup_source_file(span.lo());
+                let line = self.source_map.lookup_char_pos(span.lo()).line;
+                self.source_map.ensure_source_present(&file);
+                let text = file.get_line(line - 1)?.trim().to_string();
+                Some((self.source_map.filename_for_diagnostics(&file.name).to_string(), text))
+            })
+            .unwrap_or_default();
+        let hash = FxBuildHasher.hash_one((diagnostic.id(), diagnostic.label(), file, line));
+        format!("{hash:016x}")
+    }
+
+    fn write_log(&mut self) -> io::Result<()> {
+        let log = Log {
+            schema: "https://json.schemastore.org/sarif-2.1.0.json",
+            version: "2.1.0",
+            runs: [Run {
+                tool: Tool {
+                    driver: Driver {
+                        name: "solar",
+                        information_uri: "https://github.com/paradigmxyz/solar",
+                        version: crate::VERSION,
+                        rules: &self.rules,
+                    },
+                },
+                column_kind: "unicodeCodePoints",
+                results: &self.results,
+            }],
+        };
+        if self.pretty {
+            serde_json::to_writer_pretty(&mut *self.writer, &log)
+        } else {
+            serde_json::to_writer(&mut *self.writer, &log)
+        }?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()
+    }
+}
+
+fn to_level(level: Level) -> &'static str {
+    match level {
+        Level::Bug | Level::Fatal | Level::Error => "error",
+        Level::Warning => "warning",
+        Level::Note
+        | Level::OnceNote
+        | Level::Help
+        | Level::OnceHelp
+        | Level::FailureNote
+        | Level::Allow => "note",
+    }
+}
+
+#[derive(Serialize)]
+struct Log<'a> {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: [Run<'a>; 1],
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Run<'a> {
+    tool: Tool<'a>,
+    column_kind: &'static str,
+    results: &'a [SarifResult],
+}
+
+#[derive(Serialize)]
+struct Tool<'a> {
+    driver: Driver<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Driver<'a> {
+    name: &'static str,
+    information_uri: &'static str,
+    version: &'static str,
+    rules: &'a [Rule],
+}
+
+#[derive(Serialize)]
+struct Rule {
+    id: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_index: Option<usize>,
+    /// "error", "warning", or "note".
+    level: &'static str,
+    message: Message,
+    locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    related_locations: Vec<Location>,
+    fingerprints: BTreeMap<&'static str, String>,
+}
+
+#[derive(Serialize)]
+struct Message {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    physical_location: PhysicalLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<Message>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    region: Region,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactLocation {
+    uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    uri_base_id: Option<&'static str>,
+}
+
+/// 1-based, with an exclusive end column.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    start_line: usize,
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{diagnostics::DiagCtxt, source_map::FileName, BytePos};
+    use std::sync::Mutex;
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn sarif_log() {
+        let source_map = Arc::new(SourceMap::empty());
+        let file = source_map
+            .new_source_file(FileName::real("a.sol"), || {
+                Ok("contract A {\n    uint x = y;\n}\n".into())
+            })
+            .unwrap();
+        let buffer = SharedBuffer::default();
+        let emitter = SarifEmitter::new(Box::new(buffer.clone()), source_map);
+        let dcx =
+            DiagCtxt::new(Box::new(emitter)).set_flags(|flags| flags.track_diagnostics = false);
+
+        let lo = file.start_pos + BytePos(26);
+        let span = Span::new(lo, lo + BytePos(1));
+        let _ = dcx.err("unresolved symbol `y`").code(crate::error_code!(1234)).span(span).emit();
+        dcx.warn("no span").emit();
+        drop(dcx);
+
+        let log: serde_json::Value = serde_json::from_slice(&buffer.0.lock().unwrap()).unwrap();
+        assert_eq!(log["version"], "2.1.0");
+        let run = &log["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "1234");
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+
+        let error = &results[0];
+        assert_eq!(error["ruleId"], "1234");
+        assert_eq!(error["ruleIndex"], 0);
+        assert_eq!(error["level"], "error");
+        assert_eq!(error["message"]["text"], "unresolved symbol `y`");
+        let location = &error["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "a.sol");
+        assert_eq!(location["region"]["startLine"], 2);
+        assert_eq!(location["region"]["startColumn"], 14);
+        assert_eq!(location["region"]["endColumn"], 15);
+        assert!(error["fingerprints"]["solar/v1"].is_string());
+
+        let warning = &results[1];
+        assert_eq!(warning["level"], "warning");
+        assert!(warning.get("ruleId").is_none());
+        assert_eq!(warning["locations"].as_array().unwrap().len(), 0);
+    }
+}
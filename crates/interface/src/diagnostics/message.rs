@@ -119,6 +119,16 @@ impl MultiSpan {
         replacements_occurred
     }
 
+    /// Replaces all the primary and label spans with the result of `f`.
+    pub fn map_spans(&mut self, mut f: impl FnMut(Span) -> Span) {
+        for span in &mut self.primary_spans {
+            *span = f(*span);
+        }
+        for (span, _) in &mut self.span_labels {
+            *span = f(*span);
+        }
+    }
+
     pub fn pop_span_label(&mut self) -> Option<(Span, DiagnosticMessage)> {
         self.span_labels.pop()
     }
@@ -6,18 +6,23 @@ use crate::Span;
 use anstyle::{AnsiColor, Color};
 use std::{borrow::Cow, fmt, panic::Location};
 
+mod baseline;
+pub use baseline::{Baseline, Fingerprint};
+
 mod builder;
 pub use builder::{DiagnosticBuilder, EmissionGuarantee};
 
 mod context;
 pub use context::{DiagCtxt, DiagCtxtFlags};
 
+pub mod registry;
+
 mod emitter;
-#[cfg(feature = "json")]
-pub use emitter::JsonEmitter;
 pub use emitter::{
     DynEmitter, Emitter, HumanBufferEmitter, HumanEmitter, LocalEmitter, SilentEmitter,
 };
+#[cfg(feature = "json")]
+pub use emitter::{JsonEmitter, SarifEmitter};
 
 mod message;
 pub use message::{DiagnosticMessage, MultiSpan, SpanLabel};
@@ -113,6 +118,41 @@ impl DiagnosticId {
     }
 }
 
+/// A category of warnings whose level can be changed on the command line with `-A`, `-W`, and
+/// `-D`.
+///
+/// See [`DiagCtxt::set_lint_level`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Lint {
+    /// All warnings.
+    Warnings,
+    /// The warnings with the given code.
+    Code(DiagnosticId),
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Warnings => f.write_str("warnings"),
+            Self::Code(code) => f.write_str(&code.as_string()),
+        }
+    }
+}
+
+impl std::str::FromStr for Lint {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "warnings" {
+            return Ok(Self::Warnings);
+        }
+        match s.parse::<u32>() {
+            Ok(id @ 1..=9999) if s.len() == 4 => Ok(Self::Code(DiagnosticId { id })),
+            _ => Err("expected `warnings` or a 4-digit warning code"),
+        }
+    }
+}
+
 /// Used for creating an error code. The input must be exactly 4 decimal digits.
 ///
 /// # Examples
@@ -311,6 +351,51 @@ impl SubDiagnostic {
     }
 }
 
+/// A suggested replacement for a span of source code, attached to a parent diagnostic.
+#[derive(Clone, Debug, PartialEq, Hash)]
+pub struct CodeSuggestion {
+    pub msg: DiagnosticMessage,
+    pub span: Span,
+    pub replacement: String,
+    pub applicability: Applicability,
+}
+
+/// Indicates the confidence in the correctness of a suggestion.
+///
+/// All suggestions are marked with an `Applicability`. Tools use the applicability of a suggestion
+/// to determine whether it should be automatically applied or if the user should be consulted
+/// before applying the suggestion.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Applicability {
+    /// The suggestion is definitely what the user intended, or maintains the exact meaning of the
+    /// code. This suggestion should be automatically applied.
+    MachineApplicable,
+
+    /// The suggestion may be what the user intended, but it is uncertain. The suggestion should
+    /// result in valid Solidity code if it is applied.
+    MaybeIncorrect,
+
+    /// The suggestion contains placeholders like `(...)` or `{ /* fields */ }`. The suggestion
+    /// cannot be applied automatically because it will not result in valid Solidity code. The
+    /// user will need to fill in the placeholders.
+    HasPlaceholders,
+
+    /// The applicability of the suggestion is unknown.
+    Unspecified,
+}
+
+impl Applicability {
+    /// Returns the string representation of the applicability.
+    pub fn to_str(self) -> &'static str {
+        match self {
+            Self::MachineApplicable => "MachineApplicable",
+            Self::MaybeIncorrect => "MaybeIncorrect",
+            Self::HasPlaceholders => "HasPlaceholders",
+            Self::Unspecified => "Unspecified",
+        }
+    }
+}
+
 /// A compiler diagnostic.
 #[must_use]
 #[derive(Clone, Debug)]
@@ -320,6 +405,7 @@ pub struct Diagnostic {
     pub messages: Vec<(DiagnosticMessage, Style)>,
     pub span: MultiSpan,
     pub children: Vec<SubDiagnostic>,
+    pub suggestions: Vec<CodeSuggestion>,
     pub code: Option<DiagnosticId>,
 
     pub created_at: &'static Location<'static>,
@@ -353,7 +439,7 @@ impl Diagnostic {
             code: None,
             span: MultiSpan::new(),
             children: vec![],
-            // suggestions: Ok(vec![]),
+            suggestions: vec![],
             // args: Default::default(),
             // sort_span: DUMMY_SP,
             // is_lint: false,
@@ -387,6 +473,11 @@ impl Diagnostic {
         self.code.as_ref().map(|code| code.as_string())
     }
 
+    /// Returns the fields that determine whether two diagnostics are duplicates.
+    pub(crate) fn dedup_key(&self) -> impl std::hash::Hash + '_ {
+        (&self.level, &self.messages, &self.code, self.span.primary_span())
+    }
+
     /// Fields used for `PartialEq` and `Hash` implementations.
     fn keys(&self) -> impl PartialEq + std::hash::Hash + '_ {
         (
@@ -395,7 +486,7 @@ impl Diagnostic {
             // self.args().collect(),
             &self.code,
             &self.span,
-            // &self.suggestions,
+            &self.suggestions,
             // (if self.is_lint { None } else { Some(&self.children) }),
             &self.children,
         )
@@ -538,6 +629,23 @@ impl Diagnostic {
         self.sub(Level::Help, msg, span)
     }
 
+    /// Adds a suggestion to replace the source code at `span` with `replacement`.
+    pub fn span_suggestion(
+        &mut self,
+        span: Span,
+        msg: impl Into<DiagnosticMessage>,
+        replacement: impl Into<String>,
+        applicability: Applicability,
+    ) -> &mut Self {
+        self.suggestions.push(CodeSuggestion {
+            msg: msg.into(),
+            span,
+            replacement: replacement.into(),
+            applicability,
+        });
+        self
+    }
+
     fn sub(
         &mut self,
         level: Level,
@@ -0,0 +1,104 @@
+//! The registry of diagnostic codes and their long explanations.
+//!
+//! Codes are the same as the ones used by `solc` when the diagnostic has an equivalent there.
+//! Each explanation is a Markdown file in the `codes` directory, named after its code.
+//! Codes can also link to the relevant page of the [Solidity documentation](DOCS_URL).
+
+use super::DiagnosticId;
+
+/// The base URL of the Solidity documentation.
+pub const DOCS_URL: &str = "https://docs.soliditylang.org/en/latest/";
+
+macro_rules! registry {
+    ($($code:literal $(=> $docs:literal)?),* $(,)?) => {
+        /// All the registered codes, their explanations, and their documentation pages, sorted by
+        /// code.
+        const REGISTRY: &[(u32, &str, Option<&str>)] = &[
+            $((
+                $code,
+                include_str!(concat!("codes/", stringify!($code), ".md")),
+                registry!(@docs $($docs)?),
+            ),)*
+        ];
+    };
+    (@docs) => { None };
+    (@docs $docs:literal) => { Some($docs) };
+}
+
+registry! {
+    2046 => "types.html#structs",
+    2333 => "control-structures.html#scoping-and-declarations",
+    2418 => "yul.html#switch",
+    3164 => "security-considerations.html#tx-origin",
+    3445 => "contracts.html#special-functions",
+    3881 => "natspec-format.html#tags",
+    5333 => "layout-of-source-files.html#version-pragma",
+    6162 => "types.html#function-types",
+    6275 => "path-resolution.html",
+    6508 => "layout-of-source-files.html#version-pragma",
+    7576 => "control-structures.html#scoping-and-declarations",
+    8751 => "security-considerations.html#reentrancy",
+    9302 => "units-and-global-variables.html#members-of-address-types",
+    9592 => "yul.html#switch",
+}
+
+impl DiagnosticId {
+    /// Returns the long explanation of this code, if any.
+    pub fn explanation(&self) -> Option<&'static str> {
+        Some(entry(self.id)?.1)
+    }
+
+    /// Returns the URL of the section of the Solidity documentation related to this code, if any.
+    pub fn docs_url(&self) -> Option<String> {
+        entry(self.id)?.2.map(|page| format!("{DOCS_URL}{page}"))
+    }
+}
+
+/// Returns the long explanation of the given code, such as `"7576"`, if any.
+///
+/// This is used by `solar --explain`.
+pub fn explain(code: &str) -> Option<&'static str> {
+    if code.len() != 4 {
+        return None;
+    }
+    Some(entry(code.parse().ok()?)?.1)
+}
+
+/// Returns an iterator over all the registered codes.
+pub fn codes() -> impl ExactSizeIterator<Item = DiagnosticId> {
+    REGISTRY.iter().map(|&(id, ..)| DiagnosticId { id })
+}
+
+fn entry(id: u32) -> Option<&'static (u32, &'static str, Option<&'static str>)> {
+    let i = REGISTRY.binary_search_by_key(&id, |&(id, ..)| id).ok()?;
+    Some(&REGISTRY[i])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_is_sorted() {
+        assert!(REGISTRY.is_sorted_by_key(|&(id, ..)| id));
+    }
+
+    #[test]
+    fn explain_codes() {
+        assert!(explain("7576").unwrap().contains("undeclared identifier"));
+        assert_eq!(explain("7576"), crate::error_code!(7576).explanation());
+        assert_eq!(explain("0001"), None);
+        assert_eq!(explain("757"), None);
+        assert_eq!(explain("E7576"), None);
+        assert_eq!(codes().len(), REGISTRY.len());
+    }
+
+    #[test]
+    fn docs_urls() {
+        assert_eq!(
+            crate::error_code!(6275).docs_url().as_deref(),
+            Some("https://docs.soliditylang.org/en/latest/path-resolution.html")
+        );
+        assert_eq!(crate::error_code!(1).docs_url(), None);
+    }
+}
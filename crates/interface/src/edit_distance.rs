@@ -0,0 +1,143 @@
+//! Edit distances, used to find similar names for "did you mean" suggestions.
+//!
+//! Modified from [`rustc_span::edit_distance`](https://github.com/rust-lang/rust/blob/master/compiler/rustc_span/src/edit_distance.rs).
+
+/// Finds the [Levenshtein distance](https://en.wikipedia.org/wiki/Levenshtein_distance) between
+/// two strings.
+///
+/// Returns `None` if the distance exceeds the limit.
+pub fn edit_distance(a: &str, b: &str, limit: usize) -> Option<usize> {
+    let mut a = &a.chars().collect::<Vec<_>>()[..];
+    let mut b = &b.chars().collect::<Vec<_>>()[..];
+
+    // Ensure that `b` is the shorter string, minimizing memory use.
+    if a.len() < b.len() {
+        std::mem::swap(&mut a, &mut b);
+    }
+
+    let min_dist = a.len() - b.len();
+    // If we know the limit will be exceeded, we can return early.
+    if min_dist > limit {
+        return None;
+    }
+
+    // Strip common prefix.
+    while let Some(((b_char, b_rest), (a_char, a_rest))) = b.split_first().zip(a.split_first()) {
+        if a_char != b_char {
+            break;
+        }
+        a = a_rest;
+        b = b_rest;
+    }
+    // Strip common suffix.
+    while let Some(((b_char, b_rest), (a_char, a_rest))) = b.split_last().zip(a.split_last()) {
+        if a_char != b_char {
+            break;
+        }
+        a = a_rest;
+        b = b_rest;
+    }
+
+    // If either string is empty, the distance is the length of the other.
+    // We know that `b` is the shorter string, so we don't need to check `a`.
+    if b.is_empty() {
+        return Some(min_dist);
+    }
+
+    let mut prev_prev = vec![usize::MAX; b.len() + 1];
+    let mut prev = (0..=b.len()).collect::<Vec<_>>();
+    let mut current = vec![0; b.len() + 1];
+
+    // `prev_prev` is used for the transposition of adjacent characters.
+    for i in 1..=a.len() {
+        current[0] = i;
+        let a_idx = i - 1;
+
+        for j in 1..=b.len() {
+            let b_idx = j - 1;
+
+            // There is no cost to substitute a character with itself.
+            let substitution_cost = if a[a_idx] == b[b_idx] { 0 } else { 1 };
+
+            current[j] = std::cmp::min(
+                // deletion
+                prev[j] + 1,
+                std::cmp::min(
+                    // insertion
+                    current[j - 1] + 1,
+                    // substitution
+                    prev[j - 1] + substitution_cost,
+                ),
+            );
+
+            if (i > 1) && (j > 1) && (a[a_idx] == b[b_idx - 1]) && (a[a_idx - 1] == b[b_idx]) {
+                // transposition
+                current[j] = std::cmp::min(current[j], prev_prev[j - 2] + 1);
+            }
+        }
+
+        // Rotate the buffers, reusing the memory.
+        [prev_prev, prev, current] = [prev, current, prev_prev];
+    }
+
+    // `prev` because we already rotated the buffers.
+    let distance = prev[b.len()];
+    (distance <= limit).then_some(distance)
+}
+
+/// Finds the best match for `lookup` in `candidates`.
+///
+/// Only candidates within an edit distance of a third of the length of `lookup` are considered,
+/// and candidates that differ only in case are preferred. Returns `None` if there is no such
+/// candidate, or if `lookup` itself is one of the candidates.
+pub fn find_best_match<'a>(
+    candidates: impl IntoIterator<Item = &'a str>,
+    lookup: &str,
+) -> Option<&'a str> {
+    let limit = std::cmp::max(lookup.len(), 3) / 3;
+    let lookup_lower = lookup.to_lowercase();
+    let mut best = None;
+    let mut best_dist = usize::MAX;
+    for candidate in candidates {
+        if candidate == lookup {
+            return None;
+        }
+        if candidate.to_lowercase() == lookup_lower {
+            best = Some(candidate);
+            best_dist = 0;
+            continue;
+        }
+        if let Some(dist) = edit_distance(candidate, lookup, limit) {
+            if dist < best_dist {
+                best = Some(candidate);
+                best_dist = dist;
+            }
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance() {
+        assert_eq!(edit_distance("", "", 0), Some(0));
+        assert_eq!(edit_distance("abc", "abc", 0), Some(0));
+        assert_eq!(edit_distance("abc", "", 3), Some(3));
+        assert_eq!(edit_distance("kitten", "sitting", 3), Some(3));
+        assert_eq!(edit_distance("kitten", "sitting", 2), None);
+        assert_eq!(edit_distance("ab", "ba", 1), Some(1));
+        assert_eq!(edit_distance("Token.sol", "Tokne.sol", 1), Some(1));
+    }
+
+    #[test]
+    fn best_match() {
+        let candidates = ["ERC20.sol", "ERC721.sol", "Ownable.sol"];
+        assert_eq!(find_best_match(candidates, "ERC02.sol"), Some("ERC20.sol"));
+        assert_eq!(find_best_match(candidates, "erc721.sol"), Some("ERC721.sol"));
+        assert_eq!(find_best_match(candidates, "Ownable.sol"), None);
+        assert_eq!(find_best_match(candidates, "Math.sol"), None);
+    }
+}
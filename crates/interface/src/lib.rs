@@ -12,6 +12,8 @@ extern crate tracing;
 pub mod diagnostics;
 use diagnostics::ErrorGuaranteed;
 
+pub mod edit_distance;
+
 mod globals;
 pub use globals::SessionGlobals;
 
@@ -32,6 +34,10 @@ pub use symbol::{kw, sym, Ident, Symbol};
 
 pub mod panic_hook;
 
+pub mod progress;
+
+pub mod timings;
+
 pub use anstream::ColorChoice;
 pub use dunce::canonicalize;
 pub use solar_config as config;
@@ -39,6 +45,11 @@ pub use solar_config as config;
 /// The current version of the Solar compiler.
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// The version of the Solidity language implemented by the compiler.
+///
+/// This is the default version that `pragma solidity` directives are checked against.
+pub const SOLIDITY_VERSION: semver::Version = semver::Version::new(0, 8, 28);
+
 /// Compiler result type.
 pub type Result<T = (), E = ErrorGuaranteed> = std::result::Result<T, E>;
 
@@ -0,0 +1,152 @@
+//! Progress reporting for long compilations, enabled with `--progress`.
+
+use solar_data_structures::sync::Lock;
+use std::{
+    io::{self, Write},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// The delay before the progress line is first drawn, so that short compilations do not flicker.
+const INITIAL_DELAY: Duration = Duration::from_millis(500);
+/// The minimum delay between two redraws of the progress line.
+const REDRAW_DELAY: Duration = Duration::from_millis(100);
+
+/// Reports the progress of the compiler stages on a single, continuously updated line of stderr.
+///
+/// A disabled reporter, the default, does nothing. Clones share the same line.
+#[derive(Clone, Debug, Default)]
+pub struct Progress(Option<Arc<Lock<State>>>);
+
+#[derive(Debug)]
+struct State {
+    created: Instant,
+    stage: &'static str,
+    stage_start: Instant,
+    done: usize,
+    total: usize,
+    last_draw: Option<Instant>,
+    drawn: bool,
+}
+
+impl Progress {
+    /// Creates a new reporter that draws to stderr.
+    ///
+    /// The caller is responsible for only enabling it when stderr is a terminal.
+    pub fn stderr() -> Self {
+        let now = Instant::now();
+        Self(Some(Arc::new(Lock::new(State {
+            created: now,
+            stage: "",
+            stage_start: now,
+            done: 0,
+            total: 0,
+            last_draw: None,
+            drawn: false,
+        }))))
+    }
+
+    /// Returns `true` if progress is reported.
+    pub fn is_enabled(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Starts the stage `stage`, which processes `total` items.
+    pub fn start(&self, stage: &'static str, total: usize) {
+        self.update(|state| {
+            state.stage = stage;
+            state.stage_start = Instant::now();
+            state.done = 0;
+            state.total = total;
+        });
+    }
+
+    /// Adds `n` items to process to the current stage.
+    pub fn add_total(&self, n: usize) {
+        self.update(|state| state.total += n);
+    }
+
+    /// Marks an item of the current stage as processed.
+    pub fn inc(&self) {
+        self.update(|state| state.done = (state.done + 1).min(state.total));
+    }
+
+    /// Erases the progress line, so that something else can be written to stderr.
+    ///
+    /// The line is drawn again on the next update.
+    pub fn clear(&self) {
+        if let Some(state) = &self.0 {
+            state.lock().clear();
+        }
+    }
+
+    /// Ends the current stage and erases the progress line.
+    pub fn finish(&self) {
+        if let Some(state) = &self.0 {
+            let mut state = state.lock();
+            state.clear();
+            state.stage = "";
+        }
+    }
+
+    fn update(&self, f: impl FnOnce(&mut State)) {
+        let Some(state) = &self.0 else { return };
+        let mut state = state.lock();
+        f(&mut state);
+        let now = Instant::now();
+        let ready = now.duration_since(state.created) >= INITIAL_DELAY
+            && state.last_draw.is_none_or(|last| now.duration_since(last) >= REDRAW_DELAY);
+        if ready && !state.stage.is_empty() {
+            state.last_draw = Some(now);
+            state.draw(now);
+        }
+    }
+}
+
+impl State {
+    fn draw(&mut self, now: Instant) {
+        let mut line = format!("{}: {}/{}", self.stage, self.done, self.total);
+        let elapsed = now.duration_since(self.stage_start);
+        if self.done > 0 && elapsed >= INITIAL_DELAY {
+            let remaining = elapsed.mul_f64((self.total - self.done) as f64 / self.done as f64);
+            line.push_str(&format!(", ETA {}s", remaining.as_secs()));
+        }
+        let mut stderr = io::stderr().lock();
+        let _ = write!(stderr, "\r\x1b[2K{line}").and_then(|()| stderr.flush());
+        self.drawn = true;
+    }
+
+    fn clear(&mut self) {
+        if self.drawn {
+            let mut stderr = io::stderr().lock();
+            let _ = write!(stderr, "\r\x1b[2K").and_then(|()| stderr.flush());
+            self.drawn = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled() {
+        let progress = Progress::default();
+        assert!(!progress.is_enabled());
+        progress.start("parsing", 1);
+        progress.inc();
+        progress.finish();
+    }
+
+    #[test]
+    fn counts() {
+        let progress = Progress::stderr();
+        progress.start("parsing", 1);
+        progress.add_total(1);
+        progress.inc();
+        progress.inc();
+        progress.inc();
+        let state = progress.0.as_ref().unwrap().lock();
+        assert_eq!((state.stage, state.done, state.total), ("parsing", 2, 2));
+    }
+}
@@ -1,9 +1,16 @@
 use crate::{
     diagnostics::{DiagCtxt, EmittedDiagnostics},
+    progress::Progress,
+    timings::Timings,
     ColorChoice, SessionGlobals, SourceMap,
 };
-use solar_config::{CompilerOutput, CompilerStage, Dump, EvmVersion, Language};
-use std::{collections::BTreeSet, num::NonZeroUsize, path::PathBuf, sync::Arc};
+use solar_config::{CompilerOutput, CompilerStage, Dump, EvmVersion, Language, MetadataHash};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    num::NonZeroUsize,
+    path::PathBuf,
+    sync::Arc,
+};
 
 /// Information about the current compiler session.
 #[derive(derive_builder::Builder)]
@@ -22,6 +29,9 @@ pub struct Session {
     /// EVM version.
     #[builder(default)]
     pub evm_version: EvmVersion,
+    /// The Solidity version that `pragma solidity` directives are checked against.
+    #[builder(default = "crate::SOLIDITY_VERSION")]
+    pub solidity_version: semver::Version,
     /// Source code language.
     #[builder(default)]
     pub language: Language,
@@ -31,6 +41,27 @@ pub struct Session {
     /// Types of output to emit.
     #[builder(default)]
     pub emit: BTreeSet<CompilerOutput>,
+    /// The hash of the contract metadata to append to the runtime bytecode.
+    #[builder(default)]
+    pub metadata_hash: MetadataHash,
+    /// Whether to append the CBOR-encoded metadata to the runtime bytecode.
+    #[builder(default = "true")]
+    pub cbor_metadata: bool,
+    /// Whether to run the Yul optimizer on the generated code, and the peephole optimizer on the
+    /// generated assembly.
+    #[builder(default)]
+    pub optimize: bool,
+    /// The expected number of times each opcode of the deployed code is executed.
+    #[builder(default = "200")]
+    pub optimizer_runs: u32,
+    /// The sequence of Yul optimizer steps to run instead of the default one.
+    #[builder(default)]
+    pub yul_optimizations: Option<String>,
+    /// The addresses of the libraries to link, keyed by fully qualified name, `path:Name`.
+    ///
+    /// The references to the other libraries are left as placeholders in the bytecode.
+    #[builder(default)]
+    pub libraries: BTreeMap<String, [u8; 20]>,
     /// Output directory.
     #[builder(default)]
     pub out_dir: Option<PathBuf>,
@@ -46,6 +77,21 @@ pub struct Session {
     /// Whether to emit AST stats.
     #[builder(default)]
     pub ast_stats: bool,
+    /// Whether to drop the source code of the files loaded from disk once the sources have been
+    /// lowered. The source code is read again when it is needed, such as to render diagnostics.
+    ///
+    /// See [`SourceMap::drop_source_texts`].
+    #[builder(default)]
+    pub drop_source_text: bool,
+    /// Whether to record the wall time and statistics of the compiler passes in `timings`.
+    #[builder(default)]
+    pub time_passes: bool,
+    /// The compiler passes recorded with [`time`](Self::time).
+    #[builder(default)]
+    pub timings: Timings,
+    /// The progress reporter of the compiler stages. Disabled by default.
+    #[builder(default)]
+    pub progress: Progress,
 }
 
 #[derive(Debug)]
@@ -171,7 +217,18 @@ impl Session {
     /// Returns `true` if compilation should stop after the given stage.
     #[inline]
     pub fn stop_after(&self, stage: CompilerStage) -> bool {
-        self.stop_after >= Some(stage)
+        self.stop_after.is_some_and(|stop_after| stop_after <= stage)
+    }
+
+    /// Runs `f` as the compiler pass `name`, recording its wall time in
+    /// [`timings`](Self::timings) if [`time_passes`](Self::time_passes) is enabled.
+    #[inline]
+    pub fn time<R>(&self, name: &'static str, f: impl FnOnce() -> R) -> R {
+        if self.time_passes {
+            self.timings.time(name, f)
+        } else {
+            f()
+        }
     }
 
     /// Returns `true` if parallelism is not enabled.
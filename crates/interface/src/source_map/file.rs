@@ -1,9 +1,9 @@
-use crate::{pos::RelativeBytePos, BytePos, CharPos};
+use crate::{pos::RelativeBytePos, BytePos, CharPos, Span};
 use std::{
     fmt, io,
     ops::RangeInclusive,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, OnceLock},
 };
 
 /// Identifies an offset of a multi-byte character in a `SourceFile`.
@@ -200,6 +200,183 @@ impl From<OffsetOverflowError> for io::Error {
     }
 }
 
+/// A range of a generated source file, and the span of the source code that it was generated from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SpanOrigin {
+    /// The start of the range in the generated file.
+    pub lo: RelativeBytePos,
+    /// The end of the range in the generated file, exclusive.
+    pub hi: RelativeBytePos,
+    /// The span that the range was generated from.
+    pub span: Span,
+}
+
+/// The first invalid byte of a source file that is not valid UTF-8.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidUtf8 {
+    /// The position of the replacement character in the decoded source.
+    pub pos: RelativeBytePos,
+    /// The offset of the invalid byte in the original contents of the file.
+    pub offset: usize,
+}
+
+/// Decodes the contents of a source file.
+///
+/// A UTF-8 byte order mark is removed, and files that start with a UTF-16 byte order mark are
+/// decoded as UTF-16. Invalid sequences are replaced with `U+FFFD REPLACEMENT CHARACTER`, and the
+/// first one is returned alongside the decoded source.
+pub fn decode_source(bytes: Vec<u8>) -> (String, Option<InvalidUtf8>) {
+    if let Some(bytes) = bytes.strip_prefix(b"\xFF\xFE") {
+        return decode_utf16(bytes, u16::from_le_bytes);
+    }
+    if let Some(bytes) = bytes.strip_prefix(b"\xFE\xFF") {
+        return decode_utf16(bytes, u16::from_be_bytes);
+    }
+    let bom_len = if bytes.starts_with(b"\xEF\xBB\xBF") { 3 } else { 0 };
+    match String::from_utf8(bytes) {
+        Ok(mut src) => {
+            src.drain(..bom_len);
+            (src, None)
+        }
+        Err(e) => {
+            let offset = e.utf8_error().valid_up_to();
+            let src = String::from_utf8_lossy(&e.as_bytes()[bom_len..]).into_owned();
+            let pos = RelativeBytePos::from_usize(offset - bom_len);
+            (src, Some(InvalidUtf8 { pos, offset }))
+        }
+    }
+}
+
+/// Decodes UTF-16 `bytes` that follow a byte order mark.
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> (String, Option<InvalidUtf8>) {
+    const BOM_LEN: usize = 2;
+    let mut src = String::with_capacity(bytes.len());
+    let mut invalid = None;
+    let mut offset = BOM_LEN;
+    let units = bytes.chunks_exact(2).map(|unit| from_bytes([unit[0], unit[1]]));
+    for c in char::decode_utf16(units) {
+        let c = c.unwrap_or_else(|_| {
+            let pos = RelativeBytePos::from_usize(src.len());
+            invalid.get_or_insert(InvalidUtf8 { pos, offset });
+            char::REPLACEMENT_CHARACTER
+        });
+        // Unpaired surrogates are a single code unit, like their replacement character.
+        offset += c.len_utf16() * 2;
+        src.push(c);
+    }
+    if bytes.len() % 2 != 0 {
+        let pos = RelativeBytePos::from_usize(src.len());
+        invalid.get_or_insert(InvalidUtf8 { pos, offset });
+        src.push(char::REPLACEMENT_CHARACTER);
+    }
+    (src, invalid)
+}
+
+/// The source code of a [`SourceFile`]. Dereferences to `str`.
+///
+/// The text is either owned, or memory-mapped from a file on disk. See
+/// [`FileResolver::set_mmap_threshold`](super::FileResolver::set_mmap_threshold).
+pub struct SourceText(SourceTextRepr);
+
+enum SourceTextRepr {
+    Owned(String),
+    /// A memory-mapped file that is valid UTF-8 after the first `start` bytes, which are a byte
+    /// order mark, if any.
+    #[cfg(not(target_family = "wasm"))]
+    Mapped {
+        map: memmap2::Mmap,
+        start: usize,
+    },
+}
+
+impl SourceText {
+    /// Memory-maps the file at `path`.
+    ///
+    /// Returns `Ok(None)` if the file is empty, or if it is not valid UTF-8 and must be decoded
+    /// with [`decode_source`] instead.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified while it is mapped, otherwise the text may change or no
+    /// longer be valid UTF-8. See [`memmap2::Mmap::map`].
+    #[cfg(not(target_family = "wasm"))]
+    pub unsafe fn map(path: &Path) -> io::Result<Option<Self>> {
+        let file = std::fs::File::open(path)?;
+        if file.metadata()?.len() == 0 {
+            return Ok(None);
+        }
+        // SAFETY: Upheld by the caller.
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        let start = if map.starts_with(b"\xEF\xBB\xBF") { 3 } else { 0 };
+        if map.starts_with(b"\xFF\xFE")
+            || map.starts_with(b"\xFE\xFF")
+            || std::str::from_utf8(&map[start..]).is_err()
+        {
+            return Ok(None);
+        }
+        Ok(Some(Self(SourceTextRepr::Mapped { map, start })))
+    }
+
+    /// Returns the text as a string slice.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        match &self.0 {
+            SourceTextRepr::Owned(s) => s,
+            // SAFETY: Checked to be valid UTF-8 in `map`.
+            #[cfg(not(target_family = "wasm"))]
+            SourceTextRepr::Mapped { map, start } => unsafe {
+                std::str::from_utf8_unchecked(&map[*start..])
+            },
+        }
+    }
+
+    /// Returns `true` if the text is memory-mapped.
+    pub fn is_mapped(&self) -> bool {
+        !matches!(self.0, SourceTextRepr::Owned(_))
+    }
+}
+
+impl From<String> for SourceText {
+    fn from(s: String) -> Self {
+        Self(SourceTextRepr::Owned(s))
+    }
+}
+
+impl std::ops::Deref for SourceText {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for SourceText {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl PartialEq for SourceText {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for SourceText {}
+
+impl fmt::Debug for SourceText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl fmt::Display for SourceText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
 /// A single source in the `SourceMap`.
 #[derive(Clone, Debug)]
 pub struct SourceFile {
@@ -208,7 +385,11 @@ pub struct SourceFile {
     /// (e.g., `<stdin>`).
     pub name: FileName,
     /// The complete source code.
-    pub src: Arc<String>,
+    ///
+    /// Empty if the source code was dropped with
+    /// [`SourceMap::drop_source_texts`](super::SourceMap::drop_source_texts); use
+    /// [`source_text`](Self::source_text) to read it in that case.
+    pub src: Arc<SourceText>,
     /// The source code's hash.
     pub src_hash: SourceFileHash,
     /// The start position of this source in the `SourceMap`.
@@ -225,6 +406,19 @@ pub struct SourceFile {
     /// files within the crate graph and for speeding up hashing in incremental
     /// compilation.
     pub stable_id: StableSourceFileId,
+    /// The spans that the ranges of a generated file were generated from, sorted by start position.
+    /// Empty for files that were not generated.
+    ///
+    /// See [`SourceMap::new_generated_file`](super::SourceMap::new_generated_file).
+    pub origins: Vec<SpanOrigin>,
+    /// The first byte that was not valid UTF-8 when the file was loaded, if any. See
+    /// [`decode_source`].
+    pub invalid_utf8: Option<InvalidUtf8>,
+    /// Whether `src` was dropped.
+    src_dropped: bool,
+    /// The source code read again from disk after it was dropped, or `None` if it could not be
+    /// read. See [`SourceMap::ensure_source_present`](super::SourceMap::ensure_source_present).
+    reloaded_src: OnceLock<Option<Arc<SourceText>>>,
 }
 
 impl SourceFile {
@@ -232,6 +426,16 @@ impl SourceFile {
         name: FileName,
         mut src: String,
         hash_kind: SourceFileHashAlgorithm,
+    ) -> Result<Self, OffsetOverflowError> {
+        src.shrink_to_fit();
+        Self::from_text(name, src.into(), hash_kind)
+    }
+
+    /// Creates a new source file from already loaded source text, such as a memory-mapped file.
+    pub fn from_text(
+        name: FileName,
+        src: SourceText,
+        hash_kind: SourceFileHashAlgorithm,
     ) -> Result<Self, OffsetOverflowError> {
         // Compute the file hash before any normalization.
         let src_hash = SourceFileHash::new(hash_kind, &src);
@@ -243,7 +447,6 @@ impl SourceFile {
 
         let (lines, multibyte_chars, non_narrow_chars) = super::analyze::analyze_source_file(&src);
 
-        src.shrink_to_fit();
         Ok(Self {
             name,
             src: Arc::new(src),
@@ -254,9 +457,59 @@ impl SourceFile {
             multibyte_chars,
             non_narrow_chars,
             stable_id,
+            origins: Vec::new(),
+            invalid_utf8: None,
+            src_dropped: false,
+            reloaded_src: OnceLock::new(),
         })
     }
 
+    /// Returns a copy of this file without its source code.
+    pub(crate) fn without_src(&self) -> Self {
+        Self {
+            src: Arc::new(String::new().into()),
+            src_dropped: true,
+            reloaded_src: OnceLock::new(),
+            ..self.clone()
+        }
+    }
+
+    /// Returns the source code, or `None` if it was dropped and could not be read again.
+    ///
+    /// Dropped source code is only available after calling
+    /// [`SourceMap::ensure_source_present`](super::SourceMap::ensure_source_present).
+    pub fn source_text(&self) -> Option<&str> {
+        if !self.src_dropped {
+            return Some(&self.src);
+        }
+        self.reloaded_src.get()?.as_deref().map(SourceText::as_str)
+    }
+
+    /// Returns `true` if the source code was dropped with
+    /// [`SourceMap::drop_source_texts`](super::SourceMap::drop_source_texts).
+    #[inline]
+    pub fn is_src_dropped(&self) -> bool {
+        self.src_dropped
+    }
+
+    /// Reads the dropped source code again from disk, if it was not already.
+    ///
+    /// The source code is only kept if it has the same length and hash as when it was loaded.
+    pub(crate) fn reload_src(&self) -> bool {
+        if !self.src_dropped {
+            return true;
+        }
+        self.reloaded_src
+            .get_or_init(|| {
+                let FileName::Real(path) = &self.name else { return None };
+                let (src, _) = decode_source(std::fs::read(path).ok()?);
+                let unchanged =
+                    src.len() == self.source_len.to_usize() && self.src_hash.matches(&src);
+                unchanged.then(|| Arc::new(src.into()))
+            })
+            .is_some()
+    }
+
     pub fn lines(&self) -> &[RelativeBytePos] {
         &self.lines
     }
@@ -388,7 +641,7 @@ impl SourceFile {
         }
 
         let start = self.lines().get(line_number)?.to_usize();
-        Some(get_until_newline(&self.src, start))
+        Some(get_until_newline(self.source_text()?, start))
     }
 
     /// Gets a slice of the source text between two lines, including the
@@ -405,7 +658,7 @@ impl SourceFile {
         let lines = self.lines();
         let start = lines.get(start)?.to_usize();
         let end = lines.get(end)?.to_usize();
-        Some(get_until_newline(&self.src, start, end))
+        Some(get_until_newline(self.source_text()?, start, end))
     }
 
     /// Returns whether or not the file contains the given `SourceMap` byte
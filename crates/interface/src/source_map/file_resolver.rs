@@ -2,10 +2,14 @@
 //!
 //! Modified from [`solang`](https://github.com/hyperledger/solang/blob/0f032dcec2c6e96797fd66fa0175a02be0aba71c/src/file_resolver.rs).
 
-use super::SourceFile;
-use crate::SourceMap;
+use super::{SourceFile, SourceFileHash};
+use crate::{edit_distance::find_best_match, SourceMap};
 use itertools::Itertools;
 use normalize_path::NormalizePath;
+use solar_data_structures::{
+    map::{FxHashMap, FxHashSet},
+    sync::Lock,
+};
 use std::{
     borrow::Cow,
     io,
@@ -24,17 +28,98 @@ pub enum ResolveError {
     NotFound(PathBuf),
     #[error("multiple files match {0}: {}", _1.iter().map(|f| f.name.display()).format(", "))]
     MultipleMatches(PathBuf, Vec<Arc<SourceFile>>),
+    #[error("invalid import map {0:?} in {1}: {2}")]
+    InvalidImportMap(String, PathBuf, &'static str),
+    #[error("file {0} is outside of the allowed paths")]
+    NotAllowed(PathBuf),
+}
+
+/// A single import map, AKA remapping: `[context:]map=path`.
+///
+/// If `context` is set, the import map only applies to imports in files whose path starts with it.
+///
+/// See <https://docs.soliditylang.org/en/latest/path-resolution.html#import-remapping>.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImportMap {
+    pub context: Option<PathBuf>,
+    pub map: PathBuf,
+    pub path: PathBuf,
+}
+
+impl std::str::FromStr for ImportMap {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((lhs, path)) = s.split_once('=') else { return Err("missing '='") };
+        let (context, map) = match lhs.split_once(':') {
+            Some((context, map)) => (Some(context).filter(|c| !c.is_empty()), map),
+            None => (None, lhs),
+        };
+        if map.is_empty() {
+            return Err("empty prefix");
+        }
+        Ok(Self { context: context.map(Into::into), map: map.into(), path: path.into() })
+    }
 }
 
+#[derive(Clone)]
 pub struct FileResolver<'a> {
     source_map: &'a SourceMap,
-    import_paths: Vec<(Option<PathBuf>, PathBuf)>,
+    import_paths: Vec<PathBuf>,
+    import_maps: Vec<ImportMap>,
+    /// In-memory files, keyed by their absolute normalized path.
+    virtual_files: FxHashMap<PathBuf, Arc<SourceFile>>,
+    /// Whether to look for non-relative imports in `node_modules` directories.
+    node_modules: bool,
+    /// The canonical directories that files can be read from, if restricted.
+    allowed_paths: Option<Vec<PathBuf>>,
+    /// The files loaded from disk, keyed by their canonical path and content hash, so that a file
+    /// reached through different paths is only loaded once.
+    loaded_files: Arc<Lock<FxHashMap<(PathBuf, SourceFileHash), Arc<SourceFile>>>>,
+    /// The size in bytes from which files are memory-mapped instead of read, if enabled.
+    mmap_threshold: Option<u64>,
+    /// The paths that were looked up on disk but did not exist.
+    missing_files: Arc<Lock<FxHashSet<PathBuf>>>,
 }
 
 impl<'a> FileResolver<'a> {
     /// Creates a new file resolver.
     pub fn new(source_map: &'a SourceMap) -> Self {
-        Self { source_map, import_paths: Vec::new() }
+        Self {
+            source_map,
+            import_paths: Vec::new(),
+            import_maps: Vec::new(),
+            virtual_files: FxHashMap::default(),
+            node_modules: false,
+            allowed_paths: None,
+            loaded_files: Default::default(),
+            mmap_threshold: None,
+            missing_files: Default::default(),
+        }
+    }
+
+    /// Sets whether to resolve non-relative imports in `node_modules` directories, like Hardhat.
+    ///
+    /// If enabled, imports that are not found in the import paths, such as
+    /// `@openzeppelin/contracts/token/ERC20/ERC20.sol`, are looked up in the `node_modules`
+    /// directory of the importing file's directory and of each of its ancestors, from the closest
+    /// one. Disabled by default.
+    pub fn set_node_modules(&mut self, enabled: bool) {
+        self.node_modules = enabled;
+    }
+
+    /// Sets the size in bytes from which files are memory-mapped instead of read into memory, or
+    /// `None` to always read them. Disabled by default.
+    ///
+    /// Mapped files are not copied into memory, but are read from the page cache on demand. See
+    /// [`SourceMap::load_file_mapped`].
+    ///
+    /// # Safety
+    ///
+    /// Files at least `threshold` bytes large must not be modified while they are loaded in the
+    /// source map.
+    pub unsafe fn set_mmap_threshold(&mut self, threshold: Option<u64>) {
+        self.mmap_threshold = threshold;
     }
 
     /// Returns the source map.
@@ -44,37 +129,141 @@ impl<'a> FileResolver<'a> {
 
     /// Adds an import path. Returns `true` if the path is newly inserted.
     pub fn add_import_path(&mut self, path: PathBuf) -> bool {
-        let entry = (None, path);
-        let new = !self.import_paths.contains(&entry);
+        let new = !self.import_paths.contains(&path);
         if new {
-            self.import_paths.push(entry);
+            self.import_paths.push(path);
         }
         new
     }
 
-    /// Adds an import map.
+    /// Restricts reading files from disk to the given directories and their subdirectories, like
+    /// solc's `--allow-paths`.
+    ///
+    /// Can be called multiple times to allow more paths. In-memory files are always allowed. By
+    /// default, all paths are allowed.
+    pub fn allow_paths(&mut self, paths: impl IntoIterator<Item = PathBuf>) {
+        let allowed_paths = self.allowed_paths.get_or_insert_with(Vec::new);
+        for path in paths {
+            let path = if path.as_os_str().is_empty() { PathBuf::from(".") } else { path };
+            let path = crate::canonicalize(&path).unwrap_or_else(|_| path.normalize());
+            if !allowed_paths.contains(&path) {
+                allowed_paths.push(path);
+            }
+        }
+    }
+
+    /// Returns `true` if the file at `path` can be read from disk.
+    ///
+    /// Paths that don't exist are allowed, as they can't be read anyway.
+    pub fn is_allowed(&self, path: &Path) -> bool {
+        let Some(allowed_paths) = &self.allowed_paths else { return true };
+        match crate::canonicalize(path) {
+            Ok(path) => allowed_paths.iter().any(|allowed| path.starts_with(allowed)),
+            Err(_) => true,
+        }
+    }
+
+    /// Adds an import map that applies to all files.
     pub fn add_import_map(&mut self, map: PathBuf, path: PathBuf) {
-        let map = Some(map);
-        if let Some((_, e)) = self.import_paths.iter_mut().find(|(k, _)| *k == map) {
-            *e = path;
+        self.insert_import_map(ImportMap { context: None, map, path });
+    }
+
+    /// Adds an import map, replacing the path of an existing one with the same context and map.
+    pub fn insert_import_map(&mut self, import_map: ImportMap) {
+        if let Some(e) = self
+            .import_maps
+            .iter_mut()
+            .find(|e| e.context == import_map.context && e.map == import_map.map)
+        {
+            e.path = import_map.path;
         } else {
-            self.import_paths.push((map, path));
+            self.import_maps.push(import_map);
+        }
+    }
+
+    /// Loads the import maps from the `remappings.txt` file in `root`, if it exists.
+    ///
+    /// Each non-empty line is parsed as an [`ImportMap`]. Relative paths are resolved against
+    /// `root`. Returns the number of import maps that were loaded.
+    pub fn load_remappings_txt(&mut self, root: &Path) -> Result<usize, ResolveError> {
+        let file = root.join("remappings.txt");
+        if !file.is_file() {
+            return Ok(0);
+        }
+        let contents =
+            std::fs::read_to_string(&file).map_err(|e| ResolveError::ReadFile(file.clone(), e))?;
+        let mut count = 0;
+        for line in contents.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut import_map: ImportMap = line
+                .parse()
+                .map_err(|e| ResolveError::InvalidImportMap(line.to_string(), file.clone(), e))?;
+            import_map.path = root.join(&import_map.path);
+            if let Some(context) = &mut import_map.context {
+                *context = root.join(&*context);
+            }
+            self.insert_import_map(import_map);
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Adds an in-memory file with the given contents.
+    ///
+    /// During resolution, virtual files take precedence over the files on disk with the same path,
+    /// which do not have to exist. The file is added to the source map under `path`; if the source
+    /// map already contains a file with this name, that file is used and `contents` is ignored.
+    pub fn add_virtual_file(
+        &mut self,
+        path: PathBuf,
+        contents: String,
+    ) -> io::Result<Arc<SourceFile>> {
+        let file = self.source_map.new_source_file(path.normalize().into(), || Ok(contents))?;
+        self.virtual_files.insert(virtual_file_key(&path), file.clone());
+        Ok(file)
+    }
+
+    /// Returns the virtual file added with [`add_virtual_file`](Self::add_virtual_file) at `path`,
+    /// if any.
+    pub fn get_virtual_file(&self, path: &Path) -> Option<&Arc<SourceFile>> {
+        if self.virtual_files.is_empty() {
+            return None;
         }
+        self.virtual_files.get(&virtual_file_key(path))
     }
 
-    /// Get the import path and the optional mapping corresponding to `import_no`.
-    pub fn get_import_path(&self, import_no: usize) -> Option<&(Option<PathBuf>, PathBuf)> {
+    /// Get the import path corresponding to `import_no`.
+    pub fn get_import_path(&self, import_no: usize) -> Option<&PathBuf> {
         self.import_paths.get(import_no)
     }
 
     /// Get the import paths
-    pub fn get_import_paths(&self) -> &[(Option<PathBuf>, PathBuf)] {
+    pub fn get_import_paths(&self) -> &[PathBuf] {
         self.import_paths.as_slice()
     }
 
-    /// Get the import path corresponding to a map
+    /// Get the import maps
+    pub fn get_import_maps(&self) -> &[ImportMap] {
+        self.import_maps.as_slice()
+    }
+
+    /// Get the import path corresponding to a map that applies to all files
     pub fn get_import_map(&self, map: &Path) -> Option<&PathBuf> {
-        self.import_paths.iter().find(|(m, _)| m.as_deref() == Some(map)).map(|(_, pb)| pb)
+        self.import_maps.iter().find(|m| m.context.is_none() && m.map == map).map(|m| &m.path)
+    }
+
+    /// Returns the paths that were looked up on disk when resolving files but did not exist, in
+    /// order.
+    ///
+    /// These include the candidates of each import in the import paths and `node_modules`
+    /// directories that don't contain it. Creating any of these files can change how the imports
+    /// are resolved. The paths are shared between the clones of the resolver.
+    pub fn missing_files(&self) -> Vec<PathBuf> {
+        let mut paths = self.missing_files.lock().iter().cloned().collect::<Vec<_>>();
+        paths.sort();
+        paths
     }
 
     /// Resolves an import path. `parent` is the path of the file that contains the import, if any.
@@ -111,14 +300,15 @@ impl<'a> FileResolver<'a> {
         }
 
         let original_path = path;
-        let path = self.remap_path(path);
+        let path = self.remap_path(path, parent);
         let mut result = Vec::with_capacity(1);
 
         // Walk over the import paths until we find one that resolves.
-        for import in &self.import_paths {
-            if let (None, import_path) = import {
-                let path = import_path.join(&path);
-                if let Some(file) = self.try_file(&path)? {
+        for import_path in &self.import_paths {
+            let path = import_path.join(&path);
+            if let Some(file) = self.try_file(&path)? {
+                // The same file may be reached through multiple import paths.
+                if !result.iter().any(|f| Arc::ptr_eq(f, &file)) {
                     result.push(file);
                 }
             }
@@ -127,12 +317,18 @@ impl<'a> FileResolver<'a> {
         // If there was no defined import path, then try the file directly. See
         // https://docs.soliditylang.org/en/latest/path-resolution.html#base-path-and-include-paths
         // "By default the base path is empty, which leaves the source unit name unchanged."
-        if !self.import_paths.iter().any(|(m, _)| m.is_none()) {
+        if self.import_paths.is_empty() {
             if let Some(file) = self.try_file(&path)? {
                 result.push(file);
             }
         }
 
+        if result.is_empty() && self.node_modules && !path.is_absolute() {
+            if let Some(file) = self.try_node_modules(&path, parent)? {
+                result.push(file);
+            }
+        }
+
         match result.len() {
             0 => Err(ResolveError::NotFound(original_path.into())),
             1 => Ok(result.pop().unwrap()),
@@ -140,19 +336,98 @@ impl<'a> FileResolver<'a> {
         }
     }
 
-    /// Applies the import path mappings to `path`.
-    #[instrument(level = "trace", skip_all, ret)]
-    pub fn remap_path<'b>(&self, path: &'b Path) -> Cow<'b, Path> {
-        let orig = path;
-        let mut remapped = Cow::Borrowed(path);
-        for import_path in &self.import_paths {
-            if let (Some(mapping), target) = import_path {
-                if let Ok(relpath) = orig.strip_prefix(mapping) {
-                    remapped = Cow::Owned(target.join(relpath));
-                }
+    /// Looks up `path` in the closest `node_modules` directory that contains it, starting from the
+    /// directory of `parent`, or the current directory.
+    fn try_node_modules(
+        &self,
+        path: &Path,
+        parent: Option<&Path>,
+    ) -> Result<Option<Arc<SourceFile>>, ResolveError> {
+        let curdir = std::env::current_dir().unwrap_or_default();
+        let base = parent.and_then(Path::parent).unwrap_or(Path::new(""));
+        let base = curdir.join(base).normalize();
+        for dir in base.ancestors() {
+            let candidate = dir.join("node_modules").join(path);
+            // Keep the paths relative to the current directory for shorter diagnostics output.
+            let candidate = match candidate.strip_prefix(&curdir) {
+                Ok(relative) => relative.to_path_buf(),
+                Err(_) => candidate,
+            };
+            if let Some(file) = self.try_file(&candidate)? {
+                return Ok(Some(file));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns an import path similar to `path` that points to an existing file, if any.
+    ///
+    /// Only the file name is corrected: the directories that [`resolve_file`](Self::resolve_file)
+    /// would look in are searched for a file with a similar name.
+    pub fn find_similar_file(&self, path: &Path, parent: Option<&Path>) -> Option<PathBuf> {
+        let file_name = path.file_name()?.to_str()?;
+        let mut dirs = Vec::new();
+        if path.starts_with("./") || path.starts_with("../") {
+            let base = parent?.parent().unwrap_or(Path::new("."));
+            dirs.push(base.join(path));
+        } else {
+            if parent.is_none() {
+                dirs.push(path.to_path_buf());
+            }
+            let path = self.remap_path(path, parent);
+            dirs.extend(self.import_paths.iter().map(|import_path| import_path.join(&path)));
+            if self.import_paths.is_empty() {
+                dirs.push(path.into_owned());
+            }
+        }
+
+        let mut candidates = Vec::new();
+        for dir in dirs.iter().filter_map(|path| path.parent()) {
+            let dir_or_cwd = if dir.as_os_str().is_empty() { Path::new(".") } else { dir };
+            if let Ok(entries) = std::fs::read_dir(dir_or_cwd) {
+                candidates.extend(
+                    entries
+                        .filter_map(Result::ok)
+                        .filter(|entry| entry.path().is_file())
+                        .filter_map(|entry| entry.file_name().into_string().ok()),
+                );
             }
+            let key = virtual_file_key(dir);
+            candidates.extend(
+                self.virtual_files
+                    .keys()
+                    .filter(|path| path.parent() == Some(key.as_path()))
+                    .filter_map(|path| Some(path.file_name()?.to_str()?.to_string())),
+            );
+        }
+        let best = find_best_match(candidates.iter().map(String::as_str), file_name)?;
+        Some(path.with_file_name(best))
+    }
+
+    /// Applies the import maps to `path`, which is imported from `parent`.
+    ///
+    /// Only import maps whose context is a prefix of `parent` are considered. If multiple maps
+    /// apply, the one with the longest context wins, then the one with the longest prefix, then
+    /// the one that was added last.
+    #[instrument(level = "trace", skip_all, ret)]
+    pub fn remap_path<'b>(&self, path: &'b Path, parent: Option<&Path>) -> Cow<'b, Path> {
+        let best = self
+            .import_maps
+            .iter()
+            .filter(|m| match (&m.context, parent) {
+                (None, _) => true,
+                (Some(context), Some(parent)) => parent.starts_with(context),
+                (Some(_), None) => false,
+            })
+            .filter_map(|m| Some((m, path.strip_prefix(&m.map).ok()?)))
+            .max_by_key(|(m, _)| {
+                let context_len = m.context.as_ref().map_or(0, |c| c.as_os_str().len());
+                (context_len, m.map.as_os_str().len())
+            });
+        match best {
+            Some((m, relpath)) => Cow::Owned(m.path.join(relpath)),
+            None => Cow::Borrowed(path),
         }
-        remapped
     }
 
     /// Loads stdin into the source map.
@@ -163,10 +438,19 @@ impl<'a> FileResolver<'a> {
     /// Loads `path` into the source map. Returns `None` if the file doesn't exist.
     #[instrument(level = "debug", skip_all)]
     pub fn try_file(&self, path: &Path) -> Result<Option<Arc<SourceFile>>, ResolveError> {
+        if let Some(file) = self.get_virtual_file(path) {
+            trace!("loaded virtual file");
+            return Ok(Some(file.clone()));
+        }
+
+        if !self.is_allowed(path) {
+            return Err(ResolveError::NotAllowed(path.into()));
+        }
+
         let cache_path = path.normalize();
-        if let Ok(file) = self.source_map().load_file(&cache_path) {
+        if let Ok(file) = self.load_file(&cache_path) {
             trace!("loaded from cache");
-            return Ok(Some(file));
+            return Ok(Some(self.dedup_file(path, file)));
         }
 
         if let Ok(path) = crate::canonicalize(path) {
@@ -180,13 +464,49 @@ impl<'a> FileResolver<'a> {
             }
             trace!("canonicalized to {}", path.display());
             return self
-                .source_map()
                 .load_file(path)
-                .map(Some)
+                .map(|file| Some(self.dedup_file(path, file)))
                 .map_err(|e| ResolveError::ReadFile(path.into(), e));
         }
 
         trace!("not found");
+        self.missing_files.lock().insert(path.to_path_buf());
         Ok(None)
     }
+
+    /// Loads the file at `path` into the source map, memory-mapping it if it is large enough.
+    fn load_file(&self, path: &Path) -> io::Result<Arc<SourceFile>> {
+        if let Some(threshold) = self.mmap_threshold {
+            if std::fs::metadata(path)?.len() >= threshold {
+                // SAFETY: Upheld by the caller of `set_mmap_threshold`.
+                return unsafe { self.source_map().load_file_mapped(path) };
+            }
+        }
+        self.source_map().load_file(path)
+    }
+
+    /// Returns the file previously loaded from the same canonical path with the same contents as
+    /// `file`, if any, or `file` otherwise.
+    ///
+    /// This deduplicates the files reached through symbolic links or through relative paths that
+    /// are not normalized to the same path.
+    fn dedup_file(&self, path: &Path, file: Arc<SourceFile>) -> Arc<SourceFile> {
+        let Ok(canonical) = crate::canonicalize(path) else { return file };
+        let mut loaded_files = self.loaded_files.lock();
+        let loaded = loaded_files.entry((canonical, file.src_hash)).or_insert_with(|| file.clone());
+        if !Arc::ptr_eq(loaded, &file) {
+            trace!(file = %file.name.display(), loaded = %loaded.name.display(), "deduplicated file");
+        }
+        loaded.clone()
+    }
+}
+
+/// Returns the key of a virtual file, so that relative and absolute paths to the same file match.
+fn virtual_file_key(path: &Path) -> PathBuf {
+    if path.is_relative() {
+        if let Ok(curdir) = std::env::current_dir() {
+            return curdir.join(path).normalize();
+        }
+    }
+    path.normalize()
 }
@@ -8,7 +8,10 @@ use solar_data_structures::{
 use std::{
     io::{self, Read},
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
 };
 
 mod analyze;
@@ -17,7 +20,7 @@ mod file;
 pub use file::*;
 
 mod file_resolver;
-pub use file_resolver::{FileResolver, ResolveError};
+pub use file_resolver::{FileResolver, ImportMap, ResolveError};
 
 #[cfg(test)]
 mod tests;
@@ -96,10 +99,12 @@ pub struct FileLines {
 }
 
 pub struct SourceMap {
-    // INVARIANT: The only operation allowed on `source_files` is `push`.
+    // INVARIANT: Sorted by start position. Files are only removed by `reload_file`.
     source_files: RwLock<Vec<Arc<SourceFile>>>,
     stable_id_to_source_file: scc::HashIndex<StableSourceFileId, Arc<SourceFile>, FxBuildHasher>,
     hash_kind: SourceFileHashAlgorithm,
+    /// Whether files were removed, leaving gaps between the positions of the remaining files.
+    has_gaps: AtomicBool,
 }
 
 impl Default for SourceMap {
@@ -115,6 +120,7 @@ impl SourceMap {
             source_files: RwLock::new(Vec::new()),
             stable_id_to_source_file: Default::default(),
             hash_kind,
+            has_gaps: AtomicBool::new(false),
         }
     }
 
@@ -124,17 +130,65 @@ impl SourceMap {
     }
 
     /// Loads a file from the given path.
+    ///
+    /// The contents are decoded with [`decode_source`], so files that are not valid UTF-8 are
+    /// loaded lossily instead of failing.
     pub fn load_file(&self, path: &Path) -> io::Result<Arc<SourceFile>> {
         let filename = path.to_owned().into();
-        self.new_source_file(filename, || std::fs::read_to_string(path))
+        self.new_decoded_source_file(filename, || std::fs::read(path))
+    }
+
+    /// Loads a file from the given path by memory-mapping it instead of reading it into memory.
+    ///
+    /// The file is not copied into memory, but read from the page cache on demand. Files that are
+    /// empty or not valid UTF-8, and all files on targets without memory mapping, are loaded with
+    /// [`load_file`](Self::load_file) instead.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified while the returned `SourceFile` is alive. See
+    /// [`SourceText::map`].
+    pub unsafe fn load_file_mapped(&self, path: &Path) -> io::Result<Arc<SourceFile>> {
+        #[cfg(not(target_family = "wasm"))]
+        {
+            let filename: FileName = path.to_owned().into();
+            let stable_id = StableSourceFileId::from_filename_in_current_crate(&filename);
+            if let Some(file) = self.source_file_by_stable_id(stable_id) {
+                return Ok(file);
+            }
+            // SAFETY: Upheld by the caller.
+            if let Some(src) = unsafe { SourceText::map(path)? } {
+                return self.new_source_file_with(filename, |filename| {
+                    Ok(SourceFile::from_text(filename, src, self.hash_kind)?)
+                });
+            }
+        }
+        self.load_file(path)
+    }
+
+    /// Reloads a file from the given path, replacing the file with the same name that was
+    /// previously loaded, if any.
+    ///
+    /// The previous file is removed from the source map, so the spans that point into it can no
+    /// longer be looked up, and its positions are reused by the files loaded afterwards. This
+    /// must not be called while such spans are still in use, such as during a compilation.
+    pub fn reload_file(&self, path: &Path) -> io::Result<Arc<SourceFile>> {
+        let filename: FileName = path.to_owned().into();
+        let stable_id = StableSourceFileId::from_filename_in_current_crate(&filename);
+        if let Some(old) = self.source_file_by_stable_id(stable_id) {
+            self.stable_id_to_source_file.remove(&stable_id);
+            self.source_files.write().retain(|file| !Arc::ptr_eq(file, &old));
+            self.has_gaps.store(true, Ordering::Relaxed);
+        }
+        self.new_decoded_source_file(filename, || std::fs::read(path))
     }
 
     /// Loads `stdin`.
     pub fn load_stdin(&self) -> io::Result<Arc<SourceFile>> {
-        self.new_source_file(FileName::Stdin, || {
-            let mut src = String::new();
-            io::stdin().read_to_string(&mut src)?;
-            Ok(src)
+        self.new_decoded_source_file(FileName::Stdin, || {
+            let mut bytes = Vec::new();
+            io::stdin().read_to_end(&mut bytes)?;
+            Ok(bytes)
         })
     }
 
@@ -157,12 +211,58 @@ impl SourceMap {
         &self,
         filename: FileName,
         get_src: impl FnOnce() -> io::Result<String>,
+    ) -> io::Result<Arc<SourceFile>> {
+        self.new_source_file_with(filename, |filename| {
+            Ok(SourceFile::new(filename, get_src()?, self.hash_kind)?)
+        })
+    }
+
+    /// Creates a new `SourceFile` from bytes that are decoded with [`decode_source`].
+    fn new_decoded_source_file(
+        &self,
+        filename: FileName,
+        get_bytes: impl FnOnce() -> io::Result<Vec<u8>>,
+    ) -> io::Result<Arc<SourceFile>> {
+        self.new_source_file_with(filename, |filename| {
+            let (src, invalid_utf8) = decode_source(get_bytes()?);
+            let mut file = SourceFile::new(filename, src, self.hash_kind)?;
+            file.invalid_utf8 = invalid_utf8;
+            Ok(file)
+        })
+    }
+
+    /// Creates a new `SourceFile` for generated code, such as Yul generated from Solidity, along
+    /// with the spans of the source code that ranges of it were generated from.
+    ///
+    /// Diagnostics with spans in the generated file point at the original source instead. See
+    /// [`original_span`](Self::original_span).
+    ///
+    /// If a file already exists in the `SourceMap` with the same ID, that file is returned
+    /// unmodified.
+    pub fn new_generated_file(
+        &self,
+        filename: FileName,
+        src: String,
+        mut origins: Vec<SpanOrigin>,
+    ) -> io::Result<Arc<SourceFile>> {
+        origins.sort_by_key(|origin| (origin.lo, origin.hi));
+        self.new_source_file_with(filename, |filename| {
+            let mut file = SourceFile::new(filename, src, self.hash_kind)?;
+            file.origins = origins;
+            Ok(file)
+        })
+    }
+
+    fn new_source_file_with(
+        &self,
+        filename: FileName,
+        make_file: impl FnOnce(FileName) -> io::Result<SourceFile>,
     ) -> io::Result<Arc<SourceFile>> {
         let stable_id = StableSourceFileId::from_filename_in_current_crate(&filename);
         match self.stable_id_to_source_file.entry(stable_id) {
             scc::hash_index::Entry::Occupied(entry) => Ok(entry.get().clone()),
             scc::hash_index::Entry::Vacant(entry) => {
-                let file = SourceFile::new(filename, get_src()?, self.hash_kind)?;
+                let file = make_file(filename)?;
                 let file = self.new_source_file_inner(file, stable_id)?;
                 entry.insert_entry(file.clone());
                 Ok(file)
@@ -183,16 +283,30 @@ impl SourceMap {
 
         let mut source_files = self.source_files.write();
 
-        file.start_pos = BytePos(if let Some(last_file) = source_files.last() {
+        // Reuse the positions of the removed files if there is enough space before a file.
+        let len = u64::from(file.source_len.to_u32());
+        let gap = if self.has_gaps.load(Ordering::Relaxed) {
+            let starts = std::iter::once(0)
+                .chain(source_files.iter().map(|file| u64::from(file.end_position().0) + 1));
+            starts
+                .zip(source_files.iter())
+                .position(|(start, next)| start + len < u64::from(next.start_pos.0))
+        } else {
+            None
+        };
+        let index = gap.unwrap_or(source_files.len());
+
+        let prev_file = index.checked_sub(1).map(|i| &source_files[i]);
+        file.start_pos = BytePos(if let Some(prev_file) = prev_file {
             // Add one so there is some space between files. This lets us distinguish
             // positions in the `SourceMap`, even in the presence of zero-length files.
-            last_file.end_position().0.checked_add(1).ok_or(OffsetOverflowError(()))?
+            prev_file.end_position().0.checked_add(1).ok_or(OffsetOverflowError(()))?
         } else {
             0
         });
 
         let file = Arc::new(file);
-        source_files.push(file.clone());
+        source_files.insert(index, file.clone());
 
         Ok(file)
     }
@@ -201,6 +315,53 @@ impl SourceMap {
         self.source_files.read()
     }
 
+    /// Drops the source code of the files loaded from disk to reduce memory usage, such as after
+    /// the sources have been parsed.
+    ///
+    /// The files are replaced with copies without their source code, but with the same positions
+    /// and line tables. The source code is freed once the previous files are no longer referenced,
+    /// and is read again from disk when it is needed with
+    /// [`ensure_source_present`](Self::ensure_source_present).
+    pub fn drop_source_texts(&self) {
+        let replaced = self
+            .files()
+            .iter()
+            .filter(|file| !file.is_src_dropped() && matches!(file.name, FileName::Real(_)))
+            .map(|file| (file.clone(), Arc::new(file.without_src())))
+            .collect::<Vec<_>>();
+        for (old, new) in &replaced {
+            // Files that were replaced with `reload_file` are no longer looked up by name.
+            let current = self
+                .stable_id_to_source_file
+                .get(&old.stable_id)
+                .as_deref()
+                .is_some_and(|file| Arc::ptr_eq(file, old));
+            if current {
+                self.stable_id_to_source_file.remove(&old.stable_id);
+                let _ = self.stable_id_to_source_file.insert(old.stable_id, new.clone());
+            }
+        }
+        let mut source_files = self.source_files.write();
+        for (old, new) in replaced {
+            if let Ok(i) = source_files.binary_search_by_key(&old.start_pos, |file| file.start_pos)
+            {
+                if Arc::ptr_eq(&source_files[i], &old) {
+                    source_files[i] = new;
+                }
+            }
+        }
+    }
+
+    /// Reads the source code of `file` again from disk if it was dropped with
+    /// [`drop_source_texts`](Self::drop_source_texts), so that it is available with
+    /// [`SourceFile::source_text`].
+    ///
+    /// Returns `false` if the source code is not available, because the file could not be read or
+    /// was modified since it was loaded.
+    pub fn ensure_source_present(&self, file: &SourceFile) -> bool {
+        file.reload_src()
+    }
+
     pub fn source_file_by_file_name(&self, filename: &FileName) -> Option<Arc<SourceFile>> {
         let stable_id = StableSourceFileId::from_filename_in_current_crate(filename);
         self.source_file_by_stable_id(stable_id)
@@ -217,6 +378,36 @@ impl SourceMap {
         filename.display()
     }
 
+    /// Maps a span in a generated file to the span of the source code that it was generated from.
+    ///
+    /// The innermost range of the generated file that contains the whole span is used. This is
+    /// repeated for code generated from other generated code. Spans that are not in a generated
+    /// file, or that are not contained in any of its ranges, are returned unchanged.
+    ///
+    /// See [`new_generated_file`](Self::new_generated_file).
+    pub fn original_span(&self, mut span: Span) -> Span {
+        while !span.is_dummy() {
+            let file = self.lookup_source_file(span.lo());
+            if file.origins.is_empty() || !file.contains(span.hi()) {
+                break;
+            }
+            let lo = file.relative_position(span.lo());
+            let hi = file.relative_position(span.hi());
+            let origin = file
+                .origins
+                .iter()
+                .filter(|origin| origin.lo <= lo && hi <= origin.hi)
+                .min_by_key(|origin| origin.hi.0 - origin.lo.0);
+            // Generated files can only be generated from files that were added before them, which
+            // also guarantees that this terminates.
+            match origin {
+                Some(origin) if origin.span.hi() < file.start_pos => span = origin.span,
+                _ => break,
+            }
+        }
+        span
+    }
+
     /// Returns `true` if the given span is multi-line.
     pub fn is_multiline(&self, span: Span) -> bool {
         let lo = self.lookup_source_file_idx(span.lo());
@@ -325,6 +516,9 @@ impl SourceMap {
         // FIXME: now that we handle DUMMY_SP up above, we should consider
         // asserting that the line numbers here are all indeed 1-based.
         let hi_line = hi.line.saturating_sub(1);
+        if lo.line < hi.line {
+            self.ensure_source_present(&lo.file);
+        }
         for line_index in lo.line.saturating_sub(1)..hi_line {
             let line_len = lo.file.get_line(line_index).map_or(0, |s| s.chars().count());
             lines.push(LineInfo { line_index, start_col, end_col: CharPos::from_usize(line_len) });
@@ -353,7 +547,11 @@ impl SourceMap {
                 end: (local_end.sf.name.clone(), local_end.sf.start_pos),
             })))
         } else {
-            // self.ensure_source_file_source_present(&local_begin.sf);
+            if !self.ensure_source_present(&local_begin.sf) {
+                return Err(SpanSnippetError::SourceNotAvailable {
+                    filename: local_begin.sf.name.clone(),
+                });
+            }
 
             let start_index = local_begin.pos.to_usize();
             let end_index = local_end.pos.to_usize();
@@ -368,7 +566,7 @@ impl SourceMap {
                 }));
             }
 
-            extract_source(&local_begin.sf.src, start_index, end_index)
+            extract_source(local_begin.sf.source_text().unwrap(), start_index, end_index)
         }
     }
 
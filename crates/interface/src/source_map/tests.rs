@@ -1,4 +1,5 @@
 use super::*;
+use crate::{BytePos, CharPos, RelativeBytePos};
 use std::path::PathBuf;
 
 fn init_source_map() -> SourceMap {
@@ -202,6 +203,51 @@ fn t9() {
     assert_eq!(sstr, "blork.rs:2:1: 2:12");
 }
 
+/// Tests mapping spans in generated files back to the source that they were generated from.
+#[test]
+fn generated_file_original_span() {
+    let sm = SourceMap::empty();
+    let user = sm.new_dummy_source_file(PathBuf::from("a.sol"), "uint x = 1;".to_string()).unwrap();
+    let x = Span::new(user.start_pos + BytePos(5), user.start_pos + BytePos(6));
+    let stmt = Span::new(user.start_pos, user.start_pos + BytePos(11));
+
+    let origins = vec![
+        SpanOrigin { lo: RelativeBytePos(4), hi: RelativeBytePos(5), span: x },
+        SpanOrigin { lo: RelativeBytePos(0), hi: RelativeBytePos(10), span: stmt },
+    ];
+    let generated = sm
+        .new_generated_file(FileName::Custom("a.yul".into()), "let x := 1\n".to_string(), origins)
+        .unwrap();
+    let span = |lo: u32, hi: u32| {
+        Span::new(generated.start_pos + BytePos(lo), generated.start_pos + BytePos(hi))
+    };
+
+    assert_eq!(sm.original_span(span(4, 5)), x);
+    assert_eq!(sm.original_span(span(0, 3)), stmt);
+    assert_eq!(sm.original_span(span(9, 11)), span(9, 11));
+    assert_eq!(sm.original_span(x), x);
+    assert_eq!(sm.original_span(Span::DUMMY), Span::DUMMY);
+}
+
+/// Tests decoding source files with byte order marks and invalid UTF-8.
+#[test]
+fn decode_source_files() {
+    let invalid = |pos: u32, offset: usize| Some(InvalidUtf8 { pos: RelativeBytePos(pos), offset });
+
+    assert_eq!(decode_source(b"a\xC3\xA9b".to_vec()), ("a\u{e9}b".to_string(), None));
+    assert_eq!(decode_source(b"\xEF\xBB\xBFab".to_vec()), ("ab".to_string(), None));
+    assert_eq!(
+        decode_source(b"\xEF\xBB\xBFa\xFFb\xFF".to_vec()),
+        ("a\u{FFFD}b\u{FFFD}".to_string(), invalid(1, 4))
+    );
+    assert_eq!(decode_source(b"\xFF\xFEa\0b\0".to_vec()), ("ab".to_string(), None));
+    assert_eq!(decode_source(b"\xFE\xFF\0a\0b".to_vec()), ("ab".to_string(), None));
+    assert_eq!(
+        decode_source(b"\xFF\xFEa\0\x00\xD8b\0c".to_vec()),
+        ("a\u{FFFD}b\u{FFFD}".to_string(), invalid(1, 4))
+    );
+}
+
 /// Tests failing to merge two spans on different lines.
 #[test]
 fn span_merging_fail() {
@@ -625,3 +671,274 @@ fn read_binary_file_handles_lying_stat() {
     let bin = RealFileLoader.read_binary_file(kernel_max).unwrap();
     assert_eq!(&real[..], &bin[..]);
 }
+
+#[test]
+fn parse_import_map() {
+    let map: ImportMap = "a/=b/".parse().unwrap();
+    assert_eq!(map, ImportMap { context: None, map: "a/".into(), path: "b/".into() });
+    let map: ImportMap = "ctx:a/=b/".parse().unwrap();
+    assert_eq!(map, ImportMap { context: Some("ctx".into()), map: "a/".into(), path: "b/".into() });
+    let map: ImportMap = ":a/=b/".parse().unwrap();
+    assert_eq!(map.context, None);
+    assert!("a/".parse::<ImportMap>().is_err());
+    assert!("ctx:=b/".parse::<ImportMap>().is_err());
+}
+
+#[test]
+fn remap_path_with_context() {
+    let sm = SourceMap::empty();
+    let mut resolver = FileResolver::new(&sm);
+    resolver.add_import_map("dep/".into(), "lib/dep/".into());
+    resolver.add_import_map("dep/src/".into(), "lib/dep/contracts/".into());
+    resolver.insert_import_map("lib/other:dep/=lib/other/lib/dep/".parse().unwrap());
+
+    let remap = |path: &str, parent: Option<&str>| {
+        resolver.remap_path(Path::new(path), parent.map(Path::new)).into_owned()
+    };
+    assert_eq!(remap("dep/A.sol", None), Path::new("lib/dep/A.sol"));
+    assert_eq!(remap("dep/src/A.sol", None), Path::new("lib/dep/contracts/A.sol"));
+    assert_eq!(remap("dep/A.sol", Some("src/B.sol")), Path::new("lib/dep/A.sol"));
+    assert_eq!(remap("dep/A.sol", Some("lib/other/B.sol")), Path::new("lib/other/lib/dep/A.sol"));
+    assert_eq!(remap("other/A.sol", Some("lib/other/B.sol")), Path::new("other/A.sol"));
+}
+
+#[test]
+fn resolve_virtual_files() {
+    let sm = SourceMap::empty();
+    let mut resolver = FileResolver::new(&sm);
+    resolver.add_import_map("dep/".into(), "lib/dep/".into());
+    let a = resolver.add_virtual_file("virtual/A.sol".into(), "contract A {}".into()).unwrap();
+    let b = resolver.add_virtual_file("lib/dep/B.sol".into(), "contract B {}".into()).unwrap();
+    let cargo_toml = resolver.add_virtual_file("Cargo.toml".into(), "overlay".into()).unwrap();
+
+    let resolve = |path: &str, parent: Option<&str>| {
+        resolver.resolve_file(Path::new(path), parent.map(Path::new)).unwrap()
+    };
+    assert!(Arc::ptr_eq(&resolve("virtual/A.sol", None), &a));
+    assert!(Arc::ptr_eq(&resolve("./A.sol", Some("virtual/C.sol")), &a));
+    assert!(Arc::ptr_eq(&resolve("../virtual/A.sol", Some("other/C.sol")), &a));
+    assert!(Arc::ptr_eq(&resolve("dep/B.sol", Some("virtual/A.sol")), &b));
+    let abs = std::env::current_dir().unwrap().join("virtual/A.sol");
+    assert!(Arc::ptr_eq(&resolve(abs.to_str().unwrap(), None), &a));
+
+    // Virtual files take precedence over the files on disk.
+    let file = resolve("Cargo.toml", None);
+    assert!(Arc::ptr_eq(&file, &cargo_toml));
+    assert_eq!(file.src.as_str(), "overlay");
+
+    assert!(resolver.resolve_file(Path::new("virtual/D.sol"), None).is_err());
+}
+
+#[test]
+fn find_similar_virtual_files() {
+    let sm = SourceMap::empty();
+    let mut resolver = FileResolver::new(&sm);
+    resolver.add_import_map("dep/".into(), "lib/dep/".into());
+    resolver.add_virtual_file("virtual/Token.sol".into(), "contract Token {}".into()).unwrap();
+    resolver.add_virtual_file("lib/dep/ERC20.sol".into(), "contract ERC20 {}".into()).unwrap();
+
+    let similar = |path: &str, parent: Option<&str>| {
+        resolver.find_similar_file(Path::new(path), parent.map(Path::new))
+    };
+    assert_eq!(similar("virtual/Tokne.sol", None), Some("virtual/Token.sol".into()));
+    assert_eq!(similar("./token.sol", Some("virtual/A.sol")), Some("./Token.sol".into()));
+    assert_eq!(similar("dep/ERC02.sol", Some("virtual/A.sol")), Some("dep/ERC20.sol".into()));
+    assert_eq!(similar("virtual/Vault.sol", None), None);
+}
+
+#[test]
+#[cfg(unix)]
+fn dedup_symlinked_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let dir = dir.path();
+    std::fs::create_dir_all(dir.join("real")).unwrap();
+    std::fs::write(dir.join("real/A.sol"), "contract A {}").unwrap();
+    std::os::unix::fs::symlink(dir.join("real"), dir.join("link")).unwrap();
+
+    let sm = SourceMap::empty();
+    let mut resolver = FileResolver::new(&sm);
+    let real = resolver.resolve_file(&dir.join("real/A.sol"), None).unwrap();
+    let link = resolver.resolve_file(&dir.join("link/A.sol"), None).unwrap();
+    assert!(Arc::ptr_eq(&real, &link));
+
+    // Both import paths resolve to the same file, which is not ambiguous.
+    resolver.add_import_path(dir.join("real"));
+    resolver.add_import_path(dir.join("link"));
+    let file = resolver.resolve_file(Path::new("A.sol"), Some(Path::new("B.sol"))).unwrap();
+    assert!(Arc::ptr_eq(&file, &real));
+}
+
+#[test]
+fn resolve_node_modules() {
+    let dir = tempfile::tempdir().unwrap();
+    let dir = dir.path();
+    let package = dir.join("node_modules/@oz/contracts");
+    std::fs::create_dir_all(&package).unwrap();
+    std::fs::create_dir_all(dir.join("src/token")).unwrap();
+    std::fs::write(package.join("ERC20.sol"), "contract ERC20 {}").unwrap();
+
+    let sm = SourceMap::empty();
+    let mut resolver = FileResolver::new(&sm);
+    let path = Path::new("@oz/contracts/ERC20.sol");
+    let parent = dir.join("src/token/Token.sol");
+    assert!(resolver.resolve_file(path, Some(&parent)).is_err());
+
+    resolver.set_node_modules(true);
+    let file = resolver.resolve_file(path, Some(&parent)).unwrap();
+    assert_eq!(file.src.as_str(), "contract ERC20 {}");
+    assert!(resolver.resolve_file(Path::new("@oz/contracts/ERC721.sol"), Some(&parent)).is_err());
+}
+
+#[test]
+fn resolve_allowed_paths() {
+    let dir = tempfile::tempdir().unwrap();
+    let dir = dir.path();
+    std::fs::create_dir_all(dir.join("src")).unwrap();
+    std::fs::create_dir_all(dir.join("lib")).unwrap();
+    std::fs::write(dir.join("src/A.sol"), "contract A {}").unwrap();
+    std::fs::write(dir.join("lib/B.sol"), "contract B {}").unwrap();
+
+    let sm = SourceMap::empty();
+    let mut resolver = FileResolver::new(&sm);
+    resolver.add_virtual_file("virtual/C.sol".into(), "contract C {}".into()).unwrap();
+    resolver.allow_paths([dir.join("src")]);
+    assert!(resolver.resolve_file(&dir.join("src/A.sol"), None).is_ok());
+    assert!(matches!(
+        resolver.resolve_file(&dir.join("lib/B.sol"), None),
+        Err(ResolveError::NotAllowed(_))
+    ));
+    assert!(matches!(
+        resolver.resolve_file(&dir.join("src/../lib/B.sol"), None),
+        Err(ResolveError::NotAllowed(_))
+    ));
+    assert!(resolver.resolve_file(Path::new("virtual/C.sol"), None).is_ok());
+
+    resolver.allow_paths([dir.join("lib")]);
+    assert!(resolver.resolve_file(&dir.join("lib/B.sol"), None).is_ok());
+}
+
+#[test]
+fn line_tables() {
+    let sm = SourceMap::empty();
+    let file = sm.new_dummy_source_file(PathBuf::from("lines.sol"), "α\nβγ\n".into()).unwrap();
+    assert_eq!(file.lines(), [RelativeBytePos(0), RelativeBytePos(3)]);
+    assert_eq!(file.multibyte_chars.len(), 3);
+    assert_eq!(sm.lookup_char_pos(file.start_pos + BytePos(5)).col, CharPos(1));
+}
+
+#[test]
+fn resolve_mapped_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let dir = dir.path();
+    std::fs::write(dir.join("Big.sol"), "\u{FEFF}contract Big {\n}\n").unwrap();
+    std::fs::write(dir.join("Small.sol"), "contract Small {}").unwrap();
+    std::fs::write(dir.join("Invalid.sol"), b"contract Invalid \xFF {}").unwrap();
+
+    let sm = SourceMap::empty();
+    let mut resolver = FileResolver::new(&sm);
+    // SAFETY: The files are not modified.
+    unsafe { resolver.set_mmap_threshold(Some(20)) };
+    let big = resolver.resolve_file(&dir.join("Big.sol"), None).unwrap();
+    assert_eq!(big.src.is_mapped(), cfg!(not(target_family = "wasm")));
+    assert_eq!(big.src.as_str(), "contract Big {\n}\n");
+    assert_eq!(big.lines(), [RelativeBytePos(0), RelativeBytePos(15)]);
+    let small = resolver.resolve_file(&dir.join("Small.sol"), None).unwrap();
+    assert!(!small.src.is_mapped());
+    let invalid = resolver.resolve_file(&dir.join("Invalid.sol"), None).unwrap();
+    assert!(!invalid.src.is_mapped());
+    assert_eq!(invalid.src.as_str(), "contract Invalid \u{FFFD} {}");
+    assert!(invalid.invalid_utf8.is_some());
+}
+
+#[test]
+fn mapped_and_read_files_have_the_same_hash() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("A.sol");
+    std::fs::write(&path, "\u{FEFF}contract A {}\n").unwrap();
+
+    let read_sm = SourceMap::empty();
+    let read = FileResolver::new(&read_sm).resolve_file(&path, None).unwrap();
+    let mapped_sm = SourceMap::empty();
+    let mut resolver = FileResolver::new(&mapped_sm);
+    // SAFETY: The file is not modified.
+    unsafe { resolver.set_mmap_threshold(Some(0)) };
+    let mapped = resolver.resolve_file(&path, None).unwrap();
+    assert_eq!(mapped.src.is_mapped(), cfg!(not(target_family = "wasm")));
+    assert!(!read.src.is_mapped());
+
+    // Files are deduplicated by their hash, which must not depend on how they were loaded.
+    assert_eq!(mapped.src.as_str(), read.src.as_str());
+    assert!(mapped.src_hash == read.src_hash);
+}
+
+#[test]
+fn drop_source_texts() {
+    let dir = tempfile::tempdir().unwrap();
+    let a = dir.path().join("A.sol");
+    let b = dir.path().join("B.sol");
+    std::fs::write(&a, "contract A {\n    uint x;\n}\n").unwrap();
+    std::fs::write(&b, "contract B {}\n").unwrap();
+
+    let sm = SourceMap::empty();
+    let a_file = sm.load_file(&a).unwrap();
+    let b_file = sm.load_file(&b).unwrap();
+    let stdin = sm.new_source_file(FileName::Stdin, || Ok("contract C {}".to_string())).unwrap();
+    let span = Span::new(a_file.start_pos + 17, a_file.start_pos + 23);
+    assert_eq!(sm.span_to_snippet(span).unwrap(), "uint x");
+
+    sm.drop_source_texts();
+    let dropped = sm.lookup_source_file(a_file.start_pos);
+    assert!(dropped.is_src_dropped());
+    assert_eq!(dropped.src.as_str(), "");
+    assert_eq!(dropped.start_pos, a_file.start_pos);
+    assert_eq!(dropped.lines, a_file.lines);
+    assert_eq!(dropped.source_text(), None);
+    assert_eq!(dropped.get_line(1), None);
+    // Files not loaded from disk keep their source code.
+    assert!(!sm.lookup_source_file(stdin.start_pos).is_src_dropped());
+
+    // The source code is read again when needed.
+    assert_eq!(sm.span_to_snippet(span).unwrap(), "uint x");
+    assert_eq!(dropped.get_line(1), Some("    uint x;"));
+
+    // Files modified since they were loaded are not read again.
+    std::fs::write(&b, "contract B { }\n").unwrap();
+    let b_dropped = sm.lookup_source_file(b_file.start_pos);
+    assert!(!sm.ensure_source_present(&b_dropped));
+    assert_eq!(b_dropped.get_line(0), None);
+    assert!(sm.span_to_snippet(Span::new(b_file.start_pos, b_file.start_pos + 8)).is_err());
+}
+
+#[test]
+fn reload_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let a = dir.path().join("A.sol");
+    let b = dir.path().join("B.sol");
+    std::fs::write(&a, "contract A {}").unwrap();
+    std::fs::write(&b, "contract B {}").unwrap();
+
+    let sm = SourceMap::empty();
+    let old_a = sm.load_file(&a).unwrap();
+    let old_b = sm.load_file(&b).unwrap();
+
+    // The previous file is removed.
+    std::fs::write(&a, "contract A2 {}").unwrap();
+    let new_a = sm.reload_file(&a).unwrap();
+    assert_eq!(new_a.src.as_str(), "contract A2 {}");
+    assert_eq!(sm.files().len(), 2);
+    assert!(Arc::ptr_eq(&sm.lookup_source_file(new_a.start_pos), &new_a));
+    assert!(Arc::ptr_eq(&sm.load_file(&a).unwrap(), &new_a));
+    assert!(new_a.start_pos > old_b.end_position());
+
+    // Smaller files reuse the positions of the removed files.
+    std::fs::write(&a, "contract A {}").unwrap();
+    let newer_a = sm.reload_file(&a).unwrap();
+    std::fs::write(&b, "contract B {}").unwrap();
+    let new_b = sm.reload_file(&b).unwrap();
+    assert_eq!(sm.files().len(), 2);
+    assert_eq!(newer_a.start_pos, old_a.start_pos);
+    assert_eq!(new_b.start_pos, old_b.start_pos);
+    for file in [&newer_a, &new_b] {
+        assert!(Arc::ptr_eq(&sm.lookup_source_file(file.start_pos), file));
+    }
+}
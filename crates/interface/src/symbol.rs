@@ -864,6 +864,7 @@ symbols! {
         encodeWithSelector,
         encodeWithSignature,
         error,
+        evmasm,
         experimental,
         from,
         gasleft,
@@ -871,19 +872,23 @@ symbols! {
         interfaceId,
         length,
         max,
+        memory_dash_safe: "memory-safe",
         min,
         msg,
         name,
         object,
+        offset,
         push,
         require,
         ripemd160,
         runtimeCode,
+        salt,
         selector,
         send,
         sender,
         sha256,
         sig,
+        slot,
         solidity,
         super_: "super",
         this,
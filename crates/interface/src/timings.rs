@@ -0,0 +1,104 @@
+//! Wall time and statistics of the compiler passes, recorded with `-Ztime-passes`.
+
+use solar_data_structures::sync::Lock;
+use std::time::{Duration, Instant};
+
+/// A compiler pass recorded with [`Session::time`](crate::Session::time).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PassTiming {
+    /// The name of the pass.
+    pub name: &'static str,
+    /// The wall time spent in the pass.
+    pub time: Duration,
+    /// The number of source files processed by the pass, if known.
+    pub files: Option<usize>,
+    /// The number of source bytes processed by the pass, if known.
+    pub bytes: Option<usize>,
+    /// The number of AST nodes produced by the pass, if known.
+    pub nodes: Option<usize>,
+    /// The total size in bytes of the code before and after the pass, if the pass optimizes
+    /// code.
+    pub code_size: Option<(usize, usize)>,
+}
+
+/// The passes recorded in a session, in the order they finished.
+#[derive(Debug, Default)]
+pub struct Timings {
+    passes: Lock<Vec<PassTiming>>,
+}
+
+impl Timings {
+    /// Runs `f` and records its wall time as the pass `name`.
+    pub fn time<R>(&self, name: &'static str, f: impl FnOnce() -> R) -> R {
+        let start = Instant::now();
+        let r = f();
+        let time = start.elapsed();
+        self.passes.lock().push(PassTiming { name, time, ..Default::default() });
+        r
+    }
+
+    /// Runs `f` and adds its wall time to the last recorded pass named `name`, recording the pass
+    /// if it does not exist yet.
+    ///
+    /// This is used for passes that run multiple times, such as once per contract.
+    pub fn accumulate<R>(&self, name: &'static str, f: impl FnOnce() -> R) -> R {
+        let start = Instant::now();
+        let r = f();
+        let time = start.elapsed();
+        let mut passes = self.passes.lock();
+        match passes.iter_mut().rev().find(|pass| pass.name == name) {
+            Some(pass) => pass.time += time,
+            None => passes.push(PassTiming { name, time, ..Default::default() }),
+        }
+        r
+    }
+
+    /// Updates the statistics of the last recorded pass named `name`, if any.
+    pub fn update(&self, name: &str, f: impl FnOnce(&mut PassTiming)) {
+        if let Some(pass) = self.passes.lock().iter_mut().rev().find(|pass| pass.name == name) {
+            f(pass);
+        }
+    }
+
+    /// Returns the recorded passes.
+    pub fn passes(&self) -> Vec<PassTiming> {
+        self.passes.lock().clone()
+    }
+
+    /// Returns the total wall time of the recorded passes.
+    pub fn total(&self) -> Duration {
+        self.passes.lock().iter().map(|pass| pass.time).sum()
+    }
+
+    /// Clears the recorded passes.
+    pub fn clear(&self) {
+        self.passes.lock().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_passes() {
+        let timings = Timings::default();
+        assert_eq!(timings.time("parse", || 1), 1);
+        timings.time("typeck", || ());
+        timings.update("parse", |pass| pass.files = Some(2));
+        timings.update("missing", |_| unreachable!());
+        timings.accumulate("peephole", || ());
+        timings.accumulate("peephole", || ());
+
+        let passes = timings.passes();
+        let names = passes.iter().map(|pass| pass.name).collect::<Vec<_>>();
+        assert_eq!(names, ["parse", "typeck", "peephole"]);
+        assert_eq!(passes[0].files, Some(2));
+        assert_eq!(passes[1].files, None);
+        assert_eq!(timings.total(), passes.iter().map(|pass| pass.time).sum::<Duration>());
+
+        timings.clear();
+        assert!(timings.passes().is_empty());
+    }
+}
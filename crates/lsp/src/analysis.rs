@@ -0,0 +1,256 @@
+//! Compiler queries used by the language server.
+
+use crate::utils::{file_name, file_url, position_to_offset, span_to_location, span_to_range};
+use lsp_types::{
+    DiagnosticSeverity, DocumentSymbol, Location, NumberOrString, Position, Range, SymbolKind, Url,
+};
+use solar_ast::{self as ast, ItemKind};
+use solar_interface::{
+    diagnostics::{DiagCtxt, Diagnostic, Emitter, Level},
+    source_map::{FileName, FileResolver, SourceFile},
+    Session, SourceMap,
+};
+use solar_parse::Parser;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+/// The contents of the open documents, which take precedence over the files on disk.
+pub(crate) type Documents = HashMap<PathBuf, String>;
+
+/// Emitter that collects all the emitted diagnostics.
+#[derive(Clone, Default)]
+struct CollectEmitter(Arc<Mutex<Vec<Diagnostic>>>);
+
+impl Emitter for CollectEmitter {
+    fn emit_diagnostic(&mut self, diagnostic: &Diagnostic) {
+        self.0.lock().unwrap().push(diagnostic.clone());
+    }
+}
+
+/// Creates a new session.
+fn new_session(emitter: CollectEmitter) -> Session {
+    Session::new(DiagCtxt::new(Box::new(emitter)), Arc::new(SourceMap::empty()))
+}
+
+/// Adds the open documents to `resolver` as virtual files.
+fn add_documents(resolver: &mut FileResolver<'_>, documents: &Documents) {
+    for (path, src) in documents {
+        let _ = resolver.add_virtual_file(file_name(path), src.clone());
+    }
+}
+
+/// Loads `path`, from the open documents if possible.
+fn load_file(sess: &Session, documents: &Documents, path: &Path) -> Option<Arc<SourceFile>> {
+    let name = FileName::real(file_name(path));
+    match documents.get(path) {
+        Some(src) => sess.source_map().new_source_file(name, || Ok(src.clone())).ok(),
+        None => sess.source_map().load_file(&file_name(path)).ok(),
+    }
+}
+
+/// Compiles `path` and its imports, returning the emitted diagnostics grouped by file.
+///
+/// The result always contains an entry for `path`, even if it has no diagnostics.
+pub(crate) fn diagnostics(
+    documents: &Documents,
+    path: &Path,
+) -> HashMap<Url, Vec<lsp_types::Diagnostic>> {
+    let emitter = CollectEmitter::default();
+    let emitted = emitter.0.clone();
+    let sess = new_session(emitter);
+    let _ = sess.enter(|| -> solar_interface::Result<()> {
+        let mut pcx = solar_sema::ParsingContext::new(&sess);
+        add_documents(&mut pcx.file_resolver, documents);
+        pcx.file_resolver
+            .load_remappings_txt(Path::new(""))
+            .map_err(|e| sess.dcx.err(e.to_string()).emit())?;
+        pcx.load_file(&file_name(path))?;
+        pcx.parse_and_resolve()
+    });
+
+    let mut diagnostics = HashMap::<Url, Vec<_>>::new();
+    let Some(url) = file_url(&FileName::real(path)) else { return diagnostics };
+    diagnostics.insert(url.clone(), Vec::new());
+    for diag in emitted.lock().unwrap().iter() {
+        let location = diag.span.primary_span().and_then(|span| {
+            // Skip spans that do not belong to any file.
+            (!span.is_dummy()).then(|| span_to_location(sess.source_map(), span)).flatten()
+        });
+        let (url, range) = location.unwrap_or_else(|| (url.clone(), Range::default()));
+        diagnostics.entry(url).or_default().push(convert_diagnostic(diag, range));
+    }
+    diagnostics
+}
+
+fn convert_diagnostic(diag: &Diagnostic, range: Range) -> lsp_types::Diagnostic {
+    let mut message = diag.label().into_owned();
+    for child in &diag.children {
+        message.push_str(&format!("\n{}: {}", child.level.to_str(), child.label()));
+    }
+    for suggestion in &diag.suggestions {
+        let msg = suggestion.msg.as_str();
+        message.push_str(&format!("\nhelp: {msg}: `{}`", suggestion.replacement));
+    }
+    lsp_types::Diagnostic {
+        range,
+        severity: Some(match diag.level() {
+            Level::Warning => DiagnosticSeverity::WARNING,
+            Level::Note | Level::OnceNote => DiagnosticSeverity::INFORMATION,
+            Level::Help | Level::OnceHelp => DiagnosticSeverity::HINT,
+            _ => DiagnosticSeverity::ERROR,
+        }),
+        code: diag.id().map(NumberOrString::String),
+        source: Some("solar".into()),
+        message,
+        ..Default::default()
+    }
+}
+
+/// Parses `path` and calls `f` with the resulting AST and source file.
+///
+/// Parse errors are ignored, as they are reported by [`diagnostics`].
+fn with_ast<R: Send>(
+    documents: &Documents,
+    path: &Path,
+    f: impl FnOnce(&Session, &SourceFile, &ast::SourceUnit<'_>) -> R + Send,
+) -> Option<R> {
+    let sess = new_session(CollectEmitter::default());
+    sess.enter(|| {
+        let file = load_file(&sess, documents, path)?;
+        let arena = ast::Arena::new();
+        let mut parser = Parser::from_source_file(&sess, &arena, &file);
+        let ast = parser.parse_file().map_err(|e| e.emit()).ok()?;
+        Some(f(&sess, &file, &ast))
+    })
+}
+
+/// Returns the file imported by the import path at `position` in `path`.
+pub(crate) fn goto_definition(
+    documents: &Documents,
+    path: &Path,
+    position: Position,
+) -> Option<Location> {
+    with_ast(documents, path, |sess, file, ast| {
+        let offset = position_to_offset(&file.src, position) as u32 + file.start_pos.0;
+        let import = ast.items.iter().find_map(|item| match &item.kind {
+            ItemKind::Import(import)
+                if import.path.span.lo().0 <= offset && offset <= import.path.span.hi().0 =>
+            {
+                Some(import)
+            }
+            _ => None,
+        })?;
+        let mut resolver = FileResolver::new(sess.source_map());
+        add_documents(&mut resolver, documents);
+        let _ = resolver.load_remappings_txt(Path::new(""));
+        let import_path = Path::new(import.path.value.as_str());
+        let imported = resolver.resolve_file(import_path, Some(&file_name(path))).ok()?;
+        Some(Location::new(file_url(&imported.name)?, Range::default()))
+    })
+    .flatten()
+}
+
+/// Returns the symbols defined in `path`.
+pub(crate) fn document_symbols(documents: &Documents, path: &Path) -> Vec<DocumentSymbol> {
+    with_ast(documents, path, |_, file, ast| items_symbols(file, &ast.items.raw, false))
+        .unwrap_or_default()
+}
+
+fn items_symbols(
+    file: &SourceFile,
+    items: &[ast::Item<'_>],
+    in_contract: bool,
+) -> Vec<DocumentSymbol> {
+    items.iter().filter_map(|item| item_symbol(file, item, in_contract)).collect()
+}
+
+fn item_symbol(
+    file: &SourceFile,
+    item: &ast::Item<'_>,
+    in_contract: bool,
+) -> Option<DocumentSymbol> {
+    let (name, kind, children) = match &item.kind {
+        ItemKind::Pragma(_) | ItemKind::Import(_) | ItemKind::Using(_) => return None,
+        ItemKind::Contract(contract) => {
+            let kind = match contract.kind {
+                ast::ContractKind::Interface => SymbolKind::INTERFACE,
+                ast::ContractKind::Library => SymbolKind::MODULE,
+                ast::ContractKind::Contract | ast::ContractKind::AbstractContract => {
+                    SymbolKind::CLASS
+                }
+            };
+            (contract.name.to_string(), kind, items_symbols(file, &contract.body, true))
+        }
+        ItemKind::Function(function) => {
+            let name = match function.header.name {
+                Some(name) => name.to_string(),
+                None => function.kind.to_str().to_string(),
+            };
+            let kind = match function.kind {
+                ast::FunctionKind::Constructor => SymbolKind::CONSTRUCTOR,
+                _ if in_contract => SymbolKind::METHOD,
+                _ => SymbolKind::FUNCTION,
+            };
+            (name, kind, Vec::new())
+        }
+        ItemKind::Variable(var) => {
+            let kind = if var.mutability.is_some_and(|m| m.is_constant()) {
+                SymbolKind::CONSTANT
+            } else if in_contract {
+                SymbolKind::FIELD
+            } else {
+                SymbolKind::VARIABLE
+            };
+            (var.name?.to_string(), kind, Vec::new())
+        }
+        ItemKind::Struct(strukt) => {
+            let fields = strukt
+                .fields
+                .iter()
+                .filter_map(|field| {
+                    let name = field.name?;
+                    Some(symbol(file, name.to_string(), SymbolKind::FIELD, field.span, name.span))
+                })
+                .collect();
+            (strukt.name.to_string(), SymbolKind::STRUCT, fields)
+        }
+        ItemKind::Enum(enumm) => {
+            let variants = enumm
+                .variants
+                .iter()
+                .map(|v| symbol(file, v.to_string(), SymbolKind::ENUM_MEMBER, v.span, v.span))
+                .collect();
+            (enumm.name.to_string(), SymbolKind::ENUM, variants)
+        }
+        ItemKind::Udvt(udvt) => (udvt.name.to_string(), SymbolKind::TYPE_PARAMETER, Vec::new()),
+        ItemKind::Error(error) => (error.name.to_string(), SymbolKind::OBJECT, Vec::new()),
+        ItemKind::Event(event) => (event.name.to_string(), SymbolKind::EVENT, Vec::new()),
+    };
+    let selection_span = item.name().map_or(item.span, |name| name.span);
+    let mut symbol = symbol(file, name, kind, item.span, selection_span);
+    symbol.children = (!children.is_empty()).then_some(children);
+    Some(symbol)
+}
+
+#[allow(deprecated)] // `DocumentSymbol::deprecated`
+fn symbol(
+    file: &SourceFile,
+    name: String,
+    kind: SymbolKind,
+    span: ast::Span,
+    selection_span: ast::Span,
+) -> DocumentSymbol {
+    DocumentSymbol {
+        name,
+        detail: None,
+        kind,
+        tags: None,
+        deprecated: None,
+        range: span_to_range(file, span),
+        selection_range: span_to_range(file, selection_span),
+        children: None,
+    }
+}
@@ -0,0 +1,13 @@
+//! The main entry point for the Solar language server.
+
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    match solar_lsp::run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("solar-lsp: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
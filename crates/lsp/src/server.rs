@@ -0,0 +1,180 @@
+//! The language server main loop.
+
+use crate::analysis::{self, Documents};
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+        Notification as _, PublishDiagnostics,
+    },
+    request::{DocumentSymbolRequest, GotoDefinition, Request as _},
+    DocumentSymbolResponse, GotoDefinitionResponse, InitializeParams, OneOf,
+    PublishDiagnosticsParams, ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind,
+    Url,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    path::PathBuf,
+};
+
+type Result<T, E = Box<dyn Error + Send + Sync>> = std::result::Result<T, E>;
+
+/// Runs the language server over stdin and stdout until the client shuts it down.
+pub fn run() -> Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        definition_provider: Some(OneOf::Left(true)),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        ..Default::default()
+    };
+    let params = connection.initialize(serde_json::to_value(capabilities)?)?;
+    let params: InitializeParams = serde_json::from_value(params)?;
+
+    // Paths are resolved relative to the current directory, like in the CLI.
+    if let Some(root) = workspace_root(&params) {
+        std::env::set_current_dir(root)?;
+    }
+
+    Server::new(&connection).main_loop()?;
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
+
+#[allow(deprecated)] // `root_uri`
+fn workspace_root(params: &InitializeParams) -> Option<PathBuf> {
+    let folder = params.workspace_folders.as_ref().and_then(|folders| folders.first());
+    let uri = folder.map(|folder| &folder.uri).or(params.root_uri.as_ref())?;
+    uri.to_file_path().ok()
+}
+
+struct Server<'a> {
+    connection: &'a Connection,
+    documents: Documents,
+    /// The files with published diagnostics for each analyzed file, so that they can be cleared
+    /// once they are fixed.
+    published: HashMap<Url, HashSet<Url>>,
+}
+
+impl<'a> Server<'a> {
+    fn new(connection: &'a Connection) -> Self {
+        Self { connection, documents: Documents::new(), published: HashMap::new() }
+    }
+
+    fn main_loop(&mut self) -> Result<()> {
+        for msg in &self.connection.receiver {
+            match msg {
+                Message::Request(req) => {
+                    if self.connection.handle_shutdown(&req)? {
+                        return Ok(());
+                    }
+                    self.handle_request(req)?;
+                }
+                Message::Notification(not) => self.handle_notification(not)?,
+                Message::Response(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_request(&mut self, req: Request) -> Result<()> {
+        let response = match req.method.as_str() {
+            DocumentSymbolRequest::METHOD => {
+                let (id, params) =
+                    req.extract::<lsp_types::DocumentSymbolParams>(DocumentSymbolRequest::METHOD)?;
+                let symbols = to_path(&params.text_document.uri)
+                    .map(|path| analysis::document_symbols(&self.documents, &path))
+                    .unwrap_or_default();
+                Response::new_ok(id, DocumentSymbolResponse::Nested(symbols))
+            }
+            GotoDefinition::METHOD => {
+                let (id, params) =
+                    req.extract::<lsp_types::GotoDefinitionParams>(GotoDefinition::METHOD)?;
+                let position = params.text_document_position_params;
+                let location = to_path(&position.text_document.uri).and_then(|path| {
+                    analysis::goto_definition(&self.documents, &path, position.position)
+                });
+                Response::new_ok(id, location.map(GotoDefinitionResponse::Scalar))
+            }
+            method => Response::new_err(
+                req.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("unsupported request: {method}"),
+            ),
+        };
+        self.connection.sender.send(response.into())?;
+        Ok(())
+    }
+
+    fn handle_notification(&mut self, not: Notification) -> Result<()> {
+        match not.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: lsp_types::DidOpenTextDocumentParams =
+                    not.extract(DidOpenTextDocument::METHOD)?;
+                let doc = params.text_document;
+                if let Some(path) = to_path(&doc.uri) {
+                    self.documents.insert(path, doc.text);
+                }
+                self.publish_diagnostics(doc.uri)?;
+            }
+            DidChangeTextDocument::METHOD => {
+                let mut params: lsp_types::DidChangeTextDocumentParams =
+                    not.extract(DidChangeTextDocument::METHOD)?;
+                let uri = params.text_document.uri;
+                // Only full synchronization is advertised, so the last change is the whole text.
+                if let (Some(path), Some(change)) = (to_path(&uri), params.content_changes.pop()) {
+                    self.documents.insert(path, change.text);
+                }
+                self.publish_diagnostics(uri)?;
+            }
+            DidSaveTextDocument::METHOD => {
+                let params: lsp_types::DidSaveTextDocumentParams =
+                    not.extract(DidSaveTextDocument::METHOD)?;
+                self.publish_diagnostics(params.text_document.uri)?;
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: lsp_types::DidCloseTextDocumentParams =
+                    not.extract(DidCloseTextDocument::METHOD)?;
+                let uri = params.text_document.uri;
+                if let Some(path) = to_path(&uri) {
+                    self.documents.remove(&path);
+                }
+                for url in self.published.remove(&uri).unwrap_or_default() {
+                    self.send_diagnostics(url, Vec::new())?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Analyzes the file at `uri` and publishes the diagnostics of it and its imports.
+    fn publish_diagnostics(&mut self, uri: Url) -> Result<()> {
+        let Some(path) = to_path(&uri) else { return Ok(()) };
+        let diagnostics = analysis::diagnostics(&self.documents, &path);
+        let published = self.published.insert(uri, diagnostics.keys().cloned().collect());
+        for url in published.unwrap_or_default() {
+            if !diagnostics.contains_key(&url) {
+                self.send_diagnostics(url, Vec::new())?;
+            }
+        }
+        for (url, diagnostics) in diagnostics {
+            self.send_diagnostics(url, diagnostics)?;
+        }
+        Ok(())
+    }
+
+    fn send_diagnostics(&self, uri: Url, diagnostics: Vec<lsp_types::Diagnostic>) -> Result<()> {
+        let params = PublishDiagnosticsParams::new(uri, diagnostics, None);
+        let not = Notification::new(PublishDiagnostics::METHOD.into(), params);
+        self.connection.sender.send(not.into())?;
+        Ok(())
+    }
+}
+
+fn to_path(uri: &Url) -> Option<PathBuf> {
+    uri.to_file_path().ok()
+}
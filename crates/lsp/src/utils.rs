@@ -0,0 +1,108 @@
+//! Conversions between compiler and LSP locations.
+
+use lsp_types::{Position, Range, Url};
+use solar_interface::{
+    source_map::{FileName, SourceFile},
+    SourceMap, Span,
+};
+use std::path::{Path, PathBuf};
+
+/// Returns the name under which `path` is loaded into the source map.
+///
+/// This matches the paths created by the file resolver, which are relative to the current
+/// directory when possible.
+pub(crate) fn file_name(path: &Path) -> PathBuf {
+    match std::env::current_dir() {
+        Ok(cwd) => path.strip_prefix(cwd).unwrap_or(path).to_path_buf(),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+/// Returns the URL of a source file, if it is a real file.
+pub(crate) fn file_url(name: &FileName) -> Option<Url> {
+    let FileName::Real(path) = name else { return None };
+    if path.is_absolute() {
+        Url::from_file_path(path).ok()
+    } else {
+        Url::from_file_path(std::env::current_dir().ok()?.join(path)).ok()
+    }
+}
+
+/// Converts a span to the URL of its file and the corresponding LSP range.
+pub(crate) fn span_to_location(sm: &SourceMap, span: Span) -> Option<(Url, Range)> {
+    let file = sm.lookup_byte_offset(span.lo()).sf;
+    let url = file_url(&file.name)?;
+    Some((url, span_to_range(&file, span)))
+}
+
+/// Converts a span in `file` to an LSP range.
+pub(crate) fn span_to_range(file: &SourceFile, span: Span) -> Range {
+    let lo = (span.lo().0 - file.start_pos.0) as usize;
+    let hi = (span.hi().0 - file.start_pos.0) as usize;
+    Range::new(offset_to_position(&file.src, lo), offset_to_position(&file.src, hi))
+}
+
+/// Converts a byte offset in `src` to an LSP position, counting columns in UTF-16 code units.
+pub(crate) fn offset_to_position(src: &str, offset: usize) -> Position {
+    let offset = offset.min(src.len());
+    let before = &src[..floor_char_boundary(src, offset)];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let line = before[..line_start].matches('\n').count();
+    let character = before[line_start..].encode_utf16().count();
+    Position::new(line as u32, character as u32)
+}
+
+/// Converts an LSP position to a byte offset in `src`, clamping it to the end of the line.
+pub(crate) fn position_to_offset(src: &str, position: Position) -> usize {
+    let mut line_start = 0;
+    for _ in 0..position.line {
+        match src[line_start..].find('\n') {
+            Some(i) => line_start += i + 1,
+            None => return src.len(),
+        }
+    }
+    let line = src[line_start..].split('\n').next().unwrap_or_default();
+    let mut utf16 = 0;
+    for (i, c) in line.char_indices() {
+        if utf16 >= position.character as usize {
+            return line_start + i;
+        }
+        utf16 += c.len_utf16();
+    }
+    line_start + line.len()
+}
+
+fn floor_char_boundary(s: &str, mut index: usize) -> usize {
+    while !s.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions() {
+        let src = "a\nbé😀c\n\nd";
+        let cases = [
+            (0, Position::new(0, 0)),
+            (1, Position::new(0, 1)),
+            (2, Position::new(1, 0)),
+            (3, Position::new(1, 1)),
+            (5, Position::new(1, 2)),
+            (9, Position::new(1, 4)),
+            (10, Position::new(1, 5)),
+            (11, Position::new(2, 0)),
+            (12, Position::new(3, 0)),
+            (13, Position::new(3, 1)),
+        ];
+        for (offset, position) in cases {
+            assert_eq!(offset_to_position(src, offset), position, "{offset}");
+            assert_eq!(position_to_offset(src, position), offset, "{position:?}");
+        }
+        assert_eq!(position_to_offset(src, Position::new(0, 100)), 1);
+        assert_eq!(position_to_offset(src, Position::new(100, 0)), src.len());
+    }
+}
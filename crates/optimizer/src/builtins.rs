@@ -0,0 +1,122 @@
+//! The properties of the EVM builtin functions that the optimizer relies on.
+//!
+//! User-defined functions can't have the same name as a builtin, so builtins are recognized by
+//! name only.
+
+use crate::ir::Expr;
+use alloy_primitives::U256;
+
+/// Returns `true` if calling the builtin `name` halts the execution or reverts.
+pub(crate) fn is_terminating(name: &str) -> bool {
+    matches!(name, "stop" | "return" | "revert" | "invalid" | "selfdestruct")
+}
+
+/// Returns `true` if calling the builtin `name` has no side effects, so that the call can be
+/// removed if its result is not used.
+///
+/// Builtins that read memory are not included, as they can expand it.
+pub(crate) fn is_side_effect_free(name: &str) -> bool {
+    matches!(
+        name,
+        "add"
+            | "sub"
+            | "mul"
+            | "div"
+            | "sdiv"
+            | "mod"
+            | "smod"
+            | "exp"
+            | "not"
+            | "lt"
+            | "gt"
+            | "slt"
+            | "sgt"
+            | "eq"
+            | "iszero"
+            | "and"
+            | "or"
+            | "xor"
+            | "byte"
+            | "shl"
+            | "shr"
+            | "sar"
+            | "addmod"
+            | "mulmod"
+            | "signextend"
+            | "address"
+            | "balance"
+            | "selfbalance"
+            | "caller"
+            | "callvalue"
+            | "calldataload"
+            | "calldatasize"
+            | "codesize"
+            | "extcodesize"
+            | "extcodehash"
+            | "returndatasize"
+            | "origin"
+            | "gasprice"
+            | "blockhash"
+            | "blobhash"
+            | "coinbase"
+            | "timestamp"
+            | "number"
+            | "difficulty"
+            | "prevrandao"
+            | "gaslimit"
+            | "chainid"
+            | "basefee"
+            | "blobbasefee"
+            | "sload"
+            | "tload"
+            | "datasize"
+            | "dataoffset"
+            | "memoryguard"
+            | "linkersymbol"
+    )
+}
+
+/// Returns `true` if evaluating the expression has no side effects.
+pub(crate) fn is_side_effect_free_expr(expr: &Expr) -> bool {
+    match expr {
+        Expr::Number(_) | Expr::Str(_) | Expr::Var(_) => true,
+        Expr::Call(name, args) => {
+            is_side_effect_free(name) && args.iter().all(is_side_effect_free_expr)
+        }
+    }
+}
+
+/// Evaluates a call to the builtin `name` with constant arguments.
+///
+/// Returns `None` if the builtin is not supported, or if the arguments don't match.
+pub(crate) fn evaluate(name: &str, args: &[U256]) -> Option<U256> {
+    let bool = |b: bool| U256::from(b as u8);
+    Some(match (name, args) {
+        ("add", &[a, b]) => a.wrapping_add(b),
+        ("sub", &[a, b]) => a.wrapping_sub(b),
+        ("mul", &[a, b]) => a.wrapping_mul(b),
+        ("div", &[a, b]) => a.checked_div(b).unwrap_or_default(),
+        ("mod", &[a, b]) => a.checked_rem(b).unwrap_or_default(),
+        ("exp", &[a, b]) => a.wrapping_pow(b),
+        ("not", &[a]) => !a,
+        ("lt", &[a, b]) => bool(a < b),
+        ("gt", &[a, b]) => bool(a > b),
+        ("eq", &[a, b]) => bool(a == b),
+        ("iszero", &[a]) => bool(a.is_zero()),
+        ("and", &[a, b]) => a & b,
+        ("or", &[a, b]) => a | b,
+        ("xor", &[a, b]) => a ^ b,
+        ("shl", &[shift, value]) => shift_value(shift).map_or(U256::ZERO, |shift| value << shift),
+        ("shr", &[shift, value]) => shift_value(shift).map_or(U256::ZERO, |shift| value >> shift),
+        ("byte", &[i, value]) => match shift_value(i) {
+            Some(i) if i < 32 => U256::from(value.byte(31 - i)),
+            _ => U256::ZERO,
+        },
+        _ => return None,
+    })
+}
+
+/// Returns the amount of a shift, or `None` if it shifts out all the bits.
+fn shift_value(shift: U256) -> Option<usize> {
+    (shift < U256::from(256)).then(|| shift.to::<usize>())
+}
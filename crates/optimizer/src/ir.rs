@@ -0,0 +1,376 @@
+//! The Yul IR that the optimizer operates on.
+//!
+//! This is an owned, simplified version of the [Yul AST](solar_ast::yul) without spans or
+//! comments, which can be freely rewritten by the optimizer steps and printed back to Yul source
+//! code.
+
+use alloy_primitives::U256;
+use solar_ast::{yul, Ident, LitKind};
+use std::fmt::{self, Write};
+
+/// A Yul object.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Object {
+    /// The name of the object, as written in the source code.
+    pub name: String,
+    /// The `code` block.
+    pub code: Block,
+    /// Sub-objects.
+    pub children: Vec<Object>,
+    /// `data` segments.
+    pub data: Vec<Data>,
+}
+
+/// A Yul `data` segment.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Data {
+    /// The name of the data segment, as written in the source code.
+    pub name: String,
+    /// The contents of the data segment.
+    pub value: Vec<u8>,
+}
+
+/// A block of statements.
+pub type Block = Vec<Stmt>;
+
+/// A Yul statement.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Stmt {
+    /// A blocked scope: `{ ... }`.
+    Block(Block),
+    /// A variable declaration: `let x, y := f()`.
+    VarDecl(Vec<String>, Option<Expr>),
+    /// An assignment: `x, y := f()`.
+    Assign(Vec<String>, Expr),
+    /// An expression statement. This can only be a function call.
+    Expr(Expr),
+    /// An if statement: `if cond { ... }`.
+    If(Expr, Block),
+    /// A switch statement.
+    Switch(Switch),
+    /// A for statement: `for { init } cond { step } { body }`.
+    For { init: Block, cond: Expr, step: Block, body: Block },
+    /// `break`.
+    Break,
+    /// `continue`.
+    Continue,
+    /// `leave`.
+    Leave,
+    /// A function definition.
+    FunctionDef(Function),
+}
+
+/// A Yul switch statement: `switch selector case 0 { ... } default { ... }`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Switch {
+    pub selector: Expr,
+    /// The non-default cases, with the values of their constants.
+    pub cases: Vec<(U256, Block)>,
+    pub default: Option<Block>,
+}
+
+/// A Yul function definition: `function f(a, b) -> c { ... }`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Function {
+    pub name: String,
+    pub parameters: Vec<String>,
+    pub returns: Vec<String>,
+    pub body: Block,
+}
+
+/// A Yul expression.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expr {
+    /// A number or boolean literal.
+    Number(U256),
+    /// A string literal. These are only kept as strings so that they can be passed to builtins
+    /// such as `datasize`.
+    Str(Vec<u8>),
+    /// A variable.
+    Var(String),
+    /// A function call. Arguments are evaluated from right to left.
+    Call(String, Vec<Expr>),
+}
+
+impl Expr {
+    /// Returns the value of the expression if it is a number literal.
+    pub fn as_number(&self) -> Option<U256> {
+        match *self {
+            Self::Number(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    /// Calls `f` on this expression and all of its sub-expressions, in post-order.
+    pub fn walk(&self, f: &mut impl FnMut(&Self)) {
+        if let Self::Call(_, args) = self {
+            for arg in args {
+                arg.walk(f);
+            }
+        }
+        f(self);
+    }
+}
+
+impl Object {
+    /// Converts the given AST object.
+    ///
+    /// Returns `None` if the object contains a literal that cannot be represented in a single EVM
+    /// word; these are reported when the object is assembled.
+    pub fn from_ast(object: &yul::Object<'_>) -> Option<Self> {
+        let data = object
+            .data
+            .iter()
+            .map(|data| match &data.data.kind {
+                LitKind::Str(_, bytes) => {
+                    Some(Data { name: data.name.value.to_string(), value: bytes.to_vec() })
+                }
+                _ => None,
+            })
+            .collect::<Option<_>>()?;
+        Some(Self {
+            name: object.name.value.to_string(),
+            code: block_from_ast(&object.code.code)?,
+            children: object.children.iter().map(Self::from_ast).collect::<Option<_>>()?,
+            data,
+        })
+    }
+}
+
+fn block_from_ast(block: &[yul::Stmt<'_>]) -> Option<Block> {
+    block.iter().map(stmt_from_ast).collect()
+}
+
+fn stmt_from_ast(stmt: &yul::Stmt<'_>) -> Option<Stmt> {
+    Some(match &stmt.kind {
+        yul::StmtKind::Block(block) => Stmt::Block(block_from_ast(block)?),
+        yul::StmtKind::AssignSingle(path, expr) => {
+            Stmt::Assign(vec![path.to_string()], expr_from_ast(expr)?)
+        }
+        yul::StmtKind::AssignMulti(paths, call) => {
+            Stmt::Assign(paths.iter().map(|path| path.to_string()).collect(), call_from_ast(call)?)
+        }
+        yul::StmtKind::Expr(call) => Stmt::Expr(call_from_ast(call)?),
+        yul::StmtKind::If(cond, body) => Stmt::If(expr_from_ast(cond)?, block_from_ast(body)?),
+        yul::StmtKind::For { init, cond, step, body } => Stmt::For {
+            init: block_from_ast(init)?,
+            cond: expr_from_ast(cond)?,
+            step: block_from_ast(step)?,
+            body: block_from_ast(body)?,
+        },
+        yul::StmtKind::Switch(switch) => Stmt::Switch(Switch {
+            selector: expr_from_ast(&switch.selector)?,
+            cases: switch
+                .branches
+                .iter()
+                .map(|case| Some((lit_value(&case.constant.kind)?, block_from_ast(&case.body)?)))
+                .collect::<Option<_>>()?,
+            default: switch.default_case.as_ref().map(|block| block_from_ast(block)).transpose()?,
+        }),
+        yul::StmtKind::Leave => Stmt::Leave,
+        yul::StmtKind::Break => Stmt::Break,
+        yul::StmtKind::Continue => Stmt::Continue,
+        yul::StmtKind::FunctionDef(f) => Stmt::FunctionDef(Function {
+            name: f.name.to_string(),
+            parameters: names(&f.parameters),
+            returns: names(&f.returns),
+            body: block_from_ast(&f.body)?,
+        }),
+        yul::StmtKind::VarDecl(idents, value) => {
+            Stmt::VarDecl(names(idents), value.as_ref().map(expr_from_ast).transpose()?)
+        }
+    })
+}
+
+fn names(idents: &[Ident]) -> Vec<String> {
+    idents.iter().map(|ident| ident.to_string()).collect()
+}
+
+fn expr_from_ast(expr: &yul::Expr<'_>) -> Option<Expr> {
+    Some(match &expr.kind {
+        yul::ExprKind::Path(path) => Expr::Var(path.to_string()),
+        yul::ExprKind::Call(call) => call_from_ast(call)?,
+        yul::ExprKind::Lit(lit) => match &lit.kind {
+            LitKind::Str(_, bytes) => Expr::Str(bytes.to_vec()),
+            kind => Expr::Number(lit_value(kind)?),
+        },
+    })
+}
+
+fn call_from_ast(call: &yul::ExprCall<'_>) -> Option<Expr> {
+    let args = call.arguments.iter().map(expr_from_ast).collect::<Option<_>>()?;
+    Some(Expr::Call(call.name.to_string(), args))
+}
+
+/// Returns the value of a literal as an EVM word.
+fn lit_value(kind: &LitKind) -> Option<U256> {
+    match kind {
+        LitKind::Number(n) => {
+            let (_, bytes) = n.to_bytes_be();
+            (bytes.len() <= 32).then(|| U256::from_be_slice(&bytes))
+        }
+        LitKind::Bool(b) => Some(U256::from(*b as u8)),
+        LitKind::Str(_, bytes) if bytes.len() <= 32 => {
+            let mut word = [0; 32];
+            word[..bytes.len()].copy_from_slice(bytes);
+            Some(U256::from_be_bytes(word))
+        }
+        LitKind::Address(address) => Some(U256::from_be_slice(address.as_slice())),
+        _ => None,
+    }
+}
+
+/// Prints the object as Yul source code.
+impl fmt::Display for Object {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut printer = Printer { s: String::new(), indent: 0 };
+        printer.object(self);
+        f.write_str(&printer.s)
+    }
+}
+
+struct Printer {
+    s: String,
+    indent: usize,
+}
+
+impl Printer {
+    fn object(&mut self, object: &Object) {
+        self.line(&format!("object \"{}\" {{", object.name));
+        self.indent += 1;
+        self.line("code {");
+        self.indent += 1;
+        self.stmts(&object.code);
+        self.indent -= 1;
+        self.line("}");
+        for child in &object.children {
+            self.object(child);
+        }
+        for data in &object.data {
+            let mut line = format!("data \"{}\" hex\"", data.name);
+            for byte in &data.value {
+                write!(line, "{byte:02x}").unwrap();
+            }
+            line.push('"');
+            self.line(&line);
+        }
+        self.indent -= 1;
+        self.line("}");
+    }
+
+    fn line(&mut self, line: &str) {
+        for _ in 0..self.indent {
+            self.s.push_str("    ");
+        }
+        self.s.push_str(line);
+        self.s.push('\n');
+    }
+
+    fn stmts(&mut self, block: &[Stmt]) {
+        for stmt in block {
+            self.stmt(stmt);
+        }
+    }
+
+    /// Prints a block that follows `head`, such as `if cond`.
+    fn block(&mut self, head: &str, block: &[Stmt]) {
+        if block.is_empty() {
+            self.line(&format!("{head}{{ }}"));
+            return;
+        }
+        self.line(&format!("{head}{{"));
+        self.indent += 1;
+        self.stmts(block);
+        self.indent -= 1;
+        self.line("}");
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Block(block) => self.block("", block),
+            Stmt::VarDecl(names, value) => {
+                let mut line = format!("let {}", names.join(", "));
+                if let Some(value) = value {
+                    write!(line, " := {value}").unwrap();
+                }
+                self.line(&line);
+            }
+            Stmt::Assign(names, value) => self.line(&format!("{} := {value}", names.join(", "))),
+            Stmt::Expr(expr) => self.line(&expr.to_string()),
+            Stmt::If(cond, body) => self.block(&format!("if {cond} "), body),
+            Stmt::Switch(switch) => {
+                self.line(&format!("switch {}", switch.selector));
+                for (value, body) in &switch.cases {
+                    self.block(&format!("case {} ", Number(*value)), body);
+                }
+                if let Some(default) = &switch.default {
+                    self.block("default ", default);
+                }
+            }
+            Stmt::For { init, cond, step, body } => {
+                // Print the header on multiple lines, as the blocks can contain any statement.
+                self.block("for ", init);
+                self.indent += 1;
+                self.line(&cond.to_string());
+                self.block("", step);
+                self.indent -= 1;
+                self.block("", body);
+            }
+            Stmt::Break => self.line("break"),
+            Stmt::Continue => self.line("continue"),
+            Stmt::Leave => self.line("leave"),
+            Stmt::FunctionDef(function) => {
+                let mut head =
+                    format!("function {}({})", function.name, function.parameters.join(", "));
+                if !function.returns.is_empty() {
+                    write!(head, " -> {}", function.returns.join(", ")).unwrap();
+                }
+                head.push(' ');
+                self.block(&head, &function.body);
+            }
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Number(n) => Number(*n).fmt(f),
+            Self::Str(bytes) => {
+                f.write_char('"')?;
+                for &byte in bytes {
+                    match byte {
+                        b'"' | b'\\' => write!(f, "\\{}", byte as char)?,
+                        0x20..=0x7e => f.write_char(byte as char)?,
+                        _ => write!(f, "\\x{byte:02x}")?,
+                    }
+                }
+                f.write_char('"')
+            }
+            Self::Var(name) => f.write_str(name),
+            Self::Call(name, args) => {
+                write!(f, "{name}(")?;
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    arg.fmt(f)?;
+                }
+                f.write_char(')')
+            }
+        }
+    }
+}
+
+/// Formats a number literal in decimal if it is small, or in hexadecimal otherwise.
+struct Number(U256);
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 <= U256::from(0xffff) {
+            write!(f, "{}", self.0)
+        } else {
+            write!(f, "0x{:x}", self.0)
+        }
+    }
+}
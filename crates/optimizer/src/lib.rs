@@ -0,0 +1,67 @@
+#![doc = include_str!("../README.md")]
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/solar/main/assets/logo.png",
+    html_favicon_url = "https://raw.githubusercontent.com/paradigmxyz/solar/main/assets/favicon.ico"
+)]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+#[macro_use]
+extern crate tracing;
+
+pub mod ir;
+pub use ir::Object;
+
+mod builtins;
+
+mod passes;
+use passes::{disambiguator, run_step, Context};
+
+mod sequence;
+pub use sequence::{ParseSequenceError, Sequence, SequenceItem, Step, DEFAULT_SEQUENCE};
+
+#[cfg(test)]
+mod tests;
+
+/// Optimizer settings.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OptimizerSettings {
+    /// The expected number of times each opcode of the deployed code is executed.
+    ///
+    /// Lower values optimize for code size, higher values for the cost of execution.
+    pub runs: u32,
+    /// The sequence of steps to run.
+    pub sequence: Sequence,
+}
+
+impl Default for OptimizerSettings {
+    fn default() -> Self {
+        Self { runs: 200, sequence: DEFAULT_SEQUENCE.parse().unwrap() }
+    }
+}
+
+/// Optimizes the code of the object and of its sub-objects in place.
+pub fn optimize(object: &mut Object, settings: &OptimizerSettings) {
+    for child in &mut object.children {
+        optimize(child, settings);
+    }
+
+    let _guard = debug_span!("optimize", object = %object.name).entered();
+    let code = &mut object.code;
+    let mut cx = Context { names: disambiguator::run(code), runs: settings.runs };
+    for item in settings.sequence.main.iter().chain(&settings.sequence.cleanup) {
+        match item {
+            SequenceItem::Step(step) => run_step(*step, code, &mut cx),
+            SequenceItem::Repeat(steps) => {
+                for _ in 0..sequence::MAX_REPETITIONS {
+                    let before = code.clone();
+                    for &step in steps {
+                        run_step(step, code, &mut cx);
+                    }
+                    if *code == before {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,28 @@
+//! Removal of unreachable code.
+
+use super::walk_blocks_mut;
+use crate::{
+    builtins::is_terminating,
+    ir::{Block, Expr, Stmt},
+};
+
+pub(crate) fn run(code: &mut Block) {
+    walk_blocks_mut(code, &mut |block| {
+        let Some(end) = block.iter().position(terminates) else { return };
+        // Function definitions are visible in the whole block, so they are kept.
+        let mut i = 0;
+        block.retain(|stmt| {
+            i += 1;
+            i <= end + 1 || matches!(stmt, Stmt::FunctionDef(_))
+        });
+    });
+}
+
+/// Returns `true` if the statement always transfers the control flow out of its block.
+fn terminates(stmt: &Stmt) -> bool {
+    match stmt {
+        Stmt::Break | Stmt::Continue | Stmt::Leave => true,
+        Stmt::Expr(Expr::Call(name, _)) => is_terminating(name),
+        _ => false,
+    }
+}
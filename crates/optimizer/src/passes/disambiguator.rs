@@ -0,0 +1,124 @@
+//! Renames the variables and functions so that every name is declared only once in the code of an
+//! object.
+//!
+//! The other steps rely on this, as it lets them identify a declaration by its name alone.
+
+use super::NameDispenser;
+use crate::ir::{Block, Expr, Stmt};
+use solar_data_structures::map::FxHashMap;
+
+/// Disambiguates the names of the code, returning the dispenser of the names that it declares.
+pub(crate) fn run(code: &mut Block) -> NameDispenser {
+    let mut disambiguator = Disambiguator { names: NameDispenser::default(), scopes: Vec::new() };
+    disambiguator.block(code);
+    disambiguator.names
+}
+
+struct Disambiguator {
+    names: NameDispenser,
+    /// The new names of the declarations visible in each scope.
+    scopes: Vec<FxHashMap<String, String>>,
+}
+
+impl Disambiguator {
+    fn block(&mut self, block: &mut Block) {
+        self.scopes.push(FxHashMap::default());
+        self.stmts(block);
+        self.scopes.pop();
+    }
+
+    /// Renames the statements of a block in the current scope. Functions are visible in the whole
+    /// block, so they are declared first.
+    fn stmts(&mut self, block: &mut Block) {
+        for stmt in block.iter_mut() {
+            if let Stmt::FunctionDef(function) = stmt {
+                function.name = self.declare(&function.name);
+            }
+        }
+        for stmt in block.iter_mut() {
+            self.stmt(stmt);
+        }
+    }
+
+    fn stmt(&mut self, stmt: &mut Stmt) {
+        match stmt {
+            Stmt::Block(block) => self.block(block),
+            Stmt::VarDecl(names, value) => {
+                // The variables are not visible in their own initializer.
+                if let Some(value) = value {
+                    self.expr(value);
+                }
+                for name in names {
+                    *name = self.declare(name);
+                }
+            }
+            Stmt::Assign(names, value) => {
+                self.expr(value);
+                for name in names {
+                    self.resolve(name);
+                }
+            }
+            Stmt::Expr(expr) => self.expr(expr),
+            Stmt::If(cond, body) => {
+                self.expr(cond);
+                self.block(body);
+            }
+            Stmt::Switch(switch) => {
+                self.expr(&mut switch.selector);
+                for (_, body) in &mut switch.cases {
+                    self.block(body);
+                }
+                if let Some(default) = &mut switch.default {
+                    self.block(default);
+                }
+            }
+            Stmt::For { init, cond, step, body } => {
+                // The scope of the init block extends to the rest of the loop.
+                self.scopes.push(FxHashMap::default());
+                self.stmts(init);
+                self.expr(cond);
+                self.block(step);
+                self.block(body);
+                self.scopes.pop();
+            }
+            Stmt::Break | Stmt::Continue | Stmt::Leave => {}
+            Stmt::FunctionDef(function) => {
+                self.scopes.push(FxHashMap::default());
+                for name in function.parameters.iter_mut().chain(&mut function.returns) {
+                    *name = self.declare(name);
+                }
+                self.block(&mut function.body);
+                self.scopes.pop();
+            }
+        }
+    }
+
+    fn expr(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::Number(_) | Expr::Str(_) => {}
+            Expr::Var(name) => self.resolve(name),
+            Expr::Call(name, args) => {
+                // Builtins are not declared, so they are not renamed.
+                self.resolve(name);
+                for arg in args {
+                    self.expr(arg);
+                }
+            }
+        }
+    }
+
+    /// Declares `name` in the current scope, returning its new name.
+    fn declare(&mut self, name: &str) -> String {
+        let new_name = self.names.fresh(name);
+        self.scopes.last_mut().unwrap().insert(name.to_string(), new_name.clone());
+        new_name
+    }
+
+    /// Replaces a reference to a declaration with its new name.
+    fn resolve(&self, name: &mut String) {
+        if let Some(new_name) = self.scopes.iter().rev().find_map(|scope| scope.get(name.as_str()))
+        {
+            name.clone_from(new_name);
+        }
+    }
+}
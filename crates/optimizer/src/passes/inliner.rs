@@ -0,0 +1,185 @@
+//! Function inlining.
+//!
+//! Calls to small functions are replaced by the body of the function when the call is the whole
+//! value of a variable declaration, of an assignment, or of an expression statement. Calls nested
+//! in other expressions are not inlined.
+//!
+//! The size limit grows with the expected number of runs, as inlining trades off code size for
+//! the cost of the calls.
+
+use super::{stmt_exprs_mut, walk_blocks_mut, walk_exprs, walk_stmts, Context, NameDispenser};
+use crate::ir::{Block, Expr, Function, Stmt};
+use solar_data_structures::map::FxHashMap;
+
+pub(crate) fn run(code: &mut Block, cx: &mut Context) {
+    let max_size = 2 * cx.runs.saturating_add(1).ilog10() as usize + 2;
+    // Only the functions defined at the top level are visible everywhere in the code, and so are
+    // the functions that they call.
+    let mut functions = FxHashMap::default();
+    for stmt in code.iter() {
+        if let Stmt::FunctionDef(function) = stmt {
+            if can_inline(function) && size(&function.body) <= max_size {
+                functions.insert(function.name.clone(), function.clone());
+            }
+        }
+    }
+    if functions.is_empty() {
+        return;
+    }
+
+    let names = &mut cx.names;
+    walk_blocks_mut(code, &mut |block| {
+        let stmts = std::mem::take(block);
+        for stmt in stmts {
+            inline(stmt, block, &functions, names);
+        }
+    });
+}
+
+/// Returns `true` if the function can be inlined: its body does not return early, does not
+/// define functions, and does not call the function itself.
+fn can_inline(function: &Function) -> bool {
+    let mut can_inline = true;
+    walk_stmts(&function.body, &mut |stmt| {
+        can_inline &= !matches!(stmt, Stmt::Leave | Stmt::FunctionDef(_));
+    });
+    walk_exprs(&function.body, &mut |expr| {
+        can_inline &= !matches!(expr, Expr::Call(name, _) if *name == function.name);
+    });
+    can_inline
+}
+
+/// Returns the number of statements and expressions in the block.
+fn size(block: &[Stmt]) -> usize {
+    let mut size = 0;
+    walk_stmts(block, &mut |_| size += 1);
+    walk_exprs(block, &mut |_| size += 1);
+    size
+}
+
+/// Pushes the statement to `block`, inlining the function that it calls if possible.
+fn inline(
+    stmt: Stmt,
+    block: &mut Block,
+    functions: &FxHashMap<String, Function>,
+    names: &mut NameDispenser,
+) {
+    match stmt {
+        Stmt::VarDecl(vars, Some(value)) if callee(&value, functions).is_some() => {
+            // The declared variables are zero-initialized, so they can be used as the return
+            // variables of the function.
+            let function = callee(&value, functions).unwrap();
+            let (body, _) = inline_call(function, into_args(value), Some(&vars), names);
+            block.push(Stmt::VarDecl(vars, None));
+            block.push(Stmt::Block(body));
+        }
+        Stmt::Assign(vars, value) if callee(&value, functions).is_some() => {
+            let function = callee(&value, functions).unwrap();
+            let (mut body, returns) = inline_call(function, into_args(value), None, names);
+            for (var, ret) in vars.into_iter().zip(returns) {
+                body.push(Stmt::Assign(vec![var], Expr::Var(ret)));
+            }
+            block.push(Stmt::Block(body));
+        }
+        Stmt::Expr(value) if callee(&value, functions).is_some() => {
+            let function = callee(&value, functions).unwrap();
+            let (body, _) = inline_call(function, into_args(value), None, names);
+            block.push(Stmt::Block(body));
+        }
+        stmt => block.push(stmt),
+    }
+}
+
+/// Returns the inlinable function called by the expression, if any.
+fn callee<'a>(expr: &Expr, functions: &'a FxHashMap<String, Function>) -> Option<&'a Function> {
+    match expr {
+        Expr::Call(name, _) => functions.get(name),
+        _ => None,
+    }
+}
+
+fn into_args(call: Expr) -> Vec<Expr> {
+    match call {
+        Expr::Call(_, args) => args,
+        _ => unreachable!(),
+    }
+}
+
+/// Returns the statements that evaluate the arguments and run the body of the function, and the
+/// names of its return variables.
+///
+/// The return variables are declared in the statements unless `vars` are given to be used
+/// instead. The other variables of the function are given fresh names.
+fn inline_call(
+    function: &Function,
+    args: Vec<Expr>,
+    vars: Option<&[String]>,
+    names: &mut NameDispenser,
+) -> (Block, Vec<String>) {
+    let mut renames = FxHashMap::default();
+    let mut rename = |name: &String| {
+        let new_name = names.fresh(name);
+        renames.insert(name.clone(), new_name.clone());
+        new_name
+    };
+    let params = function.parameters.iter().map(&mut rename).collect::<Vec<_>>();
+    let returns = match vars {
+        Some(vars) => vars.to_vec(),
+        None => function.returns.iter().map(&mut rename).collect(),
+    };
+    walk_stmts(&function.body, &mut |stmt| {
+        if let Stmt::VarDecl(vars, _) = stmt {
+            for var in vars {
+                rename(var);
+            }
+        }
+    });
+    if vars.is_some() {
+        renames.extend(function.returns.iter().cloned().zip(returns.iter().cloned()));
+    }
+
+    let mut block = Vec::new();
+    // Arguments are evaluated from right to left.
+    for (param, arg) in params.into_iter().zip(args).rev() {
+        block.push(Stmt::VarDecl(vec![param], Some(arg)));
+    }
+    if vars.is_none() && !returns.is_empty() {
+        block.push(Stmt::VarDecl(returns.clone(), None));
+    }
+    let mut body = function.body.clone();
+    rename_vars(&mut body, &renames);
+    block.extend(body);
+    (block, returns)
+}
+
+/// Renames the variables declared, assigned, and referenced in the block.
+fn rename_vars(block: &mut Block, renames: &FxHashMap<String, String>) {
+    fn rename_expr(expr: &mut Expr, renames: &FxHashMap<String, String>) {
+        match expr {
+            Expr::Var(name) => {
+                if let Some(new_name) = renames.get(name) {
+                    name.clone_from(new_name);
+                }
+            }
+            Expr::Call(_, args) => {
+                for arg in args {
+                    rename_expr(arg, renames);
+                }
+            }
+            Expr::Number(_) | Expr::Str(_) => {}
+        }
+    }
+
+    walk_blocks_mut(block, &mut |block| {
+        for stmt in block.iter_mut() {
+            if let Stmt::VarDecl(vars, _) | Stmt::Assign(vars, _) = stmt {
+                for var in vars {
+                    if let Some(new_name) = renames.get(var) {
+                        var.clone_from(new_name);
+                    }
+                }
+            }
+            stmt_exprs_mut(stmt, |expr| rename_expr(expr, renames));
+        }
+    });
+}
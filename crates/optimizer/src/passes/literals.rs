@@ -0,0 +1,47 @@
+//! Constant propagation over the variables that are never reassigned.
+//!
+//! A variable that is only assigned by its declaration is in SSA form, so if it is initialized
+//! with a literal, or not initialized at all, every reference to it can be replaced by its value.
+//! The declaration itself is left to the [unused pruner](super::unused).
+
+use super::{walk_exprs_mut, walk_stmts};
+use crate::ir::{Block, Expr, Stmt};
+use alloy_primitives::U256;
+use solar_data_structures::map::{FxHashMap, FxHashSet};
+
+pub(crate) fn run(code: &mut Block) {
+    let mut assigned = FxHashSet::default();
+    let mut values = FxHashMap::default();
+    walk_stmts(code, &mut |stmt| match stmt {
+        Stmt::Assign(names, _) => assigned.extend(names.iter().cloned()),
+        Stmt::VarDecl(names, None) => {
+            values.extend(names.iter().map(|name| (name.clone(), U256::ZERO)));
+        }
+        Stmt::VarDecl(names, Some(Expr::Number(value))) => {
+            values.insert(names[0].clone(), *value);
+        }
+        _ => {}
+    });
+    values.retain(|name, _| !assigned.contains(name));
+    if values.is_empty() {
+        return;
+    }
+
+    walk_exprs_mut(code, &mut |expr| replace(expr, &values));
+}
+
+fn replace(expr: &mut Expr, values: &FxHashMap<String, U256>) {
+    match expr {
+        Expr::Var(name) => {
+            if let Some(&value) = values.get(name) {
+                *expr = Expr::Number(value);
+            }
+        }
+        Expr::Call(_, args) => {
+            for arg in args {
+                replace(arg, values);
+            }
+        }
+        Expr::Number(_) | Expr::Str(_) => {}
+    }
+}
@@ -0,0 +1,161 @@
+//! The optimizer steps, and utilities to traverse the IR.
+
+use crate::{
+    ir::{Block, Expr, Stmt},
+    Step,
+};
+use solar_data_structures::map::FxHashSet;
+
+pub(crate) mod dead_code;
+pub(crate) mod disambiguator;
+pub(crate) mod inliner;
+pub(crate) mod literals;
+pub(crate) mod simplifier;
+pub(crate) mod structural;
+pub(crate) mod unused;
+
+/// The state shared by the steps that run on the code of an object.
+pub(crate) struct Context {
+    /// The names used in the code.
+    pub(crate) names: NameDispenser,
+    /// The expected number of runs of the deployed code.
+    pub(crate) runs: u32,
+}
+
+/// Runs a single step on the code of an object.
+pub(crate) fn run_step(step: Step, code: &mut Block, cx: &mut Context) {
+    trace!(step = %step.abbreviation(), "running optimizer step");
+    match step {
+        Step::FullInliner => inliner::run(code, cx),
+        Step::LiteralRematerialiser => literals::run(code),
+        Step::ExpressionSimplifier => simplifier::run(code),
+        Step::StructuralSimplifier => structural::run(code),
+        Step::DeadCodeEliminator => dead_code::run(code),
+        Step::UnusedPruner => unused::run(code),
+    }
+}
+
+/// Generates names that are not used anywhere in the code.
+#[derive(Default)]
+pub(crate) struct NameDispenser {
+    used: FxHashSet<String>,
+}
+
+impl NameDispenser {
+    /// Returns `base` if it is not used yet, or `base` followed by the first unused numeric
+    /// suffix, and marks the name as used.
+    pub(crate) fn fresh(&mut self, base: &str) -> String {
+        if self.used.insert(base.to_string()) {
+            return base.to_string();
+        }
+        (1..).map(|i| format!("{base}_{i}")).find(|name| self.used.insert(name.clone())).unwrap()
+    }
+}
+
+/// Calls `f` on the blocks directly nested in the statement.
+pub(crate) fn stmt_blocks_mut(stmt: &mut Stmt, mut f: impl FnMut(&mut Block)) {
+    match stmt {
+        Stmt::Block(block) | Stmt::If(_, block) => f(block),
+        Stmt::Switch(switch) => {
+            for (_, block) in &mut switch.cases {
+                f(block);
+            }
+            if let Some(block) = &mut switch.default {
+                f(block);
+            }
+        }
+        Stmt::For { init, step, body, .. } => {
+            f(init);
+            f(step);
+            f(body);
+        }
+        Stmt::FunctionDef(function) => f(&mut function.body),
+        Stmt::VarDecl(..)
+        | Stmt::Assign(..)
+        | Stmt::Expr(_)
+        | Stmt::Break
+        | Stmt::Continue
+        | Stmt::Leave => {}
+    }
+}
+
+/// Calls `f` on the block and all the blocks nested in it, innermost first.
+pub(crate) fn walk_blocks_mut(block: &mut Block, f: &mut impl FnMut(&mut Block)) {
+    for stmt in block.iter_mut() {
+        stmt_blocks_mut(stmt, |block| walk_blocks_mut(block, f));
+    }
+    f(block);
+}
+
+/// Calls `f` on the expressions directly contained in the statement, but not on their
+/// sub-expressions.
+pub(crate) fn stmt_exprs_mut(stmt: &mut Stmt, mut f: impl FnMut(&mut Expr)) {
+    match stmt {
+        Stmt::VarDecl(_, Some(expr))
+        | Stmt::Assign(_, expr)
+        | Stmt::Expr(expr)
+        | Stmt::If(expr, _)
+        | Stmt::For { cond: expr, .. } => f(expr),
+        Stmt::Switch(switch) => f(&mut switch.selector),
+        Stmt::VarDecl(_, None)
+        | Stmt::Block(_)
+        | Stmt::Break
+        | Stmt::Continue
+        | Stmt::Leave
+        | Stmt::FunctionDef(_) => {}
+    }
+}
+
+/// Calls `f` on all the expressions of the block and of its nested blocks, but not on their
+/// sub-expressions.
+pub(crate) fn walk_exprs_mut(block: &mut Block, f: &mut impl FnMut(&mut Expr)) {
+    walk_blocks_mut(block, &mut |block| {
+        for stmt in block.iter_mut() {
+            stmt_exprs_mut(stmt, &mut *f);
+        }
+    });
+}
+
+/// Calls `f` on all the statements of the block and of its nested blocks, outermost first.
+pub(crate) fn walk_stmts(block: &[Stmt], f: &mut impl FnMut(&Stmt)) {
+    for stmt in block {
+        f(stmt);
+        match stmt {
+            Stmt::Block(block) | Stmt::If(_, block) => walk_stmts(block, f),
+            Stmt::Switch(switch) => {
+                for (_, block) in &switch.cases {
+                    walk_stmts(block, f);
+                }
+                if let Some(block) = &switch.default {
+                    walk_stmts(block, f);
+                }
+            }
+            Stmt::For { init, step, body, .. } => {
+                walk_stmts(init, f);
+                walk_stmts(step, f);
+                walk_stmts(body, f);
+            }
+            Stmt::FunctionDef(function) => walk_stmts(&function.body, f),
+            Stmt::VarDecl(..)
+            | Stmt::Assign(..)
+            | Stmt::Expr(_)
+            | Stmt::Break
+            | Stmt::Continue
+            | Stmt::Leave => {}
+        }
+    }
+}
+
+/// Calls `f` on all the expressions of the block and of its nested blocks, including their
+/// sub-expressions.
+pub(crate) fn walk_exprs(block: &[Stmt], f: &mut impl FnMut(&Expr)) {
+    walk_stmts(block, &mut |stmt| match stmt {
+        Stmt::VarDecl(_, Some(expr))
+        | Stmt::Assign(_, expr)
+        | Stmt::Expr(expr)
+        | Stmt::If(expr, _)
+        | Stmt::For { cond: expr, .. } => expr.walk(f),
+        Stmt::Switch(switch) => switch.selector.walk(f),
+        _ => {}
+    });
+}
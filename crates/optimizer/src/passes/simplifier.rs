@@ -0,0 +1,81 @@
+//! Expression simplification: constant folding and arithmetic identities.
+
+use super::walk_exprs_mut;
+use crate::{
+    builtins::{evaluate, is_side_effect_free_expr},
+    ir::{Block, Expr},
+};
+use alloy_primitives::U256;
+
+pub(crate) fn run(code: &mut Block) {
+    walk_exprs_mut(code, &mut simplify);
+}
+
+fn simplify(expr: &mut Expr) {
+    let Expr::Call(name, args) = expr else { return };
+    for arg in args.iter_mut() {
+        simplify(arg);
+    }
+    if let Some(values) = args.iter().map(Expr::as_number).collect::<Option<Vec<_>>>() {
+        if let Some(value) = evaluate(name, &values) {
+            *expr = Expr::Number(value);
+            return;
+        }
+    }
+    if let Some(simplified) = simplify_identity(name, args) {
+        *expr = simplified;
+    }
+}
+
+/// Applies the identities of the builtin `name` with the given arguments.
+///
+/// Arguments are only dropped if evaluating them has no side effects.
+fn simplify_identity(name: &str, args: &mut [Expr]) -> Option<Expr> {
+    let zero = Some(U256::ZERO);
+    let one = Some(U256::from(1));
+    let max = Some(U256::MAX);
+    match args {
+        [x, y] => {
+            let (a, b) = (x.as_number(), y.as_number());
+            // Identical variables always have the same value.
+            let same_var = matches!(x, Expr::Var(_)) && x == y;
+            let iszero = |e| Expr::Call("iszero".into(), vec![e]);
+            match name {
+                "add" | "or" | "xor" if b == zero => Some(take(x)),
+                "add" | "or" | "xor" if a == zero => Some(take(y)),
+                "sub" if b == zero => Some(take(x)),
+                "mul" | "div" if b == one => Some(take(x)),
+                "mul" if a == one => Some(take(y)),
+                "and" if b == max => Some(take(x)),
+                "and" if a == max => Some(take(y)),
+                "shl" | "shr" | "sar" if a == zero => Some(take(y)),
+                "mul" | "and" | "div" | "mod" if b == zero && is_side_effect_free_expr(x) => {
+                    Some(Expr::Number(U256::ZERO))
+                }
+                "mul" | "and" | "div" if a == zero && is_side_effect_free_expr(y) => {
+                    Some(Expr::Number(U256::ZERO))
+                }
+                "eq" if b == zero => Some(iszero(take(x))),
+                "eq" if a == zero => Some(iszero(take(y))),
+                "sub" | "xor" | "lt" | "gt" if same_var => Some(Expr::Number(U256::ZERO)),
+                "eq" if same_var => Some(Expr::Number(U256::from(1))),
+                _ => None,
+            }
+        }
+        // `iszero(iszero(iszero(x)))` is `iszero(x)`.
+        [Expr::Call(inner, inner_args)] if name == "iszero" && inner == "iszero" => {
+            match &mut inner_args[..] {
+                [Expr::Call(innermost, x)] if innermost == "iszero" => {
+                    Some(Expr::Call("iszero".into(), std::mem::take(x)))
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Takes the expression, leaving a placeholder in its place.
+fn take(expr: &mut Expr) -> Expr {
+    std::mem::replace(expr, Expr::Number(U256::ZERO))
+}
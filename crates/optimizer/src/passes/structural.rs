@@ -0,0 +1,55 @@
+//! Simplification of the control flow that is known at compile time.
+
+use super::walk_blocks_mut;
+use crate::{
+    builtins::is_side_effect_free_expr,
+    ir::{Block, Expr, Stmt},
+};
+
+pub(crate) fn run(code: &mut Block) {
+    walk_blocks_mut(code, &mut |block| {
+        let stmts = std::mem::take(block);
+        for stmt in stmts {
+            simplify(stmt, block);
+        }
+    });
+}
+
+/// Pushes the simplified statement to `block`, if any.
+fn simplify(stmt: Stmt, block: &mut Block) {
+    match stmt {
+        Stmt::If(Expr::Number(cond), body) => {
+            if !cond.is_zero() {
+                block.push(Stmt::Block(body));
+            }
+        }
+        Stmt::If(cond, body) if body.is_empty() => {
+            if !is_side_effect_free_expr(&cond) {
+                block.push(Stmt::Expr(Expr::Call("pop".into(), vec![cond])));
+            }
+        }
+        Stmt::Switch(switch) if matches!(switch.selector, Expr::Number(_)) => {
+            let Expr::Number(selector) = switch.selector else { unreachable!() };
+            let body = switch
+                .cases
+                .into_iter()
+                .find_map(|(value, body)| (value == selector).then_some(body))
+                .or(switch.default);
+            if let Some(body) = body {
+                block.push(Stmt::Block(body));
+            }
+        }
+        Stmt::Switch(switch)
+            if switch.cases.is_empty() && is_side_effect_free_expr(&switch.selector) =>
+        {
+            if let Some(default) = switch.default {
+                block.push(Stmt::Block(default));
+            }
+        }
+        // The loop body is never executed, but the init block is.
+        Stmt::For { init, cond: Expr::Number(cond), .. } if cond.is_zero() => {
+            block.push(Stmt::Block(init));
+        }
+        stmt => block.push(stmt),
+    }
+}
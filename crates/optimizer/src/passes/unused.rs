@@ -0,0 +1,39 @@
+//! Removal of the functions and variables that are never referenced.
+
+use super::{walk_blocks_mut, walk_exprs, walk_stmts};
+use crate::{
+    builtins::is_side_effect_free_expr,
+    ir::{Block, Expr, Stmt},
+};
+use solar_data_structures::map::FxHashSet;
+
+pub(crate) fn run(code: &mut Block) {
+    let mut referenced = FxHashSet::default();
+    walk_exprs(code, &mut |expr| match expr {
+        Expr::Var(name) | Expr::Call(name, _) => {
+            referenced.insert(name.clone());
+        }
+        Expr::Number(_) | Expr::Str(_) => {}
+    });
+    // Assigned variables must stay declared.
+    walk_stmts(code, &mut |stmt| {
+        if let Stmt::Assign(names, _) = stmt {
+            referenced.extend(names.iter().cloned());
+        }
+    });
+
+    walk_blocks_mut(code, &mut |block| {
+        block.retain(|stmt| match stmt {
+            Stmt::FunctionDef(function) => referenced.contains(&function.name),
+            Stmt::VarDecl(names, value) => {
+                names.iter().any(|name| referenced.contains(name))
+                    || value.as_ref().is_some_and(|value| !is_side_effect_free_expr(value))
+            }
+            // `pop` discards the value of its argument.
+            Stmt::Expr(Expr::Call(name, args)) if name == "pop" => {
+                !args.iter().all(is_side_effect_free_expr)
+            }
+            _ => true,
+        });
+    });
+}
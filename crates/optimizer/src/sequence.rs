@@ -0,0 +1,159 @@
+use std::{fmt, str::FromStr};
+
+/// The default sequence of optimizer steps.
+pub const DEFAULT_SEQUENCE: &str = "[iTstDu]";
+
+/// The maximum number of times a repeated part of a sequence is run.
+pub(crate) const MAX_REPETITIONS: usize = 12;
+
+/// An optimizer step.
+///
+/// Steps are identified by the same abbreviations as the corresponding steps of solc's Yul
+/// optimizer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Step {
+    /// `i`: inlines calls to small functions in statements that declare or assign the result of
+    /// the call, or that discard it.
+    FullInliner,
+    /// `T`: replaces the references to variables that are never reassigned, i.e. that are in SSA
+    /// form, and whose value is a literal by that literal.
+    LiteralRematerialiser,
+    /// `s`: folds the calls to builtins with constant arguments, and applies arithmetic
+    /// identities such as `add(x, 0) = x`.
+    ExpressionSimplifier,
+    /// `t`: removes or unwraps the `if`, `switch`, and `for` statements whose control flow is
+    /// known at compile time.
+    StructuralSimplifier,
+    /// `D`: removes the unreachable statements that follow a statement which always terminates
+    /// the block.
+    DeadCodeEliminator,
+    /// `u`: removes the functions and variables that are never referenced.
+    UnusedPruner,
+}
+
+impl Step {
+    /// All the supported steps.
+    pub const ALL: [Self; 6] = [
+        Self::FullInliner,
+        Self::LiteralRematerialiser,
+        Self::ExpressionSimplifier,
+        Self::StructuralSimplifier,
+        Self::DeadCodeEliminator,
+        Self::UnusedPruner,
+    ];
+
+    /// Returns the step with the given abbreviation.
+    pub fn from_abbreviation(abbreviation: char) -> Option<Self> {
+        Self::ALL.into_iter().find(|step| step.abbreviation() == abbreviation)
+    }
+
+    /// Returns the abbreviation of the step.
+    pub const fn abbreviation(self) -> char {
+        match self {
+            Self::FullInliner => 'i',
+            Self::LiteralRematerialiser => 'T',
+            Self::ExpressionSimplifier => 's',
+            Self::StructuralSimplifier => 't',
+            Self::DeadCodeEliminator => 'D',
+            Self::UnusedPruner => 'u',
+        }
+    }
+}
+
+/// An item of a [`Sequence`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SequenceItem {
+    /// A single step.
+    Step(Step),
+    /// Steps that are repeated until the code does not change anymore: `[...]`.
+    Repeat(Vec<Step>),
+}
+
+/// A sequence of optimizer steps, in the format of solc's `--yul-optimizations`.
+///
+/// Each step is identified by its [abbreviation](Step::abbreviation). Steps enclosed in square
+/// brackets are repeated until the code does not change anymore, up to a limit. The steps after
+/// an optional colon form the cleanup sequence, which is run after the main sequence. Whitespace
+/// is ignored.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Sequence {
+    /// The main sequence.
+    pub main: Vec<SequenceItem>,
+    /// The cleanup sequence.
+    pub cleanup: Vec<SequenceItem>,
+}
+
+/// An error that occurred while parsing a [`Sequence`].
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum ParseSequenceError {
+    #[error("unknown or unsupported optimizer step `{0}`")]
+    UnknownStep(char),
+    #[error("nested brackets are not allowed")]
+    NestedBrackets,
+    #[error("unbalanced brackets")]
+    UnbalancedBrackets,
+    #[error("the cleanup sequence can only be specified once")]
+    MultipleCleanupSequences,
+}
+
+impl FromStr for Sequence {
+    type Err = ParseSequenceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut sequence = Self::default();
+        let mut cleanup = false;
+        let mut repeat: Option<Vec<Step>> = None;
+        for c in s.chars().filter(|c| !c.is_whitespace()) {
+            let items = if cleanup { &mut sequence.cleanup } else { &mut sequence.main };
+            match c {
+                '[' if repeat.is_some() => return Err(ParseSequenceError::NestedBrackets),
+                '[' => repeat = Some(Vec::new()),
+                ']' => match repeat.take() {
+                    Some(steps) => items.push(SequenceItem::Repeat(steps)),
+                    None => return Err(ParseSequenceError::UnbalancedBrackets),
+                },
+                ':' if repeat.is_some() => return Err(ParseSequenceError::UnbalancedBrackets),
+                ':' if cleanup => return Err(ParseSequenceError::MultipleCleanupSequences),
+                ':' => cleanup = true,
+                c => {
+                    let step =
+                        Step::from_abbreviation(c).ok_or(ParseSequenceError::UnknownStep(c))?;
+                    match &mut repeat {
+                        Some(steps) => steps.push(step),
+                        None => items.push(SequenceItem::Step(step)),
+                    }
+                }
+            }
+        }
+        if repeat.is_some() {
+            return Err(ParseSequenceError::UnbalancedBrackets);
+        }
+        Ok(sequence)
+    }
+}
+
+impl fmt::Display for Sequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let write_items = |f: &mut fmt::Formatter<'_>, items: &[SequenceItem]| {
+            for item in items {
+                match item {
+                    SequenceItem::Step(step) => write!(f, "{}", step.abbreviation())?,
+                    SequenceItem::Repeat(steps) => {
+                        f.write_str("[")?;
+                        for step in steps {
+                            write!(f, "{}", step.abbreviation())?;
+                        }
+                        f.write_str("]")?;
+                    }
+                }
+            }
+            Ok(())
+        };
+        write_items(f, &self.main)?;
+        if !self.cleanup.is_empty() {
+            f.write_str(":")?;
+            write_items(f, &self.cleanup)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,142 @@
+use super::*;
+use solar_ast::Arena;
+use solar_interface::{source_map::FileName, Session};
+use solar_parse::Parser;
+
+#[test]
+fn parse_sequence() {
+    for (s, expected) in [
+        ("", ""),
+        (DEFAULT_SEQUENCE, DEFAULT_SEQUENCE),
+        ("sTu:Dt", "sTu:Dt"),
+        (" [ s T ] u ", "[sT]u"),
+        (":[tD]", ":[tD]"),
+    ] {
+        let sequence = s.parse::<Sequence>().unwrap_or_else(|e| panic!("{s:?}: {e}"));
+        assert_eq!(sequence.to_string(), expected, "{s:?}");
+    }
+
+    for (s, expected) in [
+        ("x", ParseSequenceError::UnknownStep('x')),
+        ("[[s]]", ParseSequenceError::NestedBrackets),
+        ("[s", ParseSequenceError::UnbalancedBrackets),
+        ("s]", ParseSequenceError::UnbalancedBrackets),
+        ("[s:t]", ParseSequenceError::UnbalancedBrackets),
+        ("s:t:u", ParseSequenceError::MultipleCleanupSequences),
+    ] {
+        assert_eq!(s.parse::<Sequence>(), Err(expected), "{s:?}");
+    }
+}
+
+fn check(src: &str, settings: &OptimizerSettings, expected: &str) {
+    let sess = Session::builder().with_test_emitter().build();
+    sess.enter(|| {
+        let arena = Arena::new();
+        let mut parser =
+            Parser::from_source_code(&sess, &arena, FileName::Custom("test".into()), src.into())
+                .unwrap();
+        let ast = parser.parse_yul_file_object().map_err(|e| e.emit()).unwrap();
+        let mut object = Object::from_ast(&ast).unwrap();
+        optimize(&mut object, settings);
+        assert_eq!(object.to_string(), expected);
+    });
+}
+
+#[test]
+fn propagate_constants() {
+    let src = r#"
+object "A" {
+    code {
+        let x := 2
+        let y := add(x, 3)
+        sstore(0, mul(y, 1))
+    }
+}
+"#;
+    let expected = r#"object "A" {
+    code {
+        sstore(0, 5)
+    }
+}
+"#;
+    check(src, &OptimizerSettings::default(), expected);
+}
+
+#[test]
+fn inline_functions() {
+    let src = r#"
+object "B" {
+    code {
+        function double(a) -> r {
+            r := add(a, a)
+        }
+        let v := double(calldataload(0))
+        if lt(2, 1) { revert(0, 0) }
+        sstore(0, v)
+    }
+}
+"#;
+    let expected = r#"object "B" {
+    code {
+        let v
+        {
+            let a_1 := calldataload(0)
+            v := add(a_1, a_1)
+        }
+        sstore(0, v)
+    }
+}
+"#;
+    check(src, &OptimizerSettings::default(), expected);
+
+    // Inlining is disabled by the sequence.
+    let settings = OptimizerSettings { sequence: "[Tstu]".parse().unwrap(), ..Default::default() };
+    let expected = r#"object "B" {
+    code {
+        function double(a) -> r {
+            r := add(a, a)
+        }
+        let v := double(calldataload(0))
+        sstore(0, v)
+    }
+}
+"#;
+    check(src, &settings, expected);
+}
+
+#[test]
+fn remove_dead_code() {
+    let src = r#"
+object "C" {
+    code {
+        switch 1
+        case 0 { sstore(0, 1) }
+        default {
+            return(0, 0)
+            sstore(0, 2)
+        }
+        sstore(0, 3)
+    }
+    object "C_deployed" {
+        code {
+            for { let i := 0 } 0 { } { sstore(i, i) }
+        }
+    }
+}
+"#;
+    let expected = r#"object "C" {
+    code {
+        {
+            return(0, 0)
+        }
+        sstore(0, 3)
+    }
+    object "C_deployed" {
+        code {
+            { }
+        }
+    }
+}
+"#;
+    check(src, &OptimizerSettings::default(), expected);
+}
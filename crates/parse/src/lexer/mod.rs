@@ -2,10 +2,12 @@
 
 use solar_ast::{
     token::{BinOpToken, CommentKind, Delimiter, Token, TokenKind, TokenLitKind},
-    Base,
+    Base, Comment, CommentStyle, Comments,
 };
 use solar_interface::{
-    diagnostics::DiagCtxt, source_map::SourceFile, sym, BytePos, Session, Span, Symbol,
+    diagnostics::{DiagCtxt, Lint},
+    source_map::SourceFile,
+    sym, BytePos, Session, Span, Symbol,
 };
 
 mod cursor;
@@ -46,6 +48,14 @@ pub struct Lexer<'sess, 'src> {
     /// in this file, it's safe to treat further occurrences of the non-breaking
     /// space character as whitespace.
     nbsp_is_whitespace: bool,
+
+    /// The `solar-disable` comments that have not been closed by a `solar-enable` comment yet,
+    /// with the start of the suppressed region.
+    open_suppressions: Vec<(Lint, BytePos)>,
+
+    /// The comments collected by [`into_tokens_and_comments`](Self::into_tokens_and_comments),
+    /// if enabled with [`collect_comments`](Self::collect_comments).
+    comments: Option<Vec<Comment>>,
 }
 
 impl<'sess, 'src> Lexer<'sess, 'src> {
@@ -69,6 +79,8 @@ impl<'sess, 'src> Lexer<'sess, 'src> {
             cursor: Cursor::new(src),
             token: Token::DUMMY,
             nbsp_is_whitespace: false,
+            open_suppressions: Vec::new(),
+            comments: None,
         };
         (lexer.token, _) = lexer.bump();
         lexer
@@ -80,13 +92,29 @@ impl<'sess, 'src> Lexer<'sess, 'src> {
         &self.sess.dcx
     }
 
+    /// Enables collecting all the comments, including doc-comments, into a side table that is
+    /// returned by [`into_tokens_and_comments`](Self::into_tokens_and_comments).
+    pub fn collect_comments(mut self) -> Self {
+        self.comments = Some(Vec::new());
+        self
+    }
+
     /// Consumes the lexer and collects the remaining tokens into a vector.
     ///
     /// Note that this skips comments, as [required by the parser](crate::Parser::new).
     ///
     /// Prefer using this method instead of manually collecting tokens using [`Iterator`].
+    pub fn into_tokens(self) -> Vec<Token> {
+        self.into_tokens_and_comments().0
+    }
+
+    /// Consumes the lexer and collects the remaining tokens into a vector, like
+    /// [`into_tokens`](Self::into_tokens), and the comments into a side table.
+    ///
+    /// The comments are only collected if enabled with
+    /// [`collect_comments`](Self::collect_comments); otherwise, the table is empty.
     #[instrument(name = "lex", level = "debug", skip_all)]
-    pub fn into_tokens(mut self) -> Vec<Token> {
+    pub fn into_tokens_and_comments(mut self) -> (Vec<Token>, Comments) {
         // `src.len() / 8` is an estimate of the number of tokens in the source.
         let mut tokens = Vec::with_capacity(self.src.len() / 8);
         loop {
@@ -94,6 +122,14 @@ impl<'sess, 'src> Lexer<'sess, 'src> {
             if token.is_eof() {
                 break;
             }
+            if let TokenKind::Comment(is_doc, kind, symbol) = token.kind {
+                if self.comments.is_some() {
+                    let style = self.comment_style(token.span);
+                    let comment =
+                        Comment { kind, is_doc, span: token.span, symbol, style, attachment: None };
+                    self.comments.as_mut().unwrap().push(comment);
+                }
+            }
             if token.is_comment() {
                 continue;
             }
@@ -105,7 +141,22 @@ impl<'sess, 'src> Lexer<'sess, 'src> {
             ratio = %format!("{:.2}", self.src.len() as f64 / tokens.len() as f64),
             "lexed"
         );
-        tokens
+        (tokens, Comments::new(self.comments.unwrap_or_default()))
+    }
+
+    /// Returns the style of the comment with the given span, based on the code around it.
+    fn comment_style(&self, span: Span) -> CommentStyle {
+        let before = &self.src[..self.src_index(span.lo())];
+        let before = &before[before.rfind('\n').map_or(0, |i| i + 1)..];
+        let after = &self.src[self.src_index(span.hi())..];
+        let after = &after[..after.find('\n').unwrap_or(after.len())];
+        if !after.trim().is_empty() {
+            CommentStyle::Mixed
+        } else if !before.trim().is_empty() {
+            CommentStyle::Trailing
+        } else {
+            CommentStyle::Isolated
+        }
     }
 
     /// Returns the next token, advancing the lexer.
@@ -142,6 +193,9 @@ impl<'sess, 'src> Lexer<'sess, 'src> {
                     // Opening delimiter is not included into the symbol.
                     let content_start = start + BytePos(if is_doc { 3 } else { 2 });
                     let content = self.str_from(content_start);
+                    if !is_doc {
+                        self.suppression_comment(start, content);
+                    }
                     self.cook_doc_comment(content_start, content, is_doc, CommentKind::Line)
                 }
                 RawTokenKind::BlockComment { is_doc, terminated } => {
@@ -279,13 +333,79 @@ impl<'sess, 'src> Lexer<'sess, 'src> {
                     }
                 }
 
-                RawTokenKind::Eof => TokenKind::Eof,
+                RawTokenKind::Eof => {
+                    // Unclosed `solar-disable` comments extend until the end of the file.
+                    for (lint, lo) in std::mem::take(&mut self.open_suppressions) {
+                        self.dcx().suppress(lint, self.new_span(lo, self.pos));
+                    }
+                    TokenKind::Eof
+                }
             };
             let span = self.new_span(start, self.pos);
             return (Token::new(kind, span), preceded_by_whitespace);
         }
     }
 
+    /// Handles the comments that suppress warnings:
+    /// - `// solar-disable-next-line [LINT...]`: in the next line;
+    /// - `// solar-disable [LINT...]`: until a `// solar-enable` comment with the same lints, or
+    ///   the end of the file.
+    ///
+    /// If no lints are given, all warnings are suppressed.
+    fn suppression_comment(&mut self, start: BytePos, content: &str) {
+        let Some(rest) = content.trim().strip_prefix("solar-") else { return };
+        let (directive, lints) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        if !matches!(directive, "disable-next-line" | "disable" | "enable") {
+            return;
+        }
+
+        let mut parsed = Vec::new();
+        for lint in lints.split(|c: char| c == ',' || c.is_whitespace()).filter(|s| !s.is_empty()) {
+            match lint.parse::<Lint>() {
+                Ok(lint) => parsed.push(lint),
+                Err(e) => {
+                    let msg = format!("invalid lint `{lint}` in `solar-{directive}` comment");
+                    let span = self.new_span(start, self.pos);
+                    self.dcx().warn(msg).span(span).note(e).emit();
+                }
+            }
+        }
+        if parsed.is_empty() {
+            if !lints.trim().is_empty() {
+                return;
+            }
+            parsed.push(Lint::Warnings);
+        }
+
+        match directive {
+            "disable-next-line" => {
+                let rest = self.str_from_to_end(self.pos);
+                let Some(next_line_start) = rest.find('\n').map(|i| i + 1) else { return };
+                let next_line_len =
+                    rest[next_line_start..].find('\n').unwrap_or(rest.len() - next_line_start);
+                let lo = self.pos + BytePos::from_usize(next_line_start);
+                let span = self.new_span(lo, lo + BytePos::from_usize(next_line_len));
+                for lint in parsed {
+                    self.dcx().suppress(lint, span);
+                }
+            }
+            "disable" => {
+                self.open_suppressions.extend(parsed.into_iter().map(|lint| (lint, start)))
+            }
+            "enable" => {
+                for lint in parsed {
+                    let Some(i) = self.open_suppressions.iter().position(|(open, _)| *open == lint)
+                    else {
+                        continue;
+                    };
+                    let (lint, lo) = self.open_suppressions.remove(i);
+                    self.dcx().suppress(lint, self.new_span(lo, start));
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+
     fn cook_doc_comment(
         &self,
         _content_start: BytePos,
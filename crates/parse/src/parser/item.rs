@@ -12,6 +12,9 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
     }
 
     /// Parses a list of items until the given token is encountered.
+    ///
+    /// Items that fail to parse are reported and skipped, so that the rest of the list can still
+    /// be parsed.
     fn parse_items(&mut self, end: &TokenKind) -> PResult<'sess, Box<'ast, [Item<'ast>]>> {
         let get_msg_note = |this: &mut Self| {
             let (prefix, list, link);
@@ -31,19 +34,34 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
         };
 
         let mut items = Vec::new();
-        while let Some(item) = self.parse_item()? {
-            if self.in_contract && !item.is_allowed_in_contract() {
-                let msg = format!("{}s are not allowed in contracts", item.description());
-                let (_, note) = get_msg_note(self);
-                self.dcx().err(msg).span(item.span).note(note).emit();
-            } else {
-                items.push(item);
+        loop {
+            match self.parse_item() {
+                Ok(Some(item)) => {
+                    if self.in_contract && !item.is_allowed_in_contract() {
+                        let msg = format!("{}s are not allowed in contracts", item.description());
+                        let (_, note) = get_msg_note(self);
+                        self.dcx().err(msg).span(item.span).note(note).emit();
+                    } else {
+                        items.push(item);
+                    }
+                }
+                Ok(None) => {
+                    if self.eat(end) {
+                        break;
+                    }
+                    let (msg, note) = get_msg_note(self);
+                    self.dcx().err(msg).span(self.token.span).note(note).emit();
+                    if self.token.is_eof() {
+                        break;
+                    }
+                    self.recover_to_boundary(!self.in_contract);
+                }
+                Err(e) => {
+                    e.emit();
+                    self.recover_to_boundary(!self.in_contract);
+                }
             }
         }
-        if !self.eat(end) {
-            let (msg, note) = get_msg_note(self);
-            return Err(self.dcx().err(msg).span(self.token.span).note(note));
-        }
         Ok(self.alloc_vec(items))
     }
 
@@ -52,7 +70,7 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
     pub fn parse_item(&mut self) -> PResult<'sess, Option<Item<'ast>>> {
         let docs = self.parse_doc_comments()?;
         self.parse_spanned(Self::parse_item_kind)
-            .map(|(span, kind)| kind.map(|kind| Item { docs, span, kind }))
+            .map(|(span, kind)| kind.map(|kind| Item { id: self.next_node_id(), docs, span, kind }))
     }
 
     fn parse_item_kind(&mut self) -> PResult<'sess, Option<ItemKind<'ast>>> {
@@ -1503,4 +1521,105 @@ mod tests {
             ("0.8.1", "0.8 || 0.9", true),
         ]);
     }
+
+    #[test]
+    fn comments() {
+        let src = "// SPDX-License-Identifier: MIT
+contract C {
+    /// Docs.
+    uint a; // trailing
+    function f(/* none */) {
+        // empty
+    }
+}
+";
+        let sess = Session::builder().with_test_emitter().build();
+        sess.enter(|| {
+            let arena = Arena::new();
+            let file = sess
+                .source_map()
+                .new_source_file(FileName::Custom("comments".into()), || Ok(src.into()))
+                .unwrap();
+            let mut parser = Parser::from_source_file_with_comments(&sess, &arena, &file);
+            let source_unit = parser.parse_file().map_err(|e| e.emit()).unwrap();
+            let mut comments = parser.take_comments();
+            comments.attach(&source_unit, &file);
+
+            let first_line = |span: Span| {
+                let snippet = sess.source_map().span_to_snippet(span).unwrap();
+                snippet.lines().next().unwrap().to_string()
+            };
+            let comments = comments
+                .iter()
+                .map(|comment| {
+                    let attachment = comment.attachment.unwrap();
+                    let node = first_line(attachment.span);
+                    (first_line(comment.span), comment.style, attachment.position, node)
+                })
+                .collect::<Vec<_>>();
+            let comments = comments
+                .iter()
+                .map(|(comment, style, position, node)| {
+                    (comment.as_str(), *style, *position, node.as_str())
+                })
+                .collect::<Vec<_>>();
+            use CommentPosition::*;
+            use CommentStyle::*;
+            let function = "function f(/* none */) {";
+            assert_eq!(
+                comments,
+                [
+                    ("// SPDX-License-Identifier: MIT", Isolated, Leading, "contract C {"),
+                    ("/// Docs.", Isolated, Leading, "uint a;"),
+                    ("// trailing", Trailing, Trailing, "uint a;"),
+                    ("/* none */", Mixed, Inner, function),
+                    ("// empty", Isolated, Inner, function),
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn pretty_print() {
+        let src = r#"import "a.sol" as a;
+import {X, Y as Z} from 'b.sol';
+import * as c from "c.sol";
+pragma abicoder v2;
+using {add as +, neg as -} for T global;
+type T is int128;
+function g(T x) pure returns (string memory) {
+    if (true) { if (false) return "a\"b\n"; } else return unicode"héllo";
+    return hex"00ff";
+}
+"#;
+        let expected = r#"import "a.sol" as a;
+import {X, Y as Z} from "b.sol";
+import * as c from "c.sol";
+pragma abicoder v2;
+using {add as +, neg as -} for T global;
+type T is int128;
+
+function g(T x) pure returns (string memory) {
+    if (true) {
+        if (false) return "a\"b\n";
+    } else return unicode"héllo";
+    return hex"00ff";
+}
+"#;
+        let sess = Session::builder().with_test_emitter().build();
+        sess.enter(|| {
+            let print = |name: &str, src: &str| {
+                let arena = Arena::new();
+                let name = FileName::Custom(name.into());
+                let mut parser =
+                    Parser::from_source_code(&sess, &arena, name, src.to_string()).unwrap();
+                let source_unit = parser.parse_file().map_err(|e| e.emit()).unwrap();
+                sess.dcx.has_errors().unwrap();
+                pretty::source_unit_to_string(&source_unit)
+            };
+            let printed = print("src", src);
+            assert_eq!(printed, expected);
+            assert_eq!(print("printed", &printed), expected, "printing is not idempotent");
+        });
+    }
 }
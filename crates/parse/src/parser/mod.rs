@@ -3,7 +3,7 @@ use smallvec::SmallVec;
 use solar_ast::{
     self as ast,
     token::{Delimiter, Token, TokenKind},
-    AstPath, Box, DocComment, DocComments, PathSlice,
+    AstPath, Box, Comments, DocComment, DocComments, NodeId, PathSlice,
 };
 use solar_data_structures::{fmt::or_list, BumpExt};
 use solar_interface::{
@@ -11,11 +11,12 @@ use solar_interface::{
     source_map::{FileName, SourceFile},
     Ident, Result, Session, Span, Symbol,
 };
-use std::{fmt, path::Path};
+use std::{cell::Cell, fmt, path::Path};
 
 mod expr;
 mod item;
 mod lit;
+mod natspec;
 mod stmt;
 mod ty;
 mod yul;
@@ -45,6 +46,12 @@ pub struct Parser<'sess, 'ast> {
 
     /// The token stream.
     tokens: std::vec::IntoIter<Token>,
+
+    /// The comments collected by the lexer, if enabled. See [`Lexer::collect_comments`].
+    comments: Comments,
+
+    /// The number of node IDs assigned so far. See [`next_node_id`](Self::next_node_id).
+    node_count: Cell<u32>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -136,6 +143,8 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
             in_yul: false,
             in_contract: false,
             tokens: tokens.into_iter(),
+            comments: Comments::default(),
+            node_count: Cell::new(0),
         };
         parser.bump();
         parser
@@ -181,9 +190,41 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
         Self::from_lexer(arena, Lexer::from_source_file(sess, file))
     }
 
+    /// Creates a new parser from a source file, collecting its comments.
+    ///
+    /// See [`comments`](Self::comments).
+    pub fn from_source_file_with_comments(
+        sess: &'sess Session,
+        arena: &'ast ast::Arena,
+        file: &SourceFile,
+    ) -> Self {
+        Self::from_lexer(arena, Lexer::from_source_file(sess, file).collect_comments())
+    }
+
     /// Creates a new parser from a lexer.
+    ///
+    /// The comments are kept if the lexer [collects them](Lexer::collect_comments).
     pub fn from_lexer(arena: &'ast ast::Arena, lexer: Lexer<'sess, '_>) -> Self {
-        Self::new(lexer.sess, arena, lexer.into_tokens())
+        let sess = lexer.sess;
+        let (tokens, comments) = lexer.into_tokens_and_comments();
+        let mut parser = Self::new(sess, arena, tokens);
+        parser.comments = comments;
+        parser
+    }
+
+    /// Returns the comments collected by the lexer.
+    ///
+    /// This is empty unless the parser was created with
+    /// [`from_source_file_with_comments`](Self::from_source_file_with_comments), or from a lexer
+    /// that [collects comments](Lexer::collect_comments). The comments can be attached to the
+    /// parsed AST nodes with [`Comments::attach`].
+    pub fn comments(&self) -> &Comments {
+        &self.comments
+    }
+
+    /// Takes the comments collected by the lexer. See [`comments`](Self::comments).
+    pub fn take_comments(&mut self) -> Comments {
+        std::mem::take(&mut self.comments)
     }
 
     /// Returns the diagnostic context.
@@ -192,6 +233,13 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
         &self.sess.dcx
     }
 
+    /// Returns a new node ID, unique within the parsed source.
+    pub fn next_node_id(&self) -> NodeId {
+        let id = self.node_count.get();
+        self.node_count.set(id + 1);
+        NodeId::new(id)
+    }
+
     /// Allocates an object on the AST arena.
     pub fn alloc<T>(&self, value: T) -> Box<'ast, T> {
         self.arena.alloc(value)
@@ -708,6 +756,40 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
         Ok((self.alloc_smallvec(v), recovered))
     }
 
+    /// Skips tokens until the next item or statement boundary, to recover from a parse error.
+    ///
+    /// Stops after a `;` or a `{ ... }` block at the current nesting level, or before an unmatched
+    /// `}`, which is also consumed if `eat_unmatched_brace` is `true`.
+    fn recover_to_boundary(&mut self, eat_unmatched_brace: bool) {
+        let mut depth = 0usize;
+        loop {
+            match self.token.kind {
+                TokenKind::Eof => break,
+                TokenKind::Semi if depth == 0 => {
+                    self.bump();
+                    break;
+                }
+                TokenKind::OpenDelim(_) => depth += 1,
+                TokenKind::CloseDelim(Delimiter::Brace) if depth == 0 => {
+                    if eat_unmatched_brace {
+                        self.bump();
+                    }
+                    break;
+                }
+                TokenKind::CloseDelim(delim) if depth > 0 => {
+                    depth -= 1;
+                    if depth == 0 && delim == Delimiter::Brace {
+                        self.bump();
+                        break;
+                    }
+                }
+                _ => {}
+            }
+            self.bump();
+        }
+        self.expected_tokens.clear();
+    }
+
     /// Advance the parser by one token.
     pub fn bump(&mut self) {
         let mut next = self.tokens.next().unwrap_or(Token::EOF);
@@ -825,9 +907,10 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
             if !is_doc {
                 self.comment_in_stream(span);
             }
-            doc_comments.push(DocComment { kind, span, symbol });
+            doc_comments.push(DocComment { kind, span, symbol, natspec: Default::default() });
             self.bump();
         }
+        self.parse_natspec(&mut doc_comments);
         Ok(self.alloc_smallvec(doc_comments))
     }
 
@@ -0,0 +1,130 @@
+use crate::Parser;
+use solar_ast::{token::CommentKind, DocComment, NatSpecItem, NatSpecKind};
+use solar_interface::{Ident, Span, Symbol};
+
+impl<'sess, 'ast> Parser<'sess, 'ast> {
+    /// Parses the NatSpec items of the given doc-comments, and stores them in the comment in which
+    /// their tag starts.
+    ///
+    /// Untagged text at the start is an implicit `@notice`. Lines that do not start with a tag
+    /// continue the previous item, even across separate comments.
+    ///
+    /// Reference: <https://docs.soliditylang.org/en/latest/natspec-format.html>
+    pub(super) fn parse_natspec(&self, comments: &mut [DocComment<'ast>]) {
+        let mut items = comments.iter().map(|_| Vec::new()).collect::<Vec<_>>();
+        let mut current: Option<(usize, Span, NatSpecKind, String)> = None;
+        // Whether the last tag was invalid, in which case its continuation lines are ignored.
+        let mut invalid = false;
+        let mut flush = |current: &mut Option<(usize, Span, NatSpecKind, String)>| {
+            if let Some((i, span, kind, content)) = current.take() {
+                items[i].push(NatSpecItem { span, kind, content: Symbol::intern(&content) });
+            }
+        };
+
+        for (i, comment) in comments.iter().enumerate() {
+            // Both `///` and `/**` are 3 bytes long.
+            let start = comment.span.lo() + 3;
+            let span =
+                |lo: usize, len: usize| Span::new(start + lo as u32, start + (lo + len) as u32);
+
+            let text = comment.symbol.as_str();
+            let mut line_start = 0;
+            for line in text.split('\n') {
+                let line_offset = line_start;
+                line_start += line.len() + 1;
+
+                let mut trimmed = line.trim_start();
+                if comment.kind == CommentKind::Block {
+                    if let Some(rest) = trimmed.strip_prefix('*') {
+                        trimmed = rest.trim_start();
+                    }
+                }
+                let trimmed = trimmed.trim_end();
+                if trimmed.is_empty() {
+                    continue;
+                }
+                // The offset of a suffix of `line` in the comment.
+                let offset_of = |s: &str| line_offset + line.len() - s.len();
+
+                let Some(tag_and_rest) = trimmed.strip_prefix('@') else {
+                    if invalid {
+                        continue;
+                    }
+                    match &mut current {
+                        Some((_, _, _, content)) => {
+                            if !content.is_empty() {
+                                content.push(' ');
+                            }
+                            content.push_str(trimmed);
+                        }
+                        None => {
+                            let span = span(offset_of(trimmed), trimmed.len());
+                            let kind = NatSpecKind::Notice;
+                            current = Some((i, span, kind, trimmed.to_string()));
+                        }
+                    }
+                    continue;
+                };
+
+                flush(&mut current);
+                invalid = true;
+                let (tag, rest) =
+                    tag_and_rest.split_once(char::is_whitespace).unwrap_or((tag_and_rest, ""));
+                let rest = rest.trim_start();
+                let tag_span = span(offset_of(trimmed), 1 + tag.len());
+                let mut content = rest;
+                let kind = match tag {
+                    "title" => NatSpecKind::Title,
+                    "author" => NatSpecKind::Author,
+                    "notice" => NatSpecKind::Notice,
+                    "dev" => NatSpecKind::Dev,
+                    "return" => NatSpecKind::Return,
+                    "param" | "inheritdoc" => {
+                        let (word, after) =
+                            rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+                        if word.is_empty() {
+                            let what = if tag == "param" { "a parameter" } else { "a contract" };
+                            let msg = format!("expected the name of {what} after `@{tag}`");
+                            self.dcx().err(msg).span(tag_span).emit();
+                            continue;
+                        }
+                        let ident =
+                            Ident::new(Symbol::intern(word), span(offset_of(rest), word.len()));
+                        content = after.trim_start();
+                        if tag == "param" {
+                            NatSpecKind::Param { name: ident }
+                        } else {
+                            NatSpecKind::Inheritdoc { contract: ident }
+                        }
+                    }
+                    _ => {
+                        if let Some(name) = tag.strip_prefix("custom:") {
+                            let name_span = span(offset_of(trimmed) + 8, name.len());
+                            let valid = name.starts_with(|c: char| c.is_ascii_lowercase())
+                                && name.bytes().all(|b| b.is_ascii_lowercase() || b == b'-');
+                            if !valid {
+                                let msg = format!("invalid custom NatSpec tag name `{name}`");
+                                let help = "custom tag names must start with a lowercase letter \
+                                            and only contain lowercase letters and hyphens";
+                                self.dcx().err(msg).span(tag_span).help(help).emit();
+                                continue;
+                            }
+                            NatSpecKind::Custom {
+                                name: Ident::new(Symbol::intern(name), name_span),
+                            }
+                        } else {
+                            let name_span = span(offset_of(trimmed) + 1, tag.len());
+                            NatSpecKind::Unknown(Ident::new(Symbol::intern(tag), name_span))
+                        }
+                    }
+                };
+                invalid = false;
+                current = Some((i, tag_span, kind, content.to_string()));
+            }
+        }
+        flush(&mut current);
+        for (comment, items) in comments.iter_mut().zip(items) {
+            comment.natspec = self.alloc_vec(items);
+        }
+    }
+}
@@ -1,16 +1,21 @@
 use super::item::VarFlags;
-use crate::{parser::SeqSep, PResult, Parser};
+use crate::{PResult, Parser};
 use smallvec::SmallVec;
 use solar_ast::{token::*, *};
 use solar_data_structures::BumpExt;
-use solar_interface::{kw, sym, Ident, Span};
+use solar_interface::{diagnostics::Applicability, kw, sym, Ident, Span};
 
 impl<'sess, 'ast> Parser<'sess, 'ast> {
     /// Parses a statement.
     #[instrument(level = "debug", skip_all)]
     pub fn parse_stmt(&mut self) -> PResult<'sess, Stmt<'ast>> {
         let docs = self.parse_doc_comments()?;
-        self.parse_spanned(Self::parse_stmt_kind).map(|(span, kind)| Stmt { docs, kind, span })
+        self.parse_spanned(Self::parse_stmt_kind).map(|(span, kind)| Stmt {
+            id: self.next_node_id(),
+            docs,
+            kind,
+            span,
+        })
     }
 
     /// Parses a statement into a new allocation.
@@ -47,7 +52,13 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
             Ok(StmtKind::Return(expr))
         } else if self.eat_keyword(kw::Throw) {
             let msg = "`throw` statements have been removed; use `revert`, `require`, or `assert` instead";
-            Err(self.dcx().err(msg).span(self.prev_token.span))
+            let span = self.prev_token.span;
+            Err(self.dcx().err(msg).span(span).span_suggestion(
+                span,
+                "use `revert` instead",
+                "revert()",
+                Applicability::MachineApplicable,
+            ))
         } else if self.eat_keyword(kw::Try) {
             semi = false;
             self.parse_stmt_try().map(|stmt| StmtKind::Try(self.alloc(stmt)))
@@ -73,8 +84,31 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
     }
 
     /// Parses a block of statements.
+    ///
+    /// Statements that fail to parse are reported and replaced with [`StmtKind::Err`], so that the
+    /// rest of the block can still be parsed.
     pub(super) fn parse_block(&mut self) -> PResult<'sess, Block<'ast>> {
-        self.parse_delim_seq(Delimiter::Brace, SeqSep::none(), true, Self::parse_stmt)
+        self.expect(&TokenKind::OpenDelim(Delimiter::Brace))?;
+        let mut stmts = SmallVec::<[_; 8]>::new();
+        while !self.check(&TokenKind::CloseDelim(Delimiter::Brace)) {
+            if let TokenKind::CloseDelim(..) | TokenKind::Eof = self.token.kind {
+                break;
+            }
+            let lo = self.token.span;
+            match self.parse_stmt() {
+                Ok(stmt) => stmts.push(stmt),
+                Err(e) => {
+                    let guar = e.emit();
+                    self.recover_to_boundary(false);
+                    let span = lo.to(self.prev_token.span);
+                    let id = self.next_node_id();
+                    let docs = Default::default();
+                    stmts.push(Stmt { id, docs, span, kind: StmtKind::Err(guar) });
+                }
+            }
+        }
+        self.expect(&TokenKind::CloseDelim(Delimiter::Brace))?;
+        Ok(self.alloc_smallvec(stmts))
     }
 
     /// Parses an if statement.
@@ -217,6 +251,7 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
                         Self::parse_expr,
                     )?;
                     let partially_parsed = Expr {
+                        id: self.next_node_id(),
                         span: lo.to(self.prev_token.span),
                         kind: ExprKind::Tuple(self.alloc_smallvec(components)),
                     };
@@ -433,20 +468,23 @@ impl<'ast> IndexAccessedPath<'ast> {
         let mut path = self.path.into_iter();
 
         let mut expr = parser.alloc(match path.next()? {
-            IapKind::Member(ident) => Expr::from_ident(ident),
+            IapKind::Member(ident) => Expr::from_ident(parser.next_node_id(), ident),
             IapKind::MemberTy(span, kind) => {
-                Expr { span, kind: ExprKind::Type(Type { span, kind: TypeKind::Elementary(kind) }) }
+                let ty = Type { span, kind: TypeKind::Elementary(kind) };
+                Expr::from_ty(parser.next_node_id(), ty)
             }
             IapKind::Index(..) => panic!("should not happen"),
         });
         for index in path {
             expr = parser.alloc(match index {
                 IapKind::Member(ident) => {
-                    Expr { span: expr.span.to(ident.span), kind: ExprKind::Member(expr, ident) }
+                    let span = expr.span.to(ident.span);
+                    Expr { id: parser.next_node_id(), span, kind: ExprKind::Member(expr, ident) }
                 }
                 IapKind::MemberTy(..) => panic!("should not happen"),
                 IapKind::Index(span, kind) => {
-                    Expr { span: expr.span.to(span), kind: ExprKind::Index(expr, kind) }
+                    let span = expr.span.to(span);
+                    Expr { id: parser.next_node_id(), span, kind: ExprKind::Index(expr, kind) }
                 }
             });
         }
@@ -465,6 +503,7 @@ fn smallvec_repeat_none<T>(n: usize) -> SmallVec<[Option<T>; 8]> {
 mod tests {
     use super::*;
     use solar_interface::{source_map::FileName, Result, Session};
+    use std::ops::ControlFlow;
 
     #[test]
     fn optional_items_seq() {
@@ -508,4 +547,50 @@ mod tests {
             ("(a,,c)", &[Some("a"), None, Some("c")]),
         ]);
     }
+
+    #[test]
+    fn node_ids() {
+        struct CollectIds(Vec<NodeId>);
+
+        impl<'ast> visit::Visit<'ast> for CollectIds {
+            type BreakValue = solar_data_structures::Never;
+
+            fn visit_item(&mut self, item: &'ast Item<'ast>) -> ControlFlow<Self::BreakValue> {
+                self.0.push(item.id);
+                self.walk_item(item)
+            }
+
+            fn visit_stmt(&mut self, stmt: &'ast Stmt<'ast>) -> ControlFlow<Self::BreakValue> {
+                self.0.push(stmt.id);
+                self.walk_stmt(stmt)
+            }
+
+            fn visit_expr(&mut self, expr: &'ast Expr<'ast>) -> ControlFlow<Self::BreakValue> {
+                self.0.push(expr.id);
+                self.walk_expr(expr)
+            }
+        }
+
+        let src = "contract C { function f(uint[] memory a) { a[0] = (1 + 2) * a.length; } }";
+        solar_interface::enter(|| -> Result {
+            let sess = Session::builder().with_test_emitter().build();
+            let parse = || -> Result<Vec<NodeId>> {
+                let arena = Arena::new();
+                let name = FileName::Custom("test".into());
+                let mut parser = Parser::from_source_code(&sess, &arena, name, src.into())?;
+                let source_unit = parser.parse_file().map_err(|e| e.emit())?;
+                let mut ids = CollectIds(Vec::new());
+                let _ = visit::Visit::visit_source_unit(&mut ids, &source_unit);
+                Ok(ids.0)
+            };
+            let mut ids = parse()?;
+            assert_eq!(ids, parse()?, "node IDs are not stable");
+            ids.sort();
+            let n = ids.len();
+            ids.dedup();
+            assert_eq!(ids.len(), n, "node IDs are not unique");
+            sess.dcx.has_errors()
+        })
+        .unwrap();
+    }
 }
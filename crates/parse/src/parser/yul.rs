@@ -211,7 +211,8 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
         if branches.is_empty() {
             let span = lo.to(self.prev_token.span);
             if default_case.is_none() {
-                self.dcx().err("`switch` statement has no cases").span(span).emit();
+                let msg = "`switch` statement has no cases";
+                self.dcx().err(msg).code(error_code!(2418)).span(span).emit();
             } else {
                 self.dcx()
                     .warn("`switch` statement has only a default case")
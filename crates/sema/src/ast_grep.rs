@@ -0,0 +1,271 @@
+//! Structural queries over the AST, used by `solar ast-grep`.
+
+use solar_ast::{self as ast, visit::Visit, yul};
+use solar_data_structures::Never;
+use solar_interface::{source_map::SourceFile, Ident, Result, Session, Span};
+use solar_parse::Parser;
+use std::{fmt, ops::ControlFlow, str::FromStr};
+
+/// The item kinds that can be queried.
+const ITEM_KINDS: &[&str] =
+    &["contract", "function", "modifier", "variable", "struct", "enum", "error", "event"];
+
+/// A structural query over the AST.
+///
+/// Queries are written as `KIND` or `KIND:NAME`:
+/// - `member:NAME`: member accesses, like `member:delegatecall`. The name can be qualified with
+///   the identifiers that the member is accessed on, like `member:tx.origin`;
+/// - `call:NAME`: calls of a function or member named `NAME`, including Yul builtins;
+/// - `ident:NAME`: identifiers used in expressions;
+/// - `undocumented`: functions and modifiers without NatSpec comments;
+/// - `contract`, `function`, `modifier`, `variable`, `struct`, `enum`, `error`, `event`: items of
+///   the given kind, optionally with the given name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Query {
+    /// `member:NAME`. The last segment is the member name.
+    Member(Vec<String>),
+    /// `call:NAME`.
+    Call(String),
+    /// `ident:NAME`.
+    Ident(String),
+    /// `undocumented`.
+    Undocumented,
+    /// An item kind, with an optional name.
+    Item(&'static str, Option<String>),
+}
+
+impl FromStr for Query {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, name) = match s.split_once(':') {
+            Some((kind, name)) => (kind, Some(name)),
+            None => (s, None),
+        };
+        if name.is_some_and(|name| {
+            name.split('.').any(str::is_empty) || name.contains(char::is_whitespace)
+        }) {
+            return Err(format!("invalid name in query `{s}`"));
+        }
+        let required = || {
+            let msg = || format!("`{kind}` requires a name, like `{kind}:NAME`");
+            name.map(str::to_string).ok_or_else(msg)
+        };
+        Ok(match kind {
+            "member" => Self::Member(required()?.split('.').map(str::to_string).collect()),
+            "call" => Self::Call(required()?),
+            "ident" => Self::Ident(required()?),
+            "undocumented" if name.is_none() => Self::Undocumented,
+            "undocumented" => return Err("`undocumented` does not take a name".into()),
+            _ => match ITEM_KINDS.iter().find(|&&k| k == kind) {
+                Some(&kind) => Self::Item(kind, name.map(str::to_string)),
+                None => {
+                    let kinds = ["member", "call", "ident", "undocumented"]
+                        .iter()
+                        .chain(ITEM_KINDS)
+                        .map(|k| format!("`{k}`"))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    return Err(format!("unknown query kind `{kind}`; expected one of {kinds}"));
+                }
+            },
+        })
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Member(path) => write!(f, "member:{}", path.join(".")),
+            Self::Call(name) => write!(f, "call:{name}"),
+            Self::Ident(name) => write!(f, "ident:{name}"),
+            Self::Undocumented => f.write_str("undocumented"),
+            Self::Item(kind, None) => f.write_str(kind),
+            Self::Item(kind, Some(name)) => write!(f, "{kind}:{name}"),
+        }
+    }
+}
+
+/// Parses `file` and returns the spans of the nodes that match `query`, in source order.
+///
+/// Emits an error and returns `Err` if the file could not be parsed.
+pub fn grep_file(sess: &Session, file: &SourceFile, query: &Query) -> Result<Vec<Span>> {
+    let arena = ast::Arena::new();
+    let errors = sess.dcx.err_count();
+    let mut parser = Parser::from_source_file(sess, &arena, file);
+    let source_unit = parser.parse_file().map_err(|e| e.emit())?;
+    if sess.dcx.err_count() > errors {
+        sess.dcx.has_errors()?;
+    }
+    Ok(grep(&source_unit, query))
+}
+
+/// Returns the spans of the nodes in `source_unit` that match `query`, in source order.
+pub fn grep(source_unit: &ast::SourceUnit<'_>, query: &Query) -> Vec<Span> {
+    let mut finder = Finder { query, matches: Vec::new() };
+    let _ = finder.visit_source_unit(source_unit);
+    finder.matches.sort_by_key(|span| (span.lo(), span.hi()));
+    finder.matches
+}
+
+struct Finder<'a> {
+    query: &'a Query,
+    matches: Vec<Span>,
+}
+
+impl Finder<'_> {
+    fn matches_item(&self, item: &ast::Item<'_>) -> bool {
+        let kind = match &item.kind {
+            ast::ItemKind::Contract(_) => "contract",
+            ast::ItemKind::Function(f) if f.kind.is_modifier() => "modifier",
+            ast::ItemKind::Function(_) => "function",
+            ast::ItemKind::Variable(_) => "variable",
+            ast::ItemKind::Struct(_) => "struct",
+            ast::ItemKind::Enum(_) => "enum",
+            ast::ItemKind::Error(_) => "error",
+            ast::ItemKind::Event(_) => "event",
+            _ => return false,
+        };
+        match self.query {
+            Query::Undocumented => matches!(kind, "function" | "modifier") && item.docs.is_empty(),
+            Query::Item(query_kind, name) => {
+                *query_kind == kind
+                    && name
+                        .as_ref()
+                        .is_none_or(|name| item.name().is_some_and(|i| is_named(i, name)))
+            }
+            _ => false,
+        }
+    }
+
+    fn matches_expr(&self, expr: &ast::Expr<'_>) -> bool {
+        match (self.query, &expr.kind) {
+            (Query::Member(path), ast::ExprKind::Member(base, member)) => {
+                let (name, qualifiers) = path.split_last().unwrap();
+                is_named(*member, name) && (qualifiers.is_empty() || is_path(base, qualifiers))
+            }
+            (Query::Call(name), ast::ExprKind::Call(callee, _)) => {
+                let mut callee = &**callee;
+                while let ast::ExprKind::CallOptions(inner, _) = &callee.kind {
+                    callee = inner;
+                }
+                match &callee.kind {
+                    ast::ExprKind::Ident(ident) | ast::ExprKind::Member(_, ident) => {
+                        is_named(*ident, name)
+                    }
+                    _ => false,
+                }
+            }
+            (Query::Ident(name), ast::ExprKind::Ident(ident)) => is_named(*ident, name),
+            _ => false,
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Finder<'_> {
+    type BreakValue = Never;
+
+    fn visit_item(&mut self, item: &'ast ast::Item<'ast>) -> ControlFlow<Self::BreakValue> {
+        if self.matches_item(item) {
+            self.matches.push(item.span);
+        }
+        self.walk_item(item)
+    }
+
+    fn visit_expr(&mut self, expr: &'ast ast::Expr<'ast>) -> ControlFlow<Self::BreakValue> {
+        if self.matches_expr(expr) {
+            self.matches.push(expr.span);
+        }
+        self.walk_expr(expr)
+    }
+
+    fn visit_yul_expr(&mut self, expr: &'ast yul::Expr<'ast>) -> ControlFlow<Self::BreakValue> {
+        if let (Query::Call(name), yul::ExprKind::Call(call)) = (self.query, &expr.kind) {
+            if is_named(call.name, name) {
+                self.matches.push(expr.span);
+            }
+        }
+        self.walk_yul_expr(expr)
+    }
+}
+
+fn is_named(ident: Ident, name: &str) -> bool {
+    ident.as_str() == name
+}
+
+/// Returns `true` if `expr` is the chain of member accesses `a.b.c` given by `path`.
+fn is_path(expr: &ast::Expr<'_>, path: &[String]) -> bool {
+    match (&expr.kind, path.split_last()) {
+        (ast::ExprKind::Ident(ident), Some((name, []))) => is_named(*ident, name),
+        (ast::ExprKind::Member(base, member), Some((name, rest))) => {
+            is_named(*member, name) && is_path(base, rest)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solar_interface::source_map::FileName;
+
+    #[test]
+    fn parse_queries() {
+        let query = |s: &str| s.parse::<Query>();
+        assert_eq!(
+            query("member:tx.origin"),
+            Ok(Query::Member(vec!["tx".into(), "origin".into()]))
+        );
+        assert_eq!(query("call:delegatecall"), Ok(Query::Call("delegatecall".into())));
+        assert_eq!(query("undocumented"), Ok(Query::Undocumented));
+        assert_eq!(query("function"), Ok(Query::Item("function", None)));
+        assert_eq!(query("event:Transfer"), Ok(Query::Item("event", Some("Transfer".into()))));
+        assert!(query("call").is_err());
+        assert!(query("ident:").is_err());
+        assert!(query("undocumented:f").is_err());
+        assert!(query("statement").is_err());
+        for s in ["member:tx.origin", "undocumented", "function", "event:Transfer"] {
+            assert_eq!(query(s).unwrap().to_string(), s);
+        }
+    }
+
+    #[test]
+    fn grep_source() {
+        let src = "
+contract C {
+    /// @notice Documented.
+    function f(address a) external {
+        a.delegatecall{gas: 1}(\"\");
+        require(tx.origin == msg.sender);
+        assembly { pop(delegatecall(gas(), a, 0, 0, 0, 0)) }
+    }
+
+    modifier m() { _; }
+}
+";
+        let sess = Session::builder().with_test_emitter().build();
+        sess.enter(|| {
+            let file = sess
+                .source_map()
+                .new_source_file(FileName::Custom("test".into()), || Ok(src.into()))
+                .unwrap();
+            let snippets = |query: &str| {
+                let spans = grep_file(&sess, &file, &query.parse().unwrap()).unwrap();
+                spans
+                    .into_iter()
+                    .map(|span| sess.source_map().span_to_snippet(span).unwrap())
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(
+                snippets("call:delegatecall"),
+                ["a.delegatecall{gas: 1}(\"\")", "delegatecall(gas(), a, 0, 0, 0, 0)"]
+            );
+            assert_eq!(snippets("member:delegatecall"), ["a.delegatecall"]);
+            assert_eq!(snippets("member:tx.origin"), ["tx.origin"]);
+            assert!(snippets("member:msg.origin").is_empty());
+            assert_eq!(snippets("ident:a"), ["a"]);
+            assert_eq!(snippets("undocumented"), ["modifier m() { _; }"]);
+            assert_eq!(snippets("function:f").len(), 1);
+        });
+    }
+}
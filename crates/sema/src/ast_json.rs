@@ -0,0 +1,1007 @@
+//! Compact JSON AST export.
+//!
+//! Serializes parsed source units into the same schema that solc produces with
+//! `--ast-compact-json`.
+//!
+//! Only the syntactic information is exported, since the ASTs are not resolved: the fields that
+//! depend on name resolution and type checking, like `referencedDeclaration`, `fullyImplemented`
+//! and `contractDependencies`, are omitted.
+//!
+//! Reference: <https://docs.soliditylang.org/en/latest/using-the-compiler.html#compiler-input-and-output-json-description>
+
+use crate::ParsedSource;
+use serde_json::{json, Map, Value};
+use solar_ast::{self as ast, yul};
+use solar_interface::{BytePos, Ident, Session, Span};
+
+/// Exports parsed ASTs in the solc compact JSON AST format.
+///
+/// Node IDs are unique across all the sources exported with the same exporter.
+pub struct AstJsonExporter<'sess> {
+    sess: &'sess Session,
+    next_id: usize,
+    source_index: usize,
+    file_start: BytePos,
+    /// The IDs of the enclosing scopes (source unit, contract, function, block).
+    scopes: Vec<usize>,
+}
+
+impl<'sess> AstJsonExporter<'sess> {
+    /// Creates a new exporter.
+    pub fn new(sess: &'sess Session) -> Self {
+        Self { sess, next_id: 0, source_index: 0, file_start: BytePos(0), scopes: Vec::new() }
+    }
+
+    /// Exports the given parsed source, which is identified by `source_index` in `src` ranges.
+    ///
+    /// Returns `None` if the source does not have a Solidity AST.
+    pub fn export(&mut self, source_index: usize, source: &ParsedSource<'_>) -> Option<Value> {
+        let unit = source.ast.as_ref()?;
+        self.source_index = source_index;
+        self.file_start = source.file.start_pos;
+
+        let id = self.next_id();
+        self.scopes.push(id);
+        let nodes: Vec<_> = unit.items.iter().map(|item| self.item(item)).collect();
+        self.scopes.pop();
+
+        let mut exported_symbols = Map::new();
+        for (item, node) in unit.items.iter().zip(&nodes) {
+            if let Some(name) = item.name() {
+                exported_symbols.insert(name.to_string(), json!([node["id"]]));
+            }
+        }
+
+        let span = unit
+            .items
+            .iter()
+            .map(|item| item.span)
+            .reduce(Span::to)
+            .unwrap_or_else(|| Span::new(self.file_start, source.file.end_position()));
+        Some(json!({
+            "absolutePath": source.file.name.display().to_string(),
+            "exportedSymbols": exported_symbols,
+            "id": id,
+            "nodeType": "SourceUnit",
+            "nodes": nodes,
+            "src": self.src(span.with_lo(self.file_start)),
+        }))
+    }
+
+    fn next_id(&mut self) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    fn scope(&self) -> usize {
+        self.scopes.last().copied().unwrap_or_default()
+    }
+
+    /// Formats a span as `start:length:source_index`.
+    fn src(&self, span: Span) -> String {
+        if span.is_dummy() {
+            return "-1:-1:-1".to_string();
+        }
+        let start = span.lo().0 - self.file_start.0;
+        let len = span.hi().0 - span.lo().0;
+        format!("{start}:{len}:{}", self.source_index)
+    }
+
+    /// Creates a new node object with the common `id`, `nodeType` and `src` fields.
+    fn node(&mut self, node_type: &str, span: Span) -> Map<String, Value> {
+        let id = self.next_id();
+        self.node_with_id(id, node_type, span)
+    }
+
+    fn node_with_id(&self, id: usize, node_type: &str, span: Span) -> Map<String, Value> {
+        let mut map = Map::new();
+        map.insert("id".into(), id.into());
+        map.insert("nodeType".into(), node_type.into());
+        map.insert("src".into(), self.src(span).into());
+        map
+    }
+
+    fn documentation(&mut self, docs: &[ast::DocComment<'_>]) -> Value {
+        let Some(span) = docs.iter().map(|doc| doc.span).reduce(Span::to) else {
+            return Value::Null;
+        };
+        let text = docs.iter().map(|doc| doc.symbol.as_str()).collect::<Vec<_>>().join("\n");
+        let mut node = self.node("StructuredDocumentation", span);
+        node.insert("text".into(), text.into());
+        node.into()
+    }
+
+    fn item(&mut self, item: &ast::Item<'_>) -> Value {
+        match &item.kind {
+            ast::ItemKind::Pragma(pragma) => self.pragma(item.span, pragma),
+            ast::ItemKind::Import(import) => self.import(item.span, import),
+            ast::ItemKind::Using(using) => self.using(item.span, using),
+            ast::ItemKind::Contract(contract) => self.contract(item, contract),
+            ast::ItemKind::Function(function) => self.function(item, function),
+            ast::ItemKind::Variable(var) => {
+                let mut node = self.variable_declaration(var, true);
+                node.insert("documentation".into(), self.documentation(&item.docs));
+                node.into()
+            }
+            ast::ItemKind::Struct(strukt) => {
+                let id = self.next_id();
+                let mut node = self.node_with_id(id, "StructDefinition", item.span);
+                node.insert("canonicalName".into(), strukt.name.to_string().into());
+                node.insert("documentation".into(), self.documentation(&item.docs));
+                node.insert("name".into(), strukt.name.to_string().into());
+                node.insert("nameLocation".into(), self.src(strukt.name.span).into());
+                node.insert("scope".into(), self.scope().into());
+                self.scopes.push(id);
+                let members: Vec<_> = strukt
+                    .fields
+                    .iter()
+                    .map(|field| Value::from(self.variable_declaration(field, false)))
+                    .collect();
+                self.scopes.pop();
+                node.insert("members".into(), members.into());
+                node.insert("visibility".into(), "public".into());
+                node.into()
+            }
+            ast::ItemKind::Enum(enumm) => {
+                let mut node = self.node("EnumDefinition", item.span);
+                node.insert("canonicalName".into(), enumm.name.to_string().into());
+                node.insert("documentation".into(), self.documentation(&item.docs));
+                node.insert("name".into(), enumm.name.to_string().into());
+                node.insert("nameLocation".into(), self.src(enumm.name.span).into());
+                let members: Vec<_> = enumm
+                    .variants
+                    .iter()
+                    .map(|variant| {
+                        let mut node = self.node("EnumValue", variant.span);
+                        node.insert("name".into(), variant.to_string().into());
+                        node.insert("nameLocation".into(), self.src(variant.span).into());
+                        Value::from(node)
+                    })
+                    .collect();
+                node.insert("members".into(), members.into());
+                node.into()
+            }
+            ast::ItemKind::Udvt(udvt) => {
+                let mut node = self.node("UserDefinedValueTypeDefinition", item.span);
+                node.insert("name".into(), udvt.name.to_string().into());
+                node.insert("nameLocation".into(), self.src(udvt.name.span).into());
+                node.insert("underlyingType".into(), self.type_name(&udvt.ty));
+                node.into()
+            }
+            ast::ItemKind::Error(error) => {
+                let mut node = self.node("ErrorDefinition", item.span);
+                node.insert("documentation".into(), self.documentation(&item.docs));
+                node.insert("name".into(), error.name.to_string().into());
+                node.insert("nameLocation".into(), self.src(error.name.span).into());
+                node.insert("parameters".into(), self.parameter_list(item.span, &error.parameters));
+                node.into()
+            }
+            ast::ItemKind::Event(event) => {
+                let mut node = self.node("EventDefinition", item.span);
+                node.insert("anonymous".into(), event.anonymous.into());
+                node.insert("documentation".into(), self.documentation(&item.docs));
+                node.insert("name".into(), event.name.to_string().into());
+                node.insert("nameLocation".into(), self.src(event.name.span).into());
+                node.insert("parameters".into(), self.parameter_list(item.span, &event.parameters));
+                node.into()
+            }
+        }
+    }
+
+    fn pragma(&mut self, span: Span, pragma: &ast::PragmaDirective<'_>) -> Value {
+        let mut node = self.node("PragmaDirective", span);
+        let literals: Vec<String> = match &pragma.tokens {
+            ast::PragmaTokens::Version(name, req) => vec![name.to_string(), req.to_string()],
+            ast::PragmaTokens::Custom(name, value) => {
+                std::iter::once(name).chain(value).map(|lit| lit.as_str().to_string()).collect()
+            }
+            ast::PragmaTokens::Verbatim(_) => {
+                let snippet = self.sess.source_map().span_to_snippet(span).unwrap_or_default();
+                let inner = snippet.trim_start_matches("pragma").trim_end_matches(';');
+                inner.split_whitespace().map(ToString::to_string).collect()
+            }
+        };
+        node.insert("literals".into(), literals.into());
+        node.into()
+    }
+
+    fn import(&mut self, span: Span, import: &ast::ImportDirective<'_>) -> Value {
+        let mut node = self.node("ImportDirective", span);
+        node.insert("absolutePath".into(), import.path.value.as_str().into());
+        node.insert("file".into(), import.path.value.as_str().into());
+        let (unit_alias, aliases) = match &import.items {
+            ast::ImportItems::Plain(alias) | ast::ImportItems::Glob(alias) => (*alias, vec![]),
+            ast::ImportItems::Aliases(aliases) => {
+                let aliases = aliases
+                    .iter()
+                    .map(|&(name, alias)| {
+                        let mut foreign = self.identifier(name);
+                        foreign.remove("overloadedDeclarations");
+                        json!({
+                            "foreign": foreign,
+                            "local": alias.map(|alias| alias.to_string()),
+                            "nameLocation": self.src(alias.unwrap_or(name).span),
+                        })
+                    })
+                    .collect();
+                (None, aliases)
+            }
+        };
+        node.insert("nameLocation".into(), unit_alias.map(|a| self.src(a.span)).into());
+        node.insert("scope".into(), self.scope().into());
+        node.insert("symbolAliases".into(), aliases.into());
+        node.insert(
+            "unitAlias".into(),
+            unit_alias.map(|a| a.to_string()).unwrap_or_default().into(),
+        );
+        node.into()
+    }
+
+    fn using(&mut self, span: Span, using: &ast::UsingDirective<'_>) -> Value {
+        let mut node = self.node("UsingForDirective", span);
+        match &using.list {
+            ast::UsingList::Single(path) => {
+                node.insert("libraryName".into(), self.identifier_path(path).into());
+            }
+            ast::UsingList::Multiple(paths) => {
+                let functions: Vec<_> = paths
+                    .iter()
+                    .map(|(path, op)| {
+                        let path = self.identifier_path(path);
+                        match op {
+                            Some(op) => {
+                                let op = op.to_op().either(|op| op.to_str(), |op| op.to_str());
+                                json!({ "definition": path, "operator": op })
+                            }
+                            None => json!({ "function": path }),
+                        }
+                    })
+                    .collect();
+                node.insert("functionList".into(), functions.into());
+            }
+        }
+        node.insert("global".into(), using.global.into());
+        if let Some(ty) = &using.ty {
+            node.insert("typeName".into(), self.type_name(ty));
+        }
+        node.into()
+    }
+
+    fn contract(&mut self, item: &ast::Item<'_>, contract: &ast::ItemContract<'_>) -> Value {
+        let id = self.next_id();
+        let mut node = self.node_with_id(id, "ContractDefinition", item.span);
+        node.insert("abstract".into(), contract.kind.is_abstract_contract().into());
+        let bases: Vec<_> = contract
+            .bases
+            .iter()
+            .map(|base| {
+                let span = base.name.span();
+                let mut node = self.node("InheritanceSpecifier", span);
+                node.insert("baseName".into(), self.identifier_path(&base.name).into());
+                if !base.arguments.is_empty() {
+                    node.insert("arguments".into(), self.call_args(&base.arguments).into());
+                }
+                Value::from(node)
+            })
+            .collect();
+        node.insert("baseContracts".into(), bases.into());
+        let kind = match contract.kind {
+            ast::ContractKind::Contract | ast::ContractKind::AbstractContract => "contract",
+            ast::ContractKind::Interface => "interface",
+            ast::ContractKind::Library => "library",
+        };
+        node.insert("contractKind".into(), kind.into());
+        node.insert("documentation".into(), self.documentation(&item.docs));
+        node.insert("name".into(), contract.name.to_string().into());
+        node.insert("nameLocation".into(), self.src(contract.name.span).into());
+        self.scopes.push(id);
+        let nodes: Vec<_> = contract.body.iter().map(|item| self.item(item)).collect();
+        self.scopes.pop();
+        node.insert("nodes".into(), nodes.into());
+        node.insert("scope".into(), self.scope().into());
+        node.into()
+    }
+
+    fn function(&mut self, item: &ast::Item<'_>, function: &ast::ItemFunction<'_>) -> Value {
+        let header = &function.header;
+        let is_modifier = function.kind.is_modifier();
+        let node_type = if is_modifier { "ModifierDefinition" } else { "FunctionDefinition" };
+        let id = self.next_id();
+        let mut node = self.node_with_id(id, node_type, item.span);
+        node.insert("documentation".into(), self.documentation(&item.docs));
+        if !is_modifier {
+            node.insert("implemented".into(), function.body.is_some().into());
+            node.insert("kind".into(), function.kind.to_str().into());
+            let modifiers: Vec<_> = header
+                .modifiers
+                .iter()
+                .map(|modifier| {
+                    let mut node = self.node("ModifierInvocation", modifier.name.span());
+                    if !modifier.arguments.is_empty() {
+                        node.insert("arguments".into(), self.call_args(&modifier.arguments).into());
+                    }
+                    node.insert("modifierName".into(), self.identifier_path(&modifier.name).into());
+                    Value::from(node)
+                })
+                .collect();
+            node.insert("modifiers".into(), modifiers.into());
+        }
+        node.insert(
+            "name".into(),
+            header.name.map(|name| name.to_string()).unwrap_or_default().into(),
+        );
+        node.insert(
+            "nameLocation".into(),
+            self.src(header.name.map(|name| name.span).unwrap_or(item.span)).into(),
+        );
+        if let Some(override_) = &header.override_ {
+            node.insert("overrides".into(), self.override_specifier(override_));
+        }
+        self.scopes.push(id);
+        node.insert("parameters".into(), self.parameter_list(item.span, &header.parameters));
+        if !is_modifier {
+            node.insert("returnParameters".into(), self.parameter_list(item.span, &header.returns));
+        }
+        let body = function.body.as_ref().map(|body| self.block(item.span, body, false));
+        self.scopes.pop();
+        node.insert("body".into(), body.into());
+        node.insert("scope".into(), self.scope().into());
+        if !is_modifier {
+            node.insert("stateMutability".into(), header.state_mutability.to_str().into());
+        }
+        node.insert("virtual".into(), header.virtual_.into());
+        let visibility = header.visibility.unwrap_or(if function.kind.is_ordinary() {
+            ast::Visibility::Public
+        } else {
+            ast::Visibility::Internal
+        });
+        node.insert("visibility".into(), visibility.to_str().into());
+        node.into()
+    }
+
+    fn override_specifier(&mut self, override_: &ast::Override<'_>) -> Value {
+        let mut node = self.node("OverrideSpecifier", override_.span);
+        let overrides: Vec<_> =
+            override_.paths.iter().map(|path| Value::from(self.identifier_path(path))).collect();
+        node.insert("overrides".into(), overrides.into());
+        node.into()
+    }
+
+    fn parameter_list(&mut self, span: Span, list: &[ast::VariableDefinition<'_>]) -> Value {
+        let span = list.iter().map(|p| p.span).reduce(Span::to).unwrap_or(span);
+        let mut node = self.node("ParameterList", span);
+        let parameters: Vec<_> =
+            list.iter().map(|param| Value::from(self.variable_declaration(param, false))).collect();
+        node.insert("parameters".into(), parameters.into());
+        node.into()
+    }
+
+    fn variable_declaration(
+        &mut self,
+        var: &ast::VariableDefinition<'_>,
+        state_variable: bool,
+    ) -> Map<String, Value> {
+        let mut node = self.node("VariableDeclaration", var.span);
+        node.insert("constant".into(), var.mutability.is_some_and(|m| m.is_constant()).into());
+        if var.indexed {
+            node.insert("indexed".into(), true.into());
+        }
+        let mutability = var.mutability.map_or("mutable", |m| m.to_str());
+        node.insert("mutability".into(), mutability.into());
+        node.insert("name".into(), var.name.map(|n| n.to_string()).unwrap_or_default().into());
+        node.insert(
+            "nameLocation".into(),
+            var.name.map(|n| self.src(n.span)).unwrap_or_else(|| "-1:-1:-1".into()).into(),
+        );
+        if let Some(override_) = &var.override_ {
+            node.insert("overrides".into(), self.override_specifier(override_));
+        }
+        node.insert("scope".into(), self.scope().into());
+        node.insert("stateVariable".into(), state_variable.into());
+        node.insert(
+            "storageLocation".into(),
+            var.data_location.map_or("default", |l| l.to_str()).into(),
+        );
+        node.insert("typeName".into(), self.type_name(&var.ty));
+        // Initializers of local variables are part of the declaration statement instead.
+        if let Some(init) = var.initializer.as_ref().filter(|_| state_variable) {
+            node.insert("value".into(), self.expr(init));
+        }
+        let visibility = var.visibility.unwrap_or(ast::Visibility::Internal);
+        node.insert("visibility".into(), visibility.to_str().into());
+        node
+    }
+
+    fn type_name(&mut self, ty: &ast::Type<'_>) -> Value {
+        match &ty.kind {
+            ast::TypeKind::Elementary(elem) => self.elementary_type_name(ty.span, *elem).into(),
+            ast::TypeKind::Array(array) => {
+                let mut node = self.node("ArrayTypeName", ty.span);
+                node.insert("baseType".into(), self.type_name(&array.element));
+                if let Some(size) = &array.size {
+                    node.insert("length".into(), self.expr(size));
+                }
+                node.into()
+            }
+            ast::TypeKind::Function(function) => {
+                let mut node = self.node("FunctionTypeName", ty.span);
+                node.insert(
+                    "parameterTypes".into(),
+                    self.parameter_list(ty.span, &function.parameters),
+                );
+                node.insert(
+                    "returnParameterTypes".into(),
+                    self.parameter_list(ty.span, &function.returns),
+                );
+                node.insert("stateMutability".into(), function.state_mutability.to_str().into());
+                let visibility = function.visibility.unwrap_or(ast::Visibility::Internal);
+                node.insert("visibility".into(), visibility.to_str().into());
+                node.into()
+            }
+            ast::TypeKind::Mapping(mapping) => {
+                let mut node = self.node("Mapping", ty.span);
+                node.insert("keyName".into(), ident_name(mapping.key_name).into());
+                node.insert("keyType".into(), self.type_name(&mapping.key));
+                node.insert("valueName".into(), ident_name(mapping.value_name).into());
+                node.insert("valueType".into(), self.type_name(&mapping.value));
+                node.into()
+            }
+            ast::TypeKind::Custom(path) => {
+                let mut node = self.node("UserDefinedTypeName", ty.span);
+                node.insert("pathNode".into(), self.identifier_path(path).into());
+                node.into()
+            }
+        }
+    }
+
+    fn elementary_type_name(&mut self, span: Span, ty: ast::ElementaryType) -> Map<String, Value> {
+        let mut node = self.node("ElementaryTypeName", span);
+        node.insert("name".into(), ty.to_abi_str().into());
+        if let ast::ElementaryType::Address(true) = ty {
+            node.insert("stateMutability".into(), "payable".into());
+        }
+        node
+    }
+
+    fn identifier(&mut self, ident: Ident) -> Map<String, Value> {
+        let mut node = self.node("Identifier", ident.span);
+        node.insert("name".into(), ident.to_string().into());
+        node.insert("overloadedDeclarations".into(), json!([]));
+        node
+    }
+
+    fn identifier_path(&mut self, path: &ast::PathSlice) -> Map<String, Value> {
+        let mut node = self.node("IdentifierPath", path.span());
+        node.insert("name".into(), path.to_string().into());
+        let locations: Vec<_> = path.segments().iter().map(|s| self.src(s.span)).collect();
+        node.insert("nameLocations".into(), locations.into());
+        node
+    }
+
+    fn block(&mut self, span: Span, block: &[ast::Stmt<'_>], unchecked: bool) -> Value {
+        let span = block.iter().map(|stmt| stmt.span).reduce(Span::to).unwrap_or(span);
+        let id = self.next_id();
+        let mut node =
+            self.node_with_id(id, if unchecked { "UncheckedBlock" } else { "Block" }, span);
+        self.scopes.push(id);
+        let statements: Vec<_> = block.iter().map(|stmt| self.stmt(stmt)).collect();
+        self.scopes.pop();
+        node.insert("statements".into(), statements.into());
+        node.into()
+    }
+
+    fn stmt(&mut self, stmt: &ast::Stmt<'_>) -> Value {
+        let span = stmt.span;
+        let mut node = match &stmt.kind {
+            ast::StmtKind::Assembly(assembly) => {
+                let mut node = self.node("InlineAssembly", span);
+                node.insert("AST".into(), self.yul_block(span, &assembly.block));
+                node.insert("evmVersion".into(), self.sess.evm_version.to_string().into());
+                node.insert("externalReferences".into(), json!([]));
+                if !assembly.flags.is_empty() {
+                    let flags: Vec<_> =
+                        assembly.flags.iter().map(|flag| flag.value.to_string()).collect();
+                    node.insert("flags".into(), flags.into());
+                }
+                node
+            }
+            ast::StmtKind::DeclSingle(var) => {
+                let mut node = self.node("VariableDeclarationStatement", span);
+                let decl = self.variable_declaration(var, false);
+                node.insert("assignments".into(), json!([decl["id"]]));
+                node.insert("declarations".into(), json!([decl]));
+                let value = var.initializer.as_ref().map(|init| self.expr(init));
+                node.insert("initialValue".into(), value.into());
+                node
+            }
+            ast::StmtKind::DeclMulti(vars, init) => {
+                let mut node = self.node("VariableDeclarationStatement", span);
+                let decls: Vec<_> = vars
+                    .iter()
+                    .map(|var| var.as_ref().map(|var| self.variable_declaration(var, false)))
+                    .collect();
+                let assignments: Vec<_> =
+                    decls.iter().map(|decl| decl.as_ref().map(|decl| decl["id"].clone())).collect();
+                node.insert("assignments".into(), assignments.into());
+                node.insert("declarations".into(), decls.into());
+                node.insert("initialValue".into(), self.expr(init));
+                node
+            }
+            ast::StmtKind::Block(block) => return self.block(span, block, false),
+            ast::StmtKind::UncheckedBlock(block) => return self.block(span, block, true),
+            ast::StmtKind::Break => self.node("Break", span),
+            ast::StmtKind::Continue => self.node("Continue", span),
+            ast::StmtKind::DoWhile(body, cond) => {
+                let mut node = self.node("DoWhileStatement", span);
+                node.insert("body".into(), self.stmt(body));
+                node.insert("condition".into(), self.expr(cond));
+                node
+            }
+            ast::StmtKind::Emit(path, args) => {
+                let mut node = self.node("EmitStatement", span);
+                node.insert("eventCall".into(), self.call_path(span, path, args));
+                node
+            }
+            ast::StmtKind::Expr(expr) => {
+                let mut node = self.node("ExpressionStatement", span);
+                node.insert("expression".into(), self.expr(expr));
+                node
+            }
+            ast::StmtKind::For { init, cond, next, body } => {
+                let mut node = self.node("ForStatement", span);
+                node.insert("body".into(), self.stmt(body));
+                node.insert("condition".into(), cond.as_ref().map(|cond| self.expr(cond)).into());
+                node.insert(
+                    "initializationExpression".into(),
+                    init.as_ref().map(|init| self.stmt(init)).into(),
+                );
+                let next = next.as_ref().map(|next| {
+                    let mut node = self.node("ExpressionStatement", next.span);
+                    node.insert("expression".into(), self.expr(next));
+                    Value::from(node)
+                });
+                node.insert("loopExpression".into(), next.into());
+                node
+            }
+            ast::StmtKind::If(cond, then, else_) => {
+                let mut node = self.node("IfStatement", span);
+                node.insert("condition".into(), self.expr(cond));
+                node.insert("falseBody".into(), else_.as_ref().map(|e| self.stmt(e)).into());
+                node.insert("trueBody".into(), self.stmt(then));
+                node
+            }
+            ast::StmtKind::Return(expr) => {
+                let mut node = self.node("Return", span);
+                node.insert("expression".into(), expr.as_ref().map(|e| self.expr(e)).into());
+                node
+            }
+            ast::StmtKind::Revert(path, args) => {
+                let mut node = self.node("RevertStatement", span);
+                node.insert("errorCall".into(), self.call_path(span, path, args));
+                node
+            }
+            ast::StmtKind::Try(try_) => {
+                let mut node = self.node("TryStatement", span);
+                let mut clauses = Vec::with_capacity(try_.catch.len() + 1);
+                let mut success = self.node("TryCatchClause", span);
+                success.insert("block".into(), self.block(span, &try_.block, false));
+                success.insert("errorName".into(), "".into());
+                if !try_.returns.is_empty() {
+                    success.insert("parameters".into(), self.parameter_list(span, &try_.returns));
+                }
+                clauses.push(Value::from(success));
+                for catch in try_.catch.iter() {
+                    let mut clause = self.node("TryCatchClause", span);
+                    clause.insert("block".into(), self.block(span, &catch.block, false));
+                    clause.insert("errorName".into(), ident_name(catch.name).into());
+                    if !catch.args.is_empty() {
+                        clause.insert("parameters".into(), self.parameter_list(span, &catch.args));
+                    }
+                    clauses.push(clause.into());
+                }
+                node.insert("clauses".into(), clauses.into());
+                let mut call = self.expr(&try_.expr);
+                if let Some(call) = call.as_object_mut() {
+                    call.insert("tryCall".into(), true.into());
+                }
+                node.insert("externalCall".into(), call);
+                node
+            }
+            ast::StmtKind::While(cond, body) => {
+                let mut node = self.node("WhileStatement", span);
+                node.insert("body".into(), self.stmt(body));
+                node.insert("condition".into(), self.expr(cond));
+                node
+            }
+            ast::StmtKind::Placeholder => self.node("PlaceholderStatement", span),
+            ast::StmtKind::Err(_) => self.node("ErrorStatement", span),
+        };
+        if !stmt.docs.is_empty() {
+            let docs = stmt.docs.iter().map(|doc| doc.symbol.as_str()).collect::<Vec<_>>();
+            node.insert("documentation".into(), docs.join("\n").into());
+        }
+        node.into()
+    }
+
+    /// Lowers `path(args)` in `emit` and `revert` statements to a function call node.
+    fn call_path(&mut self, span: Span, path: &ast::PathSlice, args: &ast::CallArgs<'_>) -> Value {
+        let mut node = self.node("FunctionCall", span);
+        let callee = self.path_expr(path);
+        self.insert_call_args(&mut node, args);
+        node.insert("expression".into(), callee);
+        node.insert("kind".into(), "functionCall".into());
+        node.insert("tryCall".into(), false.into());
+        node.into()
+    }
+
+    /// Converts a path to nested `MemberAccess` nodes.
+    fn path_expr(&mut self, path: &ast::PathSlice) -> Value {
+        let (first, rest) = path.segments().split_first().expect("empty path");
+        let mut expr = Value::from(self.identifier(*first));
+        let mut span = first.span;
+        for segment in rest {
+            span = span.to(segment.span);
+            let mut node = self.node("MemberAccess", span);
+            node.insert("expression".into(), expr);
+            node.insert("memberLocation".into(), self.src(segment.span).into());
+            node.insert("memberName".into(), segment.to_string().into());
+            expr = node.into();
+        }
+        expr
+    }
+
+    fn call_args(&mut self, args: &ast::CallArgs<'_>) -> Vec<Value> {
+        args.exprs().map(|arg| self.expr(arg)).collect()
+    }
+
+    fn insert_call_args(&mut self, node: &mut Map<String, Value>, args: &ast::CallArgs<'_>) {
+        let names: Vec<_> = match args {
+            ast::CallArgs::Unnamed(_) => vec![],
+            ast::CallArgs::Named(args) => args.iter().map(|arg| arg.name.to_string()).collect(),
+        };
+        let locations: Vec<_> = match args {
+            ast::CallArgs::Unnamed(_) => vec![],
+            ast::CallArgs::Named(args) => args.iter().map(|arg| self.src(arg.name.span)).collect(),
+        };
+        node.insert("arguments".into(), self.call_args(args).into());
+        node.insert("nameLocations".into(), locations.into());
+        node.insert("names".into(), names.into());
+    }
+
+    fn expr(&mut self, expr: &ast::Expr<'_>) -> Value {
+        let span = expr.span;
+        let node = match &expr.kind {
+            ast::ExprKind::Array(exprs) => {
+                let mut node = self.node("TupleExpression", span);
+                let components: Vec<_> = exprs.iter().map(|e| self.expr(e)).collect();
+                node.insert("components".into(), components.into());
+                node.insert("isInlineArray".into(), true.into());
+                node
+            }
+            ast::ExprKind::Assign(lhs, op, rhs) => {
+                let mut node = self.node("Assignment", span);
+                node.insert("leftHandSide".into(), self.expr(lhs));
+                let op = match op {
+                    Some(op) => format!("{}=", op.kind.to_str()),
+                    None => "=".to_string(),
+                };
+                node.insert("operator".into(), op.into());
+                node.insert("rightHandSide".into(), self.expr(rhs));
+                node
+            }
+            ast::ExprKind::Binary(lhs, op, rhs) => {
+                let mut node = self.node("BinaryOperation", span);
+                node.insert("leftExpression".into(), self.expr(lhs));
+                node.insert("operator".into(), op.kind.to_str().into());
+                node.insert("rightExpression".into(), self.expr(rhs));
+                node
+            }
+            ast::ExprKind::Call(callee, args) => {
+                let mut node = self.node("FunctionCall", span);
+                let callee = self.expr(callee);
+                self.insert_call_args(&mut node, args);
+                node.insert("expression".into(), callee);
+                node.insert("kind".into(), "functionCall".into());
+                node.insert("tryCall".into(), false.into());
+                node
+            }
+            ast::ExprKind::CallOptions(callee, args) => {
+                let mut node = self.node("FunctionCallOptions", span);
+                node.insert("expression".into(), self.expr(callee));
+                let names: Vec<_> = args.iter().map(|arg| arg.name.to_string()).collect();
+                let options: Vec<_> = args.iter().map(|arg| self.expr(&arg.value)).collect();
+                node.insert("names".into(), names.into());
+                node.insert("options".into(), options.into());
+                node
+            }
+            ast::ExprKind::Delete(expr) => {
+                let mut node = self.node("UnaryOperation", span);
+                node.insert("operator".into(), "delete".into());
+                node.insert("prefix".into(), true.into());
+                node.insert("subExpression".into(), self.expr(expr));
+                node
+            }
+            ast::ExprKind::Ident(ident) => self.identifier(*ident),
+            ast::ExprKind::Index(base, kind) => match kind {
+                ast::IndexKind::Index(index) => {
+                    let mut node = self.node("IndexAccess", span);
+                    node.insert("baseExpression".into(), self.expr(base));
+                    node.insert(
+                        "indexExpression".into(),
+                        index.as_ref().map(|i| self.expr(i)).into(),
+                    );
+                    node
+                }
+                ast::IndexKind::Range(start, end) => {
+                    let mut node = self.node("IndexRangeAccess", span);
+                    node.insert("baseExpression".into(), self.expr(base));
+                    node.insert("endExpression".into(), end.as_ref().map(|e| self.expr(e)).into());
+                    node.insert(
+                        "startExpression".into(),
+                        start.as_ref().map(|s| self.expr(s)).into(),
+                    );
+                    node
+                }
+            },
+            ast::ExprKind::Lit(lit, sub) => {
+                let mut node = self.literal(lit);
+                if let Some(sub) = sub {
+                    node.insert("subdenomination".into(), sub.to_str().into());
+                }
+                node
+            }
+            ast::ExprKind::Member(base, member) => {
+                let mut node = self.node("MemberAccess", span);
+                node.insert("expression".into(), self.expr(base));
+                node.insert("memberLocation".into(), self.src(member.span).into());
+                node.insert("memberName".into(), member.to_string().into());
+                node
+            }
+            ast::ExprKind::New(ty) => {
+                let mut node = self.node("NewExpression", span);
+                node.insert("typeName".into(), self.type_name(ty));
+                node
+            }
+            ast::ExprKind::Payable(args) => {
+                let mut node = self.node("FunctionCall", span);
+                let mut callee = self.node("ElementaryTypeNameExpression", span);
+                let ty = ast::ElementaryType::Address(true);
+                callee.insert("typeName".into(), self.elementary_type_name(span, ty).into());
+                self.insert_call_args(&mut node, args);
+                node.insert("expression".into(), callee.into());
+                node.insert("kind".into(), "typeConversion".into());
+                node.insert("tryCall".into(), false.into());
+                node
+            }
+            ast::ExprKind::Ternary(cond, true_, false_) => {
+                let mut node = self.node("Conditional", span);
+                node.insert("condition".into(), self.expr(cond));
+                node.insert("falseExpression".into(), self.expr(false_));
+                node.insert("trueExpression".into(), self.expr(true_));
+                node
+            }
+            ast::ExprKind::Tuple(exprs) => {
+                let mut node = self.node("TupleExpression", span);
+                let components: Vec<_> =
+                    exprs.iter().map(|e| e.as_ref().map(|e| self.expr(e))).collect();
+                node.insert("components".into(), components.into());
+                node.insert("isInlineArray".into(), false.into());
+                node
+            }
+            ast::ExprKind::TypeCall(ty) => {
+                let mut node = self.node("FunctionCall", span);
+                let callee =
+                    self.identifier(Ident::new(solar_interface::kw::Type, span.shrink_to_lo()));
+                let arg = self.type_expr(ty);
+                node.insert("arguments".into(), json!([arg]));
+                node.insert("expression".into(), callee.into());
+                node.insert("kind".into(), "functionCall".into());
+                node.insert("nameLocations".into(), json!([]));
+                node.insert("names".into(), json!([]));
+                node.insert("tryCall".into(), false.into());
+                node
+            }
+            ast::ExprKind::Type(ty) => return self.type_expr(ty),
+            ast::ExprKind::Unary(op, expr) => {
+                let mut node = self.node("UnaryOperation", span);
+                node.insert("operator".into(), op.kind.to_str().into());
+                node.insert("prefix".into(), op.kind.is_prefix().into());
+                node.insert("subExpression".into(), self.expr(expr));
+                node
+            }
+        };
+        node.into()
+    }
+
+    /// Converts a type in expression position.
+    fn type_expr(&mut self, ty: &ast::Type<'_>) -> Value {
+        match &ty.kind {
+            ast::TypeKind::Elementary(elem) => {
+                let mut node = self.node("ElementaryTypeNameExpression", ty.span);
+                node.insert("typeName".into(), self.elementary_type_name(ty.span, *elem).into());
+                node.into()
+            }
+            ast::TypeKind::Custom(path) => self.path_expr(path),
+            _ => self.type_name(ty),
+        }
+    }
+
+    fn literal(&mut self, lit: &ast::Lit) -> Map<String, Value> {
+        let mut node = self.node("Literal", lit.span);
+        let (kind, bytes): (_, &[u8]) = match &lit.kind {
+            ast::LitKind::Str(ast::StrKind::Str, bytes) => ("string", &bytes[..]),
+            ast::LitKind::Str(ast::StrKind::Unicode, bytes) => ("unicodeString", &bytes[..]),
+            ast::LitKind::Str(ast::StrKind::Hex, bytes) => ("hexString", &bytes[..]),
+            ast::LitKind::Bool(_) => ("bool", lit.symbol.as_str().as_bytes()),
+            ast::LitKind::Number(_)
+            | ast::LitKind::Rational(_)
+            | ast::LitKind::Address(_)
+            | ast::LitKind::Err(_) => ("number", lit.symbol.as_str().as_bytes()),
+        };
+        node.insert("hexValue".into(), alloy_primitives::hex::encode(bytes).into());
+        node.insert("kind".into(), kind.into());
+        let value = match &lit.kind {
+            ast::LitKind::Str(..) => String::from_utf8(bytes.to_vec()).ok(),
+            _ => Some(lit.symbol.to_string()),
+        };
+        node.insert("value".into(), value.into());
+        node
+    }
+
+    fn yul_block(&mut self, span: Span, block: &[yul::Stmt<'_>]) -> Value {
+        let span = block.iter().map(|stmt| stmt.span).reduce(Span::to).unwrap_or(span);
+        let mut node = self.yul_node("YulBlock", span);
+        let statements: Vec<_> = block.iter().map(|stmt| self.yul_stmt(stmt)).collect();
+        node.insert("statements".into(), statements.into());
+        node.into()
+    }
+
+    /// Yul nodes do not have IDs.
+    fn yul_node(&self, node_type: &str, span: Span) -> Map<String, Value> {
+        let mut map = Map::new();
+        map.insert("nativeSrc".into(), self.src(span).into());
+        map.insert("nodeType".into(), node_type.into());
+        map.insert("src".into(), self.src(span).into());
+        map
+    }
+
+    fn yul_stmt(&mut self, stmt: &yul::Stmt<'_>) -> Value {
+        let span = stmt.span;
+        let node = match &stmt.kind {
+            yul::StmtKind::Block(block) => return self.yul_block(span, block),
+            yul::StmtKind::AssignSingle(path, expr) => {
+                let mut node = self.yul_node("YulAssignment", span);
+                node.insert("value".into(), self.yul_expr(expr));
+                node.insert("variableNames".into(), json!([self.yul_identifier_path(path)]));
+                node
+            }
+            yul::StmtKind::AssignMulti(paths, call) => {
+                let mut node = self.yul_node("YulAssignment", span);
+                node.insert("value".into(), self.yul_call(span, call));
+                let names: Vec<_> = paths.iter().map(|p| self.yul_identifier_path(p)).collect();
+                node.insert("variableNames".into(), names.into());
+                node
+            }
+            yul::StmtKind::Expr(call) => {
+                let mut node = self.yul_node("YulExpressionStatement", span);
+                node.insert("expression".into(), self.yul_call(span, call));
+                node
+            }
+            yul::StmtKind::If(cond, body) => {
+                let mut node = self.yul_node("YulIf", span);
+                node.insert("body".into(), self.yul_block(span, body));
+                node.insert("condition".into(), self.yul_expr(cond));
+                node
+            }
+            yul::StmtKind::For { init, cond, step, body } => {
+                let mut node = self.yul_node("YulForLoop", span);
+                node.insert("body".into(), self.yul_block(span, body));
+                node.insert("condition".into(), self.yul_expr(cond));
+                node.insert("post".into(), self.yul_block(span, step));
+                node.insert("pre".into(), self.yul_block(span, init));
+                node
+            }
+            yul::StmtKind::Switch(switch) => {
+                let mut node = self.yul_node("YulSwitch", span);
+                let mut cases = Vec::with_capacity(switch.branches.len() + 1);
+                for case in switch.branches.iter() {
+                    let case_span = case.constant.span.to(span_of(&case.body, case.constant.span));
+                    let mut case_node = self.yul_node("YulCase", case_span);
+                    case_node.insert("body".into(), self.yul_block(case_span, &case.body));
+                    case_node.insert("value".into(), self.yul_literal(&*case.constant).into());
+                    cases.push(Value::from(case_node));
+                }
+                if let Some(default) = &switch.default_case {
+                    let mut case_node = self.yul_node("YulCase", span_of(default, span));
+                    case_node.insert("body".into(), self.yul_block(span, default));
+                    case_node.insert("value".into(), "default".into());
+                    cases.push(case_node.into());
+                }
+                node.insert("cases".into(), cases.into());
+                node.insert("expression".into(), self.yul_expr(&switch.selector));
+                node
+            }
+            yul::StmtKind::Leave => self.yul_node("YulLeave", span),
+            yul::StmtKind::Break => self.yul_node("YulBreak", span),
+            yul::StmtKind::Continue => self.yul_node("YulContinue", span),
+            yul::StmtKind::FunctionDef(function) => {
+                let mut node = self.yul_node("YulFunctionDefinition", span);
+                node.insert("body".into(), self.yul_block(span, &function.body));
+                node.insert("name".into(), function.name.to_string().into());
+                if !function.parameters.is_empty() {
+                    node.insert("parameters".into(), self.yul_typed_names(&function.parameters));
+                }
+                if !function.returns.is_empty() {
+                    node.insert("returnVariables".into(), self.yul_typed_names(&function.returns));
+                }
+                node
+            }
+            yul::StmtKind::VarDecl(names, value) => {
+                let mut node = self.yul_node("YulVariableDeclaration", span);
+                node.insert("value".into(), value.as_ref().map(|v| self.yul_expr(v)).into());
+                node.insert("variables".into(), self.yul_typed_names(names));
+                node
+            }
+        };
+        node.into()
+    }
+
+    fn yul_typed_names(&self, names: &[Ident]) -> Value {
+        names
+            .iter()
+            .map(|name| {
+                let mut node = self.yul_node("YulTypedName", name.span);
+                node.insert("name".into(), name.to_string().into());
+                node.insert("type".into(), "".into());
+                Value::from(node)
+            })
+            .collect()
+    }
+
+    fn yul_expr(&mut self, expr: &yul::Expr<'_>) -> Value {
+        match &expr.kind {
+            yul::ExprKind::Path(path) => self.yul_identifier_path(path),
+            yul::ExprKind::Call(call) => self.yul_call(expr.span, call),
+            yul::ExprKind::Lit(lit) => self.yul_literal(lit).into(),
+        }
+    }
+
+    fn yul_call(&mut self, span: Span, call: &yul::ExprCall<'_>) -> Value {
+        let span = call.arguments.last().map_or(span, |arg| call.name.span.to(arg.span));
+        let mut node = self.yul_node("YulFunctionCall", span);
+        let arguments: Vec<_> = call.arguments.iter().map(|arg| self.yul_expr(arg)).collect();
+        node.insert("arguments".into(), arguments.into());
+        let mut name = self.yul_node("YulIdentifier", call.name.span);
+        name.insert("name".into(), call.name.to_string().into());
+        node.insert("functionName".into(), name.into());
+        node.into()
+    }
+
+    fn yul_identifier_path(&self, path: &ast::PathSlice) -> Value {
+        let mut node = self.yul_node("YulIdentifier", path.span());
+        node.insert("name".into(), path.to_string().into());
+        node.into()
+    }
+
+    fn yul_literal(&self, lit: &ast::Lit) -> Map<String, Value> {
+        let mut node = self.yul_node("YulLiteral", lit.span);
+        let (kind, value) = match &lit.kind {
+            ast::LitKind::Str(_, bytes) => ("string", String::from_utf8_lossy(bytes).into_owned()),
+            ast::LitKind::Bool(_) => ("bool", lit.symbol.to_string()),
+            _ => ("number", lit.symbol.to_string()),
+        };
+        node.insert("kind".into(), kind.into());
+        node.insert("type".into(), "".into());
+        node.insert("value".into(), value.into());
+        node
+    }
+}
+
+fn ident_name(ident: Option<Ident>) -> String {
+    ident.map(|ident| ident.to_string()).unwrap_or_default()
+}
+
+fn span_of(block: &[yul::Stmt<'_>], default: Span) -> Span {
+    block.iter().map(|stmt| stmt.span).reduce(Span::to).unwrap_or(default)
+}
@@ -15,16 +15,23 @@ impl<'ast> super::LoweringContext<'_, 'ast, '_> {
             let mut hir_source = hir::Source {
                 file: source.file.clone(),
                 imports: self.arena.alloc_slice_copy(&source.imports),
+                license: source.license.as_deref().map(|license| &*self.arena.alloc_str(license)),
                 items: &[],
+                using_directives: &[],
+                abi_coder: hir::AbiCoder::default(),
             };
             if let Some(ast) = &source.ast {
                 let mut items = SmallVec::<[_; 16]>::new();
                 self.current_source_id = id;
                 for item in ast.items.iter() {
                     match &item.kind {
-                        ast::ItemKind::Pragma(_)
-                        | ast::ItemKind::Import(_)
-                        | ast::ItemKind::Using(_) => {}
+                        ast::ItemKind::Pragma(pragma) => {
+                            if let Some(abi_coder) = abi_coder_pragma(pragma) {
+                                hir_source.abi_coder = abi_coder;
+                            }
+                        }
+                        ast::ItemKind::Import(_) => {}
+                        ast::ItemKind::Using(_) => self.using_directives.push((id, None, item)),
                         ast::ItemKind::Contract(_)
                         | ast::ItemKind::Function(_)
                         | ast::ItemKind::Variable(_)
@@ -52,15 +59,18 @@ impl<'ast> super::LoweringContext<'_, 'ast, '_> {
             span: item.span,
             name: contract.name,
             kind: contract.kind,
+            docs: &[],
 
             // Set later.
             bases: &[],
             linearized_bases: &[],
+            bases_args: &[],
 
             ctor: None,
             fallback: None,
             receive: None,
             items: &[],
+            using_directives: &[],
         });
         let prev_contract_id = std::mem::replace(&mut self.current_contract_id, Some(id));
         debug_assert_eq!(prev_contract_id, None);
@@ -71,7 +81,10 @@ impl<'ast> super::LoweringContext<'_, 'ast, '_> {
                 ast::ItemKind::Pragma(_)
                 | ast::ItemKind::Import(_)
                 | ast::ItemKind::Contract(_) => unreachable!("illegal item in contract body"),
-                ast::ItemKind::Using(_) => continue,
+                ast::ItemKind::Using(_) => {
+                    self.using_directives.push((self.current_source_id, Some(id), item));
+                    continue;
+                }
                 ast::ItemKind::Variable(_) => {
                     let hir::ItemId::Variable(id) = self.lower_item(item) else { unreachable!() };
                     items.push(hir::ItemId::Variable(id));
@@ -117,10 +130,28 @@ impl<'ast> super::LoweringContext<'_, 'ast, '_> {
             ast::ItemKind::Error(i) => hir::ItemId::Error(self.lower_error(item, i)),
             ast::ItemKind::Event(i) => hir::ItemId::Event(self.lower_event(item, i)),
         };
+        self.lower_docs(item_id, item);
         self.hir_to_ast.insert(item_id, item);
         item_id
     }
 
+    fn lower_docs(&mut self, id: hir::ItemId, item: &ast::Item<'_>) {
+        let docs = item.docs.iter().flat_map(|doc| doc.natspec.iter().copied());
+        let docs = docs.collect::<SmallVec<[_; 8]>>();
+        if docs.is_empty() {
+            return;
+        }
+        let docs = self.arena.alloc_slice_copy(&docs);
+        match id {
+            hir::ItemId::Contract(id) => self.hir.contracts[id].docs = docs,
+            hir::ItemId::Function(id) => self.hir.functions[id].docs = docs,
+            hir::ItemId::Variable(id) => self.hir.variables[id].docs = docs,
+            hir::ItemId::Event(id) => self.hir.events[id].docs = docs,
+            hir::ItemId::Error(id) => self.hir.errors[id].docs = docs,
+            hir::ItemId::Struct(_) | hir::ItemId::Enum(_) | hir::ItemId::Udvt(_) => {}
+        }
+    }
+
     fn lower_function(
         &mut self,
         item: &ast::Item<'_>,
@@ -144,6 +175,7 @@ impl<'ast> super::LoweringContext<'_, 'ast, '_> {
             span: item.span,
             name,
             kind,
+            docs: &[],
             gettee: None,
             modifiers: &[],
             marked_virtual: virtual_,
@@ -153,7 +185,11 @@ impl<'ast> super::LoweringContext<'_, 'ast, '_> {
                     .is_some_and(|id| self.hir.contract(id).kind.is_interface()),
             override_: override_.is_some(),
             overrides: &[],
-            visibility: visibility.unwrap_or(ast::Visibility::Public),
+            // Free functions are always internal.
+            visibility: match self.current_contract_id {
+                Some(_) => visibility.unwrap_or(ast::Visibility::Public),
+                None => ast::Visibility::Internal,
+            },
             state_mutability,
             parameters: &[],
             returns: &[],
@@ -219,6 +255,7 @@ impl<'ast> super::LoweringContext<'_, 'ast, '_> {
             contract: self.current_contract_id,
             span: item.span,
             name,
+            docs: &[],
             parameters: &[],
         })
     }
@@ -231,6 +268,7 @@ impl<'ast> super::LoweringContext<'_, 'ast, '_> {
             contract: self.current_contract_id,
             span: item.span,
             name,
+            docs: &[],
             anonymous,
             parameters: &[],
         })
@@ -266,6 +304,7 @@ pub(super) fn lower_variable_partial(
         kind,
         ty: hir::Type::DUMMY,
         name,
+        docs: &[],
         visibility,
         mutability,
         data_location,
@@ -291,6 +330,7 @@ fn generate_partial_getter(hir: &mut hir::Hir<'_>, id: hir::VariableId) -> hir::
         kind,
         ty: _,
         name,
+        docs: _,
         visibility,
         mutability: _,
         data_location,
@@ -311,7 +351,8 @@ fn generate_partial_getter(hir: &mut hir::Hir<'_>, id: hir::VariableId) -> hir::
         span,
         name,
         kind: ast::FunctionKind::Function,
-        visibility: ast::Visibility::Public,
+        docs: &[],
+        visibility: ast::Visibility::External,
         state_mutability: ast::StateMutability::View,
         modifiers: &[],
         marked_virtual: false,
@@ -324,3 +365,13 @@ fn generate_partial_getter(hir: &mut hir::Hir<'_>, id: hir::VariableId) -> hir::
         gettee: Some(id),
     })
 }
+
+/// Returns the ABI coder version selected by the given pragma, if any.
+fn abi_coder_pragma(pragma: &ast::PragmaDirective<'_>) -> Option<hir::AbiCoder> {
+    let ast::PragmaTokens::Custom(name, Some(value)) = &pragma.tokens else { return None };
+    match (name.as_str(), value.as_str()) {
+        ("abicoder", "v1") => Some(hir::AbiCoder::V1),
+        ("abicoder", "v2") | ("experimental", "ABIEncoderV2") => Some(hir::AbiCoder::V2),
+        _ => None,
+    }
+}
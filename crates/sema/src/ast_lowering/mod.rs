@@ -60,6 +60,9 @@ struct LoweringContext<'sess, 'ast, 'hir> {
     current_source_id: hir::SourceId,
     /// Current contract being lowered.
     current_contract_id: Option<hir::ContractId>,
+    /// The `using for` directives, with the source and contract they are in. These are lowered
+    /// while resolving symbols.
+    using_directives: Vec<(hir::SourceId, Option<hir::ContractId>, &'ast ast::Item<'ast>)>,
 
     resolver: SymbolResolver<'sess>,
 }
@@ -72,6 +75,7 @@ impl<'sess, 'hir> LoweringContext<'sess, '_, 'hir> {
             hir: Hir::new(),
             current_source_id: hir::SourceId::MAX,
             current_contract_id: None,
+            using_directives: Vec::new(),
             hir_to_ast: FxHashMap::default(),
             resolver: SymbolResolver::new(&sess.dcx),
         }
@@ -1,16 +1,16 @@
 use crate::{builtins::Builtin, hir, ParsedSources};
-use solar_ast as ast;
+use solar_ast::{self as ast, visit::Visit};
 use solar_data_structures::{
     index::{Idx, IndexVec},
     map::{FxIndexMap, IndexEntry},
     smallvec::SmallVec,
-    BumpExt,
+    BumpExt, Never,
 };
 use solar_interface::{
     diagnostics::{DiagCtxt, ErrorGuaranteed},
-    sym, Ident, Session, Span, Symbol,
+    error_code, kw, sym, Ident, Session, Span, Symbol,
 };
-use std::{fmt, sync::atomic::AtomicUsize};
+use std::{fmt, ops::ControlFlow, sync::atomic::AtomicUsize};
 
 pub(crate) use crate::hir::Res;
 
@@ -190,6 +190,18 @@ impl super::LoweringContext<'_, '_, '_> {
                     self.dcx().err(msg).span(name.span()).emit();
                     continue;
                 }
+                if self.hir.contract(base_id).kind.is_library() {
+                    let msg = "libraries cannot be inherited from";
+                    self.dcx().err(msg).span(name.span()).emit();
+                    continue;
+                }
+                if self.hir.contract(contract_id).kind.is_interface()
+                    && !self.hir.contract(base_id).kind.is_interface()
+                {
+                    let msg = "interfaces can only inherit from other interfaces";
+                    self.dcx().err(msg).span(name.span()).emit();
+                    continue;
+                }
                 bases.push(base_id);
             }
             self.hir.contracts[contract_id].bases = self.arena.alloc_slice_copy(&bases);
@@ -219,9 +231,13 @@ impl super::LoweringContext<'_, '_, '_> {
                             continue;
                         }
                         let msg = format!("{} function already declared", func.kind);
-                        let note = "previous declaration here";
                         let prev_span = self.hir.function(prev).span;
-                        self.dcx().err(msg).span(func.span).span_note(prev_span, note).emit();
+                        self.dcx()
+                            .err(msg)
+                            .span(func.span)
+                            .span_label(func.span, "redeclared here")
+                            .span_note(prev_span, "first declared here")
+                            .emit();
                     } else {
                         *slot = Some(function_id);
                     }
@@ -300,6 +316,8 @@ impl<'hir> super::LoweringContext<'_, '_, 'hir> {
             self.resolve_var(id, next_id);
         }
 
+        self.resolve_base_args(next_id);
+
         for id in self.hir.function_ids() {
             let func = self.hir.function(id);
 
@@ -315,7 +333,8 @@ impl<'hir> super::LoweringContext<'_, '_, 'hir> {
 
             let scopes = SymbolResolverScopes::new_in(func.source, func.contract);
 
-            self.hir.functions[id].modifiers = {
+            // The arguments are lowered later, in the scope of the function parameters.
+            let modifiers = {
                 let mut modifiers = SmallVec::<[_; 8]>::new();
                 for modifier in ast_func.header.modifiers.iter() {
                     let expected = if func.kind.is_constructor() {
@@ -343,9 +362,9 @@ impl<'hir> super::LoweringContext<'_, '_, 'hir> {
                             continue;
                         }
                     }
-                    modifiers.push(id);
+                    modifiers.push((id, modifier));
                 }
-                self.arena.alloc_smallvec(modifiers)
+                modifiers
             };
 
             let func = self.hir.function(id);
@@ -377,17 +396,172 @@ impl<'hir> super::LoweringContext<'_, '_, 'hir> {
                 cx.lower_variables(ast_func.header.parameters, hir::VarKind::FunctionParam);
             cx.hir.functions[id].returns =
                 cx.lower_variables(ast_func.header.returns, hir::VarKind::FunctionReturn);
+            let arena = cx.arena;
+            cx.hir.functions[id].modifiers =
+                arena.alloc_slice_fill_iter(modifiers.iter().map(|&(id, modifier)| {
+                    hir::Modifier {
+                        id,
+                        span: modifier.name.span(),
+                        args: cx.lower_call_args(&modifier.arguments),
+                    }
+                }));
             if let Some(body) = &ast_func.body {
                 cx.hir.functions[id].body = Some(cx.lower_stmts(body));
             }
         }
 
+        self.resolve_using_directives(next_id);
+
         // Resolve function parameters and local variables, created while resolving functions.
         for id in self.hir.variable_ids().skip(normal_vars) {
             self.resolve_var(id, next_id);
         }
     }
 
+    /// Resolves the base constructor arguments given in the inheritance lists of the contracts.
+    fn resolve_base_args(&mut self, next_id: &AtomicUsize) {
+        for contract_id in self.hir.contract_ids() {
+            let item = self.hir_to_ast[&hir::ItemId::Contract(contract_id)];
+            let ast::ItemKind::Contract(ast_contract) = &item.kind else { unreachable!() };
+            let c = self.hir.contract(contract_id);
+            let bases = c.bases;
+
+            // The base names were already resolved in `resolve_base_contracts`.
+            let name_scopes = SymbolResolverScopes::new_in(c.source, None);
+            let scopes = SymbolResolverScopes::new_in(c.source, Some(contract_id));
+            let mut cx = ResolveContext::new(self, scopes, next_id, None);
+            let mut bases_args = SmallVec::<[_; 8]>::new();
+            for base in ast_contract.bases.iter() {
+                if base.arguments.is_empty() {
+                    continue;
+                }
+                let Ok(decl) = cx.resolver.resolve_path(base.name, &name_scopes) else {
+                    continue;
+                };
+                let Res::Item(hir::ItemId::Contract(base_id)) = decl.res else { continue };
+                if !bases.contains(&base_id) {
+                    continue;
+                }
+                bases_args.push(hir::Modifier {
+                    id: hir::ItemId::Contract(base_id),
+                    span: base.name.span(),
+                    args: cx.lower_call_args(&base.arguments),
+                });
+            }
+            self.hir.contracts[contract_id].bases_args = self.arena.alloc_smallvec(bases_args);
+        }
+    }
+
+    /// Resolves the `using for` directives, and assigns them to their sources and contracts.
+    ///
+    /// Attaching the functions to the type is checked after type lowering, in
+    /// `typeck::check_using_directive`.
+    fn resolve_using_directives(&mut self, next_id: &AtomicUsize) {
+        let mut source_directives =
+            (0..self.hir.sources.len()).map(|_| Vec::new()).collect::<Vec<_>>();
+        let mut contract_directives =
+            (0..self.hir.contracts.len()).map(|_| Vec::new()).collect::<Vec<_>>();
+        for (source, contract, item) in std::mem::take(&mut self.using_directives) {
+            let ast::ItemKind::Using(using) = &item.kind else { unreachable!() };
+            let scopes = SymbolResolverScopes::new_in(source, contract);
+            let list = match &using.list {
+                ast::UsingList::Single(path) => {
+                    let Ok(id) = self.resolver.resolve_path_as(path, &scopes, "library") else {
+                        continue;
+                    };
+                    match id {
+                        hir::ItemId::Contract(id) if self.hir.contract(id).kind.is_library() => {
+                            hir::UsingList::Library(id)
+                        }
+                        _ => {
+                            let msg = format!(
+                                "expected library, found {}",
+                                self.hir.item(id).description()
+                            );
+                            self.dcx()
+                                .err(msg)
+                                .span(path.span())
+                                .help("use `using {...} for ...` to attach a single function")
+                                .emit();
+                            continue;
+                        }
+                    }
+                }
+                ast::UsingList::Multiple(paths) => {
+                    let functions = paths.iter().filter_map(|(path, operator)| {
+                        let id = self.resolve_using_function(path, contract, &scopes)?;
+                        Some(hir::UsingFunction { id, span: path.span(), operator: *operator })
+                    });
+                    hir::UsingList::Functions(
+                        self.arena.alloc_smallvec(functions.collect::<SmallVec<[_; 8]>>()),
+                    )
+                }
+            };
+            let mut cx = ResolveContext::new(self, scopes, next_id, None);
+            let ty = using.ty.as_ref().map(|ty| cx.lower_type(ty));
+            let directive = hir::UsingDirective { span: item.span, list, ty, global: using.global };
+            match contract {
+                Some(id) => contract_directives[id.index()].push(directive),
+                None => source_directives[source.index()].push(directive),
+            }
+        }
+        for (id, directives) in source_directives.into_iter().enumerate() {
+            self.hir.sources[hir::SourceId::from_usize(id)].using_directives =
+                self.arena.alloc_slice_fill_iter(directives);
+        }
+        for (id, directives) in contract_directives.into_iter().enumerate() {
+            self.hir.contracts[hir::ContractId::from_usize(id)].using_directives =
+                self.arena.alloc_slice_fill_iter(directives);
+        }
+    }
+
+    /// Resolves a function in a `using {...} for` directive in `contract`.
+    fn resolve_using_function(
+        &self,
+        path: &ast::PathSlice,
+        contract: Option<hir::ContractId>,
+        scopes: &SymbolResolverScopes,
+    ) -> Option<hir::FunctionId> {
+        let decls = self
+            .resolver
+            .resolve_paths(path, scopes)
+            .map_err(self.resolver.emit_resolver_error())
+            .ok()?;
+        let id = match *decls {
+            [decl] => match decl.res {
+                Res::Item(hir::ItemId::Function(id)) => id,
+                Res::Err(_) => return None,
+                _ => {
+                    self.resolver.report_expected("function", decl.description(), path.span());
+                    return None;
+                }
+            },
+            _ => {
+                let msg = format!("`{path}` is not a unique function name");
+                self.dcx().err(msg).span(path.span()).emit();
+                return None;
+            }
+        };
+        let func = self.hir.function(id);
+        if let Some(c) = func.contract {
+            if !self.hir.contract(c).kind.is_library() {
+                let msg = "only file-level functions and library functions can be attached to a \
+                           type in a `using for` directive";
+                self.dcx().err(msg).span(path.span()).emit();
+                return None;
+            }
+            if func.visibility == ast::Visibility::Private && contract != Some(c) {
+                let msg = format!(
+                    "function `{path}` is private and therefore cannot be attached to a type \
+                     outside of the library where it is defined"
+                );
+                self.dcx().err(msg).span(path.span()).emit();
+                return None;
+            }
+        }
+        Some(id)
+    }
+
     fn resolve_var(&mut self, id: hir::VariableId, next_id: &AtomicUsize) {
         let var = self.hir.variable(id);
 
@@ -712,10 +886,32 @@ impl<'sess, 'hir, 'a> ResolveContext<'sess, 'hir, 'a> {
                 })),
                 self.lower_expr(expr),
             ),
-            ast::StmtKind::Assembly(_) => hir::StmtKind::Err(
-                // self.dcx().err("assembly is not yet implemented").span(stmt.span).emit(),
-                ErrorGuaranteed::new_unchecked(),
-            ),
+            ast::StmtKind::Assembly(assembly) => {
+                let mut collector =
+                    YulCollector { calls: Vec::new(), paths: Vec::new(), in_function: false };
+                let _ = collector.visit_yul_block(&assembly.block);
+                let calls = self.arena.alloc_slice_copy(&collector.calls);
+                let mut variables = SmallVec::<[_; 8]>::new();
+                let mut references = SmallVec::<[_; 8]>::new();
+                for path in collector.paths {
+                    if let Some(reference) = self.resolve_assembly_reference(path) {
+                        if let hir::ItemId::Variable(id) = reference.item {
+                            if !variables.contains(&id) {
+                                variables.push(id);
+                            }
+                        }
+                        references.push(reference);
+                    }
+                }
+                let variables = self.arena.alloc_smallvec(variables);
+                let references = self.arena.alloc_smallvec(references);
+                hir::StmtKind::Assembly(self.arena.alloc(hir::StmtAssembly {
+                    memory_safe: assembly.is_memory_safe(),
+                    calls,
+                    variables,
+                    references,
+                }))
+            }
             ast::StmtKind::Block(stmts) => hir::StmtKind::Block(self.lower_block(stmts)),
             ast::StmtKind::UncheckedBlock(stmts) => {
                 hir::StmtKind::UncheckedBlock(self.lower_block(stmts))
@@ -757,6 +953,7 @@ impl<'sess, 'hir, 'a> ResolveContext<'sess, 'hir, 'a> {
                 }))
             }
             ast::StmtKind::Placeholder => hir::StmtKind::Placeholder,
+            ast::StmtKind::Err(guar) => hir::StmtKind::Err(*guar),
         };
         hir::Stmt { span: stmt.span, kind }
     }
@@ -787,13 +984,92 @@ impl<'sess, 'hir, 'a> ResolveContext<'sess, 'hir, 'a> {
         self.hir.variables[id].initializer = self.lower_expr_opt(var.initializer.as_deref());
         let mut guar = Ok(());
         if let Some(name) = var.name {
+            if matches!(
+                kind,
+                hir::VarKind::FunctionParam
+                    | hir::VarKind::FunctionReturn
+                    | hir::VarKind::Statement
+                    | hir::VarKind::TryCatch
+            ) {
+                self.check_shadowing(name, var.span);
+            }
             let res = Res::Item(hir::ItemId::Variable(id));
             guar = self.scopes.current_scope().declare_res(self.sess, self.hir, name, res);
         }
         (id, guar)
     }
 
+    /// Warns if a variable named `name` shadows a declaration of an enclosing scope, including
+    /// inherited members and builtins.
+    fn check_shadowing(&mut self, name: Ident, span: Span) {
+        // Conflicts in the current scope are reported when declaring the variable.
+        self.scopes.current_scope();
+        let Some(decl) = self
+            .scopes
+            .get(self.resolver)
+            .skip(1)
+            .find_map(|scope| scope.resolve(name))
+            .and_then(|decls| decls.first())
+        else {
+            return;
+        };
+        match decl.res {
+            Res::Err(_) => {}
+            Res::Builtin(_) => {
+                let msg = "this declaration shadows a builtin symbol";
+                self.sess.dcx.warn(msg).code(error_code!(2319)).span(span).emit();
+            }
+            _ => {
+                let msg = "this declaration shadows an existing declaration";
+                let mut warn = self.sess.dcx.warn(msg).code(error_code!(2519)).span(span);
+                if !decl.span.is_dummy() {
+                    warn = warn.span_note(decl.span, "the shadowed declaration is here");
+                }
+                warn.emit();
+            }
+        }
+    }
+
     /// Desugars a `while`, `do while`, or `for` loop into a `loop` HIR statement.
+    /// Resolves a path in an assembly block to a Solidity declaration.
+    ///
+    /// Returns `None` if the path does not refer to a Solidity declaration, like identifiers
+    /// declared in the block itself.
+    fn resolve_assembly_reference(&self, path: YulPath<'_>) -> Option<hir::AssemblyReference> {
+        let YulPath { path, is_assignment, in_function } = path;
+        let decls = self.resolver.resolve_name_raw(*path.first(), &self.scopes)?;
+        let suffix = match path.segments() {
+            [_] => None,
+            [_, suffix]
+                if [sym::slot, sym::offset, sym::length, kw::Address, sym::selector]
+                    .contains(&suffix.name) =>
+            {
+                Some(*suffix)
+            }
+            [_, rest @ ..] => {
+                let msg = format!("unknown suffix `.{}`", ast::PathSlice::from_slice(rest));
+                self.sess
+                    .dcx
+                    .err(msg)
+                    .span(path.span())
+                    .help(
+                        "expected one of `.slot`, `.offset`, `.length`, `.address`, or `.selector`",
+                    )
+                    .emit();
+                return None;
+            }
+            [] => unreachable!(),
+        };
+        let [decl] = decls else {
+            let msg = "multiple matching identifiers; resolving overloaded identifiers is not \
+                       supported in inline assembly";
+            self.sess.dcx.err(msg).code(error_code!(4718)).span(path.span()).emit();
+            return None;
+        };
+        let Res::Item(item) = decl.res else { return None };
+        Some(hir::AssemblyReference { span: path.span(), item, suffix, is_assignment, in_function })
+    }
+
     fn lower_loop_stmt(&mut self, stmt: &ast::Stmt<'_>) -> hir::StmtKind<'hir> {
         let span = stmt.span;
         match &stmt.kind {
@@ -1024,6 +1300,9 @@ struct ResolverError {
 }
 
 enum ResolverErrorKind {
+    /// The first segment of a path did not resolve to any declaration in scope.
+    Undeclared,
+    /// A member segment of a path did not resolve to any declaration in the parent's scope.
     Unresolved,
     NotAScope(Res),
     MultipleDeclarations,
@@ -1045,6 +1324,7 @@ impl ResolverError {
     fn format(&self) -> String {
         let name = self.name;
         match self.kind {
+            ResolverErrorKind::Undeclared => format!("undeclared identifier `{name}`"),
             ResolverErrorKind::Unresolved => format!("unresolved symbol `{name}`"),
             ResolverErrorKind::NotAScope(kind) => {
                 format!(
@@ -1094,7 +1374,13 @@ impl<'sess> SymbolResolver<'sess> {
     }
 
     fn emit_resolver_error(&self) -> impl Fn(ResolverError) -> ErrorGuaranteed + '_ {
-        move |e| self.dcx.err(e.format()).span(e.span()).emit()
+        move |e| {
+            let mut err = self.dcx.err(e.format()).span(e.span());
+            if let ResolverErrorKind::Undeclared = e.kind {
+                err = err.code(error_code!(7576));
+            }
+            err.emit()
+        }
     }
 
     fn resolve_path(
@@ -1119,7 +1405,7 @@ impl<'sess> SymbolResolver<'sess> {
         let name = *segments.next().unwrap();
         let mut decls = self
             .resolve_name_raw(name, scopes)
-            .ok_or_else(|| ResolverError::new(name, ResolverErrorKind::Unresolved))?;
+            .ok_or_else(|| ResolverError::new(name, ResolverErrorKind::Undeclared))?;
         for (prev_i, &segment) in segments.enumerate() {
             let [decl] = decls else {
                 return Err(ResolverError::from_path(
@@ -1368,13 +1654,19 @@ pub(super) fn report_conflict(
 ) -> ErrorGuaranteed {
     debug_assert_ne!(decl.span, previous.span);
 
-    let mut err = sess.dcx.err(format!("identifier `{name}` already declared")).span(decl.span);
+    let msg = format!("identifier `{name}` already declared");
+    let mut err = sess
+        .dcx
+        .err(msg)
+        .code(error_code!(2333))
+        .span(decl.span)
+        .span_label(decl.span, "redeclared here");
 
     // If `previous` is coming from an import, show both the import and the real span.
     if let Res::Item(item_id) = previous.res {
         if let Ok(snippet) = sess.source_map().span_to_snippet(previous.span) {
             if snippet.starts_with("import") {
-                err = err.span_note(previous.span, "previous declaration imported here");
+                err = err.span_note(previous.span, "first imported here");
                 let real_span = hir.item(item_id).span();
                 previous.span = real_span;
             }
@@ -1382,8 +1674,78 @@ pub(super) fn report_conflict(
     }
 
     if !previous.span.is_dummy() {
-        err = err.span_note(previous.span, "previous declaration declared here");
+        err = err.span_note(previous.span, "first declared here");
     }
 
     err.emit()
 }
+
+/// Collects the function calls and the referenced names in a Yul block. See
+/// [`hir::StmtAssembly`].
+struct YulCollector<'ast> {
+    calls: Vec<(Symbol, Span)>,
+    paths: Vec<YulPath<'ast>>,
+    in_function: bool,
+}
+
+/// A path referenced in a Yul block, which may resolve to a Solidity declaration.
+#[derive(Clone, Copy)]
+struct YulPath<'ast> {
+    path: &'ast ast::PathSlice,
+    is_assignment: bool,
+    in_function: bool,
+}
+
+impl<'ast> YulCollector<'ast> {
+    fn push_path(&mut self, path: &'ast ast::PathSlice, is_assignment: bool) {
+        self.paths.push(YulPath { path, is_assignment, in_function: self.in_function });
+    }
+}
+
+impl<'ast> Visit<'ast> for YulCollector<'ast> {
+    type BreakValue = Never;
+
+    fn visit_path(&mut self, path: &'ast ast::PathSlice) -> ControlFlow<Self::BreakValue> {
+        self.push_path(path, false);
+        self.walk_path(path)
+    }
+
+    fn visit_yul_stmt(
+        &mut self,
+        stmt: &'ast ast::yul::Stmt<'ast>,
+    ) -> ControlFlow<Self::BreakValue> {
+        match &stmt.kind {
+            ast::yul::StmtKind::AssignSingle(path, expr) => {
+                self.push_path(path, true);
+                self.visit_yul_expr(expr)
+            }
+            ast::yul::StmtKind::AssignMulti(paths, call) => {
+                for path in paths.iter() {
+                    self.push_path(path, true);
+                }
+                self.visit_yul_expr_call(call)
+            }
+            _ => self.walk_yul_stmt(stmt),
+        }
+    }
+
+    fn visit_yul_function(
+        &mut self,
+        function: &'ast ast::yul::Function<'ast>,
+    ) -> ControlFlow<Self::BreakValue> {
+        let in_function = std::mem::replace(&mut self.in_function, true);
+        let r = self.walk_yul_function(function);
+        self.in_function = in_function;
+        r
+    }
+
+    fn visit_yul_expr(
+        &mut self,
+        expr: &'ast ast::yul::Expr<'ast>,
+    ) -> ControlFlow<Self::BreakValue> {
+        if let ast::yul::ExprKind::Call(call) = &expr.kind {
+            self.calls.push((call.name.name, expr.span));
+        }
+        self.walk_yul_expr(expr)
+    }
+}
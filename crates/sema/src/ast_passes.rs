@@ -1,13 +1,15 @@
 //! AST-related passes.
 
 use solar_ast::{self as ast, visit::Visit};
-use solar_data_structures::Never;
-use solar_interface::{diagnostics::DiagCtxt, sym, Session, Span};
+use solar_data_structures::{map::FxHashSet, Never};
+use solar_interface::{diagnostics::DiagCtxt, error_code, sym, Ident, Session, Span, Symbol};
 use std::ops::ControlFlow;
 
 #[instrument(name = "ast_passes", level = "debug", skip_all)]
 pub(crate) fn run(sess: &Session, ast: &ast::SourceUnit<'_>) {
     validate(sess, ast);
+    check_unused_imports(sess, ast);
+    crate::lints::check_source_unit(sess, ast);
 }
 
 /// Performs AST validation.
@@ -17,10 +19,82 @@ pub fn validate(sess: &Session, ast: &ast::SourceUnit<'_>) {
     validator.visit_source_unit(ast);
 }
 
+/// Warns about the symbols imported by name or under an alias that are never referenced in the
+/// source unit.
+///
+/// Imports of all the symbols of a source into the current scope are not checked.
+fn check_unused_imports(sess: &Session, ast: &ast::SourceUnit<'_>) {
+    let mut imported = Vec::new();
+    for item in ast.items.iter() {
+        let ast::ItemKind::Import(import) = &item.kind else { continue };
+        match &import.items {
+            ast::ImportItems::Plain(alias) | ast::ImportItems::Glob(alias) => {
+                imported.extend(*alias);
+            }
+            ast::ImportItems::Aliases(aliases) => {
+                imported.extend(aliases.iter().map(|&(name, alias)| alias.unwrap_or(name)));
+            }
+        }
+    }
+    if imported.is_empty() {
+        return;
+    }
+
+    let mut collector = UsedNames { names: FxHashSet::default() };
+    let _ = collector.visit_source_unit(ast);
+    for name in imported {
+        if !collector.names.contains(&name.name) {
+            let msg = format!("unused import: `{name}`");
+            sess.dcx.warn(msg).code(error_code!(8005)).span(name.span).emit();
+        }
+    }
+}
+
+/// Collects all the names that are referenced outside of import directives, including the
+/// contracts referenced by `@inheritdoc` tags.
+struct UsedNames {
+    names: FxHashSet<Symbol>,
+}
+
+impl<'ast> Visit<'ast> for UsedNames {
+    type BreakValue = Never;
+
+    fn visit_import_directive(
+        &mut self,
+        _import: &'ast ast::ImportDirective<'ast>,
+    ) -> ControlFlow<Self::BreakValue> {
+        ControlFlow::Continue(())
+    }
+
+    fn visit_doc_comment(
+        &mut self,
+        doc_comment: &'ast ast::DocComment<'ast>,
+    ) -> ControlFlow<Self::BreakValue> {
+        for item in doc_comment.natspec.iter() {
+            if let ast::NatSpecKind::Inheritdoc { contract } = item.kind {
+                self.names.insert(contract.name);
+            }
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn visit_path(&mut self, path: &'ast ast::PathSlice) -> ControlFlow<Self::BreakValue> {
+        // Only the first segment is looked up in the source scope.
+        self.names.insert(path.first().name);
+        ControlFlow::Continue(())
+    }
+
+    fn visit_ident(&mut self, ident: &'ast Ident) -> ControlFlow<Self::BreakValue> {
+        self.names.insert(ident.name);
+        ControlFlow::Continue(())
+    }
+}
+
 /// AST validator.
 struct AstValidator<'sess, 'ast> {
     span: Span,
     dcx: &'sess DiagCtxt,
+    solidity_version: ast::SemverVersion,
     contract: Option<&'ast ast::ItemContract<'ast>>,
     function_kind: Option<ast::FunctionKind>,
     in_unchecked_block: bool,
@@ -32,6 +106,7 @@ impl<'sess> AstValidator<'sess, '_> {
         Self {
             span: Span::DUMMY,
             dcx: &sess.dcx,
+            solidity_version: sess.solidity_version.clone().into(),
             contract: None,
             function_kind: None,
             in_unchecked_block: false,
@@ -48,6 +123,276 @@ impl<'sess> AstValidator<'sess, '_> {
     fn in_loop(&self) -> bool {
         self.in_loop_depth != 0
     }
+
+    /// Checks that the NatSpec tags of an item are valid for its kind, and that the `@param` and
+    /// `@return` tags match its signature.
+    fn check_natspec(&self, item: &ast::Item<'_>) {
+        use ast::NatSpecKind as K;
+
+        let mut natspec = item.docs.iter().flat_map(|doc| doc.natspec.iter()).peekable();
+        if natspec.peek().is_none() {
+            return;
+        }
+
+        let (what, params, returns) = match &item.kind {
+            ast::ItemKind::Contract(_) => ("contracts", None, None),
+            ast::ItemKind::Function(f) => {
+                let what = if f.kind.is_modifier() { "modifiers" } else { "functions" };
+                (what, Some(&*f.header.parameters), Some(&*f.header.returns))
+            }
+            ast::ItemKind::Variable(_) => ("state variables", None, None),
+            ast::ItemKind::Struct(_) => ("structs", None, None),
+            ast::ItemKind::Enum(_) => ("enums", None, None),
+            ast::ItemKind::Error(e) => ("errors", Some(&*e.parameters), None),
+            ast::ItemKind::Event(e) => ("events", Some(&*e.parameters), None),
+            ast::ItemKind::Pragma(_)
+            | ast::ItemKind::Import(_)
+            | ast::ItemKind::Using(_)
+            | ast::ItemKind::Udvt(_) => return,
+        };
+        let is_public_var = matches!(
+            &item.kind,
+            ast::ItemKind::Variable(var) if var.visibility == Some(ast::Visibility::Public)
+        );
+
+        let mut documented_params = Vec::new();
+        let mut n_returns = 0;
+        for doc in natspec {
+            let valid = match doc.kind {
+                K::Notice | K::Dev | K::Custom { .. } => true,
+                K::Title | K::Author => matches!(item.kind, ast::ItemKind::Contract(_)),
+                K::Param { .. } => params.is_some(),
+                K::Return | K::Inheritdoc { .. } => returns.is_some() || is_public_var,
+                K::Unknown(_) => {
+                    let msg = format!("invalid NatSpec tag `{}`", doc.kind);
+                    self.dcx().err(msg).span(doc.span).emit();
+                    continue;
+                }
+            };
+            if !valid {
+                let msg = format!("documentation tag `{}` is not valid for {what}", doc.kind);
+                self.dcx().err(msg).span(doc.span).emit();
+                continue;
+            }
+            if matches!(item.kind, ast::ItemKind::Variable(_))
+                && matches!(doc.kind, K::Notice)
+                && !is_public_var
+            {
+                let msg = "documentation tag `@notice` is only allowed on public state variables";
+                self.dcx().err(msg).span(doc.span).emit();
+            }
+
+            match doc.kind {
+                K::Param { name } => {
+                    let params = params.unwrap_or_default();
+                    if !params.iter().any(|param| param.name == Some(name)) {
+                        let kind = &what[..what.len() - 1];
+                        let msg = format!(
+                            "documented parameter `{name}` not found in the parameter list of the \
+                             {kind}"
+                        );
+                        self.dcx().err(msg).code(error_code!(3881)).span(name.span).emit();
+                    } else if documented_params.contains(&name.name) {
+                        let msg = format!("parameter `{name}` is documented more than once");
+                        self.dcx().err(msg).span(name.span).emit();
+                    } else {
+                        documented_params.push(name.name);
+                    }
+                }
+                K::Return => {
+                    let Some(returns) = returns else { continue };
+                    let Some(ret) = returns.get(n_returns) else {
+                        let msg = "documentation tag `@return` exceeds the number of return \
+                                   parameters";
+                        self.dcx().err(msg).span(doc.span).emit();
+                        continue;
+                    };
+                    n_returns += 1;
+                    if let Some(ret_name) = ret.name {
+                        let content = doc.content.as_str();
+                        if content.split_whitespace().next() != Some(ret_name.as_str()) {
+                            let msg = format!(
+                                "documentation tag `@return` does not contain the name of its \
+                                 return parameter `{ret_name}`"
+                            );
+                            self.dcx().err(msg).span(doc.span).emit();
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Checks that a library does not inherit, and does not declare non-constant state variables
+    /// or `fallback` and `receive` functions.
+    ///
+    /// Reference: <https://docs.soliditylang.org/en/latest/contracts.html#libraries>
+    fn check_library(&self, contract: &ast::ItemContract<'_>) {
+        let dcx = self.dcx();
+        if let Some(base) = contract.bases.first() {
+            dcx.err("library is not allowed to inherit").span(base.name.span()).emit();
+        }
+        for item in contract.body.iter() {
+            match &item.kind {
+                ast::ItemKind::Variable(var) if var.mutability != Some(ast::VarMut::Constant) => {
+                    let msg = "library cannot have non-constant state variables";
+                    dcx.err(msg).span(var.span).emit();
+                }
+                ast::ItemKind::Function(func) if func.kind.is_fallback() => {
+                    dcx.err("libraries cannot have fallback functions").span(item.span).emit();
+                }
+                ast::ItemKind::Function(func) if func.kind.is_receive() => {
+                    let msg = "libraries cannot have receive ether functions";
+                    dcx.err(msg).span(item.span).emit();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Checks that an interface only declares external functions without implementations, and
+    /// no constructor, modifiers, or state variables.
+    ///
+    /// Reference: <https://docs.soliditylang.org/en/latest/contracts.html#interfaces>
+    fn check_interface(&self, contract: &ast::ItemContract<'_>) {
+        let dcx = self.dcx();
+        for item in contract.body.iter() {
+            match &item.kind {
+                ast::ItemKind::Variable(var) => {
+                    dcx.err("variables cannot be declared in interfaces").span(var.span).emit();
+                }
+                ast::ItemKind::Function(func) => match func.kind {
+                    ast::FunctionKind::Constructor => {
+                        let msg = "constructors cannot be defined in interfaces";
+                        dcx.err(msg).span(item.span).emit();
+                    }
+                    ast::FunctionKind::Modifier => {
+                        dcx.err("modifiers cannot be declared in interfaces")
+                            .span(item.span)
+                            .emit();
+                    }
+                    _ => {
+                        let span = func.header.name.map_or(item.span, |name| name.span);
+                        // `fallback` and `receive` functions are checked separately.
+                        if func.kind.is_function()
+                            && func.header.visibility != Some(ast::Visibility::External)
+                        {
+                            let msg = "functions in interfaces must be declared external";
+                            dcx.err(msg).span(span).emit();
+                        }
+                        if func.body.is_some() {
+                            let msg = "functions in interfaces cannot have an implementation";
+                            dcx.err(msg).span(span).emit();
+                        }
+                    }
+                },
+                _ => {}
+            }
+        }
+    }
+
+    /// Checks the signatures of `fallback` and `receive` functions.
+    ///
+    /// Duplicate declarations are reported when assigning them to their contract.
+    ///
+    /// Reference: <https://docs.soliditylang.org/en/latest/contracts.html#special-functions>
+    fn check_special_function(&self, func: &ast::ItemFunction<'_>) {
+        let dcx = self.dcx();
+        let header = &func.header;
+        let params_span = |params: &[ast::VariableDefinition<'_>]| match params {
+            [] => self.span,
+            [param] => param.span,
+            [first, .., last] => first.span.to(last.span),
+        };
+        if header.visibility != Some(ast::Visibility::External) {
+            let what = if func.kind.is_receive() { "receive ether" } else { "fallback" };
+            let msg = format!("{what} function must be defined as `external`");
+            dcx.err(msg).span(self.span).emit();
+        }
+        match func.kind {
+            ast::FunctionKind::Fallback => {
+                if matches!(
+                    header.state_mutability,
+                    ast::StateMutability::Pure | ast::StateMutability::View
+                ) {
+                    let msg = format!(
+                        "fallback function must be payable or non-payable, but is `{}`",
+                        header.state_mutability
+                    );
+                    dcx.err(msg).span(self.span).emit();
+                }
+                let is_bytes = |params: &[ast::VariableDefinition<'_>], loc| {
+                    matches!(
+                        params,
+                        [param] if matches!(
+                            param.ty.kind,
+                            ast::TypeKind::Elementary(ast::ElementaryType::Bytes)
+                        ) && param.data_location == Some(loc)
+                    )
+                };
+                let valid = match (&*header.parameters, &*header.returns) {
+                    ([], []) => true,
+                    (params, returns) => {
+                        is_bytes(params, ast::DataLocation::Calldata)
+                            && is_bytes(returns, ast::DataLocation::Memory)
+                    }
+                };
+                if !valid {
+                    let span = if header.parameters.is_empty() {
+                        params_span(&header.returns)
+                    } else {
+                        params_span(&header.parameters)
+                    };
+                    dcx.err("invalid fallback function signature")
+                        .span(span)
+                        .help(
+                            "fallback functions have to be declared as `fallback()` or \
+                             `fallback(bytes calldata) returns (bytes memory)`",
+                        )
+                        .emit();
+                }
+            }
+            ast::FunctionKind::Receive => {
+                if !header.state_mutability.is_payable() {
+                    let msg = format!(
+                        "receive ether function must be payable, but is `{}`",
+                        header.state_mutability
+                    );
+                    dcx.err(msg).span(self.span).emit();
+                }
+                if !header.parameters.is_empty() {
+                    dcx.err("receive ether function cannot take parameters")
+                        .span(params_span(&header.parameters))
+                        .emit();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Checks the header and the body of a free function.
+    ///
+    /// Overrides are reported when resolving them.
+    fn check_free_function(&self, func: &ast::ItemFunction<'_>) {
+        let span = func.header.name.map_or(self.span, |name| name.span);
+        let dcx = self.dcx();
+        if func.header.visibility.is_some() {
+            dcx.err("free functions cannot have visibility").span(span).emit();
+        }
+        if func.header.state_mutability.is_payable() {
+            dcx.err("free functions cannot be payable").span(span).emit();
+        }
+        if func.header.virtual_ {
+            dcx.err("free functions cannot be virtual").span(span).emit();
+        }
+        if let Some(modifier) = func.header.modifiers.first() {
+            dcx.err("free functions cannot have modifiers").span(modifier.name.span()).emit();
+        }
+        if func.body.is_none() {
+            dcx.err("free functions must be implemented").span(span).emit();
+        }
+    }
 }
 
 impl<'ast> Visit<'ast> for AstValidator<'_, 'ast> {
@@ -55,6 +400,7 @@ impl<'ast> Visit<'ast> for AstValidator<'_, 'ast> {
 
     fn visit_item(&mut self, item: &'ast ast::Item<'ast>) -> ControlFlow<Self::BreakValue> {
         self.span = item.span;
+        self.check_natspec(item);
         self.walk_item(item)
     }
 
@@ -88,10 +434,14 @@ impl<'ast> Visit<'ast> for AstValidator<'_, 'ast> {
         pragma: &'ast ast::PragmaDirective<'ast>,
     ) -> ControlFlow<Self::BreakValue> {
         match &pragma.tokens {
-            ast::PragmaTokens::Version(name, _version) => {
+            ast::PragmaTokens::Version(name, req) => {
                 if name.name != sym::solidity {
                     let msg = "only `solidity` is supported as a version pragma";
                     self.dcx().err(msg).span(name.span).emit();
+                } else if !req.matches(&self.solidity_version) {
+                    let msg = "source file requires a different compiler version";
+                    let note = format!("the current compiler version is {}", self.solidity_version);
+                    self.dcx().err(msg).code(error_code!(5333)).span(self.span).note(note).emit();
                 }
             }
             ast::PragmaTokens::Custom(name, value) => {
@@ -168,6 +518,11 @@ impl<'ast> Visit<'ast> for AstValidator<'_, 'ast> {
         contract: &'ast ast::ItemContract<'ast>,
     ) -> ControlFlow<Self::BreakValue> {
         self.contract = Some(contract);
+        if contract.kind.is_library() {
+            self.check_library(contract);
+        } else if contract.kind.is_interface() {
+            self.check_interface(contract);
+        }
         let r = self.walk_item_contract(contract);
         self.contract = None;
         r
@@ -191,6 +546,11 @@ impl<'ast> Visit<'ast> for AstValidator<'_, 'ast> {
                     }
                 }
             }
+            if func.kind.is_fallback() || func.kind.is_receive() {
+                self.check_special_function(func);
+            }
+        } else if func.kind.is_function() {
+            self.check_free_function(func);
         }
 
         let r = self.walk_item_function(func);
@@ -230,6 +590,32 @@ impl<'ast> Visit<'ast> for AstValidator<'_, 'ast> {
         self.walk_using_directive(using)
     }
 
+    fn visit_stmt_assembly(
+        &mut self,
+        assembly: &'ast ast::StmtAssembly<'ast>,
+    ) -> ControlFlow<Self::BreakValue> {
+        let ast::StmtAssembly { dialect, flags, block: _ } = assembly;
+        if let Some(dialect) = dialect {
+            if dialect.value != sym::evmasm {
+                let msg = format!("unknown inline assembly dialect {:?}", dialect.value.as_str());
+                self.dcx().err(msg).span(dialect.span).help("only \"evmasm\" is supported").emit();
+            }
+        }
+
+        for (i, flag) in flags.iter().enumerate() {
+            if let Some(prev) = flags[..i].iter().find(|prev| prev.value == flag.value) {
+                let msg =
+                    format!("inline assembly flag {:?} used multiple times", flag.value.as_str());
+                self.dcx().err(msg).span(flag.span).span_note(prev.span, "first used here").emit();
+            } else if flag.value != sym::memory_dash_safe {
+                let msg = format!("unknown inline assembly flag {:?}", flag.value.as_str());
+                self.dcx().err(msg).span(flag.span).emit();
+            }
+        }
+
+        self.walk_stmt_assembly(assembly)
+    }
+
     // Intentionally override unused default implementations to reduce bloat.
     fn visit_expr(&mut self, _expr: &'ast ast::Expr<'ast>) -> ControlFlow<Self::BreakValue> {
         ControlFlow::Continue(())
@@ -4,7 +4,7 @@ use crate::{
     ty::{Gcx, Ty},
 };
 use solar_ast::StateMutability as SM;
-use solar_interface::{kw, sym, Span, Symbol};
+use solar_interface::{config::EvmVersion, kw, sym, Span, Symbol};
 
 pub(crate) mod members;
 pub use members::{Member, MemberList};
@@ -90,13 +90,13 @@ declare_builtins! {
                            => gcx.mk_builtin_fn(&[gcx.types.uint(256), gcx.types.uint(256), gcx.types.uint(256)], SM::Pure, &[gcx.types.uint(256)]);
 
     Keccak256              => kw::Keccak256
-                           => gcx.mk_builtin_fn(&[gcx.types.bytes_ref.memory], SM::View, &[gcx.types.fixed_bytes(32)]);
+                           => gcx.mk_builtin_fn(&[gcx.types.bytes_ref.memory], SM::Pure, &[gcx.types.fixed_bytes(32)]);
     Sha256                 => sym::sha256
-                           => gcx.mk_builtin_fn(&[gcx.types.bytes_ref.memory], SM::View, &[gcx.types.fixed_bytes(32)]);
+                           => gcx.mk_builtin_fn(&[gcx.types.bytes_ref.memory], SM::Pure, &[gcx.types.fixed_bytes(32)]);
     Ripemd160              => sym::ripemd160
-                           => gcx.mk_builtin_fn(&[gcx.types.bytes_ref.memory], SM::View, &[gcx.types.fixed_bytes(20)]);
+                           => gcx.mk_builtin_fn(&[gcx.types.bytes_ref.memory], SM::Pure, &[gcx.types.fixed_bytes(20)]);
     EcRecover              => sym::ecrecover
-                           => gcx.mk_builtin_fn(&[gcx.types.fixed_bytes(32), gcx.types.uint(8), gcx.types.fixed_bytes(32), gcx.types.fixed_bytes(32)], SM::View, &[gcx.types.address]);
+                           => gcx.mk_builtin_fn(&[gcx.types.fixed_bytes(32), gcx.types.uint(8), gcx.types.fixed_bytes(32), gcx.types.fixed_bytes(32)], SM::Pure, &[gcx.types.address]);
 
     Block                  => sym::block
                            => gcx.mk_builtin_mod(Self::Block);
@@ -178,9 +178,9 @@ declare_builtins! {
     AddressCodehash        => sym::codehash
                            => gcx.types.fixed_bytes(32);
     AddressCall            => kw::Call
-                           => gcx.mk_builtin_fn(&[gcx.types.bytes_ref.memory], SM::View, &[gcx.types.bytes_ref.memory]);
+                           => gcx.mk_builtin_fn(&[gcx.types.bytes_ref.memory], SM::Payable, &[gcx.types.bytes_ref.memory]);
     AddressDelegatecall    => kw::Delegatecall
-                           => gcx.mk_builtin_fn(&[gcx.types.bytes_ref.memory], SM::View, &[gcx.types.bytes_ref.memory]);
+                           => gcx.mk_builtin_fn(&[gcx.types.bytes_ref.memory], SM::NonPayable, &[gcx.types.bytes_ref.memory]);
     AddressStaticcall      => kw::Staticcall
                            => gcx.mk_builtin_fn(&[gcx.types.bytes_ref.memory], SM::View, &[gcx.types.bytes_ref.memory]);
 
@@ -292,6 +292,20 @@ impl Builtin {
             _ => return None,
         })
     }
+
+    /// Returns the first EVM version in which the builtin is available, if it is not available in
+    /// all of them.
+    pub fn min_evm_version(self) -> Option<EvmVersion> {
+        use EvmVersion::*;
+        Some(match self {
+            Self::AddressStaticcall => Byzantium,
+            Self::AddressCodehash => Constantinople,
+            Self::BlockChainid => Istanbul,
+            Self::BlockBasefee => London,
+            Self::Blobhash | Self::BlockBlobbasefee => Cancun,
+            _ => return None,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,374 @@
+//! Inter-procedural call graph.
+//!
+//! See [`CallGraph`].
+
+use crate::{
+    builtins::Builtin,
+    hir::{self, CallArgs, Res},
+    ty::Gcx,
+    typeck,
+};
+use solar_data_structures::smallvec::SmallVec;
+use solar_interface::Span;
+
+/// The call graph of all the functions and modifiers in the HIR.
+///
+/// Calls are resolved statically, without type checking the arguments:
+/// - virtual calls are attributed to the function they resolve to in the calling contract;
+/// - calls to overloaded functions that can't be told apart by their number of arguments produce
+///   a call to each candidate;
+/// - calls through function pointers are not included.
+///
+/// The calls of each function are computed lazily by [`Gcx::function_calls`].
+#[derive(Clone, Copy, Debug)]
+pub struct CallGraph<'gcx> {
+    gcx: Gcx<'gcx>,
+}
+
+impl<'gcx> CallGraph<'gcx> {
+    /// Creates a new call graph.
+    pub fn new(gcx: Gcx<'gcx>) -> Self {
+        Self { gcx }
+    }
+
+    /// Returns an iterator over all the calls, grouped by caller.
+    pub fn calls(self) -> impl Iterator<Item = &'gcx Call> + Clone + use<'gcx> {
+        self.gcx.hir.function_ids().flat_map(move |id| self.callees(id))
+    }
+
+    /// Returns an iterator over the calls made by the given function or modifier, in source order.
+    pub fn callees(
+        self,
+        id: hir::FunctionId,
+    ) -> impl Iterator<Item = &'gcx Call> + Clone + use<'gcx> {
+        self.gcx.function_calls(id).iter()
+    }
+
+    /// Returns an iterator over the calls to the given function or modifier.
+    pub fn callers(
+        self,
+        id: hir::FunctionId,
+    ) -> impl Iterator<Item = &'gcx Call> + Clone + use<'gcx> {
+        self.calls().filter(move |call| call.callee == id)
+    }
+}
+
+/// A call from a function or modifier to another, i.e. an edge in the [`CallGraph`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Call {
+    /// The calling function or modifier.
+    pub caller: hir::FunctionId,
+    /// The called function or modifier.
+    pub callee: hir::FunctionId,
+    /// The kind of the call.
+    pub kind: CallKind,
+    /// The span of the call expression, or of the modifier name.
+    pub span: Span,
+}
+
+/// The kind of a [`Call`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CallKind {
+    /// A call to a free function or to a function of the same contract or of a base contract,
+    /// including `super` calls and base constructor calls.
+    Internal,
+    /// A message call to a function of a contract, including calls through `this`, and contract
+    /// creations with `new`.
+    External,
+    /// A call to a function of a library from outside of the library.
+    Library,
+    /// A modifier invocation.
+    Modifier,
+}
+
+impl CallKind {
+    /// Returns the string representation of the call kind.
+    pub const fn to_str(self) -> &'static str {
+        match self {
+            Self::Internal => "internal",
+            Self::External => "external",
+            Self::Library => "library",
+            Self::Modifier => "modifier",
+        }
+    }
+}
+
+/// Collects the calls made by the given function or modifier, in source order.
+pub(crate) fn function_calls(gcx: Gcx<'_>, id: hir::FunctionId) -> Vec<Call> {
+    let func = gcx.hir.function(id);
+    let mut collector = CallCollector { gcx, id, func, calls: Vec::new() };
+    for modifier in func.modifiers {
+        match modifier.id {
+            hir::ItemId::Function(callee) => {
+                collector.push(callee, CallKind::Modifier, modifier.span);
+            }
+            hir::ItemId::Contract(base) => {
+                if let Some(ctor) = gcx.hir.contract(base).ctor {
+                    collector.push(ctor, CallKind::Internal, modifier.span);
+                }
+            }
+            _ => {}
+        }
+        collector.visit_args(&modifier.args);
+    }
+    if let Some(body) = func.body {
+        collector.visit_block(body);
+    }
+    collector.calls
+}
+
+struct CallCollector<'gcx> {
+    gcx: Gcx<'gcx>,
+    id: hir::FunctionId,
+    func: &'gcx hir::Function<'gcx>,
+    calls: Vec<Call>,
+}
+
+impl<'gcx> CallCollector<'gcx> {
+    fn push(&mut self, callee: hir::FunctionId, kind: CallKind, span: Span) {
+        self.calls.push(Call { caller: self.id, callee, kind, span });
+    }
+
+    /// Records the calls to the functions in `candidates` that accept `n_args` arguments.
+    fn push_candidates(
+        &mut self,
+        candidates: impl IntoIterator<Item = hir::FunctionId>,
+        n_args: usize,
+        span: Span,
+        kind: impl Fn(&Self, hir::FunctionId) -> CallKind,
+    ) {
+        for callee in candidates {
+            if self.gcx.hir.function(callee).parameters.len() == n_args {
+                self.push(callee, kind(self, callee), span);
+            }
+        }
+    }
+
+    /// Returns the kind of a call to `callee` that is not a message call.
+    fn internal_kind(&self, callee: hir::FunctionId) -> CallKind {
+        match self.gcx.hir.function(callee).contract {
+            Some(c)
+                if Some(c) != self.func.contract && self.gcx.hir.contract(c).kind.is_library() =>
+            {
+                CallKind::Library
+            }
+            _ => CallKind::Internal,
+        }
+    }
+
+    /// Records the calls to the functions that `callee` resolves to.
+    fn record_call(&mut self, callee: &'gcx hir::Expr<'gcx>, n_args: usize, span: Span) {
+        let gcx = self.gcx;
+        match callee.kind {
+            hir::ExprKind::CallOptions(callee, _) | hir::ExprKind::Tuple(&[Some(callee)]) => {
+                self.record_call(callee, n_args, span)
+            }
+            hir::ExprKind::New(hir::Type {
+                kind: hir::TypeKind::Custom(hir::ItemId::Contract(id)),
+                ..
+            }) => {
+                if let Some(ctor) = gcx.hir.contract(id).ctor {
+                    self.push(ctor, CallKind::External, span);
+                }
+            }
+            hir::ExprKind::Ident(res) => {
+                let candidates = res
+                    .iter()
+                    .filter_map(|res| match *res {
+                        Res::Item(hir::ItemId::Function(id)) => Some(id),
+                        _ => None,
+                    })
+                    .collect::<SmallVec<[_; 4]>>();
+                // Overridden functions of the base contracts are in scope too, so only keep the
+                // most derived function out of the ones with the same parameter types.
+                let contract = self.func.contract;
+                let rank = |f: hir::FunctionId| {
+                    let base = gcx.hir.function(f).contract?;
+                    gcx.hir.contract(contract?).linearized_bases.iter().position(|&b| b == base)
+                };
+                let is_overridden = |f: hir::FunctionId| {
+                    candidates.iter().any(|&other| {
+                        other != f
+                            && rank(other) < rank(f)
+                            && gcx.item_parameter_types(other) == gcx.item_parameter_types(f)
+                    })
+                };
+                let candidates = candidates
+                    .iter()
+                    .copied()
+                    .filter(|&f| !is_overridden(f))
+                    .collect::<SmallVec<[_; 4]>>();
+                self.push_candidates(candidates, n_args, span, Self::internal_kind);
+            }
+            hir::ExprKind::Member(base, member) => {
+                let functions_named = |c: hir::ContractId| {
+                    gcx.hir.contract(c).functions().filter(move |&f| {
+                        gcx.hir.function(f).name.is_some_and(|name| name.name == member.name)
+                    })
+                };
+                match base.kind {
+                    // Internal calls to library functions and to functions of base contracts.
+                    hir::ExprKind::Ident(&[Res::Item(hir::ItemId::Contract(id))]) => {
+                        self.push_candidates(
+                            functions_named(id),
+                            n_args,
+                            span,
+                            Self::internal_kind,
+                        );
+                    }
+                    hir::ExprKind::Ident(&[Res::Builtin(Builtin::Super)]) => {
+                        let Some(contract) = self.func.contract else { return };
+                        let bases = &gcx.hir.contract(contract).linearized_bases[1..];
+                        let Some(base) = bases.iter().copied().find(|&base| {
+                            functions_named(base)
+                                .any(|f| gcx.hir.function(f).parameters.len() == n_args)
+                        }) else {
+                            return;
+                        };
+                        self.push_candidates(functions_named(base), n_args, span, |_, _| {
+                            CallKind::Internal
+                        });
+                    }
+                    // Message calls, and calls to functions attached with `using for`.
+                    _ => {
+                        let Some(ty) = typeck::expr_ty(gcx, self.func, base) else { return };
+                        let Some(members) = typeck::members(gcx, self.func, ty) else { return };
+                        for m in members {
+                            let Some(Res::Item(hir::ItemId::Function(id))) = m.res else {
+                                continue;
+                            };
+                            // The type of attached functions doesn't include the first parameter.
+                            if m.name != member.name
+                                || m.ty.parameters().is_none_or(|params| params.len() != n_args)
+                            {
+                                continue;
+                            }
+                            let is_message_call = gcx
+                                .hir
+                                .function(id)
+                                .contract
+                                .is_some_and(|c| !gcx.hir.contract(c).kind.is_library());
+                            let kind = if is_message_call {
+                                CallKind::External
+                            } else {
+                                self.internal_kind(id)
+                            };
+                            self.push(id, kind, span);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_block(&mut self, block: hir::Block<'gcx>) {
+        for stmt in block {
+            self.visit_stmt(stmt);
+        }
+    }
+
+    fn visit_stmt(&mut self, stmt: &'gcx hir::Stmt<'gcx>) {
+        match stmt.kind {
+            hir::StmtKind::DeclSingle(id) => {
+                if let Some(init) = self.gcx.hir.variable(id).initializer {
+                    self.visit_expr(init);
+                }
+            }
+            hir::StmtKind::DeclMulti(_, expr) | hir::StmtKind::Expr(expr) => self.visit_expr(expr),
+            hir::StmtKind::Block(block)
+            | hir::StmtKind::UncheckedBlock(block)
+            | hir::StmtKind::Loop(block, _) => self.visit_block(block),
+            hir::StmtKind::Emit(_, ref args) | hir::StmtKind::Revert(_, ref args) => {
+                self.visit_args(args)
+            }
+            hir::StmtKind::Return(expr) => {
+                if let Some(expr) = expr {
+                    self.visit_expr(expr);
+                }
+            }
+            hir::StmtKind::If(cond, then, else_) => {
+                self.visit_expr(cond);
+                self.visit_stmt(then);
+                if let Some(else_) = else_ {
+                    self.visit_stmt(else_);
+                }
+            }
+            hir::StmtKind::Try(try_) => {
+                self.visit_expr(&try_.expr);
+                self.visit_block(try_.block);
+                for catch in try_.catch {
+                    self.visit_block(catch.block);
+                }
+            }
+            hir::StmtKind::Assembly(_)
+            | hir::StmtKind::Break
+            | hir::StmtKind::Continue
+            | hir::StmtKind::Placeholder
+            | hir::StmtKind::Err(_) => {}
+        }
+    }
+
+    fn visit_args(&mut self, args: &'gcx CallArgs<'gcx>) {
+        for expr in args.exprs() {
+            self.visit_expr(expr);
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &'gcx hir::Expr<'gcx>) {
+        match expr.kind {
+            hir::ExprKind::Array(exprs) => {
+                for expr in exprs {
+                    self.visit_expr(expr);
+                }
+            }
+            hir::ExprKind::Assign(lhs, _, rhs) | hir::ExprKind::Binary(lhs, _, rhs) => {
+                self.visit_expr(lhs);
+                self.visit_expr(rhs);
+            }
+            hir::ExprKind::Call(callee, ref args) => {
+                self.record_call(callee, args.len(), expr.span);
+                self.visit_expr(callee);
+                self.visit_args(args);
+            }
+            hir::ExprKind::CallOptions(callee, options) => {
+                self.visit_expr(callee);
+                for option in options {
+                    self.visit_expr(&option.value);
+                }
+            }
+            hir::ExprKind::Delete(expr)
+            | hir::ExprKind::Member(expr, _)
+            | hir::ExprKind::Payable(expr)
+            | hir::ExprKind::Unary(_, expr) => self.visit_expr(expr),
+            hir::ExprKind::Index(expr, index) => {
+                self.visit_expr(expr);
+                if let Some(index) = index {
+                    self.visit_expr(index);
+                }
+            }
+            hir::ExprKind::Slice(expr, start, end) => {
+                self.visit_expr(expr);
+                for expr in [start, end].into_iter().flatten() {
+                    self.visit_expr(expr);
+                }
+            }
+            hir::ExprKind::Ternary(cond, then, else_) => {
+                self.visit_expr(cond);
+                self.visit_expr(then);
+                self.visit_expr(else_);
+            }
+            hir::ExprKind::Tuple(exprs) => {
+                for expr in exprs.iter().flatten() {
+                    self.visit_expr(expr);
+                }
+            }
+            hir::ExprKind::Ident(_)
+            | hir::ExprKind::Lit(_)
+            | hir::ExprKind::New(_)
+            | hir::ExprKind::TypeCall(_)
+            | hir::ExprKind::Type(_)
+            | hir::ExprKind::Err(_) => {}
+        }
+    }
+}
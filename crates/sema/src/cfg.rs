@@ -0,0 +1,366 @@
+//! Control-flow graphs of function bodies.
+//!
+//! See [`Cfg`].
+
+use crate::{
+    builtins::Builtin,
+    hir::{self, Res},
+};
+use solar_data_structures::{
+    index::{IndexSlice, IndexVec},
+    newtype_index, BumpExt,
+};
+use solar_interface::Span;
+use std::fmt;
+
+newtype_index! {
+    /// A [`BasicBlock`] ID.
+    pub struct BlockId;
+}
+
+/// The control-flow graph of a function or modifier body.
+///
+/// Statements that don't affect control flow are grouped into [basic blocks](BasicBlock), which
+/// are connected by their [terminators](Terminator). Blocks that follow a terminator in the source,
+/// like the statements after a `return`, are kept in the graph, but don't have any predecessors.
+///
+/// Control flow inside expressions, like short-circuiting operators and reverting calls in
+/// subexpressions, is not represented.
+///
+/// Built by [`Gcx::function_cfg`](crate::ty::Gcx::function_cfg).
+#[derive(Clone, Copy)]
+pub struct Cfg<'hir> {
+    blocks: &'hir IndexSlice<BlockId, [BasicBlock<'hir>]>,
+}
+
+impl fmt::Debug for Cfg<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.blocks.iter_enumerated()).finish()
+    }
+}
+
+impl<'hir> Cfg<'hir> {
+    /// The ID of the entry block.
+    pub const ENTRY: BlockId = BlockId::new(0);
+
+    /// Builds the control-flow graph of the given body, allocating it in `bump`.
+    pub fn build(bump: &'hir bumpalo::Bump, body: hir::Block<'hir>) -> Self {
+        let mut builder =
+            CfgBuilder { bump, blocks: IndexVec::new(), current: Self::ENTRY, loops: Vec::new() };
+        builder.current = builder.new_block();
+        builder.lower_block(body);
+        builder.blocks[builder.current].terminator = Some(Terminator::Return(None));
+        let blocks = builder.blocks.into_iter().map(|block| BasicBlock {
+            stmts: bump.alloc_vec(block.stmts),
+            span: block.span,
+            terminator: block.terminator.expect("unterminated block"),
+        });
+        Self { blocks: IndexSlice::from_slice(bump.alloc_from_iter(blocks)) }
+    }
+
+    /// Returns the basic blocks of the graph.
+    pub fn blocks(&self) -> &'hir IndexSlice<BlockId, [BasicBlock<'hir>]> {
+        self.blocks
+    }
+
+    /// Returns the basic block with the given ID.
+    #[inline]
+    #[track_caller]
+    pub fn block(&self, id: BlockId) -> &'hir BasicBlock<'hir> {
+        &self.blocks[id]
+    }
+
+    /// Returns, for each block, whether it is reachable from the entry block.
+    pub fn reachable_blocks(&self) -> IndexVec<BlockId, bool> {
+        let mut reachable = IndexVec::from_elem_n(false, self.blocks.len());
+        let mut stack = vec![Self::ENTRY];
+        while let Some(id) = stack.pop() {
+            if std::mem::replace(&mut reachable[id], true) {
+                continue;
+            }
+            stack.extend(self.blocks[id].terminator.successors().filter(|&s| !reachable[s]));
+        }
+        reachable
+    }
+
+    /// Returns the predecessors of each block.
+    pub fn predecessors(&self) -> IndexVec<BlockId, Vec<BlockId>> {
+        let mut predecessors = IndexVec::from_elem_n(Vec::new(), self.blocks.len());
+        for (id, block) in self.blocks.iter_enumerated() {
+            for successor in block.terminator.successors() {
+                predecessors[successor].push(id);
+            }
+        }
+        predecessors
+    }
+}
+
+/// A basic block: a sequence of statements that are executed in order, followed by a
+/// [`Terminator`].
+#[derive(Debug)]
+pub struct BasicBlock<'hir> {
+    /// The statements that don't affect control flow, like expressions and variable declarations.
+    ///
+    /// Never contains blocks, loops, `if`, `try`, `return`, `break`, `continue`, or `revert`
+    /// statements.
+    pub stmts: &'hir [&'hir hir::Stmt<'hir>],
+    /// The smallest span covering the statements that start in this block, including the control
+    /// flow statements and the conditions evaluated by the terminator. Dummy if there are none.
+    pub span: Span,
+    /// How control leaves the block.
+    pub terminator: Terminator<'hir>,
+}
+
+/// How control leaves a [`BasicBlock`].
+#[derive(Clone, Copy, Debug)]
+pub enum Terminator<'hir> {
+    /// Jumps to the given block.
+    Goto(BlockId),
+    /// Jumps to `then` if `cond` is `true`, and to `else_` otherwise.
+    Branch { cond: &'hir hir::Expr<'hir>, then: BlockId, else_: BlockId },
+    /// A `try` statement. Jumps to the first target if the external call succeeds, and to one of
+    /// the others, the catch clauses in source order, otherwise.
+    Try { stmt: &'hir hir::StmtTry<'hir>, targets: &'hir [BlockId] },
+    /// Returns from the function with the given value, or with the current values of the return
+    /// variables. This also terminates the last block of the body.
+    Return(Option<&'hir hir::Expr<'hir>>),
+    /// Reverts the execution, with a `revert` statement or a call to the `revert` builtin.
+    Revert,
+}
+
+impl Terminator<'_> {
+    /// Returns the blocks that control can jump to.
+    pub fn successors(&self) -> impl Iterator<Item = BlockId> + '_ {
+        let (first, second, rest) = match *self {
+            Self::Goto(target) => (Some(target), None, &[][..]),
+            Self::Branch { then, else_, .. } => (Some(then), Some(else_), &[][..]),
+            Self::Try { targets, .. } => (None, None, targets),
+            Self::Return(_) | Self::Revert => (None, None, &[][..]),
+        };
+        first.into_iter().chain(second).chain(rest.iter().copied())
+    }
+}
+
+/// A basic block that is being built.
+#[derive(Default)]
+struct BlockBuilder<'hir> {
+    stmts: Vec<&'hir hir::Stmt<'hir>>,
+    span: Span,
+    terminator: Option<Terminator<'hir>>,
+}
+
+/// The targets of `break` and `continue` statements in a loop.
+#[derive(Clone, Copy)]
+struct LoopTargets {
+    break_: BlockId,
+    continue_: BlockId,
+}
+
+struct CfgBuilder<'hir> {
+    bump: &'hir bumpalo::Bump,
+    blocks: IndexVec<BlockId, BlockBuilder<'hir>>,
+    /// The block that statements are added to.
+    current: BlockId,
+    loops: Vec<LoopTargets>,
+}
+
+impl<'hir> CfgBuilder<'hir> {
+    fn new_block(&mut self) -> BlockId {
+        self.blocks.push(BlockBuilder::default())
+    }
+
+    /// Extends the span of the current block to cover `span`.
+    fn add_span(&mut self, span: Span) {
+        let block = &mut self.blocks[self.current];
+        block.span = if block.span.is_dummy() { span } else { block.span.to(span) };
+    }
+
+    /// Terminates the current block, and continues in a new block without predecessors.
+    fn terminate(&mut self, terminator: Terminator<'hir>) {
+        let next = self.new_block();
+        self.goto(terminator, next);
+    }
+
+    /// Terminates the current block, and continues in `next`.
+    fn goto(&mut self, terminator: Terminator<'hir>, next: BlockId) {
+        let prev = self.blocks[self.current].terminator.replace(terminator);
+        debug_assert!(prev.is_none(), "block {:?} terminated twice", self.current);
+        self.current = next;
+    }
+
+    fn lower_block(&mut self, block: hir::Block<'hir>) {
+        for stmt in block {
+            self.lower_stmt(stmt);
+        }
+    }
+
+    fn lower_stmt(&mut self, stmt: &'hir hir::Stmt<'hir>) {
+        match stmt.kind {
+            hir::StmtKind::Block(block) | hir::StmtKind::UncheckedBlock(block) => {
+                self.lower_block(block)
+            }
+            hir::StmtKind::Loop(block, source) => {
+                self.add_span(stmt.span);
+                self.lower_loop(stmt.span, block, source);
+            }
+            hir::StmtKind::If(cond, then, else_) => {
+                self.add_span(stmt.span);
+                let then_block = self.new_block();
+                let else_block = self.new_block();
+                let join = if else_.is_some() { self.new_block() } else { else_block };
+                let branch = Terminator::Branch { cond, then: then_block, else_: else_block };
+                self.goto(branch, then_block);
+                self.lower_stmt(then);
+                self.goto(Terminator::Goto(join), else_block);
+                if let Some(else_) = else_ {
+                    self.lower_stmt(else_);
+                    self.goto(Terminator::Goto(join), join);
+                }
+            }
+            hir::StmtKind::Try(try_) => {
+                self.add_span(stmt.span);
+                let blocks = std::iter::once(try_.block).chain(try_.catch.iter().map(|c| c.block));
+                let targets = blocks.clone().map(|_| self.new_block()).collect::<Vec<_>>();
+                let join = self.new_block();
+                let targets = &*self.bump.alloc_vec(targets);
+                self.goto(Terminator::Try { stmt: try_, targets }, targets[0]);
+                for (&target, block) in targets.iter().zip(blocks) {
+                    self.current = target;
+                    self.lower_block(block);
+                    self.goto(Terminator::Goto(join), join);
+                }
+            }
+            hir::StmtKind::Return(expr) => {
+                self.add_span(stmt.span);
+                self.terminate(Terminator::Return(expr));
+            }
+            hir::StmtKind::Revert(..) => {
+                self.add_span(stmt.span);
+                self.terminate(Terminator::Revert);
+            }
+            hir::StmtKind::Expr(expr) if is_revert_call(expr) => {
+                self.add_span(stmt.span);
+                self.terminate(Terminator::Revert);
+            }
+            hir::StmtKind::Break | hir::StmtKind::Continue => {
+                self.add_span(stmt.span);
+                // `break` and `continue` outside of loops are reported during AST validation.
+                if let Some(&targets) = self.loops.last() {
+                    let target = if matches!(stmt.kind, hir::StmtKind::Break) {
+                        targets.break_
+                    } else {
+                        targets.continue_
+                    };
+                    self.terminate(Terminator::Goto(target));
+                }
+            }
+            hir::StmtKind::Assembly(_)
+            | hir::StmtKind::DeclSingle(_)
+            | hir::StmtKind::DeclMulti(..)
+            | hir::StmtKind::Emit(..)
+            | hir::StmtKind::Expr(_)
+            | hir::StmtKind::Placeholder
+            | hir::StmtKind::Err(_) => {
+                self.add_span(stmt.span);
+                self.blocks[self.current].stmts.push(stmt);
+            }
+        }
+    }
+
+    /// Lowers a loop, recovering the condition and the loop expression of the desugared `for`,
+    /// `while`, and `do while` loops, so that `continue` jumps to them.
+    fn lower_loop(&mut self, span: Span, block: hir::Block<'hir>, source: hir::LoopSource) {
+        // Other loops are infinite loops over `block`.
+        let LoopParts { cond, body, next } = LoopParts::split(span, block, source)
+            .unwrap_or(LoopParts { cond: None, body: block, next: None });
+        let is_do_while = source == hir::LoopSource::DoWhile && cond.is_some();
+
+        // The start of the loop: the condition, or the body of `do while` loops.
+        let header = self.new_block();
+        let exit = self.new_block();
+        let cond_block = if is_do_while { self.new_block() } else { header };
+        let next_block = if next.is_some() { self.new_block() } else { header };
+        let continue_ = if is_do_while { cond_block } else { next_block };
+
+        self.goto(Terminator::Goto(header), header);
+        if let Some(cond) = cond {
+            let then = if is_do_while { header } else { self.new_block() };
+            self.current = cond_block;
+            self.add_span(cond.span);
+            self.goto(Terminator::Branch { cond, then, else_: exit }, then);
+        }
+
+        self.loops.push(LoopTargets { break_: exit, continue_ });
+        self.lower_block(body);
+        self.loops.pop();
+        self.goto(Terminator::Goto(continue_), next_block);
+
+        if let Some(next) = next {
+            self.lower_stmt(next);
+            self.goto(Terminator::Goto(header), exit);
+        }
+        self.current = exit;
+    }
+}
+
+/// The parts of a desugared loop. See `lower_loop_stmt` in AST lowering for the desugaring.
+struct LoopParts<'hir> {
+    cond: Option<&'hir hir::Expr<'hir>>,
+    body: hir::Block<'hir>,
+    /// The loop expression of a `for` loop, as a statement.
+    next: Option<&'hir hir::Stmt<'hir>>,
+}
+
+impl<'hir> LoopParts<'hir> {
+    /// Splits the block of a desugared loop.
+    ///
+    /// `span` is the span of the loop statement, which is also the span of the `break` statement
+    /// that is added when desugaring the condition.
+    fn split(span: Span, block: hir::Block<'hir>, source: hir::LoopSource) -> Option<Self> {
+        let is_break = |stmt: Option<&hir::Stmt<'_>>| {
+            stmt.is_some_and(|s| matches!(s.kind, hir::StmtKind::Break) && s.span == span)
+        };
+        match (source, block) {
+            // loop { if (<cond>) <stmt> else break; }
+            (
+                hir::LoopSource::While,
+                [hir::Stmt { kind: hir::StmtKind::If(cond, stmt, else_), .. }],
+            ) if is_break(*else_) => {
+                Some(Self { cond: Some(*cond), body: std::slice::from_ref(*stmt), next: None })
+            }
+
+            // loop { { <stmt> } if (<cond>) continue; else break; }
+            (
+                hir::LoopSource::DoWhile,
+                [stmt, hir::Stmt { kind: hir::StmtKind::If(cond, _, else_), .. }],
+            ) if is_break(*else_) => {
+                Some(Self { cond: Some(*cond), body: std::slice::from_ref(stmt), next: None })
+            }
+
+            // loop { if (<cond>) { { <body> } <next>; } else break; }
+            (hir::LoopSource::For, [stmt]) => {
+                let (cond, stmt) = match stmt.kind {
+                    hir::StmtKind::If(cond, then, else_) if is_break(else_) => (Some(cond), then),
+                    _ => (None, stmt),
+                };
+                // The block that is added for the loop expression has the span of the body.
+                let (body, next) = match stmt.kind {
+                    hir::StmtKind::Block(
+                        [body, next @ hir::Stmt { kind: hir::StmtKind::Expr(_), .. }],
+                    ) if body.span == stmt.span => (body, Some(next)),
+                    _ => (stmt, None),
+                };
+                Some(Self { cond, body: std::slice::from_ref(body), next })
+            }
+
+            _ => None,
+        }
+    }
+}
+
+/// Returns `true` if the given expression is a call to the `revert` builtin.
+fn is_revert_call(expr: &hir::Expr<'_>) -> bool {
+    let hir::ExprKind::Call(callee, _) = &expr.kind else { return false };
+    let hir::ExprKind::Ident(res) = callee.kind else { return false };
+    res.iter().any(|res| matches!(res, Res::Builtin(Builtin::Revert | Builtin::RevertMsg)))
+}
@@ -0,0 +1,40 @@
+//! Code generation backends.
+//!
+//! Code generation is implemented outside of this crate, by `solar-codegen`. The backend is set on
+//! the [`ParsingContext`](crate::ParsingContext), and is used to generate the code of the
+//! contracts once they are analyzed.
+
+use crate::{hir, ty::Gcx};
+use solar_data_structures::map::FxHashMap;
+use solar_interface::{BytePos, Result};
+use std::fmt;
+
+/// A code generation backend.
+pub trait CodegenBackend: fmt::Debug + Sync {
+    /// Generates the Yul IR of the given contract.
+    fn contract_ir(&self, gcx: Gcx<'_>, id: hir::ContractId) -> Result<String>;
+
+    /// Compiles the given contract to EVM bytecode.
+    ///
+    /// `source_ids` maps the start position of each source file to its source ID, and is used to
+    /// build the source maps.
+    fn contract_bytecode(
+        &self,
+        gcx: Gcx<'_>,
+        id: hir::ContractId,
+        source_ids: &FxHashMap<BytePos, usize>,
+    ) -> Result<ContractBytecode>;
+}
+
+/// The bytecode of a contract, generated by a [`CodegenBackend`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ContractBytecode {
+    /// The hex-encoded creation code.
+    pub bin: String,
+    /// The hex-encoded runtime code.
+    pub bin_runtime: String,
+    /// The source map of the creation code.
+    pub srcmap: String,
+    /// The source map of the runtime code.
+    pub srcmap_runtime: String,
+}
@@ -0,0 +1,348 @@
+//! Library entry point for compiling sources in-process.
+
+use crate::{codegen::CodegenBackend, CombinedJson, CombinedJsonContract, ParsingContext};
+use serde::Serialize;
+use solar_interface::{
+    config::{CompilerOutput, CompilerStage, EvmVersion, MetadataHash},
+    diagnostics::{DiagCtxt, Diagnostic, DiagnosticId, Emitter, HumanBufferEmitter},
+    source_map::ImportMap,
+    ColorChoice, Result, Session, SourceMap,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    num::NonZeroUsize,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+/// Compiles sources in-process, collecting the diagnostics and the compiler outputs instead of
+/// printing them.
+///
+/// Created with [`Compiler::builder`].
+///
+/// # Examples
+///
+/// ```
+/// use solar_sema::{interface::config::CompilerOutput, Compiler};
+///
+/// let compiled = Compiler::builder()
+///     .source("Counter.sol", "contract Counter { uint256 public count; }")
+///     .emit(CompilerOutput::Abi)
+///     .compile();
+/// assert!(!compiled.has_errors(), "{}", compiled.render_diagnostics());
+///
+/// let output = compiled.output.unwrap();
+/// let (name, contract) = output.contracts.iter().next().unwrap();
+/// assert!(name.ends_with(":Counter"));
+/// assert_eq!(contract.abi.as_ref().unwrap().len(), 1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Compiler {
+    sources: Vec<(PathBuf, String)>,
+    files: Vec<PathBuf>,
+    import_paths: Vec<PathBuf>,
+    import_maps: Vec<ImportMap>,
+    node_modules: bool,
+    evm_version: EvmVersion,
+    metadata_hash: MetadataHash,
+    emit: BTreeSet<CompilerOutput>,
+    stop_after: Option<CompilerStage>,
+    jobs: NonZeroUsize,
+    codegen: Option<&'static dyn CodegenBackend>,
+}
+
+/// Builder for [`Compiler`].
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct CompilerBuilder(Compiler);
+
+impl Default for CompilerBuilder {
+    fn default() -> Self {
+        Self(Compiler {
+            sources: Vec::new(),
+            files: Vec::new(),
+            import_paths: Vec::new(),
+            import_maps: Vec::new(),
+            node_modules: false,
+            evm_version: EvmVersion::default(),
+            metadata_hash: MetadataHash::default(),
+            emit: BTreeSet::new(),
+            stop_after: None,
+            jobs: NonZeroUsize::MIN,
+            codegen: None,
+        })
+    }
+}
+
+impl CompilerBuilder {
+    /// Adds an in-memory source file.
+    ///
+    /// In-memory sources take precedence over the files on disk with the same path when resolving
+    /// imports.
+    pub fn source(mut self, path: impl Into<PathBuf>, src: impl Into<String>) -> Self {
+        self.0.sources.push((path.into(), src.into()));
+        self
+    }
+
+    /// Adds a source file to be read from disk.
+    pub fn file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.0.files.push(path.into());
+        self
+    }
+
+    /// Adds a directory to search for imported files.
+    pub fn import_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.0.import_paths.push(path.into());
+        self
+    }
+
+    /// Adds an import map, AKA remapping.
+    pub fn import_map(mut self, map: ImportMap) -> Self {
+        self.0.import_maps.push(map);
+        self
+    }
+
+    /// Sets whether to resolve imports in `node_modules` directories. See
+    /// [`FileResolver::set_node_modules`](solar_interface::source_map::FileResolver::set_node_modules).
+    pub fn node_modules(mut self, enabled: bool) -> Self {
+        self.0.node_modules = enabled;
+        self
+    }
+
+    /// Sets the EVM version.
+    pub fn evm_version(mut self, evm_version: EvmVersion) -> Self {
+        self.0.evm_version = evm_version;
+        self
+    }
+
+    /// Sets the hash of the contract metadata to append to the runtime bytecode.
+    pub fn metadata_hash(mut self, metadata_hash: MetadataHash) -> Self {
+        self.0.metadata_hash = metadata_hash;
+        self
+    }
+
+    /// Requests the given output to be included in [`Compiled::output`].
+    ///
+    /// The compact JSON ASTs are requested with [`CompilerOutput::Ast`].
+    pub fn emit(mut self, output: CompilerOutput) -> Self {
+        self.0.emit.insert(output);
+        self
+    }
+
+    /// Stops compilation after the given stage.
+    pub fn stop_after(mut self, stage: CompilerStage) -> Self {
+        self.0.stop_after = Some(stage);
+        self
+    }
+
+    /// Sets the number of threads to use. Zero specifies the number of logical cores.
+    ///
+    /// Defaults to 1.
+    pub fn jobs(mut self, jobs: usize) -> Self {
+        self.0.jobs = NonZeroUsize::new(jobs)
+            .unwrap_or_else(|| std::thread::available_parallelism().unwrap_or(NonZeroUsize::MIN));
+        self
+    }
+
+    /// Sets the backend used to generate the Yul IR and the bytecode of the contracts.
+    ///
+    /// Code generation outputs can only be requested with a backend, such as `solar-codegen`'s
+    /// `EvmBackend`.
+    pub fn codegen_backend(mut self, backend: &'static dyn CodegenBackend) -> Self {
+        self.0.codegen = Some(backend);
+        self
+    }
+
+    /// Builds the compiler.
+    pub fn build(self) -> Compiler {
+        self.0
+    }
+
+    /// Builds the compiler and compiles the sources. See [`Compiler::compile`].
+    pub fn compile(self) -> Compiled {
+        self.build().compile()
+    }
+}
+
+impl Compiler {
+    /// Creates a new compiler builder.
+    #[inline]
+    pub fn builder() -> CompilerBuilder {
+        CompilerBuilder::default()
+    }
+
+    /// Parses and semantically analyzes the sources, recursing into imports.
+    ///
+    /// Each call starts a new compilation from scratch.
+    pub fn compile(&self) -> Compiled {
+        let emitter = CollectEmitter::default();
+        let diagnostics = emitter.0.clone();
+        let source_map = Arc::new(SourceMap::empty());
+        let mut sess = Session::new(DiagCtxt::new(Box::new(emitter)), source_map.clone());
+        sess.evm_version = self.evm_version;
+        sess.metadata_hash = self.metadata_hash;
+        sess.emit = self.emit.clone();
+        sess.stop_after = self.stop_after;
+        sess.jobs = self.jobs;
+
+        let mut output = CombinedJson::new();
+        let result = sess.enter(|| -> Result<()> {
+            let mut pcx = ParsingContext::new(&sess);
+            pcx.codegen = self.codegen;
+            for map in &self.import_maps {
+                pcx.file_resolver.insert_import_map(map.clone());
+            }
+            for path in &self.import_paths {
+                pcx.file_resolver.add_import_path(path.clone());
+            }
+            pcx.file_resolver.set_node_modules(self.node_modules);
+            for (path, src) in &self.sources {
+                let file =
+                    pcx.file_resolver.add_virtual_file(path.clone(), src.clone()).map_err(|e| {
+                        let msg = format!("failed to load {}: {e}", path.display());
+                        sess.dcx.err(msg).emit()
+                    })?;
+                pcx.add_file(file);
+            }
+            pcx.load_files(&self.files)?;
+            pcx.parse_and_resolve_output(&mut output)
+        });
+
+        let diagnostics = std::mem::take(&mut *diagnostics.lock().unwrap());
+        Compiled { diagnostics, output: result.ok().map(|()| output), source_map }
+    }
+}
+
+/// The result of [`Compiler::compile`].
+pub struct Compiled {
+    /// The emitted diagnostics, in emission order.
+    pub diagnostics: Vec<Diagnostic>,
+    /// The requested outputs, or `None` if compilation failed.
+    pub output: Option<CombinedJson>,
+    /// The source map containing all the loaded sources, used to resolve the spans in
+    /// `diagnostics`.
+    pub source_map: Arc<SourceMap>,
+}
+
+impl Compiled {
+    /// Returns `true` if compilation failed.
+    pub fn has_errors(&self) -> bool {
+        self.output.is_none() || self.diagnostics.iter().any(Diagnostic::is_error)
+    }
+
+    /// Returns an iterator over the emitted errors.
+    pub fn errors(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.iter().filter(|diagnostic| diagnostic.is_error())
+    }
+
+    /// Renders the emitted diagnostics in the human-readable format, without colors.
+    pub fn render_diagnostics(&self) -> String {
+        let mut emitter =
+            HumanBufferEmitter::new(ColorChoice::Never).source_map(Some(self.source_map.clone()));
+        for diagnostic in &self.diagnostics {
+            emitter.emit_diagnostic(diagnostic);
+        }
+        emitter.buffer().to_string()
+    }
+
+    /// Converts the compilation into a [`CompilationResult`], resolving the locations of the
+    /// diagnostics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use solar_sema::{interface::config::CompilerOutput, Compiler};
+    ///
+    /// let result = Compiler::builder()
+    ///     .source("A.sol", "import \"B.sol\"; contract A is B {}")
+    ///     .source("B.sol", "contract B { function f() public {} }")
+    ///     .emit(CompilerOutput::Abi)
+    ///     .compile()
+    ///     .into_result();
+    /// assert!(result.success);
+    /// let (_, a) = result.contracts.iter().find(|(name, _)| name.ends_with(":A")).unwrap();
+    /// assert!(a.abi.is_some());
+    /// assert!(result.dependencies["A.sol"].contains("B.sol"));
+    /// let json = serde_json::to_string(&result).unwrap();
+    /// ```
+    pub fn into_result(self) -> CompilationResult {
+        let success = !self.has_errors();
+        let mut diagnostics = BTreeMap::<String, Vec<_>>::new();
+        for diagnostic in &self.diagnostics {
+            let (file, diagnostic) = CompilationDiagnostic::new(diagnostic, &self.source_map);
+            diagnostics.entry(file).or_default().push(diagnostic);
+        }
+        let (contracts, dependencies) = match self.output {
+            Some(output) => (output.contracts, output.dependencies),
+            None => Default::default(),
+        };
+        CompilationResult { success, diagnostics, dependencies, contracts }
+    }
+}
+
+/// The result of a compilation, with typed artifacts and diagnostics. Serializable to JSON.
+///
+/// Created with [`Compiled::into_result`].
+#[derive(Serialize)]
+pub struct CompilationResult {
+    /// Whether the compilation succeeded without errors.
+    pub success: bool,
+    /// The emitted diagnostics, grouped by the file of their primary span and in emission order.
+    /// Diagnostics that are not associated with a file are keyed by the empty string.
+    pub diagnostics: BTreeMap<String, Vec<CompilationDiagnostic>>,
+    /// The files directly imported by each source file, keyed by file name.
+    pub dependencies: BTreeMap<String, BTreeSet<String>>,
+    /// The requested outputs of each contract, keyed by fully qualified name.
+    pub contracts: BTreeMap<String, CombinedJsonContract>,
+}
+
+/// A diagnostic in a [`CompilationResult`].
+#[derive(Clone, Debug, Serialize)]
+pub struct CompilationDiagnostic {
+    /// The level of the diagnostic, like `error` or `warning`.
+    pub level: &'static str,
+    /// The diagnostic code, if any.
+    pub code: Option<String>,
+    /// The main message.
+    pub message: String,
+    /// The 1-based line of the start of the primary span, if any.
+    pub line: Option<usize>,
+    /// The 1-based column of the start of the primary span, if any.
+    pub column: Option<usize>,
+    /// The diagnostic rendered in the human-readable format, without colors.
+    pub rendered: String,
+}
+
+impl CompilationDiagnostic {
+    /// Returns the diagnostic along with the name of the file of its primary span.
+    fn new(diagnostic: &Diagnostic, source_map: &Arc<SourceMap>) -> (String, Self) {
+        let loc = diagnostic
+            .span
+            .primary_span()
+            .filter(|span| !span.is_dummy())
+            .map(|span| source_map.lookup_char_pos(span.lo()));
+        let mut emitter =
+            HumanBufferEmitter::new(ColorChoice::Never).source_map(Some(source_map.clone()));
+        emitter.emit_diagnostic(diagnostic);
+        let file = loc.as_ref().map(|loc| loc.file.name.display().to_string()).unwrap_or_default();
+        let diagnostic = Self {
+            level: diagnostic.level().to_str(),
+            code: diagnostic.code.as_ref().map(DiagnosticId::as_string),
+            message: diagnostic.label().into_owned(),
+            line: loc.as_ref().map(|loc| loc.line),
+            column: loc.as_ref().map(|loc| loc.col.0 + 1),
+            rendered: emitter.buffer().to_string(),
+        };
+        (file, diagnostic)
+    }
+}
+
+/// Emitter that collects all the emitted diagnostics.
+#[derive(Clone, Default)]
+struct CollectEmitter(Arc<Mutex<Vec<Diagnostic>>>);
+
+impl Emitter for CollectEmitter {
+    fn emit_diagnostic(&mut self, diagnostic: &Diagnostic) {
+        self.0.lock().unwrap().push(diagnostic.clone());
+    }
+}
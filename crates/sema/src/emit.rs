@@ -1,41 +1,535 @@
-use crate::ty::Gcx;
+use crate::{
+    ast, call_graph,
+    codegen::CodegenBackend,
+    hir,
+    ty::{DevDoc, Gcx, StorageLayout, UserDoc},
+    AstJsonExporter, ParsedSources,
+};
 use serde::Serialize;
-use solar_interface::config::CompilerOutput;
+use solar_data_structures::map::FxHashMap;
+use solar_interface::{
+    config::{CompilerOutput, CompilerStage},
+    source_map::FileName,
+    BytePos, Session, Span,
+};
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     io::{self, Write},
     path::Path,
 };
 
+/// The compiler output, in the same format as solc's `--combined-json`.
 #[derive(Default, Serialize)]
-struct CombinedJson {
+pub struct CombinedJson {
+    /// The outputs of each contract, keyed by fully qualified name.
     #[serde(skip_serializing_if = "BTreeMap::is_empty")]
-    contracts: BTreeMap<String, CombinedJsonContract>,
-    version: &'static str,
+    pub contracts: BTreeMap<String, CombinedJsonContract>,
+    /// The names of the source files, indexed by source ID. Only emitted along with the ASTs and
+    /// the source maps.
+    #[serde(rename = "sourceList", skip_serializing_if = "Vec::is_empty")]
+    pub source_list: Vec<String>,
+    /// The source IDs of the source files.
+    #[serde(skip)]
+    pub source_ids: SourceIds,
+    /// The outputs of each source file, keyed by file name.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub sources: BTreeMap<String, CombinedJsonSource>,
+    /// The call graph.
+    #[serde(rename = "callGraph", skip_serializing_if = "Option::is_none")]
+    pub call_graph: Option<CallGraph>,
+    /// The call graph, in the Graphviz DOT format.
+    ///
+    /// This is written separately from the rest of the output.
+    #[serde(skip)]
+    pub call_graph_dot: Option<String>,
+    /// The import graph.
+    #[serde(rename = "importGraph", skip_serializing_if = "Option::is_none")]
+    pub import_graph: Option<ImportGraph>,
+    /// The files directly imported by each source file, keyed by file name.
+    ///
+    /// This is always computed, but it is not part of the JSON output.
+    #[serde(skip)]
+    pub dependencies: BTreeMap<String, BTreeSet<String>>,
+    /// The import graph, in the Graphviz DOT format.
+    ///
+    /// This is written separately from the rest of the output.
+    #[serde(skip)]
+    pub import_graph_dot: Option<String>,
+    /// The source code printed back from the ASTs.
+    ///
+    /// This is written separately from the rest of the output.
+    #[serde(skip)]
+    pub ast_pretty: Option<String>,
+    /// The generated interfaces of the contracts.
+    ///
+    /// This is written separately from the rest of the output.
+    #[serde(skip)]
+    pub interfaces: Option<String>,
+    /// The compiler version.
+    pub version: &'static str,
+}
+
+impl CombinedJson {
+    /// Creates a new empty output.
+    pub fn new() -> Self {
+        Self { version: solar_interface::VERSION, ..Default::default() }
+    }
+
+    /// Returns `true` if any of the outputs that are computed from the ASTs, before name
+    /// resolution, was requested.
+    pub(crate) fn has_ast_outputs(&self) -> bool {
+        !self.sources.is_empty()
+            || self.ast_pretty.is_some()
+            || self.import_graph.is_some()
+            || self.import_graph_dot.is_some()
+    }
 }
 
+/// The source IDs of the source files, which identify them in the `src` fields of the JSON ASTs,
+/// in the source maps, and in `sourceList`.
+///
+/// Like in solc, source IDs are the indices of the source files sorted by name, so they only
+/// depend on the set of compiled files and not on the order in which they were parsed.
+#[derive(Clone, Debug, Default)]
+pub struct SourceIds {
+    names: Vec<FileName>,
+    ids: FxHashMap<FileName, usize>,
+}
+
+impl SourceIds {
+    /// Assigns the source IDs of the given source files.
+    pub fn new(names: impl IntoIterator<Item = FileName>) -> Self {
+        let mut names = names.into_iter().collect::<Vec<_>>();
+        names.sort_by_cached_key(|name| name.display().to_string());
+        names.dedup();
+        let ids = names.iter().enumerate().map(|(id, name)| (name.clone(), id)).collect();
+        Self { names, ids }
+    }
+
+    /// Returns the source ID of the given source file.
+    pub fn id(&self, name: &FileName) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    /// Returns the name of the source file with the given source ID.
+    pub fn name(&self, id: usize) -> Option<&FileName> {
+        self.names.get(id)
+    }
+
+    /// Returns the names of the source files, indexed by source ID.
+    pub fn names(&self) -> &[FileName] {
+        &self.names
+    }
+}
+
+/// The outputs of a single contract in [`CombinedJson`].
 #[derive(Default, Serialize)]
-struct CombinedJsonContract {
+pub struct CombinedJsonContract {
+    /// The JSON ABI.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub abi: Option<Vec<alloy_json_abi::AbiItem<'static>>>,
+    /// The function signature hashes, keyed by signature.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hashes: Option<BTreeMap<String, String>>,
+    /// The error signature hashes, keyed by signature. Emitted alongside `hashes`.
+    #[serde(rename = "errorHashes", skip_serializing_if = "Option::is_none")]
+    pub error_hashes: Option<BTreeMap<String, String>>,
+    /// The event topics, keyed by signature. Emitted alongside `hashes`.
+    #[serde(rename = "eventHashes", skip_serializing_if = "Option::is_none")]
+    pub event_hashes: Option<BTreeMap<String, String>>,
+    /// The creation bytecode, hex-encoded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bin: Option<String>,
+    /// The runtime bytecode, hex-encoded.
+    #[serde(rename = "bin-runtime", skip_serializing_if = "Option::is_none")]
+    pub bin_runtime: Option<String>,
+    /// The NatSpec developer documentation.
     #[serde(skip_serializing_if = "Option::is_none")]
-    abi: Option<Abi>,
+    pub devdoc: Option<DevDoc>,
+    /// The Yul intermediate representation.
     #[serde(skip_serializing_if = "Option::is_none")]
-    hashes: Option<Hashes>,
+    pub ir: Option<String>,
+    /// The metadata JSON.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<String>,
+    /// The source map of the creation bytecode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub srcmap: Option<String>,
+    /// The source map of the runtime bytecode.
+    #[serde(rename = "srcmap-runtime", skip_serializing_if = "Option::is_none")]
+    pub srcmap_runtime: Option<String>,
+    /// The storage layout.
+    #[serde(rename = "storage-layout", skip_serializing_if = "Option::is_none")]
+    pub storage_layout: Option<StorageLayout>,
+    /// The NatSpec user documentation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub userdoc: Option<UserDoc>,
+}
+
+/// The outputs of a single source file in [`CombinedJson`].
+#[derive(Serialize)]
+pub struct CombinedJsonSource {
+    /// The compact JSON AST.
+    #[serde(rename = "AST")]
+    pub ast: serde_json::Value,
+    /// The source ID, as used in `src` ranges.
+    pub id: usize,
+}
+
+/// The graph of the calls between functions and modifiers.
+#[derive(Serialize)]
+pub struct CallGraph {
+    /// The functions and modifiers.
+    pub nodes: Vec<CallGraphNode>,
+    /// The calls, grouped by caller and in source order.
+    pub edges: Vec<CallGraphEdge>,
+}
+
+/// A function or modifier in [`CallGraph`].
+#[derive(Serialize)]
+pub struct CallGraphNode {
+    /// The function ID.
+    pub id: usize,
+    /// The name of the function, prefixed by the name of its contract.
+    pub name: String,
+    /// The kind of the function, like `function` or `modifier`.
+    pub kind: &'static str,
+    /// The location of the function, as `file:line:column`.
+    pub location: String,
+}
+
+/// A call in [`CallGraph`].
+#[derive(Serialize)]
+pub struct CallGraphEdge {
+    /// The ID of the calling function.
+    pub from: usize,
+    /// The ID of the called function.
+    pub to: usize,
+    /// The kind of the call, like `internal` or `external`.
+    pub kind: &'static str,
+    /// The location of the call, as `file:line:column`.
+    pub location: String,
+}
+
+/// The graph of the resolved imports between source files.
+#[derive(Serialize)]
+pub struct ImportGraph {
+    /// The source files.
+    pub nodes: Vec<ImportGraphNode>,
+    /// The import directives, in source order.
+    pub edges: Vec<ImportGraphEdge>,
+}
+
+/// A source file in [`ImportGraph`].
+#[derive(Serialize)]
+pub struct ImportGraphNode {
+    /// The source ID, as used in `src` ranges.
+    pub id: usize,
+    /// The source file name.
+    pub name: String,
+}
+
+/// An import directive in [`ImportGraph`].
+#[derive(Serialize)]
+pub struct ImportGraphEdge {
+    /// The ID of the importing source.
+    pub from: usize,
+    /// The ID of the imported source.
+    pub to: usize,
+    /// The source range of the import directive, as `start:length:sourceId`.
+    pub src: String,
+}
+
+/// Returns the IDs of `sources` sorted by source ID.
+///
+/// This is the canonical order of the outputs, which only depends on the names of the source
+/// files and not on the order in which they were resolved and parsed.
+fn canonical_order(sources: &ParsedSources<'_>, source_ids: &SourceIds) -> Vec<hir::SourceId> {
+    let mut order = sources.indices().collect::<Vec<_>>();
+    order.sort_by_key(|&id| source_ids.id(&sources[id].file.name));
+    order
+}
+
+/// Adds the import graph of `sources` to the output, if requested.
+///
+/// Nodes are identified by their source ID, so this must be called after [`emit_asts`].
+pub(crate) fn emit_import_graph(
+    sess: &Session,
+    sources: &ParsedSources<'_>,
+    output: &mut CombinedJson,
+) {
+    let json = sess.do_emit(CompilerOutput::ImportGraph);
+    let dot = sess.do_emit(CompilerOutput::ImportGraphDot);
+    if !json && !dot {
+        return;
+    }
+
+    let ids = sources
+        .iter()
+        .map(|source| output.source_ids.id(&source.file.name).unwrap())
+        .collect::<Vec<_>>();
+    let order = canonical_order(sources, &output.source_ids);
+
+    let nodes = order
+        .iter()
+        .map(|&source_id| ImportGraphNode {
+            id: ids[source_id.index()],
+            name: sources[source_id].file.name.display().to_string(),
+        })
+        .collect::<Vec<_>>();
+    let mut edges = Vec::new();
+    for &source_id in &order {
+        let source = &sources[source_id];
+        let id = ids[source_id.index()];
+        for &(item_id, import_id) in &source.imports {
+            let src = match &source.ast {
+                Some(ast) => {
+                    let span = ast.items[item_id].span;
+                    let start = span.lo().0 - source.file.start_pos.0;
+                    let len = span.hi().0 - span.lo().0;
+                    format!("{start}:{len}:{id}")
+                }
+                None => "-1:-1:-1".to_string(),
+            };
+            edges.push(ImportGraphEdge { from: id, to: ids[import_id.index()], src });
+        }
+    }
+
+    if dot {
+        let mut s = String::from("digraph imports {\n");
+        for node in &nodes {
+            s.push_str(&format!("    {} [label={:?}];\n", node.id, node.name));
+        }
+        for edge in &edges {
+            s.push_str(&format!("    {} -> {} [label={:?}];\n", edge.from, edge.to, edge.src));
+        }
+        s.push_str("}\n");
+        output.import_graph_dot = Some(s);
+    }
+    if json {
+        output.import_graph = Some(ImportGraph { nodes, edges });
+    }
+}
+
+/// Adds the files directly imported by each source file to the output.
+pub(crate) fn emit_dependencies(sources: &ParsedSources<'_>, output: &mut CombinedJson) {
+    for source in sources.iter() {
+        let imports = source
+            .imports
+            .iter()
+            .map(|&(_, import_id)| sources[import_id].file.name.display().to_string())
+            .collect();
+        output.dependencies.insert(source.file.name.display().to_string(), imports);
+    }
+}
+
+/// Assigns the source IDs of `sources`, and adds their compact JSON ASTs to the output, if
+/// requested, along with the list of the source files that the ASTs and the source maps refer to.
+///
+/// This must be called before the ASTs are dropped.
+pub(crate) fn emit_asts(sess: &Session, sources: &ParsedSources<'_>, output: &mut CombinedJson) {
+    output.source_ids = SourceIds::new(sources.iter().map(|source| source.file.name.clone()));
+
+    let ast = sess.emit.contains(&CompilerOutput::Ast);
+    let srcmap = [CompilerOutput::Srcmap, CompilerOutput::SrcmapRuntime]
+        .iter()
+        .any(|output| sess.emit.contains(output));
+    if !ast && !srcmap {
+        return;
+    }
+    output.source_list =
+        output.source_ids.names().iter().map(|name| name.display().to_string()).collect();
+    if !ast {
+        return;
+    }
+    // Node IDs are assigned sequentially, so the sources are exported in canonical order.
+    let mut exporter = AstJsonExporter::new(sess);
+    for source_id in canonical_order(sources, &output.source_ids) {
+        let source = &sources[source_id];
+        let id = output.source_ids.id(&source.file.name).unwrap();
+        if let Some(ast) = exporter.export(id, source) {
+            let name = source.file.name.display().to_string();
+            output.sources.insert(name, CombinedJsonSource { ast, id });
+        }
+    }
+}
+
+/// Adds the source code printed back from the ASTs of `sources` to the output, if requested.
+///
+/// The sources are printed in the order of their source IDs, so this must be called after
+/// [`emit_asts`], and before the ASTs are dropped.
+pub(crate) fn emit_ast_pretty(
+    sess: &Session,
+    sources: &ParsedSources<'_>,
+    output: &mut CombinedJson,
+) {
+    if !sess.do_emit(CompilerOutput::AstPretty) {
+        return;
+    }
+    let mut s = String::new();
+    for source_id in canonical_order(sources, &output.source_ids) {
+        let source = &sources[source_id];
+        let printed = if let Some(ast) = &source.ast {
+            ast::pretty::source_unit_to_string(ast)
+        } else if let Some(object) = &source.yul_object {
+            ast::pretty::yul_object_to_string(object) + "\n"
+        } else {
+            continue;
+        };
+        if !s.is_empty() {
+            s.push('\n');
+        }
+        s.push_str(&format!("// {}\n", source.file.name.display()));
+        s.push_str(&printed);
+    }
+    output.ast_pretty = Some(s);
+}
+
+/// Adds the call graph to the output, if requested.
+fn emit_call_graph(gcx: Gcx<'_>, output: &mut CombinedJson) {
+    let json = gcx.sess.do_emit(CompilerOutput::CallGraph);
+    let dot = gcx.sess.do_emit(CompilerOutput::CallGraphDot);
+    if !json && !dot {
+        return;
+    }
+
+    let location = |span: Span| {
+        let loc = gcx.sess.source_map().lookup_char_pos(span.lo());
+        format!("{}:{}:{}", loc.file.name.display(), loc.line, loc.col.to_usize() + 1)
+    };
+    let nodes = gcx
+        .hir
+        .functions_enumerated()
+        .map(|(id, f)| {
+            let name = match f.name {
+                Some(_) => gcx.item_canonical_name(id).to_string(),
+                None => match f.contract {
+                    Some(c) => format!("{}.{}", gcx.item_name(c), f.kind),
+                    None => f.kind.to_string(),
+                },
+            };
+            CallGraphNode {
+                id: id.index(),
+                name,
+                kind: f.kind.to_str(),
+                location: location(f.name.map_or(f.span, |name| name.span)),
+            }
+        })
+        .collect::<Vec<_>>();
+    let edges = call_graph::CallGraph::new(gcx)
+        .calls()
+        .map(|call| CallGraphEdge {
+            from: call.caller.index(),
+            to: call.callee.index(),
+            kind: call.kind.to_str(),
+            location: location(call.span),
+        })
+        .collect::<Vec<_>>();
+
+    if dot {
+        let mut s = String::from("digraph calls {\n");
+        for node in &nodes {
+            s.push_str(&format!("    {} [label={:?}];\n", node.id, node.name));
+        }
+        for edge in &edges {
+            s.push_str(&format!("    {} -> {} [label={:?}];\n", edge.from, edge.to, edge.kind));
+        }
+        s.push_str("}\n");
+        output.call_graph_dot = Some(s);
+    }
+    if json {
+        output.call_graph = Some(CallGraph { nodes, edges });
+    }
+}
+
+/// Adds the generated interfaces of the contracts to the output, if requested.
+///
+/// Libraries are skipped, since their external functions can't be declared in an interface.
+fn emit_interfaces(gcx: Gcx<'_>, output: &mut CombinedJson) {
+    if !gcx.sess.do_emit(CompilerOutput::Interface) {
+        return;
+    }
+
+    let mut s = String::new();
+    for id in gcx.hir.contract_ids() {
+        if gcx.hir.contract(id).kind.is_library() {
+            continue;
+        }
+        if !s.is_empty() {
+            s.push('\n');
+        }
+        s.push_str(&format!("// {}\n", gcx.contract_fully_qualified_name(id)));
+        s.push_str(&gcx.contract_interface(id));
+    }
+    output.interfaces = Some(s);
 }
 
-type Abi = Vec<alloy_json_abi::AbiItem<'static>>;
-type Hashes = BTreeMap<String, String>;
+pub(crate) fn emit(gcx: Gcx<'_>, codegen: Option<&dyn CodegenBackend>, output: &mut CombinedJson) {
+    emit_call_graph(gcx, output);
+    emit_interfaces(gcx, output);
+
+    let codegen_outputs = [
+        CompilerOutput::Bin,
+        CompilerOutput::BinRuntime,
+        CompilerOutput::Ir,
+        CompilerOutput::Srcmap,
+        CompilerOutput::SrcmapRuntime,
+    ];
+    if codegen.is_none() && gcx.sess.emit.iter().any(|emit| codegen_outputs.contains(emit)) {
+        gcx.dcx().err("no code generation backend was set").emit();
+        return;
+    }
+
+    // Don't add empty entries for each contract if only outputs that are not part of them were
+    // requested.
+    let global = [
+        CompilerOutput::Ast,
+        CompilerOutput::AstPretty,
+        CompilerOutput::CallGraph,
+        CompilerOutput::CallGraphDot,
+        CompilerOutput::ImportGraph,
+        CompilerOutput::ImportGraphDot,
+        CompilerOutput::Interface,
+    ];
+    if gcx.sess.emit.iter().all(|emit| global.contains(emit)) {
+        return;
+    }
 
-pub(crate) fn emit(gcx: Gcx<'_>) {
-    let mut output =
-        CombinedJson { contracts: Default::default(), version: solar_interface::VERSION };
+    let source_ids = source_ids(gcx, &output.source_ids);
     for id in gcx.hir.contract_ids() {
         let name = gcx.contract_fully_qualified_name(id).to_string();
         let contract_output = output.contracts.entry(name).or_default();
+        let wants_bytecode = !gcx.sess.stop_after(CompilerStage::Ir)
+            && [
+                CompilerOutput::Bin,
+                CompilerOutput::BinRuntime,
+                CompilerOutput::Srcmap,
+                CompilerOutput::SrcmapRuntime,
+            ]
+            .iter()
+            .any(|output| gcx.sess.emit.contains(output));
+        let bytecode = codegen
+            .filter(|_| wants_bytecode && gcx.hir.contract(id).can_be_deployed())
+            .and_then(|codegen| codegen.contract_bytecode(gcx, id, &source_ids).ok());
         for &emit in &gcx.sess.emit {
             match emit {
                 CompilerOutput::Abi => contract_output.abi = Some(gcx.contract_abi(id)),
+                CompilerOutput::Ast
+                | CompilerOutput::AstPretty
+                | CompilerOutput::CallGraph
+                | CompilerOutput::CallGraphDot
+                | CompilerOutput::ImportGraph
+                | CompilerOutput::ImportGraphDot
+                | CompilerOutput::Interface => {}
+                CompilerOutput::Bin => {
+                    contract_output.bin = bytecode.as_ref().map(|b| b.bin.clone());
+                }
+                CompilerOutput::BinRuntime => {
+                    contract_output.bin_runtime = bytecode.as_ref().map(|b| b.bin_runtime.clone());
+                }
+                CompilerOutput::Devdoc => contract_output.devdoc = Some(gcx.contract_devdoc(id)),
                 CompilerOutput::Hashes => {
-                    let mut hashes = Hashes::default();
+                    let mut hashes = BTreeMap::new();
                     for f in gcx.interface_functions(id) {
                         hashes.insert(
                             gcx.item_signature(f.id.into()).to_string(),
@@ -43,18 +537,111 @@ pub(crate) fn emit(gcx: Gcx<'_>) {
                         );
                     }
                     contract_output.hashes = Some(hashes);
+
+                    let mut error_hashes = BTreeMap::new();
+                    let mut event_hashes = BTreeMap::new();
+                    for item in gcx.interface_events_and_errors(id) {
+                        let signature = gcx.item_signature(item).to_string();
+                        match item {
+                            hir::ItemId::Error(id) => {
+                                let selector = gcx.function_selector(id);
+                                error_hashes
+                                    .insert(signature, alloy_primitives::hex::encode(selector));
+                            }
+                            hir::ItemId::Event(id) => {
+                                let topic = gcx.event_selector(id);
+                                event_hashes
+                                    .insert(signature, alloy_primitives::hex::encode(topic));
+                            }
+                            _ => unreachable!(),
+                        }
+                    }
+                    contract_output.error_hashes = Some(error_hashes);
+                    contract_output.event_hashes = Some(event_hashes);
+                }
+                CompilerOutput::Ir => {
+                    if let Some(codegen) = codegen {
+                        if gcx.hir.contract(id).can_be_deployed() {
+                            contract_output.ir = codegen.contract_ir(gcx, id).ok();
+                        }
+                    }
+                }
+                CompilerOutput::Metadata => {
+                    contract_output.metadata = Some(gcx.contract_metadata(id));
+                }
+                CompilerOutput::Srcmap => {
+                    contract_output.srcmap = bytecode.as_ref().map(|b| b.srcmap.clone());
+                }
+                CompilerOutput::SrcmapRuntime => {
+                    contract_output.srcmap_runtime =
+                        bytecode.as_ref().map(|b| b.srcmap_runtime.clone());
+                }
+                CompilerOutput::StorageLayout => {
+                    contract_output.storage_layout = Some(gcx.storage_layout(id));
+                }
+                CompilerOutput::Userdoc => {
+                    contract_output.userdoc = Some(gcx.contract_userdoc(id));
                 }
             }
         }
     }
+}
+
+/// Returns the source ID of each source file, keyed by its start position.
+fn source_ids(gcx: Gcx<'_>, ids: &SourceIds) -> FxHashMap<BytePos, usize> {
+    gcx.hir
+        .sources()
+        .filter_map(|source| Some((source.file.start_pos, ids.id(&source.file.name)?)))
+        .collect()
+}
+
+/// Writes the combined JSON output to `--out-dir`, or to stdout.
+///
+/// The DOT call and import graphs, the printed ASTs, and the generated interfaces are written to
+/// `call_graph.dot`, `import_graph.dot`, `ast_pretty.sol` and `interfaces.sol` instead, and the
+/// combined JSON is only written if any other output was requested.
+pub(crate) fn write_output(sess: &Session, output: &CombinedJson) {
+    let separate = [
+        CompilerOutput::CallGraphDot,
+        CompilerOutput::ImportGraphDot,
+        CompilerOutput::AstPretty,
+        CompilerOutput::Interface,
+    ];
+    let json = sess.emit.iter().any(|emit| !separate.contains(emit));
     let _ = (|| {
-        let out_path = gcx.sess.out_dir.as_deref().map(|dir| dir.join("combined.json"));
-        let mut writer = out_writer(out_path.as_deref())?;
-        to_json(&mut writer, &output, gcx.sess.pretty_json)?;
-        writer.flush()?;
+        if let Some(dot) = &output.call_graph_dot {
+            let out_path = sess.out_dir.as_deref().map(|dir| dir.join("call_graph.dot"));
+            let mut writer = out_writer(out_path.as_deref())?;
+            writer.write_all(dot.as_bytes())?;
+            writer.flush()?;
+        }
+        if let Some(dot) = &output.import_graph_dot {
+            let out_path = sess.out_dir.as_deref().map(|dir| dir.join("import_graph.dot"));
+            let mut writer = out_writer(out_path.as_deref())?;
+            writer.write_all(dot.as_bytes())?;
+            writer.flush()?;
+        }
+        if let Some(ast_pretty) = &output.ast_pretty {
+            let out_path = sess.out_dir.as_deref().map(|dir| dir.join("ast_pretty.sol"));
+            let mut writer = out_writer(out_path.as_deref())?;
+            writer.write_all(ast_pretty.as_bytes())?;
+            writer.flush()?;
+        }
+        if let Some(interfaces) = &output.interfaces {
+            let out_path = sess.out_dir.as_deref().map(|dir| dir.join("interfaces.sol"));
+            let mut writer = out_writer(out_path.as_deref())?;
+            writer.write_all(interfaces.as_bytes())?;
+            writer.flush()?;
+        }
+        if json {
+            let out_path = sess.out_dir.as_deref().map(|dir| dir.join("combined.json"));
+            let mut writer = out_writer(out_path.as_deref())?;
+            to_json(&mut writer, output, sess.pretty_json)?;
+            writer.flush()?;
+        }
         Ok::<_, io::Error>(())
     })()
-    .map_err(|e| gcx.dcx().err(format!("failed to write to output: {e}")).emit());
+    .map_err(|e| sess.dcx.err(format!("failed to write to output: {e}")).emit());
 }
 
 fn out_writer(path: Option<&Path>) -> io::Result<impl io::Write> {
@@ -77,3 +664,19 @@ fn to_json<W: io::Write, T: Serialize>(
         serde_json::to_writer(writer, value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn source_ids() {
+        let name = |s: &str| FileName::Real(s.into());
+        let ids = SourceIds::new([name("b.sol"), name("a.sol"), name("c/a.sol"), name("a.sol")]);
+        assert_eq!(ids.names(), [name("a.sol"), name("b.sol"), name("c/a.sol")]);
+        assert_eq!(ids.id(&name("b.sol")), Some(1));
+        assert_eq!(ids.id(&FileName::Stdin), None);
+        assert_eq!(ids.name(2), Some(&name("c/a.sol")));
+        assert_eq!(ids.name(3), None);
+    }
+}
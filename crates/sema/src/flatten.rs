@@ -0,0 +1,160 @@
+//! Flattening of source files and their imports into a single source file.
+
+use crate::{ast, ParsingContext};
+use solar_interface::{Result, Session, Span};
+use std::{fmt::Write, ops::Range};
+use thread_local::ThreadLocal;
+
+const SPDX: &str = "SPDX-License-Identifier:";
+
+/// Flattens the loaded sources and their imports into a single source file.
+///
+/// The sources are concatenated in topological order, so that every file comes after the files
+/// that it imports. Import directives are removed, the `pragma solidity` requirements are combined
+/// into a single directive, the other pragmas are deduplicated, and the SPDX license identifiers
+/// are merged into a single comment at the top of the file.
+///
+/// Imports that declare aliases cannot be flattened, and are reported as errors.
+pub fn flatten(pcx: ParsingContext<'_>) -> Result<String> {
+    let sess = pcx.sess;
+    if sess.language.is_yul() {
+        return Err(sess.dcx.err("cannot flatten Yul sources").emit());
+    }
+
+    let arenas = ThreadLocal::<ast::Arena>::new();
+    let mut sources = pcx.parse(&arenas);
+    sess.dcx.has_errors()?;
+    sources.topo_sort();
+
+    let mut licenses = Vec::new();
+    let mut versions = Vec::new();
+    let mut pragmas = Vec::new();
+    let mut bodies = Vec::new();
+    for source in sources.iter() {
+        let Some(ast) = &source.ast else { continue };
+        let file = &source.file;
+        let src = &file.src[..];
+        let range = |span: Span| {
+            (span.lo().0 - file.start_pos.0) as usize..(span.hi().0 - file.start_pos.0) as usize
+        };
+
+        let mut removed = Vec::new();
+        for item in ast.items.iter() {
+            match &item.kind {
+                ast::ItemKind::Import(import) => {
+                    check_import(sess, item.span, import);
+                }
+                ast::ItemKind::Pragma(pragma) => match &pragma.tokens {
+                    ast::PragmaTokens::Version(name, _) => {
+                        let req = &src[range(name.span).end..range(item.span).end];
+                        push_unique(&mut versions, req.trim().trim_end_matches(';').trim());
+                    }
+                    _ => push_unique(&mut pragmas, &src[range(item.span)]),
+                },
+                _ => continue,
+            }
+            removed.push(line_range(src, range(item.span)));
+        }
+
+        let mut start = 0;
+        for line in src.split_inclusive('\n') {
+            let end = start + line.len();
+            if let Some(license) = spdx_license(line) {
+                push_unique(&mut licenses, license);
+                removed.push(start..end);
+            }
+            start = end;
+        }
+
+        removed.sort_by_key(|range| range.start);
+        let mut body = String::with_capacity(src.len());
+        let mut pos = 0;
+        for range in removed {
+            if range.start > pos {
+                body.push_str(&src[pos..range.start]);
+            }
+            pos = pos.max(range.end);
+        }
+        body.push_str(&src[pos..]);
+        bodies.push((file.name.display().to_string(), body.trim().to_string()));
+    }
+
+    if versions.len() > 1 && versions.iter().any(|req| req.contains("||")) {
+        let msg = "cannot combine `pragma solidity` requirements that contain `||`";
+        let note = format!("the requirements are: `{}`", versions.join("`, `"));
+        sess.dcx.err(msg).note(note).emit();
+    }
+    sess.dcx.has_errors()?;
+
+    let mut out = String::new();
+    match &licenses[..] {
+        [] => {}
+        [license] => writeln!(out, "// {SPDX} {license}").unwrap(),
+        licenses => {
+            let licenses = licenses.iter().map(|license| {
+                if license.contains(' ') {
+                    format!("({license})")
+                } else {
+                    license.clone()
+                }
+            });
+            writeln!(out, "// {SPDX} {}", licenses.collect::<Vec<_>>().join(" AND ")).unwrap();
+        }
+    }
+    if !versions.is_empty() {
+        writeln!(out, "pragma solidity {};", versions.join(" ")).unwrap();
+    }
+    for pragma in &pragmas {
+        writeln!(out, "{pragma}").unwrap();
+    }
+    for (name, body) in &bodies {
+        if body.is_empty() {
+            continue;
+        }
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        writeln!(out, "// {name}\n{body}").unwrap();
+    }
+    Ok(out)
+}
+
+/// Reports imports whose aliases would not be declared in the flattened source.
+fn check_import(sess: &Session, span: Span, import: &ast::ImportDirective<'_>) {
+    let has_alias = match &import.items {
+        ast::ImportItems::Plain(alias) | ast::ImportItems::Glob(alias) => alias.is_some(),
+        ast::ImportItems::Aliases(aliases) => aliases.iter().any(|(_, alias)| alias.is_some()),
+    };
+    if has_alias {
+        let help = "refer to the imported items by their original names instead";
+        sess.dcx.err("cannot flatten imports with aliases").span(span).help(help).emit();
+    }
+}
+
+/// Extends `range` to the whole line if the rest of the line only contains whitespace.
+fn line_range(src: &str, range: Range<usize>) -> Range<usize> {
+    let rest = &src[range.end..];
+    let line_end = rest.find('\n').map_or(src.len(), |i| range.end + i + 1);
+    let line_start = src[..range.start].rfind('\n').map_or(0, |i| i + 1);
+    if src[range.end..line_end].trim().is_empty() && src[line_start..range.start].trim().is_empty()
+    {
+        line_start..line_end
+    } else {
+        range
+    }
+}
+
+/// Returns the license of an SPDX license identifier comment line.
+fn spdx_license(line: &str) -> Option<&str> {
+    let line = line.trim();
+    let comment = line.strip_prefix("//").or_else(|| line.strip_prefix("/*"))?;
+    let license = comment.trim_start().strip_prefix(SPDX)?;
+    let license = license.trim_end().trim_end_matches("*/").trim();
+    (!license.is_empty()).then_some(license)
+}
+
+fn push_unique(v: &mut Vec<String>, s: &str) {
+    if !v.iter().any(|x| x == s) {
+        v.push(s.to_string());
+    }
+}
@@ -9,13 +9,13 @@ use solar_data_structures::{
     index::{Idx, IndexVec},
     newtype_index, BumpExt,
 };
-use solar_interface::{diagnostics::ErrorGuaranteed, source_map::SourceFile, Ident, Span};
+use solar_interface::{diagnostics::ErrorGuaranteed, source_map::SourceFile, Ident, Span, Symbol};
 use std::{fmt, ops::ControlFlow, sync::Arc};
 use strum::EnumIs;
 
 pub use ast::{
-    BinOp, BinOpKind, ContractKind, DataLocation, ElementaryType, FunctionKind, Lit,
-    StateMutability, UnOp, UnOpKind, VarMut, Visibility,
+    BinOp, BinOpKind, ContractKind, DataLocation, ElementaryType, FunctionKind, Lit, NatSpecItem,
+    NatSpecKind, StateMutability, UnOp, UnOpKind, VarMut, Visibility,
 };
 
 /// HIR arena allocator.
@@ -264,8 +264,14 @@ newtype_index! {
 pub struct Source<'hir> {
     pub file: Arc<SourceFile>,
     pub imports: &'hir [(ast::ItemId, SourceId)],
+    /// The SPDX license identifier of the source file, if any.
+    pub license: Option<&'hir str>,
     /// The source items.
     pub items: &'hir [ItemId],
+    /// The file-level `using for` directives.
+    pub using_directives: &'hir [UsingDirective<'hir>],
+    /// The ABI coder version selected by the `pragma abicoder` directives of the source file.
+    pub abi_coder: AbiCoder,
 }
 
 impl fmt::Debug for Source<'_> {
@@ -273,11 +279,24 @@ impl fmt::Debug for Source<'_> {
         f.debug_struct("Source")
             .field("file", &self.file.name)
             .field("imports", &self.imports)
+            .field("license", &self.license)
             .field("items", &self.items)
+            .field("using_directives", &self.using_directives)
+            .field("abi_coder", &self.abi_coder)
             .finish()
     }
 }
 
+/// An ABI coder version.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum AbiCoder {
+    /// `pragma abicoder v1;`
+    V1,
+    /// `pragma abicoder v2;` or `pragma experimental ABIEncoderV2;`. The default since 0.8.0.
+    #[default]
+    V2,
+}
+
 #[derive(Clone, Copy, Debug, EnumIs)]
 pub enum Item<'a, 'hir> {
     Contract(&'a Contract<'hir>),
@@ -336,6 +355,21 @@ impl<'hir> Item<'_, 'hir> {
         }
     }
 
+    /// Returns the ID of the source the item is defined in.
+    #[inline]
+    pub fn source(self) -> SourceId {
+        match self {
+            Item::Contract(c) => c.source,
+            Item::Function(f) => f.source,
+            Item::Struct(s) => s.source,
+            Item::Enum(e) => e.source,
+            Item::Udvt(u) => u.source,
+            Item::Error(e) => e.source,
+            Item::Event(e) => e.source,
+            Item::Variable(v) => v.source,
+        }
+    }
+
     /// Returns the contract ID if this item is part of a contract.
     #[inline]
     pub fn contract(self) -> Option<ContractId> {
@@ -489,10 +523,14 @@ pub struct Contract<'hir> {
     pub name: Ident,
     /// The contract kind.
     pub kind: ContractKind,
+    /// The NatSpec documentation.
+    pub docs: &'hir [NatSpecItem],
     /// The contract bases.
     pub bases: &'hir [ContractId],
     /// The linearized contract bases.
     pub linearized_bases: &'hir [ContractId],
+    /// The base constructor calls with arguments in the inheritance list: `is A(1)`.
+    pub bases_args: &'hir [Modifier<'hir>],
     /// The constructor function.
     pub ctor: Option<FunctionId>,
     /// The `fallback` function.
@@ -504,6 +542,10 @@ pub struct Contract<'hir> {
     /// Note that this only includes items defined in the contract itself, not inherited items.
     /// For getting all items, use [`Hir::contract_items`].
     pub items: &'hir [ItemId],
+    /// The `using for` directives in the contract.
+    ///
+    /// These are not inherited by derived contracts.
+    pub using_directives: &'hir [UsingDirective<'hir>],
 }
 
 impl Contract<'_> {
@@ -550,12 +592,14 @@ pub struct Function<'hir> {
     pub name: Option<Ident>,
     /// The function kind.
     pub kind: FunctionKind,
+    /// The NatSpec documentation.
+    pub docs: &'hir [NatSpecItem],
     /// The visibility of the function.
     pub visibility: Visibility,
     /// The state mutability of the function.
     pub state_mutability: StateMutability,
     /// Modifiers, or base classes if this is a constructor.
-    pub modifiers: &'hir [ItemId],
+    pub modifiers: &'hir [Modifier<'hir>],
     /// Whether this function is marked with the `virtual` keyword.
     pub marked_virtual: bool,
     /// Whether this function is marked with the `virtual` keyword or is defined in an interface.
@@ -598,6 +642,50 @@ impl Function<'_> {
     }
 }
 
+/// A modifier invocation, or a base constructor call if the function is a constructor.
+#[derive(Debug)]
+pub struct Modifier<'hir> {
+    /// The modifier, or the base contract.
+    pub id: ItemId,
+    /// The span of the modifier name.
+    pub span: Span,
+    /// The arguments of the invocation.
+    pub args: CallArgs<'hir>,
+}
+
+/// A `using for` directive: `using L for T;` or `using {f, g as +} for T global;`.
+#[derive(Debug)]
+pub struct UsingDirective<'hir> {
+    /// The directive span.
+    pub span: Span,
+    /// The library or the functions to attach.
+    pub list: UsingList<'hir>,
+    /// The type the functions are attached to. This is `*` if the value is `None`.
+    pub ty: Option<Type<'hir>>,
+    /// Whether the functions are attached to the type everywhere the type is available.
+    pub global: bool,
+}
+
+/// The functions attached by a [`UsingDirective`].
+#[derive(Clone, Copy, Debug)]
+pub enum UsingList<'hir> {
+    /// `using L for T`: attaches the non-private functions of library `L`.
+    Library(ContractId),
+    /// `using {f, L.g, h as +} for T`: attaches the listed free or library functions.
+    Functions(&'hir [UsingFunction]),
+}
+
+/// A function in [`UsingList::Functions`].
+#[derive(Clone, Copy, Debug)]
+pub struct UsingFunction {
+    /// The function.
+    pub id: FunctionId,
+    /// The span of the path to the function.
+    pub span: Span,
+    /// The operator the function is bound to, if any.
+    pub operator: Option<ast::UserDefinableOperator>,
+}
+
 /// A struct.
 #[derive(Debug)]
 pub struct Struct<'hir> {
@@ -653,6 +741,8 @@ pub struct Event<'hir> {
     pub span: Span,
     /// The event name.
     pub name: Ident,
+    /// The NatSpec documentation.
+    pub docs: &'hir [NatSpecItem],
     /// Whether this event is anonymous.
     pub anonymous: bool,
     pub parameters: &'hir [VariableId],
@@ -677,6 +767,8 @@ pub struct Error<'hir> {
     pub span: Span,
     /// The error name.
     pub name: Ident,
+    /// The NatSpec documentation.
+    pub docs: &'hir [NatSpecItem],
     pub parameters: &'hir [VariableId],
 }
 
@@ -697,6 +789,8 @@ pub struct Variable<'hir> {
     pub ty: Type<'hir>,
     /// The variable's name.
     pub name: Option<Ident>,
+    /// The NatSpec documentation.
+    pub docs: &'hir [NatSpecItem],
     /// The visibility of the variable.
     pub visibility: Option<Visibility>,
     pub mutability: Option<VarMut>,
@@ -720,6 +814,7 @@ impl<'hir> Variable<'hir> {
             kind,
             ty,
             name,
+            docs: &[],
             visibility: None,
             mutability: None,
             data_location: None,
@@ -885,9 +980,8 @@ pub struct Stmt<'hir> {
 /// A kind of statement.
 #[derive(Debug)]
 pub enum StmtKind<'hir> {
-    // TODO: Yul to HIR.
-    // /// An assembly block, with optional flags: `assembly "evmasm" (...) { ... }`.
-    // Assembly(StmtAssembly<'hir>),
+    /// An assembly block, with optional flags: `assembly "evmasm" (...) { ... }`.
+    Assembly(&'hir StmtAssembly<'hir>),
     /// A single-variable declaration statement: `uint256 foo = 42;`.
     DeclSingle(VariableId),
 
@@ -935,6 +1029,38 @@ pub enum StmtKind<'hir> {
     Err(ErrorGuaranteed),
 }
 
+/// An assembly block: `assembly { ... }`.
+///
+/// Yul is not lowered to HIR yet, so only the information needed by the analysis passes is kept.
+#[derive(Debug)]
+pub struct StmtAssembly<'hir> {
+    /// Whether the block is marked as memory-safe with the `"memory-safe"` flag.
+    pub memory_safe: bool,
+    /// The names of the functions called in the block, including builtins, with the spans of the
+    /// calls, in source order.
+    pub calls: &'hir [(Symbol, Span)],
+    /// The Solidity variables referenced in the block.
+    pub variables: &'hir [VariableId],
+    /// The references to Solidity declarations in the block, in source order.
+    pub references: &'hir [AssemblyReference],
+}
+
+/// A reference to a Solidity declaration in an assembly block: `x`, `x.slot`.
+#[derive(Clone, Copy, Debug)]
+pub struct AssemblyReference {
+    /// The span of the whole path.
+    pub span: Span,
+    /// The referenced declaration.
+    pub item: ItemId,
+    /// The suffix, like `slot` in `x.slot`. One of `slot`, `offset`, `length`, `address`, or
+    /// `selector`.
+    pub suffix: Option<Ident>,
+    /// Whether the reference is assigned to.
+    pub is_assignment: bool,
+    /// Whether the reference is inside of a Yul function definition.
+    pub in_function: bool,
+}
+
 /// A try statement: `try fooBar(42) returns (...) { ... } catch (...) { ... }`.
 ///
 /// Reference: <https://docs.soliditylang.org/en/latest/grammar.html#a4.SolidityParser.tryStatement>
@@ -9,9 +9,14 @@
 #[macro_use]
 extern crate tracing;
 
+use codegen::CodegenBackend;
 use rayon::prelude::*;
 use solar_data_structures::{trustme, OnDrop};
-use solar_interface::{config::CompilerStage, Result, Session};
+use solar_interface::{
+    config::{CompilerStage, DumpKind},
+    diagnostics::{DiagCtxt, Diagnostic},
+    Result, Session,
+};
 use thread_local::ThreadLocal;
 use ty::Gcx;
 
@@ -21,26 +26,80 @@ pub use bumpalo;
 pub use solar_ast as ast;
 pub use solar_interface as interface;
 
+pub mod ast_grep;
+
+mod ast_json;
+pub use ast_json::AstJsonExporter;
+
 mod ast_lowering;
 mod ast_passes;
 
 mod parse;
 pub use parse::{ParsedSource, ParsedSources, ParsingContext};
 
+mod spdx;
+
+mod compiler;
+pub use compiler::{CompilationDiagnostic, CompilationResult, Compiled, Compiler, CompilerBuilder};
+
 pub mod builtins;
+pub mod call_graph;
+pub mod cfg;
 pub mod eval;
 pub mod hir;
+pub mod lints;
 pub mod ty;
 
 mod typeck;
 
+pub mod codegen;
+
+mod yul;
+
+mod flatten;
+pub use flatten::flatten;
+
 mod emit;
+pub use emit::{CombinedJson, CombinedJsonContract, CombinedJsonSource, SourceIds};
 
 pub mod stats;
 
 /// Parses and semantically analyzes all the loaded sources, recursing into imports.
+///
+/// The compiler outputs requested with [`Session::emit`] are written to
+/// [`Session::out_dir`], or to stdout.
 pub fn parse_and_resolve(pcx: ParsingContext<'_>) -> Result<()> {
     let sess = pcx.sess;
+    let mut output = CombinedJson::new();
+    let result = parse_and_resolve_output(pcx, &mut output);
+    if sess.emit.is_empty() {
+        return result;
+    }
+    // The outputs computed from the ASTs are still written if a later stage failed.
+    if result.is_ok() || output.has_ast_outputs() {
+        emit::write_output(sess, &output);
+    }
+    result?;
+    sess.dcx.has_errors()
+}
+
+/// Parses and semantically analyzes all the loaded sources, recursing into imports.
+///
+/// Compilation runs in stages, and stops after the one set in [`Session::stop_after`], if any:
+/// - [`Parsed`](CompilerStage::Parsed): the sources are parsed into ASTs;
+/// - [`Resolved`](CompilerStage::Resolved): the ASTs are validated and lowered into the HIR;
+/// - [`Checked`](CompilerStage::Checked): the HIR is type checked;
+/// - [`Ir`](CompilerStage::Ir) and [`Codegen`](CompilerStage::Codegen): the Yul IR and the
+///   bytecode of all the contracts are generated by the [`ParsingContext::codegen`] backend, even
+///   if not requested.
+///
+/// Stores the compiler outputs requested with [`Session::emit`] in `output` instead of writing
+/// them. The outputs other than the ASTs, the printed ASTs and the import graph are only
+/// available if compilation does not stop before code generation. The outputs computed from the
+/// ASTs are kept in `output` even if a later stage fails.
+pub fn parse_and_resolve_output(pcx: ParsingContext<'_>, output: &mut CombinedJson) -> Result<()> {
+    let sess = pcx.sess;
+    let codegen = pcx.codegen;
 
     if pcx.sources.is_empty() {
         let msg = "no files found";
@@ -52,11 +111,20 @@ pub fn parse_and_resolve(pcx: ParsingContext<'_>) -> Result<()> {
         debug!(asts_allocated = arenas.iter_mut().map(|a| a.allocated_bytes()).sum::<usize>());
         debug_span!("dropping_ast_arenas").in_scope(|| drop(arenas));
     });
-    let mut sources = pcx.parse(&ast_arenas);
+    let mut sources = sess.time("parse", || pcx.parse(&ast_arenas));
+    if sess.time_passes {
+        sess.timings.update("parse", |pass| {
+            pass.files = Some(sources.len());
+            pass.bytes = Some(sources.iter().map(|s| s.file.src.len()).sum());
+            pass.nodes = Some(sources.asts().map(stats::count_ast_nodes).sum());
+        });
+    }
 
     if let Some(dump) = &sess.dump {
-        if dump.kind.is_ast() {
-            dump_ast(sess, &sources, dump.paths.as_deref())?;
+        match dump.kind {
+            DumpKind::Tokens => dump_tokens(sess, &sources, dump.paths.as_deref())?,
+            DumpKind::Ast => dump_ast(sess, &sources, dump.paths.as_deref())?,
+            DumpKind::Hir => {}
         }
     }
 
@@ -66,17 +134,30 @@ pub fn parse_and_resolve(pcx: ParsingContext<'_>) -> Result<()> {
         }
     }
 
-    if sess.language.is_yul() || sess.stop_after(CompilerStage::Parsed) {
+    sess.time("emit_asts", || emit::emit_asts(sess, &sources, output));
+    emit::emit_ast_pretty(sess, &sources, output);
+    emit::emit_import_graph(sess, &sources, output);
+    emit::emit_dependencies(&sources, output);
+
+    if sess.stop_after(CompilerStage::Parsed) {
         return Ok(());
     }
 
+    if sess.language.is_yul() {
+        sess.dcx.has_errors()?;
+        let objects = sources.as_raw_slice().par_iter().filter_map(|s| s.yul_object.as_ref());
+        sess.time("yul_check", || objects.for_each(|object| yul::check(sess, object)));
+        return sess.dcx.has_errors();
+    }
+
+    sources.check_import_cycles(&sess.dcx);
     sources.topo_sort();
 
     let hir_arena = OnDrop::new(ThreadLocal::<hir::Arena>::new(), |hir_arena| {
         debug!(hir_allocated = hir_arena.get_or_default().allocated_bytes());
         debug_span!("dropping_hir_arena").in_scope(|| drop(hir_arena));
     });
-    let (hir, symbol_resolver) = lower(sess, &sources, hir_arena.get_or_default())?;
+    let (mut hir, symbol_resolver) = lower(sess, &sources, hir_arena.get_or_default())?;
 
     // Drop the ASTs and AST arenas in a separate thread.
     sess.spawn({
@@ -90,14 +171,35 @@ pub fn parse_and_resolve(pcx: ParsingContext<'_>) -> Result<()> {
         }
     });
 
+    if sess.drop_source_text {
+        sess.time("drop_source_text", || {
+            let sm = sess.source_map();
+            sm.drop_source_texts();
+            for source in hir.sources.iter_mut() {
+                source.file = sm.lookup_source_file(source.file.start_pos);
+            }
+        });
+    }
+
     let global_context =
         OnDrop::new(ty::GlobalCtxt::new(sess, &hir_arena, hir, symbol_resolver), |gcx| {
             debug_span!("drop_gcx").in_scope(|| drop(gcx));
         });
     let gcx = ty::Gcx::new(unsafe { trustme::decouple_lt(&global_context) });
-    analysis(gcx)?;
+    analysis(gcx, codegen, output)
+}
 
-    Ok(())
+/// Calls `f` on each item in parallel, and returns the diagnostics that it emitted in the order of
+/// the items instead of emitting them.
+///
+/// Emitting the returned diagnostics with [`DiagCtxt::emit_buffered`] makes the output independent
+/// of thread scheduling.
+pub(crate) fn par_for_each_buffered<I: ParallelIterator>(
+    dcx: &DiagCtxt,
+    iter: I,
+    f: impl Fn(I::Item) + Sync + Send,
+) -> Vec<Diagnostic> {
+    iter.flat_map_iter(|item| dcx.buffer_diagnostics(|| f(item)).1).collect()
 }
 
 /// Lowers the parsed ASTs into the HIR.
@@ -106,72 +208,157 @@ fn lower<'sess, 'hir>(
     sources: &ParsedSources<'_>,
     arena: &'hir hir::Arena,
 ) -> Result<(hir::Hir<'hir>, ast_lowering::SymbolResolver<'sess>)> {
-    debug_span!("all_ast_passes").in_scope(|| {
-        sources.par_asts().for_each(|ast| {
-            ast_passes::run(sess, ast);
+    sess.time("ast_passes", || {
+        debug_span!("all_ast_passes").in_scope(|| {
+            sess.progress.start("resolving", sources.asts().count());
+            let diagnostics = par_for_each_buffered(&sess.dcx, sources.par_asts(), |ast| {
+                ast_passes::run(sess, ast);
+                sess.progress.inc();
+            });
+            sess.dcx.emit_buffered(diagnostics);
         });
     });
 
     sess.dcx.has_errors()?;
 
-    Ok(ast_lowering::lower(sess, sources, arena))
+    Ok(sess.time("ast_lowering", || ast_lowering::lower(sess, sources, arena)))
 }
 
 #[instrument(level = "debug", skip_all)]
-fn analysis(gcx: Gcx<'_>) -> Result<()> {
+fn analysis(
+    gcx: Gcx<'_>,
+    codegen: Option<&dyn CodegenBackend>,
+    output: &mut CombinedJson,
+) -> Result<()> {
     if let Some(dump) = &gcx.sess.dump {
         if dump.kind.is_hir() {
             dump_hir(gcx, dump.paths.as_deref())?;
         }
     }
 
+    if gcx.sess.stop_after(CompilerStage::Resolved) {
+        return Ok(());
+    }
+
     // Lower HIR types.
-    gcx.hir.par_item_ids().for_each(|id| {
-        let _ = gcx.type_of_item(id);
-        match id {
-            hir::ItemId::Struct(id) => _ = gcx.struct_field_types(id),
-            hir::ItemId::Contract(id) => _ = gcx.interface_functions(id),
-            _ => {}
-        }
+    gcx.sess.time("type_lowering", || {
+        let diagnostics = par_for_each_buffered(gcx.dcx(), gcx.hir.par_item_ids(), |id| {
+            let _ = gcx.type_of_item(id);
+            match id {
+                hir::ItemId::Struct(id) => _ = gcx.struct_field_types(id),
+                hir::ItemId::Contract(id) => _ = gcx.interface_functions(id),
+                _ => {}
+            }
+        });
+        gcx.dcx().emit_buffered(diagnostics);
     });
     gcx.sess.dcx.has_errors()?;
 
-    typeck::check(gcx);
+    gcx.sess.time("typeck", || typeck::check(gcx));
     gcx.sess.dcx.has_errors()?;
 
+    gcx.sess.time("lints", || lints::check(gcx));
+    gcx.sess.dcx.has_errors()?;
+
+    if gcx.sess.stop_after(CompilerStage::Checked) {
+        return Ok(());
+    }
+
+    gcx.sess.time("codegen", || generate_code(gcx, codegen))?;
+
     if !gcx.sess.emit.is_empty() {
-        emit::emit(gcx);
+        gcx.sess.time("emit", || emit::emit(gcx, codegen, output));
         gcx.sess.dcx.has_errors()?;
     }
 
     Ok(())
 }
 
+/// Generates the IR or the bytecode of all the deployable contracts when stopping after the
+/// corresponding stage, so that code generation can be run without requesting its outputs.
+fn generate_code(gcx: Gcx<'_>, codegen: Option<&dyn CodegenBackend>) -> Result<()> {
+    let stage = match gcx.sess.stop_after {
+        Some(stage @ (CompilerStage::Ir | CompilerStage::Codegen)) => stage,
+        _ => return Ok(()),
+    };
+    let Some(codegen) = codegen else {
+        return Err(gcx.dcx().err("no code generation backend was set").emit());
+    };
+    let source_ids = Default::default();
+    for id in gcx.hir.contract_ids() {
+        if !gcx.hir.contract(id).can_be_deployed() {
+            continue;
+        }
+        let _ = match stage {
+            CompilerStage::Ir => codegen.contract_ir(gcx, id).map(drop),
+            _ => codegen.contract_bytecode(gcx, id, &source_ids).map(drop),
+        };
+    }
+    gcx.sess.dcx.has_errors()
+}
+
+fn dump_tokens(
+    sess: &Session,
+    sources: &ParsedSources<'_>,
+    paths: Option<&[String]>,
+) -> Result<()> {
+    let sources = match dump_sources(sess, sources, DumpKind::Tokens, paths)? {
+        Some(matched) => matched,
+        None => sources.iter().collect(),
+    };
+    for source in sources {
+        println!("// {}", source.file.name.display());
+        // The diagnostics were already emitted when parsing.
+        let (tokens, _) = sess.dcx.buffer_diagnostics(|| {
+            solar_parse::Lexer::from_source_file(sess, &source.file).into_tokens()
+        });
+        for token in tokens {
+            let loc = sess.source_map().lookup_char_pos(token.span.lo());
+            println!("{}:{} {:?}", loc.line, loc.col.0 + 1, token.kind);
+        }
+    }
+    Ok(())
+}
+
 fn dump_ast(sess: &Session, sources: &ParsedSources<'_>, paths: Option<&[String]>) -> Result<()> {
-    if let Some(paths) = paths {
-        for path in paths {
-            if let Some(source) = sources.iter().find(|&s| match_file_name(&s.file.name, path)) {
+    match dump_sources(sess, sources, DumpKind::Ast, paths)? {
+        Some(matched) => {
+            for source in matched {
                 println!("{source:#?}");
-            } else {
-                let msg = format!("`-Zdump=ast={path:?}` did not match any source file");
-                let note = format!(
-                    "available source files: {}",
-                    sources
-                        .iter()
-                        .map(|s| s.file.name.display().to_string())
-                        .collect::<Vec<_>>()
-                        .join(", ")
-                );
-                return Err(sess.dcx.err(msg).note(note).emit());
             }
         }
-    } else {
-        println!("{sources:#?}");
+        None => println!("{sources:#?}"),
     }
-
     Ok(())
 }
 
+/// Returns the sources matching the given `-Zdump` paths, or `None` if no paths were given.
+fn dump_sources<'a, 'ast>(
+    sess: &Session,
+    sources: &'a ParsedSources<'ast>,
+    kind: DumpKind,
+    paths: Option<&[String]>,
+) -> Result<Option<Vec<&'a ParsedSource<'ast>>>> {
+    let Some(paths) = paths else { return Ok(None) };
+    let mut matched = Vec::with_capacity(paths.len());
+    for path in paths {
+        let Some(source) = sources.iter().find(|&s| match_file_name(&s.file.name, path)) else {
+            let msg = format!("`-Zdump={kind}={path:?}` did not match any source file");
+            let note = format!(
+                "available source files: {}",
+                sources
+                    .iter()
+                    .map(|s| s.file.name.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            return Err(sess.dcx.err(msg).note(note).emit());
+        };
+        matched.push(source);
+    }
+    Ok(Some(matched))
+}
+
 fn dump_hir(gcx: Gcx<'_>, paths: Option<&[String]>) -> Result<()> {
     println!("{:#?}", gcx.hir);
     if let Some(paths) = paths {
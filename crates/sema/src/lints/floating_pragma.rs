@@ -0,0 +1,36 @@
+use solar_ast::{self as ast, SemverOp, SemverReqComponentKind, SemverVersionNumber};
+use solar_interface::{error_code, sym, Session};
+
+/// Warns about `pragma solidity` directives that allow more than one compiler version, like
+/// `pragma solidity ^0.8.0;`.
+///
+/// Contracts should be deployed with the compiler version they were tested with.
+pub(super) fn check_source_unit(sess: &Session, ast: &ast::SourceUnit<'_>) {
+    for item in ast.items.iter() {
+        let ast::ItemKind::Pragma(pragma) = &item.kind else { continue };
+        let ast::PragmaTokens::Version(name, req) = &pragma.tokens else { continue };
+        if name.name != sym::solidity || is_exact(req) {
+            continue;
+        }
+        let help =
+            format!("pin the compiler version, like `pragma solidity {};`", sess.solidity_version);
+        sess.dcx
+            .warn(format!("floating compiler version requirement `{req}`"))
+            .code(error_code!(6508))
+            .span(item.span)
+            .help(help)
+            .emit();
+    }
+}
+
+/// Returns `true` if `req` only matches a single version.
+fn is_exact(req: &ast::SemverReq<'_>) -> bool {
+    let [con] = &*req.dis else { return false };
+    let [component] = &*con.components else { return false };
+    let SemverReqComponentKind::Op(None | Some(SemverOp::Exact), version) = &component.kind else {
+        return false;
+    };
+    [Some(version.major), version.minor, version.patch]
+        .iter()
+        .all(|n| matches!(n, Some(SemverVersionNumber::Number(_))))
+}
@@ -0,0 +1,283 @@
+//! Lint passes.
+//!
+//! Lints are checks for code that compiles but is likely to be wrong or insecure. Each lint emits
+//! warnings with its own code, so its level can be changed like the level of any other warning,
+//! with `-A`, `-W`, and `-D` on the command line or with `solar-disable` comments.
+//!
+//! Some lints are allowed by default, because they are too noisy for most projects; these are only
+//! run when enabled by their code, like `-W 6508`.
+//!
+//! See [`LINTS`] for the list of lints.
+
+use crate::{
+    hir::{self, CallArgs},
+    par_for_each_buffered,
+    ty::{Gcx, TyKind},
+    typeck,
+};
+use solar_ast::{self as ast, ElementaryType};
+use solar_interface::{
+    diagnostics::{DiagCtxt, DiagnosticId, Level},
+    error_code, kw, Ident, Session,
+};
+
+mod floating_pragma;
+mod reentrancy;
+mod shadowed_builtins;
+mod tx_origin;
+mod unchecked_call;
+
+/// All the lints, sorted by name.
+pub static LINTS: &[LintPass] = &[
+    LintPass {
+        name: "floating-pragma",
+        code: error_code!(6508),
+        default_level: Level::Allow,
+        description: "`pragma solidity` directives that do not pin an exact compiler version",
+        check_source_unit: Some(floating_pragma::check_source_unit),
+        check_item: None,
+        check_function: None,
+    },
+    LintPass {
+        name: "reentrancy",
+        code: error_code!(8751),
+        default_level: Level::Allow,
+        description: "state variables that are written after an external call",
+        check_source_unit: None,
+        check_item: None,
+        check_function: Some(reentrancy::check_function),
+    },
+    LintPass {
+        name: "shadowed-builtin",
+        code: error_code!(2319),
+        default_level: Level::Warning,
+        description: "declarations with the same name as a builtin symbol",
+        check_source_unit: None,
+        check_item: Some(shadowed_builtins::check_item),
+        check_function: None,
+    },
+    LintPass {
+        name: "tx-origin",
+        code: error_code!(3164),
+        default_level: Level::Warning,
+        description: "comparisons with `tx.origin`, which should not be used for authorization",
+        check_source_unit: None,
+        check_item: None,
+        check_function: Some(tx_origin::check_function),
+    },
+    LintPass {
+        name: "unchecked-call",
+        code: error_code!(9302),
+        default_level: Level::Warning,
+        description: "low-level calls whose success value is not used",
+        check_source_unit: None,
+        check_item: None,
+        check_function: Some(unchecked_call::check_function),
+    },
+];
+
+/// A lint pass: a check that emits a single kind of warning.
+///
+/// A pass can check the AST of each source unit, before it is lowered, and the resolved items and
+/// functions of the HIR, after type checking.
+#[derive(Debug)]
+pub struct LintPass {
+    /// The name of the lint, in kebab-case.
+    pub name: &'static str,
+    /// The code of the emitted warnings.
+    pub code: DiagnosticId,
+    /// The level of the warnings when it is not changed with [`DiagCtxt::set_lint_level`].
+    pub default_level: Level,
+    /// A short description of what the lint checks.
+    pub description: &'static str,
+    check_source_unit: Option<fn(&Session, &ast::SourceUnit<'_>)>,
+    check_item: Option<fn(Gcx<'_>, hir::ItemId)>,
+    check_function: Option<fn(Gcx<'_>, hir::FunctionId)>,
+}
+
+impl LintPass {
+    /// Returns `true` if the warnings of this lint are emitted.
+    pub fn is_enabled(&self, dcx: &DiagCtxt) -> bool {
+        dcx.lint_level(&self.code, self.default_level) != Level::Allow
+    }
+}
+
+/// Runs the enabled lint passes on the AST of a source unit.
+pub(crate) fn check_source_unit(sess: &Session, ast: &ast::SourceUnit<'_>) {
+    for lint in LINTS {
+        if let Some(check) = lint.check_source_unit {
+            if lint.is_enabled(&sess.dcx) {
+                check(sess, ast);
+            }
+        }
+    }
+}
+
+/// Runs the enabled lint passes on the HIR.
+#[instrument(name = "lints", level = "debug", skip_all)]
+pub(crate) fn check(gcx: Gcx<'_>) {
+    let dcx = gcx.dcx();
+    let enabled = LINTS.iter().filter(|lint| lint.is_enabled(dcx)).collect::<Vec<_>>();
+    let item_checks = enabled.iter().filter_map(|lint| lint.check_item).collect::<Vec<_>>();
+    let function_checks = enabled.iter().filter_map(|lint| lint.check_function).collect::<Vec<_>>();
+    if !item_checks.is_empty() {
+        let diagnostics = par_for_each_buffered(dcx, gcx.hir.par_item_ids(), |id| {
+            item_checks.iter().for_each(|check| check(gcx, id));
+        });
+        dcx.emit_buffered(diagnostics);
+    }
+    if !function_checks.is_empty() {
+        let diagnostics = par_for_each_buffered(dcx, gcx.hir.par_function_ids(), |id| {
+            function_checks.iter().for_each(|check| check(gcx, id));
+        });
+        dcx.emit_buffered(diagnostics);
+    }
+}
+
+/// A visitor over the statements and expressions of a function body.
+///
+/// The `walk_*` methods visit the children of a node in source order, except for assignments,
+/// whose right-hand side is visited first since it is evaluated first.
+trait FunctionVisitor<'gcx> {
+    fn gcx(&self) -> Gcx<'gcx>;
+
+    fn visit_stmt(&mut self, stmt: &'gcx hir::Stmt<'gcx>) {
+        self.walk_stmt(stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &'gcx hir::Expr<'gcx>) {
+        self.walk_expr(expr);
+    }
+
+    fn visit_block(&mut self, block: hir::Block<'gcx>) {
+        for stmt in block {
+            self.visit_stmt(stmt);
+        }
+    }
+
+    fn visit_args(&mut self, args: &'gcx CallArgs<'gcx>) {
+        for expr in args.exprs() {
+            self.visit_expr(expr);
+        }
+    }
+
+    fn walk_stmt(&mut self, stmt: &'gcx hir::Stmt<'gcx>) {
+        match stmt.kind {
+            hir::StmtKind::DeclSingle(id) => {
+                if let Some(init) = self.gcx().hir.variable(id).initializer {
+                    self.visit_expr(init);
+                }
+            }
+            hir::StmtKind::DeclMulti(_, expr) | hir::StmtKind::Expr(expr) => self.visit_expr(expr),
+            hir::StmtKind::Block(block)
+            | hir::StmtKind::UncheckedBlock(block)
+            | hir::StmtKind::Loop(block, _) => self.visit_block(block),
+            hir::StmtKind::Emit(_, ref args) | hir::StmtKind::Revert(_, ref args) => {
+                self.visit_args(args)
+            }
+            hir::StmtKind::Return(expr) => {
+                if let Some(expr) = expr {
+                    self.visit_expr(expr);
+                }
+            }
+            hir::StmtKind::If(cond, then, else_) => {
+                self.visit_expr(cond);
+                self.visit_stmt(then);
+                if let Some(else_) = else_ {
+                    self.visit_stmt(else_);
+                }
+            }
+            hir::StmtKind::Try(try_) => {
+                self.visit_expr(&try_.expr);
+                self.visit_block(try_.block);
+                for catch in try_.catch {
+                    self.visit_block(catch.block);
+                }
+            }
+            hir::StmtKind::Assembly(_)
+            | hir::StmtKind::Break
+            | hir::StmtKind::Continue
+            | hir::StmtKind::Placeholder
+            | hir::StmtKind::Err(_) => {}
+        }
+    }
+
+    fn walk_expr(&mut self, expr: &'gcx hir::Expr<'gcx>) {
+        match expr.kind {
+            hir::ExprKind::Array(exprs) => {
+                for expr in exprs {
+                    self.visit_expr(expr);
+                }
+            }
+            hir::ExprKind::Assign(lhs, _, rhs) => {
+                self.visit_expr(rhs);
+                self.visit_expr(lhs);
+            }
+            hir::ExprKind::Binary(lhs, _, rhs) => {
+                self.visit_expr(lhs);
+                self.visit_expr(rhs);
+            }
+            hir::ExprKind::Call(callee, ref args) => {
+                self.visit_expr(callee);
+                self.visit_args(args);
+            }
+            hir::ExprKind::CallOptions(callee, options) => {
+                self.visit_expr(callee);
+                for option in options {
+                    self.visit_expr(&option.value);
+                }
+            }
+            hir::ExprKind::Delete(expr)
+            | hir::ExprKind::Member(expr, _)
+            | hir::ExprKind::Payable(expr)
+            | hir::ExprKind::Unary(_, expr) => self.visit_expr(expr),
+            hir::ExprKind::Index(expr, index) => {
+                self.visit_expr(expr);
+                if let Some(index) = index {
+                    self.visit_expr(index);
+                }
+            }
+            hir::ExprKind::Slice(expr, start, end) => {
+                self.visit_expr(expr);
+                for expr in [start, end].into_iter().flatten() {
+                    self.visit_expr(expr);
+                }
+            }
+            hir::ExprKind::Ternary(cond, then, else_) => {
+                self.visit_expr(cond);
+                self.visit_expr(then);
+                self.visit_expr(else_);
+            }
+            hir::ExprKind::Tuple(exprs) => {
+                for expr in exprs.iter().flatten() {
+                    self.visit_expr(expr);
+                }
+            }
+            hir::ExprKind::Ident(_)
+            | hir::ExprKind::Lit(_)
+            | hir::ExprKind::New(_)
+            | hir::ExprKind::TypeCall(_)
+            | hir::ExprKind::Type(_)
+            | hir::ExprKind::Err(_) => {}
+        }
+    }
+}
+
+/// If `expr` is a call of the `call`, `delegatecall`, or `staticcall` member of an address,
+/// returns the name of the member.
+fn low_level_call<'gcx>(
+    gcx: Gcx<'gcx>,
+    func: &hir::Function<'_>,
+    expr: &'gcx hir::Expr<'gcx>,
+) -> Option<Ident> {
+    let hir::ExprKind::Call(mut callee, _) = expr.kind else { return None };
+    while let hir::ExprKind::CallOptions(inner, _) = callee.kind {
+        callee = inner;
+    }
+    let hir::ExprKind::Member(base, member) = callee.kind else { return None };
+    if ![kw::Call, kw::Delegatecall, kw::Staticcall].contains(&member.name) {
+        return None;
+    }
+    let ty = typeck::expr_ty(gcx, func, base)?;
+    matches!(ty.kind, TyKind::Elementary(ElementaryType::Address(_))).then_some(member)
+}
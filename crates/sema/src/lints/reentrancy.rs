@@ -0,0 +1,214 @@
+use super::{low_level_call, FunctionVisitor};
+use crate::{
+    call_graph::CallKind,
+    cfg::{BlockId, Terminator},
+    hir::{self, ElementaryType, Res, StateMutability, UnOpKind},
+    ty::{Gcx, TyKind},
+    typeck,
+};
+use solar_data_structures::{index::IndexVec, map::FxHashSet};
+use solar_interface::{error_code, kw, sym, Span};
+
+/// Warns about the writes to state variables that can happen after an external call in the same
+/// function, which break the checks-effects-interactions pattern.
+///
+/// External calls are the message calls to functions that are not `view` or `pure`, and the
+/// low-level `call` and `delegatecall`. The order of execution is taken from the control-flow graph
+/// of the function, so a call in one branch of an `if` does not affect the other branch, but a call
+/// at the end of a loop affects the start of the loop. Writes through local storage pointers and in
+/// inline assembly, and calls in the invoked modifiers and internal functions, are not tracked.
+///
+/// Reference: <https://docs.soliditylang.org/en/latest/security-considerations.html#reentrancy>
+pub(super) fn check_function(gcx: Gcx<'_>, id: hir::FunctionId) {
+    let func = gcx.hir.function(id);
+    if matches!(func.state_mutability, StateMutability::View | StateMutability::Pure) {
+        return;
+    }
+    let Some(cfg) = gcx.function_cfg(id) else { return };
+    let external_calls = gcx
+        .function_calls(id)
+        .iter()
+        .filter(|call| {
+            call.kind == CallKind::External
+                && !matches!(
+                    gcx.hir.function(call.callee).state_mutability,
+                    StateMutability::View | StateMutability::Pure
+                )
+        })
+        .map(|call| call.span)
+        .collect();
+
+    // Collect the external calls and the state variable writes of each block, in execution order.
+    let mut collector = EffectCollector { gcx, func, external_calls, effects: Vec::new() };
+    let blocks = cfg.blocks();
+    let effects = blocks
+        .iter()
+        .map(|block| {
+            for &stmt in block.stmts {
+                collector.visit_stmt(stmt);
+            }
+            match block.terminator {
+                Terminator::Branch { cond: expr, .. } | Terminator::Return(Some(expr)) => {
+                    collector.visit_expr(expr)
+                }
+                Terminator::Try { stmt, .. } => collector.visit_expr(&stmt.expr),
+                Terminator::Goto(_) | Terminator::Return(None) | Terminator::Revert => {}
+            }
+            std::mem::take(&mut collector.effects)
+        })
+        .collect::<IndexVec<BlockId, _>>();
+
+    // Propagate the first external call that can have been made before entering each block forward,
+    // until a fixed point is reached.
+    let reachable = cfg.reachable_blocks();
+    let mut entry = IndexVec::<BlockId, _>::from_elem_n(None::<Span>, blocks.len());
+    let mut worklist =
+        reachable.iter_enumerated().filter(|&(_, &r)| r).map(|(id, _)| id).collect::<Vec<_>>();
+    while let Some(block) = worklist.pop() {
+        let Some(exit) = entry[block].or_else(|| first_call(&effects[block])) else { continue };
+        for succ in blocks[block].terminator.successors() {
+            if entry[succ].is_none() {
+                entry[succ] = Some(exit);
+                worklist.push(succ);
+            }
+        }
+    }
+
+    let mut reported = FxHashSet::default();
+    for (block, effects) in effects.iter_enumerated() {
+        if !reachable[block] {
+            continue;
+        }
+        let mut call = entry[block];
+        for effect in effects {
+            match *effect {
+                Effect::Call(span) => call = call.or(Some(span)),
+                Effect::Write(var, span) => {
+                    let Some(call) = call else { continue };
+                    if !reported.insert(span) {
+                        continue;
+                    }
+                    let name = gcx.hir.variable(var).name.unwrap();
+                    gcx.dcx()
+                        .warn(format!("state variable `{name}` is written after an external call"))
+                        .code(error_code!(8751))
+                        .span(span)
+                        .span_note(call, "the external call is here")
+                        .help("update the state before making external calls")
+                        .emit();
+                }
+            }
+        }
+    }
+}
+
+/// Returns the span of the first external call in `effects`, if any.
+fn first_call(effects: &[Effect]) -> Option<Span> {
+    effects.iter().find_map(|effect| match *effect {
+        Effect::Call(span) => Some(span),
+        Effect::Write(..) => None,
+    })
+}
+
+/// An effect of an expression that matters for reentrancy.
+enum Effect {
+    /// An external call.
+    Call(Span),
+    /// A write to a state variable.
+    Write(hir::VariableId, Span),
+}
+
+/// Collects the effects of statements and expressions, in execution order.
+struct EffectCollector<'gcx> {
+    gcx: Gcx<'gcx>,
+    func: &'gcx hir::Function<'gcx>,
+    /// The spans of the message calls to functions that can modify the state.
+    external_calls: FxHashSet<Span>,
+    effects: Vec<Effect>,
+}
+
+impl<'gcx> EffectCollector<'gcx> {
+    /// Records a write to the state variables that `place` refers to.
+    fn write(&mut self, place: &'gcx hir::Expr<'gcx>, span: Span) {
+        match place.kind {
+            hir::ExprKind::Tuple(places) => {
+                for place in places.iter().flatten() {
+                    self.write(place, span);
+                }
+            }
+            _ => {
+                if let Some(var) = self.state_variable(place) {
+                    self.effects.push(Effect::Write(var, span));
+                }
+            }
+        }
+    }
+
+    /// Returns the state variable that `place` is a part of, if any.
+    fn state_variable(&self, place: &'gcx hir::Expr<'gcx>) -> Option<hir::VariableId> {
+        match place.kind {
+            hir::ExprKind::Index(base, _)
+            | hir::ExprKind::Slice(base, ..)
+            | hir::ExprKind::Member(base, _) => self.state_variable(base),
+            hir::ExprKind::Ident(res) => res.iter().find_map(|res| match *res {
+                Res::Item(hir::ItemId::Variable(id))
+                    if self.gcx.hir.variable(id).is_state_variable() =>
+                {
+                    Some(id)
+                }
+                _ => None,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if `expr` is a storage array or `bytes`.
+    fn is_storage_array(&self, expr: &'gcx hir::Expr<'gcx>) -> bool {
+        typeck::expr_ty(self.gcx, self.func, expr).is_some_and(|ty| {
+            matches!(
+                ty.peel_refs().kind,
+                TyKind::DynArray(_) | TyKind::Elementary(ElementaryType::Bytes)
+            )
+        })
+    }
+}
+
+impl<'gcx> FunctionVisitor<'gcx> for EffectCollector<'gcx> {
+    fn gcx(&self) -> Gcx<'gcx> {
+        self.gcx
+    }
+
+    fn visit_expr(&mut self, expr: &'gcx hir::Expr<'gcx>) {
+        // The operands are evaluated before the expression itself.
+        self.walk_expr(expr);
+        match expr.kind {
+            hir::ExprKind::Call(callee, _) => {
+                let is_external = self.external_calls.contains(&expr.span)
+                    || low_level_call(self.gcx, self.func, expr)
+                        .is_some_and(|member| member.name != kw::Staticcall);
+                if is_external {
+                    self.effects.push(Effect::Call(expr.span));
+                }
+                if let hir::ExprKind::Member(base, member) = callee.kind {
+                    if (member.name == sym::push || member.name == kw::Pop)
+                        && self.is_storage_array(base)
+                    {
+                        self.write(base, expr.span);
+                    }
+                }
+            }
+            hir::ExprKind::Assign(lhs, _, _) | hir::ExprKind::Delete(lhs) => {
+                self.write(lhs, expr.span)
+            }
+            hir::ExprKind::Unary(op, place)
+                if matches!(
+                    op.kind,
+                    UnOpKind::PreInc | UnOpKind::PreDec | UnOpKind::PostInc | UnOpKind::PostDec
+                ) =>
+            {
+                self.write(place, expr.span)
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use crate::{builtins::Builtin, hir, ty::Gcx};
+use solar_interface::error_code;
+
+/// Warns about the items and state variables that have the same name as a global builtin, like
+/// `function assert()`.
+///
+/// Local variables and parameters are checked when they are declared, during name resolution.
+///
+/// Reference: <https://github.com/ethereum/solidity/blob/develop/libsolidity/analysis/NameAndTypeResolver.cpp>
+pub(super) fn check_item(gcx: Gcx<'_>, id: hir::ItemId) {
+    let item = gcx.hir.item(id);
+    if let hir::Item::Variable(var) = item {
+        if !var.is_state_variable() && !var.is_file_level_variable() {
+            return;
+        }
+    }
+    let Some(name) = item.name() else { return };
+    if Builtin::global().iter().any(|builtin| builtin.name() == name.name) {
+        let msg = "this declaration shadows a builtin symbol";
+        gcx.dcx().warn(msg).code(error_code!(2319)).span(name.span).emit();
+    }
+}
@@ -0,0 +1,57 @@
+use super::FunctionVisitor;
+use crate::{
+    builtins::Builtin,
+    hir::{self, Res},
+    ty::Gcx,
+};
+use solar_interface::{error_code, kw};
+
+/// Warns about comparisons with `tx.origin`, which are usually authorization checks.
+///
+/// `tx.origin` is the account that sent the transaction, not the caller, so a contract that
+/// authorizes `tx.origin` can be called by any contract that its owner interacts with.
+///
+/// Reference: <https://docs.soliditylang.org/en/latest/security-considerations.html#tx-origin>
+pub(super) fn check_function(gcx: Gcx<'_>, id: hir::FunctionId) {
+    let Some(body) = gcx.hir.function(id).body else { return };
+    TxOriginChecker { gcx }.visit_block(body);
+}
+
+struct TxOriginChecker<'gcx> {
+    gcx: Gcx<'gcx>,
+}
+
+impl<'gcx> FunctionVisitor<'gcx> for TxOriginChecker<'gcx> {
+    fn gcx(&self) -> Gcx<'gcx> {
+        self.gcx
+    }
+
+    fn visit_expr(&mut self, expr: &'gcx hir::Expr<'gcx>) {
+        if let hir::ExprKind::Binary(lhs, op, rhs) = expr.kind {
+            if matches!(op.kind, hir::BinOpKind::Eq | hir::BinOpKind::Ne)
+                && (is_tx_origin(lhs) || is_tx_origin(rhs))
+            {
+                self.gcx
+                    .dcx()
+                    .warn("`tx.origin` used for authorization")
+                    .code(error_code!(3164))
+                    .span(expr.span)
+                    .help("use `msg.sender` to check the caller instead")
+                    .emit();
+            }
+        }
+        self.walk_expr(expr);
+    }
+}
+
+/// Returns `true` if `expr` is `tx.origin`.
+fn is_tx_origin(expr: &hir::Expr<'_>) -> bool {
+    match expr.kind {
+        hir::ExprKind::Member(base, member) => {
+            member.name == kw::Origin
+                && matches!(base.kind, hir::ExprKind::Ident(&[Res::Builtin(Builtin::Tx)]))
+        }
+        hir::ExprKind::Tuple(&[Some(expr)]) => is_tx_origin(expr),
+        _ => false,
+    }
+}
@@ -0,0 +1,47 @@
+use super::{low_level_call, FunctionVisitor};
+use crate::{hir, ty::Gcx};
+use solar_interface::error_code;
+
+/// Warns about low-level calls whose return value is discarded.
+///
+/// Unlike message calls, low-level calls don't revert when the call fails, so ignoring their
+/// success value silently ignores failures.
+///
+/// Reference: <https://github.com/ethereum/solidity/blob/develop/libsolidity/analysis/StaticAnalyzer.cpp>
+pub(super) fn check_function(gcx: Gcx<'_>, id: hir::FunctionId) {
+    let func = gcx.hir.function(id);
+    let Some(body) = func.body else { return };
+    UncheckedCallChecker { gcx, func }.visit_block(body);
+}
+
+struct UncheckedCallChecker<'gcx> {
+    gcx: Gcx<'gcx>,
+    func: &'gcx hir::Function<'gcx>,
+}
+
+impl<'gcx> FunctionVisitor<'gcx> for UncheckedCallChecker<'gcx> {
+    fn gcx(&self) -> Gcx<'gcx> {
+        self.gcx
+    }
+
+    fn visit_stmt(&mut self, stmt: &'gcx hir::Stmt<'gcx>) {
+        if let hir::StmtKind::Expr(mut expr) = stmt.kind {
+            while let hir::ExprKind::Tuple(&[Some(inner)]) = expr.kind {
+                expr = inner;
+            }
+            if let Some(member) = low_level_call(self.gcx, self.func, expr) {
+                self.gcx
+                    .dcx()
+                    .warn("return value of low-level call is not used")
+                    .code(error_code!(9302))
+                    .span(expr.span)
+                    .help(format!(
+                        "`{member}` does not revert if the call fails; check the returned success \
+                         value"
+                    ))
+                    .emit();
+            }
+        }
+        self.walk_stmt(stmt);
+    }
+}
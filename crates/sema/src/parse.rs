@@ -1,14 +1,15 @@
-use crate::hir::SourceId;
+use crate::{codegen::CodegenBackend, hir::SourceId, spdx};
 use rayon::prelude::*;
 use solar_ast as ast;
 use solar_data_structures::{
     index::{Idx, IndexVec},
-    map::FxHashSet,
+    map::{FxHashMap, FxHashSet},
 };
 use solar_interface::{
-    diagnostics::DiagCtxt,
-    source_map::{FileName, FileResolver, SourceFile},
-    Result, Session,
+    diagnostics::{Applicability, DiagCtxt, ErrorGuaranteed},
+    error_code,
+    source_map::{FileName, FileResolver, ResolveError, SourceFile, SourceFileHash},
+    Result, Session, Span,
 };
 use solar_parse::{unescape, Lexer, Parser};
 use std::{borrow::Cow, fmt, path::Path, sync::Arc};
@@ -22,6 +23,8 @@ pub struct ParsingContext<'sess> {
     /// The loaded sources. Consumed once `parse` is called.
     /// The `'static` lifetime is a lie, as nothing borrowed is ever stored in this field.
     pub(crate) sources: ParsedSources<'static>,
+    /// The backend used to generate the code of the contracts, if any.
+    pub codegen: Option<&'sess dyn CodegenBackend>,
 }
 
 impl<'sess> ParsingContext<'sess> {
@@ -31,6 +34,7 @@ impl<'sess> ParsingContext<'sess> {
             sess,
             file_resolver: FileResolver::new(sess.source_map()),
             sources: ParsedSources::new(),
+            codegen: None,
         }
     }
 
@@ -90,9 +94,21 @@ impl<'sess> ParsingContext<'sess> {
         crate::parse_and_resolve(self)
     }
 
+    /// See [`crate::parse_and_resolve_output`].
+    pub fn parse_and_resolve_output(self, output: &mut crate::CombinedJson) -> Result<()> {
+        crate::parse_and_resolve_output(self, output)
+    }
+
+    /// See [`crate::flatten`].
+    pub fn flatten(self) -> Result<String> {
+        crate::flatten(self)
+    }
+
     /// Parses all the loaded sources, recursing into imports.
     ///
-    /// Sources are not guaranteed to be in any particular order, as they may be parsed in parallel.
+    /// Imports are resolved breadth-first, and the files discovered at each level are parsed in
+    /// parallel unless the session is sequential. Diagnostics are emitted in the same order
+    /// regardless of the number of threads.
     #[instrument(level = "debug", skip_all)]
     pub fn parse<'ast>(mut self, arenas: &'ast ThreadLocal<ast::Arena>) -> ParsedSources<'ast> {
         // SAFETY: The `'static` lifetime on `self.sources` is a lie since none of the asts are
@@ -101,6 +117,7 @@ impl<'sess> ParsingContext<'sess> {
         let mut sources: ParsedSources<'ast> =
             unsafe { std::mem::transmute::<ParsedSources<'static>, ParsedSources<'ast>>(sources) };
         if !sources.is_empty() {
+            self.sess.progress.start("parsing", sources.len());
             if self.sess.is_sequential() {
                 self.parse_sequential(&mut sources, arenas.get_or_default());
             } else {
@@ -114,16 +131,48 @@ impl<'sess> ParsingContext<'sess> {
             );
         }
         sources.assert_unique();
+        self.warn_identical_files(&sources);
         sources
     }
 
+    /// Warns about distinct source files with identical contents, which are usually copies of the
+    /// same file that will be compiled separately.
+    fn warn_identical_files(&self, sources: &ParsedSources<'_>) {
+        let mut seen = FxHashMap::<SourceFileHash, &SourceFile>::default();
+        for source in sources.iter() {
+            let file = &*source.file;
+            if file.src.trim().is_empty() {
+                continue;
+            }
+            let first = *seen.entry(file.src_hash).or_insert(file);
+            if !std::ptr::eq(first, file) && first.src == file.src {
+                let msg = format!(
+                    "`{}` has the same contents as `{}`",
+                    file.name.display(),
+                    first.name.display()
+                );
+                let note = "the files have different names, so they are compiled as distinct \
+                            source units";
+                self.dcx().warn(msg).note(note).emit();
+            }
+        }
+    }
+
     fn parse_sequential<'ast>(&self, sources: &mut ParsedSources<'ast>, arena: &'ast ast::Arena) {
         for i in 0.. {
             let current_file = SourceId::from_usize(i);
             let Some(source) = sources.get(current_file) else { break };
             debug_assert!(source.ast.is_none(), "source already parsed");
 
+            if self.sess.language.is_yul() {
+                let object = self.parse_yul_one(&source.file, arena);
+                sources[current_file].yul_object = object;
+                self.sess.progress.inc();
+                continue;
+            }
+
             let ast = self.parse_one(&source.file, arena);
+            let license = ast.as_ref().and_then(|ast| spdx::license(self.sess, &source.file, ast));
             let n_sources = sources.len();
             for (import_item_id, import) in resolve_imports!(self, &source.file, ast.as_ref()) {
                 sources.add_import(current_file, import_item_id, import);
@@ -132,7 +181,10 @@ impl<'sess> ParsingContext<'sess> {
             if new_files > 0 {
                 trace!(new_files);
             }
+            self.sess.progress.add_total(new_files);
+            self.sess.progress.inc();
             sources[current_file].ast = ast;
+            sources[current_file].license = license;
         }
     }
 
@@ -150,24 +202,42 @@ impl<'sess> ParsingContext<'sess> {
             }
             trace!(start, "parsing {} files", to_parse.len());
             start += to_parse.len();
-            let imports = to_parse
+            // Diagnostics are buffered per file and emitted in source order once the whole level
+            // has been parsed, so that the output does not depend on thread scheduling.
+            let results = to_parse
                 .par_iter_mut()
-                .enumerate()
-                .flat_map_iter(|(i, source)| {
+                .map(|source| {
                     debug_assert!(source.ast.is_none(), "source already parsed");
-                    source.ast = self.parse_one(&source.file, arenas.get_or_default());
-                    resolve_imports!(self, &source.file, source.ast.as_ref())
-                        .map(move |import| (i, import))
+                    self.dcx().buffer_diagnostics(|| {
+                        let arena = arenas.get_or_default();
+                        if self.sess.language.is_yul() {
+                            source.yul_object = self.parse_yul_one(&source.file, arena);
+                        } else {
+                            source.ast = self.parse_one(&source.file, arena);
+                            source.license = source
+                                .ast
+                                .as_ref()
+                                .and_then(|ast| spdx::license(self.sess, &source.file, ast));
+                        }
+                        let imports = resolve_imports!(self, &source.file, source.ast.as_ref())
+                            .collect::<Vec<_>>();
+                        self.sess.progress.inc();
+                        imports
+                    })
                 })
-                .collect_vec_list();
+                .collect::<Vec<_>>();
             let n_sources = sources.len();
-            for (i, (import_item_id, import)) in imports.into_iter().flatten() {
-                sources.add_import(SourceId::from_usize(base + i), import_item_id, import);
+            for (i, (imports, diagnostics)) in results.into_iter().enumerate() {
+                self.dcx().emit_buffered(diagnostics);
+                for (import_item_id, import) in imports {
+                    sources.add_import(SourceId::from_usize(base + i), import_item_id, import);
+                }
             }
             let new_files = sources.len() - n_sources;
             if new_files > 0 {
                 trace!(new_files);
             }
+            self.sess.progress.add_total(new_files);
         }
     }
 
@@ -178,17 +248,68 @@ impl<'sess> ParsingContext<'sess> {
         file: &SourceFile,
         arena: &'ast ast::Arena,
     ) -> Option<ast::SourceUnit<'ast>> {
+        self.check_utf8(file);
         let lexer = Lexer::from_source_file(self.sess, file);
         let mut parser = Parser::from_lexer(arena, lexer);
-        let r = if self.sess.language.is_yul() {
-            let _file = parser.parse_yul_file_object().map_err(|e| e.emit());
-            None
-        } else {
-            parser.parse_file().map_err(|e| e.emit()).ok()
-        };
+        let r = parser.parse_file().map_err(|e| e.emit()).ok();
         trace!(allocated = arena.allocated_bytes(), used = arena.used_bytes(), "AST arena stats");
         r
     }
+
+    /// Parses a single Yul file.
+    #[instrument(level = "debug", skip_all, fields(file = %file.name.display()))]
+    fn parse_yul_one<'ast>(
+        &self,
+        file: &SourceFile,
+        arena: &'ast ast::Arena,
+    ) -> Option<ast::yul::Object<'ast>> {
+        self.check_utf8(file);
+        let lexer = Lexer::from_source_file(self.sess, file);
+        let mut parser = Parser::from_lexer(arena, lexer);
+        let r = parser.parse_yul_file_object().map_err(|e| e.emit()).ok();
+        trace!(allocated = arena.allocated_bytes(), used = arena.used_bytes(), "AST arena stats");
+        r
+    }
+
+    /// Emits an error if `file` was not valid UTF-8 when it was loaded.
+    ///
+    /// The invalid bytes were replaced when the file was decoded, so it can still be parsed.
+    fn check_utf8(&self, file: &SourceFile) {
+        let Some(invalid) = file.invalid_utf8 else { return };
+        let lo = file.absolute_position(invalid.pos);
+        let span = Span::new(lo, lo + char::REPLACEMENT_CHARACTER.len_utf8() as u32);
+        self.dcx()
+            .err("source file is not valid UTF-8")
+            .span(span)
+            .span_label(span, format!("invalid byte at offset {}", invalid.offset))
+            .note("invalid bytes are replaced with `U+FFFD`")
+            .emit();
+    }
+
+    /// Emits an error for an import that could not be resolved, suggesting a file with a similar
+    /// name if the imported file does not exist.
+    fn import_error(
+        &self,
+        e: ResolveError,
+        import: &ast::ImportDirective<'_>,
+        span: Span,
+        path: &Path,
+        parent: Option<&Path>,
+    ) -> ErrorGuaranteed {
+        let mut err = self.dcx().err(e.to_string()).span(span);
+        if let ResolveError::NotFound(_) = e {
+            err = err.code(error_code!(6275));
+            if let Some(similar) = self.file_resolver.find_similar_file(path, parent) {
+                err = err.span_suggestion(
+                    import.path.span,
+                    "a file with a similar name exists",
+                    format!("\"{}\"", similar.display()),
+                    Applicability::MaybeIncorrect,
+                );
+            }
+        }
+        err.emit()
+    }
 }
 
 /// Resolves the imports of the given file, returning an iterator over all the imported files.
@@ -225,7 +346,7 @@ macro_rules! resolve_imports {
                 };
                 this.file_resolver
                     .resolve_file(path, parent.as_deref())
-                    .map_err(|e| this.dcx().err(e.to_string()).span(span).emit())
+                    .map_err(|e| this.import_error(e, import, span, path, parent.as_deref()))
                     .ok()
                     .map(|file| (id, file))
             })
@@ -350,6 +471,80 @@ impl<'ast> ParsedSources<'ast> {
         });
     }
 
+    /// Warns about import cycles.
+    ///
+    /// Cycles are allowed in Solidity, so this only emits warnings. Each cycle is reported once,
+    /// starting from the source that was loaded first.
+    #[instrument(level = "debug", skip_all)]
+    pub(crate) fn check_import_cycles(&self, dcx: &DiagCtxt) {
+        let mut states = IndexVec::from_elem_n(VisitState::Unvisited, self.len());
+        let mut stack = Vec::new();
+        for id in self.sources.indices() {
+            if states[id] == VisitState::Unvisited {
+                self.check_import_cycles_rec(dcx, id, &mut states, &mut stack);
+            }
+        }
+    }
+
+    /// `stack` contains the sources that are currently being visited, along with the import
+    /// through which the next source in the stack was reached.
+    fn check_import_cycles_rec(
+        &self,
+        dcx: &DiagCtxt,
+        id: SourceId,
+        states: &mut IndexVec<SourceId, VisitState>,
+        stack: &mut Vec<(SourceId, ast::ItemId)>,
+    ) {
+        states[id] = VisitState::InProgress;
+        let mut seen = FxHashSet::default();
+        for &(item_id, import_id) in &self.sources[id].imports {
+            // Only consider the first import of each file.
+            if !seen.insert(import_id) {
+                continue;
+            }
+            match states[import_id] {
+                VisitState::Unvisited => {
+                    stack.push((id, item_id));
+                    self.check_import_cycles_rec(dcx, import_id, states, stack);
+                    stack.pop();
+                }
+                VisitState::InProgress => {
+                    let start = stack.iter().position(|&(s, _)| s == import_id);
+                    let mut cycle = stack[start.unwrap_or(stack.len())..].to_vec();
+                    cycle.push((id, item_id));
+                    self.report_import_cycle(dcx, &cycle);
+                }
+                VisitState::Done => {}
+            }
+        }
+        states[id] = VisitState::Done;
+    }
+
+    fn report_import_cycle(&self, dcx: &DiagCtxt, cycle: &[(SourceId, ast::ItemId)]) {
+        let name = |id: SourceId| self.sources[id].file.name.display().to_string();
+        let import_span = |(id, item_id): (SourceId, ast::ItemId)| {
+            self.sources[id].ast.as_ref().map_or(Span::DUMMY, |ast| ast.items[item_id].span)
+        };
+
+        let (first, rest) = cycle.split_first().unwrap();
+        if rest.is_empty() {
+            let note = "this is allowed, but it is only useful to refer to the file's own items                         through an alias";
+            dcx.warn("source file imports itself").span(import_span(*first)).note(note).emit();
+            return;
+        }
+
+        let chain = cycle.iter().chain(std::iter::once(first)).map(|&(id, _)| name(id));
+        let mut diag = dcx
+            .warn(format!("import cycle: {}", chain.collect::<Vec<_>>().join(" -> ")))
+            .span(import_span(*first));
+        for (i, &import) in rest.iter().enumerate() {
+            let next = cycle.get(i + 2).unwrap_or(first).0;
+            let msg = format!("`{}` imports `{}` here", name(import.0), name(next));
+            diag = diag.span_note(import_span(import), msg);
+        }
+        diag.note("import cycles are allowed, but are often unintended").emit();
+    }
+
     fn topo_order(&self, id: SourceId, order: &mut Vec<SourceId>, seen: &mut FxHashSet<SourceId>) {
         if !seen.insert(id) {
             return;
@@ -377,6 +572,13 @@ impl std::ops::DerefMut for ParsedSources<'_> {
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum VisitState {
+    Unvisited,
+    InProgress,
+    Done,
+}
+
 /// A single parsed source.
 pub struct ParsedSource<'ast> {
     /// The source file.
@@ -385,6 +587,12 @@ pub struct ParsedSource<'ast> {
     pub imports: Vec<(ast::ItemId, SourceId)>,
     /// The AST. `None` if an error occurred during parsing, or if the source is a Yul file.
     pub ast: Option<ast::SourceUnit<'ast>>,
+    /// The Yul object. `None` if an error occurred during parsing, or if the source is a
+    /// Solidity file.
+    pub yul_object: Option<ast::yul::Object<'ast>>,
+    /// The SPDX license expression from the `SPDX-License-Identifier` comment. `None` if there
+    /// is no license identifier, or more than one.
+    pub license: Option<String>,
 }
 
 impl fmt::Debug for ParsedSource<'_> {
@@ -394,6 +602,12 @@ impl fmt::Debug for ParsedSource<'_> {
         if let Some(ast) = &self.ast {
             dbg.field("ast", &ast);
         }
+        if let Some(yul_object) = &self.yul_object {
+            dbg.field("yul_object", &yul_object);
+        }
+        if let Some(license) = &self.license {
+            dbg.field("license", license);
+        }
         dbg.finish()
     }
 }
@@ -401,7 +615,7 @@ impl fmt::Debug for ParsedSource<'_> {
 impl ParsedSource<'_> {
     /// Creates a new empty source.
     pub fn new(file: Arc<SourceFile>) -> Self {
-        Self { file, ast: None, imports: Vec::new() }
+        Self { file, ast: None, yul_object: None, imports: Vec::new(), license: None }
     }
 }
 
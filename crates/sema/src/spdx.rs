@@ -0,0 +1,375 @@
+//! SPDX license identifiers.
+//!
+//! Reference: <https://spdx.github.io/spdx-spec/v2.3/SPDX-license-expressions/>
+
+use solar_ast as ast;
+use solar_interface::{error_code, source_map::SourceFile, BytePos, Session, Span};
+use solar_parse::{lexer::token::RawTokenKind, Cursor};
+use std::ops::Range;
+
+const TAG: &str = "SPDX-License-Identifier:";
+
+/// Finds the SPDX license identifier of a source file and validates its license expression.
+///
+/// Like solc, only the comments outside of the top-level items are searched, which includes the
+/// items' doc-comments. Emits a warning if there is no license identifier, and an error if there
+/// is more than one.
+///
+/// Returns the license expression, if there is exactly one.
+pub(crate) fn license(
+    sess: &Session,
+    file: &SourceFile,
+    ast: &ast::SourceUnit<'_>,
+) -> Option<String> {
+    let src = file.src.as_str();
+    let start = file.start_pos;
+    let offset = |pos: BytePos| (pos.0 - start.0) as usize;
+    let to_span = |lo: usize, hi: usize| Span::new(start + lo as u32, start + hi as u32);
+
+    let mut items = ast.items.iter().map(|item| offset(item.span.lo())..offset(item.span.hi()));
+    let mut next_item = items.next();
+    let mut found = Vec::new();
+    let mut pos = 0;
+    for token in Cursor::new(src) {
+        let (lo, hi) = (pos, pos + token.len as usize);
+        pos = hi;
+        if !matches!(
+            token.kind,
+            RawTokenKind::LineComment { .. } | RawTokenKind::BlockComment { .. }
+        ) {
+            continue;
+        }
+        while next_item.as_ref().is_some_and(|item| item.end <= lo) {
+            next_item = items.next();
+        }
+        if next_item.as_ref().is_some_and(|item| item.start <= lo) {
+            continue;
+        }
+
+        let comment = &src[lo..hi];
+        for (i, _) in comment.match_indices(TAG) {
+            let value = &comment[i + TAG.len()..];
+            let value_start = value.len() - value.trim_start_matches([' ', '\t']).len();
+            let value = &value[value_start..];
+            let len = value
+                .find(|c: char| !(c.is_ascii_alphanumeric() || " ()+.-".contains(c)))
+                .unwrap_or(value.len());
+            let value = value[..len].trim_end();
+            let value_lo = lo + i + TAG.len() + value_start;
+            found.push((value, to_span(value_lo, value_lo + value.len())));
+        }
+    }
+
+    let dcx = &sess.dcx;
+    match *found {
+        [] => {
+            let msg = "SPDX license identifier not provided in source file";
+            dcx.warn(msg)
+                .code(error_code!(1878))
+                .span(to_span(0, 0))
+                .note(
+                    "before publishing, consider adding a comment containing \
+                     `SPDX-License-Identifier: <SPDX-License>` to each source file",
+                )
+                .help("use `SPDX-License-Identifier: UNLICENSED` for non-open-source code")
+                .emit();
+            None
+        }
+        [(value, span)] => {
+            if let Err((msg, span)) = validate(value, span) {
+                dcx.warn(msg)
+                    .span(span)
+                    .help("see <https://spdx.org/licenses/> for the list of SPDX licenses")
+                    .emit();
+            }
+            Some(value.to_string())
+        }
+        [(_, first), (_, second), ..] => {
+            let msg = "multiple SPDX license identifiers found in source file";
+            dcx.err(msg)
+                .code(error_code!(3716))
+                .span(second)
+                .span_note(first, "first license identifier found here")
+                .help("use `AND` or `OR` to combine multiple licenses")
+                .emit();
+            None
+        }
+    }
+}
+
+/// Validates an SPDX license expression, returning an error message and its span.
+fn validate(expr: &str, span: Span) -> Result<(), (String, Span)> {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    for part in expr.split_inclusive([' ', '(', ')']) {
+        let (word, delim) = match part.as_bytes().last() {
+            Some(b' ' | b'(' | b')') => part.split_at(part.len() - 1),
+            _ => (part, ""),
+        };
+        if !word.is_empty() {
+            tokens.push((word, pos));
+        }
+        if !delim.is_empty() && delim != " " {
+            tokens.push((delim, pos + word.len()));
+        }
+        pos += part.len();
+    }
+
+    let mut parser = ExprParser { tokens: &tokens, pos: 0, end: expr.len() };
+    let result = parser.parse_or().and_then(|()| match parser.peek() {
+        Some(token) => {
+            Err(token_error(format!("unexpected `{}` in SPDX license expression", token.0), token))
+        }
+        None => Ok(()),
+    });
+    result.map_err(|(msg, range)| {
+        let lo = span.lo() + range.start as u32;
+        (msg, Span::new(lo, lo + range.len() as u32))
+    })
+}
+
+/// A recursive-descent parser for SPDX license expressions.
+///
+/// ```text
+/// or-expr   = and-expr *("OR" and-expr)
+/// and-expr  = with-expr *("AND" with-expr)
+/// with-expr = "(" or-expr ")" / license ["+"] ["WITH" exception]
+/// ```
+struct ExprParser<'a> {
+    /// The tokens and their offsets in the expression.
+    tokens: &'a [(&'a str, usize)],
+    pos: usize,
+    /// The length of the expression, used for errors at the end.
+    end: usize,
+}
+
+type PResult = Result<(), (String, Range<usize>)>;
+
+impl<'a> ExprParser<'a> {
+    fn peek(&self) -> Option<(&'a str, usize)> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    /// Returns an error at the current token, or at the end of the expression.
+    fn expected(&self, what: &str) -> (String, Range<usize>) {
+        let pos = self.peek().map_or(self.end, |(_, pos)| pos);
+        (format!("expected {what} in SPDX license expression"), pos..pos)
+    }
+
+    fn eat_op(&mut self, op: &str) -> bool {
+        let eaten = self.peek().is_some_and(|(token, _)| token.eq_ignore_ascii_case(op));
+        if eaten {
+            self.pos += 1;
+        }
+        eaten
+    }
+
+    fn bump_word(&mut self, what: &str) -> Result<(&'a str, usize), (String, Range<usize>)> {
+        let token = self.peek().filter(|&(token, _)| !matches!(token, "(" | ")"));
+        let Some(token) = token else { return Err(self.expected(what)) };
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn parse_or(&mut self) -> PResult {
+        self.parse_and()?;
+        while self.eat_op("OR") {
+            self.parse_and()?;
+        }
+        Ok(())
+    }
+
+    fn parse_and(&mut self) -> PResult {
+        self.parse_with()?;
+        while self.eat_op("AND") {
+            self.parse_with()?;
+        }
+        Ok(())
+    }
+
+    fn parse_with(&mut self) -> PResult {
+        if self.peek().is_some_and(|(token, _)| token == "(") {
+            self.pos += 1;
+            self.parse_or()?;
+            if !self.peek().is_some_and(|(token, _)| token == ")") {
+                return Err(self.expected("`)`"));
+            }
+            self.pos += 1;
+            return Ok(());
+        }
+
+        let license = self.bump_word("license identifier")?;
+        let id = license.0.strip_suffix('+').unwrap_or(license.0);
+        if !is_license(id) {
+            let msg = format!("unknown SPDX license identifier `{id}`");
+            return Err(token_error(msg, license));
+        }
+        if self.eat_op("WITH") {
+            let exception = self.bump_word("license exception")?;
+            if !EXCEPTIONS.iter().any(|e| e.eq_ignore_ascii_case(exception.0)) {
+                let msg = format!("unknown SPDX license exception `{}`", exception.0);
+                return Err(token_error(msg, exception));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns an error at the given token.
+fn token_error(msg: String, (token, pos): (&str, usize)) -> (String, Range<usize>) {
+    (msg, pos..pos + token.len())
+}
+
+fn is_license(id: &str) -> bool {
+    id.strip_prefix("LicenseRef-").is_some_and(|s| !s.is_empty())
+        || LICENSES.iter().any(|l| l.eq_ignore_ascii_case(id))
+}
+
+/// Licenses from the SPDX license list, and `UNLICENSED`, which solc accepts for
+/// non-open-source code.
+static LICENSES: &[&str] = &[
+    "0BSD",
+    "AAL",
+    "AFL-1.1",
+    "AFL-1.2",
+    "AFL-2.0",
+    "AFL-2.1",
+    "AFL-3.0",
+    "AGPL-1.0",
+    "AGPL-1.0-only",
+    "AGPL-1.0-or-later",
+    "AGPL-3.0",
+    "AGPL-3.0-only",
+    "AGPL-3.0-or-later",
+    "Apache-1.0",
+    "Apache-1.1",
+    "Apache-2.0",
+    "APSL-1.0",
+    "APSL-2.0",
+    "Artistic-1.0",
+    "Artistic-2.0",
+    "Beerware",
+    "BlueOak-1.0.0",
+    "BSD-1-Clause",
+    "BSD-2-Clause",
+    "BSD-2-Clause-Patent",
+    "BSD-3-Clause",
+    "BSD-3-Clause-Clear",
+    "BSD-3-Clause-LBNL",
+    "BSD-4-Clause",
+    "BSL-1.0",
+    "BUSL-1.1",
+    "CC-BY-1.0",
+    "CC-BY-2.0",
+    "CC-BY-3.0",
+    "CC-BY-4.0",
+    "CC-BY-NC-4.0",
+    "CC-BY-NC-ND-4.0",
+    "CC-BY-NC-SA-4.0",
+    "CC-BY-ND-4.0",
+    "CC-BY-SA-3.0",
+    "CC-BY-SA-4.0",
+    "CC0-1.0",
+    "CDDL-1.0",
+    "CDDL-1.1",
+    "CECILL-2.1",
+    "CERN-OHL-P-2.0",
+    "CERN-OHL-S-2.0",
+    "CERN-OHL-W-2.0",
+    "CPAL-1.0",
+    "CPL-1.0",
+    "ECL-2.0",
+    "EFL-2.0",
+    "Elastic-2.0",
+    "EPL-1.0",
+    "EPL-2.0",
+    "EUPL-1.0",
+    "EUPL-1.1",
+    "EUPL-1.2",
+    "GFDL-1.2-only",
+    "GFDL-1.2-or-later",
+    "GFDL-1.3-only",
+    "GFDL-1.3-or-later",
+    "GPL-1.0",
+    "GPL-1.0-only",
+    "GPL-1.0-or-later",
+    "GPL-2.0",
+    "GPL-2.0-only",
+    "GPL-2.0-or-later",
+    "GPL-3.0",
+    "GPL-3.0-only",
+    "GPL-3.0-or-later",
+    "Hippocratic-2.1",
+    "ISC",
+    "JSON",
+    "LGPL-2.0",
+    "LGPL-2.0-only",
+    "LGPL-2.0-or-later",
+    "LGPL-2.1",
+    "LGPL-2.1-only",
+    "LGPL-2.1-or-later",
+    "LGPL-3.0",
+    "LGPL-3.0-only",
+    "LGPL-3.0-or-later",
+    "LPPL-1.3c",
+    "MIT",
+    "MIT-0",
+    "MPL-1.0",
+    "MPL-1.1",
+    "MPL-2.0",
+    "MPL-2.0-no-copyleft-exception",
+    "MS-PL",
+    "MS-RL",
+    "MulanPSL-2.0",
+    "NCSA",
+    "ODbL-1.0",
+    "OFL-1.1",
+    "OpenSSL",
+    "OSL-3.0",
+    "Parity-7.0.0",
+    "PolyForm-Noncommercial-1.0.0",
+    "PolyForm-Small-Business-1.0.0",
+    "PostgreSQL",
+    "Python-2.0",
+    "Ruby",
+    "Sleepycat",
+    "SSPL-1.0",
+    "Unicode-DFS-2016",
+    "Unlicense",
+    "UNLICENSED",
+    "UPL-1.0",
+    "Vim",
+    "W3C",
+    "WTFPL",
+    "X11",
+    "Zlib",
+    "ZPL-2.0",
+    "ZPL-2.1",
+];
+
+/// License exceptions from the SPDX license exception list.
+static EXCEPTIONS: &[&str] = &[
+    "389-exception",
+    "Autoconf-exception-3.0",
+    "Bison-exception-2.2",
+    "Classpath-exception-2.0",
+    "eCos-exception-2.0",
+    "Font-exception-2.0",
+    "freertos-exception-2.0",
+    "GCC-exception-2.0",
+    "GCC-exception-3.1",
+    "GPL-3.0-linking-exception",
+    "GPL-3.0-linking-source-exception",
+    "i2p-gpl-java-exception",
+    "LGPL-3.0-linking-exception",
+    "Libtool-exception",
+    "Linux-syscall-note",
+    "LLVM-exception",
+    "OCaml-LGPL-linking-exception",
+    "OpenJDK-assembly-exception-1.0",
+    "Qt-LGPL-exception-1.1",
+    "Swift-exception",
+    "u-boot-exception-2.0",
+    "Universal-FOSS-exception-1.0",
+    "WxWindows-exception-3.1",
+];
@@ -39,6 +39,13 @@ pub fn print_ast_stats(ast: &ast::SourceUnit<'_>, title: &str, prefix: &str) {
     collector.print(title, prefix)
 }
 
+/// Returns the number of nodes in the AST, as counted in the AST stats.
+pub fn count_ast_nodes(ast: &ast::SourceUnit<'_>) -> usize {
+    let mut collector = StatCollector { nodes: FxHashMap::default(), seen: FxHashSet::default() };
+    collector.visit_source_unit(ast);
+    collector.nodes.values().map(|node| node.stats.count).sum()
+}
+
 impl StatCollector {
     // Record a top-level node.
     fn record<T: ?Sized>(&mut self, label: &'static str, id: Option<ItemId>, val: &T) {
@@ -356,7 +363,8 @@ impl<'ast> Visit<'ast> for StatCollector {
                 Try,
                 UncheckedBlock,
                 While,
-                Placeholder
+                Placeholder,
+                Err
             ]
         );
         self.walk_stmt(stmt)
@@ -525,7 +533,7 @@ impl<'ast> Visit<'ast> for StatCollector {
 
     fn visit_doc_comment(
         &mut self,
-        doc_comment: &'ast ast::DocComment,
+        doc_comment: &'ast ast::DocComment<'ast>,
     ) -> ControlFlow<Self::BreakValue> {
         self.record("DocComment", None, doc_comment);
         // Don't visit span field since it isn't boxed
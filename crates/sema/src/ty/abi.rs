@@ -2,6 +2,7 @@ use super::{Gcx, Ty, TyKind};
 use crate::hir;
 use alloy_json_abi as json;
 use solar_ast::ElementaryType;
+use solar_data_structures::map::FxIndexSet;
 use std::{fmt, ops::ControlFlow};
 
 impl<'gcx> Gcx<'gcx> {
@@ -41,13 +42,11 @@ impl<'gcx> Gcx<'gcx> {
         for f in self.interface_functions(id) {
             items.push(self.function_abi(f.id).into());
         }
-        // TODO: Does not include referenced items.
-        // See solc `interfaceEvents` and `interfaceErrors`.
-        for item in self.hir.contract_item_ids(id) {
+        for item in self.interface_events_and_errors(id) {
             match item {
                 hir::ItemId::Event(id) => items.push(self.event_abi(id).into()),
                 hir::ItemId::Error(id) => items.push(self.error_abi(id).into()),
-                _ => {}
+                _ => unreachable!(),
             }
         }
 
@@ -61,6 +60,28 @@ impl<'gcx> Gcx<'gcx> {
         items
     }
 
+    /// Returns the events and errors that are part of the interface of the given contract.
+    ///
+    /// These are the ones declared in the contract and its bases, and the ones emitted or reverted
+    /// with in their functions. See solc `interfaceEvents` and `interfaceErrors`.
+    // TODO: Does not include items referenced through calls to library and free functions.
+    pub fn interface_events_and_errors(self, id: hir::ContractId) -> FxIndexSet<hir::ItemId> {
+        let mut items = FxIndexSet::default();
+        items.extend(
+            self.hir
+                .contract_item_ids(id)
+                .filter(|item| matches!(item, hir::ItemId::Event(_) | hir::ItemId::Error(_))),
+        );
+        for &base in self.hir.contract(id).linearized_bases {
+            for f in self.hir.contract(base).all_functions() {
+                if let Some(body) = self.hir.function(f).body {
+                    collect_emitted_items(&self.hir, body, &mut items);
+                }
+            }
+        }
+        items
+    }
+
     fn function_abi(self, id: hir::FunctionId) -> json::Function {
         let f = self.hir.function(id);
         json::Function {
@@ -133,6 +154,64 @@ impl<'gcx> Gcx<'gcx> {
     }
 }
 
+/// Collects the events emitted and the errors reverted with in `block`.
+fn collect_emitted_items(
+    hir: &hir::Hir<'_>,
+    block: hir::Block<'_>,
+    items: &mut FxIndexSet<hir::ItemId>,
+) {
+    for stmt in block {
+        match &stmt.kind {
+            hir::StmtKind::Emit(res, args) | hir::StmtKind::Revert(res, args) => {
+                if let Some(item) = resolve_emitted_item(hir, res, args) {
+                    items.insert(item);
+                }
+            }
+            hir::StmtKind::Block(block)
+            | hir::StmtKind::UncheckedBlock(block)
+            | hir::StmtKind::Loop(block, _) => collect_emitted_items(hir, block, items),
+            hir::StmtKind::If(_, then, else_) => {
+                collect_emitted_items(hir, std::slice::from_ref(*then), items);
+                if let Some(else_) = else_ {
+                    collect_emitted_items(hir, std::slice::from_ref(*else_), items);
+                }
+            }
+            hir::StmtKind::Try(try_) => {
+                collect_emitted_items(hir, try_.block, items);
+                for catch in try_.catch {
+                    collect_emitted_items(hir, catch.block, items);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Returns the event or error that an `emit` or `revert` statement refers to.
+///
+/// Overloaded events are disambiguated by the number of arguments.
+fn resolve_emitted_item(
+    hir: &hir::Hir<'_>,
+    res: &[hir::Res],
+    args: &hir::CallArgs<'_>,
+) -> Option<hir::ItemId> {
+    let n_params = |item: &hir::ItemId| match *item {
+        hir::ItemId::Event(id) => Some(hir.event(id).parameters.len()),
+        hir::ItemId::Error(id) => Some(hir.error(id).parameters.len()),
+        _ => None,
+    };
+    let mut candidates = res.iter().filter_map(|res| match res {
+        hir::Res::Item(item) => Some(*item),
+        _ => None,
+    });
+    if let [_] = res {
+        return candidates.find(|item| n_params(item).is_some());
+    }
+    let mut matching = candidates.filter(|item| n_params(item) == Some(args.len()));
+    let item = matching.next()?;
+    matching.next().is_none().then_some(item)
+}
+
 fn json_state_mutability(s: hir::StateMutability) -> json::StateMutability {
     match s {
         hir::StateMutability::Pure => json::StateMutability::Pure,
@@ -213,11 +292,17 @@ impl<'gcx, W: fmt::Write> TyAbiPrinter<'gcx, W> {
                 self.print(ty)?;
                 write!(self.buf, "[{len}]")
             }
+            TyKind::Mapping(key, value) => {
+                self.buf.write_str("mapping(")?;
+                self.print(key)?;
+                self.buf.write_str(" => ")?;
+                self.print(value)?;
+                self.buf.write_str(")")
+            }
 
             TyKind::StringLiteral(..)
             | TyKind::IntLiteral(_)
             | TyKind::Tuple(_)
-            | TyKind::Mapping(..)
             | TyKind::Error(..)
             | TyKind::Event(..)
             | TyKind::Module(_)
@@ -246,26 +331,26 @@ impl<'gcx, W: fmt::Write> TyAbiPrinter<'gcx, W> {
 /// This is mainly used in the `internalType` field of the ABI.
 ///
 /// Example: https://github.com/ethereum/solidity/blob/9d7cc42bc1c12bb43e9dccf8c6c36833fdfcbbca/libsolidity/ast/Types.cpp#L2352-L2358
-struct TySolcPrinter<'gcx, W> {
+pub(super) struct TySolcPrinter<'gcx, W> {
     gcx: Gcx<'gcx>,
     buf: W,
     data_locations: bool,
 }
 
 impl<'gcx, W: fmt::Write> TySolcPrinter<'gcx, W> {
-    fn new(gcx: Gcx<'gcx>, buf: W) -> Self {
+    pub(super) fn new(gcx: Gcx<'gcx>, buf: W) -> Self {
         Self { gcx, buf, data_locations: false }
     }
 
     /// Whether to print data locations for reference types.
     ///
     /// Default: `false`.
-    fn data_locations(mut self, yes: bool) -> Self {
+    pub(super) fn data_locations(mut self, yes: bool) -> Self {
         self.data_locations = yes;
         self
     }
 
-    fn print(&mut self, ty: Ty<'gcx>) -> fmt::Result {
+    pub(super) fn print(&mut self, ty: Ty<'gcx>) -> fmt::Result {
         match ty.kind {
             TyKind::Elementary(ty) => {
                 ty.write_abi_str(&mut self.buf)?;
@@ -300,11 +385,17 @@ impl<'gcx, W: fmt::Write> TySolcPrinter<'gcx, W> {
                 self.print(ty)?;
                 write!(self.buf, "[{len}]")
             }
+            TyKind::Mapping(key, value) => {
+                self.buf.write_str("mapping(")?;
+                self.print(key)?;
+                self.buf.write_str(" => ")?;
+                self.print(value)?;
+                self.buf.write_str(")")
+            }
 
             TyKind::StringLiteral(..)
             | TyKind::IntLiteral(_)
             | TyKind::Tuple(_)
-            | TyKind::Mapping(..)
             | TyKind::Error(..)
             | TyKind::Event(..)
             | TyKind::Module(_)
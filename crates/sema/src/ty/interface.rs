@@ -0,0 +1,231 @@
+use super::{Gcx, Ty, TyKind};
+use crate::hir;
+use solar_ast::{DataLocation, StateMutability};
+use solar_data_structures::map::FxIndexSet;
+use solar_interface::Ident;
+
+impl<'gcx> Gcx<'gcx> {
+    /// Returns the source code of an interface named `I{name}` that declares the external
+    /// interface of the given contract.
+    ///
+    /// The interface contains the public and external functions of the contract and its bases,
+    /// the events and errors of [`interface_events_and_errors`](Self::interface_events_and_errors),
+    /// and declarations of the structs, enums, and user-defined value types used in their
+    /// signatures. Contract types are replaced with `address`.
+    pub fn contract_interface(self, id: hir::ContractId) -> String {
+        let c = self.hir.contract(id);
+        let mut printer = InterfacePrinter { gcx: self, types: FxIndexSet::default() };
+
+        let mut events = String::new();
+        let mut errors = String::new();
+        for item in self.interface_events_and_errors(id) {
+            match item {
+                hir::ItemId::Event(id) => {
+                    let event = self.hir.event(id);
+                    events.push_str(&format!("    event {}(", event.name));
+                    printer.print_event_params(&mut events, id);
+                    events.push(')');
+                    if event.anonymous {
+                        events.push_str(" anonymous");
+                    }
+                    events.push_str(";\n");
+                }
+                hir::ItemId::Error(id) => {
+                    errors.push_str(&format!("    error {}(", self.hir.error(id).name));
+                    printer.print_params(&mut errors, item, None);
+                    errors.push_str(");\n");
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        let mut functions = String::new();
+        let special = [c.fallback, c.receive];
+        let ordinary = self.interface_functions(id).all().iter().map(|f| f.id);
+        for f_id in special.into_iter().flatten().chain(ordinary) {
+            let f = self.hir.function(f_id);
+            functions.push_str("    ");
+            match f.name {
+                Some(name) if f.kind.is_ordinary() => {
+                    functions.push_str(&format!("function {name}("));
+                }
+                _ => functions.push_str(&format!("{}(", f.kind)),
+            }
+            printer.print_params(&mut functions, f_id.into(), Some(DataLocation::Calldata));
+            functions.push_str(") external");
+            if f.state_mutability != StateMutability::NonPayable {
+                functions.push_str(&format!(" {}", f.state_mutability));
+            }
+            if !f.returns.is_empty() {
+                functions.push_str(" returns (");
+                printer.print_returns(&mut functions, f_id);
+                functions.push(')');
+            }
+            functions.push_str(";\n");
+        }
+
+        // Struct declarations can add more types to declare, so this must be done last.
+        let mut types = String::new();
+        let mut i = 0;
+        while let Some(&item) = printer.types.get_index(i) {
+            printer.print_type_declaration(&mut types, item);
+            i += 1;
+        }
+
+        let mut s = format!("interface I{} {{\n", c.name);
+        let groups = [types, events, errors, functions];
+        for (i, group) in groups.iter().filter(|group| !group.is_empty()).enumerate() {
+            if i > 0 {
+                s.push('\n');
+            }
+            s.push_str(group);
+        }
+        s.push_str("}\n");
+        s
+    }
+}
+
+/// Prints the signatures of an interface, collecting the user-defined types that they use.
+struct InterfacePrinter<'gcx> {
+    gcx: Gcx<'gcx>,
+    /// The structs, enums, and user-defined value types to declare in the interface.
+    types: FxIndexSet<hir::ItemId>,
+}
+
+impl<'gcx> InterfacePrinter<'gcx> {
+    /// Prints the parameters of a function-like item, with the given data location for reference
+    /// types.
+    fn print_params(&mut self, s: &mut String, id: hir::ItemId, loc: Option<DataLocation>) {
+        let params = self.gcx.item_parameters(id);
+        let tys = self.gcx.item_parameter_types(id);
+        for (i, (&ty, &param)) in tys.iter().zip(params).enumerate() {
+            if i > 0 {
+                s.push_str(", ");
+            }
+            self.print_param(s, ty, loc, self.gcx.hir.variable(param).name);
+        }
+    }
+
+    /// Prints the return parameters of a function.
+    fn print_returns(&mut self, s: &mut String, id: hir::FunctionId) {
+        let TyKind::FnPtr(f) = self.gcx.type_of_item(id.into()).kind else { unreachable!() };
+        let returns = self.gcx.hir.function(id).returns;
+        for (i, (&ty, &ret)) in f.returns.iter().zip(returns).enumerate() {
+            if i > 0 {
+                s.push_str(", ");
+            }
+            self.print_param(s, ty, Some(DataLocation::Memory), self.gcx.hir.variable(ret).name);
+        }
+    }
+
+    fn print_event_params(&mut self, s: &mut String, id: hir::EventId) {
+        let event = self.gcx.hir.event(id);
+        let tys = self.gcx.item_parameter_types(id);
+        for (i, (&ty, &param)) in tys.iter().zip(event.parameters).enumerate() {
+            if i > 0 {
+                s.push_str(", ");
+            }
+            let param = self.gcx.hir.variable(param);
+            self.print_ty(s, ty);
+            if param.indexed {
+                s.push_str(" indexed");
+            }
+            if let Some(name) = param.name {
+                s.push_str(&format!(" {name}"));
+            }
+        }
+    }
+
+    fn print_param(
+        &mut self,
+        s: &mut String,
+        ty: Ty<'gcx>,
+        loc: Option<DataLocation>,
+        name: Option<Ident>,
+    ) {
+        self.print_ty(s, ty);
+        if let Some(loc) = loc {
+            if ty.peel_refs().is_reference_type() {
+                s.push_str(&format!(" {loc}"));
+            }
+        }
+        if let Some(name) = name {
+            s.push_str(&format!(" {name}"));
+        }
+    }
+
+    fn print_ty(&mut self, s: &mut String, ty: Ty<'gcx>) {
+        match ty.kind {
+            TyKind::Elementary(ty) => s.push_str(&ty.to_string()),
+            TyKind::Ref(ty, _) => self.print_ty(s, ty),
+            TyKind::DynArray(ty) => {
+                self.print_ty(s, ty);
+                s.push_str("[]");
+            }
+            TyKind::Array(ty, len) => {
+                self.print_ty(s, ty);
+                s.push_str(&format!("[{len}]"));
+            }
+            TyKind::Contract(_) => s.push_str("address"),
+            TyKind::FnPtr(f) => {
+                s.push_str("function(");
+                for (i, &ty) in f.parameters.iter().enumerate() {
+                    if i > 0 {
+                        s.push_str(", ");
+                    }
+                    self.print_param(s, ty, Some(DataLocation::Memory), None);
+                }
+                s.push_str(&format!(") {}", f.visibility));
+                if f.state_mutability != StateMutability::NonPayable {
+                    s.push_str(&format!(" {}", f.state_mutability));
+                }
+                if !f.returns.is_empty() {
+                    s.push_str(" returns (");
+                    for (i, &ty) in f.returns.iter().enumerate() {
+                        if i > 0 {
+                            s.push_str(", ");
+                        }
+                        self.print_param(s, ty, Some(DataLocation::Memory), None);
+                    }
+                    s.push(')');
+                }
+            }
+            TyKind::Struct(id) => self.print_user_defined(s, id.into()),
+            TyKind::Enum(id) => self.print_user_defined(s, id.into()),
+            TyKind::Udvt(_, id) => self.print_user_defined(s, id.into()),
+            _ => s.push_str(&ty.display(self.gcx).to_string()),
+        }
+    }
+
+    fn print_user_defined(&mut self, s: &mut String, id: hir::ItemId) {
+        self.types.insert(id);
+        s.push_str(self.gcx.item_name(id).as_str());
+    }
+
+    fn print_type_declaration(&mut self, s: &mut String, id: hir::ItemId) {
+        let name = self.gcx.item_name(id);
+        match id {
+            hir::ItemId::Struct(struct_id) => {
+                s.push_str(&format!("    struct {name} {{\n"));
+                for (ty, field) in self.gcx.item_fields(struct_id) {
+                    s.push_str("        ");
+                    self.print_param(s, ty, None, self.gcx.hir.variable(field).name);
+                    s.push_str(";\n");
+                }
+                s.push_str("    }\n");
+            }
+            hir::ItemId::Enum(enum_id) => {
+                let variants = self.gcx.hir.enumm(enum_id).variants;
+                let variants = variants.iter().map(|v| v.as_str()).collect::<Vec<_>>();
+                s.push_str(&format!("    enum {name} {{ {} }}\n", variants.join(", ")));
+            }
+            hir::ItemId::Udvt(_) => {
+                let TyKind::Udvt(ty, _) = self.gcx.type_of_item(id).kind else { unreachable!() };
+                s.push_str(&format!("    type {name} is "));
+                self.print_ty(s, ty);
+                s.push_str(";\n");
+            }
+            _ => unreachable!(),
+        }
+    }
+}
@@ -0,0 +1,420 @@
+use super::Gcx;
+use crate::hir;
+use alloy_primitives::{hex, keccak256, B256};
+use serde_json::{json, Map};
+use solar_data_structures::map::FxIndexSet;
+use solar_interface::config::MetadataHash;
+
+/// The size of a Swarm chunk.
+const SWARM_CHUNK_SIZE: usize = 4096;
+
+/// The maximum size of the data in an IPFS block. Larger files are split into multiple blocks.
+const IPFS_BLOCK_SIZE: usize = 256 * 1024;
+
+impl<'gcx> Gcx<'gcx> {
+    /// Returns the metadata JSON of the given contract.
+    ///
+    /// The JSON is minified and its keys are sorted, like in solc, since its hash is appended to
+    /// the runtime bytecode.
+    ///
+    /// Reference: <https://docs.soliditylang.org/en/latest/metadata.html>
+    pub fn contract_metadata(self, id: hir::ContractId) -> String {
+        let c = self.hir.contract(id);
+
+        // The source of the contract and all of its transitive imports.
+        let mut source_ids = FxIndexSet::default();
+        source_ids.insert(c.source);
+        let mut i = 0;
+        while let Some(&source_id) = source_ids.get_index(i) {
+            source_ids.extend(self.hir.source(source_id).imports.iter().map(|&(_, id)| id));
+            i += 1;
+        }
+        let mut sources = Map::new();
+        for source_id in source_ids {
+            let source = self.hir.source(source_id);
+            let file = &source.file;
+            // The source code may have been dropped after lowering.
+            let content: &[u8] = if self.sess.source_map().ensure_source_present(file) {
+                file.source_text().unwrap().as_bytes()
+            } else {
+                let msg = format!("`{}` was modified during compilation", file.name.display());
+                self.dcx().err(msg).emit();
+                &[]
+            };
+            let mut entry = json!({
+                "keccak256": keccak256(content).to_string(),
+                "urls": [
+                    format!("bzz-raw://{}", hex::encode(swarm_hash(content))),
+                    format!("dweb:/ipfs/{}", base58(&ipfs_hash(content))),
+                ],
+            });
+            if let Some(license) = source.license {
+                entry["license"] = license.into();
+            }
+            sources.insert(source.file.name.display().to_string(), entry);
+        }
+
+        let mut compilation_target = Map::new();
+        let file_name = self.hir.source(c.source).file.name.display().to_string();
+        compilation_target.insert(file_name, c.name.as_str().into());
+        let mut metadata_settings = Map::new();
+        let bytecode_hash = match self.sess.metadata_hash {
+            MetadataHash::Ipfs => None,
+            MetadataHash::Swarm => Some("bzzr1"),
+            MetadataHash::None => Some("none"),
+        };
+        if let Some(bytecode_hash) = bytecode_hash {
+            metadata_settings.insert("bytecodeHash".into(), bytecode_hash.into());
+        }
+        if !self.sess.cbor_metadata {
+            metadata_settings.insert("appendCBOR".into(), false.into());
+        }
+
+        let mut optimizer =
+            json!({ "enabled": self.sess.optimize, "runs": self.sess.optimizer_runs });
+        if let Some(steps) = &self.sess.yul_optimizations {
+            optimizer["details"] = json!({ "yulDetails": { "optimizerSteps": steps } });
+        }
+
+        let libraries = self
+            .sess
+            .libraries
+            .iter()
+            .map(|(name, address)| (name.clone(), format!("0x{}", hex::encode(address)).into()))
+            .collect::<serde_json::Map<_, _>>();
+
+        let metadata = json!({
+            "compiler": { "version": solar_interface::VERSION },
+            "language": "Solidity",
+            "output": {
+                "abi": self.contract_abi(id),
+                "devdoc": self.contract_devdoc(id),
+                "userdoc": self.contract_userdoc(id),
+            },
+            "settings": {
+                "compilationTarget": compilation_target,
+                "evmVersion": self.sess.evm_version.to_str(),
+                "libraries": libraries,
+                "metadata": metadata_settings,
+                "optimizer": optimizer,
+                "viaIR": true,
+            },
+            "sources": sources,
+            "version": 1,
+        });
+        serde_json::to_string(&metadata).unwrap()
+    }
+
+    /// Returns the CBOR-encoded hash of the given metadata and the compiler version, followed by
+    /// the length of the encoding as a big-endian `u16`.
+    ///
+    /// This is appended to the runtime bytecode.
+    pub fn metadata_cbor(self, metadata: &str) -> Vec<u8> {
+        let hash = match self.sess.metadata_hash {
+            MetadataHash::Ipfs => Some(("ipfs", ipfs_hash(metadata.as_bytes()))),
+            MetadataHash::Swarm => Some(("bzzr1", swarm_hash(metadata.as_bytes()).to_vec())),
+            MetadataHash::None => None,
+        };
+        cbor_metadata(hash, solar_interface::VERSION)
+    }
+}
+
+/// Encodes the metadata hash and the compiler version as a CBOR map, followed by its length.
+fn cbor_metadata(hash: Option<(&str, Vec<u8>)>, version: &str) -> Vec<u8> {
+    let mut cbor = Vec::with_capacity(64);
+    cbor_header(&mut cbor, 5, 1 + hash.is_some() as usize);
+    if let Some((key, hash)) = hash {
+        cbor_header(&mut cbor, 3, key.len());
+        cbor.extend_from_slice(key.as_bytes());
+        cbor_header(&mut cbor, 2, hash.len());
+        cbor.extend_from_slice(&hash);
+    }
+    cbor_header(&mut cbor, 3, "solar".len());
+    cbor.extend_from_slice(b"solar");
+    // Release versions are encoded as three bytes, like solc does.
+    let release =
+        version.split('.').map(|part| part.parse::<u8>().ok()).collect::<Option<Vec<_>>>();
+    match release {
+        Some(parts) if parts.len() == 3 => {
+            cbor_header(&mut cbor, 2, 3);
+            cbor.extend_from_slice(&parts);
+        }
+        _ => {
+            cbor_header(&mut cbor, 3, version.len());
+            cbor.extend_from_slice(version.as_bytes());
+        }
+    }
+    let len = u16::try_from(cbor.len()).unwrap();
+    cbor.extend_from_slice(&len.to_be_bytes());
+    cbor
+}
+
+/// Writes the header of a CBOR data item of the given major type and length.
+fn cbor_header(out: &mut Vec<u8>, major: u8, len: usize) {
+    let major = major << 5;
+    if len < 24 {
+        out.push(major | len as u8);
+    } else if let Ok(len) = u8::try_from(len) {
+        out.extend_from_slice(&[major | 24, len]);
+    } else {
+        let len = u16::try_from(len).unwrap();
+        out.push(major | 25);
+        out.extend_from_slice(&len.to_be_bytes());
+    }
+}
+
+/// Returns the Swarm hash of the given data, as `bzzr1`.
+///
+/// The data is split into chunks, which are hashed with the binary Merkle tree hash. The chunk
+/// hashes are then grouped into intermediate chunks, until a single root hash remains.
+fn swarm_hash(data: &[u8]) -> B256 {
+    if data.len() <= SWARM_CHUNK_SIZE {
+        return swarm_chunk_hash(data, data.len());
+    }
+    let mut level = data
+        .chunks(SWARM_CHUNK_SIZE)
+        .map(|chunk| (swarm_chunk_hash(chunk, chunk.len()), chunk.len()))
+        .collect::<Vec<_>>();
+    let branches = SWARM_CHUNK_SIZE / 32;
+    while level.len() > 1 {
+        level = level
+            .chunks(branches)
+            .map(|children| {
+                let span = children.iter().map(|&(_, span)| span).sum();
+                let refs = children.iter().flat_map(|(hash, _)| hash.0).collect::<Vec<_>>();
+                (swarm_chunk_hash(&refs, span), span)
+            })
+            .collect();
+    }
+    level[0].0
+}
+
+/// Hashes a Swarm chunk that spans `span` bytes of the original data.
+fn swarm_chunk_hash(data: &[u8], span: usize) -> B256 {
+    let mut padded = [0; SWARM_CHUNK_SIZE];
+    padded[..data.len()].copy_from_slice(data);
+    let mut preimage = [0; 40];
+    preimage[..8].copy_from_slice(&(span as u64).to_le_bytes());
+    preimage[8..].copy_from_slice(bmt_hash(&padded).as_slice());
+    keccak256(preimage)
+}
+
+/// Returns the binary Merkle tree hash of the given data, with 32-byte segments.
+fn bmt_hash(data: &[u8]) -> B256 {
+    if data.len() <= 64 {
+        return keccak256(data);
+    }
+    let (left, right) = data.split_at(data.len() / 2);
+    let mut preimage = [0; 64];
+    preimage[..32].copy_from_slice(bmt_hash(left).as_slice());
+    preimage[32..].copy_from_slice(bmt_hash(right).as_slice());
+    keccak256(preimage)
+}
+
+/// Returns the IPFS multihash of the given data, as it would be added with `ipfs add` with the
+/// default options.
+///
+/// The data is stored as a UnixFS file in a `dag-pb` node, which is hashed with SHA-256.
+fn ipfs_hash(data: &[u8]) -> Vec<u8> {
+    if data.len() <= IPFS_BLOCK_SIZE {
+        return multihash(&ipfs_leaf(data));
+    }
+
+    let leaves = data.chunks(IPFS_BLOCK_SIZE).map(ipfs_leaf).collect::<Vec<_>>();
+    let mut node = Vec::new();
+    for leaf in &leaves {
+        let mut link = Vec::new();
+        protobuf_bytes(&mut link, 1, &multihash(leaf));
+        protobuf_bytes(&mut link, 2, b"");
+        protobuf_varint(&mut link, 3, leaf.len() as u64);
+        protobuf_bytes(&mut node, 2, &link);
+    }
+    let mut unixfs = Vec::new();
+    protobuf_varint(&mut unixfs, 1, 2);
+    protobuf_varint(&mut unixfs, 3, data.len() as u64);
+    for chunk in data.chunks(IPFS_BLOCK_SIZE) {
+        protobuf_varint(&mut unixfs, 4, chunk.len() as u64);
+    }
+    protobuf_bytes(&mut node, 1, &unixfs);
+    multihash(&node)
+}
+
+/// Encodes a `dag-pb` node containing a UnixFS file with the given data.
+fn ipfs_leaf(data: &[u8]) -> Vec<u8> {
+    let mut unixfs = Vec::with_capacity(data.len() + 16);
+    // Type: File.
+    protobuf_varint(&mut unixfs, 1, 2);
+    if !data.is_empty() {
+        protobuf_bytes(&mut unixfs, 2, data);
+    }
+    protobuf_varint(&mut unixfs, 3, data.len() as u64);
+    let mut node = Vec::with_capacity(unixfs.len() + 8);
+    protobuf_bytes(&mut node, 1, &unixfs);
+    node
+}
+
+/// Returns the SHA-256 multihash of the given data.
+fn multihash(data: &[u8]) -> Vec<u8> {
+    let mut hash = vec![0x12, 0x20];
+    hash.extend_from_slice(&sha256(data));
+    hash
+}
+
+fn protobuf_varint(out: &mut Vec<u8>, field: u32, value: u64) {
+    varint(out, u64::from(field) << 3);
+    varint(out, value);
+}
+
+fn protobuf_bytes(out: &mut Vec<u8>, field: u32, bytes: &[u8]) {
+    varint(out, (u64::from(field) << 3) | 2);
+    varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+fn varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Encodes the given bytes in base58, with the Bitcoin alphabet used by IPFS.
+fn base58(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+    let zeros = bytes.iter().take_while(|&&b| b == 0).count();
+    // Little-endian base58 digits.
+    let mut digits = Vec::<u8>::with_capacity(bytes.len() * 138 / 100 + 1);
+    for &byte in &bytes[zeros..] {
+        let mut carry = u32::from(byte);
+        for digit in &mut digits {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    let mut s = String::with_capacity(zeros + digits.len());
+    s.extend(std::iter::repeat_n('1', zeros));
+    s.extend(digits.iter().rev().map(|&digit| ALPHABET[digit as usize] as char));
+    s
+}
+
+/// Returns the SHA-256 hash of the given data.
+fn sha256(data: &[u8]) -> [u8; 32] {
+    const K: [u32; 64] = [
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4,
+        0xab1c5ed5, 0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe,
+        0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f,
+        0x4a7484aa, 0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7,
+        0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc,
+        0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b,
+        0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116,
+        0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7,
+        0xc67178f2,
+    ];
+    let mut state: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&(data.len() as u64 * 8).to_be_bytes());
+
+    for block in message.chunks_exact(64) {
+        let mut w = [0u32; 64];
+        for (w, word) in w.iter_mut().zip(block.chunks_exact(4)) {
+            *w = u32::from_be_bytes(word.try_into().unwrap());
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h.wrapping_add(s1).wrapping_add(ch).wrapping_add(K[i]).wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (state, value) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+
+    let mut hash = [0; 32];
+    for (bytes, word) in hash.chunks_exact_mut(4).zip(state) {
+        bytes.copy_from_slice(&word.to_be_bytes());
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sha256_hashes() {
+        let cases: &[(&[u8], &str)] = &[
+            (b"", "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"),
+            (b"abc", "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"),
+            (
+                b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+                "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+            ),
+        ];
+        for &(data, expected) in cases {
+            assert_eq!(hex::encode(sha256(data)), expected);
+        }
+    }
+
+    #[test]
+    fn ipfs_hashes() {
+        assert_eq!(base58(&ipfs_hash(b"")), "QmbFMke1KXqnYyBBWxB74N4c5SBnJMVAiMNRcGu6x1AwQH");
+        assert_eq!(
+            base58(&ipfs_hash(b"hello world\n")),
+            "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o"
+        );
+    }
+
+    #[test]
+    fn base58_leading_zeros() {
+        assert_eq!(base58(&[]), "");
+        assert_eq!(base58(&[0, 0, 1]), "112");
+        assert_eq!(base58(&[0xff; 2]), "LUv");
+    }
+
+    #[test]
+    fn cbor() {
+        let hash = Some(("ipfs", vec![0x12, 0x20].into_iter().chain([0xab; 32]).collect()));
+        let cbor = cbor_metadata(hash, "0.8.28");
+        let mut expected = hex::decode("a264697066735822").unwrap();
+        expected.extend_from_slice(&[0x12, 0x20]);
+        expected.extend_from_slice(&[0xab; 32]);
+        expected.extend_from_slice(&hex::decode("65736f6c61724300081c0034").unwrap());
+        assert_eq!(cbor, expected);
+
+        let cbor = cbor_metadata(None, "0.1.0-dev");
+        assert_eq!(hex::encode(cbor), "a165736f6c617269302e312e302d6465760011");
+    }
+}
@@ -1,6 +1,8 @@
 use crate::{
     ast_lowering::SymbolResolver,
     builtins::{members, Builtin},
+    call_graph::{self, Call},
+    cfg::Cfg,
     hir::{self, Hir},
 };
 use alloy_primitives::{keccak256, Selector, B256, U256};
@@ -14,7 +16,7 @@ use solar_data_structures::{
 };
 use solar_interface::{
     diagnostics::{DiagCtxt, ErrorGuaranteed},
-    Ident, Session, Span,
+    error_code, Ident, Session, Span,
 };
 use std::{
     fmt,
@@ -29,9 +31,19 @@ pub use abi::{TyAbiPrinter, TyAbiPrinterMode};
 mod common;
 pub use common::{CommonTypes, EachDataLoc};
 
+mod interface;
+
 mod interner;
 use interner::Interner;
 
+mod metadata;
+
+mod natspec;
+pub use natspec::{DevDoc, DevDocItem, UserDoc, UserDocItem};
+
+mod storage;
+pub use storage::{StorageLayout, StorageSlot, StorageType};
+
 #[allow(clippy::module_inception)]
 mod ty;
 pub use ty::{Ty, TyData, TyFlags, TyFnPtr, TyKind};
@@ -451,6 +463,16 @@ impl<'gcx> Gcx<'gcx> {
             hir::Res::Err(guar) => self.mk_ty_err(guar),
         }
     }
+
+    /// Returns the declarations named `name` in the top-level scope of the given source.
+    pub fn source_declarations(
+        self,
+        source: hir::SourceId,
+        name: Ident,
+    ) -> impl Iterator<Item = hir::Res> + use<'gcx> {
+        let decls = self.symbol_resolver.source_scopes[source].resolve(name).unwrap_or_default();
+        decls.iter().map(|decl| decl.res)
+    }
 }
 
 macro_rules! cached {
@@ -637,6 +659,18 @@ pub fn struct_field_types(gcx: _, id: hir::StructId) -> &'gcx [Ty<'gcx>] {
     gcx.mk_ty_iter(gcx.hir.strukt(id).fields.iter().map(|&f| gcx.type_of_item(f.into())))
 }
 
+/// Returns the calls made by the given function or modifier, in source order.
+///
+/// See [`CallGraph`](crate::call_graph::CallGraph) for the whole graph.
+pub fn function_calls(gcx: _, id: hir::FunctionId) -> &'gcx [Call] {
+    gcx.bump().alloc_vec(call_graph::function_calls(gcx, id))
+}
+
+/// Returns the control-flow graph of the body of the given function or modifier, if any.
+pub fn function_cfg(gcx: _, id: hir::FunctionId) -> Option<Cfg<'gcx>> {
+    gcx.hir.function(id).body.map(|body| Cfg::build(gcx.bump(), body))
+}
+
 /// Returns the recursiveness of the given struct.
 pub fn struct_recursiveness(gcx: _, id: hir::StructId) -> Recursiveness {
     use solar_data_structures::cycle::*;
@@ -684,7 +718,11 @@ pub fn struct_recursiveness(gcx: _, id: hir::StructId) -> Recursiveness {
         CycleDetectorResult::Break(Either::Left(guar)) => Recursiveness::Infinite(guar),
         CycleDetectorResult::Break(Either::Right(())) => Recursiveness::Recursive,
         CycleDetectorResult::Cycle(id) => Recursiveness::Infinite(
-            gcx.dcx().err("recursive struct definition").span(gcx.item_span(id)).emit()
+            gcx.dcx()
+                .err("recursive struct definition")
+                .code(error_code!(2046))
+                .span(gcx.item_span(id))
+                .emit(),
         ),
     }
 }
@@ -787,6 +825,18 @@ fn var_type<'gcx>(gcx: Gcx<'gcx>, var: &'gcx hir::Variable<'gcx>, ty: Ty<'gcx>)
                         "initialization of transient storage state variables is not supported";
                     gcx.dcx().err(msg).span(var.span).emit();
                 }
+                if has_reference_or_mapping_type {
+                    let msg = "transient storage is only supported for value types";
+                    gcx.dcx().err(msg).span(var.span).emit();
+                }
+                let evm_version = gcx.sess.evm_version;
+                if !evm_version.has_transient_storage() {
+                    let msg =
+                        "transient storage variables are not supported for EVM versions before \
+                         Cancun";
+                    let note = format!("you are currently compiling for `{evm_version}`");
+                    gcx.dcx().err(msg).span(var.span).note(note).emit();
+                }
                 Transient
             }
             Some(_) => unreachable!(),
@@ -0,0 +1,384 @@
+use super::Gcx;
+use crate::hir::{self, NatSpecItem, NatSpecKind};
+use serde::Serialize;
+use solar_interface::Ident;
+use std::{collections::BTreeMap, mem::discriminant};
+
+/// The version of the NatSpec output format.
+const NATSPEC_VERSION: u32 = 1;
+
+/// The user documentation of a contract, in the same format as solc's `userdoc` output.
+///
+/// Reference: <https://docs.soliditylang.org/en/latest/natspec-format.html#user-documentation>
+#[derive(Clone, Debug, Serialize)]
+pub struct UserDoc {
+    /// The documented errors, keyed by signature.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub errors: BTreeMap<String, Vec<UserDocItem>>,
+    /// The documented events, keyed by signature.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub events: BTreeMap<String, UserDocItem>,
+    /// Always `user`.
+    pub kind: &'static str,
+    /// The documented functions, keyed by signature, or `constructor`.
+    pub methods: BTreeMap<String, UserDocItem>,
+    /// The `@notice` of the contract.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notice: Option<String>,
+    /// The version of the output format.
+    pub version: u32,
+}
+
+/// A documented item in a [`UserDoc`].
+#[derive(Clone, Debug, Serialize)]
+pub struct UserDocItem {
+    /// The `@notice` of the item.
+    pub notice: String,
+}
+
+/// The developer documentation of a contract, in the same format as solc's `devdoc` output.
+///
+/// Reference: <https://docs.soliditylang.org/en/latest/natspec-format.html#developer-documentation>
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DevDoc {
+    /// The `@author` of the contract.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// The `@custom:*` tags of the contract, keyed by `custom:{name}`.
+    #[serde(flatten)]
+    pub custom: BTreeMap<String, String>,
+    /// The `@dev` of the contract.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    /// The documented errors, keyed by signature.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub errors: BTreeMap<String, Vec<DevDocItem>>,
+    /// The documented events, keyed by signature.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub events: BTreeMap<String, DevDocItem>,
+    /// Always `dev`.
+    pub kind: &'static str,
+    /// The documented functions, keyed by signature, or `constructor`.
+    pub methods: BTreeMap<String, DevDocItem>,
+    /// The documented state variables, keyed by name.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub state_variables: BTreeMap<String, DevDocItem>,
+    /// The `@title` of the contract.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// The version of the output format.
+    pub version: u32,
+}
+
+/// A documented item in a [`DevDoc`].
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct DevDocItem {
+    /// The `@custom:*` tags, keyed by `custom:{name}`.
+    #[serde(flatten)]
+    pub custom: BTreeMap<String, String>,
+    /// The `@dev` of the item.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    /// The `@param` tags, keyed by parameter name.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: BTreeMap<String, String>,
+    /// The `@return` of a state variable, if it has exactly one.
+    #[serde(rename = "return", skip_serializing_if = "Option::is_none")]
+    pub return_: Option<String>,
+    /// The `@return` tags, keyed by return parameter name, or `_{index}` if it is unnamed.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub returns: BTreeMap<String, String>,
+}
+
+impl DevDocItem {
+    fn new(docs: &[NatSpecItem]) -> Self {
+        let params = docs.iter().filter_map(|item| match item.kind {
+            NatSpecKind::Param { name } => Some((name.to_string(), item.content.to_string())),
+            _ => None,
+        });
+        Self {
+            custom: custom_tags(docs),
+            details: tag_content(docs, NatSpecKind::Dev),
+            params: params.collect(),
+            return_: None,
+            returns: BTreeMap::new(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.custom.is_empty()
+            && self.details.is_none()
+            && self.params.is_empty()
+            && self.return_.is_none()
+            && self.returns.is_empty()
+    }
+}
+
+impl<'gcx> Gcx<'gcx> {
+    /// Returns the user documentation of the given contract.
+    pub fn contract_userdoc(self, id: hir::ContractId) -> UserDoc {
+        let c = self.hir.contract(id);
+        let mut methods = BTreeMap::new();
+        if let Some(ctor) = c.ctor {
+            if let Some(notice) = tag_content(self.hir.function(ctor).docs, NatSpecKind::Notice) {
+                methods.insert("constructor".to_string(), UserDocItem { notice });
+            }
+        }
+        for f in self.interface_functions(id) {
+            let docs = self.resolved_docs(f.id.into());
+            if let Some(notice) = tag_content(&docs, NatSpecKind::Notice) {
+                methods
+                    .insert(self.item_signature(f.id.into()).to_string(), UserDocItem { notice });
+            }
+        }
+
+        let mut events = BTreeMap::new();
+        let mut errors = BTreeMap::<_, Vec<_>>::new();
+        for item in self.interface_events_and_errors(id) {
+            let Some(notice) = tag_content(self.item_docs(item), NatSpecKind::Notice) else {
+                continue;
+            };
+            let signature = self.item_signature(item).to_string();
+            match item {
+                hir::ItemId::Event(_) => _ = events.insert(signature, UserDocItem { notice }),
+                hir::ItemId::Error(_) => {
+                    errors.entry(signature).or_default().push(UserDocItem { notice })
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        UserDoc {
+            errors,
+            events,
+            kind: "user",
+            methods,
+            notice: tag_content(c.docs, NatSpecKind::Notice),
+            version: NATSPEC_VERSION,
+        }
+    }
+
+    /// Returns the developer documentation of the given contract.
+    pub fn contract_devdoc(self, id: hir::ContractId) -> DevDoc {
+        let c = self.hir.contract(id);
+        let mut methods = BTreeMap::new();
+        if let Some(ctor) = c.ctor {
+            let doc = DevDocItem::new(self.hir.function(ctor).docs);
+            if !doc.is_empty() {
+                methods.insert("constructor".to_string(), doc);
+            }
+        }
+        for f in self.interface_functions(id) {
+            let func = self.hir.function(f.id);
+            if func.is_getter() {
+                continue;
+            }
+            let docs = self.resolved_docs(f.id.into());
+            let mut doc = DevDocItem::new(&docs);
+            doc.returns = return_tags(&docs, self.return_names(func.returns));
+            if !doc.is_empty() {
+                methods.insert(self.item_signature(f.id.into()).to_string(), doc);
+            }
+        }
+
+        let mut state_variables = BTreeMap::new();
+        for var_id in c.variables() {
+            let var = self.hir.variable(var_id);
+            let Some(name) = var.name else { continue };
+            let docs = self.resolved_docs(var_id.into());
+            let mut doc = DevDocItem::new(&docs);
+            if docs.iter().filter(|item| item.kind == NatSpecKind::Return).count() == 1 {
+                doc.return_ = tag_content(&docs, NatSpecKind::Return);
+            }
+            if let Some(getter) = var.getter {
+                let returns = self.return_names(self.hir.function(getter).returns);
+                doc.returns = return_tags(&docs, returns);
+            }
+            if !doc.is_empty() {
+                state_variables.insert(name.to_string(), doc);
+            }
+        }
+
+        let mut events = BTreeMap::new();
+        let mut errors = BTreeMap::<_, Vec<_>>::new();
+        for item in self.interface_events_and_errors(id) {
+            let doc = DevDocItem::new(self.item_docs(item));
+            if doc.is_empty() {
+                continue;
+            }
+            let signature = self.item_signature(item).to_string();
+            match item {
+                hir::ItemId::Event(_) => _ = events.insert(signature, doc),
+                hir::ItemId::Error(_) => errors.entry(signature).or_default().push(doc),
+                _ => unreachable!(),
+            }
+        }
+
+        DevDoc {
+            author: tag_content(c.docs, NatSpecKind::Author),
+            custom: custom_tags(c.docs),
+            details: tag_content(c.docs, NatSpecKind::Dev),
+            errors,
+            events,
+            kind: "dev",
+            methods,
+            state_variables,
+            title: tag_content(c.docs, NatSpecKind::Title),
+            version: NATSPEC_VERSION,
+        }
+    }
+
+    /// Returns the NatSpec documentation of the given item, as written in the source.
+    ///
+    /// Getter functions return the documentation of their variable.
+    fn item_docs(self, id: hir::ItemId) -> &'gcx [NatSpecItem] {
+        match id {
+            hir::ItemId::Contract(id) => self.hir.contract(id).docs,
+            hir::ItemId::Function(id) => {
+                let f = self.hir.function(id);
+                match f.gettee {
+                    Some(var) => self.hir.variable(var).docs,
+                    None => f.docs,
+                }
+            }
+            hir::ItemId::Variable(id) => self.hir.variable(id).docs,
+            hir::ItemId::Event(id) => self.hir.event(id).docs,
+            hir::ItemId::Error(id) => self.hir.error(id).docs,
+            hir::ItemId::Struct(_) | hir::ItemId::Enum(_) | hir::ItemId::Udvt(_) => &[],
+        }
+    }
+
+    /// Returns the NatSpec documentation of the given function or variable, including the tags
+    /// that it inherits from the base function it overrides.
+    ///
+    /// Tags are inherited from the base function referenced by `@inheritdoc`, or from the
+    /// overridden function if the function is not documented at all. Tags that are already
+    /// present are not inherited, and neither are custom tags.
+    fn resolved_docs(self, id: hir::ItemId) -> Vec<NatSpecItem> {
+        let id = match id {
+            hir::ItemId::Function(f) => match self.hir.function(f).gettee {
+                Some(var) => var.into(),
+                None => id,
+            },
+            _ => id,
+        };
+        let docs = self.item_docs(id);
+        let inheritdoc = docs.iter().find_map(|item| match item.kind {
+            NatSpecKind::Inheritdoc { contract } => Some(contract),
+            _ => None,
+        });
+        let base = match (inheritdoc, id) {
+            (Some(contract), _) => self.base_function(id, Some(contract)),
+            (None, hir::ItemId::Function(f))
+                if docs.is_empty() && self.hir.function(f).override_ =>
+            {
+                self.base_function(id, None)
+            }
+            _ => None,
+        };
+
+        let mut resolved = docs.to_vec();
+        if let Some(base) = base {
+            for item in self.resolved_docs(base.into()) {
+                let inherit = !matches!(
+                    item.kind,
+                    NatSpecKind::Inheritdoc { .. } | NatSpecKind::Custom { .. }
+                ) && !docs
+                    .iter()
+                    .any(|doc| discriminant(&doc.kind) == discriminant(&item.kind));
+                if inherit {
+                    resolved.push(item);
+                }
+            }
+        }
+        resolved
+    }
+
+    /// Returns the external function of a base contract that the given function or public state
+    /// variable overrides, optionally only looking in the base contract named `contract`.
+    fn base_function(self, id: hir::ItemId, contract: Option<Ident>) -> Option<hir::FunctionId> {
+        let (contract_id, name, f) = match id {
+            hir::ItemId::Function(f) => {
+                let func = self.hir.function(f);
+                (func.contract?, func.name?, f)
+            }
+            hir::ItemId::Variable(v) => {
+                let var = self.hir.variable(v);
+                (var.contract?, var.name?, var.getter?)
+            }
+            _ => return None,
+        };
+        if !self.hir.function(f).is_part_of_external_interface() {
+            return None;
+        }
+        let selector = self.function_selector(f);
+        let bases = &self.hir.contract(contract_id).linearized_bases[1..];
+        bases
+            .iter()
+            .map(|&base| self.hir.contract(base))
+            .filter(|base| contract.is_none_or(|contract| base.name.name == contract.name))
+            .flat_map(|base| base.functions())
+            .find(|&base_f| {
+                let base_func = self.hir.function(base_f);
+                base_func.name.is_some_and(|base_name| base_name.name == name.name)
+                    && base_func.is_part_of_external_interface()
+                    && self.function_selector(base_f) == selector
+            })
+    }
+
+    fn return_names(
+        self,
+        returns: &'gcx [hir::VariableId],
+    ) -> impl Iterator<Item = Option<Ident>> + use<'gcx> {
+        returns.iter().map(move |&ret| self.hir.variable(ret).name)
+    }
+}
+
+/// Returns the concatenated content of all the tags of the given kind, if any.
+fn tag_content(docs: &[NatSpecItem], kind: NatSpecKind) -> Option<String> {
+    let mut content = None::<String>;
+    for item in docs.iter().filter(|item| item.kind == kind) {
+        content.get_or_insert_default().push_str(item.content.as_str());
+    }
+    content
+}
+
+/// Returns the content of the `@custom:*` tags, keyed by `custom:{name}`.
+fn custom_tags(docs: &[NatSpecItem]) -> BTreeMap<String, String> {
+    let mut custom = BTreeMap::<String, String>::new();
+    for item in docs {
+        if let NatSpecKind::Custom { name } = item.kind {
+            custom.entry(format!("custom:{name}")).or_default().push_str(item.content.as_str());
+        }
+    }
+    custom
+}
+
+/// Returns the content of the `@return` tags, keyed by the name of the return parameter they
+/// document, or `_{index}` if it is unnamed.
+///
+/// The name of named return parameters is stripped from the start of the content.
+fn return_tags(
+    docs: &[NatSpecItem],
+    names: impl Iterator<Item = Option<Ident>>,
+) -> BTreeMap<String, String> {
+    let returns = docs.iter().filter(|item| item.kind == NatSpecKind::Return);
+    returns
+        .zip(names)
+        .enumerate()
+        .map(|(i, (item, name))| {
+            let content = item.content.as_str();
+            match name {
+                Some(name) => {
+                    let content = content
+                        .split_once(char::is_whitespace)
+                        .filter(|&(first, _)| first == name.as_str())
+                        .map_or(content, |(_, rest)| rest.trim_start());
+                    (name.to_string(), content.to_string())
+                }
+                None => (format!("_{i}"), content.to_string()),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,372 @@
+use super::{abi::TySolcPrinter, Gcx, Ty, TyKind};
+use crate::hir;
+use alloy_primitives::U256;
+use serde::Serialize;
+use solar_ast::{DataLocation, ElementaryType, StateMutability, Visibility};
+use std::{collections::BTreeMap, fmt::Write};
+
+/// The storage layout of a contract, in the same format as solc's `storageLayout` output.
+///
+/// Reference: <https://docs.soliditylang.org/en/latest/internals/layout_in_storage.html#json-output>
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct StorageLayout {
+    /// The state variables stored in storage, in order of their slot.
+    pub storage: Vec<StorageSlot>,
+    /// The types of the variables in `storage`, keyed by type identifier.
+    pub types: BTreeMap<String, StorageType>,
+}
+
+/// A state variable or struct member in a [`StorageLayout`].
+#[derive(Clone, Debug, Serialize)]
+pub struct StorageSlot {
+    /// The fully qualified name of the contract.
+    pub contract: String,
+    /// The name of the variable.
+    pub label: String,
+    /// The offset in bytes within the slot.
+    pub offset: u8,
+    /// The storage slot, as a decimal string.
+    pub slot: String,
+    /// The type identifier, used as a key in [`StorageLayout::types`].
+    #[serde(rename = "type")]
+    pub ty: String,
+}
+
+/// A type in a [`StorageLayout`].
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageType {
+    /// The element type of arrays.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+    /// How the data is encoded: `inplace`, `mapping`, `dynamic_array`, or `bytes`.
+    pub encoding: &'static str,
+    /// The key type of mappings.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// The canonical type name.
+    pub label: String,
+    /// The members of structs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub members: Option<Vec<StorageSlot>>,
+    /// The number of bytes used, as a decimal string.
+    pub number_of_bytes: String,
+    /// The value type of mappings.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+impl<'gcx> Gcx<'gcx> {
+    /// Returns the storage layout of the given contract.
+    ///
+    /// Constant, immutable and transient state variables are not stored in storage, and are
+    /// therefore not included.
+    ///
+    /// Note that the numbers in type identifiers are internal IDs rather than AST IDs.
+    pub fn storage_layout(self, id: hir::ContractId) -> StorageLayout {
+        let mut cx = StorageLayoutCx {
+            gcx: self,
+            contract: self.contract_fully_qualified_name(id).to_string(),
+            types: BTreeMap::new(),
+        };
+        let vars = self.storage_variables(id, false).collect::<Vec<_>>();
+        let (storage, _) = cx.layout(&vars);
+        StorageLayout { storage, types: cx.types }
+    }
+
+    /// Returns the storage slot and byte offset of each state variable of the given contract that
+    /// is stored in storage.
+    pub fn storage_slots(
+        self,
+        id: hir::ContractId,
+    ) -> impl Iterator<Item = (hir::VariableId, U256, u8)> + use<'gcx> {
+        self.variable_slots(self.storage_variables(id, false).collect())
+    }
+
+    /// Returns the transient storage slot and byte offset of each transient state variable of the
+    /// given contract.
+    ///
+    /// Transient storage is laid out like storage, independently of it.
+    pub fn transient_storage_slots(
+        self,
+        id: hir::ContractId,
+    ) -> impl Iterator<Item = (hir::VariableId, U256, u8)> + use<'gcx> {
+        self.variable_slots(self.storage_variables(id, true).collect())
+    }
+
+    fn variable_slots(
+        self,
+        vars: Vec<(Ty<'gcx>, hir::VariableId)>,
+    ) -> impl Iterator<Item = (hir::VariableId, U256, u8)> + use<'gcx> {
+        let (offsets, _) = slot_offsets(self, vars.iter().map(|&(ty, _)| ty));
+        std::iter::zip(vars, offsets).map(|((_, var), (slot, offset))| (var, slot, offset))
+    }
+
+    /// Returns the state variables of the given contract that are stored in storage, or in
+    /// transient storage if `transient` is `true`, in order.
+    fn storage_variables(
+        self,
+        id: hir::ContractId,
+        transient: bool,
+    ) -> impl Iterator<Item = (Ty<'gcx>, hir::VariableId)> {
+        // Variables of base contracts come first.
+        let bases = self.hir.contract(id).linearized_bases.iter().rev();
+        let vars = bases.flat_map(move |&base| self.hir.contract(base).variables());
+        vars.filter(move |&var| {
+            let v = self.hir.variable(var);
+            !v.is_constant()
+                && !v.is_immutable()
+                && (v.data_location == Some(DataLocation::Transient)) == transient
+        })
+        .map(move |var| (self.type_of_item(var.into()), var))
+    }
+
+    /// Returns the number of bytes that a value of the given type occupies in storage.
+    ///
+    /// Values smaller than 32 bytes can be packed together in a single slot.
+    fn storage_bytes(self, ty: Ty<'gcx>) -> u8 {
+        match ty.peel_refs().kind {
+            TyKind::Elementary(ty) => match ty {
+                ElementaryType::Address(_) => 20,
+                ElementaryType::Bool => 1,
+                ElementaryType::String | ElementaryType::Bytes => 32,
+                ElementaryType::Fixed(size, _)
+                | ElementaryType::UFixed(size, _)
+                | ElementaryType::Int(size)
+                | ElementaryType::UInt(size)
+                | ElementaryType::FixedBytes(size) => size.bytes(),
+            },
+            TyKind::Contract(_) => 20,
+            TyKind::FnPtr(f) => {
+                if f.visibility == Visibility::External {
+                    24
+                } else {
+                    8
+                }
+            }
+            TyKind::Enum(id) => {
+                let n_variants = self.hir.enumm(id).variants.len();
+                if n_variants <= 1 {
+                    1
+                } else {
+                    (n_variants - 1).ilog2() as u8 / 8 + 1
+                }
+            }
+            TyKind::Udvt(ty, _) => self.storage_bytes(ty),
+            _ => 32,
+        }
+    }
+
+    /// Returns the number of slots that a value of the given type occupies in storage.
+    fn storage_size(self, ty: Ty<'gcx>) -> U256 {
+        match ty.peel_refs().kind {
+            TyKind::Array(elem, len) => {
+                let elem_bytes = self.storage_bytes(elem);
+                if elem_bytes <= 16 {
+                    let per_slot = U256::from(32 / elem_bytes);
+                    len.div_ceil(per_slot)
+                } else {
+                    len.saturating_mul(self.storage_size(elem))
+                }
+            }
+            TyKind::Struct(id) => {
+                let fields = self.item_fields(id).collect::<Vec<_>>();
+                let (_, slots) = slot_offsets(self, fields.iter().map(|&(ty, _)| ty));
+                slots.max(U256::from(1))
+            }
+            _ => U256::from(1),
+        }
+    }
+}
+
+/// Computes the slots and offsets of a sequence of values stored consecutively in storage.
+///
+/// Returns the `(slot, offset)` of each value and the total number of slots used.
+fn slot_offsets<'gcx>(
+    gcx: Gcx<'gcx>,
+    tys: impl IntoIterator<Item = Ty<'gcx>>,
+) -> (Vec<(U256, u8)>, U256) {
+    let mut offsets = Vec::new();
+    let mut slot = U256::ZERO;
+    let mut offset = 0u8;
+    for ty in tys {
+        let bytes = gcx.storage_bytes(ty);
+        if offset as u32 + bytes as u32 > 32 {
+            slot += U256::from(1);
+            offset = 0;
+        }
+        offsets.push((slot, offset));
+        let size = gcx.storage_size(ty);
+        if size == U256::from(1) && offset as u32 + bytes as u32 <= 32 {
+            offset += bytes;
+        } else {
+            slot = slot.saturating_add(size);
+            offset = 0;
+        }
+    }
+    if offset > 0 {
+        slot += U256::from(1);
+    }
+    (offsets, slot)
+}
+
+struct StorageLayoutCx<'gcx> {
+    gcx: Gcx<'gcx>,
+    contract: String,
+    types: BTreeMap<String, StorageType>,
+}
+
+impl<'gcx> StorageLayoutCx<'gcx> {
+    /// Lays out the given variables consecutively, starting at slot 0.
+    fn layout(&mut self, vars: &[(Ty<'gcx>, hir::VariableId)]) -> (Vec<StorageSlot>, U256) {
+        let (offsets, slots) = slot_offsets(self.gcx, vars.iter().map(|&(ty, _)| ty));
+        let storage = vars
+            .iter()
+            .zip(offsets)
+            .map(|(&(ty, var), (slot, offset))| StorageSlot {
+                contract: self.contract.clone(),
+                label: self.gcx.item_name(var).to_string(),
+                offset,
+                slot: slot.to_string(),
+                ty: self.add_type(ty),
+            })
+            .collect();
+        (storage, slots)
+    }
+
+    /// Adds the given type and the types it contains to `types`, returning its identifier.
+    fn add_type(&mut self, ty: Ty<'gcx>) -> String {
+        let gcx = self.gcx;
+        let ty = ty.peel_refs();
+        let id = type_identifier(gcx, ty, None);
+        if self.types.contains_key(&id) {
+            return id;
+        }
+
+        let mut storage_ty = StorageType {
+            base: None,
+            encoding: "inplace",
+            key: None,
+            label: type_label(gcx, ty),
+            members: None,
+            number_of_bytes: self.number_of_bytes(ty),
+            value: None,
+        };
+        // Insert the type before visiting its components, which may refer back to it.
+        self.types.insert(id.clone(), storage_ty.clone());
+        match ty.kind {
+            TyKind::Elementary(ElementaryType::String | ElementaryType::Bytes) => {
+                storage_ty.encoding = "bytes";
+            }
+            TyKind::Mapping(key, value) => {
+                storage_ty.encoding = "mapping";
+                storage_ty.key = Some(self.add_type(key));
+                storage_ty.value = Some(self.add_type(value));
+            }
+            TyKind::DynArray(elem) => {
+                storage_ty.encoding = "dynamic_array";
+                storage_ty.base = Some(self.add_type(elem));
+            }
+            TyKind::Array(elem, _) => storage_ty.base = Some(self.add_type(elem)),
+            TyKind::Struct(struct_id) => {
+                let fields = gcx.item_fields(struct_id).collect::<Vec<_>>();
+                storage_ty.members = Some(self.layout(&fields).0);
+            }
+            _ => {}
+        }
+        self.types.insert(id.clone(), storage_ty);
+        id
+    }
+
+    fn number_of_bytes(&self, ty: Ty<'gcx>) -> String {
+        let bytes = match ty.kind {
+            TyKind::Array(..) | TyKind::Struct(_) => {
+                self.gcx.storage_size(ty).saturating_mul(U256::from(32))
+            }
+            _ => U256::from(self.gcx.storage_bytes(ty)),
+        };
+        bytes.to_string()
+    }
+}
+
+/// Returns the solc type identifier of the given type, e.g. `t_mapping(t_address,t_uint256)`.
+///
+/// `loc` is the data location of reference types, or `None` for types stored in storage.
+fn type_identifier<'gcx>(gcx: Gcx<'gcx>, ty: Ty<'gcx>, loc: Option<DataLocation>) -> String {
+    let loc_suffix = || match loc {
+        None => "_storage".to_string(),
+        Some(loc) => format!("_{loc}_ptr"),
+    };
+    match ty.kind {
+        TyKind::Elementary(ElementaryType::Address(true)) => "t_address_payable".into(),
+        TyKind::Elementary(ty @ (ElementaryType::String | ElementaryType::Bytes)) => {
+            format!("t_{}{}", ty.to_abi_str(), loc_suffix())
+        }
+        TyKind::Elementary(ty) => format!("t_{}", ty.to_abi_str()),
+        TyKind::Ref(ty, loc) => type_identifier(gcx, ty, Some(loc)),
+        TyKind::Contract(id) => format!("t_contract({}){}", gcx.item_name(id), id.get()),
+        TyKind::Enum(id) => format!("t_enum({}){}", gcx.item_name(id), id.get()),
+        TyKind::Udvt(_, id) => {
+            format!("t_userDefinedValueType({}){}", gcx.item_name(id), id.get())
+        }
+        TyKind::Struct(id) => {
+            format!("t_struct({}){}{}", gcx.item_name(id), id.get(), loc_suffix())
+        }
+        TyKind::Mapping(key, value) => format!(
+            "t_mapping({},{})",
+            type_identifier(gcx, key, loc),
+            type_identifier(gcx, value, loc)
+        ),
+        TyKind::DynArray(elem) => {
+            format!("t_array({})dyn{}", type_identifier(gcx, elem, loc), loc_suffix())
+        }
+        TyKind::Array(elem, len) => {
+            format!("t_array({}){len}{}", type_identifier(gcx, elem, loc), loc_suffix())
+        }
+        TyKind::FnPtr(f) => {
+            let kind = if f.visibility == Visibility::External { "external" } else { "internal" };
+            let list = |tys: &[Ty<'gcx>]| {
+                let ids =
+                    tys.iter().map(|&ty| type_identifier(gcx, ty, Some(DataLocation::Memory)));
+                ids.collect::<Vec<_>>().join(",")
+            };
+            format!(
+                "t_function_{kind}_{}({})returns({})",
+                f.state_mutability.to_str(),
+                list(f.parameters),
+                list(f.returns)
+            )
+        }
+        _ => unreachable!("invalid storage type: {ty:?}"),
+    }
+}
+
+/// Returns the solc type label of the given type, e.g. `mapping(address => uint256)`.
+fn type_label<'gcx>(gcx: Gcx<'gcx>, ty: Ty<'gcx>) -> String {
+    let mut s = String::new();
+    match ty.kind {
+        TyKind::FnPtr(f) => {
+            let list = |tys: &[Ty<'gcx>]| {
+                tys.iter().map(|&ty| type_label_with_locs(gcx, ty)).collect::<Vec<_>>().join(",")
+            };
+            write!(s, "function ({})", list(f.parameters)).unwrap();
+            if f.state_mutability != StateMutability::NonPayable {
+                write!(s, " {}", f.state_mutability.to_str()).unwrap();
+            }
+            if f.visibility == Visibility::External {
+                s.push_str(" external");
+            }
+            if !f.returns.is_empty() {
+                write!(s, " returns ({})", list(f.returns)).unwrap();
+            }
+        }
+        _ => TySolcPrinter::new(gcx, &mut s).print(ty).unwrap(),
+    }
+    s
+}
+
+fn type_label_with_locs<'gcx>(gcx: Gcx<'gcx>, ty: Ty<'gcx>) -> String {
+    let mut s = String::new();
+    TySolcPrinter::new(gcx, &mut s).data_locations(true).print(ty).unwrap();
+    s
+}
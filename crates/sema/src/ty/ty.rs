@@ -2,7 +2,7 @@ use super::{Gcx, Recursiveness};
 use crate::{builtins::Builtin, hir};
 use alloy_primitives::U256;
 use solar_ast::{DataLocation, ElementaryType, StateMutability, TypeSize, Visibility};
-use solar_data_structures::Interned;
+use solar_data_structures::{fmt::from_fn, Interned};
 use solar_interface::diagnostics::ErrorGuaranteed;
 use std::{borrow::Borrow, fmt, hash::Hash, ops::ControlFlow};
 
@@ -205,6 +205,42 @@ impl<'gcx> Ty<'gcx> {
         }
     }
 
+    /// Returns a type that implements [`fmt::Display`] for use in diagnostics.
+    pub fn display(self, gcx: Gcx<'gcx>) -> impl fmt::Display + use<'gcx> {
+        from_fn(move |f| match self.kind {
+            TyKind::Elementary(ty) => write!(f, "{ty}"),
+            TyKind::StringLiteral(..) => f.write_str("literal_string"),
+            TyKind::IntLiteral(_) => f.write_str("int_const"),
+            TyKind::Ref(ty, loc) => write!(f, "{} {loc}", ty.display(gcx)),
+            TyKind::DynArray(ty) => write!(f, "{}[]", ty.display(gcx)),
+            TyKind::Array(ty, len) => write!(f, "{}[{len}]", ty.display(gcx)),
+            TyKind::Tuple(tys) => {
+                f.write_str("tuple(")?;
+                for (i, ty) in tys.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{}", ty.display(gcx))?;
+                }
+                f.write_str(")")
+            }
+            TyKind::Mapping(key, value) => {
+                write!(f, "mapping({} => {})", key.display(gcx), value.display(gcx))
+            }
+            TyKind::FnPtr(_) => f.write_str("function"),
+            TyKind::Contract(id) => write!(f, "contract {}", gcx.item_canonical_name(id)),
+            TyKind::Struct(id) => write!(f, "struct {}", gcx.item_canonical_name(id)),
+            TyKind::Enum(id) => write!(f, "enum {}", gcx.item_canonical_name(id)),
+            TyKind::Error(_, id) => write!(f, "error {}", gcx.item_canonical_name(id)),
+            TyKind::Event(_, id) => write!(f, "event {}", gcx.item_canonical_name(id)),
+            TyKind::Udvt(_, id) => write!(f, "{}", gcx.item_canonical_name(id)),
+            TyKind::Module(_) => f.write_str("module"),
+            TyKind::BuiltinModule(builtin) => write!(f, "{}", builtin.name()),
+            TyKind::Type(ty) | TyKind::Meta(ty) => write!(f, "type({})", ty.display(gcx)),
+            TyKind::Err(_) => f.write_str("<error>"),
+        })
+    }
+
     /// Visits the type and its subtypes.
     pub fn visit<T>(self, f: &mut impl FnMut(Self) -> ControlFlow<T>) -> ControlFlow<T> {
         f(self)?;
@@ -0,0 +1,102 @@
+use crate::{
+    hir,
+    ty::{Gcx, Ty, TyKind},
+};
+use solar_ast::{DataLocation, ElementaryType, Visibility};
+use solar_interface::{error_code, Span};
+
+const HELP: &str = "use `pragma abicoder v2;` to enable the feature";
+
+/// Checks that the external signature of the given function only uses types supported by ABI
+/// coder v1, if its source uses it.
+///
+/// Reference: <https://docs.soliditylang.org/en/latest/layout-of-source-files.html#abi-coder-pragma>
+pub(super) fn check_function(gcx: Gcx<'_>, id: hir::FunctionId) {
+    let func = gcx.hir.function(id);
+    if func.visibility < Visibility::Public || func.is_getter() || !uses_v1(gcx, func.source) {
+        return;
+    }
+    for &var in func.parameters.iter().chain(func.returns) {
+        check_variable(gcx, var);
+    }
+}
+
+/// Checks that the parameters of the given event are supported by ABI coder v1, if its source
+/// uses it.
+pub(super) fn check_event(gcx: Gcx<'_>, id: hir::EventId) {
+    let event = gcx.hir.event(id);
+    if !uses_v1(gcx, event.source) {
+        return;
+    }
+    for &var in event.parameters {
+        check_variable(gcx, var);
+    }
+}
+
+fn check_variable(gcx: Gcx<'_>, id: hir::VariableId) {
+    let ty = gcx.type_of_item(id.into());
+    if !is_supported_by_v1(ty) {
+        gcx.dcx()
+            .err("this type is only supported in ABI coder v2")
+            .code(error_code!(4957))
+            .span(gcx.hir.variable(id).ty.span)
+            .help(HELP)
+            .emit();
+    }
+}
+
+/// Checks the argument and return types of an external call made from a source that uses ABI
+/// coder v1. The callee may be declared in a source that uses ABI coder v2.
+pub(super) fn check_external_call<'gcx>(
+    gcx: Gcx<'gcx>,
+    source: hir::SourceId,
+    span: Span,
+    args: impl IntoIterator<Item = (Span, Ty<'gcx>)>,
+    returns: &[Ty<'gcx>],
+) {
+    if !uses_v1(gcx, source) {
+        return;
+    }
+    for (span, ty) in args {
+        if !is_supported_by_v1(ty) {
+            let msg = format!(
+                "the type of this parameter, `{}`, is only supported in ABI coder v2",
+                ty.display(gcx)
+            );
+            gcx.dcx().err(msg).code(error_code!(2443)).span(span).help(HELP).emit();
+        }
+    }
+    for &ty in returns {
+        if !is_supported_by_v1(ty) {
+            let msg =
+                format!("return type `{}` is only supported in ABI coder v2", ty.display(gcx));
+            gcx.dcx().err(msg).span(span).help(HELP).emit();
+        }
+    }
+}
+
+fn uses_v1(gcx: Gcx<'_>, source: hir::SourceId) -> bool {
+    gcx.hir.source(source).abi_coder == hir::AbiCoder::V1
+}
+
+/// Returns `true` if the given type can be encoded and decoded by ABI coder v1.
+///
+/// Storage references can only appear in the signatures of library functions, which pass them as
+/// storage slots.
+fn is_supported_by_v1(ty: Ty<'_>) -> bool {
+    match ty.kind {
+        TyKind::Ref(_, DataLocation::Storage) => true,
+        TyKind::Ref(inner, _) => is_supported_by_v1(inner),
+        TyKind::Struct(_) => false,
+        TyKind::Array(element, _) | TyKind::DynArray(element) => {
+            let element = element.peel_refs();
+            let is_dynamic_array = matches!(
+                element.kind,
+                TyKind::DynArray(_)
+                    | TyKind::Elementary(ElementaryType::String | ElementaryType::Bytes)
+            );
+            !is_dynamic_array && is_supported_by_v1(element)
+        }
+        _ => true,
+    }
+}
@@ -0,0 +1,81 @@
+use crate::{hir, ty::Gcx};
+use solar_data_structures::map::{FxHashMap, StdEntry};
+use solar_interface::error_code;
+
+/// Checks that the arguments of the base constructors of the given contract are given exactly
+/// once, either in an inheritance list or in a constructor, and that they are given at all if the
+/// contract is not abstract.
+///
+/// The arguments themselves are checked in the type checker.
+///
+/// Reference: <https://github.com/ethereum/solidity/blob/develop/libsolidity/analysis/ContractLevelChecker.cpp>
+pub(super) fn check_contract(gcx: Gcx<'_>, id: hir::ContractId) {
+    let c = gcx.hir.contract(id);
+
+    // Base contract -> (contract that calls its constructor, call).
+    let mut calls = FxHashMap::<hir::ContractId, (hir::ContractId, &hir::Modifier<'_>)>::default();
+    // Duplicate calls in the bases are reported when checking the bases.
+    for &caller in c.linearized_bases {
+        for (base, call) in base_calls(gcx, caller) {
+            let (prev_caller, prev) = match calls.entry(base) {
+                StdEntry::Vacant(entry) => {
+                    entry.insert((caller, call));
+                    continue;
+                }
+                StdEntry::Occupied(entry) => *entry.get(),
+            };
+            if prev.args.is_empty() || call.args.is_empty() {
+                continue;
+            }
+            let (span, other) = if caller == id {
+                (call.span, prev.span)
+            } else if prev_caller == id {
+                (prev.span, call.span)
+            } else {
+                continue;
+            };
+            gcx.dcx()
+                .err("base constructor arguments given twice")
+                .code(error_code!(3364))
+                .span(span)
+                .span_note(other, "arguments also given here")
+                .emit();
+        }
+    }
+
+    if c.kind.is_contract() {
+        for &base in c.linearized_bases.iter().skip(1) {
+            if calls.contains_key(&base) || ctor_params(gcx, base).is_empty() {
+                continue;
+            }
+            let base = gcx.hir.contract(base);
+            let msg =
+                format!("no arguments passed to the constructor of base contract `{}`", base.name);
+            gcx.dcx()
+                .err(msg)
+                .code(error_code!(3415))
+                .span(c.name.span)
+                .help(format!("specify the arguments or mark `{}` as abstract", c.name))
+                .emit();
+        }
+    }
+}
+
+/// Returns the base constructor calls of the given contract, in its inheritance list and in its
+/// constructor.
+fn base_calls<'gcx>(
+    gcx: Gcx<'gcx>,
+    id: hir::ContractId,
+) -> impl Iterator<Item = (hir::ContractId, &'gcx hir::Modifier<'gcx>)> {
+    let c = gcx.hir.contract(id);
+    let ctor_modifiers = c.ctor.map_or(&[][..], |ctor| gcx.hir.function(ctor).modifiers);
+    c.bases_args.iter().chain(ctor_modifiers).filter_map(|call| match call.id {
+        hir::ItemId::Contract(base) => Some((base, call)),
+        _ => None,
+    })
+}
+
+/// Returns the parameters of the constructor of the given contract.
+pub(super) fn ctor_params(gcx: Gcx<'_>, id: hir::ContractId) -> &[hir::VariableId] {
+    gcx.hir.contract(id).ctor.map_or(&[], |ctor| gcx.hir.function(ctor).parameters)
+}
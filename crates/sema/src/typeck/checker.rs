@@ -0,0 +1,1451 @@
+use super::{abi_coder, base_args::ctor_params, using_for};
+use crate::{
+    builtins::Builtin,
+    hir::{self, CallArgs, Res},
+    ty::{Gcx, Ty, TyKind},
+    yul,
+};
+use either::Either;
+use num_rational::BigRational;
+use num_traits::{Signed, Zero};
+use solar_ast::{
+    ContractKind, DataLocation, ElementaryType, LitKind, TypeSize, UserDefinableOperator,
+};
+use solar_data_structures::smallvec::SmallVec;
+use solar_interface::{error_code, kw, sym, Ident, Span, Symbol};
+
+/// Checks the types of the expressions in the body of the given function.
+pub(super) fn check_function(gcx: Gcx<'_>, id: hir::FunctionId) {
+    let func = gcx.hir.function(id);
+    let mut checker = TypeChecker { gcx, function: Some(func) };
+    for modifier in func.modifiers {
+        match modifier.id {
+            hir::ItemId::Contract(base) => checker.check_base_args(base, modifier),
+            hir::ItemId::Function(m) => {
+                let params = gcx.hir.function(m).parameters;
+                checker.check_call_args(modifier.span, "modifier", params, &modifier.args);
+            }
+            _ => checker.check_args(&modifier.args),
+        }
+    }
+    let Some(body) = func.body else { return };
+    checker.check_block(body);
+}
+
+/// Checks the base constructor arguments in the inheritance list of the given contract.
+///
+/// Base constructor calls in constructors are checked as part of [`check_function`].
+pub(super) fn check_contract(gcx: Gcx<'_>, id: hir::ContractId) {
+    let mut checker = TypeChecker { gcx, function: None };
+    for modifier in gcx.hir.contract(id).bases_args {
+        let hir::ItemId::Contract(base) = modifier.id else { continue };
+        checker.check_base_args(base, modifier);
+    }
+}
+
+/// Checks the type of the initializer of the given variable, if any.
+///
+/// Variables declared in function bodies are checked as part of [`check_function`].
+pub(super) fn check_variable(gcx: Gcx<'_>, id: hir::VariableId) {
+    let var = gcx.hir.variable(id);
+    if var.function.is_some() {
+        return;
+    }
+    let Some(init) = var.initializer else { return };
+    let mut checker = TypeChecker { gcx, function: None };
+    checker.expect_ty(init, gcx.type_of_item(id.into()), false);
+}
+
+/// Expression type checker.
+///
+/// Types are computed on a best-effort basis: expressions whose type cannot be computed yet
+/// evaluate to `None`, which silences any further checks that depend on them.
+struct TypeChecker<'gcx> {
+    gcx: Gcx<'gcx>,
+    /// The function whose body is being checked, if any.
+    function: Option<&'gcx hir::Function<'gcx>>,
+}
+
+impl<'gcx> TypeChecker<'gcx> {
+    fn check_block(&mut self, block: hir::Block<'gcx>) {
+        for stmt in block {
+            self.check_stmt(stmt);
+        }
+    }
+
+    fn check_stmt(&mut self, stmt: &'gcx hir::Stmt<'gcx>) {
+        match stmt.kind {
+            hir::StmtKind::DeclSingle(id) => {
+                let var = self.gcx.hir.variable(id);
+                if let Some(init) = var.initializer {
+                    self.expect_var_ty(init, id);
+                }
+            }
+            hir::StmtKind::DeclMulti(vars, expr) => {
+                let Some(ty) = self.check_expr(expr) else { return };
+                let TyKind::Tuple(tys) = ty.kind else { return };
+                if tys.len() != vars.len() {
+                    let msg = format!(
+                        "different number of components on the left hand side ({}) than on the \
+                         right hand side ({})",
+                        vars.len(),
+                        tys.len()
+                    );
+                    self.gcx.dcx().err(msg).span(stmt.span).emit();
+                    return;
+                }
+                for (&var, &from) in vars.iter().zip(tys) {
+                    let Some(var) = var else { continue };
+                    let to = self.gcx.type_of_item(var.into());
+                    let is_pointer = to.is_ref_at(DataLocation::Storage);
+                    if !is_implicitly_convertible(self.gcx, from, to, is_pointer) {
+                        self.report_mismatch(self.gcx.hir.variable(var).span, from, to);
+                    }
+                }
+            }
+            hir::StmtKind::Block(block)
+            | hir::StmtKind::UncheckedBlock(block)
+            | hir::StmtKind::Loop(block, _) => self.check_block(block),
+            hir::StmtKind::Emit(res, ref args) => {
+                self.check_emit_or_revert(stmt.span, res, args, true)
+            }
+            hir::StmtKind::Revert(res, ref args) => {
+                self.check_emit_or_revert(stmt.span, res, args, false)
+            }
+            hir::StmtKind::Return(expr) => self.check_return(stmt.span, expr),
+            hir::StmtKind::If(cond, then, else_) => {
+                self.expect_ty(cond, self.gcx.types.bool, false);
+                self.check_stmt(then);
+                if let Some(else_) = else_ {
+                    self.check_stmt(else_);
+                }
+            }
+            hir::StmtKind::Try(try_) => {
+                self.check_try(stmt.span, try_);
+                self.check_block(try_.block);
+                for catch in try_.catch {
+                    self.check_block(catch.block);
+                }
+            }
+            hir::StmtKind::Expr(expr) => {
+                self.check_expr(expr);
+            }
+            hir::StmtKind::Assembly(assembly) => yul::check_inline_assembly(self.gcx, assembly),
+            hir::StmtKind::Break
+            | hir::StmtKind::Continue
+            | hir::StmtKind::Placeholder
+            | hir::StmtKind::Err(_) => {}
+        }
+    }
+
+    /// Checks that the expression of a `try` statement is an external call or a contract creation,
+    /// and checks its `returns` and `catch` clauses.
+    ///
+    /// Reference: <https://docs.soliditylang.org/en/latest/control-structures.html#try-catch>
+    fn check_try(&mut self, span: Span, try_: &'gcx hir::StmtTry<'gcx>) {
+        let gcx = self.gcx;
+        let ty = self.check_expr(&try_.expr);
+        let returns = match try_.expr.kind {
+            hir::ExprKind::Call(callee, _) => {
+                let callee = match callee.kind {
+                    hir::ExprKind::CallOptions(callee, _) => callee,
+                    _ => callee,
+                };
+                match callee.kind {
+                    hir::ExprKind::New(hir::Type {
+                        kind: hir::TypeKind::Custom(hir::ItemId::Contract(_)),
+                        ..
+                    }) => ty.map(|ty| gcx.mk_tys(&[ty])),
+                    hir::ExprKind::New(_)
+                    | hir::ExprKind::Ident([Res::Item(hir::ItemId::Function(_)), ..]) => {
+                        self.report_try_expr(try_.expr.span);
+                        None
+                    }
+                    _ => match self.function.and_then(|func| super::expr_ty(gcx, func, callee)) {
+                        Some(ty) => match ty.kind {
+                            TyKind::FnPtr(f) if f.visibility >= hir::Visibility::Public => {
+                                Some(f.returns)
+                            }
+                            _ => {
+                                self.report_try_expr(try_.expr.span);
+                                None
+                            }
+                        },
+                        None => None,
+                    },
+                }
+            }
+            _ => {
+                self.report_try_expr(try_.expr.span);
+                None
+            }
+        };
+
+        if let Some(returns) = returns.filter(|_| !try_.returns.is_empty()) {
+            if returns.len() != try_.returns.len() {
+                let msg = format!(
+                    "function returns {} values, but returns clause has {} variables",
+                    returns.len(),
+                    try_.returns.len()
+                );
+                gcx.dcx().err(msg).span(span).emit();
+            } else {
+                for (&var, &ty) in try_.returns.iter().zip(returns) {
+                    let var_ty = gcx.type_of_item(var.into());
+                    if !is_implicitly_convertible(self.gcx, ty, var_ty, false) {
+                        self.report_mismatch(gcx.hir.variable(var).span, ty, var_ty);
+                    }
+                }
+            }
+        }
+
+        let (mut has_low_level, mut has_error, mut has_panic) = (false, false, false);
+        for catch in try_.catch {
+            let clause_span = catch
+                .name
+                .map(|name| name.span)
+                .or_else(|| catch.args.first().map(|&arg| gcx.hir.variable(arg).span))
+                .unwrap_or(span);
+            let is_single = |expected: Ty<'gcx>| match *catch.args {
+                [arg] => {
+                    let ty = gcx.type_of_item(arg.into());
+                    ty == expected || ty.has_error().is_err()
+                }
+                _ => false,
+            };
+            let (seen, what) = match catch.name {
+                None => {
+                    if !catch.args.is_empty() && !is_single(gcx.types.bytes_ref.memory) {
+                        let msg = "expected `catch (bytes memory ...) { ... }` or `catch { ... }`";
+                        gcx.dcx().err(msg).span(clause_span).emit();
+                    }
+                    (&mut has_low_level, "a low-level")
+                }
+                Some(name) if name.as_str() == "Error" => {
+                    if !is_single(gcx.types.string_ref.memory) {
+                        let msg = "expected `catch Error(string memory ...) { ... }`";
+                        gcx.dcx().err(msg).span(clause_span).emit();
+                    }
+                    (&mut has_error, "an `Error`")
+                }
+                Some(name) if name.as_str() == "Panic" => {
+                    if !is_single(gcx.types.uint(256)) {
+                        let msg = "expected `catch Panic(uint ...) { ... }`";
+                        gcx.dcx().err(msg).span(clause_span).emit();
+                    }
+                    (&mut has_panic, "a `Panic`")
+                }
+                Some(name) => {
+                    gcx.dcx()
+                        .err(format!("invalid catch clause name `{name}`"))
+                        .span(name.span)
+                        .help("expected `catch Error(...)`, `catch Panic(...)` or `catch (...)`")
+                        .emit();
+                    continue;
+                }
+            };
+            if std::mem::replace(seen, true) {
+                let msg = format!("this try statement already has {what} catch clause");
+                gcx.dcx().err(msg).span(clause_span).emit();
+            }
+        }
+    }
+
+    fn report_try_expr(&self, span: Span) {
+        let msg = "try can only be used with external function calls and contract creation calls";
+        self.gcx.dcx().err(msg).span(span).emit();
+    }
+
+    /// Checks that an `emit` or `revert` statement refers to an event or an error, respectively,
+    /// and checks its arguments.
+    ///
+    fn check_emit_or_revert(
+        &mut self,
+        span: Span,
+        res: &[Res],
+        args: &'gcx CallArgs<'gcx>,
+        is_emit: bool,
+    ) {
+        let what = if is_emit { "event" } else { "error" };
+        let mut candidates = SmallVec::<[&[hir::VariableId]; 4]>::new();
+        for &res in res {
+            let params = match res {
+                Res::Item(hir::ItemId::Event(id)) if is_emit => self.gcx.hir.event(id).parameters,
+                Res::Item(hir::ItemId::Error(id)) if !is_emit => self.gcx.hir.error(id).parameters,
+                Res::Err(_) => {
+                    self.check_args(args);
+                    return;
+                }
+                _ => {
+                    self.check_args(args);
+                    let msg = format!("expected {what}, found {}", res.description());
+                    self.gcx.dcx().err(msg).span(span).emit();
+                    return;
+                }
+            };
+            candidates.push(params);
+        }
+        let params = match *candidates {
+            [params] => params,
+            _ => {
+                // Overloads can't be fully disambiguated with the types computed here, so only
+                // report an error if none of them match.
+                let CallArgs::Unnamed(exprs) = *args else {
+                    self.check_args(args);
+                    return;
+                };
+                let tys = exprs.iter().map(|expr| self.check_expr(expr)).collect::<Vec<_>>();
+                let matches = |params: &&[hir::VariableId]| {
+                    params.len() == tys.len()
+                        && params.iter().zip(&tys).all(|(&param, ty)| {
+                            let param_ty = self.gcx.type_of_item(param.into());
+                            ty.is_none_or(|ty| {
+                                is_implicitly_convertible(self.gcx, ty, param_ty, false)
+                            })
+                        })
+                };
+                if !candidates.iter().any(matches) {
+                    let msg = "no matching declaration found after argument-dependent lookup";
+                    self.gcx.dcx().err(msg).code(error_code!(9322)).span(span).emit();
+                }
+                return;
+            }
+        };
+        self.check_call_args(span, what, params, args);
+    }
+
+    fn check_return(&mut self, span: Span, expr: Option<&'gcx hir::Expr<'gcx>>) {
+        let Some(expr) = expr else { return };
+        let Some(func) = self.function else {
+            self.check_expr(expr);
+            return;
+        };
+        let returns = func.returns;
+        match returns {
+            [] => {
+                self.check_expr(expr);
+                let msg = "function has no return parameters, but a value was returned";
+                self.gcx.dcx().err(msg).span(span).emit();
+            }
+            &[ret] => self.expect_var_ty(expr, ret),
+            _ => {
+                let Some(ty) = self.check_expr(expr) else { return };
+                let n_values = match ty.kind {
+                    TyKind::Tuple(tys) => tys.len(),
+                    _ => 1,
+                };
+                if n_values != returns.len() {
+                    let msg = format!(
+                        "different number of arguments in return statement than in returns \
+                         declaration: {n_values} given but expected {}",
+                        returns.len()
+                    );
+                    self.gcx.dcx().err(msg).span(span).emit();
+                    return;
+                }
+                let TyKind::Tuple(tys) = ty.kind else { return };
+                for (&ret, &from) in returns.iter().zip(tys) {
+                    let to = self.gcx.type_of_item(ret.into());
+                    if !is_implicitly_convertible(
+                        self.gcx,
+                        from,
+                        to,
+                        to.is_ref_at(DataLocation::Storage),
+                    ) {
+                        self.report_mismatch(expr.span, from, to);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Checks that `expr` is implicitly convertible to `expected`.
+    ///
+    /// `is_pointer` is `true` when `expected` is a local storage pointer, which can only be
+    /// assigned other storage references.
+    fn expect_ty(&mut self, expr: &'gcx hir::Expr<'gcx>, expected: Ty<'gcx>, is_pointer: bool) {
+        let Some(ty) = self.check_expr(expr) else { return };
+        if let TyKind::IntLiteral(_) = ty.kind {
+            if let Some(value) = literal_value(expr) {
+                self.expect_literal_ty(expr, ty, &value, expected);
+                return;
+            }
+        }
+        if !is_implicitly_convertible(self.gcx, ty, expected, is_pointer) {
+            self.report_mismatch(expr.span, ty, expected);
+        }
+    }
+
+    /// Checks that `expr` is implicitly convertible to the type of the given local variable,
+    /// parameter, or return parameter.
+    fn expect_var_ty(&mut self, expr: &'gcx hir::Expr<'gcx>, var: hir::VariableId) {
+        let ty = self.gcx.type_of_item(var.into());
+        self.expect_ty(expr, ty, ty.is_ref_at(DataLocation::Storage));
+    }
+
+    /// Checks that the number literal expression `expr` of type `ty`, which evaluates to `value`,
+    /// is implicitly convertible to `expected`.
+    fn expect_literal_ty(
+        &self,
+        expr: &hir::Expr<'_>,
+        ty: Ty<'gcx>,
+        value: &BigRational,
+        expected: Ty<'gcx>,
+    ) {
+        use ElementaryType::*;
+
+        let TyKind::Elementary(to) = expected.kind else {
+            if !is_implicitly_convertible(self.gcx, ty, expected, false) {
+                self.report_literal_mismatch(expr.span, value, expected, None);
+            }
+            return;
+        };
+        let help = match to {
+            UInt(_) | Int(_) if !value.is_integer() => {
+                Some("fractional numbers cannot be implicitly converted to integers".into())
+            }
+            UInt(_) if value.is_negative() => {
+                Some("negative numbers cannot be implicitly converted to unsigned integers".into())
+            }
+            UInt(size) | Int(size) => {
+                let value = value.to_integer();
+                let bits = size.bits() as u64 - matches!(to, Int(_)) as u64;
+                let magnitude = if value.is_negative() { -value - 1u8 } else { value };
+                if magnitude.bits() <= bits {
+                    return;
+                }
+                Some(format!("the number is too large to fit in `{to}`"))
+            }
+            FixedBytes(size) => {
+                // Only zero and hexadecimal literals with the exact number of digits can be
+                // converted to fixed bytes.
+                let digits = size.bytes() as usize * 2;
+                let is_hex_of_size = match expr.peel_parens().kind {
+                    hir::ExprKind::Lit(lit) => lit
+                        .symbol
+                        .as_str()
+                        .strip_prefix("0x")
+                        .is_some_and(|hex| hex.bytes().filter(|&b| b != b'_').count() == digits),
+                    _ => false,
+                };
+                if value.is_zero() || is_hex_of_size {
+                    return;
+                }
+                Some(format!(
+                    "only zero and hexadecimal numbers with {digits} digits can be implicitly \
+                     converted to `{to}`"
+                ))
+            }
+            Fixed(..) | UFixed(..) => return,
+            Address(_) | Bool | String | Bytes => None,
+        };
+        self.report_literal_mismatch(expr.span, value, expected, help);
+    }
+
+    fn report_literal_mismatch(
+        &self,
+        span: Span,
+        value: &BigRational,
+        expected: Ty<'gcx>,
+        help: Option<String>,
+    ) {
+        let msg = format!(
+            "mismatched types: expected `{}`, found `{}`",
+            expected.display(self.gcx),
+            display_literal_value(value)
+        );
+        let mut err = self.gcx.dcx().err(msg).span(span);
+        if let Some(help) = help {
+            err = err.help(help);
+        }
+        err.emit();
+    }
+
+    fn report_mismatch(&self, span: Span, found: Ty<'gcx>, expected: Ty<'gcx>) {
+        let msg = format!(
+            "mismatched types: expected `{}`, found `{}`",
+            expected.display(self.gcx),
+            found.display(self.gcx)
+        );
+        self.gcx.dcx().err(msg).span(span).emit();
+    }
+
+    /// Checks the arguments of a call to a function, event, or error with the given parameters.
+    fn check_call_args(
+        &mut self,
+        span: Span,
+        callee: &str,
+        params: &'gcx [hir::VariableId],
+        args: &'gcx CallArgs<'gcx>,
+    ) {
+        if args.len() != params.len() {
+            self.check_args(args);
+            let msg = format!(
+                "wrong argument count for {callee} call: {} arguments given but expected {}",
+                args.len(),
+                params.len()
+            );
+            self.gcx.dcx().err(msg).span(span).emit();
+            return;
+        }
+        match *args {
+            CallArgs::Unnamed(exprs) => {
+                for (expr, &param) in exprs.iter().zip(params) {
+                    self.expect_var_ty(expr, param);
+                }
+            }
+            CallArgs::Named(named) => {
+                for arg in named {
+                    let param = params.iter().copied().find(|&param| {
+                        self.gcx.hir.variable(param).name.is_some_and(|n| n.name == arg.name.name)
+                    });
+                    match param {
+                        Some(param) => self.expect_var_ty(&arg.value, param),
+                        None => {
+                            self.check_expr(&arg.value);
+                            let msg = format!("named argument `{}` does not match", arg.name);
+                            self.gcx.dcx().err(msg).span(arg.name.span).emit();
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Checks the arguments of a call to the constructor of `base`.
+    fn check_base_args(&mut self, base: hir::ContractId, modifier: &'gcx hir::Modifier<'gcx>) {
+        let params = ctor_params(self.gcx, base);
+        self.check_call_args(modifier.span, "constructor", params, &modifier.args);
+    }
+
+    fn check_args(&mut self, args: &'gcx CallArgs<'gcx>) {
+        for expr in args.exprs() {
+            self.check_expr(expr);
+        }
+    }
+
+    /// Computes the type of the given expression, checking its subexpressions.
+    fn check_expr(&mut self, expr: &'gcx hir::Expr<'gcx>) -> Option<Ty<'gcx>> {
+        let gcx = self.gcx;
+        match expr.kind {
+            hir::ExprKind::Array(exprs) => {
+                for expr in exprs {
+                    self.check_expr(expr);
+                }
+                None
+            }
+            hir::ExprKind::Assign(lhs, op, rhs) => {
+                let lhs_ty = self.check_expr(lhs);
+                match (lhs_ty, op) {
+                    (Some(lhs_ty), None) => {
+                        self.expect_ty(rhs, lhs_ty, self.is_storage_pointer(lhs))
+                    }
+                    _ => {
+                        self.check_expr(rhs);
+                    }
+                }
+                lhs_ty
+            }
+            hir::ExprKind::Binary(lhs, op, rhs) => {
+                use hir::BinOpKind::*;
+                match op.kind {
+                    And | Or => {
+                        self.expect_ty(lhs, gcx.types.bool, false);
+                        self.expect_ty(rhs, gcx.types.bool, false);
+                        Some(gcx.types.bool)
+                    }
+                    _ => {
+                        let lhs = self.check_expr(lhs);
+                        let rhs = self.check_expr(rhs);
+                        if let (Some(lhs), Some(rhs)) = (lhs, rhs) {
+                            if is_udvt(lhs) || is_udvt(rhs) {
+                                let op = Either::Right(op.kind);
+                                return self.check_user_defined_op(expr.span, op, &[lhs, rhs]);
+                            }
+                        }
+                        match op.kind {
+                            Lt | Le | Gt | Ge | Eq | Ne => Some(gcx.types.bool),
+                            _ if lhs.is_some_and(is_int_literal)
+                                && rhs.is_some_and(is_int_literal) =>
+                            {
+                                literal_value(expr).map(|value| self.int_literal_ty(&value))
+                            }
+                            _ => lhs.filter(|&lhs| {
+                                matches!(lhs.kind, TyKind::Elementary(_)) && Some(lhs) == rhs
+                            }),
+                        }
+                    }
+                }
+            }
+            hir::ExprKind::Call(callee, ref args) => self.check_call(expr, callee, args),
+            hir::ExprKind::CallOptions(callee, options) => {
+                self.check_expr(callee);
+                for option in options {
+                    self.check_expr(&option.value);
+                }
+                self.check_call_options(expr.span, callee, options);
+                None
+            }
+            hir::ExprKind::Delete(expr) => {
+                self.check_expr(expr);
+                Some(gcx.types.unit)
+            }
+            hir::ExprKind::Ident(res) => match *res {
+                [Res::Item(id @ (hir::ItemId::Variable(_) | hir::ItemId::Function(_)))] => {
+                    Some(gcx.type_of_item(id))
+                }
+                [Res::Item(
+                    id @ (hir::ItemId::Contract(_)
+                    | hir::ItemId::Struct(_)
+                    | hir::ItemId::Enum(_)
+                    | hir::ItemId::Udvt(_)),
+                )] => Some(gcx.type_of_item(id).make_type_type(gcx)),
+                [Res::Builtin(builtin)] => {
+                    self.check_evm_version(builtin, expr.span);
+                    None
+                }
+                _ => None,
+            },
+            hir::ExprKind::Index(base, index) => {
+                let base_ty = self.check_expr(base);
+                self.check_index(base_ty, index)
+            }
+            hir::ExprKind::Slice(base, start, end) => {
+                self.check_expr(base);
+                for expr in [start, end].into_iter().flatten() {
+                    self.check_expr(expr);
+                }
+                None
+            }
+            hir::ExprKind::Lit(lit) => self.lit_ty(&lit.kind),
+            hir::ExprKind::Member(base, member) => {
+                if let hir::ExprKind::Ident(&[Res::Builtin(module)]) = base.kind {
+                    let builtin =
+                        module.members()?.iter().copied().find(|b| b.name() == member.name)?;
+                    self.check_evm_version(builtin, expr.span);
+                    return None;
+                }
+                let base_ty = self.check_expr(base)?;
+                if let TyKind::Elementary(ElementaryType::Address(_)) = base_ty.kind {
+                    for builtin in [Builtin::AddressCodehash, Builtin::AddressStaticcall] {
+                        if builtin.name() == member.name {
+                            self.check_evm_version(builtin, expr.span);
+                        }
+                    }
+                    return None;
+                }
+                self.member_ty(base_ty, member)
+            }
+            hir::ExprKind::New(ref ty) => {
+                let hir::TypeKind::Custom(hir::ItemId::Contract(id)) = ty.kind else { return None };
+                let c = gcx.hir.contract(id);
+                let msg = match c.kind {
+                    ContractKind::AbstractContract => "cannot instantiate an abstract contract",
+                    ContractKind::Interface => "cannot instantiate an interface",
+                    ContractKind::Library => "cannot instantiate a library",
+                    ContractKind::Contract => return None,
+                };
+                let mut err = gcx.dcx().err(msg).span(expr.span);
+                if c.is_abstract() {
+                    err = err.code(error_code!(4614));
+                }
+                err.emit();
+                None
+            }
+            hir::ExprKind::Payable(expr) => {
+                self.check_expr(expr);
+                Some(gcx.types.address_payable)
+            }
+            hir::ExprKind::Ternary(cond, then, else_) => {
+                self.expect_ty(cond, gcx.types.bool, false);
+                let then = self.check_expr(then);
+                let else_ = self.check_expr(else_);
+                then.filter(|_| then == else_)
+            }
+            hir::ExprKind::Tuple(exprs) => match exprs {
+                [Some(expr)] => self.check_expr(expr),
+                _ => {
+                    let tys = exprs
+                        .iter()
+                        .map(|expr| expr.and_then(|expr| self.check_expr(expr)))
+                        .collect::<Vec<_>>();
+                    let tys = tys.into_iter().collect::<Option<Vec<_>>>()?;
+                    Some(gcx.mk_ty(TyKind::Tuple(gcx.mk_tys(&tys))))
+                }
+            },
+            hir::ExprKind::TypeCall(_) => None,
+            hir::ExprKind::Type(hir::Type { kind: hir::TypeKind::Elementary(ty), .. }) => {
+                Some(gcx.mk_ty(TyKind::Elementary(ty)).make_type_type(gcx))
+            }
+            hir::ExprKind::Type(_) => None,
+            hir::ExprKind::Unary(op, operand) => {
+                use hir::UnOpKind::*;
+                match op.kind {
+                    Not => {
+                        self.expect_ty(operand, gcx.types.bool, false);
+                        Some(gcx.types.bool)
+                    }
+                    PreInc | PreDec | PostInc | PostDec | Neg | BitNot => {
+                        let ty = self.check_expr(operand)?;
+                        if is_udvt(ty) {
+                            let op = Either::Left(op.kind);
+                            return self.check_user_defined_op(expr.span, op, &[ty]);
+                        }
+                        if is_int_literal(ty) && matches!(op.kind, Neg | BitNot) {
+                            return literal_value(expr).map(|value| self.int_literal_ty(&value));
+                        }
+                        matches!(ty.kind, TyKind::Elementary(_)).then_some(ty)
+                    }
+                }
+            }
+            hir::ExprKind::Err(_) => None,
+        }
+    }
+
+    /// Checks the index of an index access on a value of type `base_ty`, and returns the type of
+    /// the accessed element.
+    fn check_index(
+        &mut self,
+        base_ty: Option<Ty<'gcx>>,
+        index: Option<&'gcx hir::Expr<'gcx>>,
+    ) -> Option<Ty<'gcx>> {
+        let gcx = self.gcx;
+        let (inner, loc) = match base_ty.map(|ty| ty.kind) {
+            Some(TyKind::Ref(inner, loc)) => (Some(inner), Some(loc)),
+            _ => (base_ty, None),
+        };
+        let (key, element) = match inner.map(|ty| ty.kind) {
+            Some(TyKind::Mapping(key, value)) => {
+                (key, element_ty(gcx, value, Some(DataLocation::Storage)))
+            }
+            Some(TyKind::Array(element, _) | TyKind::DynArray(element)) => {
+                (gcx.types.uint(256), element_ty(gcx, element, loc))
+            }
+            Some(TyKind::Elementary(ElementaryType::Bytes | ElementaryType::FixedBytes(_))) => {
+                (gcx.types.uint(256), Some(gcx.types.fixed_bytes(1)))
+            }
+            _ => {
+                if let Some(index) = index {
+                    self.check_expr(index);
+                }
+                return None;
+            }
+        };
+        self.expect_ty(index?, key, false);
+        element
+    }
+
+    /// Returns the type of the member `member` of a value of type `base_ty`.
+    ///
+    /// Only the fields of structs, the `length` of arrays and fixed bytes, the variants of enums,
+    /// and the `wrap` and `unwrap` functions of user-defined value types are typed.
+    fn member_ty(&self, base_ty: Ty<'gcx>, member: Ident) -> Option<Ty<'gcx>> {
+        let gcx = self.gcx;
+        let (inner, loc) = match base_ty.kind {
+            TyKind::Ref(inner, loc) => (inner, Some(loc)),
+            _ => (base_ty, None),
+        };
+        match inner.kind {
+            TyKind::Struct(id) => {
+                let fields = gcx.hir.strukt(id).fields;
+                let i = fields.iter().position(|&f| gcx.item_name(f).name == member.name)?;
+                element_ty(gcx, gcx.struct_field_types(id)[i], loc)
+            }
+            TyKind::Array(..) | TyKind::DynArray(_) | TyKind::Elementary(ElementaryType::Bytes)
+                if member.name == sym::length =>
+            {
+                Some(gcx.types.uint(256))
+            }
+            TyKind::Elementary(ElementaryType::FixedBytes(_)) if member.name == sym::length => {
+                Some(gcx.types.uint(8))
+            }
+            TyKind::Type(ty) if is_udvt(ty) || matches!(ty.kind, TyKind::Enum(_)) => {
+                gcx.members_of(base_ty).iter().find(|m| m.name == member.name).map(|m| m.ty)
+            }
+            _ => None,
+        }
+    }
+
+    /// Checks the options of a call: `f{value: 1, gas: 2}`, `new C{salt: s}`.
+    ///
+    /// Reference: <https://github.com/ethereum/solidity/blob/develop/libsolidity/analysis/TypeChecker.cpp>
+    fn check_call_options(
+        &mut self,
+        span: Span,
+        callee: &'gcx hir::Expr<'gcx>,
+        options: &'gcx [hir::NamedArg<'gcx>],
+    ) {
+        let gcx = self.gcx;
+        if let hir::ExprKind::CallOptions(..) = callee.kind {
+            gcx.dcx()
+                .err("function call options have already been set")
+                .span(span)
+                .help("combine them into a single `{...}`")
+                .emit();
+            return;
+        }
+
+        // `Some(is_payable)` if the callee is an external call, `None` if it's unknown.
+        let mut is_creation = false;
+        let is_payable = match callee.kind {
+            hir::ExprKind::New(hir::Type {
+                kind: hir::TypeKind::Custom(hir::ItemId::Contract(id)),
+                ..
+            }) => {
+                is_creation = true;
+                let ctor = gcx.hir.contract(id).ctor;
+                Some(ctor.is_some_and(|ctor| {
+                    gcx.hir.function(ctor).state_mutability == hir::StateMutability::Payable
+                }))
+            }
+            // Internal calls.
+            hir::ExprKind::New(_)
+            | hir::ExprKind::Ident([Res::Item(hir::ItemId::Function(_)), ..]) => {
+                self.report_call_options_target(span);
+                return;
+            }
+            _ => {
+                if let Some(name) = self.low_level_call(callee) {
+                    Some(name == kw::Call)
+                } else {
+                    match self.function.and_then(|func| super::expr_ty(gcx, func, callee)) {
+                        Some(ty) => match ty.kind {
+                            TyKind::FnPtr(f) if f.visibility >= hir::Visibility::Public => {
+                                Some(f.state_mutability == hir::StateMutability::Payable)
+                            }
+                            _ => {
+                                self.report_call_options_target(span);
+                                return;
+                            }
+                        },
+                        None => None,
+                    }
+                }
+            }
+        };
+
+        let mut seen = SmallVec::<[Symbol; 3]>::new();
+        for option in options {
+            let name = option.name;
+            if seen.contains(&name.name) {
+                let msg = format!("call option `{name}` has already been set");
+                gcx.dcx().err(msg).span(name.span).emit();
+                continue;
+            }
+            seen.push(name.name);
+            let msg = match name.name {
+                sym::salt if is_payable.is_some() && !is_creation => {
+                    "call option `salt` can only be used with `new`"
+                }
+                kw::Gas if is_creation => "call option `gas` cannot be used with `new`",
+                sym::value if is_payable == Some(false) && is_creation => {
+                    "cannot set option `value`, since the constructor is not payable"
+                }
+                sym::value if is_payable == Some(false) => {
+                    "cannot set option `value` on a non-payable function type"
+                }
+                sym::salt | kw::Gas | sym::value => continue,
+                _ => {
+                    let msg = format!("unknown call option `{name}`");
+                    gcx.dcx()
+                        .err(msg)
+                        .span(name.span)
+                        .help("valid options are `salt`, `value` and `gas`")
+                        .emit();
+                    continue;
+                }
+            };
+            gcx.dcx().err(msg).span(name.span).emit();
+        }
+    }
+
+    fn report_call_options_target(&self, span: Span) {
+        let msg = "function call options can only be set on external function calls or contract \
+                   creations";
+        self.gcx.dcx().err(msg).span(span).emit();
+    }
+
+    /// Returns the name of the member if `callee` is a low-level `call`, `delegatecall` or
+    /// `staticcall` on an address, which are typed as internal builtin functions.
+    fn low_level_call(&self, callee: &'gcx hir::Expr<'gcx>) -> Option<Symbol> {
+        let hir::ExprKind::Member(base, name) = callee.kind else { return None };
+        if !matches!(name.name, kw::Call | kw::Delegatecall | kw::Staticcall) {
+            return None;
+        }
+        let ty = super::expr_ty(self.gcx, self.function?, base)?;
+        matches!(ty.kind, TyKind::Elementary(ElementaryType::Address(_))).then_some(name.name)
+    }
+
+    fn check_call(
+        &mut self,
+        expr: &'gcx hir::Expr<'gcx>,
+        callee: &'gcx hir::Expr<'gcx>,
+        args: &'gcx CallArgs<'gcx>,
+    ) -> Option<Ty<'gcx>> {
+        let gcx = self.gcx;
+
+        // Contract creations: `new C(...)`, `new C{value: 1}(...)`.
+        let new_callee = match callee.kind {
+            hir::ExprKind::CallOptions(callee, _) => callee,
+            _ => callee,
+        };
+        if let hir::ExprKind::New(hir::Type {
+            kind: hir::TypeKind::Custom(hir::ItemId::Contract(id)),
+            ..
+        }) = new_callee.kind
+        {
+            self.check_expr(callee);
+            let params = ctor_params(self.gcx, id);
+            self.check_call_args(expr.span, "constructor", params, args);
+            return Some(gcx.type_of_item(id.into()));
+        }
+
+        // Direct calls to a single, non-overloaded function.
+        if let hir::ExprKind::Ident(&[Res::Item(hir::ItemId::Function(id))]) = callee.kind {
+            let func = gcx.hir.function(id);
+            if func.kind.is_modifier() {
+                self.check_args(args);
+                return None;
+            }
+            self.check_call_args(expr.span, "function", func.parameters, args);
+            return Some(self.returns_ty(func.returns));
+        }
+
+        // Calls to overloaded functions, resolved by the types of the arguments.
+        if let hir::ExprKind::Ident(res @ [_, _, ..]) = callee.kind {
+            let candidates = res
+                .iter()
+                .map(|res| match *res {
+                    Res::Item(hir::ItemId::Function(id)) => Some(id),
+                    _ => None,
+                })
+                .collect::<Option<SmallVec<[_; 4]>>>();
+            if let Some(candidates) = candidates {
+                return self.check_overloaded_call(callee.span, &candidates, args);
+            }
+        }
+
+        let Some(callee_ty) = self.check_expr(callee) else {
+            self.check_args(args);
+            return None;
+        };
+        match callee_ty.kind {
+            TyKind::Type(ty) => match ty.kind {
+                TyKind::Struct(id) => {
+                    let fields = gcx.hir.strukt(id).fields;
+                    if ty.has_mapping() {
+                        self.check_args(args);
+                    } else if args.len() != fields.len() {
+                        self.check_args(args);
+                        let msg = format!(
+                            "wrong argument count for struct constructor: {} arguments given but \
+                             expected {}",
+                            args.len(),
+                            fields.len()
+                        );
+                        gcx.dcx().err(msg).span(expr.span).emit();
+                    } else {
+                        self.check_args(args);
+                    }
+                    Some(ty.with_loc(gcx, DataLocation::Memory))
+                }
+                _ => self.check_conversion(expr, ty, args),
+            },
+            TyKind::FnPtr(f) => {
+                match *args {
+                    CallArgs::Unnamed(exprs) if exprs.len() == f.parameters.len() => {
+                        for (expr, &param) in exprs.iter().zip(f.parameters) {
+                            self.expect_ty(expr, param, false);
+                        }
+                    }
+                    CallArgs::Unnamed(exprs) => {
+                        self.check_args(args);
+                        let msg = format!(
+                            "wrong argument count for function call: {} arguments given but \
+                             expected {}",
+                            exprs.len(),
+                            f.parameters.len()
+                        );
+                        gcx.dcx().err(msg).span(expr.span).emit();
+                    }
+                    CallArgs::Named(_) => self.check_args(args),
+                }
+                // External calls, e.g. `c.f()`, can be made to functions declared in a source
+                // that uses a different ABI coder.
+                let is_external = f.visibility >= hir::Visibility::Public
+                    && !matches!(callee.kind, hir::ExprKind::Ident(_));
+                if let (true, Some(func)) = (is_external, self.function) {
+                    let arg_span = |i: usize| match *args {
+                        CallArgs::Unnamed(exprs) => exprs.get(i).map_or(expr.span, |arg| arg.span),
+                        CallArgs::Named(_) => expr.span,
+                    };
+                    let args = f.parameters.iter().enumerate().map(|(i, &ty)| (arg_span(i), ty));
+                    abi_coder::check_external_call(gcx, func.source, expr.span, args, f.returns);
+                }
+                Some(match *f.returns {
+                    [ret] => ret,
+                    _ => gcx.mk_ty(TyKind::Tuple(f.returns)),
+                })
+            }
+            _ => {
+                self.check_args(args);
+                None
+            }
+        }
+    }
+
+    /// Checks that the given builtin is available in the EVM version being compiled for.
+    fn check_evm_version(&self, builtin: Builtin, span: Span) {
+        let Some(since) = builtin.min_evm_version() else { return };
+        let evm_version = self.gcx.sess.evm_version;
+        if evm_version < since {
+            let msg =
+                format!("`{}` is only available for {since:?}-compatible VMs", builtin.name());
+            let note = format!("you are currently compiling for `{evm_version}`");
+            self.gcx.dcx().err(msg).span(span).note(note).emit();
+        }
+    }
+
+    /// Checks a unary or binary operation on a user-defined value type, which is only allowed
+    /// through a user-defined operator, and returns the type of its result.
+    fn check_user_defined_op(
+        &self,
+        span: Span,
+        op: Either<hir::UnOpKind, hir::BinOpKind>,
+        operands: &[Ty<'gcx>],
+    ) -> Option<Ty<'gcx>> {
+        let gcx = self.gcx;
+        let (what, op_str) = match op {
+            Either::Left(op) => ("unary", op.to_str()),
+            Either::Right(op) => ("binary", op.to_str()),
+        };
+        let functions = match UserDefinableOperator::from_op(op) {
+            Some(user_op) if operands.iter().all(|&ty| ty == operands[0]) => {
+                using_for::user_defined_operators(gcx, operands[0], user_op, operands.len())
+            }
+            _ => Default::default(),
+        };
+        match *functions {
+            [id] => Some(self.returns_ty(gcx.hir.function(id).returns)),
+            [] => {
+                let tys = operands
+                    .iter()
+                    .map(|ty| format!("`{}`", ty.display(gcx)))
+                    .collect::<Vec<_>>()
+                    .join(" and ");
+                let s = if operands.len() == 1 { "" } else { "s" };
+                let msg = format!(
+                    "built-in {what} operator `{op_str}` cannot be applied to type{s} {tys}"
+                );
+                gcx.dcx()
+                    .err(msg)
+                    .span(span)
+                    .help("no matching user-defined operator was found")
+                    .emit();
+                None
+            }
+            _ => {
+                let msg = format!(
+                    "user-defined {what} operator `{op_str}` has more than one definition \
+                     matching the operand type"
+                );
+                gcx.dcx().err(msg).span(span).emit();
+                None
+            }
+        }
+    }
+
+    /// Checks an explicit type conversion: `T(x)`.
+    fn check_conversion(
+        &mut self,
+        expr: &'gcx hir::Expr<'gcx>,
+        to: Ty<'gcx>,
+        args: &'gcx CallArgs<'gcx>,
+    ) -> Option<Ty<'gcx>> {
+        let gcx = self.gcx;
+        let CallArgs::Unnamed([arg]) = *args else {
+            self.check_args(args);
+            let msg = "exactly one unnamed argument expected for explicit type conversion";
+            gcx.dcx().err(msg).span(expr.span).emit();
+            return None;
+        };
+        let from = self.check_expr(arg);
+        if let Some(from) = from {
+            if !is_explicitly_convertible(gcx, from, to) {
+                let msg = format!(
+                    "invalid explicit type conversion from `{}` to `{}`",
+                    from.display(gcx),
+                    to.display(gcx)
+                );
+                gcx.dcx().err(msg).span(expr.span).emit();
+            }
+        }
+        if !to.is_reference_type() {
+            return Some(to);
+        }
+        // Conversions between reference types keep the data location of the argument.
+        match from?.kind {
+            TyKind::Ref(_, loc) => Some(to.with_loc(gcx, loc)),
+            _ => None,
+        }
+    }
+
+    /// Checks a call to one of the given overloaded functions, selecting the only one that
+    /// accepts the arguments.
+    ///
+    /// Overridden functions are in scope together with the functions that override them, so
+    /// candidates with the same parameter types are only considered once.
+    fn check_overloaded_call(
+        &mut self,
+        span: Span,
+        candidates: &[hir::FunctionId],
+        args: &'gcx CallArgs<'gcx>,
+    ) -> Option<Ty<'gcx>> {
+        let gcx = self.gcx;
+        let arg_tys = args.exprs().map(|arg| self.check_expr(arg)).collect::<SmallVec<[_; 8]>>();
+        let accepts = |id: hir::FunctionId| {
+            let params = gcx.hir.function(id).parameters;
+            if params.len() != args.len() {
+                return false;
+            }
+            let is_convertible = |param: hir::VariableId, ty: Option<Ty<'gcx>>| {
+                let param_ty = gcx.type_of_item(param.into());
+                ty.is_none_or(|ty| {
+                    is_implicitly_convertible(
+                        gcx,
+                        ty,
+                        param_ty,
+                        param_ty.is_ref_at(DataLocation::Storage),
+                    )
+                })
+            };
+            match *args {
+                CallArgs::Unnamed(_) => {
+                    params.iter().zip(&arg_tys).all(|(&param, &ty)| is_convertible(param, ty))
+                }
+                CallArgs::Named(named) => named.iter().zip(&arg_tys).all(|(arg, &ty)| {
+                    params.iter().any(|&param| {
+                        gcx.hir.variable(param).name.is_some_and(|n| n.name == arg.name.name)
+                            && is_convertible(param, ty)
+                    })
+                }),
+            }
+        };
+
+        let mut matching = SmallVec::<[hir::FunctionId; 4]>::new();
+        for &id in candidates {
+            let is_duplicate = matching
+                .iter()
+                .any(|&other| gcx.item_parameter_types(other) == gcx.item_parameter_types(id));
+            if !is_duplicate && accepts(id) {
+                matching.push(id);
+            }
+        }
+        match matching[..] {
+            [id] => Some(self.returns_ty(gcx.hir.function(id).returns)),
+            [] => {
+                let msg = "no matching declaration found after argument-dependent lookup";
+                gcx.dcx().err(msg).code(error_code!(9322)).span(span).emit();
+                None
+            }
+            // Arguments whose type is unknown are accepted by all the candidates.
+            [..] if arg_tys.iter().any(Option::is_none) => None,
+            [..] => {
+                let msg = "no unique declaration found after argument-dependent lookup";
+                let mut err = gcx.dcx().err(msg).code(error_code!(4487)).span(span);
+                for &id in &matching {
+                    let func = gcx.hir.function(id);
+                    let span = func.name.map_or(func.span, |name| name.span);
+                    err = err.span_note(span, "candidate function");
+                }
+                err.emit();
+                None
+            }
+        }
+    }
+
+    /// Returns the type of the value returned by calling a function with the given returns.
+    fn returns_ty(&self, returns: &[hir::VariableId]) -> Ty<'gcx> {
+        let gcx = self.gcx;
+        match returns {
+            [ret] => gcx.type_of_item((*ret).into()),
+            _ => {
+                let tys = returns.iter().map(|&ret| gcx.type_of_item(ret.into()));
+                gcx.mk_ty(TyKind::Tuple(gcx.mk_ty_iter(tys)))
+            }
+        }
+    }
+
+    fn lit_ty(&self, lit: &LitKind) -> Option<Ty<'gcx>> {
+        let gcx = self.gcx;
+        Some(match lit {
+            LitKind::Str(_, s) => gcx.mk_ty_string_literal(s),
+            LitKind::Number(n) => self.int_literal_ty(&BigRational::from_integer(n.clone())),
+            LitKind::Rational(r) => self.int_literal_ty(r),
+            LitKind::Address(_) => gcx.types.address,
+            LitKind::Bool(_) => gcx.types.bool,
+            LitKind::Err(_) => return None,
+        })
+    }
+
+    /// Returns the type of a number literal with the given value, sized by its integer part.
+    fn int_literal_ty(&self, value: &BigRational) -> Ty<'gcx> {
+        let (_, bytes) = value.to_integer().to_bytes_be();
+        let size = bytes.len().min(TypeSize::MAX as usize) as u8;
+        self.gcx.mk_ty_int_literal(TypeSize::new(size).unwrap())
+    }
+
+    /// Returns `true` if `expr` refers to a local variable that is a storage pointer.
+    fn is_storage_pointer(&self, expr: &hir::Expr<'_>) -> bool {
+        let hir::ExprKind::Ident(&[Res::Item(hir::ItemId::Variable(id))]) = expr.kind else {
+            return false;
+        };
+        let var = self.gcx.hir.variable(id);
+        var.is_local_or_return() && var.data_location == Some(DataLocation::Storage)
+    }
+}
+
+/// Returns the type of an element of an array, mapping or struct that is stored in `loc`.
+///
+/// Elements of reference types have the data location of their container, and elements of value
+/// types have no data location.
+fn element_ty<'gcx>(gcx: Gcx<'gcx>, ty: Ty<'gcx>, loc: Option<DataLocation>) -> Option<Ty<'gcx>> {
+    let ty = ty.peel_refs();
+    if ty.is_reference_type() {
+        loc.map(|loc| ty.with_loc(gcx, loc))
+    } else {
+        Some(ty)
+    }
+}
+
+fn is_udvt(ty: Ty<'_>) -> bool {
+    matches!(ty.kind, TyKind::Udvt(..))
+}
+
+fn is_int_literal(ty: Ty<'_>) -> bool {
+    matches!(ty.kind, TyKind::IntLiteral(_))
+}
+
+/// The maximum number of bits of the values that number literal expressions can evaluate to.
+const MAX_LITERAL_BITS: u64 = 4096;
+
+/// Evaluates a constant expression made only of number literals with exact rational arithmetic.
+///
+/// Returns `None` if the expression is not such an expression, or if the operation is invalid or
+/// its result is too large.
+///
+/// Reference: <https://docs.soliditylang.org/en/latest/types.html#rational-and-integer-literals>
+fn literal_value(expr: &hir::Expr<'_>) -> Option<BigRational> {
+    let expr = expr.peel_parens();
+    let value = match expr.kind {
+        hir::ExprKind::Lit(lit) => match &lit.kind {
+            LitKind::Number(n) => BigRational::from_integer(n.clone()),
+            LitKind::Rational(r) => r.clone(),
+            _ => return None,
+        },
+        hir::ExprKind::Unary(op, operand) => {
+            let value = literal_value(operand)?;
+            match op.kind {
+                hir::UnOpKind::Neg => -value,
+                hir::UnOpKind::BitNot if value.is_integer() => {
+                    BigRational::from_integer(!value.to_integer())
+                }
+                _ => return None,
+            }
+        }
+        hir::ExprKind::Binary(lhs, op, rhs) => {
+            let l = literal_value(lhs)?;
+            let r = literal_value(rhs)?;
+            literal_binop(l, op.kind, r)?
+        }
+        _ => return None,
+    };
+    let bits = value.numer().bits().max(value.denom().bits());
+    (bits <= MAX_LITERAL_BITS).then_some(value)
+}
+
+fn literal_binop(l: BigRational, op: hir::BinOpKind, r: BigRational) -> Option<BigRational> {
+    use hir::BinOpKind::*;
+
+    Some(match op {
+        Add => l + r,
+        Sub => l - r,
+        Mul => l * r,
+        Div if !r.is_zero() => l / r,
+        Pow if r.is_integer() => {
+            // Bail out before computing powers that would be too large.
+            let exp = i32::try_from(r.to_integer()).ok()?;
+            let base_bits = l.numer().bits().max(l.denom().bits());
+            if base_bits > 1 && base_bits * u64::from(exp.unsigned_abs()) > MAX_LITERAL_BITS {
+                return None;
+            }
+            if l.is_zero() && exp < 0 {
+                return None;
+            }
+            l.pow(exp)
+        }
+        _ if !l.is_integer() || !r.is_integer() => return None,
+        _ => {
+            let (l, r) = (l.to_integer(), r.to_integer());
+            BigRational::from_integer(match op {
+                Rem if !r.is_zero() => l % r,
+                BitAnd => l & r,
+                BitOr => l | r,
+                BitXor => l ^ r,
+                Shl | Shr | Sar if !r.is_negative() => {
+                    let shift = u64::try_from(r).ok().filter(|&s| s <= MAX_LITERAL_BITS)?;
+                    if op == Shl {
+                        l << shift
+                    } else {
+                        l >> shift
+                    }
+                }
+                _ => return None,
+            })
+        }
+    })
+}
+
+/// Formats the value of a number literal expression as its type is displayed by `solc`, such as
+/// `int_const 42` and `rational_const 1 / 2`, omitting the middle digits of long numbers.
+fn display_literal_value(value: &BigRational) -> String {
+    let abbreviate = |n: &num_bigint::BigInt| {
+        let s = n.to_string();
+        let (sign, digits) = s.split_at(s.starts_with('-') as usize);
+        if digits.len() <= 20 {
+            return s;
+        }
+        let omitted = digits.len() - 8;
+        format!(
+            "{sign}{}...({omitted} digits omitted)...{}",
+            &digits[..4],
+            &digits[digits.len() - 4..]
+        )
+    };
+    if value.is_integer() {
+        format!("int_const {}", abbreviate(value.numer()))
+    } else {
+        format!("rational_const {} / {}", abbreviate(value.numer()), abbreviate(value.denom()))
+    }
+}
+
+/// Returns `true` if `from` is implicitly convertible to `to`.
+///
+/// This is conservative, and only returns `false` for conversions that are known to be invalid.
+pub(super) fn is_implicitly_convertible(
+    gcx: Gcx<'_>,
+    from: Ty<'_>,
+    to: Ty<'_>,
+    is_pointer: bool,
+) -> bool {
+    use ElementaryType::*;
+
+    if from == to || from.has_error().is_err() || to.has_error().is_err() {
+        return true;
+    }
+
+    match (from.kind, to.kind) {
+        (TyKind::Elementary(from), TyKind::Elementary(to)) => match (from, to) {
+            (UInt(a), UInt(b)) | (Int(a), Int(b)) | (FixedBytes(a), FixedBytes(b)) => {
+                a.bits() <= b.bits()
+            }
+            (UInt(a), Int(b)) => a.bits() < b.bits(),
+            (Address(_), Address(false)) => true,
+            (Fixed(..) | UFixed(..), _) | (_, Fixed(..) | UFixed(..)) => true,
+            _ => false,
+        },
+
+        (TyKind::IntLiteral(size), TyKind::Elementary(UInt(b) | Int(b))) => {
+            size.bytes_raw() <= b.bytes()
+        }
+        (TyKind::IntLiteral(_), TyKind::Elementary(Bool)) => false,
+        (TyKind::IntLiteral(_), TyKind::Ref(to, _)) => !is_bytes_or_string(to),
+
+        (TyKind::StringLiteral(_, len), TyKind::Elementary(FixedBytes(n))) => {
+            len.bytes_raw() <= n.bytes()
+        }
+        (TyKind::StringLiteral(valid_utf8, _), TyKind::Elementary(String)) => valid_utf8,
+        (TyKind::StringLiteral(..), TyKind::Elementary(Bytes)) => true,
+        (TyKind::StringLiteral(..), TyKind::Elementary(_)) => false,
+        (TyKind::StringLiteral(valid_utf8, _), TyKind::Ref(to, _)) => match to.kind {
+            TyKind::Elementary(String) => valid_utf8,
+            TyKind::Elementary(Bytes) => true,
+            _ => !to.is_value_type(),
+        },
+
+        (TyKind::Ref(from, from_loc), TyKind::Ref(to, to_loc)) => {
+            if let (TyKind::Elementary(a), TyKind::Elementary(b)) = (from.kind, to.kind) {
+                if a != b {
+                    return false;
+                }
+            }
+            if from != to {
+                return false;
+            }
+            match to_loc {
+                DataLocation::Storage if is_pointer => from_loc == DataLocation::Storage,
+                DataLocation::Calldata => from_loc == DataLocation::Calldata,
+                _ => true,
+            }
+        }
+        (TyKind::Ref(..), _) => !to.is_value_type(),
+        (_, TyKind::Ref(..)) => !from.is_value_type(),
+
+        // Contracts can only be converted to their bases.
+        (TyKind::Contract(from), TyKind::Contract(to)) => {
+            gcx.hir.contract(from).linearized_bases.contains(&to)
+        }
+        (TyKind::Struct(_), TyKind::Struct(_))
+        | (TyKind::Enum(_), TyKind::Enum(_))
+        | (TyKind::Mapping(..), TyKind::Mapping(..)) => false,
+        (TyKind::Contract(_), TyKind::Elementary(_)) => false,
+        (TyKind::Elementary(_), TyKind::Contract(_)) => false,
+        (TyKind::Enum(_) | TyKind::Udvt(..), TyKind::Elementary(_))
+        | (TyKind::Elementary(_), TyKind::Enum(_) | TyKind::Udvt(..)) => false,
+        (TyKind::Udvt(..), TyKind::Udvt(..)) => false,
+
+        (TyKind::Tuple(a), TyKind::Tuple(b)) => {
+            a.len() == b.len()
+                && a.iter().zip(b).all(|(&a, &b)| is_implicitly_convertible(gcx, a, b, false))
+        }
+
+        _ => true,
+    }
+}
+
+/// Returns `true` if `from` is explicitly convertible to `to`.
+///
+/// This is conservative, and only returns `false` for conversions that are known to be invalid.
+fn is_explicitly_convertible(gcx: Gcx<'_>, from: Ty<'_>, to: Ty<'_>) -> bool {
+    use ElementaryType::*;
+
+    if is_implicitly_convertible(gcx, from, to, false) {
+        return true;
+    }
+
+    let from_bool = matches!(from.kind, TyKind::Elementary(Bool));
+    let to_bool = matches!(to.kind, TyKind::Elementary(Bool));
+    if from_bool != to_bool {
+        return false;
+    }
+
+    match (from.kind, to.kind) {
+        // Unsigned integers can be converted to integers of any size and sign, but only to fixed
+        // bytes and addresses of the same size.
+        (TyKind::Elementary(UInt(a)), TyKind::Elementary(FixedBytes(b)))
+        | (TyKind::Elementary(FixedBytes(a)), TyKind::Elementary(UInt(b))) => {
+            a.bytes() == b.bytes()
+        }
+        (TyKind::Elementary(Int(_)), TyKind::Elementary(Address(_))) => false,
+        (TyKind::Elementary(UInt(a)), TyKind::Elementary(Address(_))) => a.bits() == 160,
+        (TyKind::Elementary(FixedBytes(a)), TyKind::Elementary(Address(_))) => a.bytes() == 20,
+        (TyKind::Elementary(Address(_)), TyKind::Elementary(UInt(b))) => b.bits() == 160,
+        (TyKind::Elementary(Address(_)), TyKind::Elementary(FixedBytes(b))) => b.bytes() == 20,
+        (TyKind::Elementary(Address(_)), TyKind::Elementary(Int(_))) => false,
+        _ => true,
+    }
+}
+
+fn is_bytes_or_string(ty: Ty<'_>) -> bool {
+    matches!(ty.kind, TyKind::Elementary(ElementaryType::Bytes | ElementaryType::String))
+}
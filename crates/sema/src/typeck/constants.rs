@@ -0,0 +1,125 @@
+use crate::{
+    builtins::Builtin,
+    hir::{self, Res},
+    ty::Gcx,
+};
+use solar_interface::{error_code, Span};
+
+/// Checks that the given constant variable is initialized with a compile-time constant.
+///
+/// Reference: <https://github.com/ethereum/solidity/blob/develop/libsolidity/analysis/TypeChecker.cpp>
+pub(super) fn check_variable(gcx: Gcx<'_>, id: hir::VariableId) {
+    let var = gcx.hir.variable(id);
+    if !var.is_constant() {
+        return;
+    }
+    let Some(init) = var.initializer else { return };
+    let Some(span) = non_constant_expr(gcx, init) else { return };
+    let mut err = gcx
+        .dcx()
+        .err("initial value for constant variable has to be compile-time constant")
+        .code(error_code!(8349))
+        .span(init.span);
+    if span != init.span {
+        err = err.span_note(span, "this expression is not a compile-time constant");
+    }
+    err.emit();
+}
+
+/// Returns the span of the first subexpression of `expr` that is not a compile-time constant.
+///
+/// Expressions whose constness can't be known before type checking, like the members of
+/// constants, are assumed to be constant.
+fn non_constant_expr<'a>(gcx: Gcx<'_>, expr: &'a hir::Expr<'a>) -> Option<Span> {
+    let first = |exprs: &mut dyn Iterator<Item = &'a hir::Expr<'a>>| {
+        exprs.find_map(|expr| non_constant_expr(gcx, expr))
+    };
+    match expr.kind {
+        hir::ExprKind::Array(exprs) => first(&mut exprs.iter()),
+        hir::ExprKind::Binary(lhs, _, rhs) => first(&mut [lhs, rhs].into_iter()),
+        hir::ExprKind::Call(callee, ref args) => {
+            if !is_constant_callee(callee.peel_parens()) {
+                return Some(expr.span);
+            }
+            first(&mut std::iter::once(callee).chain(args.exprs()))
+        }
+        hir::ExprKind::Ident(res) => match *res {
+            [Res::Item(hir::ItemId::Variable(id))] if !gcx.hir.variable(id).is_constant() => {
+                Some(expr.span)
+            }
+            [Res::Item(hir::ItemId::Function(_)), ..] => Some(expr.span),
+            [Res::Builtin(builtin)] if !is_constant_builtin(builtin) => Some(expr.span),
+            _ => None,
+        },
+        hir::ExprKind::Index(base, index) => first(&mut std::iter::once(base).chain(index)),
+        hir::ExprKind::Slice(base, start, end) => {
+            first(&mut std::iter::once(base).chain(start).chain(end))
+        }
+        // Report `msg.sender` instead of `msg`.
+        hir::ExprKind::Member(base, _) => non_constant_expr(gcx, base).map(|_| expr.span),
+        hir::ExprKind::Payable(expr) => non_constant_expr(gcx, expr),
+        hir::ExprKind::Ternary(cond, then, else_) => first(&mut [cond, then, else_].into_iter()),
+        hir::ExprKind::Tuple(exprs) => first(&mut exprs.iter().flatten().copied()),
+        hir::ExprKind::Unary(op, operand) => {
+            use hir::UnOpKind::*;
+            if matches!(op.kind, PreInc | PreDec | PostInc | PostDec) {
+                return Some(expr.span);
+            }
+            non_constant_expr(gcx, operand)
+        }
+        hir::ExprKind::Assign(..)
+        | hir::ExprKind::CallOptions(..)
+        | hir::ExprKind::Delete(_)
+        | hir::ExprKind::New(_) => Some(expr.span),
+        hir::ExprKind::Lit(_)
+        | hir::ExprKind::TypeCall(_)
+        | hir::ExprKind::Type(_)
+        | hir::ExprKind::Err(_) => None,
+    }
+}
+
+/// Returns `true` if calling `callee` with constant arguments results in a compile-time
+/// constant.
+///
+/// These are type conversions, struct constructors, and pure builtin functions.
+fn is_constant_callee(callee: &hir::Expr<'_>) -> bool {
+    match callee.kind {
+        hir::ExprKind::Ident(res) => match *res {
+            [Res::Item(item)] => matches!(
+                item,
+                hir::ItemId::Contract(_)
+                    | hir::ItemId::Struct(_)
+                    | hir::ItemId::Enum(_)
+                    | hir::ItemId::Udvt(_)
+            ),
+            [Res::Builtin(builtin)] => is_constant_builtin(builtin),
+            [Res::Err(_)] => true,
+            _ => false,
+        },
+        // `abi.encode`, `bytes.concat`, `U.wrap`.
+        hir::ExprKind::Member(base, _) => match base.peel_parens().kind {
+            hir::ExprKind::Ident(res) => matches!(
+                *res,
+                [Res::Builtin(Builtin::Abi)] | [Res::Item(hir::ItemId::Udvt(_))] | [Res::Err(_)]
+            ),
+            hir::ExprKind::Type(_) => true,
+            _ => false,
+        },
+        hir::ExprKind::Type(_) | hir::ExprKind::Err(_) => true,
+        _ => false,
+    }
+}
+
+/// Returns `true` if the given builtin is a pure function or module.
+fn is_constant_builtin(builtin: Builtin) -> bool {
+    matches!(
+        builtin,
+        Builtin::Abi
+            | Builtin::AddMod
+            | Builtin::MulMod
+            | Builtin::Keccak256
+            | Builtin::Sha256
+            | Builtin::Ripemd160
+            | Builtin::EcRecover
+    )
+}
@@ -0,0 +1,57 @@
+use crate::{
+    cfg::{BlockId, Terminator},
+    hir,
+    ty::Gcx,
+};
+use solar_interface::error_code;
+
+/// Checks the control flow of the body of the given function or modifier.
+///
+/// Warns about unreachable code, and about functions with unnamed return variables that can
+/// return without a value.
+///
+/// Reference: <https://github.com/ethereum/solidity/blob/develop/libsolidity/analysis/ControlFlowAnalyzer.cpp>
+pub(super) fn check_function(gcx: Gcx<'_>, id: hir::FunctionId) {
+    let Some(cfg) = gcx.function_cfg(id) else { return };
+    let reachable = cfg.reachable_blocks();
+    let is_reachable = |&(id, _): &(BlockId, _)| reachable[id];
+
+    let func = gcx.hir.function(id);
+    let has_unnamed_returns = func.returns.iter().any(|&ret| gcx.hir.variable(ret).name.is_none());
+    if has_unnamed_returns
+        && cfg
+            .blocks()
+            .iter_enumerated()
+            .filter(is_reachable)
+            .any(|(_, block)| matches!(block.terminator, Terminator::Return(None)))
+    {
+        let span = func.name.map_or(func.span, |name| name.span);
+        gcx.dcx()
+            .warn("function must return a value on all paths")
+            .code(error_code!(6321))
+            .span(span)
+            .help(
+                "add an explicit `return` with a value to all non-reverting code paths, or name \
+                 the return variables",
+            )
+            .emit();
+    }
+
+    // Merge the overlapping spans, like the span of an unreachable `if` statement and the spans
+    // of its branches, so that each region of unreachable code is reported once.
+    let mut unreachable = cfg
+        .blocks()
+        .iter_enumerated()
+        .filter(|block| !is_reachable(block))
+        .map(|(_, block)| block.span)
+        .filter(|span| !span.is_dummy())
+        .collect::<Vec<_>>();
+    unreachable.sort_by_key(|span| span.lo());
+    let mut unreachable = unreachable.into_iter().peekable();
+    while let Some(mut span) = unreachable.next() {
+        while let Some(next) = unreachable.next_if(|next| next.lo() <= span.hi()) {
+            span = span.to(next);
+        }
+        gcx.dcx().warn("unreachable code").code(error_code!(5740)).span(span).emit();
+    }
+}
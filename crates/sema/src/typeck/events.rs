@@ -0,0 +1,53 @@
+use crate::{
+    hir,
+    ty::{Gcx, TyKind},
+};
+use solar_ast::Visibility;
+use solar_interface::error_code;
+use std::ops::ControlFlow;
+
+/// Checks the number of indexed parameters and the parameter types of the given event.
+pub(super) fn check_event(gcx: Gcx<'_>, id: hir::EventId) {
+    let event = gcx.hir.event(id);
+    let indexed = event.parameters.iter().filter(|&&param| gcx.hir.variable(param).indexed);
+    let (max, msg, code) = if event.anonymous {
+        (4, "more than 4 indexed arguments for anonymous event", error_code!(8598))
+    } else {
+        (3, "more than 3 indexed arguments for event", error_code!(7249))
+    };
+    if indexed.count() > max {
+        gcx.dcx().err(msg).code(code).span(event.span).emit();
+    }
+    check_parameters(gcx, event.parameters, "event");
+}
+
+/// Checks the parameter types of the given error.
+pub(super) fn check_error(gcx: Gcx<'_>, id: hir::ErrorId) {
+    check_parameters(gcx, gcx.hir.error(id).parameters, "error");
+}
+
+/// Checks that the given event or error parameters can be ABI-encoded.
+fn check_parameters(gcx: Gcx<'_>, params: &[hir::VariableId], what: &str) {
+    for &param in params {
+        let ty = gcx.type_of_item(param.into());
+        if ty.has_error().is_err() {
+            continue;
+        }
+        let is_internal_fn = ty
+            .visit(&mut |ty| match ty.kind {
+                TyKind::FnPtr(f) if f.visibility <= Visibility::Internal => ControlFlow::Break(()),
+                _ => ControlFlow::Continue(()),
+            })
+            .is_break();
+        let msg = if ty.has_mapping() {
+            format!("types containing mappings cannot be {what} parameter types")
+        } else if ty.is_recursive() {
+            format!("recursive types cannot be {what} parameter types")
+        } else if is_internal_fn {
+            format!("internal function types cannot be {what} parameter types")
+        } else {
+            continue;
+        };
+        gcx.dcx().err(msg).span(gcx.hir.variable(param).ty.span).emit();
+    }
+}
@@ -0,0 +1,327 @@
+use crate::{
+    hir::{self, CallArgs, Res},
+    ty::Gcx,
+};
+use solar_data_structures::map::FxHashSet;
+use solar_interface::{error_code, Span};
+
+/// Checks the initialization of the immutable state variables of the given contract.
+///
+/// Immutables must be assigned exactly once, either inline or directly in the constructor of the
+/// contract that declares them, and cannot be read at construction time before they are
+/// initialized.
+///
+/// Reference: <https://github.com/ethereum/solidity/blob/develop/libsolidity/analysis/ImmutableValidator.cpp>
+pub(super) fn check_contract(gcx: Gcx<'_>, id: hir::ContractId) {
+    let c = gcx.hir.contract(id);
+
+    for f in c.functions().chain(c.fallback).chain(c.receive) {
+        let func = gcx.hir.function(f);
+        let mut checker = ImmutableChecker::new(gcx, id, false);
+        for modifier in func.modifiers {
+            checker.visit_args(&modifier.args);
+        }
+        if let Some(body) = func.body {
+            checker.visit_block(body);
+        }
+    }
+
+    // Simulate the construction of the contract: state variables are initialized in order, and
+    // then the constructor runs. The bases have already been constructed at this point.
+    let mut checker = ImmutableChecker::new(gcx, id, true);
+    for var_id in c.variables() {
+        let var = gcx.hir.variable(var_id);
+        let Some(init) = var.initializer.filter(|_| !var.is_constant()) else { continue };
+        checker.visit_expr(init);
+        if var.is_immutable() {
+            checker.initialized.insert(var_id);
+        }
+    }
+    if let Some(ctor) = c.ctor {
+        let ctor = gcx.hir.function(ctor);
+        for modifier in ctor.modifiers {
+            checker.visit_args(&modifier.args);
+        }
+        if let Some(body) = ctor.body {
+            checker.in_ctor = true;
+            checker.visit_block(body);
+            checker.in_ctor = false;
+        }
+    }
+
+    let uninitialized = c
+        .variables()
+        .filter(|&var| gcx.hir.variable(var).is_immutable())
+        .filter(|var| !checker.initialized.contains(var))
+        .collect::<Vec<_>>();
+    if !uninitialized.is_empty() {
+        let mut err = gcx
+            .dcx()
+            .err("construction control flow ends without initializing all immutables")
+            .code(error_code!(2658))
+            .span(c.name.span);
+        for var in uninitialized {
+            err = err.span_note(gcx.hir.variable(var).span, "not initialized");
+        }
+        err.emit();
+    }
+}
+
+/// Checks the reads and writes of immutables in a function body or a state variable initializer.
+struct ImmutableChecker<'gcx> {
+    gcx: Gcx<'gcx>,
+    /// The contract being checked.
+    contract: hir::ContractId,
+    /// Whether the code runs at construction time. Otherwise, immutables cannot be written to.
+    construction: bool,
+    /// Whether the code is the body of the constructor, where immutables can be assigned.
+    in_ctor: bool,
+    /// The statement that the code is nested in, where immutables cannot be assigned, like
+    /// "a loop".
+    nested: Option<&'static str>,
+    /// The immutables of the contract that have been initialized so far.
+    initialized: FxHashSet<hir::VariableId>,
+    /// The functions that have been called at construction time.
+    called: FxHashSet<hir::FunctionId>,
+}
+
+impl<'gcx> ImmutableChecker<'gcx> {
+    fn new(gcx: Gcx<'gcx>, contract: hir::ContractId, construction: bool) -> Self {
+        Self {
+            gcx,
+            contract,
+            construction,
+            in_ctor: false,
+            nested: None,
+            initialized: FxHashSet::default(),
+            called: FxHashSet::default(),
+        }
+    }
+
+    fn visit_block(&mut self, block: hir::Block<'gcx>) {
+        for stmt in block {
+            self.visit_stmt(stmt);
+        }
+    }
+
+    /// Visits a statement in which immutables cannot be assigned.
+    fn visit_nested(&mut self, what: &'static str, f: impl FnOnce(&mut Self)) {
+        let prev = self.nested;
+        self.nested = Some(prev.unwrap_or(what));
+        f(self);
+        self.nested = prev;
+    }
+
+    fn visit_stmt(&mut self, stmt: &'gcx hir::Stmt<'gcx>) {
+        match stmt.kind {
+            hir::StmtKind::DeclSingle(id) => {
+                if let Some(init) = self.gcx.hir.variable(id).initializer {
+                    self.visit_expr(init);
+                }
+            }
+            hir::StmtKind::DeclMulti(_, expr) => self.visit_expr(expr),
+            hir::StmtKind::Block(block) | hir::StmtKind::UncheckedBlock(block) => {
+                self.visit_block(block)
+            }
+            hir::StmtKind::Loop(block, _) => {
+                self.visit_nested("a loop", |this| this.visit_block(block))
+            }
+            hir::StmtKind::Emit(_, ref args) | hir::StmtKind::Revert(_, ref args) => {
+                self.visit_args(args)
+            }
+            hir::StmtKind::Return(expr) => {
+                if let Some(expr) = expr {
+                    self.visit_expr(expr);
+                }
+            }
+            hir::StmtKind::If(cond, then, else_) => {
+                self.visit_expr(cond);
+                self.visit_nested("an if statement", |this| {
+                    this.visit_stmt(then);
+                    if let Some(else_) = else_ {
+                        this.visit_stmt(else_);
+                    }
+                });
+            }
+            hir::StmtKind::Try(try_) => {
+                self.visit_expr(&try_.expr);
+                self.visit_nested("a try/catch statement", |this| {
+                    this.visit_block(try_.block);
+                    for catch in try_.catch {
+                        this.visit_block(catch.block);
+                    }
+                });
+            }
+            hir::StmtKind::Expr(expr) => self.visit_expr(expr),
+            // Immutables cannot be accessed in inline assembly.
+            hir::StmtKind::Assembly(_)
+            | hir::StmtKind::Break
+            | hir::StmtKind::Continue
+            | hir::StmtKind::Placeholder
+            | hir::StmtKind::Err(_) => {}
+        }
+    }
+
+    fn visit_args(&mut self, args: &'gcx CallArgs<'gcx>) {
+        for expr in args.exprs() {
+            self.visit_expr(expr);
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &'gcx hir::Expr<'gcx>) {
+        match expr.kind {
+            hir::ExprKind::Array(exprs) => {
+                for expr in exprs {
+                    self.visit_expr(expr);
+                }
+            }
+            hir::ExprKind::Assign(lhs, op, rhs) => {
+                self.visit_expr(rhs);
+                self.visit_lvalue(lhs, op.is_some());
+            }
+            hir::ExprKind::Binary(lhs, _, rhs) => {
+                self.visit_expr(lhs);
+                self.visit_expr(rhs);
+            }
+            hir::ExprKind::Call(callee, ref args) => {
+                self.visit_expr(callee);
+                self.visit_args(args);
+                if let hir::ExprKind::Ident(&[Res::Item(hir::ItemId::Function(id))]) =
+                    callee.peel_parens().kind
+                {
+                    self.visit_call(id);
+                }
+            }
+            hir::ExprKind::CallOptions(callee, options) => {
+                self.visit_expr(callee);
+                for option in options {
+                    self.visit_expr(&option.value);
+                }
+            }
+            hir::ExprKind::Delete(expr) => self.visit_lvalue(expr, false),
+            hir::ExprKind::Ident(&[Res::Item(hir::ItemId::Variable(id))]) => {
+                self.read(id, expr.span)
+            }
+            hir::ExprKind::Index(base, index) => {
+                self.visit_expr(base);
+                if let Some(index) = index {
+                    self.visit_expr(index);
+                }
+            }
+            hir::ExprKind::Slice(base, start, end) => {
+                self.visit_expr(base);
+                for expr in [start, end].into_iter().flatten() {
+                    self.visit_expr(expr);
+                }
+            }
+            hir::ExprKind::Member(expr, _) | hir::ExprKind::Payable(expr) => self.visit_expr(expr),
+            hir::ExprKind::Ternary(cond, then, else_) => {
+                self.visit_expr(cond);
+                self.visit_expr(then);
+                self.visit_expr(else_);
+            }
+            hir::ExprKind::Tuple(exprs) => {
+                for expr in exprs.iter().flatten() {
+                    self.visit_expr(expr);
+                }
+            }
+            hir::ExprKind::Unary(op, operand) => {
+                use hir::UnOpKind::*;
+                if matches!(op.kind, PreInc | PreDec | PostInc | PostDec) {
+                    self.visit_lvalue(operand, true);
+                } else {
+                    self.visit_expr(operand);
+                }
+            }
+            hir::ExprKind::Ident(_)
+            | hir::ExprKind::Lit(_)
+            | hir::ExprKind::New(_)
+            | hir::ExprKind::TypeCall(_)
+            | hir::ExprKind::Type(_)
+            | hir::ExprKind::Err(_) => {}
+        }
+    }
+
+    /// Visits an expression that is assigned to. `reads` is `true` if its value is also read, like
+    /// in compound assignments.
+    fn visit_lvalue(&mut self, expr: &'gcx hir::Expr<'gcx>, reads: bool) {
+        match expr.peel_parens().kind {
+            hir::ExprKind::Ident(&[Res::Item(hir::ItemId::Variable(id))]) => {
+                if reads {
+                    self.read(id, expr.span);
+                }
+                self.write(id, expr.span);
+            }
+            hir::ExprKind::Tuple(exprs) => {
+                for expr in exprs.iter().flatten() {
+                    self.visit_lvalue(expr, reads);
+                }
+            }
+            _ => self.visit_expr(expr),
+        }
+    }
+
+    /// Visits the body of a function that is called at construction time, once.
+    fn visit_call(&mut self, id: hir::FunctionId) {
+        if !self.construction || !self.called.insert(id) {
+            return;
+        }
+        let Some(body) = self.gcx.hir.function(id).body else { return };
+        let (in_ctor, nested) = (self.in_ctor, self.nested);
+        self.in_ctor = false;
+        self.visit_block(body);
+        (self.in_ctor, self.nested) = (in_ctor, nested);
+    }
+
+    fn read(&mut self, id: hir::VariableId, span: Span) {
+        if self.construction && self.is_own_immutable(id) && !self.initialized.contains(&id) {
+            self.gcx
+                .dcx()
+                .err("immutable variables cannot be read before they are initialized")
+                .code(error_code!(7733))
+                .span(span)
+                .emit();
+        }
+    }
+
+    fn write(&mut self, id: hir::VariableId, span: Span) {
+        let var = self.gcx.hir.variable(id);
+        if !var.is_state_variable() || !var.is_immutable() {
+            return;
+        }
+        // Writes in the functions that are called by the constructor are reported when checking
+        // these functions.
+        if self.construction && !self.in_ctor {
+            return;
+        }
+        let dcx = self.gcx.dcx();
+        if !self.in_ctor {
+            let msg =
+                "cannot write to immutable here: immutable variables can only be initialized \
+                       inline or assigned directly in the constructor";
+            dcx.err(msg).code(error_code!(1581)).span(span).emit();
+        } else if var.contract != Some(self.contract) {
+            let msg = "cannot write to immutable here: immutable variables must be initialized in \
+                       the constructor of the contract they are defined in";
+            dcx.err(msg).span(span).emit();
+        } else if let Some(what) = self.nested {
+            let msg = format!(
+                "cannot write to immutable here: immutable variables cannot be initialized \
+                 inside {what}"
+            );
+            dcx.err(msg).span(span).emit();
+            self.initialized.insert(id);
+        } else if !self.initialized.insert(id) {
+            dcx.err("immutable state variable already initialized")
+                .code(error_code!(1574))
+                .span(span)
+                .emit();
+        }
+    }
+
+    /// Returns `true` if the variable is an immutable declared in the contract being checked.
+    fn is_own_immutable(&self, id: hir::VariableId) -> bool {
+        let var = self.gcx.hir.variable(id);
+        var.is_state_variable() && var.is_immutable() && var.contract == Some(self.contract)
+    }
+}
@@ -1,21 +1,209 @@
 use crate::{
     ast_lowering::resolve::{Declaration, Declarations},
+    builtins::{Builtin, Member},
     hir::{self, Res},
-    ty::{Gcx, Ty},
+    par_for_each_buffered,
+    ty::{Gcx, Ty, TyKind},
 };
-use rayon::prelude::*;
-use solar_data_structures::{map::FxHashSet, parallel};
+use solar_ast::{DataLocation, ElementaryType};
+use solar_data_structures::{
+    map::{FxHashMap, FxHashSet},
+    parallel, BumpExt,
+};
+
+mod abi_coder;
+mod base_args;
+mod checker;
+mod constants;
+mod control_flow;
+mod events;
+mod immutables;
+mod modifiers;
+mod mutability;
+mod overrides;
+mod unused;
+mod using_for;
 
 pub(crate) fn check(gcx: Gcx<'_>) {
+    let dcx = gcx.dcx();
+    let mut functions = Vec::new();
+    let mut variables = Vec::new();
+    let mut contracts = Vec::new();
+    let mut sources = Vec::new();
+    let mut events = Vec::new();
+    let mut errors = Vec::new();
+    gcx.sess.progress.start("checking", gcx.hir.function_ids().count());
     parallel!(
         gcx.sess,
-        gcx.hir.par_contract_ids().for_each(|id| {
+        functions = par_for_each_buffered(dcx, gcx.hir.par_function_ids(), |id| {
+            checker::check_function(gcx, id);
+            abi_coder::check_function(gcx, id);
+            control_flow::check_function(gcx, id);
+            modifiers::check_function(gcx, id);
+            mutability::check_function(gcx, id);
+            unused::check_function(gcx, id);
+            gcx.sess.progress.inc();
+        }),
+        variables = par_for_each_buffered(dcx, gcx.hir.par_variable_ids(), |id| {
+            checker::check_variable(gcx, id);
+            constants::check_variable(gcx, id);
+        }),
+        contracts = par_for_each_buffered(dcx, gcx.hir.par_contract_ids(), |id| {
             check_duplicate_definitions(gcx, &gcx.symbol_resolver.contract_scopes[id]);
+            check_error_selector_collisions(gcx, id);
+            checker::check_contract(gcx, id);
+            base_args::check_contract(gcx, id);
+            immutables::check_contract(gcx, id);
+            overrides::check_contract(gcx, id);
+            using_for::check_using_directives(gcx, gcx.hir.contract(id).source, Some(id));
         }),
-        gcx.hir.par_source_ids().for_each(|id| {
+        sources = par_for_each_buffered(dcx, gcx.hir.par_source_ids(), |id| {
             check_duplicate_definitions(gcx, &gcx.symbol_resolver.source_scopes[id]);
+            using_for::check_using_directives(gcx, id, None);
+        }),
+        events = par_for_each_buffered(dcx, gcx.hir.par_event_ids(), |id| {
+            events::check_event(gcx, id);
+            abi_coder::check_event(gcx, id);
+        }),
+        errors = par_for_each_buffered(dcx, gcx.hir.par_error_ids(), |id| {
+            events::check_error(gcx, id);
         }),
     );
+    // Same order as when running sequentially, where the first block runs last.
+    for diagnostics in [variables, contracts, sources, events, errors, functions] {
+        dcx.emit_buffered(diagnostics);
+    }
+}
+
+/// Computes the type of the given expression in `func`, if it can be computed without checking it.
+pub(crate) fn expr_ty<'gcx>(
+    gcx: Gcx<'gcx>,
+    func: &hir::Function<'_>,
+    expr: &'gcx hir::Expr<'gcx>,
+) -> Option<Ty<'gcx>> {
+    match expr.kind {
+        hir::ExprKind::Ident(res) => match *res {
+            [Res::Item(id @ (hir::ItemId::Variable(_) | hir::ItemId::Function(_)))] => {
+                Some(gcx.type_of_item(id))
+            }
+            [Res::Builtin(Builtin::This)] => func.contract.map(|id| gcx.type_of_item(id.into())),
+            [Res::Builtin(builtin)] if Builtin::global().contains(&builtin) => {
+                Some(builtin.ty(gcx))
+            }
+            _ => None,
+        },
+        hir::ExprKind::Member(base, member) => {
+            let ty = expr_ty(gcx, func, base)?;
+            let mut members = members(gcx, func, ty)?.iter().filter(|m| m.name == member.name);
+            let member = members.next()?;
+            if members.next().is_some() {
+                return None;
+            }
+            // Struct members are always wrapped in a reference, even value types.
+            match member.ty.kind {
+                TyKind::Ref(inner, _) if inner.is_value_type() => Some(inner),
+                _ => Some(member.ty),
+            }
+        }
+        hir::ExprKind::Index(base, Some(_)) => {
+            let ty = expr_ty(gcx, func, base)?;
+            let (inner, loc) = match ty.kind {
+                TyKind::Ref(inner, loc) => (inner, loc),
+                _ => (ty, DataLocation::Storage),
+            };
+            let element = match inner.kind {
+                TyKind::Array(element, _) | TyKind::DynArray(element) => element,
+                TyKind::Elementary(ElementaryType::Bytes) => gcx.types.fixed_bytes(1),
+                TyKind::Mapping(_, value) => value,
+                _ => return None,
+            };
+            Some(if element.is_reference_type() { element.with_loc(gcx, loc) } else { element })
+        }
+        hir::ExprKind::Call(callee, _) => match expr_ty(gcx, func, callee)?.kind {
+            TyKind::FnPtr(f) => match f.returns {
+                [ret] => Some(*ret),
+                _ => None,
+            },
+            TyKind::Type(ty) if !ty.is_reference_type() => Some(ty),
+            _ => None,
+        },
+        hir::ExprKind::CallOptions(callee, _) => expr_ty(gcx, func, callee),
+        hir::ExprKind::Payable(_) => Some(gcx.types.address_payable),
+        hir::ExprKind::Tuple(&[Some(expr)]) => expr_ty(gcx, func, expr),
+        hir::ExprKind::Type(hir::Type { kind: hir::TypeKind::Elementary(ty), .. }) => {
+            Some(gcx.mk_ty(TyKind::Elementary(ty)).make_type_type(gcx))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the members of the given type, if they can be computed, including the functions
+/// attached to it by the `using for` directives in scope in `func`.
+pub(crate) fn members<'gcx>(
+    gcx: Gcx<'gcx>,
+    func: &hir::Function<'_>,
+    ty: Ty<'gcx>,
+) -> Option<&'gcx [Member<'gcx>]> {
+    let members = match ty.kind {
+        TyKind::Elementary(ElementaryType::Address(_))
+        | TyKind::Ref(..)
+        | TyKind::Contract(_)
+        | TyKind::BuiltinModule(_)
+        | TyKind::Type(_)
+        | TyKind::Meta(_) => Some(gcx.members_of(ty)),
+        _ => None,
+    };
+    if matches!(
+        ty.kind,
+        TyKind::BuiltinModule(_)
+            | TyKind::Module(_)
+            | TyKind::Type(_)
+            | TyKind::Meta(_)
+            | TyKind::Error(..)
+            | TyKind::Event(..)
+            | TyKind::Err(_)
+    ) {
+        return members;
+    }
+    let attached = using_for::attached_members(gcx, ty, func.source, func.contract);
+    if attached.is_empty() {
+        return members;
+    }
+    let members = members.unwrap_or_default().iter().copied().chain(attached);
+    Some(&*gcx.bump().alloc_vec(members.collect()))
+}
+
+/// Checks for errors in the interface of the given contract whose selectors collide.
+///
+/// Function selector collisions are checked in [`Gcx::interface_functions`].
+fn check_error_selector_collisions(gcx: Gcx<'_>, id: hir::ContractId) {
+    let mut signatures_seen = FxHashSet::default();
+    let mut selectors = FxHashMap::default();
+    for item in gcx.interface_events_and_errors(id) {
+        let hir::ItemId::Error(error_id) = item else { continue };
+        // The same error can be declared multiple times, e.g. in different sources.
+        let signature = gcx.item_signature(item);
+        if !signatures_seen.insert(signature) {
+            continue;
+        }
+
+        let selector = gcx.function_selector(error_id);
+        if let Some(prev) = selectors.insert(selector, error_id) {
+            let msg = "error signature hash collision";
+            let full_note = format!(
+                "the error signatures `{}` and `{}` produce the same 4-byte selector `{selector}`",
+                signature,
+                gcx.item_signature(prev.into()),
+            );
+            gcx.dcx()
+                .err(msg)
+                .span(gcx.hir.contract(id).name.span)
+                .span_note(gcx.item_span(prev), "first error")
+                .span_note(gcx.item_span(error_id), "second error")
+                .note(full_note)
+                .emit();
+        }
+    }
 }
 
 /// Checks for definitions that have the same name and parameter types in the given scope.
@@ -0,0 +1,125 @@
+use crate::{
+    cfg::{BasicBlock, Cfg, Terminator},
+    hir,
+    ty::Gcx,
+};
+use solar_data_structures::{index::IndexVec, map::FxHashSet};
+
+/// Checks the placeholder statements and the recursion of the given modifier.
+///
+/// Modifiers are inlined into the functions that invoke them, so each path through the body of a
+/// modifier should execute `_` exactly once, and modifiers cannot invoke themselves, directly or
+/// through other modifiers.
+///
+/// Reference: <https://docs.soliditylang.org/en/latest/contracts.html#function-modifiers>
+pub(super) fn check_function(gcx: Gcx<'_>, id: hir::FunctionId) {
+    let func = gcx.hir.function(id);
+    if !func.kind.is_modifier() {
+        return;
+    }
+    check_placeholders(gcx, id);
+    check_recursion(gcx, id);
+}
+
+/// The number of placeholder statements that can have been executed when reaching a point of the
+/// body, as a set of zero, one, and more than one.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+struct Counts(u8);
+
+impl Counts {
+    const ZERO: Self = Self(0b001);
+    const MANY: Self = Self(0b100);
+
+    fn contains(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// Returns the counts after executing `n` more placeholder statements.
+    fn add(self, n: usize) -> Self {
+        let mut counts = Self::default();
+        for count in 0..3 {
+            if self.0 & (1 << count) != 0 {
+                counts.0 |= 1 << (count + n).min(2);
+            }
+        }
+        counts
+    }
+}
+
+fn check_placeholders(gcx: Gcx<'_>, id: hir::FunctionId) {
+    let Some(cfg) = gcx.function_cfg(id) else { return };
+    let blocks = cfg.blocks();
+    let placeholders = |block: &BasicBlock<'_>| {
+        block.stmts.iter().filter(|stmt| matches!(stmt.kind, hir::StmtKind::Placeholder)).count()
+    };
+
+    // Propagate the counts forward until a fixed point is reached. Reverting paths don't return
+    // to the function, so they are not taken into account.
+    let mut entry = IndexVec::from_elem_n(Counts::default(), blocks.len());
+    entry[Cfg::ENTRY] = Counts::ZERO;
+    let mut worklist = vec![Cfg::ENTRY];
+    let mut returned = Counts::default();
+    while let Some(block) = worklist.pop() {
+        let exit = entry[block].add(placeholders(&blocks[block]));
+        let terminator = &blocks[block].terminator;
+        if matches!(terminator, Terminator::Return(_)) {
+            returned = returned.union(exit);
+        }
+        for succ in terminator.successors() {
+            let counts = entry[succ].union(exit);
+            if counts != entry[succ] {
+                entry[succ] = counts;
+                worklist.push(succ);
+            }
+        }
+    }
+
+    let func = gcx.hir.function(id);
+    let span = func.name.map_or(func.span, |name| name.span);
+    if returned.contains(Counts::ZERO) {
+        let has_placeholder = blocks.iter().any(|block| placeholders(block) > 0);
+        let msg = if has_placeholder {
+            "modifier can return without executing `_`"
+        } else {
+            "modifier body does not contain `_`"
+        };
+        gcx.dcx()
+            .warn(msg)
+            .span(span)
+            .help("the body of the modified function only runs where `_;` is executed")
+            .emit();
+    }
+    if returned.contains(Counts::MANY) {
+        gcx.dcx().warn("modifier can execute `_` more than once").span(span).emit();
+    }
+}
+
+/// Checks that the given modifier doesn't call itself, directly or through other modifiers.
+fn check_recursion(gcx: Gcx<'_>, id: hir::FunctionId) {
+    let mut seen = FxHashSet::default();
+    let mut stack = vec![id];
+    while let Some(modifier) = stack.pop() {
+        for call in gcx.function_calls(modifier) {
+            if !gcx.hir.function(call.callee).kind.is_modifier() {
+                continue;
+            }
+            if call.callee == id {
+                let func = gcx.hir.function(id);
+                let span = func.name.map_or(func.span, |name| name.span);
+                gcx.dcx()
+                    .err("modifier invokes itself recursively")
+                    .span(span)
+                    .span_note(call.span, "recursive invocation here")
+                    .emit();
+                return;
+            }
+            if seen.insert(call.callee) {
+                stack.push(call.callee);
+            }
+        }
+    }
+}
@@ -0,0 +1,451 @@
+use crate::{
+    builtins::{Builtin, Member},
+    hir::{self, CallArgs, Res},
+    ty::{Gcx, Ty, TyKind},
+};
+use solar_ast::{DataLocation, ElementaryType, LitKind, StateMutability as SM};
+use solar_interface::{error_code, kw, sym, Span, Symbol};
+
+/// Checks that the body of the given function does not require a less restrictive state
+/// mutability than the declared one, and warns if the declared one can be restricted further.
+///
+/// Reference: <https://github.com/ethereum/solidity/blob/develop/libsolidity/analysis/ViewPureChecker.cpp>
+pub(super) fn check_function(gcx: Gcx<'_>, id: hir::FunctionId) {
+    let func = gcx.hir.function(id);
+    if func.kind.is_modifier() {
+        return;
+    }
+    let Some(body) = func.body else { return };
+    let mut checker = MutabilityChecker {
+        gcx,
+        function: func,
+        best: SM::Pure,
+        unknown: false,
+        reported: Vec::new(),
+    };
+    for modifier in func.modifiers {
+        checker.check_args(&modifier.args);
+    }
+    checker.check_block(body);
+
+    // The bodies of the invoked modifiers are not checked, so we can't know what they read or
+    // write.
+    let has_modifiers = func.modifiers.iter().any(
+        |m| matches!(m.id, hir::ItemId::Function(m) if gcx.hir.function(m).kind.is_modifier()),
+    );
+    if checker.unknown
+        || has_modifiers
+        || body.is_empty()
+        || !func.kind.is_ordinary()
+        || func.virtual_
+        || func.override_
+        || func.state_mutability == SM::Payable
+        || rank(checker.best) >= rank(func.state_mutability)
+    {
+        return;
+    }
+    let msg = format!("function state mutability can be restricted to `{}`", checker.best);
+    let span = func.name.map_or(func.span, |name| name.span);
+    gcx.dcx().warn(msg).code(error_code!(2018)).span(span).emit();
+}
+
+/// Returns the rank of the given state mutability, from the most to the least restrictive.
+fn rank(sm: SM) -> u8 {
+    match sm {
+        SM::Pure => 0,
+        SM::View => 1,
+        SM::NonPayable => 2,
+        SM::Payable => 3,
+    }
+}
+
+/// State mutability checker.
+///
+/// Like the type checker, this works on a best-effort basis: expressions whose type cannot be
+/// computed yet are skipped, and only disable the warning for functions whose state mutability
+/// can be restricted.
+struct MutabilityChecker<'gcx> {
+    gcx: Gcx<'gcx>,
+    /// The function whose body is being checked.
+    function: &'gcx hir::Function<'gcx>,
+    /// The most restrictive state mutability that the body allows, so far.
+    best: SM,
+    /// Whether the body contains an expression whose state mutability could not be computed.
+    unknown: bool,
+    /// The spans of the expressions that errors were reported for, so that their subexpressions
+    /// are not reported again.
+    reported: Vec<Span>,
+}
+
+impl<'gcx> MutabilityChecker<'gcx> {
+    fn check_block(&mut self, block: hir::Block<'gcx>) {
+        for stmt in block {
+            self.check_stmt(stmt);
+        }
+    }
+
+    fn check_stmt(&mut self, stmt: &'gcx hir::Stmt<'gcx>) {
+        match stmt.kind {
+            hir::StmtKind::Assembly(assembly) => {
+                for &(name, span) in assembly.calls {
+                    if let Some(sm) = yul_instruction_mutability(name) {
+                        self.report(sm, span);
+                    }
+                }
+            }
+            hir::StmtKind::DeclSingle(id) => {
+                if let Some(init) = self.gcx.hir.variable(id).initializer {
+                    self.check_expr(init, false);
+                }
+            }
+            hir::StmtKind::DeclMulti(_, expr) => self.check_expr(expr, false),
+            hir::StmtKind::Block(block)
+            | hir::StmtKind::UncheckedBlock(block)
+            | hir::StmtKind::Loop(block, _) => self.check_block(block),
+            hir::StmtKind::Emit(_, ref args) => {
+                self.report(SM::NonPayable, stmt.span);
+                self.check_args(args);
+            }
+            hir::StmtKind::Revert(_, ref args) => self.check_args(args),
+            hir::StmtKind::Return(expr) => {
+                if let Some(expr) = expr {
+                    self.check_expr(expr, false);
+                }
+            }
+            hir::StmtKind::If(cond, then, else_) => {
+                self.check_expr(cond, false);
+                self.check_stmt(then);
+                if let Some(else_) = else_ {
+                    self.check_stmt(else_);
+                }
+            }
+            hir::StmtKind::Try(try_) => {
+                self.check_expr(&try_.expr, false);
+                self.check_block(try_.block);
+                for catch in try_.catch {
+                    self.check_block(catch.block);
+                }
+            }
+            hir::StmtKind::Expr(expr) => self.check_expr(expr, false),
+            hir::StmtKind::Break | hir::StmtKind::Continue | hir::StmtKind::Placeholder => {}
+            hir::StmtKind::Err(_) => self.unknown = true,
+        }
+    }
+
+    fn check_args(&mut self, args: &'gcx CallArgs<'gcx>) {
+        for expr in args.exprs() {
+            self.check_expr(expr, false);
+        }
+    }
+
+    /// Checks the given expression. `writes` is `true` if the expression is assigned to.
+    fn check_expr(&mut self, expr: &'gcx hir::Expr<'gcx>, writes: bool) {
+        match expr.kind {
+            hir::ExprKind::Array(exprs) => {
+                for expr in exprs {
+                    self.check_expr(expr, false);
+                }
+            }
+            hir::ExprKind::Assign(lhs, _, rhs) => {
+                self.check_expr(lhs, true);
+                self.check_expr(rhs, false);
+            }
+            hir::ExprKind::Binary(lhs, _, rhs) => {
+                self.check_expr(lhs, false);
+                self.check_expr(rhs, false);
+            }
+            hir::ExprKind::Call(callee, ref args) => {
+                match self.call_mutability(callee, args.len()) {
+                    // Calling a payable function doesn't require the caller to be payable.
+                    Some(SM::Payable) => self.report(SM::NonPayable, expr.span),
+                    Some(sm) => self.report(sm, expr.span),
+                    None => self.unknown = true,
+                }
+                self.check_expr(callee, false);
+                self.check_args(args);
+            }
+            hir::ExprKind::CallOptions(callee, options) => {
+                self.check_expr(callee, false);
+                for option in options {
+                    self.check_expr(&option.value, false);
+                }
+            }
+            hir::ExprKind::Delete(expr) => self.check_expr(expr, true),
+            hir::ExprKind::Ident(res) => self.check_ident(expr.span, res, writes),
+            hir::ExprKind::Index(base, index) => {
+                if index.is_some() && self.expr_ty(base).is_some_and(is_in_storage) {
+                    self.report(if writes { SM::NonPayable } else { SM::View }, expr.span);
+                }
+                self.check_expr(base, writes);
+                if let Some(index) = index {
+                    self.check_expr(index, false);
+                }
+            }
+            hir::ExprKind::Slice(base, start, end) => {
+                self.check_expr(base, false);
+                for expr in [start, end].into_iter().flatten() {
+                    self.check_expr(expr, false);
+                }
+            }
+            hir::ExprKind::Member(base, member) => {
+                if let Some(sm) = self.member_mutability(base, member.name, writes) {
+                    self.report(sm, expr.span);
+                }
+                self.check_expr(base, writes);
+            }
+            hir::ExprKind::Payable(expr) => self.check_expr(expr, false),
+            hir::ExprKind::Ternary(cond, then, else_) => {
+                self.check_expr(cond, false);
+                self.check_expr(then, writes);
+                self.check_expr(else_, writes);
+            }
+            hir::ExprKind::Tuple(exprs) => {
+                for expr in exprs.iter().flatten() {
+                    self.check_expr(expr, writes);
+                }
+            }
+            hir::ExprKind::Unary(op, operand) => {
+                use hir::UnOpKind::*;
+                let writes = matches!(op.kind, PreInc | PreDec | PostInc | PostDec);
+                self.check_expr(operand, writes);
+            }
+            hir::ExprKind::Lit(_)
+            | hir::ExprKind::New(_)
+            | hir::ExprKind::TypeCall(_)
+            | hir::ExprKind::Type(_) => {}
+            hir::ExprKind::Err(_) => self.unknown = true,
+        }
+    }
+
+    fn check_ident(&mut self, span: Span, res: &[Res], writes: bool) {
+        match *res {
+            [Res::Item(hir::ItemId::Variable(id))] => {
+                let var = self.gcx.hir.variable(id);
+                if !var.is_state_variable() || var.is_constant() {
+                    return;
+                }
+                if var.is_immutable() {
+                    // Immutables that are initialized with a literal are pure.
+                    let is_number_lit = var.initializer.is_some_and(|init| match init.kind {
+                        hir::ExprKind::Lit(lit) => matches!(lit.kind, LitKind::Number(_)),
+                        _ => false,
+                    });
+                    if !is_number_lit {
+                        self.report(SM::View, span);
+                    }
+                    return;
+                }
+                self.report(if writes { SM::NonPayable } else { SM::View }, span);
+            }
+            [Res::Builtin(Builtin::This)] => self.report(SM::View, span),
+            _ => {}
+        }
+    }
+
+    /// Returns the state mutability required to access the member `name` of `base`, if any.
+    fn member_mutability(
+        &self,
+        base: &'gcx hir::Expr<'gcx>,
+        name: Symbol,
+        writes: bool,
+    ) -> Option<SM> {
+        if let hir::ExprKind::Ident(&[Res::Builtin(module)]) = base.kind {
+            let builtin = module.members()?.iter().find(|b| b.name() == name)?;
+            return Some(match builtin {
+                Builtin::MsgValue => SM::Payable,
+                Builtin::MsgData | Builtin::MsgSig => SM::Pure,
+                _ if module == Builtin::Abi => SM::Pure,
+                _ => SM::View,
+            });
+        }
+        // `type(T)` members are pure.
+        if let hir::ExprKind::TypeCall(_) = base.kind {
+            return None;
+        }
+
+        let ty = self.expr_ty(base)?;
+        match ty.kind {
+            TyKind::Elementary(ElementaryType::Address(_))
+                if [kw::Balance, sym::code, sym::codehash].contains(&name) =>
+            {
+                Some(SM::View)
+            }
+            TyKind::Ref(inner, DataLocation::Storage | DataLocation::Transient) => {
+                match inner.kind {
+                    TyKind::Struct(_) => Some(if writes { SM::NonPayable } else { SM::View }),
+                    TyKind::DynArray(_) | TyKind::Elementary(ElementaryType::Bytes)
+                        if name == sym::length =>
+                    {
+                        Some(SM::View)
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the state mutability of the function called by `callee` with `n_args` arguments,
+    /// or `None` if it cannot be computed.
+    fn call_mutability(&self, callee: &'gcx hir::Expr<'gcx>, n_args: usize) -> Option<SM> {
+        let gcx = self.gcx;
+        match callee.kind {
+            hir::ExprKind::CallOptions(callee, _) => self.call_mutability(callee, n_args),
+            hir::ExprKind::Tuple(&[Some(callee)]) => self.call_mutability(callee, n_args),
+            hir::ExprKind::New(_) => Some(SM::NonPayable),
+            // Type conversions and struct constructors.
+            hir::ExprKind::Type(_) => Some(SM::Pure),
+            hir::ExprKind::Ident(res) => {
+                if let [Res::Item(
+                    hir::ItemId::Contract(_)
+                    | hir::ItemId::Struct(_)
+                    | hir::ItemId::Enum(_)
+                    | hir::ItemId::Udvt(_),
+                )] = res
+                {
+                    return Some(SM::Pure);
+                }
+                let candidates = res.iter().filter_map(|res| match *res {
+                    Res::Item(hir::ItemId::Function(id)) => {
+                        let f = gcx.hir.function(id);
+                        (f.parameters.len() == n_args).then_some(Some(f.state_mutability))
+                    }
+                    Res::Item(id @ hir::ItemId::Variable(_)) => {
+                        Some(fn_ptr_mutability(gcx.type_of_item(id)))
+                    }
+                    Res::Builtin(builtin) if Builtin::global().contains(&builtin) => {
+                        Some(fn_ptr_mutability(builtin.ty(gcx)))
+                    }
+                    _ => Some(None),
+                });
+                unique(candidates)
+            }
+            hir::ExprKind::Member(base, member) => {
+                // Internal calls to library functions and to functions of base contracts.
+                if let hir::ExprKind::Ident(&[Res::Item(hir::ItemId::Contract(id))]) = base.kind {
+                    let candidates = gcx.hir.contract(id).functions().filter_map(|f| {
+                        let f = gcx.hir.function(f);
+                        (f.name.is_some_and(|name| name.name == member.name)
+                            && f.parameters.len() == n_args)
+                            .then_some(Some(f.state_mutability))
+                    });
+                    return unique(candidates);
+                }
+                if let hir::ExprKind::Ident(&[Res::Builtin(module)]) = base.kind {
+                    let builtin = module.members()?.iter().find(|b| b.name() == member.name)?;
+                    return fn_ptr_mutability(builtin.ty(gcx));
+                }
+                let ty = self.expr_ty(base)?;
+                let candidates = self
+                    .members(ty)?
+                    .iter()
+                    .filter(|m| m.name == member.name)
+                    .map(|m| fn_ptr_mutability(m.ty));
+                unique(candidates)
+            }
+            _ => fn_ptr_mutability(self.expr_ty(callee)?),
+        }
+    }
+
+    fn expr_ty(&self, expr: &'gcx hir::Expr<'gcx>) -> Option<Ty<'gcx>> {
+        super::expr_ty(self.gcx, self.function, expr)
+    }
+
+    fn members(&self, ty: Ty<'gcx>) -> Option<&'gcx [Member<'gcx>]> {
+        super::members(self.gcx, self.function, ty)
+    }
+
+    /// Reports that the expression at `span` requires the given state mutability.
+    fn report(&mut self, sm: SM, span: Span) {
+        if rank(sm) > rank(self.best) {
+            self.best = sm;
+        }
+        if self.reported.iter().any(|reported| reported.contains(span)) {
+            return;
+        }
+
+        let gcx = self.gcx;
+        let func = self.function;
+        let declared = func.state_mutability;
+        let err = if sm == SM::View || (sm == SM::Payable && declared == SM::Pure) {
+            if declared != SM::Pure {
+                return;
+            }
+            let msg = "function declared as `pure`, but this expression (potentially) reads from \
+                       the environment or state and thus requires `view`";
+            gcx.dcx().err(msg).code(error_code!(2527))
+        } else if sm == SM::NonPayable {
+            if !matches!(declared, SM::Pure | SM::View) {
+                return;
+            }
+            let msg = format!(
+                "function cannot be declared as `{declared}` because this expression \
+                 (potentially) modifies the state"
+            );
+            gcx.dcx().err(msg).code(error_code!(8961))
+        } else if sm == SM::Payable && declared != SM::Payable {
+            if func.kind.is_constructor() {
+                let msg = "`msg.value` and `callvalue()` can only be used in payable constructors";
+                gcx.dcx()
+                    .err(msg)
+                    .code(error_code!(7793))
+                    .help("make the constructor `payable` to avoid this error")
+            } else {
+                // Internal functions are allowed to use `msg.value`, and library functions can't
+                // be payable.
+                let is_library =
+                    func.contract.is_some_and(|id| gcx.hir.contract(id).kind.is_library());
+                if func.visibility < hir::Visibility::Public || is_library {
+                    return;
+                }
+                let msg =
+                    "`msg.value` and `callvalue()` can only be used in payable public functions";
+                let help = "make the function `payable` or use an internal function to avoid \
+                            this error";
+                gcx.dcx().err(msg).code(error_code!(5887)).help(help)
+            }
+        } else {
+            return;
+        };
+        err.span(span).emit();
+        self.reported.push(span);
+    }
+}
+
+/// Returns the state mutability of the given function pointer type.
+fn fn_ptr_mutability(ty: Ty<'_>) -> Option<SM> {
+    match ty.kind {
+        TyKind::FnPtr(f) => Some(f.state_mutability),
+        _ => None,
+    }
+}
+
+/// Returns the state mutability shared by all candidates, or `None` if there are no candidates,
+/// if any of them is unknown, or if they differ.
+fn unique(mut candidates: impl Iterator<Item = Option<SM>>) -> Option<SM> {
+    let first = candidates.next()??;
+    candidates.try_fold(first, |acc, sm| (sm? == acc).then_some(acc))
+}
+
+/// Returns `true` if the given type is stored in storage or transient storage.
+fn is_in_storage(ty: Ty<'_>) -> bool {
+    matches!(
+        ty.kind,
+        TyKind::Ref(_, DataLocation::Storage | DataLocation::Transient) | TyKind::Mapping(..)
+    )
+}
+
+/// Returns the state mutability required by the given Yul instruction, if it is one that reads
+/// or modifies the state.
+fn yul_instruction_mutability(name: Symbol) -> Option<SM> {
+    Some(match name.as_str() {
+        "sstore" | "tstore" | "log0" | "log1" | "log2" | "log3" | "log4" | "create" | "create2"
+        | "call" | "callcode" | "delegatecall" | "selfdestruct" => SM::NonPayable,
+        "address" | "selfbalance" | "balance" | "origin" | "caller" | "chainid" | "basefee"
+        | "blobbasefee" | "gas" | "gasprice" | "extcodesize" | "extcodecopy" | "extcodehash"
+        | "blockhash" | "blobhash" | "coinbase" | "timestamp" | "number" | "difficulty"
+        | "prevrandao" | "gaslimit" | "staticcall" | "sload" | "tload" => SM::View,
+        "callvalue" => SM::Payable,
+        _ => return None,
+    })
+}
@@ -0,0 +1,166 @@
+use crate::{
+    hir,
+    ty::{Gcx, Ty},
+};
+use solar_ast::{ContractKind, StateMutability, Visibility};
+use solar_data_structures::map::FxHashSet;
+use solar_interface::{error_code, Span};
+
+/// Checks the functions and the public state variables of the given contract that override
+/// functions of its bases, and that non-abstract contracts implement all of their functions.
+///
+/// Public state variables override functions through their getters. They can only override
+/// virtual external functions with the same return types, and must be marked with `override` to
+/// do so.
+///
+/// Reference: <https://docs.soliditylang.org/en/latest/contracts.html#function-overriding>
+pub(super) fn check_contract(gcx: Gcx<'_>, id: hir::ContractId) {
+    let c = gcx.hir.contract(id);
+    for f in c.functions() {
+        if !gcx.hir.function(f).kind.is_function() {
+            continue;
+        }
+        let Some(base) = overridden_functions(gcx, id, f).next() else { continue };
+        let error = if gcx.hir.function(f).is_getter() {
+            getter_override_error(gcx, f, base)
+        } else {
+            function_override_error(gcx, f, base)
+        };
+        let Some(msg) = error else { continue };
+        gcx.dcx()
+            .err(msg)
+            .span(name_span(gcx, f))
+            .span_note(name_span(gcx, base), "overridden function is here")
+            .emit();
+    }
+
+    if c.kind == ContractKind::Contract {
+        check_implementations(gcx, id);
+    }
+}
+
+/// Checks that the given contract implements all the functions and modifiers that it declares or
+/// inherits.
+fn check_implementations(gcx: Gcx<'_>, id: hir::ContractId) {
+    let c = gcx.hir.contract(id);
+    let mut seen = FxHashSet::default();
+    let mut missing = Vec::new();
+    for &base in c.linearized_bases {
+        for f in gcx.hir.contract(base).all_functions() {
+            let func = gcx.hir.function(f);
+            if func.kind.is_constructor() {
+                continue;
+            }
+            // Only the most derived function with a given signature can implement it.
+            let key = (func.kind, func.name.map(|name| name.name), external_params(gcx, f));
+            if seen.insert(key) && func.body.is_none() {
+                missing.push(f);
+            }
+        }
+    }
+    if missing.is_empty() {
+        return;
+    }
+    let msg = format!("contract `{}` should be marked as abstract", c.name);
+    let mut err = gcx.dcx().err(msg).code(error_code!(3656)).span(c.name.span);
+    for f in missing {
+        err = err.span_note(name_span(gcx, f), "missing implementation");
+    }
+    err.emit();
+}
+
+/// Returns the error of the function `id` overriding the function `base_id`, if any.
+fn function_override_error(
+    gcx: Gcx<'_>,
+    id: hir::FunctionId,
+    base_id: hir::FunctionId,
+) -> Option<String> {
+    let func = gcx.hir.function(id);
+    let base = gcx.hir.function(base_id);
+    // Overriding a state variable is reported during name resolution.
+    if base.is_getter() {
+        return None;
+    }
+    let (from, to) = (base.state_mutability, func.state_mutability);
+    let mutability_allowed = from == to
+        || matches!(
+            (from, to),
+            (StateMutability::NonPayable, StateMutability::View | StateMutability::Pure)
+                | (StateMutability::View, StateMutability::Pure)
+        );
+    if !mutability_allowed {
+        Some(format!("overriding function changes state mutability from `{from}` to `{to}`"))
+    } else if external_returns(gcx, id) != external_returns(gcx, base_id) {
+        Some("overriding function return types differ".into())
+    } else {
+        None
+    }
+}
+
+/// Returns the error of the getter `id` overriding the function `base_id`, if any.
+fn getter_override_error(
+    gcx: Gcx<'_>,
+    id: hir::FunctionId,
+    base_id: hir::FunctionId,
+) -> Option<String> {
+    let base = gcx.hir.function(base_id);
+    // Redeclarations of state variables are reported during name resolution.
+    if base.is_getter() {
+        return None;
+    }
+    let var = gcx.hir.variable(gcx.hir.function(id).gettee.unwrap());
+    let msg = if base.visibility != Visibility::External {
+        "public state variables can only override functions with external visibility".into()
+    } else if !base.virtual_ {
+        "trying to override non-virtual function".into()
+    } else if !var.override_ {
+        "overriding public state variable is missing `override` specifier".into()
+    } else if !matches!(base.state_mutability, StateMutability::View | StateMutability::NonPayable)
+    {
+        format!(
+            "overriding public state variable changes state mutability from `{}` to `view`",
+            base.state_mutability
+        )
+    } else if external_returns(gcx, id) != external_returns(gcx, base_id) {
+        "overriding public state variable return types differ".into()
+    } else {
+        return None;
+    };
+    Some(msg)
+}
+
+/// Returns the functions of the bases of `contract` that the given function overrides, meaning
+/// the ones with the same name and external parameter types, from the most derived base.
+fn overridden_functions<'gcx>(
+    gcx: Gcx<'gcx>,
+    contract: hir::ContractId,
+    id: hir::FunctionId,
+) -> impl Iterator<Item = hir::FunctionId> + 'gcx {
+    let func = gcx.hir.function(id);
+    let name = func.name.map(|name| name.name);
+    let params = external_params(gcx, id);
+    gcx.hir.contract(contract).linearized_bases[1..]
+        .iter()
+        .flat_map(move |&base| gcx.hir.contract(base).functions())
+        .filter(move |&f| {
+            let base_func = gcx.hir.function(f);
+            base_func.kind.is_function()
+                && base_func.visibility != Visibility::Private
+                && name.is_some()
+                && base_func.name.map(|name| name.name) == name
+                && external_params(gcx, f) == params
+        })
+}
+
+fn external_params<'gcx>(gcx: Gcx<'gcx>, id: hir::FunctionId) -> Option<&'gcx [Ty<'gcx>]> {
+    gcx.type_of_item(id.into()).as_externally_callable_function(gcx).parameters()
+}
+
+fn external_returns<'gcx>(gcx: Gcx<'gcx>, id: hir::FunctionId) -> Option<&'gcx [Ty<'gcx>]> {
+    gcx.type_of_item(id.into()).as_externally_callable_function(gcx).returns()
+}
+
+fn name_span(gcx: Gcx<'_>, id: hir::FunctionId) -> Span {
+    let func = gcx.hir.function(id);
+    func.name.map_or(func.span, |name| name.span)
+}
@@ -0,0 +1,179 @@
+use crate::{
+    hir::{self, CallArgs, Res},
+    ty::Gcx,
+};
+use solar_data_structures::map::FxHashSet;
+use solar_interface::error_code;
+
+/// Warns about the named parameters and local variables of the given function that are never
+/// used.
+///
+/// Reference: <https://github.com/ethereum/solidity/blob/develop/libsolidity/analysis/StaticAnalyzer.cpp>
+pub(super) fn check_function(gcx: Gcx<'_>, id: hir::FunctionId) {
+    let func = gcx.hir.function(id);
+    if func.kind.is_modifier() {
+        return;
+    }
+    let Some(body) = func.body else { return };
+    let mut checker = UnusedChecker { gcx, func, declared: Vec::new(), used: FxHashSet::default() };
+    checker.declared.extend(func.parameters.iter().chain(func.returns).copied());
+    for modifier in func.modifiers {
+        checker.visit_args(&modifier.args);
+    }
+    checker.visit_block(body);
+
+    for &var_id in &checker.declared {
+        if checker.used.contains(&var_id) {
+            continue;
+        }
+        let var = gcx.hir.variable(var_id);
+        if var.name.is_none() {
+            continue;
+        }
+        if var.is_callable_or_catch_parameter() {
+            let what = if var.is_try_catch_parameter() { "try/catch" } else { "function" };
+            gcx.dcx()
+                .warn(format!("unused {what} parameter"))
+                .code(error_code!(5667))
+                .span(var.span)
+                .help("remove or comment out the variable name to silence this warning")
+                .emit();
+        } else {
+            gcx.dcx().warn("unused local variable").code(error_code!(2072)).span(var.span).emit();
+        }
+    }
+}
+
+/// Collects the variables declared in a function body, and the ones that are used.
+struct UnusedChecker<'gcx> {
+    gcx: Gcx<'gcx>,
+    func: &'gcx hir::Function<'gcx>,
+    /// The declared variables, in source order.
+    declared: Vec<hir::VariableId>,
+    used: FxHashSet<hir::VariableId>,
+}
+
+impl<'gcx> UnusedChecker<'gcx> {
+    fn visit_block(&mut self, block: hir::Block<'gcx>) {
+        for stmt in block {
+            self.visit_stmt(stmt);
+        }
+    }
+
+    fn visit_stmt(&mut self, stmt: &'gcx hir::Stmt<'gcx>) {
+        match stmt.kind {
+            hir::StmtKind::Assembly(assembly) => self.used.extend(assembly.variables),
+            hir::StmtKind::DeclSingle(id) => {
+                if let Some(init) = self.gcx.hir.variable(id).initializer {
+                    self.visit_expr(init);
+                }
+                self.declared.push(id);
+            }
+            hir::StmtKind::DeclMulti(vars, expr) => {
+                self.visit_expr(expr);
+                self.declared.extend(vars.iter().flatten());
+            }
+            hir::StmtKind::Block(block)
+            | hir::StmtKind::UncheckedBlock(block)
+            | hir::StmtKind::Loop(block, _) => self.visit_block(block),
+            hir::StmtKind::Emit(_, ref args) | hir::StmtKind::Revert(_, ref args) => {
+                self.visit_args(args)
+            }
+            hir::StmtKind::Return(expr) => {
+                if let Some(expr) = expr {
+                    // Returning a value counts as a use of the return parameters.
+                    self.used.extend(self.func.returns);
+                    self.visit_expr(expr);
+                }
+            }
+            hir::StmtKind::If(cond, then, else_) => {
+                self.visit_expr(cond);
+                self.visit_stmt(then);
+                if let Some(else_) = else_ {
+                    self.visit_stmt(else_);
+                }
+            }
+            hir::StmtKind::Try(try_) => {
+                self.visit_expr(&try_.expr);
+                self.declared.extend(try_.returns);
+                self.visit_block(try_.block);
+                for catch in try_.catch {
+                    self.declared.extend(catch.args);
+                    self.visit_block(catch.block);
+                }
+            }
+            hir::StmtKind::Expr(expr) => self.visit_expr(expr),
+            hir::StmtKind::Break
+            | hir::StmtKind::Continue
+            | hir::StmtKind::Placeholder
+            | hir::StmtKind::Err(_) => {}
+        }
+    }
+
+    fn visit_args(&mut self, args: &'gcx CallArgs<'gcx>) {
+        for expr in args.exprs() {
+            self.visit_expr(expr);
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &'gcx hir::Expr<'gcx>) {
+        match expr.kind {
+            hir::ExprKind::Array(exprs) => {
+                for expr in exprs {
+                    self.visit_expr(expr);
+                }
+            }
+            hir::ExprKind::Assign(lhs, _, rhs) | hir::ExprKind::Binary(lhs, _, rhs) => {
+                self.visit_expr(lhs);
+                self.visit_expr(rhs);
+            }
+            hir::ExprKind::Call(callee, ref args) => {
+                self.visit_expr(callee);
+                self.visit_args(args);
+            }
+            hir::ExprKind::CallOptions(callee, options) => {
+                self.visit_expr(callee);
+                for option in options {
+                    self.visit_expr(&option.value);
+                }
+            }
+            hir::ExprKind::Delete(expr)
+            | hir::ExprKind::Member(expr, _)
+            | hir::ExprKind::Payable(expr)
+            | hir::ExprKind::Unary(_, expr) => self.visit_expr(expr),
+            hir::ExprKind::Ident(res) => {
+                self.used.extend(res.iter().filter_map(|res| match res {
+                    Res::Item(hir::ItemId::Variable(id)) => Some(*id),
+                    _ => None,
+                }));
+            }
+            hir::ExprKind::Index(expr, index) => {
+                self.visit_expr(expr);
+                if let Some(index) = index {
+                    self.visit_expr(index);
+                }
+            }
+            hir::ExprKind::Slice(expr, start, end) => {
+                self.visit_expr(expr);
+                for expr in [start, end].into_iter().flatten() {
+                    self.visit_expr(expr);
+                }
+            }
+            hir::ExprKind::Ternary(cond, then, else_) => {
+                self.visit_expr(cond);
+                self.visit_expr(then);
+                self.visit_expr(else_);
+            }
+            hir::ExprKind::Tuple(exprs) => {
+                for expr in exprs.iter().flatten() {
+                    self.visit_expr(expr);
+                }
+            }
+            hir::ExprKind::Lit(_)
+            | hir::ExprKind::New(_)
+            | hir::ExprKind::TypeCall(_)
+            | hir::ExprKind::Type(_)
+            | hir::ExprKind::Err(_) => {}
+        }
+    }
+}
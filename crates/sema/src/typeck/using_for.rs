@@ -0,0 +1,248 @@
+use super::checker::is_implicitly_convertible;
+use crate::{
+    builtins::Member,
+    hir,
+    ty::{Gcx, Ty, TyFnPtr, TyKind},
+};
+use either::Either;
+use solar_ast::{StateMutability, UserDefinableOperator, Visibility};
+use solar_data_structures::{map::FxHashSet, smallvec::SmallVec};
+
+/// Checks the `using for` directives of the given source or contract.
+///
+/// Reference: <https://github.com/ethereum/solidity/blob/develop/libsolidity/analysis/TypeChecker.cpp>
+pub(super) fn check_using_directives(
+    gcx: Gcx<'_>,
+    source: hir::SourceId,
+    contract: Option<hir::ContractId>,
+) {
+    for directive in directives(gcx, source, contract) {
+        check_using_directive(gcx, source, directive);
+    }
+}
+
+fn check_using_directive(gcx: Gcx<'_>, source: hir::SourceId, directive: &hir::UsingDirective<'_>) {
+    let Some(hir_ty) = &directive.ty else { return };
+    let ty = gcx.type_of_hir_ty(hir_ty);
+    if ty.has_error().is_err() {
+        return;
+    }
+
+    if directive.global {
+        let definition = match ty.kind {
+            TyKind::Struct(id) => Some(hir::ItemId::from(id)),
+            TyKind::Enum(id) => Some(id.into()),
+            TyKind::Udvt(_, id) => Some(id.into()),
+            TyKind::Contract(id) => Some(id.into()),
+            _ => None,
+        };
+        match definition {
+            None => {
+                let msg = "can only use `global` with user-defined types";
+                gcx.dcx().err(msg).span(hir_ty.span).emit();
+            }
+            Some(id) => {
+                let item = gcx.hir.item(id);
+                if item.source() != source || item.contract().is_some() {
+                    let msg = "can only use `global` with types defined in the same source unit \
+                               at file level";
+                    gcx.dcx().err(msg).span(hir_ty.span).emit();
+                }
+            }
+        }
+    }
+
+    // Incompatible functions of libraries are not attached, but listed functions must be
+    // compatible. The data location of reference types is not known here, so any location is
+    // accepted.
+    let hir::UsingList::Functions(functions) = directive.list else { return };
+    for function in functions {
+        let func = gcx.hir.function(function.id);
+        let name = gcx.item_canonical_name(function.id);
+        let Some(&first) = func.parameters.first() else {
+            let msg = format!(
+                "the function `{name}` does not have any parameters, and therefore cannot be \
+                 attached to the type `{}`",
+                ty.display(gcx)
+            );
+            gcx.dcx().err(msg).span(function.span).emit();
+            continue;
+        };
+        let first_ty = gcx.type_of_item(first.into());
+        if !is_implicitly_convertible(gcx, ty, first_ty, false) {
+            let msg = format!(
+                "the function `{name}` cannot be attached to the type `{}` because the type \
+                 cannot be implicitly converted to the first argument of the function (`{}`)",
+                ty.display(gcx),
+                first_ty.display(gcx)
+            );
+            gcx.dcx().err(msg).span(function.span).emit();
+            continue;
+        }
+        if let Some(op) = function.operator {
+            check_operator(gcx, directive, ty, function, op);
+        }
+    }
+}
+
+/// Checks a function bound to a user-defined operator: `using {f as +} for T global`.
+fn check_operator<'gcx>(
+    gcx: Gcx<'gcx>,
+    directive: &hir::UsingDirective<'_>,
+    ty: Ty<'gcx>,
+    function: &hir::UsingFunction,
+    op: UserDefinableOperator,
+) {
+    let func = gcx.hir.function(function.id);
+    let name = gcx.item_canonical_name(function.id);
+    let span = function.span;
+    if !directive.global {
+        let msg = "operators can only be defined in a global `using for` directive";
+        gcx.dcx().err(msg).span(span).emit();
+    }
+    if !func.is_free() || func.state_mutability != StateMutability::Pure {
+        let msg = "only pure file-level functions can be used to define operators";
+        gcx.dcx().err(msg).span(span).emit();
+    }
+    if !matches!(ty.kind, TyKind::Udvt(..)) {
+        let msg = "operators can only be implemented for user-defined value types";
+        gcx.dcx().err(msg).span(span).emit();
+        return;
+    }
+
+    let TyKind::FnPtr(f) = gcx.type_of_item(function.id.into()).kind else { return };
+    let (arity, expected) = match op {
+        UserDefinableOperator::BitNot => (1..=1, "exactly one parameter"),
+        UserDefinableOperator::Sub => (1..=2, "one or two parameters"),
+        _ => (2..=2, "two parameters"),
+    };
+    if !arity.contains(&f.parameters.len()) || f.parameters.iter().any(|&param| param != ty) {
+        let msg = format!(
+            "wrong parameters in operator definition: the function `{name}` needs to have \
+             {expected} of type `{}` to be used for the operator `{}`",
+            ty.display(gcx),
+            op.to_str(),
+        );
+        gcx.dcx().err(msg).span(span).emit();
+    }
+    let ret = if op.is_comparison() { gcx.types.bool } else { ty };
+    if f.returns != [ret] {
+        let msg = format!(
+            "wrong return parameters in operator definition: the function `{name}` needs to \
+             return exactly one value of type `{}` to be used for the operator `{}`",
+            ret.display(gcx),
+            op.to_str(),
+        );
+        gcx.dcx().err(msg).span(span).emit();
+    }
+}
+
+/// Returns the functions that implement the operator `op` with `arity` operands for the
+/// user-defined value type `ty`.
+///
+/// Operators can only be bound by `global` directives in the source unit that defines the type,
+/// so these are the same in every scope.
+pub(crate) fn user_defined_operators<'gcx>(
+    gcx: Gcx<'gcx>,
+    ty: Ty<'gcx>,
+    op: UserDefinableOperator,
+    arity: usize,
+) -> SmallVec<[hir::FunctionId; 1]> {
+    let TyKind::Udvt(_, id) = ty.kind else { return SmallVec::new() };
+    let mut functions = SmallVec::<[_; 1]>::new();
+    for directive in gcx.hir.source(gcx.hir.udvt(id).source).using_directives {
+        let bound_ty = directive.ty.as_ref().map(|hir_ty| gcx.type_of_hir_ty(hir_ty));
+        if !directive.global || bound_ty != Some(ty) {
+            continue;
+        }
+        let hir::UsingList::Functions(list) = directive.list else { continue };
+        for function in list {
+            if function.operator == Some(op)
+                && gcx.hir.function(function.id).parameters.len() == arity
+                && !functions.contains(&function.id)
+            {
+                functions.push(function.id);
+            }
+        }
+    }
+    functions
+}
+
+/// Returns the functions attached to `ty` by the `using for` directives that are in scope in the
+/// given source and contract, as members of `ty`.
+///
+/// These are the directives of the contract, the file-level directives of the source, and the
+/// `global` directives of any source. A function is attached if the type of its first parameter
+/// can be implicitly converted from `ty`.
+pub(crate) fn attached_members<'gcx>(
+    gcx: Gcx<'gcx>,
+    ty: Ty<'gcx>,
+    source: hir::SourceId,
+    contract: Option<hir::ContractId>,
+) -> Vec<Member<'gcx>> {
+    let in_contract = contract.map(|id| directives(gcx, source, Some(id)));
+    let in_scope = in_contract.into_iter().flatten().chain(directives(gcx, source, None)).chain(
+        gcx.hir
+            .sources_enumerated()
+            .filter(|&(id, _)| id != source)
+            .flat_map(|(_, s)| s.using_directives.iter().filter(|d| d.global)),
+    );
+
+    let mut seen = FxHashSet::default();
+    let mut members = Vec::new();
+    for directive in in_scope {
+        if let Some(hir_ty) = &directive.ty {
+            if gcx.type_of_hir_ty(hir_ty).peel_refs() != ty.peel_refs() {
+                continue;
+            }
+        }
+        let functions = match directive.list {
+            hir::UsingList::Library(id) => {
+                let attached = gcx.hir.contract(id).functions().filter(move |&f| {
+                    let f = gcx.hir.function(f);
+                    f.kind.is_ordinary()
+                        && (f.visibility != Visibility::Private || contract == Some(id))
+                });
+                Either::Left(attached)
+            }
+            hir::UsingList::Functions(functions) => Either::Right(functions.iter().map(|f| f.id)),
+        };
+        for id in functions {
+            let Some(bound) = bound_function_ty(gcx, ty, id) else { continue };
+            if seen.insert(id) {
+                members.push(Member::with_res(gcx.item_name(id).name, bound, id));
+            }
+        }
+    }
+    members
+}
+
+/// Returns the `using for` directives of the given contract, or the file-level directives of the
+/// given source if `contract` is `None`.
+fn directives<'gcx>(
+    gcx: Gcx<'gcx>,
+    source: hir::SourceId,
+    contract: Option<hir::ContractId>,
+) -> std::slice::Iter<'gcx, hir::UsingDirective<'gcx>> {
+    match contract {
+        Some(id) => gcx.hir.contract(id).using_directives.iter(),
+        None => gcx.hir.source(source).using_directives.iter(),
+    }
+}
+
+/// Returns the type of the function `id` attached to `ty`, without its first parameter, or `None`
+/// if `ty` can't be converted to the first parameter.
+fn bound_function_ty<'gcx>(gcx: Gcx<'gcx>, ty: Ty<'gcx>, id: hir::FunctionId) -> Option<Ty<'gcx>> {
+    let f = gcx.type_of_item(id.into());
+    let TyKind::FnPtr(f) = f.kind else { return None };
+    let (&first, parameters) = f.parameters.split_first()?;
+    if !is_implicitly_convertible(gcx, ty, first, true) {
+        return None;
+    }
+    Some(gcx.mk_ty_fn_ptr(TyFnPtr {
+        parameters,
+        returns: f.returns,
+        state_mutability: f.state_mutability,
+        visibility: f.visibility,
+    }))
+}
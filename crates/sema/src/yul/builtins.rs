@@ -0,0 +1,129 @@
+//! Yul EVM dialect builtins.
+//!
+//! Reference: <https://docs.soliditylang.org/en/latest/yul.html#evm-dialect>
+
+use solar_interface::config::EvmVersion;
+
+/// A Yul builtin function.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Builtin {
+    /// The number of arguments.
+    pub(super) params: usize,
+    /// The number of return values.
+    pub(super) returns: usize,
+    /// The kind of literal that each argument must be, if any.
+    pub(super) literal_args: &'static [Option<LiteralArg>],
+    /// The first EVM version in which the builtin is available.
+    pub(super) since: Option<EvmVersion>,
+    /// The first EVM version in which the builtin is no longer available.
+    pub(super) until: Option<EvmVersion>,
+}
+
+/// The kind of literal that a builtin argument must be.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum LiteralArg {
+    /// A string literal.
+    Str,
+    /// A string literal naming a data object or sub-object.
+    DataName,
+    /// A number literal.
+    Number,
+}
+
+impl Builtin {
+    const fn new(params: usize, returns: usize) -> Self {
+        Self { params, returns, literal_args: &[], since: None, until: None }
+    }
+
+    const fn since(mut self, version: EvmVersion) -> Self {
+        self.since = Some(version);
+        self
+    }
+
+    const fn until(mut self, version: EvmVersion) -> Self {
+        self.until = Some(version);
+        self
+    }
+
+    const fn literals(mut self, literal_args: &'static [Option<LiteralArg>]) -> Self {
+        self.literal_args = literal_args;
+        self
+    }
+
+    /// Returns `true` if the builtin is available in the given EVM version.
+    pub(super) fn is_available(&self, evm_version: EvmVersion) -> bool {
+        self.since.map_or(true, |since| evm_version >= since)
+            && self.until.map_or(true, |until| evm_version < until)
+    }
+
+    /// Returns the kind of literal that the argument at `index` must be, if any.
+    pub(super) fn literal_arg(&self, index: usize) -> Option<LiteralArg> {
+        self.literal_args.get(index).copied().flatten()
+    }
+}
+
+/// Returns the builtin with the given name, regardless of the EVM version.
+pub(super) fn builtin(name: &str) -> Option<Builtin> {
+    use EvmVersion::*;
+    use LiteralArg::*;
+
+    let b = Builtin::new;
+    Some(match name {
+        "stop" | "invalid" => b(0, 0),
+        "add" | "sub" | "mul" | "div" | "sdiv" | "mod" | "smod" | "exp" | "signextend" | "lt"
+        | "gt" | "slt" | "sgt" | "eq" | "and" | "or" | "xor" | "byte" | "keccak256" => b(2, 1),
+        "shl" | "shr" | "sar" => b(2, 1).since(Constantinople),
+        "addmod" | "mulmod" => b(3, 1),
+        "iszero" | "not" | "balance" | "calldataload" | "extcodesize" | "blockhash" | "mload"
+        | "sload" => b(1, 1),
+        "extcodehash" => b(1, 1).since(Constantinople),
+        "blobhash" | "tload" => b(1, 1).since(Cancun),
+        "address" | "origin" | "caller" | "callvalue" | "calldatasize" | "codesize"
+        | "gasprice" | "coinbase" | "timestamp" | "number" | "gaslimit" | "msize" | "gas" => {
+            b(0, 1)
+        }
+        "returndatasize" => b(0, 1).since(Byzantium),
+        "chainid" | "selfbalance" => b(0, 1).since(Istanbul),
+        "basefee" => b(0, 1).since(London),
+        "difficulty" => b(0, 1).until(Paris),
+        "prevrandao" => b(0, 1).since(Paris),
+        "blobbasefee" => b(0, 1).since(Cancun),
+        "calldatacopy" | "codecopy" => b(3, 0),
+        "returndatacopy" => b(3, 0).since(Byzantium),
+        "mcopy" => b(3, 0).since(Cancun),
+        "extcodecopy" => b(4, 0),
+        "pop" | "selfdestruct" => b(1, 0),
+        "mstore" | "mstore8" | "sstore" | "return" | "revert" | "log0" => b(2, 0),
+        "tstore" => b(2, 0).since(Cancun),
+        "log1" => b(3, 0),
+        "log2" => b(4, 0),
+        "log3" => b(5, 0),
+        "log4" => b(6, 0),
+        "create" => b(3, 1),
+        "create2" => b(4, 1).since(Constantinople),
+        "call" | "callcode" => b(7, 1),
+        "delegatecall" => b(6, 1),
+        "staticcall" => b(6, 1).since(Byzantium),
+
+        // Object access builtins.
+        "datasize" | "dataoffset" => b(1, 1).literals(&[Some(DataName)]),
+        "datacopy" => b(3, 0),
+        "setimmutable" => b(3, 0).literals(&[None, Some(Str), None]),
+        "loadimmutable" | "linkersymbol" => b(1, 1).literals(&[Some(Str)]),
+        "memoryguard" => b(1, 1).literals(&[Some(Number)]),
+
+        _ => return verbatim(name),
+    })
+}
+
+/// Parses a `verbatim_<n>i_<m>o` builtin, which takes the bytecode to insert followed by `n`
+/// arguments, and returns `m` values.
+fn verbatim(name: &str) -> Option<Builtin> {
+    let (inputs, outputs) = name.strip_prefix("verbatim_")?.split_once("i_")?;
+    let outputs = outputs.strip_suffix('o')?;
+    let parse = |s: &str| {
+        let valid = s.bytes().all(|b| b.is_ascii_digit()) && (s == "0" || !s.starts_with('0'));
+        s.parse::<usize>().ok().filter(|&n| valid && n < 100)
+    };
+    Some(Builtin::new(parse(inputs)? + 1, parse(outputs)?).literals(&[Some(LiteralArg::Str)]))
+}
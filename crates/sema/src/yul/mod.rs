@@ -0,0 +1,646 @@
+//! Yul object validation and name resolution.
+
+use crate::{
+    hir,
+    ty::{Gcx, TyKind},
+};
+use builtins::{Builtin, LiteralArg};
+use solar_ast::{yul, DataLocation, ElementaryType, LitKind, Visibility};
+use solar_data_structures::map::{FxHashMap, FxHashSet};
+use solar_interface::{
+    config::EvmVersion, diagnostics::DiagCtxt, kw, sym, Ident, Session, Span, Symbol,
+};
+
+mod builtins;
+
+/// Checks a parsed Yul object and all of its sub-objects.
+#[instrument(name = "yul_check", level = "debug", skip_all)]
+pub(crate) fn check(sess: &Session, object: &yul::Object<'_>) {
+    check_object(sess, object);
+}
+
+/// Checks an inline assembly block.
+///
+/// Checks that the builtins called in the block are available in the configured EVM version, and
+/// that the Solidity declarations referenced in the block can be accessed from inline assembly.
+///
+/// Reference: <https://github.com/ethereum/solidity/blob/develop/libsolidity/analysis/TypeChecker.cpp>
+pub(crate) fn check_inline_assembly(gcx: Gcx<'_>, assembly: &hir::StmtAssembly<'_>) {
+    let sess = gcx.sess;
+    for &(name, span) in assembly.calls {
+        let Some(builtin) = builtins::builtin(name.as_str()) else { continue };
+        if !builtin.is_available(sess.evm_version) {
+            unavailable_builtin(&sess.dcx, sess.evm_version, Ident::new(name, span), builtin);
+        }
+    }
+    for reference in assembly.references {
+        if let Err(msg) = check_reference(gcx, reference) {
+            sess.dcx.err(msg).span(reference.span).emit();
+        }
+    }
+}
+
+/// Checks that a Solidity declaration can be accessed from inline assembly in the given way.
+fn check_reference(gcx: Gcx<'_>, reference: &hir::AssemblyReference) -> Result<(), String> {
+    let &hir::AssemblyReference { span: _, item, suffix, is_assignment, in_function } = reference;
+    let hir::ItemId::Variable(id) = item else {
+        if suffix.is_some() {
+            return Err("suffixes can only be used with variables".into());
+        }
+        if is_assignment {
+            return Err("only local variables can be assigned to in inline assembly".into());
+        }
+        return match item {
+            hir::ItemId::Function(_) => {
+                Err("access to functions is not allowed in inline assembly".into())
+            }
+            hir::ItemId::Contract(id) if gcx.hir.contract(id).kind.is_library() => Ok(()),
+            hir::ItemId::Contract(_) => Err("expected a library".into()),
+            _ => Err(format!(
+                "{} cannot be accessed in inline assembly",
+                gcx.hir.item(item).description()
+            )),
+        };
+    };
+
+    let var = gcx.hir.variable(id);
+    if in_function && var.is_local_variable() {
+        return Err(
+            "cannot access local Solidity variables from inside an inline assembly function".into(),
+        );
+    }
+    if var.is_immutable() {
+        return Err("assembly access to immutable variables is not supported".into());
+    }
+    let ty = gcx.type_of_item(id.into());
+    if var.is_constant() {
+        if is_assignment {
+            return Err("constant variables cannot be assigned to".into());
+        }
+        if suffix.is_some() {
+            return Err("suffixes can only be used with non-constant variables".into());
+        }
+        if !ty.is_value_type() {
+            return Err("only direct number constants and references to such constants are \
+                        supported by inline assembly"
+                .into());
+        }
+        return Ok(());
+    }
+
+    let is_storage = var.is_state_variable() || ty.is_ref_at(DataLocation::Storage);
+    let is_calldata_array = match ty.kind {
+        TyKind::Ref(inner, DataLocation::Calldata) => matches!(
+            inner.kind,
+            TyKind::DynArray(_)
+                | TyKind::Elementary(ElementaryType::Bytes | ElementaryType::String)
+        ),
+        _ => false,
+    };
+    let is_external_fn = matches!(ty.kind, TyKind::FnPtr(f) if f.visibility >= Visibility::Public);
+    let Some(suffix) = suffix else {
+        return if var.is_state_variable() {
+            Err("only local variables are supported; to access storage variables, use the \
+                 `.slot` and `.offset` suffixes"
+                .into())
+        } else if is_storage {
+            Err("the `.slot` or `.offset` suffix is required to access storage reference variables"
+                .into())
+        } else if is_calldata_array {
+            Err("calldata arrays cannot be accessed directly; use `.offset` and `.length` to \
+                 access their calldata offset and length"
+                .into())
+        } else if is_external_fn {
+            Err("only types that use one stack slot are supported".into())
+        } else {
+            Ok(())
+        };
+    };
+
+    let suffix = suffix.name;
+    if is_storage {
+        if suffix != sym::slot && suffix != sym::offset {
+            return Err("storage variables only support `.slot` and `.offset`".into());
+        }
+        if is_assignment && var.is_state_variable() {
+            return Err("state variables cannot be assigned to; use `sstore()` instead".into());
+        }
+        if is_assignment && suffix != sym::slot {
+            return Err("only `.slot` can be assigned to".into());
+        }
+    } else if is_calldata_array {
+        if suffix != sym::offset && suffix != sym::length {
+            return Err("calldata variables only support `.offset` and `.length`".into());
+        }
+    } else if is_external_fn {
+        if suffix != sym::selector && suffix != kw::Address {
+            return Err(
+                "variables of type function pointer only support `.selector` and `.address`".into(),
+            );
+        }
+    } else {
+        return Err(format!("the suffix `.{suffix}` is not supported by this variable or type"));
+    }
+    Ok(())
+}
+
+fn check_object(sess: &Session, object: &yul::Object<'_>) {
+    let dcx = &sess.dcx;
+    let mut names = FxHashMap::<&str, Span>::default();
+    let sub_names = object
+        .children
+        .iter()
+        .map(|child| &child.name)
+        .chain(object.data.iter().map(|data| &data.name));
+    for name in sub_names {
+        let value = name.value.as_str();
+        if value.is_empty() {
+            dcx.err("object names cannot be empty").span(name.span).emit();
+        } else if value == object.name.value.as_str() {
+            let msg = "object names cannot be the same as the name of the containing object";
+            dcx.err(msg).span(name.span).emit();
+        } else if let Some(&prev) = names.get(value) {
+            let msg = format!("object name {value:?} already exists inside the containing object");
+            dcx.err(msg)
+                .span(name.span)
+                .span_label(name.span, "redeclared here")
+                .span_note(prev, "first declared here")
+                .emit();
+        } else {
+            names.insert(value, name.span);
+        }
+    }
+
+    let mut data_names = FxHashSet::default();
+    data_names.insert(object.name.value.as_str().to_string());
+    collect_data_names(object, "", &mut data_names);
+    Checker::new(sess, &data_names).check_block(&object.code.code);
+
+    for child in object.children.iter() {
+        check_object(sess, child);
+    }
+}
+
+/// Collects the names of the sub-objects and data segments accessible from `object`, qualified
+/// with `prefix`.
+fn collect_data_names(object: &yul::Object<'_>, prefix: &str, names: &mut FxHashSet<String>) {
+    for data in object.data.iter() {
+        names.insert(format!("{prefix}{}", data.name.value));
+    }
+    for child in object.children.iter() {
+        let name = format!("{prefix}{}", child.name.value);
+        collect_data_names(child, &format!("{name}."), names);
+        names.insert(name);
+    }
+}
+
+/// A declaration in a Yul scope.
+#[derive(Clone, Copy)]
+enum Decl {
+    Variable(Span),
+    Function { span: Span, params: usize, returns: usize },
+}
+
+impl Decl {
+    fn span(self) -> Span {
+        match self {
+            Self::Variable(span) | Self::Function { span, .. } => span,
+        }
+    }
+}
+
+/// A Yul scope, introduced by blocks and function definitions.
+#[derive(Default)]
+struct Scope {
+    /// Whether this is the scope of the parameters of a function, whose body can't access the
+    /// variables of the enclosing scopes.
+    is_function: bool,
+    decls: FxHashMap<Symbol, Decl>,
+}
+
+/// Where `break` and `continue` statements are allowed.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum LoopState {
+    /// Not inside of a loop.
+    None,
+    /// Inside of the init, condition, or post-iteration parts of a loop.
+    Header,
+    /// Inside of the body of a loop.
+    Body,
+}
+
+/// Resolves the identifiers of a Yul code block and checks its statements.
+struct Checker<'sess, 'a> {
+    dcx: &'sess DiagCtxt,
+    evm_version: EvmVersion,
+    /// The names accepted by `datasize` and `dataoffset`.
+    data_names: &'a FxHashSet<String>,
+    scopes: Vec<Scope>,
+    in_function: bool,
+    loop_state: LoopState,
+    in_for_init: bool,
+}
+
+impl<'sess, 'a> Checker<'sess, 'a> {
+    fn new(sess: &'sess Session, data_names: &'a FxHashSet<String>) -> Self {
+        Self {
+            dcx: &sess.dcx,
+            evm_version: sess.evm_version,
+            data_names,
+            scopes: Vec::new(),
+            in_function: false,
+            loop_state: LoopState::None,
+            in_for_init: false,
+        }
+    }
+
+    fn check_block(&mut self, block: &yul::Block<'_>) {
+        self.scopes.push(Scope::default());
+        self.check_stmts(block);
+        self.scopes.pop();
+    }
+
+    /// Checks the statements of a block in the current scope.
+    fn check_stmts(&mut self, stmts: &[yul::Stmt<'_>]) {
+        // Functions are visible in the whole block they are defined in.
+        for stmt in stmts {
+            if let yul::StmtKind::FunctionDef(func) = &stmt.kind {
+                if self.in_for_init {
+                    let msg = "functions cannot be defined inside of for-loop init blocks";
+                    self.dcx.err(msg).span(stmt.span).emit();
+                }
+                let params = func.parameters.len();
+                let returns = func.returns.len();
+                self.declare(func.name, Decl::Function { span: func.name.span, params, returns });
+            }
+        }
+        for stmt in stmts {
+            self.check_stmt(stmt);
+        }
+    }
+
+    fn check_stmt(&mut self, stmt: &yul::Stmt<'_>) {
+        match &stmt.kind {
+            yul::StmtKind::Block(block) => {
+                let in_for_init = std::mem::replace(&mut self.in_for_init, false);
+                self.check_block(block);
+                self.in_for_init = in_for_init;
+            }
+            yul::StmtKind::AssignSingle(path, expr) => {
+                self.check_single_value(expr);
+                self.resolve_variable(path);
+            }
+            yul::StmtKind::AssignMulti(paths, call) => {
+                let values = self.check_call(stmt.span, call);
+                for (i, path) in paths.iter().enumerate() {
+                    self.resolve_variable(path);
+                    if paths[..i].iter().any(|prev| prev.segments() == path.segments()) {
+                        let msg = format!(
+                            "variable `{path}` occurs multiple times on the left-hand side of the \
+                             assignment"
+                        );
+                        self.dcx.err(msg).span(path.span()).emit();
+                    }
+                }
+                if let Some(values) = values {
+                    if values != paths.len() {
+                        let msg = format!(
+                            "variable count mismatch for assignment: {} variables and {values} \
+                             values",
+                            paths.len(),
+                        );
+                        self.dcx.err(msg).span(stmt.span).emit();
+                    }
+                }
+            }
+            yul::StmtKind::Expr(call) => {
+                if let Some(values) = self.check_call(stmt.span, call) {
+                    if values != 0 {
+                        let msg = format!(
+                            "top-level expressions are not supposed to return values (this \
+                             expression returns {values} value{})",
+                            if values == 1 { "" } else { "s" },
+                        );
+                        let help = "use `pop()` or assign them";
+                        self.dcx.err(msg).span(stmt.span).help(help).emit();
+                    }
+                }
+            }
+            yul::StmtKind::If(cond, body) => {
+                self.check_single_value(cond);
+                self.check_block(body);
+            }
+            yul::StmtKind::For { init, cond, step, body } => {
+                // The init block's scope extends to the rest of the loop.
+                self.scopes.push(Scope::default());
+                let loop_state = std::mem::replace(&mut self.loop_state, LoopState::Header);
+                let in_for_init = std::mem::replace(&mut self.in_for_init, true);
+                self.check_stmts(init);
+                self.in_for_init = false;
+                self.check_single_value(cond);
+                self.check_block(step);
+                self.loop_state = LoopState::Body;
+                self.check_block(body);
+                self.loop_state = loop_state;
+                self.in_for_init = in_for_init;
+                self.scopes.pop();
+            }
+            yul::StmtKind::Switch(switch) => self.check_switch(switch),
+            yul::StmtKind::Leave => {
+                if !self.in_function {
+                    self.dcx.err("`leave` outside of a function").span(stmt.span).emit();
+                }
+            }
+            yul::StmtKind::Break | yul::StmtKind::Continue => {
+                if self.loop_state != LoopState::Body {
+                    let kind = if matches!(stmt.kind, yul::StmtKind::Break) {
+                        "break"
+                    } else {
+                        "continue"
+                    };
+                    let msg = format!("`{kind}` outside of a for-loop body");
+                    self.dcx.err(msg).span(stmt.span).emit();
+                }
+            }
+            yul::StmtKind::FunctionDef(func) => self.check_function(func),
+            yul::StmtKind::VarDecl(names, expr) => {
+                if let Some(expr) = expr {
+                    if let Some(values) = self.check_expr(expr) {
+                        if values != names.len() {
+                            let vars =
+                                names.iter().map(|name| format!("`{name}`")).collect::<Vec<_>>();
+                            let msg = format!(
+                                "variable count mismatch for declaration of {}: {} variables and \
+                                 {values} values",
+                                vars.join(", "),
+                                names.len(),
+                            );
+                            self.dcx.err(msg).span(stmt.span).emit();
+                        }
+                    }
+                }
+                for &name in names.iter() {
+                    self.declare(name, Decl::Variable(name.span));
+                }
+            }
+        }
+    }
+
+    fn check_function(&mut self, func: &yul::Function<'_>) {
+        let in_function = std::mem::replace(&mut self.in_function, true);
+        let loop_state = std::mem::replace(&mut self.loop_state, LoopState::None);
+        let in_for_init = std::mem::replace(&mut self.in_for_init, false);
+        self.scopes.push(Scope { is_function: true, ..Default::default() });
+        for &name in func.parameters.iter().chain(func.returns.iter()) {
+            self.declare(name, Decl::Variable(name.span));
+        }
+        self.check_block(&func.body);
+        self.scopes.pop();
+        self.in_function = in_function;
+        self.loop_state = loop_state;
+        self.in_for_init = in_for_init;
+    }
+
+    fn check_switch(&mut self, switch: &yul::StmtSwitch<'_>) {
+        self.check_single_value(&switch.selector);
+        for (i, case) in switch.branches.iter().enumerate() {
+            let lit = &*case.constant;
+            self.check_lit(lit);
+            let prev =
+                switch.branches[..i].iter().find(|prev| lit_eq(&prev.constant.kind, &lit.kind));
+            if let Some(prev) = prev {
+                let msg = format!("duplicate case `{}`", lit.symbol);
+                self.dcx
+                    .err(msg)
+                    .span(lit.span)
+                    .span_note(prev.constant.span, "previous case here")
+                    .emit();
+            }
+            self.check_block(&case.body);
+        }
+        if let Some(default_case) = &switch.default_case {
+            self.check_block(default_case);
+        }
+    }
+
+    /// Checks that an expression evaluates to exactly one value.
+    fn check_single_value(&mut self, expr: &yul::Expr<'_>) {
+        if let Some(values) = self.check_expr(expr) {
+            if values != 1 {
+                let msg = format!(
+                    "expected expression to evaluate to one value, but got {values} values instead"
+                );
+                self.dcx.err(msg).span(expr.span).emit();
+            }
+        }
+    }
+
+    /// Checks an expression, returning the number of values that it evaluates to, or `None` if
+    /// it could not be resolved.
+    fn check_expr(&mut self, expr: &yul::Expr<'_>) -> Option<usize> {
+        match &expr.kind {
+            yul::ExprKind::Path(path) => {
+                self.resolve_variable(path);
+                Some(1)
+            }
+            yul::ExprKind::Call(call) => self.check_call(expr.span, call),
+            yul::ExprKind::Lit(lit) => {
+                self.check_lit(lit);
+                Some(1)
+            }
+        }
+    }
+
+    /// Checks a function call, returning the number of values that it returns, or `None` if the
+    /// function could not be resolved.
+    fn check_call(&mut self, span: Span, call: &yul::ExprCall<'_>) -> Option<usize> {
+        let name = call.name;
+        let builtin = builtins::builtin(name.as_str());
+        let signature = match builtin {
+            Some(builtin) if builtin.is_available(self.evm_version) => {
+                Some((builtin.params, builtin.returns))
+            }
+            _ => match self.lookup(name.name) {
+                Some((Decl::Function { params, returns, .. }, _)) => Some((params, returns)),
+                Some((Decl::Variable(_), _)) => {
+                    let msg = format!("`{name}` is a variable, not a function");
+                    self.dcx.err(msg).span(name.span).emit();
+                    None
+                }
+                None => {
+                    match builtin {
+                        Some(builtin) => self.unavailable_builtin(name, builtin),
+                        None => {
+                            let msg = format!("undeclared identifier `{name}`");
+                            self.dcx.err(msg).span(name.span).emit();
+                        }
+                    }
+                    None
+                }
+            },
+        };
+
+        let builtin = builtin.filter(|builtin| builtin.is_available(self.evm_version));
+        for (i, arg) in call.arguments.iter().enumerate() {
+            match builtin.and_then(|builtin| builtin.literal_arg(i)) {
+                Some(kind) => self.check_literal_arg(name, i, kind, arg),
+                None => self.check_single_value(arg),
+            }
+        }
+
+        let (params, returns) = signature?;
+        if call.arguments.len() != params {
+            let msg = format!(
+                "wrong argument count for `{name}` call: {} arguments given but expected {params}",
+                call.arguments.len(),
+            );
+            self.dcx.err(msg).span(span).emit();
+        }
+        Some(returns)
+    }
+
+    /// Checks an argument of a builtin that must be a literal of the given kind.
+    fn check_literal_arg(
+        &mut self,
+        name: Ident,
+        index: usize,
+        kind: LiteralArg,
+        arg: &yul::Expr<'_>,
+    ) {
+        let lit = match &arg.kind {
+            yul::ExprKind::Lit(lit) => &lit.kind,
+            _ => {
+                self.literal_arg_error(name, index, kind, arg.span);
+                self.check_expr(arg);
+                return;
+            }
+        };
+        match (kind, lit) {
+            (LiteralArg::Str, LitKind::Str(..)) => {}
+            (LiteralArg::DataName, LitKind::Str(_, value)) => {
+                let value = String::from_utf8_lossy(value);
+                if !self.data_names.contains(&*value) {
+                    let msg = format!("unknown data object {value:?}");
+                    self.dcx.err(msg).span(arg.span).emit();
+                }
+            }
+            (LiteralArg::Number, LitKind::Number(_)) | (_, LitKind::Err(_)) => {}
+            _ => self.literal_arg_error(name, index, kind, arg.span),
+        }
+    }
+
+    fn literal_arg_error(&self, name: Ident, index: usize, kind: LiteralArg, span: Span) {
+        let expected = match kind {
+            LiteralArg::Str | LiteralArg::DataName => "string",
+            LiteralArg::Number => "number",
+        };
+        let msg = format!("argument {} of `{name}` must be a {expected} literal", index + 1);
+        self.dcx.err(msg).span(span).emit();
+    }
+
+    fn unavailable_builtin(&self, name: Ident, builtin: Builtin) {
+        unavailable_builtin(self.dcx, self.evm_version, name, builtin);
+    }
+
+    /// Checks a literal used as a value.
+    fn check_lit(&self, lit: &solar_ast::Lit) {
+        match &lit.kind {
+            LitKind::Str(_, value) if value.len() > 32 => {
+                let msg = format!("string literal too long ({} > 32)", value.len());
+                self.dcx.err(msg).span(lit.span).emit();
+            }
+            LitKind::Number(n) if n.bits() > 256 => {
+                self.dcx.err("number literal too large (> 256 bits)").span(lit.span).emit();
+            }
+            _ => {}
+        }
+    }
+
+    /// Resolves a path that must refer to a variable.
+    fn resolve_variable(&self, path: &solar_ast::PathSlice) {
+        let Some(&name) = path.get_ident() else {
+            let msg = format!("undeclared identifier `{path}`");
+            self.dcx.err(msg).span(path.span()).emit();
+            return;
+        };
+        match self.lookup(name.name) {
+            Some((Decl::Variable(_), false)) => {}
+            Some((Decl::Variable(_), true)) => {
+                let msg = format!("undeclared identifier `{name}`");
+                let note =
+                    "variables declared outside of a function cannot be accessed inside of it";
+                self.dcx.err(msg).span(name.span).note(note).emit();
+            }
+            Some((Decl::Function { .. }, _)) => {
+                let msg = format!("`{name}` is a function, not a variable");
+                self.dcx.err(msg).span(name.span).emit();
+            }
+            None if builtins::builtin(name.as_str()).is_some() => {
+                let msg = format!("builtin function `{name}` must be called");
+                self.dcx.err(msg).span(name.span).emit();
+            }
+            None => {
+                let msg = format!("undeclared identifier `{name}`");
+                self.dcx.err(msg).span(name.span).emit();
+            }
+        }
+    }
+
+    /// Looks up a name in the current scopes, returning its declaration and whether it is
+    /// declared outside of the current function.
+    fn lookup(&self, name: Symbol) -> Option<(Decl, bool)> {
+        let mut outside_function = false;
+        for scope in self.scopes.iter().rev() {
+            if let Some(&decl) = scope.decls.get(&name) {
+                return Some((decl, outside_function));
+            }
+            outside_function |= scope.is_function;
+        }
+        None
+    }
+
+    /// Declares a name in the current scope.
+    ///
+    /// Yul does not allow shadowing, so the name must not be declared in any enclosing scope,
+    /// even if it is not accessible from the current function.
+    fn declare(&mut self, name: Ident, decl: Decl) {
+        if builtins::builtin(name.as_str()).is_some_and(|b| b.is_available(self.evm_version)) {
+            let msg = format!("cannot use builtin function name `{name}` as identifier");
+            self.dcx.err(msg).span(name.span).emit();
+            return;
+        }
+        if let Some((prev, _)) = self.lookup(name.name) {
+            let msg = format!("identifier `{name}` already declared");
+            self.dcx
+                .err(msg)
+                .span(name.span)
+                .span_label(name.span, "redeclared here")
+                .span_note(prev.span(), "first declared here")
+                .emit();
+            return;
+        }
+        self.scopes.last_mut().unwrap().decls.insert(name.name, decl);
+    }
+}
+
+fn unavailable_builtin(dcx: &DiagCtxt, evm_version: EvmVersion, name: Ident, builtin: Builtin) {
+    let msg = match (builtin.since, builtin.until) {
+        (Some(since), _) if evm_version < since => {
+            format!("`{name}` is only available for {since:?}-compatible VMs")
+        }
+        (_, Some(until)) => format!("`{name}` is no longer available since {until:?}"),
+        _ => unreachable!(),
+    };
+    let note = format!("you are currently compiling for `{evm_version}`");
+    dcx.err(msg).span(name.span).note(note).emit();
+}
+
+/// Returns `true` if two switch case literals have the same value.
+fn lit_eq(a: &LitKind, b: &LitKind) -> bool {
+    match (a, b) {
+        (LitKind::Str(_, a), LitKind::Str(_, b)) => a == b,
+        (LitKind::Number(a), LitKind::Number(b)) => a == b,
+        (LitKind::Bool(a), LitKind::Bool(b)) => a == b,
+        _ => false,
+    }
+}
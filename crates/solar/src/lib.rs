@@ -10,6 +10,8 @@
 #[doc(inline)]
 pub use solar_ast as ast;
 #[doc(inline)]
+pub use solar_codegen as codegen;
+#[doc(inline)]
 pub use solar_config as config;
 #[doc(inline)]
 pub use solar_data_structures as data_structures;
@@ -18,6 +20,8 @@ pub use solar_interface as interface;
 #[doc(inline)]
 pub use solar_macros as macros;
 #[doc(inline)]
+pub use solar_optimizer as optimizer;
+#[doc(inline)]
 pub use solar_parse as parse;
 #[doc(inline)]
 pub use solar_sema as sema;
@@ -0,0 +1,60 @@
+//! WebAssembly bindings for the Solar compiler, with an API compatible with
+//! [solc-js](https://github.com/ethereum/solc-js).
+//!
+//! See <https://docs.soliditylang.org/en/latest/using-the-compiler.html#compiler-input-and-output-json-description>.
+
+use solar_cli::{cli::Args, standard_json};
+use wasm_bindgen::prelude::*;
+
+/// Compiles the given standard JSON input and returns the standard JSON output.
+///
+/// Errors, including invalid inputs, are reported in the `errors` field of the output.
+#[wasm_bindgen(js_name = compileStandard)]
+pub fn compile_standard(input: &str) -> String {
+    let args = args();
+    standard_json::compile_json(&args, input).to_string()
+}
+
+/// Alias of [`compile_standard`], like `solc.compile` in solc-js.
+#[wasm_bindgen]
+pub fn compile(input: &str) -> String {
+    compile_standard(input)
+}
+
+/// Returns the version of the compiler.
+#[wasm_bindgen]
+pub fn version() -> String {
+    solar_cli::version::SHORT_VERSION.to_string()
+}
+
+/// Returns the default arguments for the standard JSON mode, running on a single thread.
+fn args() -> Args {
+    solar_cli::parse_args(["solar", "--standard-json", "--threads=1"]).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn imports() {
+        let input = r#"{
+            "language": "Solidity",
+            "sources": {
+                "a.sol": { "content": "import \"b.sol\"; contract A is B {}" },
+                "b.sol": { "content": "contract B { function f() external pure {} }" }
+            },
+            "settings": { "outputSelection": { "*": { "*": ["abi"] } } }
+        }"#;
+        let output: serde_json::Value =
+            serde_json::from_str(&super::compile_standard(input)).unwrap();
+        assert_eq!(output["contracts"]["a.sol"]["A"]["abi"][0]["name"], "f");
+        assert_eq!(output["contracts"]["b.sol"]["B"]["abi"][0]["name"], "f");
+    }
+
+    #[test]
+    fn invalid_input() {
+        let output: serde_json::Value = serde_json::from_str(&compile("{")).unwrap();
+        assert_eq!(output["errors"][0]["type"], "JSONError");
+    }
+}
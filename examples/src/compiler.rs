@@ -0,0 +1,45 @@
+use solar::{interface::config::CompilerOutput, sema::Compiler};
+
+const TOKEN: &str = r#"
+import {Math} from "./Math.sol";
+
+contract Token {
+    uint256 public supply;
+}
+"#;
+
+const MATH: &str = r#"
+library Math {
+    function max(uint256 a, uint256 b) internal pure returns (uint256) {
+        return a > b ? a : b;
+    }
+}
+"#;
+
+#[test]
+fn main() {
+    // Compile in-memory sources, which can import each other.
+    let compiled = Compiler::builder()
+        .source("src/Token.sol", TOKEN)
+        .source("src/Math.sol", MATH)
+        .emit(CompilerOutput::Abi)
+        .emit(CompilerOutput::Ast)
+        .compile();
+
+    // Diagnostics are returned instead of being printed.
+    assert!(!compiled.has_errors(), "{}", compiled.render_diagnostics());
+
+    // The requested outputs are returned in the same format as `solar --emit`.
+    let output = compiled.output.unwrap();
+    assert_eq!(output.sources.len(), 2);
+    for (name, contract) in &output.contracts {
+        println!("{name}: {} ABI items", contract.abi.as_ref().unwrap().len());
+    }
+
+    // Errors are collected as structured diagnostics.
+    let compiled =
+        Compiler::builder().source("src/Bad.sol", "contract Bad { uint x = y; }").compile();
+    assert!(compiled.has_errors());
+    assert!(compiled.errors().next().is_some());
+    println!("{}", compiled.render_diagnostics());
+}
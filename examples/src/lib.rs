@@ -3,4 +3,5 @@
 #![allow(unreachable_pub)]
 #![cfg(test)]
 
+mod compiler;
 mod parser;
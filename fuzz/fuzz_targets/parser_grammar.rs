@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use solar_fuzz::grammar::SourceUnit;
+
+fuzz_target!(|unit: SourceUnit| {
+    solar_fuzz::parse(&unit.to_string());
+});
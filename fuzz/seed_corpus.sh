@@ -0,0 +1,21 @@
+#!/usr/bin/env bash
+# Seed the fuzzing corpora with the Solidity files from the test suite.
+
+set -eo pipefail
+
+root="$(cd "$(dirname "$0")/.." && pwd)"
+fuzz="$root/fuzz"
+
+for target in lexer parser; do
+    mkdir -p "$fuzz/corpus/$target"
+done
+
+find "$root/tests" "$root/testdata" -type f -name '*.sol' -print0 |
+while IFS= read -r -d '' file; do
+    name="$(sha1sum "$file" | cut -d' ' -f1)"
+    for target in lexer parser; do
+        cp "$file" "$fuzz/corpus/$target/$name"
+    done
+done
+
+echo "seeded $(find "$fuzz/corpus/parser" -type f | wc -l) files"
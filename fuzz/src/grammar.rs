@@ -0,0 +1,1006 @@
+//! Grammar-aware generation of Solidity source code.
+//!
+//! The types in this module mirror a subset of the Solidity grammar, so that inputs generated
+//! from them are mostly well-formed and reach deeper into the parser than arbitrary bytes. They
+//! are printed back to source code with [`fmt::Display`].
+//!
+//! The first variant of each recursive enum is not recursive, since [`Arbitrary`] picks it once
+//! the input is exhausted.
+
+use arbitrary::Arbitrary;
+use std::fmt;
+
+/// A source unit.
+#[derive(Arbitrary, Debug)]
+pub struct SourceUnit {
+    pub items: Vec<Item>,
+}
+
+/// A top-level item.
+#[derive(Arbitrary, Debug)]
+pub enum Item {
+    Pragma(u8, u8),
+    Import(Ident, Option<Ident>),
+    Contract(Contract),
+    Function(Function),
+    Struct(Ident, Vec<(Type, Ident)>),
+    Enum(Ident, Vec<Ident>),
+    Event(Ident, Vec<Param>, bool),
+    Error(Ident, Vec<Param>),
+    Udvt(Ident, ElementaryType),
+    Constant(Type, Ident, Expr),
+    Using(Ident, Option<Type>, bool),
+    Tokens(Vec<Token>),
+}
+
+/// A contract, interface, or library.
+#[derive(Arbitrary, Debug)]
+pub struct Contract {
+    pub kind: ContractKind,
+    pub name: Ident,
+    pub bases: Vec<(Ident, Vec<Expr>)>,
+    pub items: Vec<ContractItem>,
+}
+
+#[derive(Arbitrary, Debug)]
+pub enum ContractKind {
+    Contract,
+    AbstractContract,
+    Interface,
+    Library,
+}
+
+/// An item in a contract.
+#[derive(Arbitrary, Debug)]
+pub enum ContractItem {
+    Variable(Type, Vec<VarAttr>, Ident, Option<Expr>),
+    Function(Function),
+    Modifier(Ident, Vec<Param>, bool, Option<Block>),
+    Constructor(Vec<Param>, Vec<(Ident, Vec<Expr>)>, Block),
+    Fallback(Vec<Param>, Option<Mutability>, Block),
+    Receive(Block),
+    Item(Box<Item>),
+}
+
+#[derive(Arbitrary, Debug)]
+pub enum VarAttr {
+    Visibility(Visibility),
+    Constant,
+    Immutable,
+    Override,
+    Transient,
+}
+
+/// A function.
+#[derive(Arbitrary, Debug)]
+pub struct Function {
+    pub name: Ident,
+    pub params: Vec<Param>,
+    pub visibility: Option<Visibility>,
+    pub mutability: Option<Mutability>,
+    pub modifiers: Vec<(Ident, Option<Vec<Expr>>)>,
+    pub virtual_: bool,
+    pub override_: bool,
+    pub returns: Vec<Param>,
+    pub body: Option<Block>,
+}
+
+#[derive(Arbitrary, Debug)]
+pub enum Visibility {
+    Private,
+    Internal,
+    Public,
+    External,
+}
+
+#[derive(Arbitrary, Debug)]
+pub enum Mutability {
+    Pure,
+    View,
+    Payable,
+}
+
+/// A parameter or a variable declaration.
+#[derive(Arbitrary, Debug)]
+pub struct Param {
+    pub ty: Type,
+    pub location: Option<DataLocation>,
+    pub indexed: bool,
+    pub name: Option<Ident>,
+}
+
+#[derive(Arbitrary, Debug)]
+pub enum DataLocation {
+    Memory,
+    Storage,
+    Calldata,
+    Transient,
+}
+
+/// A type name.
+#[derive(Arbitrary, Debug)]
+pub enum Type {
+    Elementary(ElementaryType),
+    Custom(Ident, Option<Ident>),
+    Array(Box<Type>, Option<u8>),
+    Mapping(ElementaryType, Option<Ident>, Box<Type>, Option<Ident>),
+    Function(Vec<Type>, Option<Visibility>, Option<Mutability>, Vec<Type>),
+}
+
+#[derive(Arbitrary, Debug)]
+pub enum ElementaryType {
+    Address,
+    AddressPayable,
+    Bool,
+    String,
+    Bytes,
+    Int(u8),
+    UInt(u8),
+    FixedBytes(u8),
+    Fixed(u8, u8),
+}
+
+/// A block of statements.
+#[derive(Arbitrary, Debug)]
+pub struct Block {
+    pub stmts: Vec<Stmt>,
+}
+
+/// A statement.
+#[derive(Arbitrary, Debug)]
+pub enum Stmt {
+    Break,
+    Continue,
+    Placeholder,
+    Expr(Expr),
+    Decl(Param, Option<Expr>),
+    DeclMulti(Vec<Option<Param>>, Expr),
+    Block(Block),
+    Unchecked(Block),
+    If(Expr, Box<Stmt>, Option<Box<Stmt>>),
+    For(Option<Box<Stmt>>, Option<Expr>, Option<Expr>, Box<Stmt>),
+    While(Expr, Box<Stmt>),
+    DoWhile(Box<Stmt>, Expr),
+    Return(Option<Expr>),
+    Emit(Ident, Vec<Expr>),
+    Revert(Ident, Vec<Expr>),
+    Try(Expr, Vec<Param>, Block, Vec<(Option<Ident>, Vec<Param>, Block)>),
+    Assembly(Option<String>, Vec<Token>),
+    Tokens(Vec<Token>),
+}
+
+/// An expression.
+#[derive(Arbitrary, Debug)]
+pub enum Expr {
+    Ident(Ident),
+    Lit(Lit),
+    Type(ElementaryType),
+    Unary(UnOp, Box<Expr>),
+    Binary(Box<Expr>, BinOp, Box<Expr>),
+    Assign(Box<Expr>, Option<BinOp>, Box<Expr>),
+    Ternary(Box<Expr>, Box<Expr>, Box<Expr>),
+    Call(Box<Expr>, Vec<Expr>),
+    NamedCall(Box<Expr>, Vec<(Ident, Expr)>),
+    CallOptions(Box<Expr>, Vec<(Ident, Expr)>),
+    Member(Box<Expr>, Ident),
+    Index(Box<Expr>, Option<Box<Expr>>),
+    Slice(Box<Expr>, Option<Box<Expr>>, Option<Box<Expr>>),
+    Tuple(Vec<Option<Expr>>),
+    Array(Vec<Expr>),
+    New(Type),
+    TypeCall(Type),
+    Payable(Vec<Expr>),
+    Delete(Box<Expr>),
+}
+
+/// A literal.
+#[derive(Arbitrary, Debug)]
+pub enum Lit {
+    Bool(bool),
+    Number(u64, Option<SubDenomination>),
+    Hex(u64),
+    Rational(u32, u16, Option<SubDenomination>),
+    Exponent(u32, i8),
+    Address([u8; 20]),
+    Str(String),
+    Unicode(String),
+    HexStr(Vec<u8>),
+}
+
+#[derive(Arbitrary, Debug)]
+pub enum SubDenomination {
+    Wei,
+    Gwei,
+    Ether,
+    Seconds,
+    Minutes,
+    Hours,
+    Days,
+    Weeks,
+    Years,
+}
+
+#[derive(Arbitrary, Debug)]
+pub enum UnOp {
+    Neg,
+    Not,
+    BitNot,
+    PreInc,
+    PreDec,
+    PostInc,
+    PostDec,
+}
+
+#[derive(Arbitrary, Debug)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+    And,
+    Or,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Shl,
+    Shr,
+    Sar,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+/// An identifier, picked from a small set of names so that generated code refers to the same
+/// names often, including some keywords and builtins.
+#[derive(Arbitrary, Debug)]
+pub struct Ident(u8);
+
+/// A token with no structure, to mix arbitrary token sequences into otherwise valid code.
+#[derive(Arbitrary, Debug)]
+pub enum Token {
+    Ident(Ident),
+    Lit(Lit),
+    Punct(u8),
+    Keyword(u8),
+}
+
+const IDENTS: &[&str] = &[
+    "a",
+    "b",
+    "c",
+    "x",
+    "y",
+    "C",
+    "D",
+    "I",
+    "L",
+    "S",
+    "E",
+    "f",
+    "g",
+    "m",
+    "_",
+    "$",
+    "_x",
+    "msg",
+    "block",
+    "tx",
+    "abi",
+    "this",
+    "super",
+    "address",
+    "length",
+    "push",
+    "pop",
+    "selector",
+    "value",
+    "gas",
+    "salt",
+    "from",
+    "error",
+    "revert",
+    "layout",
+    "at",
+    "transient",
+    "emit",
+    "ether",
+];
+
+const PUNCTS: &[&str] = &[
+    "(", ")", "[", "]", "{", "}", ";", ",", ".", ":", "?", "=", "=>", "->", "+", "-", "*", "/",
+    "%", "**", "!", "~", "&", "|", "^", "<<", ">>", ">>>", "<", ">", "<=", ">=", "==", "!=", "&&",
+    "||", "++", "--", "+=", ":=", "/*", "*/", "//", "///", "/**", "\"", "'", "\\",
+];
+
+const KEYWORDS: &[&str] = &[
+    "pragma",
+    "import",
+    "contract",
+    "interface",
+    "library",
+    "abstract",
+    "is",
+    "function",
+    "modifier",
+    "event",
+    "error",
+    "struct",
+    "enum",
+    "type",
+    "using",
+    "for",
+    "global",
+    "returns",
+    "return",
+    "if",
+    "else",
+    "while",
+    "do",
+    "break",
+    "continue",
+    "try",
+    "catch",
+    "emit",
+    "revert",
+    "new",
+    "delete",
+    "assembly",
+    "unchecked",
+    "public",
+    "private",
+    "internal",
+    "external",
+    "pure",
+    "view",
+    "payable",
+    "constant",
+    "immutable",
+    "virtual",
+    "override",
+    "memory",
+    "storage",
+    "calldata",
+    "indexed",
+    "anonymous",
+    "constructor",
+    "fallback",
+    "receive",
+    "mapping",
+    "let",
+    "leave",
+    "switch",
+    "case",
+    "default",
+    "hex",
+    "unicode",
+    "true",
+    "false",
+];
+
+fn list<T>(
+    f: &mut fmt::Formatter<'_>,
+    items: impl IntoIterator<Item = T>,
+    sep: &str,
+    mut fmt_item: impl FnMut(&mut fmt::Formatter<'_>, T) -> fmt::Result,
+) -> fmt::Result {
+    for (i, item) in items.into_iter().enumerate() {
+        if i > 0 {
+            f.write_str(sep)?;
+        }
+        fmt_item(f, item)?;
+    }
+    Ok(())
+}
+
+fn comma_sep<T: fmt::Display>(f: &mut fmt::Formatter<'_>, items: &[T]) -> fmt::Result {
+    list(f, items, ", ", |f, item| write!(f, "{item}"))
+}
+
+fn call_args(f: &mut fmt::Formatter<'_>, args: &[(Ident, Expr)]) -> fmt::Result {
+    list(f, args, ", ", |f, (name, value)| write!(f, "{name}: {value}"))
+}
+
+fn bases(f: &mut fmt::Formatter<'_>, bases: &[(Ident, Vec<Expr>)]) -> fmt::Result {
+    list(f, bases, ", ", |f, (name, args)| {
+        write!(f, "{name}")?;
+        if !args.is_empty() {
+            f.write_str("(")?;
+            comma_sep(f, args)?;
+            f.write_str(")")?;
+        }
+        Ok(())
+    })
+}
+
+fn body(f: &mut fmt::Formatter<'_>, body: &Option<Block>) -> fmt::Result {
+    match body {
+        Some(block) => write!(f, " {block}"),
+        None => f.write_str(";"),
+    }
+}
+
+/// Removes the characters that would end a string literal.
+fn str_contents(s: &str) -> String {
+    s.chars().filter(|c| !matches!(c, '"' | '\\' | '\n' | '\r')).collect()
+}
+
+impl fmt::Display for SourceUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        list(f, &self.items, "\n", |f, item| write!(f, "{item}"))
+    }
+}
+
+impl fmt::Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pragma(major, minor) => write!(f, "pragma solidity ^{major}.{minor}.0;"),
+            Self::Import(path, alias) => {
+                write!(f, "import \"{path}.sol\"")?;
+                if let Some(alias) = alias {
+                    write!(f, " as {alias}")?;
+                }
+                f.write_str(";")
+            }
+            Self::Contract(contract) => contract.fmt(f),
+            Self::Function(function) => function.fmt(f),
+            Self::Struct(name, fields) => {
+                write!(f, "struct {name} {{ ")?;
+                for (ty, name) in fields {
+                    write!(f, "{ty} {name}; ")?;
+                }
+                f.write_str("}")
+            }
+            Self::Enum(name, variants) => {
+                write!(f, "enum {name} {{ ")?;
+                comma_sep(f, variants)?;
+                f.write_str(" }")
+            }
+            Self::Event(name, params, anonymous) => {
+                write!(f, "event {name}(")?;
+                comma_sep(f, params)?;
+                f.write_str(")")?;
+                if *anonymous {
+                    f.write_str(" anonymous")?;
+                }
+                f.write_str(";")
+            }
+            Self::Error(name, params) => {
+                write!(f, "error {name}(")?;
+                comma_sep(f, params)?;
+                f.write_str(");")
+            }
+            Self::Udvt(name, ty) => write!(f, "type {name} is {ty};"),
+            Self::Constant(ty, name, value) => write!(f, "{ty} constant {name} = {value};"),
+            Self::Using(name, ty, global) => {
+                write!(f, "using {name} for ")?;
+                match ty {
+                    Some(ty) => write!(f, "{ty}")?,
+                    None => f.write_str("*")?,
+                }
+                if *global {
+                    f.write_str(" global")?;
+                }
+                f.write_str(";")
+            }
+            Self::Tokens(tokens) => list(f, tokens, " ", |f, token| write!(f, "{token}")),
+        }
+    }
+}
+
+impl fmt::Display for Contract {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            ContractKind::Contract => "contract",
+            ContractKind::AbstractContract => "abstract contract",
+            ContractKind::Interface => "interface",
+            ContractKind::Library => "library",
+        };
+        write!(f, "{kind} {}", self.name)?;
+        if !self.bases.is_empty() {
+            f.write_str(" is ")?;
+            bases(f, &self.bases)?;
+        }
+        f.write_str(" {\n")?;
+        for item in &self.items {
+            writeln!(f, "{item}")?;
+        }
+        f.write_str("}")
+    }
+}
+
+impl fmt::Display for ContractItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Variable(ty, attrs, name, value) => {
+                write!(f, "{ty}")?;
+                for attr in attrs {
+                    write!(f, " {attr}")?;
+                }
+                write!(f, " {name}")?;
+                if let Some(value) = value {
+                    write!(f, " = {value}")?;
+                }
+                f.write_str(";")
+            }
+            Self::Function(function) => function.fmt(f),
+            Self::Modifier(name, params, virtual_, block) => {
+                write!(f, "modifier {name}(")?;
+                comma_sep(f, params)?;
+                f.write_str(")")?;
+                if *virtual_ {
+                    f.write_str(" virtual")?;
+                }
+                body(f, block)
+            }
+            Self::Constructor(params, modifiers, block) => {
+                f.write_str("constructor(")?;
+                comma_sep(f, params)?;
+                f.write_str(") ")?;
+                bases(f, modifiers)?;
+                write!(f, " {block}")
+            }
+            Self::Fallback(params, mutability, block) => {
+                f.write_str("fallback(")?;
+                comma_sep(f, params)?;
+                f.write_str(") external")?;
+                if let Some(mutability) = mutability {
+                    write!(f, " {mutability}")?;
+                }
+                write!(f, " {block}")
+            }
+            Self::Receive(block) => write!(f, "receive() external payable {block}"),
+            Self::Item(item) => item.fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for VarAttr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Visibility(visibility) => visibility.fmt(f),
+            Self::Constant => f.write_str("constant"),
+            Self::Immutable => f.write_str("immutable"),
+            Self::Override => f.write_str("override"),
+            Self::Transient => f.write_str("transient"),
+        }
+    }
+}
+
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "function {}(", self.name)?;
+        comma_sep(f, &self.params)?;
+        f.write_str(")")?;
+        if let Some(visibility) = &self.visibility {
+            write!(f, " {visibility}")?;
+        }
+        if let Some(mutability) = &self.mutability {
+            write!(f, " {mutability}")?;
+        }
+        for (name, args) in &self.modifiers {
+            write!(f, " {name}")?;
+            if let Some(args) = args {
+                f.write_str("(")?;
+                comma_sep(f, args)?;
+                f.write_str(")")?;
+            }
+        }
+        if self.virtual_ {
+            f.write_str(" virtual")?;
+        }
+        if self.override_ {
+            f.write_str(" override")?;
+        }
+        if !self.returns.is_empty() {
+            f.write_str(" returns (")?;
+            comma_sep(f, &self.returns)?;
+            f.write_str(")")?;
+        }
+        body(f, &self.body)
+    }
+}
+
+impl fmt::Display for Visibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Private => "private",
+            Self::Internal => "internal",
+            Self::Public => "public",
+            Self::External => "external",
+        })
+    }
+}
+
+impl fmt::Display for Mutability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Pure => "pure",
+            Self::View => "view",
+            Self::Payable => "payable",
+        })
+    }
+}
+
+impl fmt::Display for Param {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.ty)?;
+        if self.indexed {
+            f.write_str(" indexed")?;
+        }
+        if let Some(location) = &self.location {
+            write!(f, " {location}")?;
+        }
+        if let Some(name) = &self.name {
+            write!(f, " {name}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DataLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Memory => "memory",
+            Self::Storage => "storage",
+            Self::Calldata => "calldata",
+            Self::Transient => "transient",
+        })
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Elementary(ty) => ty.fmt(f),
+            Self::Custom(name, member) => {
+                write!(f, "{name}")?;
+                if let Some(member) = member {
+                    write!(f, ".{member}")?;
+                }
+                Ok(())
+            }
+            Self::Array(ty, len) => match len {
+                Some(len) => write!(f, "{ty}[{len}]"),
+                None => write!(f, "{ty}[]"),
+            },
+            Self::Mapping(key, key_name, value, value_name) => {
+                write!(f, "mapping({key}")?;
+                if let Some(key_name) = key_name {
+                    write!(f, " {key_name}")?;
+                }
+                write!(f, " => {value}")?;
+                if let Some(value_name) = value_name {
+                    write!(f, " {value_name}")?;
+                }
+                f.write_str(")")
+            }
+            Self::Function(params, visibility, mutability, returns) => {
+                f.write_str("function(")?;
+                comma_sep(f, params)?;
+                f.write_str(")")?;
+                if let Some(visibility) = visibility {
+                    write!(f, " {visibility}")?;
+                }
+                if let Some(mutability) = mutability {
+                    write!(f, " {mutability}")?;
+                }
+                if !returns.is_empty() {
+                    f.write_str(" returns (")?;
+                    comma_sep(f, returns)?;
+                    f.write_str(")")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for ElementaryType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Address => f.write_str("address"),
+            Self::AddressPayable => f.write_str("address payable"),
+            Self::Bool => f.write_str("bool"),
+            Self::String => f.write_str("string"),
+            Self::Bytes => f.write_str("bytes"),
+            // Sizes are mostly valid, with some invalid ones.
+            Self::Int(size) => write!(f, "int{}", size as u16 * 8 % 264),
+            Self::UInt(size) => write!(f, "uint{}", size as u16 * 8 % 264),
+            Self::FixedBytes(size) => write!(f, "bytes{}", size % 34),
+            Self::Fixed(size, decimals) => write!(f, "fixed{}x{}", size as u16 * 8, decimals % 82),
+        }
+    }
+}
+
+impl fmt::Display for Block {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("{\n")?;
+        for stmt in &self.stmts {
+            writeln!(f, "{stmt}")?;
+        }
+        f.write_str("}")
+    }
+}
+
+impl fmt::Display for Stmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Break => f.write_str("break;"),
+            Self::Continue => f.write_str("continue;"),
+            Self::Placeholder => f.write_str("_;"),
+            Self::Expr(expr) => write!(f, "{expr};"),
+            Self::Decl(var, value) => {
+                write!(f, "{var}")?;
+                if let Some(value) = value {
+                    write!(f, " = {value}")?;
+                }
+                f.write_str(";")
+            }
+            Self::DeclMulti(vars, value) => {
+                f.write_str("(")?;
+                list(f, vars, ", ", |f, var| match var {
+                    Some(var) => write!(f, "{var}"),
+                    None => Ok(()),
+                })?;
+                write!(f, ") = {value};")
+            }
+            Self::Block(block) => block.fmt(f),
+            Self::Unchecked(block) => write!(f, "unchecked {block}"),
+            Self::If(cond, then, else_) => {
+                write!(f, "if ({cond}) {then}")?;
+                if let Some(else_) = else_ {
+                    write!(f, " else {else_}")?;
+                }
+                Ok(())
+            }
+            Self::For(init, cond, next, body) => {
+                f.write_str("for (")?;
+                match init {
+                    Some(init) => write!(f, "{init}")?,
+                    None => f.write_str(";")?,
+                }
+                if let Some(cond) = cond {
+                    write!(f, " {cond}")?;
+                }
+                f.write_str(";")?;
+                if let Some(next) = next {
+                    write!(f, " {next}")?;
+                }
+                write!(f, ") {body}")
+            }
+            Self::While(cond, body) => write!(f, "while ({cond}) {body}"),
+            Self::DoWhile(body, cond) => write!(f, "do {body} while ({cond});"),
+            Self::Return(value) => match value {
+                Some(value) => write!(f, "return {value};"),
+                None => f.write_str("return;"),
+            },
+            Self::Emit(name, args) => {
+                write!(f, "emit {name}(")?;
+                comma_sep(f, args)?;
+                f.write_str(");")
+            }
+            Self::Revert(name, args) => {
+                write!(f, "revert {name}(")?;
+                comma_sep(f, args)?;
+                f.write_str(");")
+            }
+            Self::Try(expr, returns, block, catches) => {
+                write!(f, "try {expr}")?;
+                if !returns.is_empty() {
+                    f.write_str(" returns (")?;
+                    comma_sep(f, returns)?;
+                    f.write_str(")")?;
+                }
+                write!(f, " {block}")?;
+                for (name, params, block) in catches {
+                    f.write_str(" catch ")?;
+                    if let Some(name) = name {
+                        write!(f, "{name}")?;
+                    }
+                    if name.is_some() || !params.is_empty() {
+                        f.write_str("(")?;
+                        comma_sep(f, params)?;
+                        f.write_str(") ")?;
+                    }
+                    write!(f, "{block}")?;
+                }
+                Ok(())
+            }
+            Self::Assembly(dialect, tokens) => {
+                f.write_str("assembly ")?;
+                if let Some(dialect) = dialect {
+                    write!(f, "\"{}\" ", str_contents(dialect))?;
+                }
+                f.write_str("{ ")?;
+                list(f, tokens, " ", |f, token| write!(f, "{token}"))?;
+                f.write_str(" }")
+            }
+            Self::Tokens(tokens) => list(f, tokens, " ", |f, token| write!(f, "{token}")),
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ident(name) => name.fmt(f),
+            Self::Lit(lit) => lit.fmt(f),
+            Self::Type(ty) => ty.fmt(f),
+            Self::Unary(op, expr) => match op {
+                UnOp::PostInc => write!(f, "{expr}++"),
+                UnOp::PostDec => write!(f, "{expr}--"),
+                _ => write!(f, "{op}{expr}"),
+            },
+            Self::Binary(lhs, op, rhs) => write!(f, "({lhs} {op} {rhs})"),
+            Self::Assign(lhs, op, rhs) => match op {
+                Some(op) => write!(f, "{lhs} {op}= {rhs}"),
+                None => write!(f, "{lhs} = {rhs}"),
+            },
+            Self::Ternary(cond, then, else_) => write!(f, "({cond} ? {then} : {else_})"),
+            Self::Call(callee, args) => {
+                write!(f, "{callee}(")?;
+                comma_sep(f, args)?;
+                f.write_str(")")
+            }
+            Self::NamedCall(callee, args) => {
+                write!(f, "{callee}({{")?;
+                call_args(f, args)?;
+                f.write_str("})")
+            }
+            Self::CallOptions(callee, options) => {
+                write!(f, "{callee}{{")?;
+                call_args(f, options)?;
+                f.write_str("}")
+            }
+            Self::Member(expr, member) => write!(f, "{expr}.{member}"),
+            Self::Index(expr, index) => match index {
+                Some(index) => write!(f, "{expr}[{index}]"),
+                None => write!(f, "{expr}[]"),
+            },
+            Self::Slice(expr, start, end) => {
+                write!(f, "{expr}[")?;
+                if let Some(start) = start {
+                    write!(f, "{start}")?;
+                }
+                f.write_str(":")?;
+                if let Some(end) = end {
+                    write!(f, "{end}")?;
+                }
+                f.write_str("]")
+            }
+            Self::Tuple(exprs) => {
+                f.write_str("(")?;
+                list(f, exprs, ", ", |f, expr| match expr {
+                    Some(expr) => write!(f, "{expr}"),
+                    None => Ok(()),
+                })?;
+                f.write_str(")")
+            }
+            Self::Array(exprs) => {
+                f.write_str("[")?;
+                comma_sep(f, exprs)?;
+                f.write_str("]")
+            }
+            Self::New(ty) => write!(f, "new {ty}"),
+            Self::TypeCall(ty) => write!(f, "type({ty})"),
+            Self::Payable(args) => {
+                f.write_str("payable(")?;
+                comma_sep(f, args)?;
+                f.write_str(")")
+            }
+            Self::Delete(expr) => write!(f, "delete {expr}"),
+        }
+    }
+}
+
+impl fmt::Display for Lit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool(b) => b.fmt(f),
+            Self::Number(n, sub) => {
+                write!(f, "{n}")?;
+                if let Some(sub) = sub {
+                    write!(f, " {sub}")?;
+                }
+                Ok(())
+            }
+            Self::Hex(n) => write!(f, "{n:#x}"),
+            Self::Rational(int, fract, sub) => {
+                write!(f, "{int}.{fract}")?;
+                if let Some(sub) = sub {
+                    write!(f, " {sub}")?;
+                }
+                Ok(())
+            }
+            Self::Exponent(mantissa, exp) => write!(f, "{mantissa}e{exp}"),
+            Self::Address(bytes) => {
+                f.write_str("0x")?;
+                list(f, bytes, "", |f, b| write!(f, "{b:02x}"))
+            }
+            Self::Str(s) => write!(f, "\"{}\"", str_contents(s)),
+            Self::Unicode(s) => write!(f, "unicode\"{}\"", str_contents(s)),
+            Self::HexStr(bytes) => {
+                f.write_str("hex\"")?;
+                list(f, bytes, "", |f, b| write!(f, "{b:02x}"))?;
+                f.write_str("\"")
+            }
+        }
+    }
+}
+
+impl fmt::Display for SubDenomination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Wei => "wei",
+            Self::Gwei => "gwei",
+            Self::Ether => "ether",
+            Self::Seconds => "seconds",
+            Self::Minutes => "minutes",
+            Self::Hours => "hours",
+            Self::Days => "days",
+            Self::Weeks => "weeks",
+            Self::Years => "years",
+        })
+    }
+}
+
+impl fmt::Display for UnOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Neg => "-",
+            Self::Not => "!",
+            Self::BitNot => "~",
+            Self::PreInc | Self::PostInc => "++",
+            Self::PreDec | Self::PostDec => "--",
+        })
+    }
+}
+
+impl fmt::Display for BinOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Add => "+",
+            Self::Sub => "-",
+            Self::Mul => "*",
+            Self::Div => "/",
+            Self::Rem => "%",
+            Self::Pow => "**",
+            Self::And => "&&",
+            Self::Or => "||",
+            Self::BitAnd => "&",
+            Self::BitOr => "|",
+            Self::BitXor => "^",
+            Self::Shl => "<<",
+            Self::Shr => ">>",
+            Self::Sar => ">>>",
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+            Self::Eq => "==",
+            Self::Ne => "!=",
+        })
+    }
+}
+
+impl fmt::Display for Ident {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(IDENTS[self.0 as usize % IDENTS.len()])
+    }
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ident(ident) => ident.fmt(f),
+            Self::Lit(lit) => lit.fmt(f),
+            Self::Punct(i) => f.write_str(PUNCTS[*i as usize % PUNCTS.len()]),
+            Self::Keyword(i) => f.write_str(KEYWORDS[*i as usize % KEYWORDS.len()]),
+        }
+    }
+}
@@ -0,0 +1,39 @@
+//! Fuzz targets for the Solar lexer and parser.
+//!
+//! The targets in `fuzz_targets/` are thin wrappers around the functions in this crate, which
+//! must never panic: invalid inputs can only result in diagnostics.
+
+use solar_parse::{
+    ast,
+    interface::{source_map::FileName, Session},
+    Lexer, Parser,
+};
+
+pub mod grammar;
+
+/// Lexes the given source code.
+pub fn lex(src: &str) {
+    let sess = session();
+    sess.enter(|| {
+        for token in Lexer::new(&sess, src) {
+            std::hint::black_box(token);
+        }
+    });
+}
+
+/// Parses the given source code as a Solidity source unit.
+pub fn parse(src: &str) {
+    let sess = session();
+    let _ = sess.enter(|| -> solar_parse::interface::Result {
+        let arena = ast::Arena::new();
+        let filename = FileName::Custom("fuzz".into());
+        let mut parser = Parser::from_source_code(&sess, &arena, filename, src.into())?;
+        let ast = parser.parse_file().map_err(|e| e.emit())?;
+        std::hint::black_box(ast);
+        Ok(())
+    });
+}
+
+fn session() -> Session {
+    Session::builder().with_silent_emitter(None).build()
+}
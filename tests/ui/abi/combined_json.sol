@@ -0,0 +1,7 @@
+//@ignore-host: windows
+//@compile-flags: --combined-json abi,hashes --pretty-json
+
+contract C {
+    function f1() public {}
+    function f2() external {}
+}
@@ -0,0 +1,46 @@
+//@ignore-host: windows
+//@compile-flags: --emit=interface
+
+struct Point {
+    uint256 x;
+    uint256 y;
+}
+
+type Price is uint128;
+
+interface IBase {
+    event Transfer(address indexed from, address indexed to, uint256 amount);
+
+    function balanceOf(address owner) external view returns (uint256);
+}
+
+library L {
+    function f() external {}
+}
+
+contract C is IBase {
+    enum Kind { A, B }
+
+    struct Shape {
+        Point[] points;
+        Kind kind;
+    }
+
+    error Unauthorized(address caller);
+
+    mapping(address => uint256) public balances;
+
+    receive() external payable {}
+
+    function balanceOf(address owner) external view returns (uint256) {
+        return balances[owner];
+    }
+
+    function draw(Shape calldata shape, string memory label) public returns (bytes32 id, Price) {}
+
+    function callback(function(uint256) external returns (bool) cb) external pure {}
+
+    function create(IBase other) external payable returns (IBase) {}
+
+    function internalFn() internal {}
+}
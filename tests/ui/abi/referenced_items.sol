@@ -0,0 +1,31 @@
+//@ignore-host: windows
+//@compile-flags: --emit=abi --pretty-json
+
+// Events and errors that are emitted or reverted with are included in the ABI, even if they are
+// declared outside of the contract.
+
+event Transfer(address indexed from, uint256 amount);
+error Unauthorized();
+
+library L {
+    event LibEvent(uint256 x);
+}
+
+contract A {
+    event Declared();
+
+    function a() internal {
+        emit Transfer(address(0), 1);
+    }
+}
+
+contract B is A {
+    error Failed(uint256 code);
+
+    function b() internal {
+        if (true) {
+            revert Unauthorized();
+        }
+        emit L.LibEvent(1);
+    }
+}
@@ -0,0 +1,6 @@
+//@ignore-host: windows
+//@compile-flags: --emit=ast --pretty-json --stop-after=parsing
+
+contract C {
+    uint256 x = 1;
+}
@@ -0,0 +1,5 @@
+//@ignore-host: windows
+//@compile-flags: --emit=ast --pretty-json
+
+// The AST is emitted even if a later stage fails.
+contract C is D {} //~ ERROR: undeclared identifier `D`
@@ -0,0 +1,28 @@
+//@ignore-host: windows
+//@compile-flags: --emit=ast-pretty --stop-after=parsing
+
+pragma solidity ^0.8.0;
+/// A contract.
+abstract contract C is A(1), B {
+    using L for uint256;
+    uint256 public constant X = 1 ether;
+    mapping(address owner => uint256) balances;
+    event E(uint256 indexed a) anonymous;
+    error Err(string);
+    struct S { uint256 a; bytes b; }
+    enum K { A, B }
+    function f(uint256 a, uint256 b) external view virtual override(A, B) returns (uint256) {
+        uint256 x = (a + b) * 2 ** -a;
+        if (a > 0) if (b > 0) x++; else x--;
+        for (uint256 i; i < a; i++) x += i;
+        (uint256 y, , bool z) = g();
+        try this.h{value: 1}(x) returns (uint256 r) { return r; } catch Error(string memory m) { revert Err(m); } catch {}
+        unchecked { x -= 1; }
+        assembly ("memory-safe") {
+            let v := add(x, 1)
+            if v { v := 0 }
+            switch v case 0 {} default { leave }
+        }
+        return a > b ? a : b;
+    }
+}
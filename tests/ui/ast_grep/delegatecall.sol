@@ -0,0 +1,17 @@
+//@ignore-host: windows
+//@compile-flags: ast-grep call:delegatecall
+
+contract Proxy {
+    address implementation;
+
+    fallback() external {
+        (bool ok, ) = implementation.delegatecall(msg.data);
+        require(ok);
+    }
+
+    function forward(address target) external {
+        assembly {
+            let ok := delegatecall(gas(), target, 0, calldatasize(), 0, 0)
+        }
+    }
+}
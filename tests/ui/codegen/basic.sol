@@ -0,0 +1,28 @@
+//@ignore-host: windows
+//@compile-flags: --emit=ir --pretty-json --no-cbor-metadata
+
+contract C {
+    uint128 a;
+    bool b;
+
+    function set(uint128 x) external {
+        a = x;
+        b = !b;
+    }
+
+    function sum(uint256 n) public pure returns (uint256 total) {
+        for (uint256 i = 0; i < n; i++) {
+            total += i;
+        }
+    }
+
+    function twice(uint256 v) external pure returns (uint256) {
+        return double(v);
+    }
+
+    function double(uint256 v) internal pure returns (uint256) {
+        unchecked {
+            return v * 2;
+        }
+    }
+}
@@ -0,0 +1,4 @@
+//@ignore-host: windows
+//@compile-flags: --emit=bin,bin-runtime --pretty-json --no-cbor-metadata
+
+contract C {}
@@ -0,0 +1,11 @@
+//@ignore-host: windows
+//@compile-flags: --emit=metadata --pretty-json --metadata-hash=none
+
+// SPDX-License-Identifier: MIT
+
+contract C {
+    /// @notice Returns one.
+    function one() external pure returns (uint256) {
+        return 1;
+    }
+}
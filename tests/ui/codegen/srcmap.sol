@@ -0,0 +1,4 @@
+//@ignore-host: windows
+//@compile-flags: --emit=srcmap,srcmap-runtime --pretty-json --no-cbor-metadata
+
+contract C {}
@@ -0,0 +1,5 @@
+//@compile-flags: flatten
+
+import {Lib as L} from "./auxiliary/lib.sol"; //~ ERROR: cannot flatten imports with aliases
+import "./auxiliary/lib.sol" as Lib; //~ ERROR: cannot flatten imports with aliases
+import {Lib} from "./auxiliary/lib.sol";
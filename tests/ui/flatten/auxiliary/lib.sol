@@ -0,0 +1,8 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+library Lib {
+    function f() internal pure returns (uint256) {
+        return 1;
+    }
+}
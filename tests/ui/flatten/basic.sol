@@ -0,0 +1,12 @@
+//@ignore-host: windows
+//@compile-flags: flatten
+// SPDX-License-Identifier: Apache-2.0
+pragma solidity >=0.8.0;
+pragma abicoder v2;
+import {Lib} from "./auxiliary/lib.sol";
+
+contract C {
+    function g() public pure returns (uint256) {
+        return Lib.f();
+    }
+}
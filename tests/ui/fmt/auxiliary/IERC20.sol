@@ -0,0 +1,3 @@
+interface IERC20 {
+    function transferFrom(address from, address to, uint256 amount) external returns (bool);
+}
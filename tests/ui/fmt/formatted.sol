@@ -0,0 +1,55 @@
+//@compile-flags: fmt --check
+
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+import {IERC20} from "./auxiliary/IERC20.sol";
+
+/// A vault that holds a single token.
+contract Vault {
+    struct Deposit {
+        address owner;
+        uint256 amount; // in wei
+    }
+
+    enum State {
+        Open,
+        Closed
+    }
+
+    IERC20 public immutable token;
+    mapping(address => Deposit) internal deposits;
+    State public state;
+
+    event Deposited(address indexed owner, uint256 amount);
+
+    error Closed();
+
+    modifier whenOpen() {
+        if (state != State.Open) revert Closed();
+        _;
+    }
+
+    constructor(IERC20 token_) {
+        token = token_;
+    }
+
+    function deposit(uint256 amount) external whenOpen returns (bool) {
+        // Effects before interactions.
+        deposits[msg.sender].amount += amount;
+        emit Deposited(msg.sender, amount);
+        return token.transferFrom(msg.sender, address(this), amount);
+    }
+
+    function balanceOf(address owner) public view returns (uint256 amount, bool exists) {
+        Deposit storage d = deposits[owner];
+        (amount, exists) = (d.amount, d.owner != address(0));
+        for (uint256 i = 0; i < 10; i++) {
+            if (i == amount) {
+                break;
+            } else {
+                continue;
+            }
+        }
+    }
+}
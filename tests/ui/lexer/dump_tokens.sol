@@ -0,0 +1,7 @@
+//@ignore-host: windows
+//@compile-flags: -Zdump=tokens
+
+contract C {
+    uint x = 0x1f + 2;
+    string s = "a";
+}
@@ -0,0 +1,11 @@
+contract A {}
+
+abstract contract B {
+    function f() public virtual returns (uint256);
+}
+
+struct S {
+    uint256 x;
+}
+
+type T is uint256;
@@ -0,0 +1,6 @@
+//@compile-flags: -D warnings -A 6162
+
+function fallback() {}
+//~^ ERROR: function named `fallback`
+
+function f(function(uint x) external g) {}
@@ -0,0 +1,9 @@
+//@compile-flags: --warn=6508 --pragma-version 0.8.20
+
+pragma solidity ^0.8.0; //~ WARN: floating compiler version requirement `^0.8.0`
+pragma solidity >=0.8.0 <0.9.0; //~ WARN: floating compiler version requirement `>=0.8.0 <0.9.0`
+pragma solidity 0.8; //~ WARN: floating compiler version requirement `0.8`
+pragma solidity 0.8.19 || 0.8.20; //~ WARN: floating compiler version requirement `0.8.19 || 0.8.20`
+pragma solidity 0.8.20;
+pragma solidity =0.8.20;
+pragma abicoder v2;
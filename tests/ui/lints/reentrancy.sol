@@ -0,0 +1,50 @@
+//@compile-flags: --warn=8751
+
+interface IToken {
+    function transfer(address to, uint256 amount) external returns (bool);
+    function balanceOf(address account) external view returns (uint256);
+}
+
+contract Bank {
+    mapping(address => uint256) balances;
+    uint256 total;
+    uint256[] history;
+    IToken token;
+
+    function withdraw() external {
+        (bool success, ) = msg.sender.call{value: balances[msg.sender]}("");
+        balances[msg.sender] = 0; //~ WARN: state variable `balances` is written after an external call
+        require(success);
+    }
+
+    function withdrawFirst() external {
+        uint256 amount = balances[msg.sender];
+        balances[msg.sender] = 0;
+        (bool success, ) = msg.sender.call{value: amount}("");
+        require(success);
+    }
+
+    function pay(uint256 amount) external {
+        token.transfer(msg.sender, amount);
+        total -= amount; //~ WARN: state variable `total` is written after an external call
+    }
+
+    function viewCall() external {
+        total = token.balanceOf(address(this));
+    }
+
+    function branches(bool b) external {
+        if (b) {
+            token.transfer(msg.sender, 1);
+        } else {
+            delete total;
+        }
+    }
+
+    function loop() external {
+        for (uint256 i; i < 2; i++) {
+            history.push(i); //~ WARN: state variable `history` is written after an external call
+            token.transfer(msg.sender, i);
+        }
+    }
+}
@@ -0,0 +1,17 @@
+//@compile-flags: --warn=2319
+
+function keccak256() {} //~ WARN: this declaration shadows a builtin symbol
+
+uint constant gasleft = 1; //~ WARN: this declaration shadows a builtin symbol
+
+contract msg {} //~ WARN: this declaration shadows a builtin symbol
+
+contract C {
+    uint256 block; //~ WARN: this declaration shadows a builtin symbol
+
+    event assert(uint256 require); //~ WARN: this declaration shadows a builtin symbol
+
+    struct tx { uint256 origin; } //~ WARN: this declaration shadows a builtin symbol
+
+    function sha256() public {} //~ WARN: this declaration shadows a builtin symbol
+}
@@ -0,0 +1,26 @@
+//@compile-flags: --warn=2519 --warn=2319
+
+contract A { uint x; }
+contract B is A { function f(uint x) public {} } //~ WARN: this declaration shadows an existing declaration
+
+contract C {
+    uint y;
+    function f(uint y) public {} //~ WARN: this declaration shadows an existing declaration
+
+    event E(uint y);
+    struct S { uint y; }
+
+    function g(uint a) public {
+        uint b = a;
+        { uint b = 1; } //~ WARN: this declaration shadows an existing declaration
+    }
+
+    function h() public returns (uint block) { //~ WARN: this declaration shadows a builtin symbol
+        uint sha256; //~ WARN: this declaration shadows a builtin symbol
+    }
+
+    function i() public {
+        { uint c; }
+        { uint c; }
+    }
+}
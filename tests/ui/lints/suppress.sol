@@ -0,0 +1,16 @@
+// solar-disable-next-line 3445
+function fallback() {}
+
+// solar-disable-next-line 6162
+function receive() {}
+//~^ WARN: function named `receive`
+
+// solar-disable
+function f(function(uint x) external g) {}
+// solar-enable
+
+function h(function(uint y) external g) {}
+//~^ WARN: named function type parameters are deprecated
+
+// solar-disable-next-line 12
+//~^ WARN: invalid lint `12`
@@ -0,0 +1,17 @@
+contract C {
+    address owner;
+
+    modifier onlyOwner() {
+        require(tx.origin == owner); //~ WARN: `tx.origin` used for authorization
+        _;
+    }
+
+    function f() public view onlyOwner returns (bool) {
+        if (owner != (tx.origin)) return false; //~ WARN: `tx.origin` used for authorization
+        return msg.sender == owner;
+    }
+
+    function g() public view returns (address) {
+        return tx.origin;
+    }
+}
@@ -0,0 +1,12 @@
+contract C {
+    function f(address a, address payable b) public {
+        a.call(""); //~ WARN: return value of low-level call is not used
+        (b.delegatecall("")); //~ WARN: return value of low-level call is not used
+        a.staticcall{gas: 1000}(""); //~ WARN: return value of low-level call is not used
+
+        (bool success, ) = a.call("");
+        require(success);
+        (success, ) = b.call{value: 1}("");
+        b.transfer(1);
+    }
+}
@@ -0,0 +1,14 @@
+//@compile-flags: --warn=8005
+
+import { A, B as Base, S, T } from "./auxiliary/imports.sol";
+//~^ WARN: unused import: `A`
+//~| WARN: unused import: `S`
+import "./auxiliary/imports.sol" as Unused; //~ WARN: unused import: `Unused`
+import * as Used from "./auxiliary/imports.sol";
+
+contract C is Used.B {
+    T t;
+
+    /// @inheritdoc Base
+    function f() public pure override returns (uint256) {}
+}
@@ -0,0 +1,46 @@
+//@compile-flags: --warn=2072 --warn=5667
+
+abstract contract C {
+    uint s;
+
+    modifier m(uint x) { _; }
+
+    function used(uint a, uint b) public returns (uint r) {
+        r = a;
+        uint c = b;
+        s = c;
+    }
+
+    function unusedParam(uint a) public {} //~ WARN: unused function parameter
+
+    function unnamed(uint) public {}
+
+    function interfaceLike(uint a) public virtual;
+
+    function unusedLocal() public {
+        uint x = 1; //~ WARN: unused local variable
+        (uint y, uint z) = (1, 2); //~ WARN: unused local variable
+        s = y;
+    }
+
+    function unusedReturn() public returns (uint r) {} //~ WARN: unused function parameter
+
+    function returnedValue() public returns (uint r) {
+        return 1;
+    }
+
+    function modifierArg(uint a) public m(a) {}
+
+    function assembly_(uint a) public {
+        assembly {
+            let b := a
+        }
+    }
+
+    function tryCatch() public {
+        try this.returnedValue() returns (uint v) { //~ WARN: unused try/catch parameter
+        } catch Error(string memory reason) { //~ WARN: unused try/catch parameter
+        } catch (bytes memory) {
+        }
+    }
+}
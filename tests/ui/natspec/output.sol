@@ -0,0 +1,54 @@
+//@ignore-host: windows
+//@compile-flags: --emit=userdoc,devdoc --pretty-json
+
+/// @title A token
+/// @author Alice
+/// @notice Tracks balances.
+/// @dev Not audited.
+interface IToken {
+    /// @notice Returns the balance of `owner`.
+    /// @param owner The account to query.
+    /// @return The balance.
+    function balanceOf(address owner) external view returns (uint256);
+
+    /// @notice Transfers `amount` tokens to `to`.
+    /// @param to The recipient.
+    /// @param amount The amount to transfer.
+    /// @return ok Whether the transfer succeeded.
+    function transfer(address to, uint256 amount) external returns (bool ok);
+
+    /// @notice Emitted on transfers.
+    /// @param from The sender.
+    event Transfer(address indexed from, address indexed to, uint256 amount);
+}
+
+/// @notice A simple token.
+/// @custom:experimental This is experimental.
+contract Token is IToken {
+    /// @notice The total supply.
+    /// @dev Never changes.
+    uint256 public totalSupply;
+
+    /// @dev Not documented to users.
+    uint256 internal fee;
+
+    /// @notice Thrown when the balance is too low.
+    /// @param needed The missing amount.
+    error Insufficient(uint256 needed);
+
+    /// @notice Creates the token.
+    /// @param supply The initial supply.
+    constructor(uint256 supply) {
+        totalSupply = supply;
+    }
+
+    function balanceOf(address) external view override returns (uint256) {
+        return totalSupply;
+    }
+
+    /// @inheritdoc IToken
+    /// @dev Always reverts.
+    function transfer(address to, uint256 amount) external override returns (bool ok) {
+        revert Insufficient(amount);
+    }
+}
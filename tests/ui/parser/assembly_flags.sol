@@ -0,0 +1,15 @@
+contract C {
+    function f() public pure {
+        assembly {}
+        assembly "evmasm" {}
+        assembly ("memory-safe") {}
+        assembly "evmasm" ("memory-safe") {}
+
+        assembly "ewasm" {} //~ ERROR: unknown inline assembly dialect "ewasm"
+        assembly ("unknown") {} //~ ERROR: unknown inline assembly flag "unknown"
+        assembly (
+            "memory-safe",
+            "memory-safe" //~ ERROR: inline assembly flag "memory-safe" used multiple times
+        ) {}
+    }
+}
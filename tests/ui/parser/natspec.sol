@@ -0,0 +1,44 @@
+/// @title A contract
+/// @author Someone
+/// @notice Does things
+/// @custom:security-contact security@example.com
+contract C {
+    /// @notice Emitted when something happens.
+    /// @param who The account.
+    /// @param what Not a parameter.
+    //~^ ERROR: documented parameter `what` not found in the parameter list of the event
+    event E(address who);
+
+    /// @title Not valid here.
+    //~^ ERROR: documentation tag `@title` is not valid for functions
+    /// @param a The first
+    ///        parameter.
+    /// @param a Again.
+    //~^ ERROR: parameter `a` is documented more than once
+    /// @return sum The sum.
+    /// @return Too many.
+    //~^ ERROR: documentation tag `@return` exceeds the number of return parameters
+    function f(uint256 a) public pure returns (uint256 sum) {}
+
+    /**
+     * @dev Unnamed returns.
+     * @return The value.
+     */
+    function g() public pure returns (uint256) {}
+
+    /// @return value The value.
+    //~^ ERROR: documentation tag `@return` does not contain the name of its return parameter `result`
+    function h() public pure returns (uint256 result) {}
+
+    /// @notice Not public.
+    //~^ ERROR: documentation tag `@notice` is only allowed on public state variables
+    uint256 internal x;
+
+    /// @foo Unknown.
+    //~^ ERROR: invalid NatSpec tag `@foo`
+    /// @custom:Bad Invalid name.
+    //~^ ERROR: invalid custom NatSpec tag name `Bad`
+    /// @param
+    //~^ ERROR: expected the name of a parameter after `@param`
+    function i() public {}
+}
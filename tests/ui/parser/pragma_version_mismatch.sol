@@ -0,0 +1,13 @@
+//@compile-flags: --pragma-version 0.8.20
+
+pragma solidity ^0.8.0;
+pragma solidity >=0.8.0 <0.9.0;
+pragma solidity *;
+pragma solidity 0.8.20;
+
+pragma solidity ^0.7.0;
+//~^ ERROR: source file requires a different compiler version
+pragma solidity >=0.9.0;
+//~^ ERROR: source file requires a different compiler version
+pragma solidity 0.8.0 - 0.8.5 || >=1.0;
+//~^ ERROR: source file requires a different compiler version
@@ -0,0 +1,11 @@
+pragma solidity; //~ ERROR: empty version requirement
+
+contract C {
+    function f() public {
+        throw; //~ ERROR: `throw` statements have been removed
+        uint[3] memory a = [1, , 2]; //~ ERROR: array expression components cannot be empty
+        a;
+    }
+
+    function g() public {}
+}
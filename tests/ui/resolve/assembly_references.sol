@@ -0,0 +1,14 @@
+contract C {
+    uint256 x;
+
+    function g() internal {}
+    function g(uint256) internal {}
+
+    function f() public {
+        assembly {
+            let a := x.foo //~ ERROR: unknown suffix `.foo`
+            let b := x.slot.offset //~ ERROR: unknown suffix `.slot.offset`
+            let c := g //~ ERROR: multiple matching identifiers
+        }
+    }
+}
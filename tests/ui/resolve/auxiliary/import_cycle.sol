@@ -0,0 +1,3 @@
+import "../import_cycle.sol";
+
+contract B {}
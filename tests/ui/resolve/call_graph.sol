@@ -0,0 +1,50 @@
+//@ignore-host: windows
+//@compile-flags: --emit=call-graph --pretty-json
+
+library L {
+    function double(uint256 x) internal pure returns (uint256) {
+        return x * 2;
+    }
+}
+
+interface I {
+    function get() external view returns (uint256);
+}
+
+abstract contract Base {
+    constructor(uint256) {}
+
+    function hook() internal virtual {}
+}
+
+contract C is Base {
+    uint256 x;
+
+    modifier onlyPositive(uint256 v) {
+        require(check(v));
+        _;
+    }
+
+    constructor() Base(1) {}
+
+    function check(uint256 v) internal pure returns (bool) {
+        return v > 0;
+    }
+
+    function hook() internal override {
+        super.hook();
+    }
+
+    function f(I i) external onlyPositive(x) {
+        x = L.double(i.get());
+        hook();
+        this.g();
+        new D(x);
+    }
+
+    function g() public {}
+}
+
+contract D {
+    constructor(uint256) {}
+}
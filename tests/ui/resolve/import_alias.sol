@@ -0,0 +1,8 @@
+import { MyUdvt as U } from "./auxiliary/udvt.sol";
+import "./auxiliary/udvt.sol" as Udvt;
+
+struct S {
+    U a;
+    Udvt.MyUdvt b;
+    MyUdvt c; //~ ERROR: undeclared identifier `MyUdvt`
+}
@@ -0,0 +1,3 @@
+import "./auxiliary/import_cycle.sol"; //~ WARN: import cycle
+
+contract A {}
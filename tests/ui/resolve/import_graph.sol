@@ -0,0 +1,4 @@
+//@ignore-host: windows
+//@compile-flags: --emit=import-graph --pretty-json
+
+import "./auxiliary/udvt.sol";
@@ -0,0 +1,2 @@
+import "./auxiliary/udtv.sol";
+//~^ ERROR: file ./auxiliary/udtv.sol not found
@@ -1,4 +1,4 @@
-import "./import_twice.sol" as self;
+import "./import_twice.sol" as self; //~ WARN: source file imports itself
 import "./import_twice.sol" as self;
 
 contract C {}
@@ -13,22 +13,22 @@ function funky() {
     // ---
     // TODO: `Error: Variable declarations can only be used inside blocks.`
 
-    while (a == 0) uint a = 0; //~ ERROR: unresolved symbol
-    a; //~ ERROR: unresolved symbol
-    while (b == 0) { uint b = 0; } //~ ERROR: unresolved symbol
-    b; //~ ERROR: unresolved symbol
+    while (a == 0) uint a = 0; //~ ERROR: undeclared identifier
+    a; //~ ERROR: undeclared identifier
+    while (b == 0) { uint b = 0; } //~ ERROR: undeclared identifier
+    b; //~ ERROR: undeclared identifier
 
-    do uint c; while (c == 0); //~ ERROR: unresolved symbol
-    c; //~ ERROR: unresolved symbol
-    do { uint d; } while (d == 0); //~ ERROR: unresolved symbol
-    d; //~ ERROR: unresolved symbol
+    do uint c; while (c == 0); //~ ERROR: undeclared identifier
+    c; //~ ERROR: undeclared identifier
+    do { uint d; } while (d == 0); //~ ERROR: undeclared identifier
+    d; //~ ERROR: undeclared identifier
 
-    for (; false; e++) uint e; //~ ERROR: unresolved symbol
-    e; //~ ERROR: unresolved symbol
-    for (; false; f++) { uint f; } //~ ERROR: unresolved symbol
-    f; //~ ERROR: unresolved symbol
+    for (; false; e++) uint e; //~ ERROR: undeclared identifier
+    e; //~ ERROR: undeclared identifier
+    for (; false; f++) { uint f; } //~ ERROR: undeclared identifier
+    f; //~ ERROR: undeclared identifier
     for (uint g; false; g++) {
         g;
     }
-    g; //~ ERROR: unresolved symbol
+    g; //~ ERROR: undeclared identifier
 }
@@ -22,5 +22,5 @@ contract C is A, B {
     }
 }
 
-contract D is this.C {} //~ ERROR: unresolved symbol `this`
-contract E is super.C {} //~ ERROR: unresolved symbol `super`
+contract D is this.C {} //~ ERROR: undeclared identifier `this`
+contract E is super.C {} //~ ERROR: undeclared identifier `super`
@@ -0,0 +1,34 @@
+struct S {
+    uint256 x;
+}
+
+library L {
+    using {secret} for uint256;
+
+    function secret(uint256 x) private pure returns (uint256) {
+        return x;
+    }
+}
+
+contract C {
+    function f(uint256 x) public pure returns (uint256) {
+        return x;
+    }
+}
+
+function over(uint256 x) pure returns (uint256) {
+    return x;
+}
+
+function over(int256 x) pure returns (int256) {
+    return x;
+}
+
+using L for uint256;
+using C for uint256; //~ ERROR: expected library, found contract
+using S for uint256; //~ ERROR: expected library, found struct
+using {S} for uint256; //~ ERROR: expected function, found struct
+using {C.f} for uint256; //~ ERROR: only file-level functions and library functions can be attached
+using {L.secret} for uint256; //~ ERROR: is private
+using {over} for uint256; //~ ERROR: is not a unique function name
+using {missing} for uint256; //~ ERROR: unresolved symbol
@@ -0,0 +1,3 @@
+interface Base {
+    function base() external;
+}
@@ -0,0 +1,15 @@
+//@ignore-host: windows
+//@compile-flags: selectors
+
+import "./auxiliary/base.sol";
+
+abstract contract Token is Base {
+    event Transfer(address indexed from, address indexed to, uint256 value);
+    error InsufficientBalance(uint balance, uint needed);
+
+    function transfer(address to, uint amount) external virtual returns (bool);
+    function balanceOf(address) external view virtual returns (uint);
+    function helper() internal virtual;
+}
+
+contract Empty {}
@@ -0,0 +1,4 @@
+// SPDX-License-Identifier: MIT OR Foo-1.0
+//~^ WARN: unknown SPDX license identifier `Foo-1.0`
+
+contract C {}
@@ -0,0 +1,6 @@
+//@compile-flags: --warn=1878
+//~^ WARN: SPDX license identifier not provided in source file
+
+contract C {
+    // SPDX-License-Identifier: MIT
+}
@@ -0,0 +1,5 @@
+// SPDX-License-Identifier: MIT
+// SPDX-License-Identifier: Apache-2.0
+//~^ ERROR: multiple SPDX license identifiers found in source file
+
+contract C {}
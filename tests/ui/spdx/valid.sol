@@ -0,0 +1,8 @@
+/*
+ * SPDX-License-Identifier: (MIT OR Apache-2.0) AND GPL-3.0-or-later WITH Classpath-exception-2.0
+ */
+
+/// @title A contract.
+contract C {
+    // SPDX-License-Identifier: MIT
+}
@@ -0,0 +1,6 @@
+//@compile-flags: --stop-after=checking --emit=abi
+
+// Outputs are not emitted when stopping before code generation.
+contract C {
+    function f() public {}
+}
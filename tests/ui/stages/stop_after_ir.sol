@@ -0,0 +1,6 @@
+//@compile-flags: --stop-after=ir
+
+// Code generation runs without requesting its output.
+contract C {
+    fallback() external {} //~ ERROR: unimplemented feature
+}
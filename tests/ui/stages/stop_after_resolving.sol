@@ -0,0 +1,9 @@
+//@compile-flags: --stop-after=resolving
+
+// Type errors are not reported when stopping before type checking.
+contract C {
+    function f() public {
+        uint8 a = 256;
+        bool b = 1;
+    }
+}
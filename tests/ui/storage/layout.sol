@@ -0,0 +1,33 @@
+//@ignore-host: windows
+//@compile-flags: --emit=storage-layout --pretty-json
+
+struct S {
+    uint128 a;
+    uint256 b;
+    bool c;
+}
+
+enum E {
+    A,
+    B,
+    C
+}
+
+contract Base {
+    uint256 constant C = 1;
+    address immutable I = address(0);
+    uint8 x;
+    bool y;
+    address owner;
+}
+
+contract Derived is Base {
+    uint256 z;
+    uint128[3] small;
+    S s;
+    E e;
+    mapping(address => uint256) balances;
+    uint64[] list;
+    string name;
+    Base base;
+}
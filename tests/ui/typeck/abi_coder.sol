@@ -0,0 +1,32 @@
+pragma abicoder v1;
+
+import "./auxiliary/abi_coder_v2.sol";
+
+struct T {
+    uint x;
+}
+
+contract C {
+    event E(T); //~ ERROR: this type is only supported in ABI coder v2
+
+    function f1(T memory) public {} //~ ERROR: this type is only supported in ABI coder v2
+
+    function f2(uint[][] memory) external {} //~ ERROR: this type is only supported in ABI coder v2
+
+    function f3() external returns (string[] memory) {} //~ ERROR: this type is only supported in ABI coder v2
+
+    function f4(uint[2][] memory, string memory, bytes calldata) external {}
+
+    function f5(T memory) internal {}
+
+    function f6(V2 v, S memory s, uint[][] memory n) internal {
+        v.plain(1);
+        v.take(s); //~ ERROR: the type of this parameter, `struct S memory`, is only supported in ABI coder v2
+        v.give(); //~ ERROR: return type `struct S memory` is only supported in ABI coder v2
+        v.nested(n); //~ ERROR: the type of this parameter, `uint256[][] memory`, is only supported in ABI coder v2
+    }
+}
+
+library L {
+    function f(T storage) public {}
+}
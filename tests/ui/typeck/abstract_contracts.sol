@@ -0,0 +1,25 @@
+interface J {
+    function a() external view returns (uint256);
+    function b() external;
+}
+contract C is J { //~ ERROR: contract `C` should be marked as abstract
+    function a() external pure returns (uint256) {
+        return 1;
+    }
+}
+
+abstract contract D is J {
+    function b() external virtual override {}
+}
+contract E is D {
+    function a() external view override returns (uint256) {}
+}
+
+abstract contract K { function v() public view virtual returns (uint256); }
+contract F is K { function v() public override returns (uint256) {} } //~ ERROR: overriding function changes state mutability from `view` to `nonpayable`
+
+abstract contract L { function w() public virtual returns (uint256); }
+contract G is L { function w() public override returns (int256) {} } //~ ERROR: overriding function return types differ
+
+abstract contract M { fallback() external virtual; }
+contract H is M {} //~ ERROR: contract `H` should be marked as abstract
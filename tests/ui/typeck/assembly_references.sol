@@ -0,0 +1,50 @@
+contract C {
+    uint256 x;
+    uint256[] arr;
+    uint256 constant K = 1;
+    string constant S = "s";
+    uint256 immutable I = 1;
+
+    function g() internal {}
+
+    function f(uint256[] calldata cd, function() external fp, uint256 p) public {
+        uint256[] storage sp = arr;
+        uint256[] memory m;
+        assembly {
+            let a := x //~ ERROR: only local variables are supported
+            let b := x.slot
+            let c := x.length //~ ERROR: storage variables only support `.slot` and `.offset`
+            x.slot := 1 //~ ERROR: state variables cannot be assigned to
+
+            let d := K
+            let e := K.slot //~ ERROR: suffixes can only be used with non-constant variables
+            K := 2 //~ ERROR: constant variables cannot be assigned to
+            let s := S //~ ERROR: only direct number constants
+            let i := I //~ ERROR: assembly access to immutable variables is not supported
+
+            let h := sp //~ ERROR: the `.slot` or `.offset` suffix is required
+            sp.slot := 0
+            sp.offset := 0 //~ ERROR: only `.slot` can be assigned to
+
+            let j := cd //~ ERROR: calldata arrays cannot be accessed directly
+            let k := add(cd.offset, cd.length)
+            let l := cd.slot //~ ERROR: calldata variables only support `.offset` and `.length`
+
+            let n := fp //~ ERROR: only types that use one stack slot are supported
+            let o := add(fp.address, fp.selector)
+            let q := fp.length //~ ERROR: variables of type function pointer only support
+
+            let r := m
+            let t := m.length //~ ERROR: the suffix `.length` is not supported
+
+            let u := g //~ ERROR: access to functions is not allowed in inline assembly
+            g := 1 //~ ERROR: only local variables can be assigned to in inline assembly
+            let v := g.slot //~ ERROR: suffixes can only be used with variables
+
+            function inner() -> w {
+                w := p //~ ERROR: cannot access local Solidity variables
+            }
+            p := inner()
+        }
+    }
+}
@@ -0,0 +1,15 @@
+pragma abicoder v2;
+
+struct S {
+    uint x;
+}
+
+contract V2 {
+    function take(S memory) external {}
+
+    function give() external returns (S memory) {}
+
+    function nested(uint[][] memory) external {}
+
+    function plain(uint) external {}
+}
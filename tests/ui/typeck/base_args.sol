@@ -0,0 +1,32 @@
+contract A {
+    constructor(uint) {}
+}
+
+abstract contract B is A {}
+
+contract C is A(1) {}
+contract E is C {
+    constructor() A(2) {} //~ ERROR: base constructor arguments given twice
+}
+
+contract D is A(1) {
+    constructor() A(2) {} //~ ERROR: base constructor arguments given twice
+}
+
+contract F is A {} //~ ERROR: no arguments passed to the constructor of base contract `A`
+
+contract G is A(1, 2) {} //~ ERROR: wrong argument count for constructor call: 2 arguments given but expected 1
+
+contract H is A(true) {} //~ ERROR: mismatched types: expected `uint256`, found `bool`
+
+contract I is B {
+    constructor() A(1) {}
+}
+
+contract J {
+    function f() public {
+        new B(); //~ ERROR: cannot instantiate an abstract contract
+        new C(1); //~ ERROR: wrong argument count for constructor call: 1 arguments given but expected 0
+        new A(1);
+    }
+}
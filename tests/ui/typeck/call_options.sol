@@ -0,0 +1,30 @@
+contract C {
+    constructor() payable {}
+
+    function pay() external payable {}
+
+    function nonpay() external {}
+}
+
+contract D {
+    constructor() {}
+}
+
+contract T {
+    function f(C c, address a) public {
+        c.pay{value: 1, gas: 2}();
+        c.nonpay{gas: 2}();
+        c.nonpay{value: 1}(); //~ ERROR: cannot set option `value` on a non-payable function type
+        c.pay{value: 1, value: 2}(); //~ ERROR: call option `value` has already been set
+        c.pay{foo: 1}(); //~ ERROR: unknown call option `foo`
+        c.pay{salt: bytes32(0)}(); //~ ERROR: call option `salt` can only be used with `new`
+        new C{value: 1, salt: bytes32(0)}();
+        new C{gas: 1}(); //~ ERROR: call option `gas` cannot be used with `new`
+        new D{value: 1}(); //~ ERROR: cannot set option `value`, since the constructor is not payable
+        g{gas: 1}(); //~ ERROR: function call options can only be set on external function calls or contract creations
+        a.call{value: 1, gas: 2}("");
+        a.delegatecall{value: 1}(""); //~ ERROR: cannot set option `value` on a non-payable function type
+    }
+
+    function g() internal {}
+}
@@ -0,0 +1,16 @@
+contract C {
+    uint x;
+
+    uint constant A = 1;
+    uint constant B = A * 2;
+    bytes32 constant H = keccak256(abi.encode(A, "a"));
+    address constant Z = address(0);
+
+    uint constant C1 = x; //~ ERROR: initial value for constant variable has to be compile-time constant
+    uint constant C2 = block.timestamp; //~ ERROR: initial value for constant variable has to be compile-time constant
+    uint constant C3 = f(); //~ ERROR: initial value for constant variable has to be compile-time constant
+
+    function f() internal pure returns (uint) {
+        return 1;
+    }
+}
@@ -0,0 +1,10 @@
+error mintEfficientN2M_001Z5BWH();
+
+contract C {
+    //~^ ERROR: error signature hash collision
+    error BlazingIt4490597615();
+
+    function f() public pure {
+        revert mintEfficientN2M_001Z5BWH();
+    }
+}
@@ -0,0 +1,32 @@
+contract C {
+    event E1(uint indexed a, uint indexed b, uint indexed c);
+    event E2(uint indexed a, uint indexed b, uint indexed c, uint indexed d); //~ ERROR: more than 3 indexed arguments for event
+    event E3(uint indexed a, uint indexed b, uint indexed c, uint indexed d) anonymous;
+    event E4(uint indexed a, uint indexed b, uint indexed c, uint indexed d, uint e) anonymous;
+    event E5(uint indexed a, uint indexed b, uint indexed c, uint indexed d, uint indexed e) anonymous; //~ ERROR: more than 4 indexed arguments for anonymous event
+    event E6(function() internal p); //~ ERROR: internal function types cannot be event parameter types
+    event E7(function() external p);
+
+    event O(uint a);
+    event O(bool a);
+
+    error Err1(function() internal p); //~ ERROR: internal function types cannot be error parameter types
+    error Err2(uint a);
+
+    function f() public {
+        emit E1(1, 2, 3);
+        emit E1(1, 2); //~ ERROR: wrong argument count for event call: 2 arguments given but expected 3
+        emit Err2(1); //~ ERROR: expected event, found error
+        emit O(1);
+        emit O(true);
+        emit O("a"); //~ ERROR: no matching declaration found after argument-dependent lookup
+    }
+
+    function g() public pure {
+        revert Err2(1);
+    }
+
+    function h() public pure {
+        revert E1(1, 2, 3); //~ ERROR: expected error, found event
+    }
+}
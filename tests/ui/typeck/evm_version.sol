@@ -0,0 +1,13 @@
+//@compile-flags: --evm-version homestead
+
+contract C {
+    function f(address a) public view returns (uint256 r) {
+        r = block.blobbasefee; //~ ERROR: `blobbasefee` is only available for Cancun-compatible VMs
+        r = block.basefee; //~ ERROR: `basefee` is only available for London-compatible VMs
+        r = block.chainid; //~ ERROR: `chainid` is only available for Istanbul-compatible VMs
+        r = block.number;
+        bytes32 h = blobhash(0); //~ ERROR: `blobhash` is only available for Cancun-compatible VMs
+        h = a.codehash; //~ ERROR: `codehash` is only available for Constantinople-compatible VMs
+        (bool ok, ) = a.staticcall(""); //~ ERROR: `staticcall` is only available for Byzantium-compatible VMs
+    }
+}
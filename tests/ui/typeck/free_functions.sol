@@ -0,0 +1,11 @@
+function f1() public {} //~ ERROR: free functions cannot have visibility
+
+function f2() payable {} //~ ERROR: free functions cannot be payable
+
+function f3() virtual {} //~ ERROR: free functions cannot be virtual
+
+function f4(); //~ ERROR: free functions must be implemented
+
+function f5() pure returns (uint) {
+    return 1;
+}
@@ -0,0 +1,20 @@
+interface I0 { function a() external view returns (uint256); }
+contract C0 is I0 { uint256 public override a; }
+
+interface I1 { function a() external view returns (uint256); }
+contract C1 is I1 { uint256 public a; } //~ ERROR: overriding public state variable is missing `override` specifier
+
+interface I2 { function a() external payable returns (uint256); }
+contract C2 is I2 { uint256 public override a; } //~ ERROR: overriding public state variable changes state mutability from `payable` to `view`
+
+interface I3 { function a() external view returns (int256); }
+contract C3 is I3 { uint256 public override a; } //~ ERROR: overriding public state variable return types differ
+
+interface I4 { function a(uint256) external view returns (bool); }
+contract C4 is I4 { mapping(uint256 => bool) public override a; }
+
+abstract contract A5 { function a() public view virtual returns (uint256); }
+contract C5 is A5 { uint256 public override a; } //~ ERROR: public state variables can only override functions with external visibility
+
+abstract contract A6 { function a() external view returns (uint256) {} }
+contract C6 is A6 { uint256 public override a; } //~ ERROR: trying to override non-virtual function
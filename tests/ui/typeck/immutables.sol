@@ -0,0 +1,54 @@
+contract A {
+    uint immutable a = 1;
+    uint immutable b;
+    uint immutable c;
+    uint immutable d;
+
+    constructor() {
+        b = a + c; //~ ERROR: immutable variables cannot be read before they are initialized
+        c = 2;
+        c = 3; //~ ERROR: immutable state variable already initialized
+        a = 4; //~ ERROR: immutable state variable already initialized
+        if (block.number > 0) {
+            d = 5; //~ ERROR: cannot write to immutable here: immutable variables cannot be initialized inside an if statement
+        }
+    }
+
+    function set() public view {
+        b = 1; //~ ERROR: cannot write to immutable here: immutable variables can only be initialized inline or assigned directly in the constructor
+    }
+}
+
+contract B {
+    uint immutable x;
+    uint immutable y;
+
+    constructor() {
+        y = read();
+        x = 1;
+    }
+
+    function read() internal view returns (uint) {
+        return x; //~ ERROR: immutable variables cannot be read before they are initialized
+    }
+}
+
+contract L {
+    uint immutable z;
+
+    constructor() {
+        for (uint i = 0; i < 1; i++) {
+            z = i; //~ ERROR: cannot write to immutable here: immutable variables cannot be initialized inside a loop
+        }
+    }
+}
+
+contract D is L {
+    constructor() {
+        z = 1; //~ ERROR: cannot write to immutable here: immutable variables must be initialized in the constructor of the contract they are defined in
+    }
+}
+
+contract U { //~ ERROR: construction control flow ends without initializing all immutables
+    uint immutable u;
+}
@@ -0,0 +1,41 @@
+struct S {
+    uint256 a;
+    uint256[] b;
+}
+
+struct T {
+    uint256 a;
+}
+
+enum E1 { A }
+enum E2 { A }
+
+contract D {}
+
+contract C {
+    mapping(uint256 => uint256) m;
+    mapping(address => S) structs;
+    uint256[] arr;
+    S s;
+
+    function f(uint256 k, bytes32 h, C other) public {
+        m[k] = 1;
+        m[k] = "x"; //~ ERROR: mismatched types
+        m[true] = 1; //~ ERROR: mismatched types
+        structs[msg.sender].a = 1;
+        structs[msg.sender].a = true; //~ ERROR: mismatched types
+        S storage ptr = structs[msg.sender];
+        uint256[] storage b = ptr.b;
+        uint256 x = b[0] + arr[k] + s.b[1];
+        uint8 len = arr.length; //~ ERROR: mismatched types
+        uint8 hLen = h.length;
+        uint8 c = h[0]; //~ ERROR: mismatched types
+        bytes1 d = h[0];
+
+        S memory sm = s;
+        T memory t = sm; //~ ERROR: mismatched types
+        E1 e1 = E1.A;
+        E2 e2 = E1.A; //~ ERROR: mismatched types
+        D dd = other; //~ ERROR: mismatched types
+    }
+}
@@ -0,0 +1,12 @@
+//@compile-flags: --evm-version shanghai
+
+contract C {
+    function f() public {
+        assembly {
+            tstore(0, tload(1)) //~ ERROR: `tstore` is only available for Cancun-compatible VMs
+            //~^ ERROR: `tload` is only available for Cancun-compatible VMs
+            mcopy(0, 32, 32) //~ ERROR: `mcopy` is only available for Cancun-compatible VMs
+            pop(prevrandao())
+        }
+    }
+}
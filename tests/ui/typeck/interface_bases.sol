@@ -0,0 +1,7 @@
+contract A {}
+abstract contract B {}
+interface I {}
+
+interface J is I, A, B {}
+//~^ ERROR: interfaces can only inherit from other interfaces
+//~^^ ERROR: interfaces can only inherit from other interfaces
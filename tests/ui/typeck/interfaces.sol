@@ -0,0 +1,10 @@
+interface I {
+    uint256 constant X = 1; //~ ERROR: variables cannot be declared in interfaces
+    constructor() {} //~ ERROR: constructors cannot be defined in interfaces
+    modifier m() { _; } //~ ERROR: modifiers cannot be declared in interfaces
+    function f() public; //~ ERROR: functions in interfaces must be declared external
+    function g() external {} //~ ERROR: functions in interfaces cannot have an implementation
+    function h() external view returns (uint256);
+    fallback() external;
+    receive() external payable;
+}
@@ -0,0 +1,13 @@
+contract A {}
+
+library L is A { //~ ERROR: library is not allowed to inherit
+    uint constant c = 1;
+    uint x; //~ ERROR: library cannot have non-constant state variables
+
+    fallback() external {} //~ ERROR: libraries cannot have fallback functions
+    receive() external payable {} //~ ERROR: libraries cannot have receive ether functions
+
+    function f(uint a) public pure returns (uint) {
+        return a + c;
+    }
+}
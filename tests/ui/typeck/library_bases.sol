@@ -0,0 +1,3 @@
+library L {}
+
+contract C is L {} //~ ERROR: libraries cannot be inherited from
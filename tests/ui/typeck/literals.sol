@@ -0,0 +1,22 @@
+contract C {
+    uint8 a = 255;
+    uint8 b = 256; //~ ERROR: mismatched types
+    uint8 c = 2 ** 8 - 1;
+    int8 d = -128;
+    int8 e = -(128 + 1); //~ ERROR: mismatched types
+    uint256 f = -1; //~ ERROR: mismatched types
+    uint256 g = 0.5; //~ ERROR: mismatched types
+    uint256 h = 0.5 * 2;
+    uint256 i = 5 / 2; //~ ERROR: mismatched types
+    uint256 j = 1.5 ether;
+    uint256 k = 0.5 gwei;
+    uint32 l = 7 days;
+    uint32 m = 1e10; //~ ERROR: mismatched types
+    uint256 n = 2 ** 256 - 1;
+    uint256 o = 2 ** 256; //~ ERROR: mismatched types
+    bytes2 p = 0x1234;
+    bytes2 q = 0;
+    bytes2 r = 0x12; //~ ERROR: mismatched types
+    bytes2 s = 1; //~ ERROR: mismatched types
+    address t = 1; //~ ERROR: mismatched types
+}
@@ -0,0 +1,42 @@
+//@compile-flags: --warn=6321
+
+contract C {
+    function empty() public returns (uint) {} //~ WARN: function must return a value on all paths
+
+    function named() public returns (uint x) {}
+
+    function oneBranch(bool c) public returns (uint) { //~ WARN: function must return a value on all paths
+        if (c) {
+            return 1;
+        }
+    }
+
+    function allBranches(bool c) public returns (uint) {
+        if (c) {
+            return 1;
+        } else {
+            return 2;
+        }
+    }
+
+    function reverts(bool c) public returns (uint) {
+        if (c) {
+            return 1;
+        }
+        revert();
+    }
+
+    function whileTrue() public returns (uint) { //~ WARN: function must return a value on all paths
+        while (true) {
+            return 1;
+        }
+    }
+
+    function infiniteLoop() public returns (uint) {
+        for (;;) {}
+    }
+
+    function partiallyNamed() public returns (uint x, uint) { //~ WARN: function must return a value on all paths
+        x = 1;
+    }
+}
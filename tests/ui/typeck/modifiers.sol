@@ -0,0 +1,40 @@
+contract C {
+    bool flag;
+
+    modifier noPlaceholder() {} //~ WARN: modifier body does not contain `_`
+
+    modifier maybe() { //~ WARN: modifier can return without executing `_`
+        if (flag) {
+            _;
+        }
+    }
+
+    modifier twice() { //~ WARN: modifier can execute `_` more than once
+        _;
+        _;
+    }
+
+    modifier once() {
+        if (flag) {
+            _;
+        } else {
+            revert();
+        }
+    }
+
+    modifier recursive() { //~ ERROR: modifier invokes itself recursively
+        recursive();
+        _;
+    }
+
+    modifier withArg(uint256 x) {
+        require(x > 0);
+        _;
+    }
+
+    function f1() public once withArg(1) {}
+
+    function f2() public withArg(true) {} //~ ERROR: mismatched types: expected `uint256`, found `bool`
+
+    function f3() public withArg {} //~ ERROR: wrong argument count for modifier call: 0 arguments given but expected 1
+}
@@ -0,0 +1,79 @@
+contract C {
+    uint256 x;
+    uint256[] arr;
+    mapping(uint256 => uint256) m;
+    uint256 immutable imm = 1;
+    uint256 immutable immNow = block.timestamp;
+
+    event E();
+
+    constructor() {
+        msg.value; //~ ERROR: `msg.value` and `callvalue()` can only be used in payable constructors
+    }
+
+    function readState() public pure returns (uint256) {
+        return x; //~ ERROR: function declared as `pure`, but this expression (potentially) reads from the environment or state
+    }
+
+    function readEnv() public pure returns (address) {
+        return msg.sender; //~ ERROR: function declared as `pure`, but this expression (potentially) reads from the environment or state
+    }
+
+    function readImmutable() public pure returns (uint256) {
+        return imm + immNow; //~ ERROR: function declared as `pure`, but this expression (potentially) reads from the environment or state
+    }
+
+    function readMapping(uint256 i) public pure returns (uint256) {
+        return m[i]; //~ ERROR: function declared as `pure`, but this expression (potentially) reads from the environment or state
+    }
+
+    function callView() public pure returns (uint256) {
+        return readView(); //~ ERROR: function declared as `pure`, but this expression (potentially) reads from the environment or state
+    }
+
+    function readView() public view returns (uint256) {
+        return x + arr.length + address(this).balance;
+    }
+
+    function writeState() public view {
+        x = 1; //~ ERROR: function cannot be declared as `view` because this expression (potentially) modifies the state
+    }
+
+    function writeArray() public view {
+        arr.push(1); //~ ERROR: function cannot be declared as `view` because this expression (potentially) modifies the state
+        arr[0] = 2; //~ ERROR: function cannot be declared as `view` because this expression (potentially) modifies the state
+        delete x; //~ ERROR: function cannot be declared as `view` because this expression (potentially) modifies the state
+    }
+
+    function emitEvent() public view {
+        emit E(); //~ ERROR: function cannot be declared as `view` because this expression (potentially) modifies the state
+    }
+
+    function msgValue() public returns (uint256) {
+        return msg.value; //~ ERROR: `msg.value` and `callvalue()` can only be used in payable public functions
+    }
+
+    function msgValueInternal() internal returns (uint256) {
+        return msg.value;
+    }
+
+    function msgValuePayable() public payable returns (uint256) {
+        return msg.value;
+    }
+
+    function assemblyRead() public pure returns (uint256 r) {
+        assembly {
+            r := sload(0) //~ ERROR: function declared as `pure`, but this expression (potentially) reads from the environment or state
+        }
+    }
+
+    function assemblyWrite() public view {
+        assembly {
+            sstore(0, 1) //~ ERROR: function cannot be declared as `view` because this expression (potentially) modifies the state
+        }
+    }
+
+    function hash(bytes memory data) public pure returns (bytes32) {
+        return keccak256(abi.encode(data, msg.data, msg.sig));
+    }
+}
@@ -0,0 +1,50 @@
+//@compile-flags: --warn=2018
+
+interface I {
+    function f() external returns (uint256);
+}
+
+contract C {
+    uint256 x;
+    I i;
+
+    modifier m() {
+        _;
+    }
+
+    function canBeView() public returns (uint256) { //~ WARN: function state mutability can be restricted to `view`
+        return x;
+    }
+
+    function canBePure(uint256 a) public view returns (uint256) { //~ WARN: function state mutability can be restricted to `pure`
+        return a + 1;
+    }
+
+    function canBePureToo() internal returns (uint256 a) { //~ WARN: function state mutability can be restricted to `pure`
+        a = 1;
+    }
+
+    function writes() public {
+        x = 1;
+    }
+
+    function calls() public returns (uint256) {
+        return i.f();
+    }
+
+    function empty() public {}
+
+    function payable_() public payable returns (uint256) {
+        return 1;
+    }
+
+    function virtual_() public virtual returns (uint256) {
+        return 1;
+    }
+
+    function withModifier() public m returns (uint256) {
+        return 1;
+    }
+
+    receive() external payable {}
+}
@@ -0,0 +1,13 @@
+contract C {
+    function f(uint256) internal {}
+    function f(bool) internal {}
+    function g(uint8) internal {}
+    function g(uint16) internal {}
+    function h() public {
+        g(1); //~ ERROR: no unique declaration found after argument-dependent lookup
+        g(uint8(1));
+        f(1);
+        f(true);
+        f("x"); //~ ERROR: no matching declaration found after argument-dependent lookup
+    }
+}
@@ -0,0 +1,29 @@
+contract A {
+    fallback() {} //~ ERROR: fallback function must be defined as `external`
+    receive() payable {} //~ ERROR: receive ether function must be defined as `external`
+}
+
+contract B {
+    fallback() external view {} //~ ERROR: fallback function must be payable or non-payable, but is `view`
+    receive() external {} //~ ERROR: receive ether function must be payable, but is `nonpayable`
+}
+
+contract C {
+    fallback(uint256) external {} //~ ERROR: invalid fallback function signature
+    receive(uint256, bool) external payable {} //~ ERROR: receive ether function cannot take parameters
+}
+
+contract D {
+    fallback(bytes calldata input) external payable returns (bytes memory) {
+        return input;
+    }
+    receive() external payable {}
+}
+
+contract E {
+    fallback(bytes memory) external {} //~ ERROR: invalid fallback function signature
+}
+
+contract F {
+    fallback() external returns (bytes memory) {} //~ ERROR: invalid fallback function signature
+}
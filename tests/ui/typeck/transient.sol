@@ -0,0 +1,11 @@
+struct S {
+    uint256 x;
+}
+
+contract C {
+    uint256 transient a;
+    bool public transient b;
+    uint256[] transient c; //~ ERROR: transient storage is only supported for value types
+    S transient d; //~ ERROR: transient storage is only supported for value types
+    mapping(uint256 => uint256) transient e; //~ ERROR: transient storage is only supported for value types
+}
@@ -0,0 +1,6 @@
+//@compile-flags: --evm-version shanghai
+
+contract C {
+    uint256 transient a; //~ ERROR: transient storage variables are not supported for EVM versions before Cancun
+    bool public transient b; //~ ERROR: transient storage variables are not supported for EVM versions before Cancun
+}
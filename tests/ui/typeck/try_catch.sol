@@ -0,0 +1,24 @@
+contract C {
+    function num() external returns (uint) {}
+
+    function two() external returns (uint, bool) {}
+}
+
+contract T {
+    function f(C c) public {
+        try c.num() returns (uint) {} catch Error(string memory) {} catch Panic(uint) {} catch (bytes memory) {}
+        try new C() returns (C) {} catch {}
+        try c.two() returns (uint, bool) {} catch {}
+        try c.two() returns (uint) {} catch {} //~ ERROR: function returns 2 values, but returns clause has 1 variables
+        try c.num() returns (bool) {} catch {} //~ ERROR: mismatched types
+        try g() {} catch {} //~ ERROR: try can only be used with external function calls and contract creation calls
+        try c.num() {} catch Error(bytes memory) {} //~ ERROR: expected `catch Error(string memory ...) { ... }`
+        try c.num() {} catch Panic(string memory) {} //~ ERROR: expected `catch Panic(uint ...) { ... }`
+        try c.num() {} catch (uint) {} //~ ERROR: expected `catch (bytes memory ...) { ... }` or `catch { ... }`
+        try c.num() {} catch Foo(uint) {} //~ ERROR: invalid catch clause name `Foo`
+        try c.num() {} catch Error(string memory) {} catch Error(string memory) {} //~ ERROR: this try statement already has an `Error` catch clause
+        try c.num() {} catch {} catch (bytes memory) {} //~ ERROR: this try statement already has a low-level catch clause
+    }
+
+    function g() internal {}
+}
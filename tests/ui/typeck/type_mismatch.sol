@@ -0,0 +1,17 @@
+contract C {
+    function f() public {
+        uint8 a = 256; //~ ERROR: mismatched types
+        bool b = 1; //~ ERROR: mismatched types
+        address payable p = address(0); //~ ERROR: mismatched types
+        (uint x, uint y) = (1, 2, 3); //~ ERROR: different number of components
+        if (a) {} //~ ERROR: mismatched types
+        b = bool(1); //~ ERROR: invalid explicit type conversion
+        g(1, 2); //~ ERROR: wrong argument count
+    }
+
+    function g(uint) internal {}
+
+    function h() internal returns (uint) {
+        return true; //~ ERROR: mismatched types
+    }
+}
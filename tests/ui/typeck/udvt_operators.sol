@@ -0,0 +1,87 @@
+type Price is uint256;
+type Int is int128;
+
+using {add as +, sub as -, neg as -, lt as <} for Price global;
+using {mul as *} for Price; //~ ERROR: operators can only be defined in a global `using for` directive
+using {L.div as /} for Price global; //~ ERROR: only pure file-level functions can be used to define operators
+using {impureMod as %} for Price global; //~ ERROR: only pure file-level functions can be used to define operators
+using {bitAnd as &} for uint256 global; //~ ERROR: can only use `global` with user-defined types
+//~^ ERROR: operators can only be implemented for user-defined value types
+using {oneParam as |} for Price global; //~ ERROR: wrong parameters in operator definition
+using {add as ~} for Price global; //~ ERROR: wrong parameters in operator definition
+using {mixed as ^} for Price global; //~ ERROR: wrong parameters in operator definition
+using {add as >} for Price global; //~ ERROR: wrong return parameters in operator definition
+using {badRet as &} for Price global; //~ ERROR: wrong return parameters in operator definition
+using {addInt as +} for Int global;
+using {addInt2 as +} for Int global;
+
+library L {
+    function div(Price a, Price b) internal pure returns (Price) {
+        return Price.wrap(Price.unwrap(a) / Price.unwrap(b));
+    }
+}
+
+function add(Price a, Price b) pure returns (Price) {
+    return Price.wrap(Price.unwrap(a) + Price.unwrap(b));
+}
+
+function sub(Price a, Price b) pure returns (Price) {
+    return Price.wrap(Price.unwrap(a) - Price.unwrap(b));
+}
+
+function neg(Price a) pure returns (Price) {
+    return a;
+}
+
+function lt(Price a, Price b) pure returns (bool) {
+    return Price.unwrap(a) < Price.unwrap(b);
+}
+
+function mul(Price a, Price b) pure returns (Price) {
+    return Price.wrap(Price.unwrap(a) * Price.unwrap(b));
+}
+
+function impureMod(Price a, Price b) view returns (Price) {
+    return Price.wrap(Price.unwrap(a) % Price.unwrap(b));
+}
+
+function bitAnd(uint256 a, uint256 b) pure returns (uint256) {
+    return a & b;
+}
+
+function oneParam(Price a) pure returns (Price) {
+    return a;
+}
+
+function mixed(Price a, uint256 b) pure returns (Price) {
+    return Price.wrap(Price.unwrap(a) ^ b);
+}
+
+function badRet(Price a, Price b) pure returns (uint256) {
+    return Price.unwrap(a) + Price.unwrap(b);
+}
+
+function addInt(Int a, Int b) pure returns (Int) {
+    return Int.wrap(Int.unwrap(a) + Int.unwrap(b));
+}
+
+function addInt2(Int a, Int b) pure returns (Int) {
+    return Int.wrap(Int.unwrap(a) + Int.unwrap(b));
+}
+
+contract C {
+    function f(Price a, Price b, uint256 x, uint256 y, Int i) public pure {
+        Price c = a + b - a;
+        c = -c;
+        bool less = a < b;
+        uint256 d = a + b; //~ ERROR: mismatched types
+        c = a * b; //~ ERROR: built-in binary operator `*` cannot be applied to types `Price` and `Price`
+        c = a + Price.unwrap(b); //~ ERROR: built-in binary operator `+` cannot be applied to types `Price` and `uint256`
+        c = ~a; //~ ERROR: built-in unary operator `~` cannot be applied to type `Price`
+        c++; //~ ERROR: built-in unary operator `++` cannot be applied to type `Price`
+        i = i + i; //~ ERROR: user-defined binary operator `+` has more than one definition
+        c = Price.wrap(int256(1)); //~ ERROR: mismatched types
+        x = Price.unwrap(y); //~ ERROR: mismatched types
+        x = Price.unwrap(a, b); //~ ERROR: wrong argument count
+    }
+}
@@ -0,0 +1,86 @@
+contract C {
+    uint x;
+
+    function afterReturn() public returns (uint) {
+        return 1;
+        x = 2; //~ WARN: unreachable code
+    }
+
+    function afterRevert() public {
+        revert("no");
+        x = 1; x = 2; //~ WARN: unreachable code
+    }
+
+    function afterBranches(bool c) public {
+        if (c) {
+            return;
+        } else {
+            revert();
+        }
+        x = 1; //~ WARN: unreachable code
+    }
+
+    function oneBranch(bool c) public {
+        if (c) {
+            return;
+        }
+        x = 1;
+    }
+
+    function afterBreak() public {
+        while (true) {
+            break;
+            x = 1; //~ WARN: unreachable code
+        }
+        x = 2;
+    }
+
+    function infiniteLoop() public {
+        for (;;) {
+            x++;
+        }
+        x = 1; //~ WARN: unreachable code
+    }
+
+    function forNext() public {
+        for (uint i; i < 10; i++) { //~ WARN: unreachable code
+            return;
+        }
+    }
+
+    function forContinue() public {
+        for (uint i; i < 10; i++) {
+            continue;
+        }
+    }
+
+    function doWhileCondition(bool c) public {
+        do {
+            return;
+        } while (c); //~ WARN: unreachable code
+    }
+
+    function doWhileContinue(bool c) public {
+        do {
+            continue;
+        } while (c);
+        x = 1;
+    }
+
+    function tryCatch() public {
+        try this.afterReturn() returns (uint) {
+            return;
+        } catch {
+            revert();
+        }
+        x = 1; //~ WARN: unreachable code
+    }
+
+    function inModifier() public m {}
+
+    modifier m() {
+        _;
+        return;
+        x = 1; //~ WARN: unreachable code
+    }
+}
@@ -0,0 +1,53 @@
+struct S {
+    uint256 x;
+}
+
+library L {
+    function inc(uint256 x) internal pure returns (uint256) {
+        return x + 1;
+    }
+
+    function neg(int256 x) internal pure returns (int256) {
+        return -x;
+    }
+}
+
+function double(uint256 x) pure returns (uint256) {
+    return x * 2;
+}
+
+function none() pure {}
+
+function get(S memory s) pure returns (uint256) {
+    return s.x;
+}
+
+function isZero(int256 x) pure returns (bool) {
+    return x == 0;
+}
+
+function getY(C.Inner memory i) pure returns (uint256) {
+    return i.y;
+}
+
+using L for uint256;
+using {get} for S global;
+using {double} for uint8;
+using {none} for uint256; //~ ERROR: does not have any parameters
+using {get} for uint256; //~ ERROR: cannot be attached to the type `uint256`
+using {isZero} for uint256; //~ ERROR: cannot be attached to the type `uint256`
+using {double} for uint256 global; //~ ERROR: can only use `global` with user-defined types
+using {getY} for C.Inner global; //~ ERROR: can only use `global` with types defined in the same source unit at file level
+
+contract C {
+    struct Inner {
+        uint256 y;
+    }
+
+    using L for *;
+    using {double, get} for *;
+
+    function f(uint256 x, S memory s) public pure returns (uint256) {
+        return x.double().inc() + s.get();
+    }
+}
@@ -5,7 +5,7 @@
 #![allow(unreachable_pub)]
 
 use eyre::{eyre, Result};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use ui_test::{color_eyre::eyre, spanned::Spanned};
 
 mod errors;
@@ -57,15 +57,21 @@ pub fn run_tests(cmd: &'static Path) -> Result<()> {
 fn config(cmd: &'static Path, args: &ui_test::Args, mode: Mode) -> ui_test::Config {
     let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap().parent().unwrap();
 
-    let path = match mode {
-        Mode::Ui => "tests/ui/",
-        Mode::SolcSolidity => "testdata/solidity/test/",
-        Mode::SolcYul => "testdata/solidity/test/libyul/",
+    // Solc tests can be run from a local checkout of the Solidity repository instead of the
+    // submodule.
+    let solc_root = std::env::var_os("TESTER_SOLC_ROOT")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| root.join("testdata/solidity"));
+    let tests_root = match mode {
+        Mode::Ui => root.join("tests/ui/"),
+        Mode::SolcSolidity => solc_root.join("test/"),
+        Mode::SolcYul => solc_root.join("test/libyul/"),
     };
-    let tests_root = root.join(path);
     assert!(
         tests_root.exists(),
-        "tests root directory does not exist: {path}; you may need to initialize submodules"
+        "tests root directory does not exist: {}; you may need to initialize submodules or set \
+         `TESTER_SOLC_ROOT` to a Solidity repository checkout",
+        tests_root.display()
     );
 
     let mut config = ui_test::Config {
@@ -76,8 +82,24 @@ fn config(cmd: &'static Path, args: &ui_test::Args, mode: Mode) -> ui_test::Conf
         program: ui_test::CommandBuilder {
             program: cmd.into(),
             args: {
-                let mut args =
-                    vec!["-j1", "--error-format=rustc-json", "-Zui-testing", "-Zparse-yul"];
+                // Most tests don't have an SPDX license identifier, and don't care about functions
+                // whose state mutability can be restricted, about unused and shadowed
+                // declarations, or about functions that don't return a value on all paths; allow
+                // these warnings by default.
+                let mut args = vec![
+                    "-j1",
+                    "--error-format=rustc-json",
+                    "-Zui-testing",
+                    "--no-config",
+                    "-A1878",
+                    "-A2018",
+                    "-A2072",
+                    "-A2319",
+                    "-A2519",
+                    "-A5667",
+                    "-A6321",
+                    "-A8005",
+                ];
                 if mode.is_solc() {
                     args.push("--stop-after=parsing");
                 }
@@ -167,7 +189,11 @@ fn get_host() -> &'static str {
 }
 
 fn file_filter(path: &Path, config: &ui_test::Config, cfg: MyConfig<'_>) -> Option<bool> {
-    path.extension().filter(|&ext| ext == "sol" || (cfg.mode.allows_yul() && ext == "yul"))?;
+    path.extension().filter(|&ext| {
+        ext == "sol"
+            || (cfg.mode.allows_yul() && ext == "yul")
+            || (cfg.mode.allows_standard_json() && ext == "json")
+    })?;
     if !ui_test::default_any_file_filter(path, config) {
         return Some(false);
     }
@@ -189,6 +215,15 @@ fn per_file_config(config: &mut ui_test::Config, file: &Spanned<Vec<u8>>, cfg: M
         return solc_per_file_config(config, src, path, cfg);
     }
 
+    // Standard JSON inputs can't have comments; their output is checked against `.stdout` files.
+    if path.extension().is_some_and(|ext| ext == "json") {
+        let base = config.comment_defaults.base();
+        base.compile_flags.extend(["--standard-json".into(), "--pretty-json".into()]);
+        base.require_annotations = Spanned::dummy(false).into();
+        base.exit_status = Spanned::dummy(0).into();
+        return;
+    }
+
     assert_eq!(config.comment_start, "//");
     let has_annotations = src.contains("//~");
     config.comment_defaults.base().require_annotations = Spanned::dummy(has_annotations).into();
@@ -257,6 +292,10 @@ impl Mode {
     fn allows_yul(self) -> bool {
         !matches!(self, Self::SolcSolidity)
     }
+
+    fn allows_standard_json(self) -> bool {
+        matches!(self, Self::Ui)
+    }
 }
 
 impl std::fmt::Display for Mode {